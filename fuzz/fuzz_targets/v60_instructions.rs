@@ -0,0 +1,14 @@
+//! Instructions V60 aléatoires comparées au modèle de référence
+//!
+//! `cargo +nightly fuzz run v60_instructions` depuis la racine du dépôt.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pixel_model2_rust::testing::FuzzCase;
+
+fuzz_target!(|data: &[u8]| {
+    if let Err(violation) = FuzzCase::from_bytes(data).run() {
+        panic!("{}", violation);
+    }
+});
@@ -0,0 +1,100 @@
+/*
+ * Pixel Model 2 Rust - interface C du cœur d'émulation
+ *
+ * Bibliothèque compilée avec :
+ *   cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * Une machine est créée par pm2_core_create et libérée par pm2_core_destroy.
+ * Un même pointeur ne doit pas être utilisé depuis plusieurs threads à la fois.
+ */
+
+#ifndef PIXEL_MODEL2_H
+#define PIXEL_MODEL2_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Pm2Core Pm2Core;
+
+typedef enum Pm2Status {
+    PM2_OK = 0,
+    PM2_INVALID_ARGUMENT = 1, /* pointeur nul, chaîne invalide, joueur inexistant */
+    PM2_NO_GAME = 2,          /* aucun jeu chargé */
+    PM2_ROM = 3,
+    PM2_MEMORY = 4,
+    PM2_CPU = 5,
+    PM2_GPU = 6,
+    PM2_AUDIO = 7,
+    PM2_IO = 8,
+    PM2_SAVE_STATE = 9,       /* état illisible ou d'un autre jeu */
+    PM2_PANIC = 10            /* erreur interne inattendue */
+} Pm2Status;
+
+/* Boutons de pm2_set_input, un bit par bouton */
+#define PM2_BUTTON_UP    (1u << 0)
+#define PM2_BUTTON_DOWN  (1u << 1)
+#define PM2_BUTTON_LEFT  (1u << 2)
+#define PM2_BUTTON_RIGHT (1u << 3)
+#define PM2_BUTTON_PUNCH (1u << 4)
+#define PM2_BUTTON_KICK  (1u << 5)
+#define PM2_BUTTON_GUARD (1u << 6)
+#define PM2_BUTTON_START (1u << 7)
+
+/* Commande de retour de force (carte de pilotage des jeux de course) et
+ * effets en cours après la commande, de 0 à 15 */
+typedef struct Pm2ForceFeedback {
+    uint64_t frame;
+    uint8_t command;    /* octet reçu par la carte de pilotage */
+    uint8_t centering;
+    uint8_t friction;
+    uint8_t vibration;
+    int8_t roll;        /* couple, de -15 (gauche) à 15 (droite) */
+} Pm2ForceFeedback;
+
+/* rom_dir (facultatif, NULL accepté) : dossier de recherche des ROM.
+ * Renvoie NULL en cas d'échec. */
+Pm2Core *pm2_core_create(const char *rom_dir);
+void pm2_core_destroy(Pm2Core *core);
+
+/* Nom court du jeu dans la base, par exemple "vf2" */
+Pm2Status pm2_load_game(Pm2Core *core, const char *game);
+Pm2Status pm2_run_frame(Pm2Core *core);
+
+/* Pixels RGBA8 de la dernière frame à la résolution native, valides jusqu'au
+   prochain appel sur la machine */
+const uint8_t *pm2_framebuffer(const Pm2Core *core, uint32_t *width, uint32_t *height);
+
+/* player : 0 à 3 (places au-delà de celles du jeu ignorées) ;
+ * buttons : combinaison de PM2_BUTTON_* */
+Pm2Status pm2_set_input(Pm2Core *core, uint32_t player, uint32_t buttons);
+
+/* Copie au plus capacity commandes reçues depuis le dernier appel ;
+ * renvoie le nombre copié, les suivantes restent pour le prochain appel */
+uint32_t pm2_take_force_feedback(Pm2Core *core, Pm2ForceFeedback *events, uint32_t capacity);
+
+/* Lampes de la borne, un bit par lampe (PM2_LAMP_*) */
+#define PM2_LAMP_START1 (1u << 0)
+#define PM2_LAMP_START2 (1u << 1)
+#define PM2_LAMP_VIEW1  (1u << 2)
+#define PM2_LAMP_VIEW2  (1u << 3)
+#define PM2_LAMP_VIEW3  (1u << 4)
+#define PM2_LAMP_VIEW4  (1u << 5)
+#define PM2_LAMP_LEADER (1u << 6)
+#define PM2_LAMP_SEAT1  (1u << 7)
+#define PM2_LAMP_SEAT2  (1u << 8)
+uint32_t pm2_lamps(const Pm2Core *core);
+
+Pm2Status pm2_save_state(Pm2Core *core, const char *path);
+Pm2Status pm2_load_state(Pm2Core *core, const char *path);
+
+/* Message UTF-8 de la dernière erreur, NULL s'il n'y en a pas */
+const char *pm2_last_error(const Pm2Core *core);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PIXEL_MODEL2_H */
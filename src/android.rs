@@ -0,0 +1,36 @@
+//! Point d'entrée Android
+//!
+//! La bibliothèque est chargée par une NativeActivity, qui appelle
+//! `android_main`. La configuration est rangée dans le dossier privé de
+//! l'application ; sauvegardes, NVRAM et ROM (`roms/`) dans son dossier de
+//! stockage externe, où l'utilisateur peut copier ses fichiers. Les commandes
+//! tactiles sont actives par défaut sur cette plateforme. La vibration passe
+//! par l'API Java de l'appareil : l'application hôte la branche avec
+//! [`TouchControls::set_haptics`](crate::input::TouchControls::set_haptics).
+
+use std::path::PathBuf;
+use winit::event_loop::EventLoopBuilder;
+use winit::platform::android::activity::AndroidApp;
+use winit::platform::android::EventLoopBuilderExtAndroid;
+
+use crate::config::AppPaths;
+use crate::gui::EmulatorApp;
+
+#[no_mangle]
+fn android_main(app: AndroidApp) {
+    let config_dir = app.internal_data_path().unwrap_or_else(|| PathBuf::from("."));
+    let data_dir = app.external_data_path().unwrap_or_else(|| config_dir.clone());
+    let paths = AppPaths::from_roots(config_dir, data_dir);
+
+    let event_loop = match EventLoopBuilder::new().with_android_app(app).build() {
+        Ok(event_loop) => event_loop,
+        Err(e) => {
+            log::error!("Boucle d'événements Android indisponible: {}", e);
+            return;
+        }
+    };
+    let result = EmulatorApp::new(None, paths).and_then(|emulator| emulator.run_with(event_loop));
+    if let Err(e) = result {
+        log::error!("Arrêt de l'émulateur: {:#}", e);
+    }
+}
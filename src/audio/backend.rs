@@ -0,0 +1,228 @@
+//! Abstraction des sorties audio
+//!
+//! Le SCSP produit des échantillons entrelacés qu'il pousse vers un
+//! [`AudioBackend`]. Trois implémentations existent :
+//! - [`AudioOutput`](super::AudioOutput) (feature `audio-cpal`) : périphérique réel via cpal ;
+//! - `WebAudioBackend` (feature `web`, cible wasm32) : Web Audio d'un navigateur ;
+//! - [`NullBackend`] : consomme et jette les échantillons, pour les tests, les
+//!   serveurs et les benchmarks.
+//!
+//! Tous les chemins génèrent exactement les mêmes échantillons, ce qui garde
+//! un timing d'émulation identique avec ou sans matériel audio.
+
+use crate::config::{AudioBackendKind, AudioConfig};
+
+/// Fréquence utilisée par le backend nul et le puits muet
+pub const NULL_SINK_SAMPLE_RATE: u32 = 44_100;
+
+/// `Send` hors wasm32, pour que le cœur puisse tourner sur un thread de
+/// travail ; les objets Web Audio ne quittent jamais le thread du navigateur
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send> MaybeSend for T {}
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T> MaybeSend for T {}
+
+/// Sortie audio recevant les échantillons produits par l'émulation
+pub trait AudioBackend: MaybeSend {
+    /// Nom du backend (journalisation, diagnostics)
+    fn name(&self) -> &str;
+
+    /// Fréquence de sortie en Hz
+    fn sample_rate(&self) -> u32;
+
+    /// Nombre de canaux entrelacés
+    fn channels(&self) -> u16;
+
+    /// Pousse des échantillons entrelacés vers la sortie
+    fn push_samples(&mut self, samples: &[f32]);
+
+    /// Nombre de frames en attente de lecture
+    fn queued_frames(&self) -> usize;
+
+    /// Nombre total de frames consommées depuis l'ouverture
+    fn consumed_frames(&self) -> u64;
+
+    /// La sortie consomme les échantillons au rythme d'une horloge matérielle
+    /// (le remplissage de sa file sert alors au contrôle de débit)
+    fn is_realtime(&self) -> bool {
+        false
+    }
+
+    /// Reprend la sortie après une erreur ; retourne `true` si le format a pu changer
+    fn recover_if_needed(&mut self) -> bool {
+        false
+    }
+
+    /// Signale un changement de la liste des périphériques : une sortie muette
+    /// faute de périphérique retente son ouverture
+    fn devices_changed(&mut self) {}
+}
+
+/// Paramètres de la sortie audio demandés par la configuration
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OutputSettings {
+    /// Nom du périphérique (périphérique par défaut si absent)
+    pub device: Option<String>,
+
+    /// Fréquence souhaitée (fréquence par défaut du périphérique si non supportée)
+    pub sample_rate: Option<u32>,
+
+    /// Taille du buffer matériel en frames (prioritaire sur `latency_ms`)
+    pub buffer_frames: Option<u32>,
+
+    /// Latence cible en millisecondes
+    pub latency_ms: Option<u32>,
+}
+
+impl OutputSettings {
+    /// Construit les paramètres à partir de la configuration utilisateur
+    pub fn from_config(config: &AudioConfig) -> Self {
+        Self {
+            device: config.device.clone(),
+            sample_rate: Some(config.sample_rate),
+            buffer_frames: config.buffer_size,
+            latency_ms: Some(config.latency_ms),
+        }
+    }
+
+    /// Taille de buffer souhaitée pour une fréquence donnée, en frames
+    pub fn buffer_frames_for(&self, sample_rate: u32) -> Option<u32> {
+        self.buffer_frames.or_else(|| {
+            self.latency_ms
+                .filter(|&ms| ms > 0)
+                .map(|ms| (sample_rate as u64 * ms as u64 / 1000).max(1) as u32)
+        })
+    }
+}
+
+/// Liste les noms des périphériques de sortie (aucun sans la feature `audio-cpal`)
+#[cfg(not(feature = "audio-cpal"))]
+pub fn output_device_names() -> Vec<String> {
+    Vec::new()
+}
+
+/// Backend sans matériel : les échantillons sont comptés puis jetés
+#[derive(Debug, Clone)]
+pub struct NullBackend {
+    sample_rate: u32,
+    channels: u16,
+    consumed_frames: u64,
+}
+
+impl NullBackend {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            consumed_frames: 0,
+        }
+    }
+}
+
+impl Default for NullBackend {
+    fn default() -> Self {
+        Self::new(NULL_SINK_SAMPLE_RATE, 2)
+    }
+}
+
+impl AudioBackend for NullBackend {
+    fn name(&self) -> &str {
+        "null"
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn push_samples(&mut self, samples: &[f32]) {
+        self.consumed_frames += (samples.len() / self.channels as usize) as u64;
+    }
+
+    fn queued_frames(&self) -> usize {
+        0
+    }
+
+    fn consumed_frames(&self) -> u64 {
+        self.consumed_frames
+    }
+}
+
+/// Crée le backend demandé par la configuration, sur un thread dédié si
+/// `output_thread` est activé.
+///
+/// Sans la feature `audio-cpal`, le backend nul est toujours utilisé.
+pub fn create_backend(config: &AudioConfig) -> Box<dyn AudioBackend> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if config.output_thread && config.enabled {
+        let config = AudioConfig { output_thread: false, ..config.clone() };
+        return Box::new(super::ThreadedBackend::spawn(move || create_backend(&config)));
+    }
+
+    let settings = OutputSettings::from_config(config);
+    let null = || -> Box<dyn AudioBackend> {
+        Box::new(NullBackend::new(settings.sample_rate.unwrap_or(NULL_SINK_SAMPLE_RATE), 2))
+    };
+
+    if !config.enabled {
+        return null();
+    }
+
+    match config.backend {
+        AudioBackendKind::Null => null(),
+        #[cfg(feature = "audio-cpal")]
+        AudioBackendKind::Cpal => Box::new(super::AudioOutput::open(settings)),
+        #[cfg(not(feature = "audio-cpal"))]
+        AudioBackendKind::Cpal => {
+            log::warn!("Backend audio cpal non compilé (feature `audio-cpal`), sortie muette");
+            null()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_frames_from_latency() {
+        let settings = OutputSettings {
+            latency_ms: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(settings.buffer_frames_for(48_000), Some(960));
+
+        let explicit = OutputSettings {
+            buffer_frames: Some(256),
+            latency_ms: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(explicit.buffer_frames_for(48_000), Some(256));
+    }
+
+    #[test]
+    fn test_null_backend_consumes_frames() {
+        let mut backend = NullBackend::new(48_000, 2);
+        backend.push_samples(&[0.0; 960]);
+        assert_eq!(backend.consumed_frames(), 480);
+        assert_eq!(backend.queued_frames(), 0);
+    }
+
+    #[test]
+    fn test_null_backend_selected_from_config() {
+        let mut config = crate::config::EmulatorConfig::default().audio;
+        config.backend = AudioBackendKind::Null;
+        config.sample_rate = 32_000;
+
+        let backend = create_backend(&config);
+        assert_eq!(backend.name(), "null");
+        assert_eq!(backend.sample_rate(), 32_000);
+    }
+}
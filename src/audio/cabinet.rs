@@ -0,0 +1,305 @@
+//! Simulation des haut-parleurs de la borne
+//!
+//! Le mixeur du SCSP produit un signal stéréo « idéal » ; la borne
+//! l'entendait à travers ses haut-parleurs, dans une salle. Une chaîne de
+//! filtres placée après le mixeur en donne une approximation :
+//!
+//! 1. disposition des haut-parleurs : mono (somme des deux canaux), stéréo,
+//!    ou quatre haut-parleurs ramenés sur deux canaux, la paire du siège
+//!    étant plus sourde et légèrement en retard sur la paire avant ;
+//! 2. réponse des haut-parleurs : coupure des graves et des aigus
+//!    (filtres de Butterworth du second ordre) ;
+//! 3. réverbération de la salle (quatre filtres en peigne et deux passe-tout
+//!    par canal, à la manière de Freeverb).
+//!
+//! Les valeurs des profils sont choisies à l'oreille, pas mesurées sur des
+//! bornes.
+
+use crate::config::{AudioConfig, CabinetKind};
+
+/// Disposition des haut-parleurs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeakerLayout {
+    Mono,
+    Stereo,
+    /// Paire avant et paire du siège, mélangées sur deux canaux
+    Quad,
+}
+
+/// Paramètres de la chaîne de filtres
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CabinetProfile {
+    pub layout: SpeakerLayout,
+    /// Coupure des graves, en Hz
+    pub low_cut_hz: f32,
+    /// Coupure des aigus, en Hz
+    pub high_cut_hz: f32,
+    /// Part de réverbération, de 0.0 à 1.0
+    pub reverb: f32,
+}
+
+impl CabinetProfile {
+    /// Profil d'un type de borne
+    pub fn preset(kind: CabinetKind) -> Self {
+        let (layout, low_cut_hz, high_cut_hz, reverb) = match kind {
+            CabinetKind::Mono => (SpeakerLayout::Mono, 150.0, 6_000.0, 0.05),
+            CabinetKind::Upright => (SpeakerLayout::Stereo, 100.0, 8_000.0, 0.08),
+            CabinetKind::Twin => (SpeakerLayout::Stereo, 80.0, 9_000.0, 0.12),
+            CabinetKind::Deluxe => (SpeakerLayout::Quad, 60.0, 10_000.0, 0.15),
+        };
+        Self { layout, low_cut_hz, high_cut_hz, reverb }
+    }
+
+    /// Profil retenu pour un jeu par la configuration ; `game_default` est
+    /// la borne du jeu dans la base des jeux. None : sortie inchangée.
+    pub fn from_config(config: &AudioConfig, game: Option<&str>, game_default: Option<CabinetKind>) -> Option<Self> {
+        let mut profile = Self::preset(config.cabinet_for(game).kind(game_default)?);
+        let cabinet = &config.cabinet;
+        profile.low_cut_hz = cabinet.low_cut_hz.unwrap_or(profile.low_cut_hz);
+        profile.high_cut_hz = cabinet.high_cut_hz.unwrap_or(profile.high_cut_hz);
+        profile.reverb = cabinet.reverb.unwrap_or(profile.reverb).clamp(0.0, 1.0);
+        Some(profile)
+    }
+}
+
+/// Filtre du second ordre (forme directe II transposée)
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f32; 3],
+    a: [f32; 2],
+    state: [f32; 2],
+}
+
+impl Biquad {
+    /// Passe-bas ou passe-haut de Butterworth à `frequency` Hz
+    fn butterworth(sample_rate: u32, frequency: f32, high_pass: bool) -> Self {
+        let nyquist = sample_rate as f32 / 2.0;
+        let omega = std::f32::consts::TAU * frequency.clamp(10.0, nyquist * 0.95) / sample_rate as f32;
+        let alpha = omega.sin() / std::f32::consts::SQRT_2;
+        let cos = omega.cos();
+        let a0 = 1.0 + alpha;
+        let b = if high_pass {
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0]
+        } else {
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0]
+        };
+        Self {
+            b: b.map(|b| b / a0),
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            state: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.b[0] * input + self.state[0];
+        self.state[0] = self.b[1] * input - self.a[0] * output + self.state[1];
+        self.state[1] = self.b[2] * input - self.a[1] * output;
+        output
+    }
+}
+
+/// Ligne à retard circulaire
+#[derive(Debug, Clone)]
+struct Delay {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl Delay {
+    fn new(length: usize) -> Self {
+        Self { buffer: vec![0.0; length.max(1)], index: 0 }
+    }
+
+    /// Échantillon sorti de la ligne, remplacé par `input`
+    fn exchange(&mut self, input: f32) -> f32 {
+        let output = std::mem::replace(&mut self.buffer[self.index], input);
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+
+    fn front(&self) -> f32 {
+        self.buffer[self.index]
+    }
+}
+
+/// Longueurs des filtres de la réverbération à 44,1 kHz (Freeverb)
+const COMB_LENGTHS: [usize; 4] = [1116, 1188, 1277, 1356];
+const ALLPASS_LENGTHS: [usize; 2] = [556, 441];
+/// Décalage des longueurs du canal droit, pour élargir l'image stéréo
+const STEREO_SPREAD: usize = 23;
+
+const REVERB_FEEDBACK: f32 = 0.78;
+const REVERB_DAMPING: f32 = 0.3;
+const REVERB_INPUT_GAIN: f32 = 0.03;
+
+/// Réverbération d'un canal
+#[derive(Debug, Clone)]
+struct Reverb {
+    combs: Vec<(Delay, f32)>,
+    allpasses: Vec<Delay>,
+}
+
+impl Reverb {
+    fn new(sample_rate: u32, spread: usize) -> Self {
+        let scale = |length: usize| (length + spread) * sample_rate as usize / 44_100;
+        Self {
+            combs: COMB_LENGTHS.iter().map(|&length| (Delay::new(scale(length)), 0.0)).collect(),
+            allpasses: ALLPASS_LENGTHS.iter().map(|&length| Delay::new(scale(length))).collect(),
+        }
+    }
+
+    /// Signal réverbéré seul
+    fn process(&mut self, input: f32) -> f32 {
+        let input = input * REVERB_INPUT_GAIN;
+        let mut output = 0.0;
+        for (comb, filtered) in &mut self.combs {
+            let delayed = comb.front();
+            *filtered = delayed * (1.0 - REVERB_DAMPING) + *filtered * REVERB_DAMPING;
+            comb.exchange(input + *filtered * REVERB_FEEDBACK);
+            output += delayed;
+        }
+        for allpass in &mut self.allpasses {
+            let delayed = allpass.front();
+            allpass.exchange(output + delayed * 0.5);
+            output = delayed - output;
+        }
+        output
+    }
+}
+
+/// Étape de la chaîne, appliquée aux deux canaux
+#[derive(Debug, Clone)]
+enum Stage {
+    /// Somme des deux canaux sur chacun
+    Mono,
+    /// Paire du siège : passe-bas et retard, ajoutée à la paire avant
+    SeatSpeakers { filters: [Biquad; 2], delays: [Delay; 2] },
+    Filter([Biquad; 2]),
+    Reverb { mix: f32, channels: [Reverb; 2] },
+}
+
+/// Fréquence de coupure des haut-parleurs du siège
+const SEAT_HIGH_CUT_HZ: f32 = 2_500.0;
+/// Retard de la paire du siège sur la paire avant, en secondes
+const SEAT_DELAY: f32 = 0.006;
+/// Niveau de la paire du siège face à la paire avant
+const SEAT_GAIN: f32 = 0.7;
+
+/// Chaîne de filtres de la sortie ; vide (sortie inchangée) par défaut
+#[derive(Debug, Clone, Default)]
+pub struct CabinetChain {
+    stages: Vec<Stage>,
+}
+
+impl CabinetChain {
+    pub fn new(profile: &CabinetProfile, sample_rate: u32) -> Self {
+        let sample_rate = sample_rate.max(1);
+        let mut stages = Vec::new();
+        match profile.layout {
+            SpeakerLayout::Mono => stages.push(Stage::Mono),
+            SpeakerLayout::Stereo => {}
+            SpeakerLayout::Quad => {
+                let delay = (SEAT_DELAY * sample_rate as f32) as usize;
+                stages.push(Stage::SeatSpeakers {
+                    filters: [Biquad::butterworth(sample_rate, SEAT_HIGH_CUT_HZ, false); 2],
+                    delays: [Delay::new(delay), Delay::new(delay)],
+                });
+            }
+        }
+        stages.push(Stage::Filter([Biquad::butterworth(sample_rate, profile.low_cut_hz, true); 2]));
+        stages.push(Stage::Filter([Biquad::butterworth(sample_rate, profile.high_cut_hz, false); 2]));
+        if profile.reverb > 0.0 {
+            stages.push(Stage::Reverb {
+                mix: profile.reverb,
+                channels: [Reverb::new(sample_rate, 0), Reverb::new(sample_rate, STEREO_SPREAD)],
+            });
+        }
+        Self { stages }
+    }
+
+    /// Chaîne d'un profil, ou chaîne vide sans profil
+    pub fn for_profile(profile: Option<&CabinetProfile>, sample_rate: u32) -> Self {
+        profile.map(|profile| Self::new(profile, sample_rate)).unwrap_or_default()
+    }
+
+    /// Vrai si la sortie n'est pas modifiée
+    pub fn is_bypassed(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Traite une trame stéréo
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let mut frame = [left, right];
+        for stage in &mut self.stages {
+            match stage {
+                Stage::Mono => frame = [(frame[0] + frame[1]) / 2.0; 2],
+                Stage::SeatSpeakers { filters, delays } => {
+                    for channel in 0..2 {
+                        let seat = delays[channel].exchange(filters[channel].process(frame[channel]));
+                        frame[channel] = (frame[channel] + seat * SEAT_GAIN) / (1.0 + SEAT_GAIN);
+                    }
+                }
+                Stage::Filter(filters) => {
+                    for channel in 0..2 {
+                        frame[channel] = filters[channel].process(frame[channel]);
+                    }
+                }
+                Stage::Reverb { mix, channels } => {
+                    for channel in 0..2 {
+                        let wet = channels[channel].process(frame[channel]);
+                        frame[channel] = frame[channel] * (1.0 - *mix) + wet * *mix;
+                    }
+                }
+            }
+        }
+        (frame[0], frame[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CabinetSelection;
+
+    #[test]
+    fn test_cabinet_chain() {
+        // Sans profil : sortie identique
+        let mut chain = CabinetChain::default();
+        assert!(chain.is_bypassed());
+        assert_eq!(chain.process(0.25, -0.5), (0.25, -0.5));
+
+        // Profil du jeu, réglages de la configuration prioritaires
+        let mut config = crate::config::EmulatorConfig::default().audio;
+        assert_eq!(CabinetProfile::from_config(&config, Some("daytona"), Some(CabinetKind::Deluxe)), None);
+        config.cabinet.profile = CabinetSelection::Auto;
+        config.cabinet.reverb = Some(0.0);
+        let profile = CabinetProfile::from_config(&config, Some("daytona"), Some(CabinetKind::Deluxe)).unwrap();
+        assert_eq!(profile.layout, SpeakerLayout::Quad);
+        assert_eq!(profile.reverb, 0.0);
+        config.game_cabinet.insert("daytona".to_string(), CabinetSelection::Mono);
+        let mono = CabinetProfile::from_config(&config, Some("daytona"), Some(CabinetKind::Deluxe)).unwrap();
+        assert_eq!(mono.layout, SpeakerLayout::Mono);
+
+        // Mono : canaux identiques ; le continu est coupé par le passe-haut
+        let mut chain = CabinetChain::new(&mono, 44_100);
+        let frames: Vec<(f32, f32)> = (0..44_100).map(|_| chain.process(1.0, 0.0)).collect();
+        assert!(frames.iter().all(|(left, right)| left == right));
+        assert!(frames[44_099].0.abs() < 1e-3);
+
+        // Le passe-bas atténue la fréquence de Nyquist
+        let mut chain = CabinetChain::new(&CabinetProfile::preset(CabinetKind::Upright), 44_100);
+        let peak = (0..4_410)
+            .map(|n| chain.process(if n % 2 == 0 { 1.0 } else { -1.0 }, 0.0).0.abs())
+            .skip(100)
+            .fold(0.0f32, f32::max);
+        assert!(peak < 0.05, "{}", peak);
+
+        // Réverbération : une queue après l'impulsion, qui s'éteint
+        let mut chain = CabinetChain::new(&CabinetProfile::preset(CabinetKind::Deluxe), 48_000);
+        chain.process(1.0, 1.0);
+        let tail: Vec<f32> = (0..96_000).map(|_| chain.process(0.0, 0.0).1).collect();
+        assert!(tail[..9_600].iter().any(|sample| sample.abs() > 1e-4));
+        assert!(tail.iter().all(|sample| sample.is_finite()));
+        assert!(tail[86_400..].iter().all(|sample| sample.abs() < 1e-4));
+    }
+}
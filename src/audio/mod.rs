@@ -1,9 +1,51 @@
 //! Système audio SCSP (Saturn Custom Sound Processor) pour Model 2
 
-use anyhow::Result;
-use cpal::{traits::{HostTrait, DeviceTrait, StreamTrait}, Stream, StreamConfig};
+pub mod pitch;
+pub mod cabinet;
+pub mod time_stretch;
+pub mod pan;
+pub mod rate_control;
+pub mod backend;
+pub mod sound_hle;
+pub mod sample_clock;
+pub mod slot_registers;
+pub mod wave_inspector;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod output_thread;
+#[cfg(feature = "audio-cpal")]
+pub mod output;
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+pub mod web;
+
+pub use pitch::*;
+pub use cabinet::*;
+pub use time_stretch::*;
+pub use pan::*;
+pub use rate_control::*;
+pub use backend::*;
+pub use sound_hle::*;
+pub use sample_clock::*;
+pub use slot_registers::*;
+pub use wave_inspector::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use output_thread::*;
+#[cfg(feature = "audio-cpal")]
+pub use output::*;
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+pub use web::*;
+
+use crate::error::Result;
 use std::collections::VecDeque;
 
+/// Taille de la mémoire wave du SCSP
+pub const WAVE_MEMORY_SIZE: usize = 2 * 1024 * 1024;
+
+/// Nombre de slots du SCSP
+pub const SCSP_SLOT_COUNT: usize = 32;
+
+/// Début des registres communs, après les 32 blocs de slots
+pub const COMMON_REGISTER_BASE: u32 = SCSP_SLOT_COUNT as u32 * SLOT_REGISTER_SIZE;
+
 /// Registres SCSP (Saturn Custom Sound Processor)
 #[derive(Debug, Clone)]
 pub struct ScspRegisters {
@@ -29,33 +71,6 @@ pub struct ScspRegisters {
     pub wave_memory: Vec<u8>,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct SlotRegisters {
-    /// Volume du slot
-    pub volume: u16,
-
-    /// Fréquence du slot
-    pub frequency: u16,
-
-    /// Adresse de début dans la mémoire wave
-    pub start_address: u32,
-
-    /// Adresse de fin dans la mémoire wave
-    pub end_address: u32,
-
-    /// Adresse de boucle
-    pub loop_address: u32,
-
-    /// Contrôle du slot (attaque, decay, sustain, release)
-    pub control: u16,
-
-    /// Panoramique (gauche/droite)
-    pub pan: u16,
-
-    /// Type d'onde (PCM, noise, etc.)
-    pub wave_type: u8,
-}
-
 /// État d'un slot audio
 #[derive(Debug, Clone)]
 struct SlotState {
@@ -97,7 +112,8 @@ impl Default for EnvelopePhase {
 pub struct ScspAudio {
     sample_rate: u32,
     channels: u16,
-    _stream: Stream,
+    /// Sortie audio (périphérique cpal ou backend nul)
+    output: Box<dyn AudioBackend>,
     pub volume: f32,
     
     /// Registres SCSP
@@ -114,112 +130,319 @@ pub struct ScspAudio {
     
     /// Horloge interne
     clock_counter: u64,
+
+    /// Mode d'interpolation des échantillons PCM
+    interpolation: Interpolation,
+
+    /// Compensation de dérive entre l'émulation et la carte son
+    rate_control: RateControl,
+
+    /// Conversion des cycles écoulés en échantillons
+    sample_clock: SampleClock,
+
+    /// Fréquence native du SCSP, qui suit son horloge
+    native_rate: u32,
+
+    /// Fraction d'échantillon ajoutée ou retirée par le contrôle de débit,
+    /// reportée à la prochaine mise à jour
+    pending_samples: f32,
+
+    /// Trames transmises à la sortie lors de la dernière mise à jour
+    last_submitted_frames: u32,
+
+    /// Haut-parleurs de la borne simulés après le mixeur, si activés
+    cabinet_profile: Option<CabinetProfile>,
+    cabinet: CabinetChain,
+
+    /// Ramène à la durée réelle le son produit en avance rapide ou au ralenti
+    time_stretch: TimeStretch,
+    /// Trames étirées en attente d'envoi (réutilisé d'une mise à jour à l'autre)
+    stretched: Vec<f32>,
 }
 
 impl ScspAudio {
     pub fn new() -> Result<Self> {
-        let host = cpal::default_host();
-        let device = host.default_output_device()
-            .ok_or_else(|| anyhow::anyhow!("Aucun périphérique audio disponible"))?;
-        
-        let config = device.default_output_config()?;
-        let sample_rate = config.sample_rate().0;
-        let channels = config.channels();
-        
-        let stream_config = StreamConfig {
-            channels,
-            sample_rate: cpal::SampleRate(sample_rate),
-            buffer_size: cpal::BufferSize::Default,
+        Ok(Self::with_config(&crate::config::EmulatorConfig::default().audio))
+    }
+
+    /// Crée le processeur sonore avec la sortie décrite par la configuration
+    pub fn with_config(config: &crate::config::AudioConfig) -> Self {
+        let mut audio = Self::with_backend(create_backend(config));
+        audio.set_volume(config.volume);
+        audio.rate_control = if config.dynamic_rate {
+            RateControl::new(config.latency_ms, MAX_RATE_DELTA)
+        } else {
+            RateControl::disabled()
         };
-        
+        audio.set_stretch_mode(if config.time_stretch { StretchMode::Wsola } else { StretchMode::Resample });
+        audio.set_interpolation(config.interpolation);
+        audio
+    }
+
+    /// Crée le processeur sonore sans sortie matérielle
+    pub fn headless() -> Self {
+        Self::with_backend(Box::new(NullBackend::default()))
+    }
+
+    /// Crée le processeur sonore sur un backend déjà ouvert
+    pub fn with_backend(output: Box<dyn AudioBackend>) -> Self {
+        let sample_rate = output.sample_rate();
+        let channels = output.channels();
         let buffer_size = (sample_rate / 60) as usize * channels as usize; // Buffer pour ~1 frame à 60Hz
-        
-        let mut audio = Self {
+
+        Self {
             sample_rate,
             channels,
-            _stream: device.build_output_stream(
-                &stream_config,
-                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                    // Le callback sera configuré après l'initialisation
-                    for sample in data.iter_mut() {
-                        *sample = 0.0;
-                    }
-                },
-                move |err| eprintln!("Erreur audio: {}", err),
-                None,
-            )?,
+            output,
             volume: 1.0,
             registers: ScspRegisters::new(),
             slot_states: Default::default(),
             output_buffer: VecDeque::with_capacity(buffer_size * 2),
             buffer_size,
             clock_counter: 0,
-        };
-        
-        // Démarrer le stream audio
-        audio._stream.play()?;
-        
-        Ok(audio)
+            interpolation: Interpolation::default(),
+            rate_control: RateControl::default(),
+            sample_clock: SampleClock::new(crate::MAIN_CPU_FREQUENCY, sample_rate),
+            native_rate: SCSP_SAMPLE_RATE,
+            pending_samples: 0.0,
+            last_submitted_frames: 0,
+            cabinet_profile: None,
+            cabinet: CabinetChain::default(),
+            time_stretch: TimeStretch::new(sample_rate, channels),
+            stretched: Vec::new(),
+        }
+    }
+
+    /// Sortie audio courante
+    pub fn output(&self) -> &dyn AudioBackend {
+        self.output.as_ref()
+    }
+
+    /// Signale un changement de périphériques audio à la sortie
+    pub fn devices_changed(&mut self) {
+        self.output.devices_changed();
+    }
+
+    /// Reprend la sortie après un changement ou une perte de périphérique
+    fn recover_output(&mut self) {
+        if !self.output.recover_if_needed() {
+            return;
+        }
+
+        let sample_rate = self.output.sample_rate();
+        self.channels = self.output.channels();
+        self.buffer_size = (sample_rate / 60) as usize * self.channels as usize;
+        self.output_buffer.clear();
+        self.rate_control.reset();
+        self.rebuild_time_stretch(sample_rate);
+
+        // Les pas de lecture dépendent de la fréquence de sortie
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.sample_clock.set_sample_rate(sample_rate);
+            self.update_slot_steps();
+            self.cabinet = CabinetChain::for_profile(self.cabinet_profile.as_ref(), sample_rate);
+        }
+    }
+
+    /// Simule les haut-parleurs d'une borne après le mixeur ; None rend la
+    /// sortie du mixeur inchangée
+    pub fn set_cabinet(&mut self, profile: Option<CabinetProfile>) {
+        if profile != self.cabinet_profile {
+            self.cabinet = CabinetChain::for_profile(profile.as_ref(), self.sample_rate);
+            self.cabinet_profile = profile;
+        }
+    }
+
+    /// Haut-parleurs simulés
+    pub fn cabinet(&self) -> Option<&CabinetProfile> {
+        self.cabinet_profile.as_ref()
+    }
+
+    /// Vitesse d'émulation : le son produit en `speed` fois le temps réel est
+    /// ramené à la durée réelle selon le mode d'étirement
+    pub fn set_speed(&mut self, speed: f32) {
+        self.time_stretch.set_speed(speed);
+    }
+
+    /// Vitesse d'émulation courante
+    pub fn speed(&self) -> f32 {
+        self.time_stretch.speed()
+    }
+
+    /// Conserve la hauteur hors de la vitesse normale ([`StretchMode::Wsola`])
+    /// ou la laisse suivre la vitesse ([`StretchMode::Resample`])
+    pub fn set_stretch_mode(&mut self, mode: StretchMode) {
+        self.time_stretch.set_mode(mode);
+    }
+
+    /// Mode d'étirement courant
+    pub fn stretch_mode(&self) -> StretchMode {
+        self.time_stretch.mode()
+    }
+
+    /// Recrée l'étage d'étirement pour la sortie courante, réglages conservés
+    fn rebuild_time_stretch(&mut self, sample_rate: u32) {
+        let mut time_stretch = TimeStretch::new(sample_rate, self.channels);
+        time_stretch.set_speed(self.time_stretch.speed());
+        time_stretch.set_mode(self.time_stretch.mode());
+        self.time_stretch = time_stretch;
+    }
+
+    /// Fréquence native du SCSP, tirée de son horloge : hors de 44,1 kHz,
+    /// tous les slots jouent plus haut ou plus bas dans la même proportion
+    pub fn set_native_rate(&mut self, rate: u32) {
+        if rate != self.native_rate {
+            self.native_rate = rate.max(1);
+            self.update_slot_steps();
+        }
+    }
+
+    /// Pas de lecture d'un slot à la fréquence `frequency`
+    fn slot_step(&self, frequency: u16) -> f32 {
+        playback_step(frequency, self.sample_rate) * self.native_rate as f32 / SCSP_SAMPLE_RATE as f32
+    }
+
+    fn update_slot_steps(&mut self) {
+        for slot_id in 0..SCSP_SLOT_COUNT {
+            self.slot_states[slot_id].speed = self.slot_step(self.registers.slot_registers[slot_id].frequency);
+        }
     }
     
     pub fn set_volume(&mut self, volume: f32) {
         self.volume = volume.clamp(0.0, 1.0);
     }
     
-    /// Met à jour l'émulation audio (appelé périodiquement)
+    /// Sélectionne le mode d'interpolation des échantillons PCM
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+    }
+
+    /// Mode d'interpolation courant
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Trames (un échantillon par canal) transmises lors de la dernière mise à jour
+    pub fn last_submitted_frames(&self) -> u32 {
+        self.last_submitted_frames
+    }
+
+    /// Fréquence de l'horloge maître qui cadence la génération : les cycles
+    /// passés à [`update`](Self::update) sont comptés à cette fréquence
+    pub fn set_master_clock(&mut self, frequency: u32) {
+        if frequency != self.sample_clock.source_frequency() {
+            self.sample_clock.set_source_frequency(frequency);
+        }
+    }
+
+    /// Rapport de rééchantillonnage appliqué par le contrôle de débit
+    pub fn rate_ratio(&self) -> f32 {
+        self.rate_control.ratio()
+    }
+
+    /// Reset du processeur sonore : registres et slots reviennent à leur
+    /// état de démarrage, les échantillons en attente sont abandonnés. La
+    /// sortie, le volume, l'interpolation, le profil de borne et la vitesse
+    /// sont conservés ; la réverbération en cours est coupée.
+    pub fn reset(&mut self) {
+        self.registers = ScspRegisters::new();
+        self.slot_states = Default::default();
+        self.output_buffer.clear();
+        self.clock_counter = 0;
+        self.sample_clock.reset();
+        self.pending_samples = 0.0;
+        self.rate_control.reset();
+        self.cabinet = CabinetChain::for_profile(self.cabinet_profile.as_ref(), self.sample_rate);
+        self.time_stretch.clear();
+    }
+
+    /// Met à jour l'émulation audio : produit exactement les échantillons
+    /// correspondant aux `cycles` écoulés de l'horloge maître
     pub fn update(&mut self, cycles: u32) {
         self.clock_counter = self.clock_counter.wrapping_add(cycles as u64);
+
+        // Rouvrir la sortie si le périphérique a disparu ou changé
+        self.recover_output();
         
         // Générer des échantillons audio
-        self.generate_audio_samples();
-        
-        // Mettre à jour les enveloppes des slots
-        self.update_envelopes();
+        self.generate_audio_samples(cycles);
+
+        // Transmettre les échantillons au backend de sortie, ramenés à la
+        // durée réelle hors de la vitesse normale
+        let (head, tail) = self.output_buffer.as_slices();
+        if self.time_stretch.is_bypassed() {
+            self.last_submitted_frames = (self.output_buffer.len() / self.channels.max(1) as usize) as u32;
+            self.output.push_samples(head);
+            self.output.push_samples(tail);
+        } else {
+            self.stretched.clear();
+            self.time_stretch.process(head, &mut self.stretched);
+            self.time_stretch.process(tail, &mut self.stretched);
+            self.last_submitted_frames = (self.stretched.len() / self.channels.max(1) as usize) as u32;
+            self.output.push_samples(&self.stretched);
+        }
+        self.output_buffer.clear();
+
+        // Ajuster le débit selon le remplissage de la file du périphérique
+        if self.output.is_realtime() {
+            self.rate_control.update(self.output.queued_frames(), self.sample_rate);
+        }
         
         // Nettoyer les slots inactifs
         self.cleanup_inactive_slots();
     }
     
-    /// Génère des échantillons audio
-    fn generate_audio_samples(&mut self) {
-        let samples_needed = (self.sample_rate as f32 / 44100.0 * 128.0) as usize; // ~128 échantillons à 44.1kHz
+    /// Génère les échantillons de `cycles` cycles de l'horloge maître
+    fn generate_audio_samples(&mut self, cycles: u32) {
+        // Nombre exact d'échantillons, corrigé par le contrôle de débit
+        let ratio = self.rate_control.ratio();
+        let exact = self.sample_clock.advance(cycles);
+        self.pending_samples += exact as f32 * (ratio - 1.0);
+        let correction = self.pending_samples.trunc();
+        self.pending_samples -= correction;
+        let samples_needed = (exact as i64 + correction as i64).max(0) as usize;
         
         for _ in 0..samples_needed {
             let mut left_sample = 0.0f32;
             let mut right_sample = 0.0f32;
             
-            // Collecter les données nécessaires pour éviter les conflits d'emprunt
-            let mut active_slots = Vec::new();
-            for slot_id in 0..32 {
-                if self.slot_states[slot_id].active {
-                    let slot_regs = self.registers.slot_registers[slot_id].clone();
-                    let slot_state_pos = self.slot_states[slot_id].position;
-                    let slot_state_speed = self.slot_states[slot_id].speed;
-                    let current_volume = self.slot_states[slot_id].current_volume;
-                    active_slots.push((slot_id, slot_regs, slot_state_pos, slot_state_speed, current_volume));
+            // Registres copiés slot par slot : pas de liste intermédiaire
+            // à allouer à chaque échantillon
+            for slot_id in 0..SCSP_SLOT_COUNT {
+                let state = &self.slot_states[slot_id];
+                if !state.active {
+                    continue;
                 }
-            }
-            
-            // Générer les échantillons pour chaque slot actif
-            for (slot_id, slot_regs, mut position, speed, current_volume) in active_slots {
+                let slot_regs = self.registers.slot_registers[slot_id];
+                let (mut position, speed, current_volume) = (state.position, state.speed, state.current_volume);
+
                 // Générer l'échantillon pour ce slot
-                let sample = self.generate_slot_sample_from_data(&slot_regs, &mut position, speed);
+                // Produire plus d'échantillons impose d'avancer moins vite dans l'onde
+                let sample = self.generate_slot_sample_from_data(&slot_regs, &mut position, speed / ratio);
                 
-                // Mettre à jour la position dans le slot state
+                // Mettre à jour la position dans le slot state ; sans
+                // boucle, le slot s'éteint à la fin de l'échantillon
                 self.slot_states[slot_id].position = position;
+                if !slot_regs.loops() && position >= slot_regs.loop_end() as f32 {
+                    self.slot_states[slot_id].active = false;
+                }
                 
                 // Appliquer le volume et le panoramique
-                let volume = (slot_regs.volume as f32 / 0xFFF as f32) * current_volume;
-                let pan = slot_regs.pan as f32 / 0x1F as f32; // 0-31 -> 0.0-1.0
+                let volume = (slot_regs.volume as f32 / 0xFFF as f32) * slot_regs.total_level_gain() * current_volume;
+                let (left_gain, right_gain) = pan_gains(slot_regs.direct_send_level, slot_regs.pan);
                 
-                left_sample += sample * volume * (1.0 - pan);
-                right_sample += sample * volume * pan;
+                left_sample += sample * volume * left_gain;
+                right_sample += sample * volume * right_gain;
             }
             
             // Appliquer le volume maître
             let master_volume = self.registers.master_volume as f32 / 0xFFF as f32;
             left_sample *= master_volume * self.volume;
             right_sample *= master_volume * self.volume;
+
+            // Haut-parleurs et salle de la borne
+            let (left_sample, right_sample) = self.cabinet.process(left_sample, right_sample);
             
             // Ajouter au buffer de sortie
             self.output_buffer.push_back(left_sample);
@@ -231,6 +454,9 @@ impl ScspAudio {
             while self.output_buffer.len() > self.buffer_size * 2 {
                 self.output_buffer.pop_front();
             }
+
+            // Les enveloppes avancent d'un pas par échantillon
+            self.update_envelopes();
         }
     }
     
@@ -248,9 +474,9 @@ impl ScspAudio {
         *position += speed;
         
         // Gestion de la boucle
-        if *position >= slot_regs.end_address as f32 {
+        if slot_regs.loops() && *position >= slot_regs.loop_end() as f32 {
             if slot_regs.loop_address < slot_regs.end_address {
-                *position = slot_regs.loop_address as f32;
+                *position = slot_regs.loop_start() as f32;
             } else {
                 *position = slot_regs.start_address as f32;
             }
@@ -261,13 +487,29 @@ impl ScspAudio {
     
     /// Génère un échantillon PCM avec données locales
     fn generate_pcm_sample_from_data(&self, slot_regs: &SlotRegisters, position: f32) -> f32 {
-        let addr = position as usize;
-        if addr < self.registers.wave_memory.len() {
-            // Convertir u8 en f32 (-1.0 à 1.0)
-            (self.registers.wave_memory[addr] as f32 - 128.0) / 128.0
+        let wave = &self.registers.wave_memory;
+        let start = slot_regs.start_address as i64;
+        let end = slot_regs.loop_end() as i64;
+        let loop_start = if slot_regs.loop_address < slot_regs.end_address {
+            slot_regs.loop_start() as i64
         } else {
-            0.0
-        }
+            start
+        };
+
+        self.interpolation.sample(position, |index| {
+            // Les échantillons voisins suivent la boucle du slot
+            let addr = if index >= end && end > loop_start {
+                loop_start + (index - end) % (end - loop_start)
+            } else {
+                index.max(start)
+            };
+
+            match wave.get(addr as usize) {
+                // Convertir u8 en f32 (-1.0 à 1.0)
+                Some(&byte) => (byte as f32 - 128.0) / 128.0,
+                None => 0.0,
+            }
+        })
     }
     
     /// Génère une onde carrée avec données locales
@@ -371,17 +613,54 @@ impl ScspAudio {
             return;
         }
         
+        let speed = self.slot_step(self.registers.slot_registers[slot_id].frequency);
         let slot_regs = &self.registers.slot_registers[slot_id];
         let slot_state = &mut self.slot_states[slot_id];
         
         slot_state.active = true;
         slot_state.position = slot_regs.start_address as f32;
-        slot_state.speed = slot_regs.frequency as f32 / 1000.0; // Ajuster selon les besoins
+        slot_state.speed = speed;
         slot_state.current_volume = 0.0;
         slot_state.envelope_phase = EnvelopePhase::Attack;
         slot_state.envelope_counter = 0;
     }
     
+    /// Programme un slot sur un échantillon de la mémoire wave et le démarre
+    pub fn play_sample(&mut self, slot_id: usize, start: u32, end: u32, loop_start: Option<u32>, frequency: u16, volume: u16) {
+        let Some(slot) = self.registers.slot_registers.get_mut(slot_id) else {
+            return;
+        };
+        *slot = SlotRegisters {
+            start_address: start,
+            end_address: end.saturating_sub(start),
+            loop_address: loop_start.map_or(0, |loop_start| loop_start.saturating_sub(start)),
+            loop_control: loop_start.is_some() as u8,
+            frequency,
+            volume: volume.min(0x0FFF),
+            ..SlotRegisters::default()
+        };
+        self.start_slot(slot_id);
+    }
+
+    /// Arrête tous les slots
+    pub fn stop_all_slots(&mut self) {
+        for slot_id in 0..32 {
+            self.stop_slot(slot_id);
+        }
+    }
+
+    /// Copie des données (ROM audio) au début de la mémoire wave
+    pub fn load_wave_memory(&mut self, data: &[u8]) {
+        let wave = &mut self.registers.wave_memory;
+        let length = data.len().min(wave.len());
+        wave[..length].copy_from_slice(&data[..length]);
+    }
+
+    /// Slot en cours de lecture
+    pub fn slot_active(&self, slot_id: usize) -> bool {
+        self.slot_states.get(slot_id).is_some_and(|state| state.active)
+    }
+
     /// Arrête un slot audio
     pub fn stop_slot(&mut self, slot_id: usize) {
         if slot_id >= 32 {
@@ -395,80 +674,112 @@ impl ScspAudio {
         }
     }
     
-    /// Lit un registre SCSP
-    pub fn read_register(&self, offset: u32) -> u32 {
-        match offset {
+    /// Lit un mot de 16 bits des registres SCSP : blocs de slots de 0x000 à
+    /// 0x3FF, puis registres communs (mot haut à l'offset pair inférieur)
+    pub fn read_register_u16(&self, offset: u32) -> u16 {
+        let offset = offset & !1;
+        if offset < COMMON_REGISTER_BASE {
+            let slot = &self.registers.slot_registers[(offset / SLOT_REGISTER_SIZE) as usize];
+            return slot.read_word(offset % SLOT_REGISTER_SIZE);
+        }
+
+        let common = match (offset - COMMON_REGISTER_BASE) & !3 {
             0x00 => self.registers.control,
             0x04 => self.registers.status,
             0x08 => self.registers.master_volume as u32,
             0x0C => self.registers.slot_control,
-            _ => {
-                // Registres de slots (0x10 - 0x1FF)
-                if offset >= 0x10 && offset < 0x200 {
-                    let slot_id = ((offset - 0x10) / 0x10) as usize;
-                    let reg_offset = (offset - 0x10) % 0x10;
-                    
-                    if slot_id < 32 {
-                        match reg_offset {
-                            0x00 => self.registers.slot_registers[slot_id].volume as u32,
-                            0x04 => self.registers.slot_registers[slot_id].frequency as u32,
-                            0x08 => self.registers.slot_registers[slot_id].start_address,
-                            0x0C => self.registers.slot_registers[slot_id].control as u32,
-                            _ => 0,
-                        }
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                }
-            }
+            _ => 0,
+        };
+        if offset & 2 == 0 {
+            (common >> 16) as u16
+        } else {
+            common as u16
         }
     }
-    
-    /// Écrit dans un registre SCSP
-    pub fn write_register(&mut self, offset: u32, value: u32) {
-        match offset {
-            0x00 => self.registers.control = value,
-            0x04 => self.registers.status = value,
-            0x08 => self.registers.master_volume = value as u16,
-            0x0C => self.registers.slot_control = value,
-            _ => {
-                // Registres de slots (0x10 - 0x1FF)
-                if offset >= 0x10 && offset < 0x200 {
-                    let slot_id = ((offset - 0x10) / 0x10) as usize;
-                    let reg_offset = (offset - 0x10) % 0x10;
-                    
-                    if slot_id < 32 {
-                        match reg_offset {
-                            0x00 => self.registers.slot_registers[slot_id].volume = value as u16,
-                            0x04 => self.registers.slot_registers[slot_id].frequency = value as u16,
-                            0x08 => self.registers.slot_registers[slot_id].start_address = value,
-                            0x0C => {
-                                self.registers.slot_registers[slot_id].control = value as u16;
-                                
-                                // Vérifier les bits de contrôle
-                                let key_on = (value & 0x1000) != 0;
-                                let key_off = (value & 0x2000) != 0;
-                                
-                                if key_on {
-                                    self.start_slot(slot_id);
-                                } else if key_off {
-                                    self.stop_slot(slot_id);
-                                }
-                            },
-                            _ => {}
-                        }
-                    }
+
+    /// Écrit un mot de 16 bits des registres SCSP ; un mot 0x00 de slot avec
+    /// KYONEX applique les KYONB de tous les slots
+    pub fn write_register_u16(&mut self, offset: u32, value: u16) {
+        let offset = offset & !1;
+        if offset < COMMON_REGISTER_BASE {
+            let word = offset % SLOT_REGISTER_SIZE;
+            self.registers.slot_registers[(offset / SLOT_REGISTER_SIZE) as usize].write_word(word, value);
+
+            // Le changement de hauteur s'applique aussi aux slots en cours
+            if word == 0x10 {
+                let slot_id = (offset / SLOT_REGISTER_SIZE) as usize;
+                self.slot_states[slot_id].speed = self.slot_step(value);
+            }
+            if word == 0x00 && value & KEY_EXECUTE != 0 {
+                self.execute_key_on_off();
+            }
+            return;
+        }
+
+        let register = match (offset - COMMON_REGISTER_BASE) & !3 {
+            0x00 => &mut self.registers.control,
+            0x04 => &mut self.registers.status,
+            0x08 => {
+                // Seul le mot bas porte le volume maître
+                if offset & 2 != 0 {
+                    self.registers.master_volume = value;
                 }
+                return;
             }
+            0x0C => &mut self.registers.slot_control,
+            _ => return,
+        };
+        *register = if offset & 2 == 0 {
+            (*register & 0x0000_FFFF) | (value as u32) << 16
+        } else {
+            (*register & 0xFFFF_0000) | value as u32
+        };
+    }
+
+    /// Lit un octet des registres SCSP (octet de poids fort à l'adresse paire)
+    pub fn read_register_u8(&self, offset: u32) -> u8 {
+        let word = self.read_register_u16(offset);
+        if offset & 1 == 0 {
+            (word >> 8) as u8
+        } else {
+            word as u8
         }
     }
-    
-    /// Obtient des données audio pour le callback
-    pub fn get_audio_data(&mut self, buffer: &mut [f32]) {
-        for (i, sample) in buffer.iter_mut().enumerate() {
-            *sample = self.output_buffer.pop_front().unwrap_or(0.0) * self.volume;
+
+    /// Écrit un octet des registres SCSP : l'autre moitié du mot est conservée
+    pub fn write_register_u8(&mut self, offset: u32, value: u8) {
+        let word = self.read_register_u16(offset);
+        let word = if offset & 1 == 0 {
+            (word & 0x00FF) | (value as u16) << 8
+        } else {
+            (word & 0xFF00) | value as u16
+        };
+        self.write_register_u16(offset, word);
+    }
+
+    /// Lit un registre SCSP de 32 bits (deux mots, le haut en premier)
+    pub fn read_register(&self, offset: u32) -> u32 {
+        (self.read_register_u16(offset) as u32) << 16 | self.read_register_u16(offset + 2) as u32
+    }
+
+    /// Écrit un registre SCSP de 32 bits ; le mot haut, qui peut porter
+    /// KYONEX, est écrit après le mot bas pour que SA soit complet au key on
+    pub fn write_register(&mut self, offset: u32, value: u32) {
+        self.write_register_u16(offset + 2, value as u16);
+        self.write_register_u16(offset, (value >> 16) as u16);
+    }
+
+    /// KYONEX : démarre les slots dont KYONB est levé, relâche les autres
+    fn execute_key_on_off(&mut self) {
+        for slot_id in 0..SCSP_SLOT_COUNT {
+            let key_on = self.registers.slot_registers[slot_id].key_on;
+            let playing = self.slot_states[slot_id].active
+                && !matches!(self.slot_states[slot_id].envelope_phase, EnvelopePhase::Release);
+            if key_on && !playing {
+                self.start_slot(slot_id);
+            } else if !key_on && playing {
+                self.stop_slot(slot_id);
+            }
         }
     }
 }
@@ -482,7 +793,7 @@ impl ScspRegisters {
             slot_control: 0x00000000,
             slot_registers: [SlotRegisters::default(); 32],
             dsp_memory: [0; 2048],
-            wave_memory: vec![0; 2 * 1024 * 1024], // 2MB
+            wave_memory: vec![0; WAVE_MEMORY_SIZE],
         }
     }
 }
@@ -493,21 +804,6 @@ impl Default for ScspRegisters {
     }
 }
 
-impl Default for SlotRegisters {
-    fn default() -> Self {
-        Self {
-            volume: 0x0FFF,
-            frequency: 1000,
-            start_address: 0,
-            end_address: 0x1000,
-            loop_address: 0,
-            control: 0x0000,
-            pan: 0x0F, // Centre
-            wave_type: 0, // PCM
-        }
-    }
-}
-
 impl Default for SlotState {
     fn default() -> Self {
         Self {
@@ -523,6 +819,50 @@ impl Default for SlotState {
 
 impl Default for ScspAudio {
     fn default() -> Self {
-        Self::new().unwrap_or_else(|_| panic!("Impossible d'initialiser l'audio"))
+        // Sans périphérique, la sortie bascule sur un puits nul
+        Self::with_config(&crate::config::EmulatorConfig::default().audio)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_block_written_by_words_then_keyed_on() {
+        let mut audio = ScspAudio::headless();
+        let slot = 3 * SLOT_REGISTER_SIZE;
+
+        // Bloc complet du slot 3 : SA = 0x1_0000, LEA = 0x200, TL = 0x20
+        audio.write_register(slot, 0x0801_0000);
+        audio.write_register(slot + 0x04, 0x0000_0200);
+        audio.write_register_u16(slot + 0x0C, 0x0020);
+        audio.write_register_u8(slot + 0x16, 0xE0);
+        assert!(!audio.slot_active(3));
+
+        // KYONEX écrit par un autre slot : seul le slot 3 a KYONB
+        audio.write_register_u16(0, KEY_EXECUTE);
+        assert!(audio.slot_active(3) && !audio.slot_active(0));
+
+        let regs = &audio.registers.slot_registers[3];
+        assert_eq!((regs.start_address, regs.loop_end(), regs.total_level), (0x1_0000, 0x1_0200, 0x20));
+        assert_eq!(audio.read_register_u16(slot + 0x16), 0xE000);
+        assert_eq!(audio.read_register_u8(slot + 0x01), 0x01);
+
+        // KYONB retombé : relâchement au prochain KYONEX
+        audio.write_register_u16(slot, KEY_EXECUTE | 0x0001);
+        assert_eq!(audio.read_register_u16(slot), 0x0001);
+        assert!(matches!(audio.slot_states[3].envelope_phase, EnvelopePhase::Release));
+    }
+
+    #[test]
+    fn test_common_registers_follow_the_slot_blocks() {
+        let mut audio = ScspAudio::headless();
+        audio.write_register(COMMON_REGISTER_BASE + 0x08, 0x0000_0800);
+        assert_eq!(audio.registers.master_volume, 0x0800);
+
+        audio.write_register_u16(COMMON_REGISTER_BASE, 0x1234);
+        audio.write_register_u16(COMMON_REGISTER_BASE + 2, 0x5678);
+        assert_eq!(audio.read_register(COMMON_REGISTER_BASE), 0x1234_5678);
+        assert_eq!(audio.read_register(COMMON_REGISTER_BASE + 0x04), 1);
+    }
+}
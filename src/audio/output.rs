@@ -0,0 +1,471 @@
+//! Backend cpal : énumération, sélection du périphérique et reprise sur erreur
+//!
+//! Lorsqu'aucun périphérique n'est disponible, la sortie bascule sur un puits
+//! nul : l'émulation continue de produire des échantillons, simplement jetés.
+//! Une sortie en erreur (périphérique débranché, changement de sortie par
+//! défaut) est rouverte automatiquement, et tant qu'elle reste muette par
+//! repli, au démarrage comme après une perte, l'ouverture est retentée avec
+//! un délai qui double à chaque échec. Si aucun périphérique n'est énuméré,
+//! les tentatives s'arrêtent jusqu'au prochain
+//! [`devices_changed`](AudioBackend::devices_changed). Seul un puits nul
+//! demandé explicitement n'est jamais rouvert.
+
+use cpal::{
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    Device, Stream, StreamConfig, SupportedBufferSize,
+};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::backend::{AudioBackend, OutputSettings, NULL_SINK_SAMPLE_RATE};
+use crate::error::{AudioError, Result};
+
+/// Délai initial entre deux tentatives de réouverture d'un périphérique
+const RECOVERY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Délai maximal atteint par le recul exponentiel
+const MAX_RECOVERY_INTERVAL: Duration = Duration::from_secs(64);
+
+/// Latence maximale conservée dans la file partagée, en secondes
+const MAX_QUEUED_SECONDS: usize = 1;
+
+/// File d'échantillons partagée entre l'émulation et le callback cpal
+#[derive(Debug, Default)]
+struct SharedQueue {
+    samples: Mutex<VecDeque<f32>>,
+    consumed_samples: AtomicU64,
+    underruns: AtomicU64,
+}
+
+/// Liste les noms des périphériques de sortie disponibles
+pub fn output_device_names() -> Vec<String> {
+    cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Sortie audio ouverte (ou puits nul)
+pub struct AudioOutput {
+    settings: OutputSettings,
+    stream: Option<Stream>,
+    device_name: Option<String>,
+    sample_rate: u32,
+    channels: u16,
+    buffer_frames: Option<u32>,
+
+    /// Positionné par le callback d'erreur du flux
+    stream_failed: Arc<AtomicBool>,
+
+    /// Échantillons en attente de lecture
+    queue: Arc<SharedQueue>,
+
+    /// Dernière tentative d'ouverture
+    last_attempt: Instant,
+
+    /// Délai avant la prochaine tentative (doublé à chaque échec)
+    retry_delay: Duration,
+
+    /// La dernière tentative n'a énuméré aucun périphérique : pas de nouvel
+    /// essai avant un changement de la liste des périphériques
+    no_device: bool,
+
+    /// Puits nul demandé explicitement : aucun périphérique n'est recherché
+    null_requested: bool,
+}
+
+impl AudioOutput {
+    /// Ouvre la sortie demandée, avec repli sur le périphérique par défaut
+    /// puis sur un puits nul. Ne retourne jamais d'erreur.
+    pub fn open(settings: OutputSettings) -> Self {
+        let mut output = Self::null(settings);
+        output.null_requested = false;
+        output.reopen();
+        output
+    }
+
+    /// Crée un puits nul (aucun périphérique)
+    pub fn null(settings: OutputSettings) -> Self {
+        let sample_rate = settings.sample_rate.unwrap_or(NULL_SINK_SAMPLE_RATE);
+        Self {
+            buffer_frames: settings.buffer_frames_for(sample_rate),
+            settings,
+            stream: None,
+            device_name: None,
+            sample_rate,
+            channels: 2,
+            stream_failed: Arc::new(AtomicBool::new(false)),
+            queue: Arc::new(SharedQueue::default()),
+            last_attempt: Instant::now(),
+            retry_delay: RECOVERY_INTERVAL,
+            no_device: false,
+            null_requested: true,
+        }
+    }
+
+    /// Indique si la sortie est un puits nul
+    pub fn is_null(&self) -> bool {
+        self.stream.is_none()
+    }
+
+    /// Nom du périphérique ouvert
+    pub fn device_name(&self) -> Option<&str> {
+        self.device_name.as_deref()
+    }
+
+    /// Taille effective du buffer matériel (None = choix du pilote)
+    pub fn buffer_frames(&self) -> Option<u32> {
+        self.buffer_frames
+    }
+
+    /// Paramètres demandés
+    pub fn settings(&self) -> &OutputSettings {
+        &self.settings
+    }
+
+    /// Nombre de callbacks ayant manqué d'échantillons
+    pub fn underruns(&self) -> u64 {
+        self.queue.underruns.load(Ordering::Relaxed)
+    }
+
+    /// Rouvre la sortie si le flux a signalé une erreur, ou retente d'ouvrir
+    /// un périphérique tant que la sortie est muette par repli.
+    ///
+    /// Retourne `true` si la sortie a été rouverte.
+    fn reopen_if_needed(&mut self) -> bool {
+        let failed = self.stream_failed.load(Ordering::Relaxed);
+        let retry = !self.null_requested
+            && !self.no_device
+            && self.is_null()
+            && self.last_attempt.elapsed() >= self.retry_delay;
+
+        if !failed && !retry {
+            return false;
+        }
+
+        if failed {
+            log::warn!("Sortie audio en erreur, réouverture du périphérique");
+        }
+
+        let previous = (self.device_name.clone(), self.sample_rate, self.channels);
+        self.reopen();
+        previous != (self.device_name.clone(), self.sample_rate, self.channels) || failed
+    }
+
+    /// Ferme le flux courant et tente d'en ouvrir un nouveau
+    fn reopen(&mut self) {
+        self.stream = None;
+        self.device_name = None;
+        self.last_attempt = Instant::now();
+        self.stream_failed = Arc::new(AtomicBool::new(false));
+        self.queue.samples.lock().clear();
+
+        let host = cpal::default_host();
+        let requested = self.settings.device.as_ref().and_then(|name| {
+            let device = host
+                .output_devices()
+                .ok()
+                .and_then(|mut devices| devices.find(|d| d.name().ok().as_deref() == Some(name.as_str())));
+            if device.is_none() {
+                log::warn!("Périphérique audio '{}' introuvable, utilisation du périphérique par défaut", name);
+            }
+            device
+        });
+
+        let Some(device) = requested.or_else(|| host.default_output_device()) else {
+            if !self.no_device {
+                log::info!("Aucun périphérique audio, sortie muette jusqu'au prochain changement de périphériques");
+            }
+            self.no_device = true;
+            self.sample_rate = self.settings.sample_rate.unwrap_or(NULL_SINK_SAMPLE_RATE);
+            self.channels = 2;
+            self.buffer_frames = self.settings.buffer_frames_for(self.sample_rate);
+            return;
+        };
+
+        self.no_device = false;
+        match self.build_stream(&device) {
+            Ok(()) => {
+                self.retry_delay = RECOVERY_INTERVAL;
+                self.device_name = device.name().ok();
+                log::info!(
+                    "Sortie audio: {} ({} Hz, {} canaux)",
+                    self.device_name.as_deref().unwrap_or("?"),
+                    self.sample_rate,
+                    self.channels
+                );
+            }
+            Err(e) => {
+                self.retry_delay = next_retry_delay(self.retry_delay);
+                log::warn!(
+                    "Impossible d'ouvrir la sortie audio ({}), sortie muette, nouvel essai dans {} s",
+                    e,
+                    self.retry_delay.as_secs()
+                );
+                self.stream = None;
+            }
+        }
+    }
+
+    /// Construit et démarre le flux de sortie sur un périphérique
+    fn build_stream(&mut self, device: &Device) -> Result<()> {
+        let default_config = device.default_output_config().map_err(|e| AudioError::Config(e.to_string()))?;
+        let channels = default_config.channels();
+
+        // Utiliser la fréquence demandée si le périphérique la supporte
+        let sample_rate = self
+            .settings
+            .sample_rate
+            .filter(|&rate| {
+                device.supported_output_configs().is_ok_and(|mut configs| {
+                    configs.any(|c| {
+                        c.channels() == channels
+                            && c.min_sample_rate().0 <= rate
+                            && rate <= c.max_sample_rate().0
+                    })
+                })
+            })
+            .unwrap_or(default_config.sample_rate().0);
+
+        // Borner la taille de buffer à la plage supportée
+        let buffer_frames = self.settings.buffer_frames_for(sample_rate).map(|frames| {
+            match default_config.buffer_size() {
+                SupportedBufferSize::Range { min, max } => frames.clamp(*min, *max),
+                SupportedBufferSize::Unknown => frames,
+            }
+        });
+
+        let mut stream_config = StreamConfig {
+            channels,
+            sample_rate: cpal::SampleRate(sample_rate),
+            buffer_size: buffer_frames.map_or(cpal::BufferSize::Default, cpal::BufferSize::Fixed),
+        };
+
+        let stream = match self.create_stream(device, &stream_config) {
+            Ok(stream) => stream,
+            Err(e) if buffer_frames.is_some() => {
+                // Certains pilotes refusent une taille fixe : laisser le choix au pilote
+                log::warn!("Taille de buffer refusée ({}), utilisation de la valeur par défaut", e);
+                stream_config.buffer_size = cpal::BufferSize::Default;
+                self.create_stream(device, &stream_config)?
+            }
+            Err(e) => return Err(e),
+        };
+
+        stream.play().map_err(|e| AudioError::Stream(e.to_string()))?;
+
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+        self.buffer_frames = match stream_config.buffer_size {
+            cpal::BufferSize::Fixed(frames) => Some(frames),
+            cpal::BufferSize::Default => None,
+        };
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn create_stream(&self, device: &Device, config: &StreamConfig) -> Result<Stream> {
+        let failed = self.stream_failed.clone();
+        let queue = self.queue.clone();
+        let stream = device.build_output_stream(
+            config,
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                let mut samples = queue.samples.lock();
+                if samples.len() < data.len() {
+                    queue.underruns.fetch_add(1, Ordering::Relaxed);
+                }
+
+                let available = samples.len().min(data.len());
+                for (out, sample) in data.iter_mut().zip(samples.drain(..available)) {
+                    *out = sample;
+                }
+                data[available..].fill(0.0);
+                queue.consumed_samples.fetch_add(available as u64, Ordering::Relaxed);
+            },
+            move |err| {
+                eprintln!("Erreur audio: {}", err);
+                failed.store(true, Ordering::Relaxed);
+            },
+            None,
+        );
+        stream.map_err(|e| AudioError::Stream(e.to_string()).into())
+    }
+}
+
+impl AudioBackend for AudioOutput {
+    fn name(&self) -> &str {
+        if self.is_null() { "cpal (muet)" } else { "cpal" }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn push_samples(&mut self, samples: &[f32]) {
+        if self.is_null() {
+            // Puits muet : consommer immédiatement pour garder le même timing
+            self.queue.consumed_samples.fetch_add(samples.len() as u64, Ordering::Relaxed);
+            return;
+        }
+
+        let limit = self.sample_rate as usize * self.channels as usize * MAX_QUEUED_SECONDS;
+        let mut queue = self.queue.samples.lock();
+        queue.extend(samples.iter().copied());
+
+        // Éviter une latence qui grandit sans fin si le périphérique consomme moins vite
+        if queue.len() > limit {
+            let excess = queue.len() - limit;
+            queue.drain(..excess);
+        }
+    }
+
+    fn queued_frames(&self) -> usize {
+        self.queue.samples.lock().len() / self.channels.max(1) as usize
+    }
+
+    fn consumed_frames(&self) -> u64 {
+        self.queue.consumed_samples.load(Ordering::Relaxed) / self.channels.max(1) as u64
+    }
+
+    fn is_realtime(&self) -> bool {
+        !self.is_null()
+    }
+
+    fn recover_if_needed(&mut self) -> bool {
+        self.reopen_if_needed()
+    }
+
+    fn devices_changed(&mut self) {
+        if self.null_requested || !self.is_null() {
+            return;
+        }
+
+        // Nouvel essai dès la prochaine reprise, avec un recul réinitialisé
+        self.no_device = false;
+        self.retry_delay = RECOVERY_INTERVAL;
+        if let Some(due) = Instant::now().checked_sub(RECOVERY_INTERVAL) {
+            self.last_attempt = due;
+        }
+    }
+}
+
+/// Délai suivant du recul exponentiel
+fn next_retry_delay(delay: Duration) -> Duration {
+    (delay * 2).min(MAX_RECOVERY_INTERVAL)
+}
+
+impl std::fmt::Debug for AudioOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AudioOutput")
+            .field("device_name", &self.device_name)
+            .field("sample_rate", &self.sample_rate)
+            .field("channels", &self.channels)
+            .field("buffer_frames", &self.buffer_frames)
+            .field("null", &self.is_null())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_sink_keeps_requested_format() {
+        let output = AudioOutput::null(OutputSettings {
+            sample_rate: Some(32_000),
+            latency_ms: Some(50),
+            ..Default::default()
+        });
+
+        assert!(output.is_null());
+        assert_eq!(output.sample_rate(), 32_000);
+        assert_eq!(output.channels(), 2);
+        assert_eq!(output.buffer_frames(), Some(1600));
+    }
+
+    #[test]
+    fn test_null_sink_is_not_reopened() {
+        let mut output = AudioOutput::null(OutputSettings::default());
+        let Some(long_ago) = Instant::now().checked_sub(RECOVERY_INTERVAL * 2) else {
+            return;
+        };
+        output.last_attempt = long_ago;
+
+        assert!(!output.recover_if_needed());
+        assert_eq!(output.last_attempt, long_ago);
+        assert!(output.is_null());
+    }
+
+    #[test]
+    fn test_startup_fallback_is_retried() {
+        let mut output = AudioOutput::open(OutputSettings::default());
+        let Some(long_ago) = Instant::now().checked_sub(RECOVERY_INTERVAL * 2) else {
+            return;
+        };
+        if !output.is_null() {
+            // Un périphérique s'est ouvert d'emblée : pas de repli à tester
+            return;
+        }
+
+        // Pas de nouvelle tentative avant l'intervalle
+        let attempt = output.last_attempt;
+        assert!(!output.recover_if_needed());
+        assert_eq!(output.last_attempt, attempt);
+
+        // Puis une tentative par intervalle, qui rouvre dès qu'un périphérique
+        // existe ; sans périphérique énuméré, plus aucune tentative
+        output.last_attempt = long_ago;
+        let no_device = output.no_device;
+        let recovered = output.recover_if_needed();
+        assert_eq!(output.last_attempt > long_ago, !no_device);
+        assert_eq!(recovered, !output.is_null());
+    }
+
+    #[test]
+    fn test_no_device_waits_for_device_change() {
+        let mut output = AudioOutput::null(OutputSettings::default());
+        output.null_requested = false;
+        output.no_device = true;
+        let Some(long_ago) = Instant::now().checked_sub(MAX_RECOVERY_INTERVAL * 2) else {
+            return;
+        };
+        output.last_attempt = long_ago;
+
+        // Aucune énumération tant que la liste des périphériques n'a pas changé
+        assert!(!output.recover_if_needed());
+        assert_eq!(output.last_attempt, long_ago);
+
+        output.devices_changed();
+        assert!(!output.no_device);
+        assert_eq!(output.retry_delay, RECOVERY_INTERVAL);
+        output.recover_if_needed();
+        assert!(output.last_attempt > long_ago);
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        let mut delay = RECOVERY_INTERVAL;
+        delay = next_retry_delay(delay);
+        assert_eq!(delay, RECOVERY_INTERVAL * 2);
+        for _ in 0..16 {
+            delay = next_retry_delay(delay);
+        }
+        assert_eq!(delay, MAX_RECOVERY_INTERVAL);
+    }
+
+    #[test]
+    fn test_null_sink_consumes_pushed_samples() {
+        let mut output = AudioOutput::null(OutputSettings::default());
+        output.push_samples(&[0.5; 256]);
+        assert_eq!(output.queued_frames(), 0);
+        assert_eq!(output.consumed_frames(), 128);
+    }
+}
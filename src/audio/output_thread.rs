@@ -0,0 +1,182 @@
+//! Sortie audio sur un thread dédié
+//!
+//! [`ThreadedBackend`] enveloppe un autre [`AudioBackend`] qu'il crée et fait
+//! vivre sur son propre thread : l'émulation se contente d'envoyer les blocs
+//! d'échantillons par un canal et n'attend jamais le périphérique (verrou de
+//! la file cpal, reprise après perte du périphérique). Le remplissage de la
+//! file et le format de sortie remontent par des atomiques, ce qui garde le
+//! contrôle de débit identique au mode sans thread.
+//!
+//! Seul l'envoi au périphérique quitte le thread d'émulation : la synthèse
+//! SCSP ([`ScspAudio::update`](super::ScspAudio::update)) y reste, cadencée
+//! par les cycles émulés de chaque frame.
+
+use super::AudioBackend;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Intervalle de relevé de la file du périphérique quand aucun bloc n'arrive
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// État publié par le thread de sortie
+#[derive(Debug, Default)]
+struct SharedState {
+    sample_rate: AtomicU32,
+    channels: AtomicU32,
+    queued_frames: AtomicUsize,
+    consumed_frames: AtomicU64,
+    realtime: AtomicBool,
+    format_changed: AtomicBool,
+    devices_changed: AtomicBool,
+}
+
+impl SharedState {
+    fn publish(&self, backend: &dyn AudioBackend) {
+        self.sample_rate.store(backend.sample_rate(), Ordering::Relaxed);
+        self.channels.store(backend.channels() as u32, Ordering::Relaxed);
+        self.realtime.store(backend.is_realtime(), Ordering::Relaxed);
+        self.queued_frames.store(backend.queued_frames(), Ordering::Relaxed);
+        self.consumed_frames.store(backend.consumed_frames(), Ordering::Relaxed);
+    }
+}
+
+/// Backend audio dont la sortie réelle tourne sur un thread dédié
+pub struct ThreadedBackend {
+    name: String,
+    shared: Arc<SharedState>,
+    sender: Option<mpsc::Sender<Vec<f32>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl ThreadedBackend {
+    /// Lance le thread de sortie ; `open` y crée le backend réel (les flux
+    /// cpal ne peuvent pas changer de thread)
+    pub fn spawn<F>(open: F) -> Self
+    where
+        F: FnOnce() -> Box<dyn AudioBackend> + Send + 'static,
+    {
+        let shared = Arc::new(SharedState::default());
+        let (sender, receiver) = mpsc::channel::<Vec<f32>>();
+        let (ready_sender, ready) = mpsc::sync_channel::<String>(1);
+
+        let worker_shared = Arc::clone(&shared);
+        let worker = std::thread::Builder::new()
+            .name("audio-output".to_string())
+            .spawn(move || {
+                let mut backend = open();
+                worker_shared.publish(backend.as_ref());
+                let _ = ready_sender.send(backend.name().to_string());
+
+                loop {
+                    match receiver.recv_timeout(POLL_INTERVAL) {
+                        Ok(samples) => backend.push_samples(&samples),
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                    if worker_shared.devices_changed.swap(false, Ordering::Acquire) {
+                        backend.devices_changed();
+                    }
+                    if backend.recover_if_needed() {
+                        worker_shared.format_changed.store(true, Ordering::Release);
+                    }
+                    worker_shared.publish(backend.as_ref());
+                }
+            })
+            .expect("Impossible de lancer le thread de sortie audio");
+
+        let name = ready.recv().unwrap_or_else(|_| "?".to_string());
+        Self {
+            name: format!("{} (thread)", name),
+            shared,
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+}
+
+impl AudioBackend for ThreadedBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.shared.sample_rate.load(Ordering::Relaxed)
+    }
+
+    fn channels(&self) -> u16 {
+        self.shared.channels.load(Ordering::Relaxed) as u16
+    }
+
+    fn push_samples(&mut self, samples: &[f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        if let Some(sender) = self.sender.as_ref() {
+            let _ = sender.send(samples.to_vec());
+        }
+    }
+
+    fn queued_frames(&self) -> usize {
+        self.shared.queued_frames.load(Ordering::Relaxed)
+    }
+
+    fn consumed_frames(&self) -> u64 {
+        self.shared.consumed_frames.load(Ordering::Relaxed)
+    }
+
+    fn is_realtime(&self) -> bool {
+        self.shared.realtime.load(Ordering::Relaxed)
+    }
+
+    fn recover_if_needed(&mut self) -> bool {
+        self.shared.format_changed.swap(false, Ordering::Acquire)
+    }
+
+    fn devices_changed(&mut self) {
+        self.shared.devices_changed.store(true, Ordering::Release);
+    }
+}
+
+impl Drop for ThreadedBackend {
+    fn drop(&mut self) {
+        // Fermer le canal arrête la boucle du thread après les derniers blocs
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl std::fmt::Debug for ThreadedBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThreadedBackend")
+            .field("name", &self.name)
+            .field("sample_rate", &self.sample_rate())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::NullBackend;
+
+    #[test]
+    fn test_samples_reach_the_worker() {
+        let mut backend = ThreadedBackend::spawn(|| Box::new(NullBackend::new(48_000, 2)));
+        assert_eq!(backend.name(), "null (thread)");
+        assert_eq!((backend.sample_rate(), backend.channels()), (48_000, 2));
+
+        backend.push_samples(&[0.0; 960]);
+        backend.push_samples(&[0.0; 40]);
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        while backend.consumed_frames() < 500 && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(backend.consumed_frames(), 500);
+        assert!(!backend.recover_if_needed());
+    }
+}
@@ -0,0 +1,128 @@
+//! Loi de panoramique des slots SCSP (DISDL / DIPAN)
+//!
+//! Chaque slot envoie sa sortie directe vers le mixeur stéréo avec deux
+//! atténuations exprimées en décibels :
+//! - DISDL (3 bits) : niveau d'envoi direct, 0 = muet, puis -36 dB à 0 dB par
+//!   pas de 6 dB ;
+//! - DIPAN (5 bits) : les bits 3-0 atténuent un seul canal (-3, -6, -12 et
+//!   -24 dB cumulables, 0xF = canal coupé) et le bit 4 choisit ce canal
+//!   (0 = droite atténuée, 1 = gauche atténuée).
+//!
+//! Au centre (DIPAN = 0x00 ou 0x10), les deux canaux sont à plein niveau,
+//! comme sur la carte d'origine.
+
+/// Niveau d'envoi direct maximal (0 dB)
+pub const DISDL_MAX: u8 = 7;
+
+/// Panoramique centré
+pub const DIPAN_CENTER: u8 = 0x00;
+
+/// Atténuation en dB de chaque niveau DISDL (l'entrée 0 est muette)
+const DISDL_DB: [f32; 8] = [f32::NEG_INFINITY, -36.0, -30.0, -24.0, -18.0, -12.0, -6.0, 0.0];
+
+/// Convertit une atténuation en dB en gain linéaire
+fn db_to_gain(db: f32) -> f32 {
+    if db == f32::NEG_INFINITY {
+        0.0
+    } else {
+        10f32.powf(db / 20.0)
+    }
+}
+
+/// Gain linéaire du niveau d'envoi direct
+pub fn direct_send_gain(disdl: u8) -> f32 {
+    db_to_gain(DISDL_DB[(disdl & 0x07) as usize])
+}
+
+/// Atténuation en dB du canal désigné par DIPAN
+pub fn pan_attenuation_db(dipan: u8) -> f32 {
+    let level = dipan & 0x0F;
+    if level == 0x0F {
+        return f32::NEG_INFINITY;
+    }
+
+    [3.0f32, 6.0, 12.0, 24.0]
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| level & (1 << bit) != 0)
+        .map(|(_, db)| -db)
+        .sum()
+}
+
+/// Gains (gauche, droite) d'un slot pour ses registres DISDL et DIPAN
+pub fn pan_gains(disdl: u8, dipan: u8) -> (f32, f32) {
+    let send = direct_send_gain(disdl);
+    let attenuated = send * db_to_gain(pan_attenuation_db(dipan));
+
+    if dipan & 0x10 != 0 {
+        (attenuated, send)
+    } else {
+        (send, attenuated)
+    }
+}
+
+/// Décode le mot de registre DISDL/DIPAN (bits 15-13 et 12-8)
+pub fn decode_direct_send(word: u16) -> (u8, u8) {
+    (((word >> 13) & 0x07) as u8, ((word >> 8) & 0x1F) as u8)
+}
+
+/// Compose le mot de registre DISDL/DIPAN
+pub fn encode_direct_send(disdl: u8, dipan: u8) -> u16 {
+    (((disdl & 0x07) as u16) << 13) | (((dipan & 0x1F) as u16) << 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db(gain: f32) -> f32 {
+        20.0 * gain.log10()
+    }
+
+    #[test]
+    fn test_center_has_no_attenuation() {
+        assert_eq!(pan_gains(DISDL_MAX, DIPAN_CENTER), (1.0, 1.0));
+        assert_eq!(pan_gains(DISDL_MAX, 0x10), (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_pan_side_selection() {
+        // Bit 4 à 0 : la droite est atténuée, l'image part à gauche
+        let (left, right) = pan_gains(DISDL_MAX, 0x0F);
+        assert_eq!((left, right), (1.0, 0.0));
+
+        let (left, right) = pan_gains(DISDL_MAX, 0x1F);
+        assert_eq!((left, right), (0.0, 1.0));
+
+        let (left, right) = pan_gains(DISDL_MAX, 0x12);
+        assert!((db(left) + 6.0).abs() < 0.01);
+        assert_eq!(right, 1.0);
+    }
+
+    #[test]
+    fn test_attenuation_steps_accumulate() {
+        assert_eq!(pan_attenuation_db(0x01), -3.0);
+        assert_eq!(pan_attenuation_db(0x05), -15.0);
+        assert_eq!(pan_attenuation_db(0x0E), -42.0);
+    }
+
+    #[test]
+    fn test_direct_send_levels() {
+        assert_eq!(direct_send_gain(0), 0.0);
+        assert_eq!(direct_send_gain(DISDL_MAX), 1.0);
+        assert!((db(direct_send_gain(6)) + 6.0).abs() < 0.01);
+        assert!((db(direct_send_gain(1)) + 36.0).abs() < 0.01);
+
+        // Le niveau d'envoi s'applique aux deux canaux
+        let (left, right) = pan_gains(5, 0x02);
+        assert!((db(left) + 12.0).abs() < 0.01);
+        assert!((db(right) + 18.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_register_word_roundtrip() {
+        let word = encode_direct_send(6, 0x13);
+        assert_eq!(word, 0xD300);
+        assert_eq!(decode_direct_send(word), (6, 0x13));
+    }
+}
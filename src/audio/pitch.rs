@@ -0,0 +1,129 @@
+//! Calcul du pas de lecture des slots SCSP et interpolation des échantillons
+//!
+//! Le SCSP code la hauteur d'un slot dans un mot de 16 bits :
+//! - bits 14-11 : OCT, octave signée sur 4 bits (-8 à +7)
+//! - bits 9-0   : FNS, numéro de fréquence (mantisse sur 10 bits)
+//!
+//! Le pas de lecture vaut `2^OCT * (1024 + FNS) / 1024` échantillons source
+//! par échantillon produit à la fréquence native du SCSP (44,1 kHz).
+
+use serde::{Deserialize, Serialize};
+
+/// Fréquence d'échantillonnage native du SCSP
+pub const SCSP_SAMPLE_RATE: u32 = 44_100;
+
+/// Extrait l'octave signée (OCT) d'un registre de fréquence
+pub fn octave(frequency_reg: u16) -> i8 {
+    let raw = ((frequency_reg >> 11) & 0x0F) as i8;
+    // Extension de signe sur 4 bits
+    (raw << 4) >> 4
+}
+
+/// Extrait le numéro de fréquence (FNS) d'un registre de fréquence
+pub fn fns(frequency_reg: u16) -> u16 {
+    frequency_reg & 0x03FF
+}
+
+/// Compose un registre de fréquence à partir de OCT et FNS
+pub fn encode_frequency(octave: i8, fns: u16) -> u16 {
+    (((octave as u16) & 0x0F) << 11) | (fns & 0x03FF)
+}
+
+/// Calcule le pas de lecture d'un slot pour une fréquence de sortie donnée
+pub fn playback_step(frequency_reg: u16, output_rate: u32) -> f32 {
+    let mantissa = (1024 + fns(frequency_reg) as u32) as f32 / 1024.0;
+    let step = mantissa * 2f32.powi(octave(frequency_reg) as i32);
+
+    if output_rate == 0 {
+        return step;
+    }
+
+    step * SCSP_SAMPLE_RATE as f32 / output_rate as f32
+}
+
+/// Mode d'interpolation des échantillons PCM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interpolation {
+    /// Échantillon le plus proche (comportement du matériel)
+    None,
+    /// Interpolation linéaire entre deux échantillons
+    #[default]
+    Linear,
+    /// Interpolation cubique (Catmull-Rom) sur quatre échantillons
+    Cubic,
+}
+
+impl Interpolation {
+    /// Interpole un échantillon à une position fractionnaire.
+    ///
+    /// `fetch` renvoie l'échantillon (normalisé entre -1.0 et 1.0) à un index
+    /// entier ; c'est à l'appelant de gérer les bornes et la boucle.
+    pub fn sample<F>(self, position: f32, fetch: F) -> f32
+    where
+        F: Fn(i64) -> f32,
+    {
+        let index = position.floor() as i64;
+        let t = position - position.floor();
+
+        match self {
+            Interpolation::None => fetch(index),
+            Interpolation::Linear => {
+                let s0 = fetch(index);
+                let s1 = fetch(index + 1);
+                s0 + (s1 - s0) * t
+            }
+            Interpolation::Cubic => {
+                let p0 = fetch(index - 1);
+                let p1 = fetch(index);
+                let p2 = fetch(index + 1);
+                let p3 = fetch(index + 2);
+
+                let a = -0.5 * p0 + 1.5 * p1 - 1.5 * p2 + 0.5 * p3;
+                let b = p0 - 2.5 * p1 + 2.0 * p2 - 0.5 * p3;
+                let c = -0.5 * p0 + 0.5 * p2;
+
+                ((a * t + b) * t + c) * t + p1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_octave_sign_extension() {
+        assert_eq!(octave(encode_frequency(0, 0)), 0);
+        assert_eq!(octave(encode_frequency(7, 0)), 7);
+        assert_eq!(octave(encode_frequency(-1, 0)), -1);
+        assert_eq!(octave(encode_frequency(-8, 0)), -8);
+    }
+
+    #[test]
+    fn test_playback_step_native_rate() {
+        assert_eq!(playback_step(encode_frequency(0, 0), SCSP_SAMPLE_RATE), 1.0);
+        assert_eq!(playback_step(encode_frequency(1, 0), SCSP_SAMPLE_RATE), 2.0);
+        assert_eq!(playback_step(encode_frequency(-1, 0), SCSP_SAMPLE_RATE), 0.5);
+        assert_eq!(playback_step(encode_frequency(0, 512), SCSP_SAMPLE_RATE), 1.5);
+    }
+
+    #[test]
+    fn test_playback_step_resampling() {
+        let step = playback_step(encode_frequency(0, 0), 48_000);
+        assert!((step - 44_100.0 / 48_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interpolation_modes() {
+        let data = [0.0f32, 1.0, 0.0, -1.0];
+        let fetch = |i: i64| data.get(i.clamp(0, 3) as usize).copied().unwrap_or(0.0);
+
+        assert_eq!(Interpolation::None.sample(0.75, fetch), 0.0);
+        assert!((Interpolation::Linear.sample(0.5, fetch) - 0.5).abs() < 1e-6);
+        // Catmull-Rom passe exactement par les points d'échantillonnage
+        assert!((Interpolation::Cubic.sample(1.0, fetch) - 1.0).abs() < 1e-6);
+        assert!((Interpolation::Cubic.sample(2.0, fetch)).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,111 @@
+//! Contrôle dynamique du débit audio (compensation de dérive)
+//!
+//! L'horloge de l'émulation et celle de la carte son ne battent jamais
+//! exactement à la même fréquence : sans correction, la file de sortie se vide
+//! (craquements) ou grossit sans fin (latence). On mesure donc le remplissage
+//! de la file à chaque mise à jour et on ajuste très légèrement (±0,5 %) le
+//! rapport de rééchantillonnage pour ramener la latence vers sa cible. Un écart
+//! aussi faible est inaudible sur la hauteur des sons.
+
+/// Écart maximal du rapport de rééchantillonnage
+pub const MAX_RATE_DELTA: f32 = 0.005;
+
+/// Régulateur du rapport de rééchantillonnage
+#[derive(Debug, Clone)]
+pub struct RateControl {
+    enabled: bool,
+    target_ms: u32,
+    max_delta: f32,
+    ratio: f32,
+}
+
+impl RateControl {
+    /// Crée un régulateur visant `target_ms` millisecondes d'échantillons en file
+    pub fn new(target_ms: u32, max_delta: f32) -> Self {
+        Self {
+            enabled: target_ms > 0,
+            target_ms,
+            max_delta: max_delta.abs(),
+            ratio: 1.0,
+        }
+    }
+
+    /// Régulateur inactif (rapport fixe de 1.0)
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::new(0, 0.0)
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Rapport courant : nombre d'échantillons produits par échantillon nominal
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Latence visée en frames pour une fréquence de sortie donnée
+    pub fn target_frames(&self, sample_rate: u32) -> usize {
+        (sample_rate as u64 * self.target_ms as u64 / 1000) as usize
+    }
+
+    /// Met à jour le rapport à partir du remplissage mesuré de la file.
+    ///
+    /// File vide : on produit `1 + max_delta` fois plus d'échantillons ;
+    /// file au double de la cible : `1 - max_delta` fois.
+    pub fn update(&mut self, queued_frames: usize, sample_rate: u32) -> f32 {
+        let target = self.target_frames(sample_rate);
+        if !self.enabled || target == 0 {
+            self.ratio = 1.0;
+            return self.ratio;
+        }
+
+        let fill = (queued_frames as f32 / (2 * target) as f32).clamp(0.0, 1.0);
+        self.ratio = 1.0 + self.max_delta * (1.0 - 2.0 * fill);
+        self.ratio
+    }
+
+    /// Revient au rapport nominal (changement de périphérique, vidage de la file)
+    pub fn reset(&mut self) {
+        self.ratio = 1.0;
+    }
+}
+
+impl Default for RateControl {
+    fn default() -> Self {
+        Self::new(40, MAX_RATE_DELTA)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratio_at_target_is_nominal() {
+        let mut control = RateControl::new(40, MAX_RATE_DELTA);
+        assert_eq!(control.target_frames(48_000), 1920);
+        assert_eq!(control.update(1920, 48_000), 1.0);
+    }
+
+    #[test]
+    fn test_ratio_stays_within_bounds() {
+        let mut control = RateControl::new(40, MAX_RATE_DELTA);
+
+        // File vide : produire plus pour la remplir
+        assert!((control.update(0, 48_000) - 1.005).abs() < 1e-6);
+
+        // File pleine : produire moins pour la vider
+        assert!((control.update(100_000, 48_000) - 0.995).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_disabled_keeps_nominal_ratio() {
+        let mut control = RateControl::disabled();
+        assert!(!control.is_enabled());
+        assert_eq!(control.update(0, 48_000), 1.0);
+    }
+}
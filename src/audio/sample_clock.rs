@@ -0,0 +1,91 @@
+//! Conversion exacte des cycles de l'horloge maître en échantillons audio
+//!
+//! Chaque mise à jour produit exactement les échantillons correspondant aux
+//! cycles écoulés ; le reste de la division est reporté à la suivante. Sur
+//! la durée, la sortie compte donc précisément `sample_rate` échantillons
+//! par seconde émulée, sans dérive entre l'image et le son, quelle que soit
+//! la découpe des mises à jour.
+
+/// Horloge d'échantillonnage cadencée par l'horloge maître
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleClock {
+    /// Fréquence de l'horloge maître en Hz
+    source_hz: u64,
+    sample_rate: u64,
+    /// Cycles × fréquence de sortie pas encore convertis (toujours < `source_hz`)
+    remainder: u64,
+}
+
+impl SampleClock {
+    pub fn new(source_hz: u32, sample_rate: u32) -> Self {
+        Self {
+            source_hz: source_hz.max(1) as u64,
+            sample_rate: sample_rate as u64,
+            remainder: 0,
+        }
+    }
+
+    /// Fréquence de l'horloge maître
+    pub fn source_frequency(&self) -> u32 {
+        self.source_hz as u32
+    }
+
+    /// Change la fréquence de l'horloge maître (multiplicateur d'horloge) ;
+    /// la fraction d'échantillon en cours est abandonnée
+    pub fn set_source_frequency(&mut self, source_hz: u32) {
+        self.source_hz = source_hz.max(1) as u64;
+        self.remainder = 0;
+    }
+
+    /// Change la fréquence de sortie (changement de périphérique)
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate as u64;
+        self.remainder = 0;
+    }
+
+    /// Avance de `cycles` et retourne le nombre d'échantillons (par canal) à produire
+    pub fn advance(&mut self, cycles: u32) -> u32 {
+        let total = self.remainder + cycles as u64 * self.sample_rate;
+        self.remainder = total % self.source_hz;
+        (total / self.source_hz) as u32
+    }
+
+    pub fn reset(&mut self) {
+        self.remainder = 0;
+    }
+}
+
+impl Default for SampleClock {
+    fn default() -> Self {
+        Self::new(crate::MAIN_CPU_FREQUENCY, super::NULL_SINK_SAMPLE_RATE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_drift_over_uneven_updates() {
+        let mut clock = SampleClock::new(25_000_000, 44_100);
+
+        // Une seconde découpée en tranches irrégulières
+        let mut produced = 0;
+        let mut elapsed = 0;
+        for slice in [1u32, 416_666, 3, 999_999, 12_345].iter().cycle() {
+            let slice = (*slice).min(25_000_000 - elapsed);
+            produced += clock.advance(slice);
+            elapsed += slice;
+            if elapsed == 25_000_000 {
+                break;
+            }
+        }
+        assert_eq!(produced, 44_100);
+
+        // 60 frames d'un V60 accéléré à 150 %
+        let cycles_per_frame = 625_000;
+        clock.set_source_frequency(cycles_per_frame * 60);
+        let frames: u32 = (0..60).map(|_| clock.advance(cycles_per_frame)).sum();
+        assert_eq!(frames, 44_100);
+    }
+}
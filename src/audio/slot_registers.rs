@@ -0,0 +1,394 @@
+//! Carte des registres d'un slot SCSP
+//!
+//! Chaque slot occupe 0x20 octets, soit seize mots de 16 bits (les pilotes
+//! écrivent généralement le bloc entier à la mise en route d'une voix) :
+//!
+//! | Offset | Bits 15-0                                                        |
+//! |--------|------------------------------------------------------------------|
+//! | 0x00   | KYONEX(12) KYONB(11) SBCTL(10-9) SSCTL(8-7) LPCTL(6-5) PCM8B(4) SA(3-0) |
+//! | 0x02   | SA(15-0)                                                         |
+//! | 0x04   | LSA                                                              |
+//! | 0x06   | LEA                                                              |
+//! | 0x08   | D2R(15-11) D1R(10-6) EGHOLD(5) AR(4-0)                           |
+//! | 0x0A   | LPSLNK(14) KRS(13-10) DL(9-5) RR(4-0)                            |
+//! | 0x0C   | STWINH(9) SDIR(8) TL(7-0)                                        |
+//! | 0x0E   | MDL(15-12) MDXSL(11-6) MDYSL(5-0)                                |
+//! | 0x10   | OCT(14-11) FNS(9-0)                                              |
+//! | 0x12   | LFORE(15) LFOF(14-10) PLFOWS(9-8) PLFOS(7-5) ALFOWS(4-3) ALFOS(2-0) |
+//! | 0x14   | ISEL(6-3) IMXL(2-0)                                              |
+//! | 0x16   | DISDL(15-13) DIPAN(12-8) EFSDL(7-5) EFPAN(4-0)                   |
+//! | 0x18+  | inutilisés (lus à zéro)                                          |
+//!
+//! Les bits non définis sont ignorés à l'écriture et lus à zéro ; KYONEX
+//! n'est pas mémorisé, son écriture déclenche les key on/off de tous les
+//! slots (voir [`ScspAudio`](super::ScspAudio)). LSA et LEA sont des
+//! décalages en échantillons depuis SA.
+
+use super::{decode_direct_send, encode_direct_send, DIPAN_CENTER, DISDL_MAX};
+
+/// Taille du bloc de registres d'un slot, en octets
+pub const SLOT_REGISTER_SIZE: u32 = 0x20;
+
+/// Mots de 16 bits d'un slot
+pub const SLOT_REGISTER_WORDS: usize = (SLOT_REGISTER_SIZE / 2) as usize;
+
+/// Bit KYONEX du mot 0x00 : exécute les key on/off de tous les slots
+pub const KEY_EXECUTE: u16 = 0x1000;
+
+/// Atténuation d'un pas de TL, en dB
+const TL_STEP_DB: f32 = 0.375;
+
+/// Extrait un champ de `width` bits à partir du bit `shift`
+fn field(word: u16, shift: u32, width: u32) -> u8 {
+    ((word >> shift) & ((1 << width) - 1)) as u8
+}
+
+/// Place un champ de `width` bits au bit `shift`
+fn place(value: u8, shift: u32, width: u32) -> u16 {
+    (value as u16 & ((1 << width) - 1)) << shift
+}
+
+/// Taux de l'enveloppe (mots 0x08 et 0x0A)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EnvelopeRates {
+    /// AR : taux d'attaque (5 bits)
+    pub attack: u8,
+    /// D1R : taux de première décroissance (5 bits)
+    pub decay1: u8,
+    /// D2R : taux de seconde décroissance (5 bits)
+    pub decay2: u8,
+    /// RR : taux de relâchement (5 bits)
+    pub release: u8,
+    /// DL : niveau de passage de D1R à D2R (5 bits)
+    pub decay_level: u8,
+    /// KRS : mise à l'échelle des taux selon la hauteur (4 bits, 0xF = inactif)
+    pub key_rate_scaling: u8,
+    /// EGHOLD : maintien du niveau maximal pendant l'attaque
+    pub hold: bool,
+    /// LPSLNK : la décroissance commence à l'adresse de boucle
+    pub loop_link: bool,
+}
+
+/// Modulation par les échantillons d'autres slots (mot 0x0E)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ModulationRegisters {
+    /// MDL : profondeur (4 bits)
+    pub level: u8,
+    /// MDXSL : première source (6 bits)
+    pub x_select: u8,
+    /// MDYSL : seconde source (6 bits)
+    pub y_select: u8,
+}
+
+/// Oscillateur basse fréquence (mot 0x12)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LfoRegisters {
+    /// LFORE : remise à zéro de l'oscillateur
+    pub reset: bool,
+    /// LFOF : fréquence (5 bits)
+    pub frequency: u8,
+    /// PLFOWS : forme d'onde de la modulation de hauteur (2 bits)
+    pub pitch_waveform: u8,
+    /// PLFOS : profondeur de la modulation de hauteur (3 bits)
+    pub pitch_depth: u8,
+    /// ALFOWS : forme d'onde de la modulation d'amplitude (2 bits)
+    pub amplitude_waveform: u8,
+    /// ALFOS : profondeur de la modulation d'amplitude (3 bits)
+    pub amplitude_depth: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotRegisters {
+    /// KYONB : état de touche appliqué au prochain KYONEX
+    pub key_on: bool,
+
+    /// SBCTL : inversion des bits de l'échantillon (2 bits)
+    pub source_bit_control: u8,
+
+    /// SSCTL : source (0 = mémoire wave, 1 = bruit, 2 = zéro)
+    pub source_select: u8,
+
+    /// LPCTL : 0 = sans boucle (le slot s'arrête à la fin), 1 = boucle
+    /// normale, 2 = inverse, 3 = alternée
+    pub loop_control: u8,
+
+    /// PCM8B : échantillons 8 bits (16 bits sinon)
+    pub pcm8: bool,
+
+    /// SA : adresse de début dans la mémoire wave (20 bits)
+    pub start_address: u32,
+
+    /// LSA : début de boucle, en échantillons depuis SA
+    pub loop_address: u32,
+
+    /// LEA : fin de l'échantillon, en échantillons depuis SA
+    pub end_address: u32,
+
+    /// Taux et niveaux de l'enveloppe
+    pub envelope: EnvelopeRates,
+
+    /// STWINH : pas d'écriture dans la pile DSP
+    pub stack_write_inhibit: bool,
+
+    /// SDIR : sortie directe, sans enveloppe ni TL
+    pub sound_direct: bool,
+
+    /// TL : atténuation totale par pas de 0,375 dB (0 = plein niveau)
+    pub total_level: u8,
+
+    /// Modulation par d'autres slots
+    pub modulation: ModulationRegisters,
+
+    /// Fréquence du slot (OCT bits 14-11, FNS bits 9-0)
+    pub frequency: u16,
+
+    /// Oscillateur basse fréquence
+    pub lfo: LfoRegisters,
+
+    /// ISEL : entrée du mixeur DSP (4 bits)
+    pub input_select: u8,
+
+    /// IMXL : niveau d'entrée du mixeur DSP (3 bits)
+    pub input_mix_level: u8,
+
+    /// Panoramique de l'envoi direct (DIPAN, 5 bits)
+    pub pan: u8,
+
+    /// Niveau d'envoi direct (DISDL, 3 bits, 0 = muet)
+    pub direct_send_level: u8,
+
+    /// EFSDL : niveau d'envoi de la sortie effets (3 bits)
+    pub effect_send_level: u8,
+
+    /// EFPAN : panoramique de la sortie effets (5 bits)
+    pub effect_pan: u8,
+
+    /// Volume du slot, hors registres matériels (commandes sonores HLE)
+    pub volume: u16,
+
+    /// Type d'onde, hors registres matériels (PCM, carré, triangle, bruit)
+    pub wave_type: u8,
+}
+
+impl SlotRegisters {
+    /// Lit le mot à l'offset `offset` (pair, relatif au slot)
+    pub fn read_word(&self, offset: u32) -> u16 {
+        match offset & (SLOT_REGISTER_SIZE - 2) {
+            0x00 => {
+                place(self.key_on as u8, 11, 1)
+                    | place(self.source_bit_control, 9, 2)
+                    | place(self.source_select, 7, 2)
+                    | place(self.loop_control, 5, 2)
+                    | place(self.pcm8 as u8, 4, 1)
+                    | ((self.start_address >> 16) & 0x0F) as u16
+            }
+            0x02 => self.start_address as u16,
+            0x04 => self.loop_address as u16,
+            0x06 => self.end_address as u16,
+            0x08 => {
+                let envelope = &self.envelope;
+                place(envelope.decay2, 11, 5)
+                    | place(envelope.decay1, 6, 5)
+                    | place(envelope.hold as u8, 5, 1)
+                    | place(envelope.attack, 0, 5)
+            }
+            0x0A => {
+                let envelope = &self.envelope;
+                place(envelope.loop_link as u8, 14, 1)
+                    | place(envelope.key_rate_scaling, 10, 4)
+                    | place(envelope.decay_level, 5, 5)
+                    | place(envelope.release, 0, 5)
+            }
+            0x0C => {
+                place(self.stack_write_inhibit as u8, 9, 1) | place(self.sound_direct as u8, 8, 1) | self.total_level as u16
+            }
+            0x0E => {
+                let modulation = &self.modulation;
+                place(modulation.level, 12, 4) | place(modulation.x_select, 6, 6) | place(modulation.y_select, 0, 6)
+            }
+            0x10 => self.frequency & 0x7BFF,
+            0x12 => {
+                let lfo = &self.lfo;
+                place(lfo.reset as u8, 15, 1)
+                    | place(lfo.frequency, 10, 5)
+                    | place(lfo.pitch_waveform, 8, 2)
+                    | place(lfo.pitch_depth, 5, 3)
+                    | place(lfo.amplitude_waveform, 3, 2)
+                    | place(lfo.amplitude_depth, 0, 3)
+            }
+            0x14 => place(self.input_select, 3, 4) | place(self.input_mix_level, 0, 3),
+            0x16 => {
+                encode_direct_send(self.direct_send_level, self.pan)
+                    | place(self.effect_send_level, 5, 3)
+                    | place(self.effect_pan, 0, 5)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Écrit le mot à l'offset `offset` (pair, relatif au slot) ; seuls les
+    /// champs de ce mot changent
+    pub fn write_word(&mut self, offset: u32, value: u16) {
+        match offset & (SLOT_REGISTER_SIZE - 2) {
+            0x00 => {
+                self.key_on = field(value, 11, 1) != 0;
+                self.source_bit_control = field(value, 9, 2);
+                self.source_select = field(value, 7, 2);
+                self.loop_control = field(value, 5, 2);
+                self.pcm8 = field(value, 4, 1) != 0;
+                self.start_address = (self.start_address & 0xFFFF) | ((value as u32 & 0x0F) << 16);
+            }
+            0x02 => self.start_address = (self.start_address & 0xF_0000) | value as u32,
+            0x04 => self.loop_address = value as u32,
+            0x06 => self.end_address = value as u32,
+            0x08 => {
+                self.envelope.decay2 = field(value, 11, 5);
+                self.envelope.decay1 = field(value, 6, 5);
+                self.envelope.hold = field(value, 5, 1) != 0;
+                self.envelope.attack = field(value, 0, 5);
+            }
+            0x0A => {
+                self.envelope.loop_link = field(value, 14, 1) != 0;
+                self.envelope.key_rate_scaling = field(value, 10, 4);
+                self.envelope.decay_level = field(value, 5, 5);
+                self.envelope.release = field(value, 0, 5);
+            }
+            0x0C => {
+                self.stack_write_inhibit = field(value, 9, 1) != 0;
+                self.sound_direct = field(value, 8, 1) != 0;
+                self.total_level = value as u8;
+            }
+            0x0E => {
+                self.modulation = ModulationRegisters {
+                    level: field(value, 12, 4),
+                    x_select: field(value, 6, 6),
+                    y_select: field(value, 0, 6),
+                };
+            }
+            0x10 => self.frequency = value & 0x7BFF,
+            0x12 => {
+                self.lfo = LfoRegisters {
+                    reset: field(value, 15, 1) != 0,
+                    frequency: field(value, 10, 5),
+                    pitch_waveform: field(value, 8, 2),
+                    pitch_depth: field(value, 5, 3),
+                    amplitude_waveform: field(value, 3, 2),
+                    amplitude_depth: field(value, 0, 3),
+                };
+            }
+            0x14 => {
+                self.input_select = field(value, 3, 4);
+                self.input_mix_level = field(value, 0, 3);
+            }
+            0x16 => {
+                (self.direct_send_level, self.pan) = decode_direct_send(value);
+                self.effect_send_level = field(value, 5, 3);
+                self.effect_pan = field(value, 0, 5);
+            }
+            _ => {}
+        }
+    }
+
+    /// Lecture en boucle (LPCTL non nul) ; sinon le slot s'arrête à la fin
+    pub fn loops(&self) -> bool {
+        self.loop_control != 0
+    }
+
+    /// Adresse absolue du début de boucle
+    pub fn loop_start(&self) -> u32 {
+        self.start_address + self.loop_address
+    }
+
+    /// Adresse absolue de fin de l'échantillon
+    pub fn loop_end(&self) -> u32 {
+        self.start_address + self.end_address
+    }
+
+    /// Gain linéaire de l'atténuation TL (ignorée en sortie directe SDIR)
+    pub fn total_level_gain(&self) -> f32 {
+        if self.sound_direct {
+            1.0
+        } else {
+            10f32.powf(-(self.total_level as f32 * TL_STEP_DB) / 20.0)
+        }
+    }
+}
+
+impl Default for SlotRegisters {
+    fn default() -> Self {
+        Self {
+            key_on: false,
+            source_bit_control: 0,
+            source_select: 0,
+            loop_control: 1,
+            pcm8: true,
+            start_address: 0,
+            loop_address: 0,
+            end_address: 0x1000,
+            envelope: EnvelopeRates::default(),
+            stack_write_inhibit: false,
+            sound_direct: false,
+            total_level: 0,
+            modulation: ModulationRegisters::default(),
+            frequency: 0x0000, // OCT 0, FNS 0 : hauteur native
+            lfo: LfoRegisters::default(),
+            input_select: 0,
+            input_mix_level: 0,
+            pan: DIPAN_CENTER,
+            direct_send_level: DISDL_MAX,
+            effect_send_level: 0,
+            effect_pan: 0,
+            volume: 0x0FFF,
+            wave_type: 0, // PCM
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_word_roundtrips() {
+        let words: [u16; SLOT_REGISTER_WORDS] = [
+            0x0FFF, 0x1234, 0x0100, 0x0800, 0xFFFF, 0x7FFF, 0x03FF, 0xFFFF,
+            0x7BFF, 0xFFFF, 0x007F, 0xFFFF, 0, 0, 0, 0,
+        ];
+        let mut slot = SlotRegisters::default();
+        for (index, &word) in words.iter().enumerate() {
+            slot.write_word(index as u32 * 2, word);
+        }
+        for (index, &word) in words.iter().enumerate() {
+            assert_eq!(slot.read_word(index as u32 * 2), word, "mot 0x{:02X}", index * 2);
+        }
+
+        assert_eq!(slot.start_address, 0xF_1234);
+        assert!(slot.key_on && slot.pcm8 && slot.loops());
+        assert_eq!((slot.loop_start(), slot.loop_end()), (0xF_1334, 0xF_1A34));
+        assert_eq!(slot.envelope.attack, 0x1F);
+        assert_eq!(slot.total_level, 0xFF);
+        assert_eq!((slot.direct_send_level, slot.pan), (7, 0x1F));
+    }
+
+    #[test]
+    fn test_partial_writes_keep_other_fields() {
+        let mut slot = SlotRegisters::default();
+        slot.write_word(0x02, 0xBEEF);
+        slot.write_word(0x00, 0x0005);
+        assert_eq!(slot.start_address, 0x5_BEEF);
+
+        // KYONEX et les bits non définis ne sont pas mémorisés
+        slot.write_word(0x00, KEY_EXECUTE | 0x0800 | 0x0005);
+        assert_eq!(slot.read_word(0x00), 0x0805);
+        slot.write_word(0x10, 0xFFFF);
+        assert_eq!(slot.frequency, 0x7BFF);
+
+        // Le mot 0x16 partage DISDL/DIPAN et l'envoi effets
+        slot.write_word(0x16, 0xD300 | 0x00A5);
+        assert_eq!((slot.direct_send_level, slot.pan, slot.effect_send_level, slot.effect_pan), (6, 0x13, 5, 5));
+        assert_eq!(slot.read_word(0x1E), 0);
+
+        slot.total_level = 16;
+        assert!((slot.total_level_gain() - 10f32.powf(-6.0 / 20.0)).abs() < 1e-6);
+    }
+}
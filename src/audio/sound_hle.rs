@@ -0,0 +1,221 @@
+//! Commandes sonores simulées (HLE)
+//!
+//! Tant que le 68000 de la carte son n'est pas émulé, les commandes envoyées
+//! par le programme principal restent sans effet. Le mode HLE les lit à sa
+//! place sur le port série et joue directement sur le SCSP les échantillons
+//! que la base des jeux associe aux commandes connues. Il est désactivé
+//! quand l'émulation du 68000 (LLE) est demandée.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::ScspAudio;
+use crate::memory::{SoundLatch, SCSP_MIDI_INPUT};
+
+/// Échantillon de la ROM audio joué sur un slot du SCSP
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleCue {
+    /// Slot du SCSP (0 à 31)
+    pub slot: u8,
+    /// Début de l'échantillon dans la ROM audio
+    pub start: u32,
+    /// Fin (exclue) de l'échantillon
+    pub end: u32,
+    /// Point de boucle ; absent, l'échantillon est joué une seule fois
+    #[serde(default)]
+    pub loop_start: Option<u32>,
+    /// Hauteur (OCT/FNS), 0 pour la hauteur native
+    #[serde(default)]
+    pub frequency: u16,
+    /// Volume sur 12 bits
+    #[serde(default = "default_cue_volume")]
+    pub volume: u16,
+}
+
+fn default_cue_volume() -> u16 {
+    0x0FFF
+}
+
+/// Étape d'une séquence : un échantillon lancé après un délai
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceStep {
+    /// Frames d'attente après l'étape précédente
+    #[serde(default)]
+    pub delay: u32,
+    pub sample: SampleCue,
+}
+
+/// Effet d'une commande sonore en mode HLE
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SoundCueAction {
+    /// Joue un échantillon
+    Sample(SampleCue),
+    /// Joue une suite d'échantillons espacés dans le temps
+    Sequence { steps: Vec<SequenceStep> },
+    /// Arrête un slot
+    Stop { slot: u8 },
+    /// Arrête tous les slots et les séquences en cours
+    StopAll,
+}
+
+/// Commande sonore connue d'un jeu, dans la base des jeux
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoundCommandConfig {
+    /// Octet envoyé par le programme principal
+    pub command: u8,
+    /// Nom de la commande, pour le journal
+    #[serde(default)]
+    pub name: String,
+    #[serde(flatten)]
+    pub action: SoundCueAction,
+}
+
+/// Séquence en cours de lecture
+#[derive(Debug, Clone)]
+struct ActiveSequence {
+    steps: Vec<SequenceStep>,
+    next: usize,
+    /// Frames restantes avant l'étape suivante
+    wait: u32,
+}
+
+/// Table des commandes sonores d'un jeu et séquences en cours
+#[derive(Debug, Clone, Default)]
+pub struct SoundHle {
+    commands: BTreeMap<u8, SoundCommandConfig>,
+    sequences: Vec<ActiveSequence>,
+    /// Commandes reçues sans entrée dans la table, avec leur nombre
+    unknown_commands: BTreeMap<u8, u64>,
+}
+
+impl SoundHle {
+    pub fn new(commands: impl IntoIterator<Item = SoundCommandConfig>) -> Self {
+        Self {
+            commands: commands.into_iter().map(|entry| (entry.command, entry)).collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Commandes connues, pour le journal de chargement
+    pub fn report(&self) -> String {
+        self.commands
+            .values()
+            .map(|entry| format!("  0x{:02X} {}", entry.command, entry.name))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Entrée de la table pour `command`
+    pub fn command(&self, command: u8) -> Option<&SoundCommandConfig> {
+        self.commands.get(&command)
+    }
+
+    /// Abandonne les séquences en cours (reset de la carte son)
+    pub fn reset(&mut self) {
+        self.sequences.clear();
+    }
+
+    /// Commandes reçues sans entrée dans la table, avec leur nombre
+    pub fn unknown_commands(&self) -> &BTreeMap<u8, u64> {
+        &self.unknown_commands
+    }
+
+    /// Lit les commandes en attente sur le port série à la place du 68000,
+    /// puis fait avancer les séquences d'une frame
+    pub fn run_frame(&mut self, latch: &mut SoundLatch, audio: &mut ScspAudio) {
+        while latch.sound_irq() {
+            let command = latch.sound_read(SCSP_MIDI_INPUT) as u8;
+            self.dispatch(command, audio);
+        }
+
+        for sequence in &mut self.sequences {
+            while sequence.wait == 0 {
+                let Some(step) = sequence.steps.get(sequence.next) else { break };
+                play(&step.sample, audio);
+                sequence.next += 1;
+                sequence.wait = sequence.steps.get(sequence.next).map_or(0, |step| step.delay);
+            }
+            sequence.wait = sequence.wait.saturating_sub(1);
+        }
+        self.sequences.retain(|sequence| sequence.next < sequence.steps.len());
+    }
+
+    fn dispatch(&mut self, command: u8, audio: &mut ScspAudio) {
+        let Some(entry) = self.commands.get(&command) else {
+            let count = self.unknown_commands.entry(command).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                log::warn!("Son HLE: commande inconnue 0x{:02X}", command);
+            }
+            return;
+        };
+
+        log::debug!("Son HLE: commande 0x{:02X} ({})", command, entry.name);
+        match &entry.action {
+            SoundCueAction::Sample(sample) => play(sample, audio),
+            SoundCueAction::Sequence { steps } => self.sequences.push(ActiveSequence {
+                steps: steps.clone(),
+                next: 0,
+                wait: steps.first().map_or(0, |step| step.delay),
+            }),
+            SoundCueAction::Stop { slot } => audio.stop_slot(*slot as usize),
+            SoundCueAction::StopAll => {
+                self.sequences.clear();
+                audio.stop_all_slots();
+            }
+        }
+    }
+}
+
+fn play(sample: &SampleCue, audio: &mut ScspAudio) {
+    audio.play_sample(sample.slot as usize, sample.start, sample.end, sample.loop_start, sample.frequency, sample.volume);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SOUND_LATCH_DATA;
+
+    fn sample(slot: u8) -> SampleCue {
+        SampleCue { slot, start: 0, end: 0x100, loop_start: None, frequency: 0, volume: 0x0FFF }
+    }
+
+    #[test]
+    fn test_commands_start_samples_and_sequences() {
+        let json = r#"[
+            {"command": 1, "name": "pièce", "action": "sample", "slot": 0, "start": 0, "end": 256},
+            {"command": 2, "name": "musique", "action": "sequence", "steps": [
+                {"sample": {"slot": 1, "start": 0, "end": 256, "loop_start": 0}},
+                {"delay": 2, "sample": {"slot": 2, "start": 0, "end": 256}}
+            ]},
+            {"command": 3, "action": "stop_all"}
+        ]"#;
+        let commands: Vec<SoundCommandConfig> = serde_json::from_str(json).unwrap();
+        assert_eq!(commands[0].action, SoundCueAction::Sample(sample(0)));
+        let mut hle = SoundHle::new(commands);
+        let mut latch = SoundLatch::new();
+        let mut audio = ScspAudio::headless();
+
+        latch.main_write(SOUND_LATCH_DATA, 1);
+        latch.main_write(SOUND_LATCH_DATA, 2);
+        latch.main_write(SOUND_LATCH_DATA, 9);
+        hle.run_frame(&mut latch, &mut audio);
+        assert!(!latch.sound_irq());
+        assert!(latch.take_main_irq());
+        assert!(audio.slot_active(0) && audio.slot_active(1));
+        assert!(!audio.slot_active(2));
+        assert_eq!(hle.unknown_commands().get(&9), Some(&1));
+
+        // Deuxième étape deux frames plus tard
+        hle.run_frame(&mut latch, &mut audio);
+        assert!(!audio.slot_active(2));
+        hle.run_frame(&mut latch, &mut audio);
+        assert!(audio.slot_active(2));
+        assert!(hle.sequences.is_empty());
+    }
+}
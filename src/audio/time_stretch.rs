@@ -0,0 +1,305 @@
+//! Étirement temporel de l'audio en avance rapide et au ralenti
+//!
+//! À une vitesse d'émulation `speed`, le SCSP produit `speed` secondes de
+//! son par seconde de l'hôte. [`TimeStretch`] ramène ce flux à la durée
+//! réelle sans changer sa hauteur, par WSOLA : des segments de
+//! [`WINDOW_MS`] sont lus dans l'entrée tous les `speed` demi-segments et
+//! recollés tous les demi-segments avec un fondu, chacun décalé d'au plus
+//! [`SEARCH_MS`] pour se raccorder au mieux au précédent. Sans étirement
+//! ([`StretchMode::Resample`]), le flux est simplement rééchantillonné et la
+//! hauteur suit la vitesse, comme une bande magnétique. À vitesse 1, les
+//! échantillons traversent l'étage sans modification.
+
+/// Longueur des segments recollés, en millisecondes
+pub const WINDOW_MS: u32 = 20;
+
+/// Décalage maximal d'un segment pour le raccord, en millisecondes
+pub const SEARCH_MS: u32 = 5;
+
+/// Vitesses acceptées
+pub const MIN_SPEED: f32 = 0.1;
+pub const MAX_SPEED: f32 = 8.0;
+
+/// Traitement appliqué hors de la vitesse normale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StretchMode {
+    /// Durée ramenée à la vitesse normale, hauteur conservée
+    #[default]
+    Wsola,
+    /// Rééchantillonnage : la hauteur suit la vitesse
+    Resample,
+}
+
+/// Étage d'étirement, sur des trames entrelacées de `channels` canaux
+#[derive(Debug, Clone)]
+pub struct TimeStretch {
+    mode: StretchMode,
+    speed: f32,
+    channels: usize,
+    /// Demi-segment (pas de synthèse), en trames
+    hop: usize,
+    /// Décalage maximal d'un segment, en trames
+    search: usize,
+    /// Demi-fenêtre de Hann montante, `hop` coefficients
+    fade_in: Vec<f32>,
+
+    /// Trames reçues et pas encore entièrement consommées
+    input: Vec<f32>,
+    /// Début nominal du prochain segment dans `input`, en trames
+    position: f64,
+    /// Suite naturelle du segment précédent dans `input` (son début plus un
+    /// demi-segment), à laquelle le suivant doit ressembler
+    continuation: Option<usize>,
+    /// Seconde moitié du segment précédent, déjà atténuée, à ajouter au
+    /// début du suivant
+    tail: Vec<f32>,
+}
+
+impl TimeStretch {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        let hop = (sample_rate * WINDOW_MS / 2000).max(1) as usize;
+        let fade_in = (0..hop)
+            .map(|i| (std::f32::consts::FRAC_PI_2 * (i as f32 + 0.5) / hop as f32).sin().powi(2))
+            .collect();
+        Self {
+            mode: StretchMode::default(),
+            speed: 1.0,
+            channels: channels.max(1) as usize,
+            hop,
+            search: (sample_rate * SEARCH_MS / 1000) as usize,
+            fade_in,
+            input: Vec::new(),
+            position: 0.0,
+            continuation: None,
+            tail: Vec::new(),
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Vitesse d'émulation, bornée à [`MIN_SPEED`]..=[`MAX_SPEED`] ; le
+    /// retour à 1 laisse repartir les trames retenues au prochain appel
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = if speed.is_finite() { speed.clamp(MIN_SPEED, MAX_SPEED) } else { 1.0 };
+    }
+
+    pub fn mode(&self) -> StretchMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: StretchMode) {
+        self.mode = mode;
+    }
+
+    /// Vrai si les trames traversent l'étage sans modification
+    pub fn is_bypassed(&self) -> bool {
+        self.speed == 1.0 && self.input.is_empty() && self.tail.is_empty()
+    }
+
+    /// Oublie les trames retenues (reset, changement de sortie)
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.tail.clear();
+        self.position = 0.0;
+        self.continuation = None;
+    }
+
+    /// Ajoute à `output` les trames correspondant à `input`
+    pub fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        if self.is_bypassed() {
+            output.extend_from_slice(input);
+            return;
+        }
+        self.input.extend_from_slice(input);
+        if self.speed == 1.0 {
+            self.flush(output);
+            return;
+        }
+        match self.mode {
+            StretchMode::Wsola => self.wsola(output),
+            StretchMode::Resample => self.resample(output),
+        }
+    }
+
+    /// Retour à la vitesse normale : le segment en cours est prolongé par
+    /// les trames retenues, sans coupure
+    fn flush(&mut self, output: &mut Vec<f32>) {
+        let channels = self.channels;
+        let start = match (self.continuation, self.mode) {
+            (Some(continuation), StretchMode::Wsola) => continuation,
+            _ => self.position as usize,
+        };
+        let rest = self.input.get(start * channels..).unwrap_or_default();
+        for (index, &sample) in rest.iter().enumerate() {
+            let frame = index / channels;
+            let sample = match self.tail.get(index) {
+                Some(&tail) => tail + sample * self.fade_in[frame],
+                None => sample,
+            };
+            output.push(sample);
+        }
+        self.clear();
+    }
+
+    fn wsola(&mut self, output: &mut Vec<f32>) {
+        let channels = self.channels;
+        let (hop, search) = (self.hop, self.search);
+        let window = 2 * hop;
+        let step = hop as f64 * self.speed as f64;
+
+        loop {
+            let frames = self.input.len() / channels;
+            let nominal = self.position.round() as usize;
+            if nominal + search + window > frames {
+                break;
+            }
+
+            // Segment le mieux raccordé à la suite naturelle du précédent
+            let start = match self.continuation {
+                Some(template) => {
+                    let low = nominal.saturating_sub(search);
+                    (low..=nominal + search)
+                        .step_by(2)
+                        .map(|candidate| (candidate, self.correlation(template, candidate)))
+                        .fold((nominal, f32::MIN), |best, (candidate, score)| if score > best.1 { (candidate, score) } else { best })
+                        .0
+                }
+                None => nominal,
+            };
+
+            // Première moitié : fondu avec la fin du segment précédent (ou
+            // trames brutes au premier segment) ; seconde moitié retenue
+            for frame in 0..hop {
+                for channel in 0..channels {
+                    let sample = self.input[(start + frame) * channels + channel];
+                    let index = frame * channels + channel;
+                    output.push(match self.tail.get(index) {
+                        Some(&tail) => tail + sample * self.fade_in[frame],
+                        None => sample,
+                    });
+                }
+            }
+            self.tail.clear();
+            for frame in hop..window {
+                let fade_out = self.fade_in[window - 1 - frame];
+                for channel in 0..channels {
+                    self.tail.push(self.input[(start + frame) * channels + channel] * fade_out);
+                }
+            }
+
+            self.continuation = Some(start + hop);
+            self.position += step;
+        }
+        self.compact();
+    }
+
+    /// Ressemblance entre `hop` trames à `template` et à `candidate`, sur la
+    /// somme des canaux, une trame sur deux
+    fn correlation(&self, template: usize, candidate: usize) -> f32 {
+        let channels = self.channels;
+        let frame = |start: usize, offset: usize| -> f32 {
+            let base = (start + offset) * channels;
+            self.input[base..base + channels].iter().sum()
+        };
+        (0..self.hop).step_by(2).map(|offset| frame(template, offset) * frame(candidate, offset)).sum()
+    }
+
+    fn resample(&mut self, output: &mut Vec<f32>) {
+        let channels = self.channels;
+        let frames = self.input.len() / channels;
+        while (self.position as usize) + 1 < frames {
+            let index = self.position as usize;
+            let fraction = (self.position - index as f64) as f32;
+            for channel in 0..channels {
+                let a = self.input[index * channels + channel];
+                let b = self.input[(index + 1) * channels + channel];
+                output.push(a + (b - a) * fraction);
+            }
+            self.position += self.speed as f64;
+        }
+        self.compact();
+    }
+
+    /// Retire les trames dont plus aucun segment n'aura besoin
+    fn compact(&mut self) {
+        let position = self.position.floor() as usize;
+        let needed = match (self.continuation, self.mode) {
+            (Some(continuation), StretchMode::Wsola) => continuation.min(position.saturating_sub(self.search)),
+            _ => position,
+        };
+        let consumed = needed.min(self.input.len() / self.channels);
+        if consumed == 0 {
+            return;
+        }
+        self.input.drain(..consumed * self.channels);
+        self.position -= consumed as f64;
+        self.continuation = self.continuation.map(|continuation| continuation - consumed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sinusoïde stéréo de `frames` trames à `frequency` Hz
+    fn tone(frames: usize, frequency: f32, sample_rate: u32) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|n| {
+                let sample = (std::f32::consts::TAU * frequency * n as f32 / sample_rate as f32).sin() * 0.5;
+                [sample, sample]
+            })
+            .collect()
+    }
+
+    /// Fréquence estimée par les passages par zéro montants du canal gauche
+    fn frequency(samples: &[f32], sample_rate: u32) -> f32 {
+        let left: Vec<f32> = samples.iter().step_by(2).copied().collect();
+        let crossings = left.windows(2).filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0).count();
+        crossings as f32 * sample_rate as f32 / left.len() as f32
+    }
+
+    #[test]
+    fn test_stretch_keeps_pitch_and_scales_duration() {
+        let rate = 44_100;
+        let input = tone(rate as usize * 2, 440.0, rate);
+
+        // Vitesse normale : identique
+        let mut stretch = TimeStretch::new(rate, 2);
+        let mut output = Vec::new();
+        stretch.process(&input, &mut output);
+        assert!(stretch.is_bypassed());
+        assert_eq!(output, input);
+
+        for speed in [2.0f32, 0.5] {
+            let mut stretch = TimeStretch::new(rate, 2);
+            stretch.set_speed(speed);
+            let mut output = Vec::new();
+            for chunk in input.chunks(1470) {
+                stretch.process(chunk, &mut output);
+            }
+            // Durée divisée par la vitesse (aux trames retenues près)
+            let expected = input.len() as f32 / speed;
+            assert!((output.len() as f32 - expected).abs() < expected * 0.03, "{} : {} / {}", speed, output.len(), expected);
+            // Hauteur conservée, sans saturation
+            let measured = frequency(&output, rate);
+            assert!((measured - 440.0).abs() < 6.0, "{} : {} Hz", speed, measured);
+            assert!(output.iter().all(|sample| sample.abs() <= 0.55));
+
+            // Retour à la vitesse normale : les trames retenues repartent
+            stretch.set_speed(1.0);
+            stretch.process(&input[..2 * 441], &mut output);
+            assert!(stretch.is_bypassed());
+        }
+
+        // Rééchantillonnage : la hauteur suit la vitesse
+        let mut stretch = TimeStretch::new(rate, 2);
+        stretch.set_mode(StretchMode::Resample);
+        stretch.set_speed(2.0);
+        let mut output = Vec::new();
+        stretch.process(&input, &mut output);
+        assert!((output.len() as i64 - input.len() as i64 / 2).abs() <= 2);
+        assert!((frequency(&output, rate) - 880.0).abs() < 6.0);
+    }
+}
@@ -0,0 +1,198 @@
+//! Inspection de la mémoire wave
+//!
+//! [`wave_regions`] relève les plages de la mémoire wave lues par les slots
+//! PCM actifs (de SA à SA + LEA) et les slots qui lisent chacune. Une plage
+//! qui déborde de la mémoire wave, ou qui en chevauche une autre sans la
+//! recouvrir exactement, est le signe habituel d'adresses mal décodées :
+//! le slot joue alors des octets qui ne sont pas un échantillon, d'où un
+//! bruit parasite. [`wave_peaks`] et [`render_waveform`] dessinent une plage
+//! en texte, et [`preview_region`] la fait entendre sur la sortie audio sans
+//! occuper de slot émulé.
+
+use std::fmt;
+
+use crate::error::{AudioError, Result};
+
+use super::{ScspAudio, SCSP_SAMPLE_RATE, SCSP_SLOT_COUNT, WAVE_MEMORY_SIZE};
+
+/// Plage de la mémoire wave lue par un ou plusieurs slots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaveRegion {
+    pub start: u32,
+
+    /// Adresse de fin (exclue)
+    pub end: u32,
+
+    /// Début de boucle, si les slots bouclent
+    pub loop_start: Option<u32>,
+
+    /// Slots qui lisent cette plage
+    pub slots: Vec<usize>,
+
+    /// La plage sort de la mémoire wave
+    pub out_of_range: bool,
+
+    /// La plage chevauche partiellement une autre plage
+    pub overlaps: bool,
+}
+
+impl fmt::Display for WaveRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:06X}-0x{:06X} ({} octets)", self.start, self.end, self.end.saturating_sub(self.start))?;
+        if let Some(loop_start) = self.loop_start {
+            write!(f, " boucle 0x{:06X}", loop_start)?;
+        }
+        let slots: Vec<String> = self.slots.iter().map(|slot| slot.to_string()).collect();
+        write!(f, " slots {}", slots.join(","))?;
+        if self.out_of_range {
+            write!(f, " ⚠ hors mémoire wave")?;
+        }
+        if self.overlaps {
+            write!(f, " ⚠ chevauchement")?;
+        }
+        Ok(())
+    }
+}
+
+/// Plages lues par les slots PCM actifs, triées par adresse ; les slots qui
+/// lisent la même plage sont regroupés
+pub fn wave_regions(audio: &ScspAudio) -> Vec<WaveRegion> {
+    let mut regions: Vec<WaveRegion> = Vec::new();
+    for slot in (0..SCSP_SLOT_COUNT).filter(|&slot| audio.slot_active(slot)) {
+        let registers = &audio.registers.slot_registers[slot];
+        if registers.wave_type != 0 {
+            continue;
+        }
+        let (start, end) = (registers.start_address, registers.loop_end());
+        let loop_start = (registers.loops() && registers.loop_address < registers.end_address).then(|| registers.loop_start());
+        match regions.iter_mut().find(|region| (region.start, region.end, region.loop_start) == (start, end, loop_start)) {
+            Some(region) => region.slots.push(slot),
+            None => regions.push(WaveRegion {
+                start,
+                end,
+                loop_start,
+                slots: vec![slot],
+                out_of_range: end as usize > WAVE_MEMORY_SIZE,
+                overlaps: false,
+            }),
+        }
+    }
+
+    regions.sort_by_key(|region| (region.start, region.end));
+    for index in 0..regions.len() {
+        let (start, end) = (regions[index].start, regions[index].end);
+        regions[index].overlaps = regions.iter().enumerate().any(|(other, region)| {
+            other != index && (region.start, region.end) != (start, end) && region.start < end && start < region.end
+        });
+    }
+    regions
+}
+
+/// Échantillon PCM 8 bits tel que le lit un slot
+fn decode(byte: u8) -> f32 {
+    (byte as f32 - 128.0) / 128.0
+}
+
+/// Minimum et maximum des échantillons de `start..end` par colonne ; les
+/// octets hors de la mémoire wave valent 0
+pub fn wave_peaks(wave_memory: &[u8], start: u32, end: u32, columns: usize) -> Vec<(f32, f32)> {
+    let length = end.saturating_sub(start) as usize;
+    if length == 0 || columns == 0 {
+        return Vec::new();
+    }
+    let columns = columns.min(length);
+    (0..columns)
+        .map(|column| {
+            let from = start as usize + column * length / columns;
+            let to = start as usize + (column + 1) * length / columns;
+            (from..to)
+                .map(|address| wave_memory.get(address).map_or(0.0, |&byte| decode(byte)))
+                .fold((f32::MAX, f32::MIN), |(min, max), sample| (min.min(sample), max.max(sample)))
+        })
+        .collect()
+}
+
+/// Dessine des crêtes sur `rows` lignes de texte, amplitude maximale en haut
+pub fn render_waveform(peaks: &[(f32, f32)], rows: usize) -> String {
+    let rows = rows.max(1);
+    let mut text = String::new();
+    for row in 0..rows {
+        // Amplitudes couvertes par la ligne, de 1.0 à -1.0
+        let top = 1.0 - 2.0 * row as f32 / rows as f32;
+        let bottom = 1.0 - 2.0 * (row + 1) as f32 / rows as f32;
+        text.extend(peaks.iter().map(|&(min, max)| if min <= top && max >= bottom { '█' } else { ' ' }));
+        text.push('\n');
+    }
+    text
+}
+
+/// Plage `start..end` saisie par l'utilisateur, fin ramenée à la taille de
+/// la mémoire wave ; erreur si elle est vide
+pub fn wave_range(start: u32, end: u32) -> Result<(u32, u32)> {
+    let end = end.min(WAVE_MEMORY_SIZE as u32);
+    if end <= start {
+        return Err(AudioError::WaveRange { start, end }.into());
+    }
+    Ok((start, end))
+}
+
+/// Joue `start..end` sur la sortie audio, à la fréquence de base du SCSP
+/// et sans enveloppe ; renvoie le nombre de trames envoyées
+pub fn preview_region(audio: &mut ScspAudio, start: u32, end: u32) -> Result<usize> {
+    let (start, end) = wave_range(start, end)?;
+    let step = SCSP_SAMPLE_RATE as f32 / audio.sample_rate.max(1) as f32;
+    let length = end.saturating_sub(start) as f32;
+    let channels = audio.channels.max(1) as usize;
+    let wave = &audio.registers.wave_memory;
+
+    let mut samples = Vec::with_capacity((length / step) as usize * channels + channels);
+    let mut position = 0.0f32;
+    while position < length {
+        let address = start as usize + position as usize;
+        let sample = wave.get(address).map_or(0.0, |&byte| decode(byte)) * audio.volume;
+        samples.extend(std::iter::repeat_n(sample, channels));
+        position += step;
+    }
+    audio.output.push_samples(&samples);
+    Ok(samples.len() / channels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encode_frequency;
+
+    #[test]
+    fn test_regions_waveform_and_preview() {
+        let mut audio = ScspAudio::headless();
+        let mut wave = vec![128u8; 0x300];
+        wave[0x100..0x200].iter_mut().enumerate().for_each(|(i, byte)| *byte = if i < 0x80 { 255 } else { 0 });
+        audio.load_wave_memory(&wave);
+
+        audio.play_sample(3, 0x100, 0x200, Some(0x180), encode_frequency(0, 0), 0x800);
+        audio.play_sample(9, 0x100, 0x200, Some(0x180), encode_frequency(0, 0), 0x800);
+        audio.play_sample(1, 0x1C0, 0x240, None, encode_frequency(0, 0), 0x800);
+        audio.play_sample(2, 0x1FF_F00, 0x200_100, None, encode_frequency(0, 0), 0x800);
+
+        let regions = wave_regions(&audio);
+        assert_eq!(regions.len(), 3);
+        assert_eq!((regions[0].start, regions[0].end, regions[0].loop_start), (0x100, 0x200, Some(0x180)));
+        assert_eq!(regions[0].slots, vec![3, 9]);
+        assert!(regions[0].overlaps && regions[1].overlaps);
+        assert!(regions[2].out_of_range && !regions[2].overlaps);
+        assert_eq!(regions[0].to_string(), "0x000100-0x000200 (256 octets) boucle 0x000180 slots 3,9 ⚠ chevauchement");
+
+        // Créneau : moitié haute puis moitié basse
+        let peaks = wave_peaks(&audio.registers.wave_memory, 0x100, 0x200, 4);
+        assert_eq!(peaks.len(), 4);
+        assert!(peaks[0].0 > 0.9 && peaks[3].1 < -0.9);
+        assert_eq!(render_waveform(&peaks, 2), "██  \n  ██\n");
+
+        // Sortie nulle à 44,1 kHz : une trame par octet
+        assert_eq!(preview_region(&mut audio, 0x100, 0x200).unwrap(), 0x100);
+        // Plage saisie de travers : vide, ou fin ramenée à la mémoire wave
+        assert!(preview_region(&mut audio, 0x200, 0x100).is_err());
+        assert_eq!(wave_range(0x1F_FF00, u32::MAX).unwrap(), (0x1F_FF00, 0x20_0000));
+        assert!(wave_range(0x20_0000, u32::MAX).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+//! Backend Web Audio pour le frontend navigateur (feature `web`, cible wasm32)
+//!
+//! Chaque lot d'échantillons devient un `AudioBuffer` programmé juste après
+//! le précédent sur l'horloge du contexte audio. Le remplissage de la file
+//! est l'avance de cette programmation sur l'horloge, ce qui permet au
+//! contrôle de débit de fonctionner comme avec cpal.
+
+use web_sys::{AudioContext, AudioContextOptions};
+
+use super::backend::AudioBackend;
+use crate::error::{AudioError, Result};
+
+/// Avance maximale sur l'horloge audio, en secondes ; au-delà, les
+/// échantillons sont jetés plutôt que d'accumuler de la latence
+const MAX_QUEUED_SECONDS: f64 = 0.25;
+
+/// Sortie audio d'un navigateur
+pub struct WebAudioBackend {
+    context: AudioContext,
+    sample_rate: u32,
+    channels: u16,
+
+    /// Instant (horloge du contexte, en secondes) où finit le dernier lot programmé
+    next_start: f64,
+
+    consumed_frames: u64,
+
+    /// Tampon de désentrelacement d'un canal
+    channel_buffer: Vec<f32>,
+}
+
+impl WebAudioBackend {
+    /// Ouvre un contexte audio ; le navigateur peut imposer une autre fréquence
+    pub fn open(sample_rate: u32, channels: u16) -> Result<Self> {
+        let options = AudioContextOptions::new();
+        options.set_sample_rate(sample_rate as f32);
+        let context = AudioContext::new_with_context_options(&options)
+            .map_err(|e| AudioError::Stream(format!("{:?}", e)))?;
+        let sample_rate = context.sample_rate() as u32;
+        log::info!("Web Audio ouvert à {} Hz", sample_rate);
+
+        Ok(Self {
+            context,
+            sample_rate,
+            channels: channels.max(1),
+            next_start: 0.0,
+            consumed_frames: 0,
+            channel_buffer: Vec::new(),
+        })
+    }
+
+    /// Contexte audio ; les navigateurs le suspendent jusqu'à une interaction
+    /// de l'utilisateur, le frontend le reprend sur un clic ou une touche
+    pub fn context(&self) -> AudioContext {
+        self.context.clone()
+    }
+
+    /// Programme un lot d'échantillons entrelacés à la suite du précédent
+    fn schedule(&mut self, samples: &[f32]) -> std::result::Result<(), wasm_bindgen::JsValue> {
+        let channels = self.channels as usize;
+        let frames = samples.len() / channels;
+        let buffer = self.context.create_buffer(channels as u32, frames as u32, self.sample_rate as f32)?;
+        for channel in 0..channels {
+            self.channel_buffer.clear();
+            self.channel_buffer.extend(samples.iter().skip(channel).step_by(channels).take(frames));
+            buffer.copy_to_channel(&self.channel_buffer, channel as i32)?;
+        }
+
+        let source = self.context.create_buffer_source()?;
+        source.set_buffer(Some(&buffer));
+        source.connect_with_audio_node(&self.context.destination())?;
+        let start = self.next_start.max(self.context.current_time());
+        source.start_with_when(start)?;
+        self.next_start = start + frames as f64 / self.sample_rate as f64;
+        Ok(())
+    }
+
+    /// Avance de la programmation sur l'horloge audio, en secondes
+    fn queued_seconds(&self) -> f64 {
+        (self.next_start - self.context.current_time()).max(0.0)
+    }
+}
+
+impl AudioBackend for WebAudioBackend {
+    fn name(&self) -> &str {
+        "web-audio"
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn push_samples(&mut self, samples: &[f32]) {
+        let frames = samples.len() / self.channels as usize;
+        if frames == 0 || self.queued_seconds() > MAX_QUEUED_SECONDS {
+            return;
+        }
+        match self.schedule(samples) {
+            Ok(()) => self.consumed_frames += frames as u64,
+            Err(e) => log::warn!("Programmation audio impossible: {:?}", e),
+        }
+    }
+
+    fn queued_frames(&self) -> usize {
+        (self.queued_seconds() * self.sample_rate as f64) as usize
+    }
+
+    fn consumed_frames(&self) -> u64 {
+        self.consumed_frames
+    }
+
+    fn is_realtime(&self) -> bool {
+        true
+    }
+}
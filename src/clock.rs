@@ -0,0 +1,12 @@
+//! Horloges de la plateforme
+//!
+//! `std::time::Instant` et `SystemTime` paniquent dans un navigateur
+//! (wasm32) : ces types y sont remplacés par ceux de `web-time`, qui
+//! s'appuient sur `performance.now()` et `Date.now()`. Les autres cibles
+//! utilisent directement la bibliothèque standard.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(target_arch = "wasm32")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
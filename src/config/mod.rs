@@ -1,8 +1,20 @@
 //! Configuration de l'émulateur
 
+pub mod paths;
+pub mod settings;
+
+pub use paths::*;
+pub use settings::*;
+
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use std::collections::BTreeMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::audio::Interpolation;
+use crate::cpu::{CpuTiming, QuarantinePolicy};
+use crate::input::{Button, MAX_PLAYERS};
 
 /// Configuration principale de l'émulateur
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,8 +29,540 @@ pub struct EmulatorConfig {
 pub struct VideoConfig {
     pub resolution: String, // "496x384" ou "640x480"
     pub fullscreen: bool,
-    pub vsync: bool,
+    /// Mode de présentation (`true`/`false` acceptés pour compatibilité)
+    pub vsync: VsyncMode,
     pub texture_filtering: String,
+
+    /// Cadence des frames émulées face à l'écran de l'hôte
+    #[serde(default)]
+    pub frame_pacing: FramePacing,
+
+    /// Type de plein écran utilisé quand `fullscreen` est actif
+    #[serde(default)]
+    pub fullscreen_mode: FullscreenMode,
+
+    /// Nom de l'écran cible (écran courant si absent)
+    #[serde(default)]
+    pub monitor: Option<String>,
+
+    /// Taille et position de la fenêtre, mémorisées entre les sessions
+    #[serde(default)]
+    pub window: WindowGeometry,
+
+    /// Mise à l'échelle de l'image dans la fenêtre
+    #[serde(default)]
+    pub aspect: AspectMode,
+
+    /// Rotation de l'image (écrans verticaux, bornes cocktail)
+    #[serde(default)]
+    pub rotation: Rotation,
+
+    /// Visibilité des polygones : profil du jeu, Z-buffer ou tri par priorité
+    #[serde(default)]
+    pub polygon_sorting: PolygonSorting,
+
+    /// Réglages du moniteur appliqués par défaut
+    #[serde(default)]
+    pub color: ColorAdjustment,
+
+    /// Réglages du moniteur propres à un jeu, modifiés depuis le menu de pause
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub game_colors: BTreeMap<String, ColorAdjustment>,
+
+    /// API graphique utilisée pour le rendu
+    #[serde(default)]
+    pub backend: GraphicsBackend,
+
+    /// Carte graphique : nom (ou partie du nom) ou numéro de `--list-gpus`
+    #[serde(default)]
+    pub adapter: Option<String>,
+
+    /// Anticrénelage de l'image 3D, modifiable depuis le menu de pause
+    #[serde(default)]
+    pub anti_aliasing: AntiAliasing,
+
+    /// Multiple de la résolution native pour le rendu 3D (1 à
+    /// [`MAX_INTERNAL_SCALE`]) ; les lectures de l'image restent à la taille
+    /// native
+    #[serde(default = "default_internal_scale")]
+    pub internal_scale: u32,
+
+    /// Multiple propre à un jeu, pour ceux dont les effets supposent la
+    /// résolution native
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub game_internal_scale: BTreeMap<String, u32>,
+
+    /// Précision de la transformation des sommets : profil du jeu,
+    /// flottants ou virgule fixe du TGP
+    #[serde(default)]
+    pub geometry_precision: GeometryPrecision,
+
+    /// Précision propre à un jeu, pour comparer avec le matériel
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub game_geometry_precision: BTreeMap<String, GeometryPrecision>,
+
+    /// Mipmaps des textures 3D, désactivées par défaut comme sur le matériel
+    #[serde(default)]
+    pub mipmaps: MipmapSettings,
+
+    /// Mipmaps propres à un jeu
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub game_mipmaps: BTreeMap<String, MipmapSettings>,
+}
+
+/// Multiple maximal de la résolution interne
+pub const MAX_INTERNAL_SCALE: u32 = 4;
+
+fn default_internal_scale() -> u32 {
+    1
+}
+
+impl VideoConfig {
+    /// Multiple de la résolution interne d'un jeu, borné à
+    /// `1..=MAX_INTERNAL_SCALE`
+    pub fn internal_scale_for(&self, game: Option<&str>) -> u32 {
+        let scale = game.and_then(|game| self.game_internal_scale.get(game)).copied().unwrap_or(self.internal_scale);
+        scale.clamp(1, MAX_INTERNAL_SCALE)
+    }
+
+    /// Précision de la géométrie d'un jeu (réglage général s'il n'en a pas)
+    pub fn geometry_precision_for(&self, game: Option<&str>) -> GeometryPrecision {
+        game.and_then(|game| self.game_geometry_precision.get(game)).copied().unwrap_or(self.geometry_precision)
+    }
+
+    /// Mipmaps d'un jeu (réglage général s'il n'en a pas)
+    pub fn mipmaps_for(&self, game: Option<&str>) -> MipmapSettings {
+        game.and_then(|game| self.game_mipmaps.get(game)).copied().unwrap_or(self.mipmaps)
+    }
+
+    /// Réglages du moniteur d'un jeu (réglages par défaut s'il n'en a pas)
+    pub fn color_for(&self, game: Option<&str>) -> ColorAdjustment {
+        game.and_then(|game| self.game_colors.get(game)).copied().unwrap_or(self.color)
+    }
+
+    /// Réglages modifiables d'un jeu, créés à partir des réglages par défaut
+    pub fn color_for_mut(&mut self, game: Option<&str>) -> &mut ColorAdjustment {
+        match game {
+            Some(game) => self.game_colors.entry(game.to_string()).or_insert(self.color),
+            None => &mut self.color,
+        }
+    }
+}
+
+/// Mode de synchronisation verticale / présentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VsyncMode {
+    /// File d'attente synchronisée sur le balayage (VSync classique)
+    #[default]
+    Fifo,
+    /// Triple buffering : pas de déchirure, l'image la plus récente est affichée
+    Mailbox,
+    /// Présentation immédiate, déchirure possible
+    Immediate,
+    /// VSync avec une seule image en vol, attente de la fin du rendu à chaque frame
+    LowLatency,
+}
+
+impl<'de> Deserialize<'de> for VsyncMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Flag(bool),
+            Name(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Flag(true) => Ok(VsyncMode::Fifo),
+            Repr::Flag(false) => Ok(VsyncMode::Immediate),
+            Repr::Name(name) => match name.as_str() {
+                "fifo" => Ok(VsyncMode::Fifo),
+                "mailbox" => Ok(VsyncMode::Mailbox),
+                "immediate" => Ok(VsyncMode::Immediate),
+                "low_latency" => Ok(VsyncMode::LowLatency),
+                other => Err(serde::de::Error::custom(format!("Mode vsync inconnu: {}", other))),
+            },
+        }
+    }
+}
+
+/// Cadence des frames émulées (57,52 Hz) face à l'écran de l'hôte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FramePacing {
+    /// Une frame par rafraîchissement (ou tous les N à 120/144 Hz), audio
+    /// rééchantillonné sur le rythme obtenu
+    #[default]
+    Vsync,
+    /// Frames à 57,52 Hz exactes sur l'horloge de l'hôte, images répétées
+    FreeRun,
+    /// Comme `free_run`, en mélangeant les deux dernières frames
+    Blend,
+}
+
+/// Type de plein écran
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FullscreenMode {
+    /// Fenêtre sans bordure couvrant l'écran
+    #[default]
+    Borderless,
+    /// Plein écran exclusif avec changement de mode vidéo
+    Exclusive,
+}
+
+/// API graphique demandée à wgpu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphicsBackend {
+    /// Meilleure API disponible sur la plateforme
+    #[default]
+    Auto,
+    Vulkan,
+    Dx12,
+    Metal,
+    /// OpenGL / OpenGL ES, pour les pilotes anciens
+    Gl,
+}
+
+/// Mise à l'échelle de l'image émulée dans la fenêtre
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AspectMode {
+    /// Image 4:3 comme sur le moniteur de la borne, bandes noires si besoin
+    #[default]
+    #[serde(rename = "4:3")]
+    Ratio4x3,
+    /// Image étirée sur toute la fenêtre
+    #[serde(rename = "stretch")]
+    Stretch,
+    /// Pixels carrés agrandis d'un facteur entier
+    #[serde(rename = "integer")]
+    Integer,
+}
+
+/// Rotation de l'image dans le sens horaire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "u32", into = "u32")]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Half,
+    Clockwise270,
+}
+
+impl Rotation {
+    /// Angle en degrés
+    pub fn degrees(self) -> u32 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Clockwise90 => 90,
+            Rotation::Half => 180,
+            Rotation::Clockwise270 => 270,
+        }
+    }
+
+    /// Vrai si la largeur et la hauteur de l'image sont échangées
+    pub fn is_quarter_turn(self) -> bool {
+        matches!(self, Rotation::Clockwise90 | Rotation::Clockwise270)
+    }
+}
+
+/// Anticrénelage de l'image 3D
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AntiAliasing {
+    /// Bords nets comme sur la borne
+    #[default]
+    Off,
+    /// Multiéchantillonnage de la cible de rendu (2, 4 ou 8 échantillons par pixel)
+    #[serde(rename = "msaa2x")]
+    Msaa2x,
+    #[serde(rename = "msaa4x")]
+    Msaa4x,
+    #[serde(rename = "msaa8x")]
+    Msaa8x,
+    /// Passe de lissage des contours sur l'image finie, moins coûteuse
+    Fxaa,
+}
+
+impl AntiAliasing {
+    /// Modes dans l'ordre du menu de pause
+    pub const ALL: [AntiAliasing; 5] = [
+        AntiAliasing::Off,
+        AntiAliasing::Msaa2x,
+        AntiAliasing::Msaa4x,
+        AntiAliasing::Msaa8x,
+        AntiAliasing::Fxaa,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AntiAliasing::Off => "désactivé",
+            AntiAliasing::Msaa2x => "MSAA 2x",
+            AntiAliasing::Msaa4x => "MSAA 4x",
+            AntiAliasing::Msaa8x => "MSAA 8x",
+            AntiAliasing::Fxaa => "FXAA",
+        }
+    }
+
+    /// Échantillons par pixel de la cible de rendu
+    pub fn sample_count(self) -> u32 {
+        match self {
+            AntiAliasing::Msaa2x => 2,
+            AntiAliasing::Msaa4x => 4,
+            AntiAliasing::Msaa8x => 8,
+            AntiAliasing::Off | AntiAliasing::Fxaa => 1,
+        }
+    }
+
+    /// Mode le plus proche n'utilisant pas plus de `max_samples` échantillons
+    pub fn limited_to(self, max_samples: u32) -> Self {
+        let mut mode = self;
+        while mode.sample_count() > max_samples.max(1) {
+            mode = match mode {
+                AntiAliasing::Msaa8x => AntiAliasing::Msaa4x,
+                AntiAliasing::Msaa4x => AntiAliasing::Msaa2x,
+                _ => AntiAliasing::Off,
+            };
+        }
+        mode
+    }
+
+    /// Mode suivant (`steps` positif) ou précédent dans [`AntiAliasing::ALL`], sans boucler
+    pub fn step(self, steps: i32) -> Self {
+        let index = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0) as i32;
+        Self::ALL[(index + steps).clamp(0, Self::ALL.len() as i32 - 1) as usize]
+    }
+
+    /// Position dans le menu (0..1), pour l'affichage
+    pub fn normalized(self) -> f32 {
+        let index = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0);
+        index as f32 / (Self::ALL.len() - 1) as f32
+    }
+}
+
+impl TryFrom<u32> for Rotation {
+    type Error = String;
+
+    fn try_from(degrees: u32) -> std::result::Result<Self, Self::Error> {
+        match degrees {
+            0 => Ok(Rotation::None),
+            90 => Ok(Rotation::Clockwise90),
+            180 => Ok(Rotation::Half),
+            270 => Ok(Rotation::Clockwise270),
+            other => Err(format!("Rotation invalide: {} (0, 90, 180 ou 270)", other)),
+        }
+    }
+}
+
+impl From<Rotation> for u32 {
+    fn from(rotation: Rotation) -> Self {
+        rotation.degrees()
+    }
+}
+
+/// Choix entre Z-buffer et tri des polygones par priorité
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolygonSorting {
+    /// Selon le profil du jeu dans la base des jeux
+    #[default]
+    Auto,
+    /// Test de profondeur par pixel pour tous les jeux
+    ZBuffer,
+    /// Tri du plus lointain au plus proche, comme le matériel d'origine
+    Priority,
+}
+
+impl PolygonSorting {
+    /// Le tri par priorité est-il retenu, compte tenu du profil du jeu
+    pub fn use_priority(self, profile_prefers_priority: bool) -> bool {
+        match self {
+            PolygonSorting::Auto => profile_prefers_priority,
+            PolygonSorting::ZBuffer => false,
+            PolygonSorting::Priority => true,
+        }
+    }
+}
+
+/// Précision du calcul des sommets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeometryPrecision {
+    /// Selon le profil du jeu dans la base des jeux
+    #[default]
+    Auto,
+    /// Flottants 32 bits, plus stables
+    Float,
+    /// Virgule fixe 1.15.16 du TGP : tremblement des sommets et culling
+    /// identiques au matériel
+    Fixed,
+}
+
+impl GeometryPrecision {
+    /// La virgule fixe est-elle retenue, compte tenu du profil du jeu
+    pub fn use_fixed_point(self, profile_prefers_fixed: bool) -> bool {
+        match self {
+            GeometryPrecision::Auto => profile_prefers_fixed,
+            GeometryPrecision::Float => false,
+            GeometryPrecision::Fixed => true,
+        }
+    }
+}
+
+/// Mipmaps des textures 3D
+///
+/// Le Model 2 échantillonne toujours la texture entière : les polygones
+/// lointains scintillent, d'autant plus à une résolution interne élevée.
+/// Les mipmaps, générées au chargement des textures, les adoucissent.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MipmapSettings {
+    pub enabled: bool,
+    /// Décalage du niveau de détail des polygones filtrés (négatif : plus
+    /// net, positif : plus flou)
+    pub lod_bias: f32,
+}
+
+impl Default for MipmapSettings {
+    fn default() -> Self {
+        Self { enabled: false, lod_bias: 0.0 }
+    }
+}
+
+/// Réglages du moniteur émulé (comme les potentiomètres des bornes)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorAdjustment {
+    /// Gamma (1.0 neutre, plus élevé éclaircit les tons moyens)
+    pub gamma: f32,
+    /// Luminosité ajoutée (0.0 neutre)
+    pub brightness: f32,
+    /// Contraste autour du gris moyen (1.0 neutre)
+    pub contrast: f32,
+    /// Saturation (1.0 neutre, 0.0 noir et blanc)
+    pub saturation: f32,
+}
+
+impl Default for ColorAdjustment {
+    fn default() -> Self {
+        Self { gamma: 1.0, brightness: 0.0, contrast: 1.0, saturation: 1.0 }
+    }
+}
+
+/// Réglage du moniteur
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorParameter {
+    Gamma,
+    Brightness,
+    Contrast,
+    Saturation,
+}
+
+impl ColorParameter {
+    pub const ALL: [ColorParameter; 4] = [
+        ColorParameter::Gamma,
+        ColorParameter::Brightness,
+        ColorParameter::Contrast,
+        ColorParameter::Saturation,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorParameter::Gamma => "Gamma",
+            ColorParameter::Brightness => "Luminosité",
+            ColorParameter::Contrast => "Contraste",
+            ColorParameter::Saturation => "Saturation",
+        }
+    }
+
+    /// Nom du champ dans la configuration
+    pub fn key(self) -> &'static str {
+        match self {
+            ColorParameter::Gamma => "gamma",
+            ColorParameter::Brightness => "brightness",
+            ColorParameter::Contrast => "contrast",
+            ColorParameter::Saturation => "saturation",
+        }
+    }
+
+    /// Valeurs minimale et maximale
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            ColorParameter::Gamma => (0.5, 2.5),
+            ColorParameter::Brightness => (-0.5, 0.5),
+            ColorParameter::Contrast => (0.5, 2.0),
+            ColorParameter::Saturation => (0.0, 2.0),
+        }
+    }
+
+    /// Pas d'un appui sur une flèche
+    pub fn step(self) -> f32 {
+        match self {
+            ColorParameter::Brightness => 0.02,
+            _ => 0.05,
+        }
+    }
+}
+
+impl ColorAdjustment {
+    pub fn get(&self, parameter: ColorParameter) -> f32 {
+        match parameter {
+            ColorParameter::Gamma => self.gamma,
+            ColorParameter::Brightness => self.brightness,
+            ColorParameter::Contrast => self.contrast,
+            ColorParameter::Saturation => self.saturation,
+        }
+    }
+
+    /// Modifie un réglage en le gardant dans sa plage
+    pub fn set(&mut self, parameter: ColorParameter, value: f32) {
+        let (min, max) = parameter.range();
+        let value = value.clamp(min, max);
+        match parameter {
+            ColorParameter::Gamma => self.gamma = value,
+            ColorParameter::Brightness => self.brightness = value,
+            ColorParameter::Contrast => self.contrast = value,
+            ColorParameter::Saturation => self.saturation = value,
+        }
+    }
+
+    /// Avance un réglage d'un nombre de pas (négatif pour diminuer)
+    pub fn step(&mut self, parameter: ColorParameter, steps: i32) {
+        let value = self.get(parameter) + parameter.step() * steps as f32;
+        // Arrondi au pas pour éviter l'accumulation d'erreurs
+        let value = (value / parameter.step()).round() * parameter.step();
+        self.set(parameter, value);
+    }
+
+    /// Position d'un réglage dans sa plage (0..1), pour l'affichage
+    pub fn normalized(&self, parameter: ColorParameter) -> f32 {
+        let (min, max) = parameter.range();
+        (self.get(parameter) - min) / (max - min)
+    }
+}
+
+/// Géométrie de la fenêtre en mode fenêtré
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: u32,
+    pub height: u32,
+    /// Position du coin supérieur gauche (placement par le système si absente)
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+}
+
+impl Default for WindowGeometry {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 600,
+            x: None,
+            y: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,12 +570,326 @@ pub struct AudioConfig {
     pub enabled: bool,
     pub volume: f32,
     pub sample_rate: u32,
+
+    /// Backend de sortie (`cpal` ou `null` pour un fonctionnement sans matériel)
+    #[serde(default)]
+    pub backend: AudioBackendKind,
+
+    /// Nom du périphérique de sortie (périphérique par défaut si absent)
+    #[serde(default)]
+    pub device: Option<String>,
+
+    /// Taille du buffer matériel en frames (déduite de `latency_ms` si absente)
+    #[serde(default)]
+    pub buffer_size: Option<u32>,
+
+    /// Latence de sortie visée en millisecondes
+    #[serde(default = "default_audio_latency_ms")]
+    pub latency_ms: u32,
+
+    /// Ajuste finement (±0,5 %) le débit pour garder la latence stable
+    #[serde(default = "default_audio_dynamic_rate")]
+    pub dynamic_rate: bool,
+
+    /// Transmet les échantillons au périphérique depuis un thread dédié ; la
+    /// synthèse reste sur le thread d'émulation
+    #[serde(default)]
+    pub output_thread: bool,
+
+    /// Conserve la hauteur du son en avance rapide et au ralenti ; false :
+    /// le son est accéléré ou ralenti comme une bande magnétique
+    #[serde(default = "default_audio_time_stretch")]
+    pub time_stretch: bool,
+
+    /// Interpolation des échantillons PCM (`none`, `linear` ou `cubic`)
+    #[serde(default)]
+    pub interpolation: Interpolation,
+
+    /// Simulation des haut-parleurs de la borne, après le mixeur
+    #[serde(default)]
+    pub cabinet: CabinetConfig,
+
+    /// Profil de borne propre à un jeu (sinon `cabinet.profile`)
+    #[serde(default)]
+    pub game_cabinet: BTreeMap<String, CabinetSelection>,
+}
+
+impl AudioConfig {
+    /// Profil de borne retenu pour un jeu
+    pub fn cabinet_for(&self, game: Option<&str>) -> CabinetSelection {
+        game.and_then(|game| self.game_cabinet.get(game)).copied().unwrap_or(self.cabinet.profile)
+    }
+}
+
+/// Type de borne dont la sortie sonore est simulée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CabinetKind {
+    /// Un haut-parleur (bornes d'entrée de gamme, conversions)
+    Mono,
+    /// Borne verticale standard, deux haut-parleurs
+    Upright,
+    /// Borne twin : chaque siège a sa paire de haut-parleurs
+    Twin,
+    /// Borne deluxe (Daytona DX) : paire avant et paire du siège
+    Deluxe,
+}
+
+/// Profil de borne choisi dans la configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CabinetSelection {
+    /// Sortie du mixeur inchangée
+    #[default]
+    Off,
+    /// Profil de la borne d'origine du jeu, d'après la base des jeux
+    Auto,
+    Mono,
+    Upright,
+    Twin,
+    Deluxe,
+}
+
+impl CabinetSelection {
+    /// Type de borne simulé, `game_default` étant celui de la base des jeux
+    pub fn kind(self, game_default: Option<CabinetKind>) -> Option<CabinetKind> {
+        match self {
+            CabinetSelection::Off => None,
+            CabinetSelection::Auto => game_default,
+            CabinetSelection::Mono => Some(CabinetKind::Mono),
+            CabinetSelection::Upright => Some(CabinetKind::Upright),
+            CabinetSelection::Twin => Some(CabinetKind::Twin),
+            CabinetSelection::Deluxe => Some(CabinetKind::Deluxe),
+        }
+    }
+}
+
+/// Simulation des haut-parleurs : profil, et réglages qui remplacent ceux
+/// du profil
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CabinetConfig {
+    #[serde(default)]
+    pub profile: CabinetSelection,
+
+    /// Coupure des graves des haut-parleurs, en Hz
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_cut_hz: Option<f32>,
+
+    /// Coupure des aigus des haut-parleurs, en Hz
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_cut_hz: Option<f32>,
+
+    /// Part de réverbération de la salle, de 0.0 à 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverb: Option<f32>,
+}
+
+fn default_audio_latency_ms() -> u32 {
+    40
+}
+
+fn default_audio_dynamic_rate() -> bool {
+    true
+}
+
+fn default_audio_time_stretch() -> bool {
+    true
+}
+
+/// Backend de sortie audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioBackendKind {
+    /// Périphérique réel via cpal (feature `audio-cpal`)
+    #[default]
+    Cpal,
+    /// Aucune sortie : les échantillons sont générés puis jetés
+    Null,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "InputConfigFile")]
 pub struct InputConfig {
-    pub player1_keys: PlayerKeyConfig,
-    pub player2_keys: PlayerKeyConfig,
+    /// Touches de chaque joueur, dans l'ordre des places de la borne
+    /// (`[[input.players]]`, quatre au plus)
+    pub players: Vec<PlayerKeyConfig>,
+
+    /// Pointeur et pistolets optiques
+    #[serde(default)]
+    pub lightgun: LightGunConfig,
+
+    /// Commandes tactiles à l'écran (joueur 1)
+    #[serde(default)]
+    pub touch: TouchConfig,
+}
+
+/// Section `[input]` telle qu'écrite dans le fichier : les anciennes
+/// sections `player1_keys` et `player2_keys` sont reprises dans `players`
+#[derive(Deserialize)]
+struct InputConfigFile {
+    players: Option<Vec<PlayerKeyConfig>>,
+    player1_keys: Option<PlayerKeyConfig>,
+    player2_keys: Option<PlayerKeyConfig>,
+    #[serde(default)]
+    lightgun: LightGunConfig,
+    #[serde(default)]
+    touch: TouchConfig,
+}
+
+impl From<InputConfigFile> for InputConfig {
+    fn from(file: InputConfigFile) -> Self {
+        let mut players = file.players.unwrap_or_else(|| {
+            let mut players = default_player_keys();
+            for (player, keys) in [file.player1_keys, file.player2_keys].into_iter().enumerate() {
+                if let Some(keys) = keys {
+                    players[player] = keys;
+                }
+            }
+            players
+        });
+        // Joueurs sans section : touches par défaut, pour pouvoir les remapper
+        players.truncate(MAX_PLAYERS);
+        players.extend(default_player_keys().into_iter().skip(players.len()));
+        Self { players, lightgun: file.lightgun, touch: file.touch }
+    }
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        Self {
+            players: default_player_keys(),
+            lightgun: LightGunConfig::default(),
+            touch: TouchConfig::default(),
+        }
+    }
+}
+
+/// Touches par défaut des quatre joueurs
+fn default_player_keys() -> Vec<PlayerKeyConfig> {
+    let keys = |keys: [&str; 8]| {
+        let [up, down, left, right, punch, kick, guard, start] = keys.map(str::to_string);
+        PlayerKeyConfig { up, down, left, right, punch, kick, guard, start }
+    };
+    vec![
+        keys(["W", "S", "A", "D", "J", "K", "L", "Return"]),
+        keys(["Up", "Down", "Left", "Right", "Numpad1", "Numpad2", "Numpad3", "NumpadEnter"]),
+        keys(["T", "G", "F", "H", "Z", "X", "C", "Digit3"]),
+        keys(["Numpad8", "Numpad5", "Numpad4", "Numpad6", "Numpad7", "Numpad9", "NumpadAdd", "Digit4"]),
+    ]
+}
+
+/// Capture du pointeur pendant le jeu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PointerCapture {
+    /// Curseur libre et visible
+    Off,
+    /// Curseur masqué au-dessus de la fenêtre
+    #[default]
+    Hide,
+    /// Curseur masqué et confiné à la fenêtre
+    Confine,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightGunConfig {
+    /// Capture du pointeur pendant le jeu (relâché en pause)
+    pub pointer_capture: PointerCapture,
+
+    /// Afficher un viseur par joueur
+    pub show_crosshair: bool,
+
+    /// Couleur RGB du viseur de chaque joueur
+    pub crosshair_colors: Vec<[f32; 3]>,
+}
+
+impl Default for LightGunConfig {
+    fn default() -> Self {
+        Self {
+            pointer_capture: PointerCapture::default(),
+            show_crosshair: false,
+            crosshair_colors: vec![[1.0, 0.2, 0.2], [0.2, 0.6, 1.0]],
+        }
+    }
+}
+
+/// Commandes tactiles à l'écran
+///
+/// Les positions sont normalisées sur la fenêtre (0.0 à 1.0, origine en haut
+/// à gauche) ; les rayons sont des fractions de la hauteur de la fenêtre.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TouchConfig {
+    /// Affiche et lit les commandes (actif par défaut sur Android)
+    pub enabled: bool,
+
+    /// Vibration à l'appui d'un bouton
+    pub haptics: bool,
+
+    /// Opacité des commandes dessinées par-dessus l'image
+    pub opacity: f32,
+
+    /// Zone de direction : le doigt y donne haut/bas/gauche/droite
+    pub stick: TouchStickConfig,
+
+    /// Zone de volant (jeux de course), absente par défaut
+    pub steering: Option<TouchSteeringConfig>,
+
+    /// Boutons à l'écran
+    pub buttons: Vec<TouchButtonConfig>,
+}
+
+/// Zone de direction tactile
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TouchStickConfig {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+
+    /// Zone morte au centre, en fraction du rayon
+    pub dead_zone: f32,
+}
+
+/// Zone de volant tactile : bande horizontale où la position du doigt donne
+/// un braquage de -1 (gauche) à 1 (droite). Centre et dimensions sont
+/// normalisés sur la fenêtre.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TouchSteeringConfig {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+
+    /// Braquage en deçà duquel ni gauche ni droite ne sont enfoncés
+    pub dead_zone: f32,
+}
+
+/// Bouton tactile circulaire
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TouchButtonConfig {
+    pub button: Button,
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+}
+
+impl Default for TouchConfig {
+    fn default() -> Self {
+        let button = |button, x, y, radius| TouchButtonConfig { button, x, y, radius };
+        Self {
+            enabled: cfg!(target_os = "android"),
+            haptics: true,
+            opacity: 0.35,
+            stick: TouchStickConfig { x: 0.15, y: 0.72, radius: 0.16, dead_zone: 0.25 },
+            steering: None,
+            buttons: vec![
+                button(Button::Punch, 0.74, 0.80, 0.08),
+                button(Button::Kick, 0.86, 0.68, 0.08),
+                button(Button::Guard, 0.90, 0.88, 0.08),
+                button(Button::Start, 0.50, 0.92, 0.05),
+            ],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,11 +904,200 @@ pub struct PlayerKeyConfig {
     pub start: String,
 }
 
+impl PlayerKeyConfig {
+    /// Nom de la touche associée à un bouton
+    pub fn key(&self, button: Button) -> &str {
+        match button {
+            Button::Up => &self.up,
+            Button::Down => &self.down,
+            Button::Left => &self.left,
+            Button::Right => &self.right,
+            Button::Punch => &self.punch,
+            Button::Kick => &self.kick,
+            Button::Guard => &self.guard,
+            Button::Start => &self.start,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmulationConfig {
     pub cpu_speed_multiplier: f32,
     pub accurate_timing: bool,
     pub debug_mode: bool,
+
+    /// Sauvegarde un état à la fermeture et le reprend au lancement suivant
+    #[serde(default)]
+    pub auto_save_state: bool,
+
+    /// Port local du point d'accès JSON des statistiques (désactivé si absent)
+    #[serde(default)]
+    pub stats_port: Option<u16>,
+
+    /// Destination des lampes de la borne, `udp://hôte:port` ou
+    /// `serial:port` (désactivé si absent)
+    #[serde(default)]
+    pub lamp_output: Option<String>,
+
+    /// Simule les routines du micrologiciel listées pour le jeu (tests de
+    /// RAM, sécurité) au lieu de les exécuter, quand la ROM système qui les
+    /// contient n'est pas chargée
+    #[serde(default = "default_firmware_hle")]
+    pub firmware_hle: bool,
+
+    /// Applique les contournements prévus par le profil du jeu
+    #[serde(default = "default_game_hacks")]
+    pub game_hacks: bool,
+
+    /// Évalue les succès définis pour le jeu (répertoire `achievements`)
+    #[serde(default = "default_achievements")]
+    pub achievements: bool,
+
+    /// Joue directement les commandes sonores connues du jeu, tant que le
+    /// 68000 de la carte son n'est pas émulé
+    #[serde(default = "default_sound_hle")]
+    pub sound_hle: bool,
+
+    /// Émule le 68000 de la carte son (pas encore disponible : les commandes
+    /// restent alors en attente) ; désactive le son HLE
+    #[serde(default)]
+    pub audio_lle: bool,
+
+    /// Multiplicateur d'horloge du V60, combiné à `cpu_speed_multiplier`
+    /// (au-dessus de 1, supprime les ralentissements du jeu)
+    #[serde(default = "default_cpu_clock")]
+    pub main_cpu_clock: f32,
+
+    /// Multiplicateur d'horloge du 68000 audio, combiné à `cpu_speed_multiplier`
+    #[serde(default = "default_cpu_clock")]
+    pub sound_cpu_clock: f32,
+
+    /// Fréquence du quartz maître en Hz (50 MHz d'origine si absent) : les
+    /// horloges du CPU, de la géométrie, du SCSP et du 68000 en dérivent
+    #[serde(default)]
+    pub master_clock_hz: Option<u32>,
+
+    /// Écrit à la fermeture un rapport de compatibilité local (premier
+    /// opcode inconnu, première commande GPU ignorée...) à joindre aux
+    /// tickets ; rien n'est envoyé
+    #[serde(default)]
+    pub compat_report: bool,
+
+    /// Vitesse de l'avance rapide (touche Tab maintenue), de 1 à 8
+    #[serde(default = "default_fast_forward_speed")]
+    pub fast_forward_speed: f32,
+
+    /// Vitesse du ralenti (basculé par F4), de 0.1 à 1
+    #[serde(default = "default_slow_motion_speed")]
+    pub slow_motion_speed: f32,
+
+    /// Conduite face à un opcode inconnu, une instruction ou une commande
+    /// GPU non implémentée : `error` (la frame s'arrête), `skip` (ignorée,
+    /// signalée une fois), `halt` (CPU arrêté) ou `break` (débogueur)
+    #[serde(default)]
+    pub unimplemented_policy: QuarantinePolicy,
+
+    /// Carte de chaleur des accès mémoire du CPU
+    #[serde(default)]
+    pub heatmap: HeatmapConfig,
+
+    /// Patchs IPS/BPS appliqués aux ROMs, par nom court de jeu
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rom_patches: BTreeMap<String, Vec<RomPatchConfig>>,
+
+    /// Durées d'instruction propres à un jeu : `fast` pour un jeu qui tourne
+    /// mieux avec les durées fixes, `accurate` pour forcer le mode précis
+    /// malgré `accurate_timing = false`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub game_cpu_timing: BTreeMap<String, CpuTiming>,
+}
+
+impl EmulationConfig {
+    /// Durées d'instruction du V60 pour un jeu (`accurate_timing` s'il n'en
+    /// a pas)
+    pub fn cpu_timing_for(&self, game: Option<&str>) -> CpuTiming {
+        let default = if self.accurate_timing { CpuTiming::Accurate } else { CpuTiming::Fast };
+        game.and_then(|game| self.game_cpu_timing.get(game)).copied().unwrap_or(default)
+    }
+}
+
+/// Carte de chaleur des accès mémoire : lectures et écritures du CPU
+/// comptées par bloc sur une plage d'adresses, relevées toutes les
+/// `interval_frames` frames
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeatmapConfig {
+    pub enabled: bool,
+
+    /// Début de la plage observée (RAM principale par défaut)
+    pub start: u32,
+
+    /// Longueur de la plage en octets
+    pub length: u32,
+
+    /// Taille d'un bloc en octets (arrondie à une puissance de deux)
+    pub block_size: u32,
+
+    /// Frames cumulées par relevé
+    pub interval_frames: u32,
+
+    /// Enregistre chaque relevé en PNG dans `<données>/heatmaps/<jeu>`
+    pub export: bool,
+
+    /// Affiche le dernier relevé en surimpression
+    pub overlay: bool,
+}
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: 0x0000_0000,
+            length: 0x0080_0000,
+            block_size: 256,
+            interval_frames: 60,
+            export: true,
+            overlay: false,
+        }
+    }
+}
+
+/// Patch d'une ROM d'un jeu
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RomPatchConfig {
+    /// Nom du fichier ROM patché, tel que dans la base des jeux
+    pub rom: String,
+
+    /// Fichier .ips ou .bps
+    pub file: PathBuf,
+}
+
+fn default_cpu_clock() -> f32 {
+    1.0
+}
+
+fn default_firmware_hle() -> bool {
+    true
+}
+
+fn default_game_hacks() -> bool {
+    true
+}
+
+fn default_achievements() -> bool {
+    true
+}
+
+fn default_sound_hle() -> bool {
+    true
+}
+
+fn default_fast_forward_speed() -> f32 {
+    3.0
+}
+
+fn default_slow_motion_speed() -> f32 {
+    0.5
 }
 
 impl Default for EmulatorConfig {
@@ -59,59 +1106,271 @@ impl Default for EmulatorConfig {
             video: VideoConfig {
                 resolution: "496x384".to_string(),
                 fullscreen: false,
-                vsync: true,
+                vsync: VsyncMode::Fifo,
+                frame_pacing: FramePacing::default(),
                 texture_filtering: "linear".to_string(),
+                fullscreen_mode: FullscreenMode::default(),
+                monitor: None,
+                window: WindowGeometry::default(),
+                aspect: AspectMode::default(),
+                rotation: Rotation::default(),
+                polygon_sorting: PolygonSorting::default(),
+                color: ColorAdjustment::default(),
+                game_colors: BTreeMap::new(),
+                backend: GraphicsBackend::default(),
+                adapter: None,
+                anti_aliasing: AntiAliasing::default(),
+                internal_scale: default_internal_scale(),
+                game_internal_scale: BTreeMap::new(),
+                geometry_precision: GeometryPrecision::default(),
+                game_geometry_precision: BTreeMap::new(),
+                mipmaps: MipmapSettings::default(),
+                game_mipmaps: BTreeMap::new(),
             },
             audio: AudioConfig {
                 enabled: true,
                 volume: 1.0,
                 sample_rate: 44100,
+                backend: AudioBackendKind::default(),
+                device: None,
+                buffer_size: None,
+                latency_ms: default_audio_latency_ms(),
+                dynamic_rate: default_audio_dynamic_rate(),
+                output_thread: false,
+                time_stretch: default_audio_time_stretch(),
+                interpolation: Interpolation::default(),
+                cabinet: CabinetConfig::default(),
+                game_cabinet: BTreeMap::new(),
             },
-            input: InputConfig {
-                player1_keys: PlayerKeyConfig {
-                    up: "W".to_string(),
-                    down: "S".to_string(),
-                    left: "A".to_string(),
-                    right: "D".to_string(),
-                    punch: "J".to_string(),
-                    kick: "K".to_string(),
-                    guard: "L".to_string(),
-                    start: "Return".to_string(),
-                },
-                player2_keys: PlayerKeyConfig {
-                    up: "Up".to_string(),
-                    down: "Down".to_string(),
-                    left: "Left".to_string(),
-                    right: "Right".to_string(),
-                    punch: "Numpad1".to_string(),
-                    kick: "Numpad2".to_string(),
-                    guard: "Numpad3".to_string(),
-                    start: "NumpadEnter".to_string(),
-                },
-            },
+            input: InputConfig::default(),
             emulation: EmulationConfig {
                 cpu_speed_multiplier: 1.0,
                 accurate_timing: true,
                 debug_mode: false,
+                auto_save_state: false,
+                stats_port: None,
+                lamp_output: None,
+                firmware_hle: default_firmware_hle(),
+                game_hacks: default_game_hacks(),
+                achievements: default_achievements(),
+                sound_hle: default_sound_hle(),
+                audio_lle: false,
+                main_cpu_clock: default_cpu_clock(),
+                sound_cpu_clock: default_cpu_clock(),
+                master_clock_hz: None,
+                compat_report: false,
+                fast_forward_speed: default_fast_forward_speed(),
+                slow_motion_speed: default_slow_motion_speed(),
+                unimplemented_policy: QuarantinePolicy::default(),
+                heatmap: HeatmapConfig::default(),
+                rom_patches: BTreeMap::new(),
+                game_cpu_timing: BTreeMap::new(),
             },
         }
     }
 }
 
 impl EmulatorConfig {
-    pub fn load_from_file(path: &str) -> Result<Self> {
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let contents = fs::read_to_string(path)?;
         let config: EmulatorConfig = toml::from_str(&contents)?;
         Ok(config)
     }
     
-    pub fn save_to_file(&self, path: &str) -> Result<()> {
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let contents = toml::to_string_pretty(self)?;
+        if let Some(parent) = path.as_ref().parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
         fs::write(path, contents)?;
         Ok(())
     }
     
-    pub fn load_or_default(path: &str) -> Self {
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Self {
         Self::load_from_file(path).unwrap_or_default()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_config_loads_with_defaults() {
+        let config: EmulatorConfig = toml::from_str(include_str!("../../config.toml")).unwrap();
+        assert_eq!(config.video.fullscreen_mode, FullscreenMode::Borderless);
+        assert_eq!(config.video.window, WindowGeometry::default());
+        assert_eq!(config.video.vsync, VsyncMode::Fifo);
+
+        let mut emulation = config.emulation;
+        assert_eq!(emulation.cpu_timing_for(Some("vf2")), CpuTiming::Accurate);
+        emulation.game_cpu_timing.insert("vf2".to_string(), CpuTiming::Fast);
+        assert_eq!(emulation.cpu_timing_for(Some("vf2")), CpuTiming::Fast);
+        emulation.accurate_timing = false;
+        assert_eq!(emulation.cpu_timing_for(None), CpuTiming::Fast);
+    }
+
+    #[test]
+    fn test_legacy_player_key_sections_migrate() {
+        let section = |name: &str, up: &str| format!(
+            "[input.{}]\nup = \"{}\"\ndown = \"S\"\nleft = \"A\"\nright = \"D\"\npunch = \"J\"\nkick = \"K\"\nguard = \"L\"\nstart = \"Return\"\n",
+            name, up
+        );
+        let text = toml::to_string(&EmulatorConfig::default()).unwrap();
+        let start = text.find("[[input.players]]").unwrap();
+        let end = text.find("[input.lightgun]").unwrap();
+        let legacy = format!("{}{}{}{}", &text[..start], section("player1_keys", "I"), section("player2_keys", "O"), &text[end..]);
+
+        let config: EmulatorConfig = toml::from_str(&legacy).unwrap();
+        let ups: Vec<&str> = config.input.players.iter().map(|keys| keys.up.as_str()).collect();
+        assert_eq!(ups, ["I", "O", "T", "Numpad8"]);
+        assert_eq!(config.input.players[1].key(Button::Start), "Return");
+
+        // Réécrit sous la forme actuelle
+        let rewritten = toml::to_string(&config).unwrap();
+        assert!(!rewritten.contains("player1_keys"));
+        assert_eq!(rewritten.matches("[[input.players]]").count(), 4);
+    }
+
+    #[test]
+    fn test_missing_player_sections_get_default_keys() {
+        let text = toml::to_string(&EmulatorConfig::default()).unwrap();
+        let third = text.match_indices("[[input.players]]").nth(2).unwrap().0;
+        let end = text.find("[input.lightgun]").unwrap();
+        let two_players = format!("{}{}", &text[..third], &text[end..]);
+
+        let config: EmulatorConfig = toml::from_str(&two_players).unwrap();
+        assert_eq!(config.input.players.len(), MAX_PLAYERS);
+        assert_eq!(config.input.players[2].up, "T");
+
+        // Le joueur 3 se remappe comme les autres
+        let mut settings = Settings::new(config);
+        settings.set("input.players.2.up", "I").unwrap();
+        assert_eq!(settings.input.players[2].up, "I");
+    }
+
+    #[test]
+    fn test_audio_interpolation_setting() {
+        let config: EmulatorConfig = toml::from_str(include_str!("../../config.toml")).unwrap();
+        assert_eq!(config.audio.interpolation, Interpolation::Linear);
+
+        let mut settings = Settings::new(config);
+        let changes = settings.subscribe("audio.");
+        settings.set_str("audio.interpolation", "cubic").unwrap();
+        assert_eq!(settings.audio.interpolation, Interpolation::Cubic);
+        assert_eq!(changes.try_recv().unwrap().key, "audio.interpolation");
+        assert!(settings.set_str("audio.interpolation", "sinc").is_err());
+    }
+
+    #[test]
+    fn test_partial_touch_section_keeps_defaults() {
+        let touch: TouchConfig = toml::from_str("enabled = true\nopacity = 0.5\n").unwrap();
+        let default = TouchConfig::default();
+        assert!(touch.enabled);
+        assert_eq!(touch.opacity, 0.5);
+        assert_eq!((touch.haptics, touch.stick, touch.buttons), (default.haptics, default.stick, default.buttons));
+    }
+
+    #[test]
+    fn test_vsync_mode_parsing() {
+        let parse = |text: &str| toml::from_str::<VideoConfig>(&format!(
+            "resolution = \"496x384\"\nfullscreen = false\ntexture_filtering = \"linear\"\nvsync = {}", text
+        ));
+
+        assert_eq!(parse("false").unwrap().vsync, VsyncMode::Immediate);
+        assert_eq!(parse("\"mailbox\"").unwrap().vsync, VsyncMode::Mailbox);
+        assert_eq!(parse("\"low_latency\"").unwrap().vsync, VsyncMode::LowLatency);
+        assert!(parse("\"triple\"").is_err());
+    }
+
+    #[test]
+    fn test_output_transform_parsing() {
+        let parse = |text: &str| toml::from_str::<VideoConfig>(&format!(
+            "resolution = \"496x384\"\nfullscreen = false\nvsync = true\ntexture_filtering = \"linear\"\n{}", text
+        ));
+
+        let video = parse("").unwrap();
+        assert_eq!((video.aspect, video.rotation), (AspectMode::Ratio4x3, Rotation::None));
+
+        assert_eq!(video.polygon_sorting, PolygonSorting::Auto);
+        assert!(video.polygon_sorting.use_priority(true));
+        assert!(!parse("polygon_sorting = \"z_buffer\"").unwrap().polygon_sorting.use_priority(true));
+
+        let video = parse("aspect = \"integer\"\nrotation = 270").unwrap();
+        assert_eq!((video.aspect, video.rotation), (AspectMode::Integer, Rotation::Clockwise270));
+        assert!(parse("rotation = 45").is_err());
+        assert!(parse("aspect = \"16:9\"").is_err());
+
+        assert_eq!((video.backend, video.adapter.as_deref()), (GraphicsBackend::Auto, None));
+        let video = parse("backend = \"dx12\"\nadapter = \"Intel\"").unwrap();
+        assert_eq!((video.backend, video.adapter.as_deref()), (GraphicsBackend::Dx12, Some("Intel")));
+        assert!(parse("backend = \"directx\"").is_err());
+
+        assert_eq!(video.anti_aliasing, AntiAliasing::Off);
+        assert_eq!(parse("anti_aliasing = \"msaa4x\"").unwrap().anti_aliasing, AntiAliasing::Msaa4x);
+        assert_eq!(parse("anti_aliasing = \"fxaa\"").unwrap().anti_aliasing, AntiAliasing::Fxaa);
+        assert!(parse("anti_aliasing = \"msaa16x\"").is_err());
+
+        assert_eq!(video.internal_scale, 1);
+        let video = parse("internal_scale = 4\n[game_internal_scale]\nvf2 = 1\ndaytona = 9").unwrap();
+        assert_eq!(video.internal_scale_for(None), 4);
+        assert_eq!(video.internal_scale_for(Some("srallyc")), 4);
+        assert_eq!(video.internal_scale_for(Some("vf2")), 1);
+        assert_eq!(video.internal_scale_for(Some("daytona")), MAX_INTERNAL_SCALE);
+
+        assert_eq!(video.geometry_precision_for(Some("vf2")), GeometryPrecision::Auto);
+        let video = parse("geometry_precision = \"float\"\n[game_geometry_precision]\nvf2 = \"fixed\"").unwrap();
+        assert!(!video.geometry_precision_for(Some("daytona")).use_fixed_point(true));
+        assert!(video.geometry_precision_for(Some("vf2")).use_fixed_point(false));
+        assert!(GeometryPrecision::Auto.use_fixed_point(true));
+        assert!(parse("geometry_precision = \"double\"").is_err());
+
+        assert!(!video.mipmaps_for(Some("vf2")).enabled);
+        let video = parse("[mipmaps]\nlod_bias = -0.5\n[game_mipmaps.daytona]\nenabled = true").unwrap();
+        assert_eq!(video.mipmaps_for(None), MipmapSettings { enabled: false, lod_bias: -0.5 });
+        assert_eq!(video.mipmaps_for(Some("daytona")), MipmapSettings { enabled: true, lod_bias: 0.0 });
+    }
+
+    #[test]
+    fn test_anti_aliasing_limits_and_steps() {
+        assert_eq!(AntiAliasing::Msaa8x.limited_to(4), AntiAliasing::Msaa4x);
+        assert_eq!(AntiAliasing::Msaa4x.limited_to(1), AntiAliasing::Off);
+        assert_eq!(AntiAliasing::Fxaa.limited_to(1), AntiAliasing::Fxaa);
+        assert_eq!(AntiAliasing::Msaa2x.limited_to(8), AntiAliasing::Msaa2x);
+
+        assert_eq!(AntiAliasing::Off.step(-1), AntiAliasing::Off);
+        assert_eq!(AntiAliasing::Msaa8x.step(1), AntiAliasing::Fxaa);
+        assert_eq!(AntiAliasing::Fxaa.step(1), AntiAliasing::Fxaa);
+        assert_eq!(AntiAliasing::Fxaa.normalized(), 1.0);
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut config = EmulatorConfig::default();
+        config.video.fullscreen_mode = FullscreenMode::Exclusive;
+        config.video.window.x = Some(-1920);
+
+        config.video.color_for_mut(Some("daytona")).gamma = 1.4;
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        let parsed: EmulatorConfig = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.video.fullscreen_mode, FullscreenMode::Exclusive);
+        assert_eq!(parsed.video.window.x, Some(-1920));
+        assert_eq!(parsed.video.color_for(Some("daytona")).gamma, 1.4);
+        assert_eq!(parsed.video.color_for(Some("vf2")), ColorAdjustment::default());
+    }
+
+    #[test]
+    fn test_color_adjustment_steps_stay_in_range() {
+        let mut color = ColorAdjustment::default();
+        color.step(ColorParameter::Brightness, 3);
+        assert!((color.brightness - 0.06).abs() < 1e-6);
+
+        color.step(ColorParameter::Gamma, 100);
+        assert_eq!(color.gamma, 2.5);
+        color.step(ColorParameter::Saturation, -100);
+        assert_eq!(color.saturation, 0.0);
+        assert!((color.normalized(ColorParameter::Contrast) - 1.0 / 3.0).abs() < 1e-6);
+    }
+}
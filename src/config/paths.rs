@@ -0,0 +1,316 @@
+//! Répertoires par utilisateur de l'émulateur
+//!
+//! Les chemins relatifs au répertoire courant (`./roms`, `config.toml`...) ne
+//! fonctionnent que si l'émulateur est lancé depuis la racine du projet. Ce
+//! module résout des répertoires stables selon la plateforme :
+//! - Linux : `$XDG_CONFIG_HOME` / `$XDG_DATA_HOME` (ou `~/.config`, `~/.local/share`)
+//! - Windows : `%APPDATA%` / `%LOCALAPPDATA%`
+//! - macOS : `~/Library/Application Support`
+//!
+//! Chaque chemin peut être surchargé depuis la ligne de commande.
+
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Nom du répertoire de l'application dans les dossiers utilisateur
+pub const APP_DIR_NAME: &str = "pixel-model2";
+
+/// Nom du fichier de configuration
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Fichier témoin, dans `data_dir`, d'une migration déjà effectuée
+const MIGRATION_MARKER: &str = ".legacy-migrated";
+
+/// Surcharges de chemins passées en ligne de commande
+#[derive(Debug, Clone, Default)]
+pub struct PathOverrides {
+    /// `--config <fichier>`
+    pub config_file: Option<PathBuf>,
+
+    /// `--data-dir <répertoire>`
+    pub data_dir: Option<PathBuf>,
+
+    /// `--rom-dir <répertoire>` (peut être répété)
+    pub rom_dirs: Vec<PathBuf>,
+}
+
+impl PathOverrides {
+    /// Extrait les surcharges de chemins d'une liste d'arguments.
+    ///
+    /// Les arguments non reconnus sont ignorés pour laisser les autres options
+    /// de la ligne de commande à l'appelant.
+    pub fn from_args<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut overrides = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let flag = arg.as_ref().to_string();
+            let mut value = || {
+                args.next()
+                    .map(|v| PathBuf::from(v.as_ref()))
+                    .ok_or_else(|| anyhow!("Valeur manquante pour l'option {}", flag))
+            };
+
+            match arg.as_ref() {
+                "--config" => overrides.config_file = Some(value()?),
+                "--data-dir" => overrides.data_dir = Some(value()?),
+                "--rom-dir" => overrides.rom_dirs.push(value()?),
+                _ => {}
+            }
+        }
+
+        Ok(overrides)
+    }
+}
+
+/// Ensemble des répertoires utilisés par l'émulateur
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppPaths {
+    /// Fichier de configuration
+    pub config_file: PathBuf,
+
+    /// Racine des données utilisateur
+    pub data_dir: PathBuf,
+
+    /// États sauvegardés
+    pub saves_dir: PathBuf,
+
+    /// Mémoire non volatile des jeux (EEPROM, high scores)
+    pub nvram_dir: PathBuf,
+
+    /// Captures d'écran
+    pub screenshots_dir: PathBuf,
+
+    /// Définitions des succès, un fichier par jeu
+    pub achievements_dir: PathBuf,
+
+    /// Jaquettes affichées par le lanceur (`<jeu>.png`)
+    pub boxart_dir: PathBuf,
+
+    /// Répertoires de recherche des ROMs, par ordre de priorité
+    pub rom_dirs: Vec<PathBuf>,
+}
+
+impl AppPaths {
+    /// Résout les répertoires de la plateforme courante
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var_os(name).map(PathBuf::from))
+    }
+
+    /// Résout les répertoires à partir d'une source de variables d'environnement
+    pub fn from_env<F>(var: F) -> Self
+    where
+        F: Fn(&str) -> Option<PathBuf>,
+    {
+        let (config_root, data_root) = platform_roots(&var);
+        Self::from_roots(config_root.join(APP_DIR_NAME), data_root.join(APP_DIR_NAME))
+    }
+
+    /// Construit l'arborescence à partir d'un répertoire de configuration et de données
+    pub fn from_roots(config_dir: PathBuf, data_dir: PathBuf) -> Self {
+        Self {
+            config_file: config_dir.join(CONFIG_FILE_NAME),
+            saves_dir: data_dir.join("saves"),
+            nvram_dir: data_dir.join("nvram"),
+            screenshots_dir: data_dir.join("screenshots"),
+            achievements_dir: data_dir.join("achievements"),
+            boxart_dir: data_dir.join("boxart"),
+            rom_dirs: vec![data_dir.join("roms")],
+            data_dir,
+        }
+    }
+
+    /// Applique les surcharges de la ligne de commande
+    pub fn with_overrides(mut self, overrides: &PathOverrides) -> Self {
+        if let Some(data_dir) = &overrides.data_dir {
+            let config_file = self.config_file.clone();
+            self = Self::from_roots(PathBuf::new(), data_dir.clone());
+            self.config_file = config_file;
+        }
+
+        if let Some(config_file) = &overrides.config_file {
+            self.config_file = config_file.clone();
+        }
+
+        if !overrides.rom_dirs.is_empty() {
+            let mut rom_dirs = overrides.rom_dirs.clone();
+            rom_dirs.append(&mut self.rom_dirs);
+            self.rom_dirs = rom_dirs;
+        }
+
+        self
+    }
+
+    /// Crée les répertoires manquants
+    pub fn ensure_dirs(&self) -> Result<()> {
+        if let Some(parent) = self.config_file.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        for dir in [&self.data_dir, &self.saves_dir, &self.nvram_dir, &self.screenshots_dir, &self.achievements_dir, &self.boxart_dir] {
+            fs::create_dir_all(dir)?;
+        }
+
+        Ok(())
+    }
+
+    /// Migre les fichiers d'une installation « portable » (répertoire de
+    /// l'exécutable).
+    ///
+    /// La migration n'a lieu qu'une fois : un fichier témoin dans `data_dir`
+    /// l'empêche de ramener ensuite des sauvegardes supprimées. Les fichiers
+    /// existants dans les répertoires utilisateur ne sont jamais écrasés.
+    /// Retourne la liste des fichiers copiés.
+    pub fn migrate_legacy(&self, legacy_root: &Path) -> Result<Vec<PathBuf>> {
+        let marker = self.data_dir.join(MIGRATION_MARKER);
+        if marker.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut migrated = Vec::new();
+
+        let legacy_config = legacy_root.join(CONFIG_FILE_NAME);
+        if legacy_config.is_file() && !self.config_file.exists() {
+            if let Some(parent) = self.config_file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&legacy_config, &self.config_file)?;
+            migrated.push(self.config_file.clone());
+        }
+
+        for (name, target) in [("saves", &self.saves_dir), ("nvram", &self.nvram_dir)] {
+            let source = legacy_root.join(name);
+            if !source.is_dir() {
+                continue;
+            }
+
+            fs::create_dir_all(target)?;
+            for entry in fs::read_dir(&source)? {
+                let entry = entry?;
+                let destination = target.join(entry.file_name());
+                if entry.file_type()?.is_file() && !destination.exists() {
+                    fs::copy(entry.path(), &destination)?;
+                    migrated.push(destination);
+                }
+            }
+        }
+
+        fs::create_dir_all(&self.data_dir)?;
+        fs::write(&marker, legacy_root.display().to_string())?;
+        Ok(migrated)
+    }
+
+    /// Répertoires de recherche des ROMs, suivis des emplacements hérités
+    /// relatifs à l'exécutable et au répertoire courant
+    pub fn rom_search_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.rom_dirs.clone();
+
+        if let Some(exe_dir) = executable_dir() {
+            paths.push(exe_dir.join("roms"));
+        }
+        paths.push(PathBuf::from("roms"));
+
+        let mut unique = Vec::with_capacity(paths.len());
+        for path in paths {
+            if !unique.contains(&path) {
+                unique.push(path);
+            }
+        }
+        unique
+    }
+}
+
+impl Default for AppPaths {
+    fn default() -> Self {
+        Self::detect()
+    }
+}
+
+/// Répertoire contenant l'exécutable (racine d'une installation portable)
+pub fn executable_dir() -> Option<PathBuf> {
+    std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf))
+}
+
+/// Répertoires racine de configuration et de données de la plateforme
+fn platform_roots<F>(var: &F) -> (PathBuf, PathBuf)
+where
+    F: Fn(&str) -> Option<PathBuf>,
+{
+    let home = var("HOME").or_else(|| var("USERPROFILE")).unwrap_or_else(|| PathBuf::from("."));
+
+    if cfg!(windows) {
+        let roaming = var("APPDATA").unwrap_or_else(|| home.join("AppData").join("Roaming"));
+        let local = var("LOCALAPPDATA").unwrap_or_else(|| home.join("AppData").join("Local"));
+        (roaming, local)
+    } else if cfg!(target_os = "macos") {
+        let support = home.join("Library").join("Application Support");
+        (support.clone(), support)
+    } else {
+        let config = var("XDG_CONFIG_HOME")
+            .filter(|p| p.is_absolute())
+            .unwrap_or_else(|| home.join(".config"));
+        let data = var("XDG_DATA_HOME")
+            .filter(|p| p.is_absolute())
+            .unwrap_or_else(|| home.join(".local").join("share"));
+        (config, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_xdg_directories() {
+        let paths = AppPaths::from_env(|name| match name {
+            "HOME" => Some(PathBuf::from("/home/user")),
+            "XDG_DATA_HOME" => Some(PathBuf::from("/data")),
+            _ => None,
+        });
+
+        assert_eq!(paths.config_file, PathBuf::from("/home/user/.config/pixel-model2/config.toml"));
+        assert_eq!(paths.saves_dir, PathBuf::from("/data/pixel-model2/saves"));
+        assert_eq!(paths.rom_dirs, vec![PathBuf::from("/data/pixel-model2/roms")]);
+    }
+
+    #[test]
+    fn test_overrides_from_args() {
+        let args = ["--rom", "daytona", "--config", "my.toml", "--rom-dir", "/arcade"];
+        let overrides = PathOverrides::from_args(args).unwrap();
+        let paths = AppPaths::from_roots(PathBuf::from("/cfg"), PathBuf::from("/data"))
+            .with_overrides(&overrides);
+
+        assert_eq!(paths.config_file, PathBuf::from("my.toml"));
+        assert_eq!(paths.rom_dirs[0], PathBuf::from("/arcade"));
+        assert_eq!(paths.rom_dirs[1], PathBuf::from("/data/roms"));
+
+        assert!(PathOverrides::from_args(["--data-dir"]).is_err());
+    }
+
+    #[test]
+    fn test_migrate_legacy_files() {
+        let legacy = tempfile::tempdir().unwrap();
+        let target = tempfile::tempdir().unwrap();
+        fs::write(legacy.path().join(CONFIG_FILE_NAME), "[video]").unwrap();
+        fs::create_dir(legacy.path().join("nvram")).unwrap();
+        fs::write(legacy.path().join("nvram").join("daytona.nv"), [1, 2, 3]).unwrap();
+
+        let paths = AppPaths::from_roots(target.path().join("cfg"), target.path().join("data"));
+        let migrated = paths.migrate_legacy(legacy.path()).unwrap();
+        assert_eq!(migrated.len(), 2);
+        assert_eq!(fs::read(paths.nvram_dir.join("daytona.nv")).unwrap(), vec![1, 2, 3]);
+
+        // Une seconde migration ne recopie rien, même un fichier supprimé depuis
+        fs::remove_file(paths.nvram_dir.join("daytona.nv")).unwrap();
+        assert!(paths.migrate_legacy(legacy.path()).unwrap().is_empty());
+        assert!(!paths.nvram_dir.join("daytona.nv").exists());
+    }
+}
@@ -0,0 +1,374 @@
+//! Service des réglages
+//!
+//! Le fichier de configuration, les options de la ligne de commande
+//! (`--set`), le menu de pause et la console du débogueur modifient tous la
+//! même [`EmulatorConfig`] à travers [`Settings`]. Un réglage est désigné
+//! par son chemin dans le fichier (`audio.volume`,
+//! `video.texture_filtering`, `input.players.2.up` pour un élément d'une
+//! liste de sections) ; toute modification est validée avant
+//! d'être appliquée, puis chaque valeur changée est notifiée aux abonnés
+//! ([`Settings::subscribe`]), qui la reportent en direct sur le sous-système
+//! concerné. Les options de la ligne de commande ne valent que pour la
+//! session : [`Settings::persisted`] rend la configuration à enregistrer,
+//! sans elles.
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use super::{ColorParameter, EmulatorConfig, MAX_INTERNAL_SCALE};
+
+/// Valeur d'un réglage modifiée
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingChange {
+    /// Chemin du réglage (`audio.volume`)
+    pub key: String,
+    /// Ancienne valeur (absente pour une option qui n'était pas définie)
+    pub old: Option<toml::Value>,
+    /// Nouvelle valeur (absente pour une option retirée)
+    pub new: Option<toml::Value>,
+}
+
+impl SettingChange {
+    /// Vrai si le réglage est `prefix` ou se trouve sous `prefix`
+    pub fn is_under(&self, prefix: &str) -> bool {
+        prefix.is_empty()
+            || self.key.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    }
+
+    /// Nouvelle valeur lue dans le type du réglage
+    pub fn value<T: DeserializeOwned>(&self) -> Option<T> {
+        self.new.clone().and_then(|value| value.try_into().ok())
+    }
+}
+
+/// Configuration de l'émulateur et abonnés à ses modifications ; la
+/// lecture passe par [`Deref`], l'écriture uniquement par le service
+#[derive(Debug, Default)]
+pub struct Settings {
+    config: EmulatorConfig,
+    subscribers: Vec<(String, Sender<SettingChange>)>,
+
+    /// Réglages modifiés pour la session seulement, avec la valeur à
+    /// enregistrer (absente pour une option qui n'était pas définie)
+    overrides: BTreeMap<String, Option<toml::Value>>,
+}
+
+impl Settings {
+    pub fn new(config: EmulatorConfig) -> Self {
+        Self { config, subscribers: Vec::new(), overrides: BTreeMap::new() }
+    }
+
+    pub fn config(&self) -> &EmulatorConfig {
+        &self.config
+    }
+
+    pub fn into_config(self) -> EmulatorConfig {
+        self.config
+    }
+
+    /// Reçoit les modifications des réglages sous `prefix` (`video`,
+    /// `audio.volume`, ou vide pour tous)
+    pub fn subscribe(&mut self, prefix: &str) -> Receiver<SettingChange> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push((prefix.trim_end_matches('.').to_string(), sender));
+        receiver
+    }
+
+    /// Tous les réglages définis et leur valeur, par chemin
+    pub fn entries(&self) -> BTreeMap<String, toml::Value> {
+        flatten(&self.config)
+    }
+
+    /// Valeur brute d'un réglage ; un chemin intermédiaire (`video.color`)
+    /// rend la table entière
+    pub fn value(&self, key: &str) -> Result<toml::Value> {
+        let root = toml::Value::try_from(&self.config)?;
+        key.split('.')
+            .try_fold(&root, child)
+            .cloned()
+            .with_context(|| format!("réglage inconnu ou non défini : {}", key))
+    }
+
+    /// Valeur d'un réglage, lue dans le type demandé
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
+        self.value(key)?
+            .try_into()
+            .with_context(|| format!("type inattendu pour le réglage {}", key))
+    }
+
+    /// Modifie un réglage ; retourne les valeurs réellement changées
+    pub fn set<T: Serialize>(&mut self, key: &str, value: T) -> Result<Vec<SettingChange>> {
+        let value = toml::Value::try_from(value).with_context(|| format!("valeur invalide pour {}", key))?;
+        self.set_value(key, value)
+    }
+
+    /// Modifie un réglage depuis du texte (ligne de commande, console) :
+    /// syntaxe TOML (`0.5`, `true`, `"linear"`), ou chaîne sans guillemets
+    pub fn set_str(&mut self, key: &str, text: &str) -> Result<Vec<SettingChange>> {
+        let text = text.trim();
+        let value = toml::from_str::<toml::Table>(&format!("value = {}", text))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| toml::Value::String(text.to_string()));
+        self.set_value(key, value)
+    }
+
+    /// Comme [`set_str`](Self::set_str), pour la session seulement
+    /// (`--set`) : la valeur n'est pas enregistrée, sauf si le réglage est
+    /// modifié à nouveau autrement
+    pub fn set_override(&mut self, key: &str, text: &str) -> Result<Vec<SettingChange>> {
+        let earlier = self.overrides.clone();
+        let changes = self.set_str(key, text)?;
+        for change in &changes {
+            let persisted = earlier.get(&change.key).cloned().unwrap_or_else(|| change.old.clone());
+            self.overrides.insert(change.key.clone(), persisted);
+        }
+        Ok(changes)
+    }
+
+    /// Configuration à enregistrer : les réglages modifiés pour la session
+    /// y gardent leur valeur précédente
+    pub fn persisted(&self) -> Result<EmulatorConfig> {
+        let mut root = toml::Value::try_from(&self.config)?;
+        for (key, value) in &self.overrides {
+            let Some((table, leaf)) = leaf_table(&mut root, key) else {
+                continue;
+            };
+            match value {
+                Some(value) => table.insert(leaf.to_string(), value.clone()),
+                None => table.remove(leaf),
+            };
+        }
+        Ok(root.try_into()?)
+    }
+
+    fn set_value(&mut self, key: &str, mut value: toml::Value) -> Result<Vec<SettingChange>> {
+        let mut root = toml::Value::try_from(&self.config)?;
+        let (table, leaf) = leaf_table(&mut root, key).with_context(|| format!("réglage inconnu : {}", key))?;
+
+        // Entier donné pour un réglage décimal (`volume = 1`)
+        if let (Some(toml::Value::Float(_)), toml::Value::Integer(integer)) = (table.get(leaf), &value) {
+            value = toml::Value::Float(*integer as f64);
+        }
+        table.insert(leaf.to_string(), value);
+
+        let config: EmulatorConfig = root.try_into().with_context(|| format!("valeur invalide pour {}", key))?;
+        // Un champ inconnu est ignoré par la désérialisation
+        if !flatten(&config).keys().any(|entry| entry == key || entry.starts_with(&format!("{}.", key))) {
+            bail!("réglage inconnu : {}", key);
+        }
+        self.replace(config)
+    }
+
+    /// Modifie plusieurs réglages à la fois sur la configuration
+    pub fn update(&mut self, change: impl FnOnce(&mut EmulatorConfig)) -> Result<Vec<SettingChange>> {
+        let mut config = self.config.clone();
+        change(&mut config);
+        self.replace(config)
+    }
+
+    /// Remplace toute la configuration (fichier relu) ; les différences
+    /// sont notifiées comme des modifications individuelles
+    pub fn replace(&mut self, config: EmulatorConfig) -> Result<Vec<SettingChange>> {
+        let (before, after) = (flatten(&self.config), flatten(&config));
+        let keys: std::collections::BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+        let changes: Vec<SettingChange> = keys
+            .into_iter()
+            .filter(|key| before.get(*key) != after.get(*key))
+            .map(|key| SettingChange { key: key.clone(), old: before.get(key).cloned(), new: after.get(key).cloned() })
+            .collect();
+        // Seules les valeurs modifiées sont vérifiées : une valeur hors
+        // plage du fichier reste bornée à l'usage, comme avant
+        for change in &changes {
+            check_range(change)?;
+        }
+        self.config = config;
+        for change in &changes {
+            self.overrides.remove(&change.key);
+        }
+
+        self.subscribers.retain(|(prefix, sender)| {
+            changes
+                .iter()
+                .filter(|change| change.is_under(prefix))
+                .all(|change| sender.send(change.clone()).is_ok())
+        });
+        Ok(changes)
+    }
+}
+
+impl Deref for Settings {
+    type Target = EmulatorConfig;
+
+    fn deref(&self) -> &EmulatorConfig {
+        &self.config
+    }
+}
+
+/// Refuse une valeur numérique hors de la plage de son réglage
+fn check_range(change: &SettingChange) -> Result<()> {
+    let Some(value) = change.new.as_ref().and_then(|value| value.as_float().or(value.as_integer().map(|value| value as f64))) else {
+        return Ok(());
+    };
+    let leaf = change.key.rsplit('.').next().unwrap_or_default();
+    let is_color = change.key.starts_with("video.color.") || change.key.starts_with("video.game_colors.");
+    let (min, max) = match change.key.as_str() {
+        "audio.volume" => (0.0, 1.0),
+        "audio.sample_rate" => (8_000.0, 192_000.0),
+        "audio.latency_ms" => (1.0, 1_000.0),
+        "audio.cabinet.low_cut_hz" | "audio.cabinet.high_cut_hz" => (20.0, 20_000.0),
+        "audio.cabinet.reverb" => (0.0, 1.0),
+        "emulation.fast_forward_speed" => (1.0, 8.0),
+        "emulation.slow_motion_speed" => (0.1, 1.0),
+        "emulation.heatmap.block_size" => (4.0, 16_777_216.0),
+        "emulation.heatmap.interval_frames" => (1.0, 36_000.0),
+        "video.internal_scale" => (1.0, MAX_INTERNAL_SCALE as f64),
+        _ if is_color => match ColorParameter::ALL.into_iter().find(|parameter| parameter.key() == leaf) {
+            Some(parameter) => {
+                let (min, max) = parameter.range();
+                (min as f64, max as f64)
+            }
+            None => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    if !(min..=max).contains(&value) {
+        bail!("{} hors limites : {} (de {} à {})", change.key, value, min, max);
+    }
+    Ok(())
+}
+
+/// Table ou élément d'une liste désigné par une partie de chemin
+fn child<'a>(value: &'a toml::Value, part: &str) -> Option<&'a toml::Value> {
+    match (value, part.parse::<usize>()) {
+        (toml::Value::Array(array), Ok(index)) => array.get(index),
+        (value, _) => value.get(part),
+    }
+}
+
+fn child_mut<'a>(value: &'a mut toml::Value, part: &str) -> Option<&'a mut toml::Value> {
+    match (value, part.parse::<usize>()) {
+        (toml::Value::Array(array), Ok(index)) => array.get_mut(index),
+        (value, _) => value.get_mut(part),
+    }
+}
+
+/// Table qui contient le réglage `key`, et son nom dans cette table
+fn leaf_table<'a, 'k>(root: &'a mut toml::Value, key: &'k str) -> Option<(&'a mut toml::Table, &'k str)> {
+    let (parents, leaf) = key.rsplit_once('.').map_or((None, key), |(parents, leaf)| (Some(parents), leaf));
+    let table = parents
+        .into_iter()
+        .flat_map(|parents| parents.split('.'))
+        .try_fold(root, child_mut)
+        .and_then(toml::Value::as_table_mut)?;
+    Some((table, leaf))
+}
+
+/// Valeurs de la configuration par chemin, tables et listes de tables
+/// (`[[input.players]]`) dépliées
+fn flatten(config: &EmulatorConfig) -> BTreeMap<String, toml::Value> {
+    fn walk(prefix: &str, value: &toml::Value, entries: &mut BTreeMap<String, toml::Value>) {
+        match value {
+            toml::Value::Table(table) => {
+                for (key, value) in table {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    walk(&path, value, entries);
+                }
+            }
+            toml::Value::Array(array) if !array.is_empty() && array.iter().all(toml::Value::is_table) => {
+                for (index, value) in array.iter().enumerate() {
+                    walk(&format!("{}.{}", prefix, index), value, entries);
+                }
+            }
+            value => {
+                entries.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+
+    let mut entries = BTreeMap::new();
+    if let Ok(root) = toml::Value::try_from(config) {
+        walk("", &root, &mut entries);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AntiAliasing, FramePacing};
+
+    #[test]
+    fn test_settings_validate_and_notify() {
+        let mut settings = Settings::new(EmulatorConfig::default());
+        let video = settings.subscribe("video");
+        let all = settings.subscribe("");
+
+        assert_eq!(settings.get::<f32>("audio.volume").unwrap(), 1.0);
+        assert_eq!(settings.get::<FramePacing>("video.frame_pacing").unwrap(), FramePacing::Vsync);
+        assert!(settings.entries().contains_key("video.color.gamma"));
+
+        // Texte de la ligne de commande : entier converti, chaîne sans guillemets
+        let changes = settings.set_str("audio.volume", "0").unwrap();
+        assert_eq!(changes[0].value::<f32>(), Some(0.0));
+        settings.set_str("video.texture_filtering", "nearest").unwrap();
+        settings.set("video.anti_aliasing", AntiAliasing::Msaa4x).unwrap();
+        assert_eq!(settings.video.anti_aliasing, AntiAliasing::Msaa4x);
+
+        // Valeur inchangée : aucune notification
+        assert!(settings.set("video.texture_filtering", "nearest").unwrap().is_empty());
+
+        // Refusés sans toucher à la configuration
+        assert!(settings.set_str("audio.volume", "2.5").is_err());
+        assert!(settings.set_str("video.frame_pacing", "turbo").is_err());
+        assert!(settings.set_str("video.texture_filter", "linear").is_err());
+        assert!(settings.set_str("nope.volume", "1").is_err());
+        assert!(settings.update(|config| config.video.color.gamma = 9.0).is_err());
+        assert_eq!(settings.audio.volume, 0.0);
+
+        // Élément d'une liste de sections
+        assert_eq!(settings.get::<String>("input.players.2.up").unwrap(), "T");
+        settings.set("input.players.2.up", "I").unwrap();
+        assert_eq!(settings.input.players[2].up, "I");
+        assert!(settings.set("input.players.9.up", "I").is_err());
+
+        // Option absente, puis réglages d'un jeu modifiés en bloc
+        settings.set("emulation.master_clock_hz", 48_000_000).unwrap();
+        settings.update(|config| config.video.color_for_mut(Some("vf2")).gamma = 1.2).unwrap();
+
+        let video: Vec<String> = video.try_iter().map(|change| change.key).collect();
+        assert_eq!(video, ["video.texture_filtering", "video.anti_aliasing", "video.game_colors.vf2.brightness", "video.game_colors.vf2.contrast", "video.game_colors.vf2.gamma", "video.game_colors.vf2.saturation"]);
+        let all: Vec<SettingChange> = all.try_iter().collect();
+        assert_eq!(all.len(), 9);
+        assert_eq!(all[3].key, "input.players.2.up");
+        assert_eq!(all[4], SettingChange {
+            key: "emulation.master_clock_hz".to_string(),
+            old: None,
+            new: Some(toml::Value::Integer(48_000_000)),
+        });
+    }
+
+    #[test]
+    fn test_session_overrides_are_not_persisted() {
+        let mut settings = Settings::new(EmulatorConfig::default());
+        settings.set_str("audio.volume", "0.8").unwrap();
+        settings.set_override("audio.volume", "0.2").unwrap();
+        settings.set_override("audio.volume", "0.3").unwrap();
+        settings.set_override("audio.device", "Casque").unwrap();
+        settings.set_override("video.texture_filtering", "nearest").unwrap();
+        assert_eq!(settings.audio.volume, 0.3);
+
+        // Réglage modifié ensuite depuis le menu ou la console : enregistré
+        settings.set_str("video.texture_filtering", "bilinear").unwrap();
+
+        let persisted = settings.persisted().unwrap();
+        assert_eq!(persisted.audio.volume, 0.8);
+        assert_eq!(persisted.audio.device, None);
+        assert_eq!(persisted.video.texture_filtering, "bilinear");
+        assert_eq!(settings.audio.device.as_deref(), Some("Casque"));
+    }
+}
@@ -1,7 +1,7 @@
 //! Opérations arithmétiques avancées pour le NEC V60
 
 use super::registers::ProcessorStatusWord;
-use anyhow::Result;
+use crate::error::{CpuException, Result};
 
 /// Résultat d'une opération arithmétique avec flags
 #[derive(Debug, Clone)]
@@ -46,8 +46,7 @@ impl ArithmeticUnit {
         let (result, carry) = operand1.overflowing_add(operand2);
         
         // Détection de débordement signé
-        let overflow = ((operand1 as i32 > 0) && (operand2 as i32 > 0) && ((result as i32) < 0)) ||
-                      (((operand1 as i32) < 0) && ((operand2 as i32) < 0) && (result as i32 > 0));
+        let overflow = (operand1 as i32).overflowing_add(operand2 as i32).1;
         
         ArithmeticResult::new(result, carry, overflow)
     }
@@ -57,8 +56,7 @@ impl ArithmeticUnit {
         let (result, carry) = operand1.overflowing_sub(operand2);
         
         // Détection de débordement signé
-        let overflow = ((operand1 as i32 > 0) && ((operand2 as i32) < 0) && ((result as i32) < 0)) ||
-                      (((operand1 as i32) < 0) && (operand2 as i32 > 0) && (result as i32 > 0));
+        let overflow = (operand1 as i32).overflowing_sub(operand2 as i32).1;
         
         ArithmeticResult::new(result, carry, overflow)
     }
@@ -76,7 +74,7 @@ impl ArithmeticUnit {
     /// Division 32-bit avec gestion des erreurs
     pub fn div(operand1: u32, operand2: u32) -> Result<ArithmeticResult> {
         if operand2 == 0 {
-            return Err(anyhow::anyhow!("Division par zéro"));
+            return Err(CpuException::DivisionByZero.into());
         }
         
         let result = operand1 / operand2;
@@ -168,6 +166,14 @@ mod tests {
         assert!(!result.overflow);
     }
     
+    #[test]
+    fn test_arithmetic_signed_overflow_edges() {
+        // i32::MIN + i32::MIN donne 0, 0 - i32::MIN ne tient pas sur 32 bits
+        assert!(ArithmeticUnit::add(0x8000_0000, 0x8000_0000).overflow);
+        assert!(ArithmeticUnit::sub(0, 0x8000_0000).overflow);
+        assert!(!ArithmeticUnit::sub(0, 1).overflow);
+    }
+    
     #[test]
     fn test_arithmetic_mul() {
         let result = ArithmeticUnit::mul(6, 7);
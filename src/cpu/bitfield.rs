@@ -0,0 +1,149 @@
+//! Instructions de champs de bits NEC V60 (EXTBF, INSBF, CMPBF)
+//!
+//! Un champ de bits est désigné par une adresse de base, un décalage signé
+//! en bits depuis le bit 0 de l'octet de base et une longueur de 1 à 32
+//! bits. Les bits sont numérotés du poids faible au poids fort, dans l'ordre
+//! little-endian de la mémoire : un champ peut donc chevaucher des octets et
+//! des mots, sur 5 octets au plus.
+
+use super::arithmetic::{ArithmeticResult, ArithmeticUnit};
+use crate::memory::MemoryInterface;
+use crate::error::{CpuException, Result};
+
+/// Longueur maximale d'un champ de bits
+pub const MAX_BITFIELD_LENGTH: u32 = 32;
+
+/// Champ de bits en mémoire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitField {
+    /// Octet qui contient le premier bit du champ
+    pub address: u32,
+
+    /// Position du premier bit dans cet octet (0 à 7)
+    pub bit: u32,
+
+    pub length: u32,
+}
+
+impl BitField {
+    /// Champ de `length` bits à `offset` bits de `base`
+    pub fn new(base: u32, offset: i32, length: u32) -> Result<Self> {
+        if length == 0 || length > MAX_BITFIELD_LENGTH {
+            return Err(CpuException::InvalidBitFieldLength(length).into());
+        }
+        Ok(Self {
+            address: base.wrapping_add((offset >> 3) as u32),
+            bit: (offset & 7) as u32,
+            length,
+        })
+    }
+
+    /// Octets couverts par le champ
+    fn bytes(&self) -> u32 {
+        (self.bit + self.length).div_ceil(8)
+    }
+
+    /// Masque du champ dans la fenêtre de ses octets
+    fn mask(&self) -> u64 {
+        ((1u64 << self.length) - 1) << self.bit
+    }
+}
+
+/// Unité de champs de bits
+pub struct BitFieldUnit;
+
+impl BitFieldUnit {
+    /// Lit les octets couverts par le champ, le premier en poids faible
+    fn read_window<M>(memory: &M, field: &BitField) -> Result<u64>
+    where
+        M: MemoryInterface,
+    {
+        let mut window = 0u64;
+        for index in 0..field.bytes() {
+            window |= (memory.read_u8(field.address.wrapping_add(index))? as u64) << (8 * index);
+        }
+        Ok(window)
+    }
+
+    /// Étend le signe d'une valeur de `length` bits
+    pub fn sign_extend(value: u32, length: u32) -> u32 {
+        let shift = 32 - length.clamp(1, 32);
+        (((value << shift) as i32) >> shift) as u32
+    }
+
+    /// Valeur du champ, étendue en signe si `signed` (EXTBFS) ou complétée
+    /// de zéros (EXTBFZ)
+    pub fn extract<M>(memory: &M, field: &BitField, signed: bool) -> Result<u32>
+    where
+        M: MemoryInterface,
+    {
+        let value = ((Self::read_window(memory, field)? & field.mask()) >> field.bit) as u32;
+        Ok(if signed { Self::sign_extend(value, field.length) } else { value })
+    }
+
+    /// Range les `length` bits de poids faible de `value` dans le champ
+    /// (INSBF) ; les bits voisins sont conservés
+    pub fn insert<M>(memory: &mut M, field: &BitField, value: u32) -> Result<()>
+    where
+        M: MemoryInterface,
+    {
+        let window = Self::read_window(memory, field)?;
+        let window = (window & !field.mask()) | (((value as u64) << field.bit) & field.mask());
+        for index in 0..field.bytes() {
+            memory.write_u8(field.address.wrapping_add(index), (window >> (8 * index)) as u8)?;
+        }
+        Ok(())
+    }
+
+    /// Compare le champ à `value` comme une soustraction champ - valeur
+    /// (CMPBFS, CMPBFZ) : seuls les indicateurs comptent
+    pub fn compare<M>(memory: &M, field: &BitField, value: u32, signed: bool) -> Result<ArithmeticResult>
+    where
+        M: MemoryInterface,
+    {
+        Ok(ArithmeticUnit::sub(Self::extract(memory, field, signed)?, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::ram::Ram;
+
+    #[test]
+    fn test_fields_across_word_boundaries() {
+        let mut memory = Ram::new(0x100);
+        memory.write_u32(0x10, 0x89AB_CDEF).unwrap();
+        memory.write_u32(0x14, 0x0123_4567).unwrap();
+
+        // 12 bits à cheval sur les deux mots : bits 28-31 puis 0-7
+        let field = BitField::new(0x10, 28, 12).unwrap();
+        assert_eq!(BitFieldUnit::extract(&memory, &field, false).unwrap(), 0x678);
+        assert_eq!(BitFieldUnit::extract(&memory, &field, true).unwrap(), 0x678);
+
+        // Décalage négatif : 4 bits avant la base
+        let field = BitField::new(0x14, -4, 4).unwrap();
+        assert_eq!(BitFieldUnit::extract(&memory, &field, false).unwrap(), 0x8);
+        assert_eq!(BitFieldUnit::extract(&memory, &field, true).unwrap(), 0xFFFF_FFF8);
+
+        // Champ de 32 bits non aligné, sur 5 octets
+        let field = BitField::new(0x10, 4, 32).unwrap();
+        assert_eq!(BitFieldUnit::extract(&memory, &field, false).unwrap(), 0x789A_BCDE);
+
+        // Insertion : seuls les bits du champ changent
+        let field = BitField::new(0x10, 30, 6).unwrap();
+        BitFieldUnit::insert(&mut memory, &field, 0xFFFF_FF00).unwrap();
+        assert_eq!(memory.read_u32(0x10).unwrap(), 0x09AB_CDEF);
+        assert_eq!(memory.read_u32(0x14).unwrap(), 0x0123_4560);
+
+        // Comparaison : 0xA vaut -6 en signé, 10 en non signé
+        let field = BitField::new(0x14, -12, 4).unwrap();
+        let signed = BitFieldUnit::compare(&memory, &field, 1, true).unwrap();
+        assert!(signed.negative && !signed.zero && !signed.carry);
+        assert!(BitFieldUnit::compare(&memory, &field, 10, false).unwrap().zero);
+        assert!(BitFieldUnit::compare(&memory, &field, 11, false).unwrap().carry);
+
+        assert!(BitField::new(0x10, 0, 0).is_err());
+        assert!(BitField::new(0x10, 0, 33).is_err());
+    }
+}
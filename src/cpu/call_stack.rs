@@ -0,0 +1,254 @@
+//! Pile d'appels fantôme du V60
+//!
+//! Le CPU empile une trame à chaque CALL (ou prise d'interruption) et la
+//! dépile au RET correspondant, ce qui permet d'afficher la pile d'appels
+//! courante sans avoir à interpréter la pile mémoire du jeu. En option, chaque
+//! entrée et sortie de fonction est journalisée avec son horodatage en cycles
+//! pour suivre le déroulement du code.
+//!
+//! Le code de jeu ne respecte pas toujours l'appariement CALL/RET (retours
+//! anticipés vers un appelant plus haut, sauts directs hors d'une routine) :
+//! au retour, on déroule jusqu'à la trame dont l'adresse de retour correspond,
+//! et un retour sans trame correspondante est simplement compté.
+
+/// Profondeur maximale suivie ; au-delà, les trames les plus anciennes sont oubliées
+pub const MAX_CALL_DEPTH: usize = 256;
+
+/// Nombre maximal d'événements conservés dans le journal
+pub const MAX_CALL_EVENTS: usize = 1_000_000;
+
+/// Origine d'une trame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    /// Instruction CALL
+    Call,
+    /// Prise d'interruption ou d'exception
+    Interrupt,
+}
+
+/// Trame de la pile d'appels fantôme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallFrame {
+    /// Adresse de la fonction appelée
+    pub target: u32,
+
+    /// Adresse de retour attendue
+    pub return_address: u32,
+
+    /// Pointeur de pile juste après l'empilement de l'adresse de retour
+    pub stack_pointer: u32,
+
+    /// Cycle CPU de l'appel
+    pub cycle: u64,
+
+    pub kind: CallKind,
+}
+
+/// Type d'événement journalisé
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallEventKind {
+    /// Entrée dans une fonction
+    Enter,
+    /// Sortie d'une fonction
+    Exit,
+}
+
+/// Entrée ou sortie de fonction horodatée
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallEvent {
+    pub kind: CallEventKind,
+
+    /// Fonction concernée
+    pub function: u32,
+
+    /// Adresse de l'appel (entrée) ou de retour (sortie)
+    pub site: u32,
+
+    /// Cycle CPU de l'événement
+    pub cycle: u64,
+
+    /// Profondeur de la fonction dans la pile (0 pour le niveau le plus haut)
+    pub depth: usize,
+
+    pub call_kind: CallKind,
+}
+
+/// Pile d'appels reconstruite à partir des CALL/RET exécutés
+#[derive(Debug, Clone, Default)]
+pub struct CallStack {
+    frames: Vec<CallFrame>,
+    events: Option<Vec<CallEvent>>,
+    unmatched_returns: u64,
+}
+
+impl CallStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Active la journalisation des entrées/sorties de fonction
+    pub fn enable_trace(&mut self) {
+        self.events.get_or_insert_with(Vec::new);
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.events.is_some()
+    }
+
+    /// Trames courantes, de la plus ancienne à la plus récente
+    pub fn frames(&self) -> &[CallFrame] {
+        &self.frames
+    }
+
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Fonction en cours d'exécution (None au niveau le plus haut)
+    pub fn current(&self) -> Option<&CallFrame> {
+        self.frames.last()
+    }
+
+    /// Retours rencontrés sans trame correspondante
+    pub fn unmatched_returns(&self) -> u64 {
+        self.unmatched_returns
+    }
+
+    /// Événements journalisés
+    pub fn events(&self) -> &[CallEvent] {
+        self.events.as_deref().unwrap_or_default()
+    }
+
+    /// Retire les événements journalisés (la journalisation reste active)
+    pub fn take_events(&mut self) -> Vec<CallEvent> {
+        self.events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Enregistre un appel
+    pub fn push(&mut self, frame: CallFrame) {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            self.frames.remove(0);
+        }
+        self.frames.push(frame);
+
+        let depth = self.frames.len() - 1;
+        self.log(CallEventKind::Enter, &frame, frame.return_address, frame.cycle, depth);
+    }
+
+    /// Enregistre un retour vers `return_address` et retourne la trame quittée.
+    ///
+    /// Les trames intermédiaires (fonctions quittées sans RET) sont déroulées
+    /// et journalisées comme des sorties.
+    pub fn pop(&mut self, return_address: u32, kind: CallKind, cycle: u64) -> Option<CallFrame> {
+        let Some(index) = self
+            .frames
+            .iter()
+            .rposition(|frame| frame.kind == kind && frame.return_address == return_address)
+        else {
+            self.unmatched_returns += 1;
+            return None;
+        };
+
+        let mut left = None;
+        while self.frames.len() > index {
+            let frame = self.frames.pop()?;
+            let depth = self.frames.len();
+            self.log(CallEventKind::Exit, &frame, return_address, cycle, depth);
+            left = Some(frame);
+        }
+        left
+    }
+
+    /// Vide la pile (réinitialisation du CPU) ; le journal est conservé
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.unmatched_returns = 0;
+    }
+
+    fn log(&mut self, kind: CallEventKind, frame: &CallFrame, site: u32, cycle: u64, depth: usize) {
+        let Some(events) = self.events.as_mut() else {
+            return;
+        };
+
+        if events.len() < MAX_CALL_EVENTS {
+            events.push(CallEvent {
+                kind,
+                function: frame.target,
+                site,
+                cycle,
+                depth,
+                call_kind: frame.kind,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(target: u32, return_address: u32, cycle: u64) -> CallFrame {
+        CallFrame {
+            target,
+            return_address,
+            stack_pointer: 0,
+            cycle,
+            kind: CallKind::Call,
+        }
+    }
+
+    #[test]
+    fn test_push_and_matching_return() {
+        let mut stack = CallStack::new();
+        stack.push(call(0x1000, 0x0104, 10));
+        stack.push(call(0x2000, 0x1008, 20));
+        assert_eq!(stack.depth(), 2);
+        assert_eq!(stack.current().unwrap().target, 0x2000);
+
+        let left = stack.pop(0x1008, CallKind::Call, 30).unwrap();
+        assert_eq!(left.target, 0x2000);
+        assert_eq!(stack.depth(), 1);
+    }
+
+    #[test]
+    fn test_return_to_outer_caller_unwinds() {
+        let mut stack = CallStack::new();
+        stack.enable_trace();
+        stack.push(call(0x1000, 0x0104, 10));
+        stack.push(call(0x2000, 0x1008, 20));
+
+        // Retour direct vers l'appelant de 0x1000
+        let left = stack.pop(0x0104, CallKind::Call, 30).unwrap();
+        assert_eq!(left.target, 0x1000);
+        assert_eq!(stack.depth(), 0);
+
+        let events = stack.take_events();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[2].kind, CallEventKind::Exit);
+        assert_eq!(events[2].function, 0x2000);
+        assert_eq!(events[2].depth, 1);
+        assert_eq!(events[3].function, 0x1000);
+        assert_eq!(events[3].cycle, 30);
+    }
+
+    #[test]
+    fn test_unmatched_return_is_counted() {
+        let mut stack = CallStack::new();
+        stack.push(call(0x1000, 0x0104, 10));
+
+        assert!(stack.pop(0x5000, CallKind::Call, 20).is_none());
+        assert!(stack.pop(0x0104, CallKind::Interrupt, 20).is_none());
+        assert_eq!(stack.unmatched_returns(), 2);
+        assert_eq!(stack.depth(), 1);
+    }
+
+    #[test]
+    fn test_depth_is_bounded() {
+        let mut stack = CallStack::new();
+        for i in 0..(MAX_CALL_DEPTH as u32 + 10) {
+            stack.push(call(0x1000 + i, 0x2000 + i, i as u64));
+        }
+        assert_eq!(stack.depth(), MAX_CALL_DEPTH);
+        assert_eq!(stack.frames()[0].target, 0x1000 + 10);
+    }
+}
@@ -3,7 +3,7 @@
 use super::instructions::*;
 use super::instruction_formats::*;
 use super::registers::*;
-use anyhow::{Result, anyhow};
+use crate::error::Result;
 
 /// Décode une instruction brute en instruction structurée
 pub fn decode_instruction(opcode: u32, address: u32) -> Result<DecodedInstruction> {
@@ -1,10 +1,13 @@
 //! Exécuteur d'instructions NEC V60
 
 use super::{NecV60, instructions::*, arithmetic::ArithmeticUnit, logical::LogicalUnit, 
-           floating_point::FloatingPointUnit, bit_manipulation::BitManipulationUnit, bcd::BcdUnit,
-           registers::ProcessorStatusWord};
+           floating_point::{FloatingPointUnit, FloatResult, FpuControl, FpuExceptions, TKCW_REGISTER}, bit_manipulation::BitManipulationUnit, bcd::BcdUnit,
+           bitfield::{BitField, BitFieldUnit},
+           registers::ProcessorStatusWord, call_stack::{CallFrame, CallKind},
+           string_operations::{StringState, StringUnit, STRING_STEP_ELEMENTS},
+           timing::{CpuTiming, BRANCH_TAKEN_PENALTY, fast_cycles, multiply_cycles, divide_cycles, string_element_cycles}};
 use crate::memory::MemoryInterface;
-use anyhow::{Result, anyhow};
+use crate::error::{CpuException, Result};
 
 /// Statistiques d'exécution
 #[derive(Debug, Default)]
@@ -40,8 +43,15 @@ impl NecV60 {
         }
         
         // Mise à jour des statistiques
+        let cycles = match self.timing {
+            CpuTiming::Accurate => instruction.cycles,
+            CpuTiming::Fast => fast_cycles(&instruction.instruction),
+        };
         self.stats.instructions_executed += 1;
-        self.stats.cycles_executed += instruction.cycles as u64;
+        self.stats.cycles_executed += cycles as u64;
+
+        // Part de la durée qui dépend des opérandes ou du branchement
+        let mut variable_cycles = 0;
         
         match &instruction.instruction {
             // Instructions arithmétiques
@@ -52,7 +62,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, arithmetic_result.value, memory)?;
                 arithmetic_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                 
                 // Exception si overflow signé OU carry (overflow non signé)
                 if arithmetic_result.overflow || arithmetic_result.carry {
@@ -67,7 +77,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, arithmetic_result.value, memory)?;
                 arithmetic_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                 
                 if arithmetic_result.overflow {
                     self.stats.exceptions_raised += 1;
@@ -78,10 +88,11 @@ impl NecV60 {
                 let val1 = self.read_operand(src1, memory)?;
                 let val2 = self.read_operand(src2, memory)?;
                 let arithmetic_result = ArithmeticUnit::mul(val1, val2);
+                variable_cycles = multiply_cycles(val2);
                 
                 self.write_operand(dest, arithmetic_result.value, memory)?;
                 arithmetic_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                 
                 if arithmetic_result.overflow {
                     self.stats.exceptions_raised += 1;
@@ -91,16 +102,17 @@ impl NecV60 {
             Instruction::Div { dest, src1, src2 } => {
                 let val1 = self.read_operand(src1, memory)?;
                 let val2 = self.read_operand(src2, memory)?;
+                variable_cycles = divide_cycles(val1);
                 
                 match ArithmeticUnit::div(val1, val2) {
                     Ok(arithmetic_result) => {
                         self.write_operand(dest, arithmetic_result.value, memory)?;
                         arithmetic_result.update_psw(&mut self.registers.psw);
-                        self.registers.pc += instruction.size;
+                        self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                     }
                     Err(_) => {
                         self.stats.exceptions_raised += 1;
-                        return Err(anyhow!("Division par zéro"));
+                        return Err(CpuException::DivisionByZero.into());
                     }
                 }
             },
@@ -113,7 +125,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, logical_result.value, memory)?;
                 logical_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::Or { dest, src1, src2 } => {
@@ -123,7 +135,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, logical_result.value, memory)?;
                 logical_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::Xor { dest, src1, src2 } => {
@@ -133,7 +145,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, logical_result.value, memory)?;
                 logical_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::Not { dest, src } => {
@@ -142,7 +154,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, logical_result.value, memory)?;
                 logical_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             // Instructions de décalage
@@ -153,7 +165,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, logical_result.value, memory)?;
                 logical_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::Shr { dest, src, shift } => {
@@ -163,42 +175,42 @@ impl NecV60 {
                 
                 self.write_operand(dest, logical_result.value, memory)?;
                 logical_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             // Instructions de transfert
             Instruction::Mov { dest, src } => {
                 let val = self.read_operand(src, memory)?;
                 self.write_operand(dest, val, memory)?;
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::Load { dest, address, size } => {
-                let addr = self.read_operand(address, memory)?;
+                let addr = self.effective_address(address);
                 let val = match size {
                     DataSize::Byte => memory.read_u8(addr)? as u32,
                     DataSize::Word => memory.read_u16(addr)? as u32,
                     DataSize::DWord => memory.read_u32(addr)?,
                 };
                 self.write_operand(dest, val, memory)?;
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                 self.stats.memory_accesses += 1;
             },
             
             Instruction::Store { src, address, size } => {
                 let val = self.read_operand(src, memory)?;
-                let addr = self.read_operand(address, memory)?;
+                let addr = self.effective_address(address);
                 match size {
                     DataSize::Byte => memory.write_u8(addr, val as u8)?,
                     DataSize::Word => memory.write_u16(addr, val as u16)?,
                     DataSize::DWord => memory.write_u32(addr, val)?,
                 };
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                 self.stats.memory_accesses += 1;
             },
             
             Instruction::Nop => {
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             // Instructions de branchement
@@ -213,79 +225,75 @@ impl NecV60 {
                     let target_addr = self.read_operand(target, memory)?;
                     self.registers.pc = target_addr;
                     self.stats.branches_taken += 1;
+                    variable_cycles = BRANCH_TAKEN_PENALTY;
                 } else {
-                    self.registers.pc += instruction.size;
+                    self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                 }
             },
             
+            Instruction::Call { target } => {
+                let target_addr = self.read_operand(target, memory)?;
+                let return_addr = self.registers.pc.wrapping_add(instruction.size);
+
+                self.registers.sp = self.registers.sp.wrapping_sub(4);
+                self.stats.memory_accesses += 1;
+                memory.write_u32(self.registers.sp, return_addr)?;
+
+                self.call_stack.push(CallFrame {
+                    target: target_addr,
+                    return_address: return_addr,
+                    stack_pointer: self.registers.sp,
+                    cycle: self.cycle_count,
+                    kind: CallKind::Call,
+                });
+                self.registers.pc = target_addr;
+                self.stats.branches_taken += 1;
+            },
+
+            Instruction::Return => {
+                self.stats.memory_accesses += 1;
+                let return_addr = memory.read_u32(self.registers.sp)?;
+                self.registers.sp = self.registers.sp.wrapping_add(4);
+
+                self.call_stack.pop(return_addr, CallKind::Call, self.cycle_count);
+                self.registers.pc = return_addr;
+                self.stats.branches_taken += 1;
+            },
+            
             // Instructions en virgule flottante
             Instruction::FloatAdd { dest, src1, src2 } => {
                 let val1 = self.read_operand(src1, memory)?;
                 let val2 = self.read_operand(src2, memory)?;
-                let float_result = FloatingPointUnit::add(val1, val2);
-                
-                self.write_operand(dest, float_result.to_u32(), memory)?;
-                float_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
-                
-                if float_result.overflow {
-                    self.stats.exceptions_raised += 1;
-                }
+                let float_result = FloatingPointUnit::add_with(val1, val2, &self.fpu_control());
+                self.complete_float(Some(dest), float_result, instruction.size, memory)?;
             },
             
             Instruction::FloatSub { dest, src1, src2 } => {
                 let val1 = self.read_operand(src1, memory)?;
                 let val2 = self.read_operand(src2, memory)?;
-                let float_result = FloatingPointUnit::sub(val1, val2);
-                
-                self.write_operand(dest, float_result.to_u32(), memory)?;
-                float_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
-                
-                if float_result.overflow {
-                    self.stats.exceptions_raised += 1;
-                }
+                let float_result = FloatingPointUnit::sub_with(val1, val2, &self.fpu_control());
+                self.complete_float(Some(dest), float_result, instruction.size, memory)?;
             },
             
             Instruction::FloatMul { dest, src1, src2 } => {
                 let val1 = self.read_operand(src1, memory)?;
                 let val2 = self.read_operand(src2, memory)?;
-                let float_result = FloatingPointUnit::mul(val1, val2);
-                
-                self.write_operand(dest, float_result.to_u32(), memory)?;
-                float_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
-                
-                if float_result.overflow {
-                    self.stats.exceptions_raised += 1;
-                }
+                let float_result = FloatingPointUnit::mul_with(val1, val2, &self.fpu_control());
+                self.complete_float(Some(dest), float_result, instruction.size, memory)?;
             },
             
             Instruction::FloatDiv { dest, src1, src2 } => {
                 let val1 = self.read_operand(src1, memory)?;
                 let val2 = self.read_operand(src2, memory)?;
-                let float_result = FloatingPointUnit::div(val1, val2);
-                
-                self.write_operand(dest, float_result.to_u32(), memory)?;
-                float_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
-                
-                if float_result.overflow || float_result.nan {
-                    self.stats.exceptions_raised += 1;
-                }
+                let float_result = FloatingPointUnit::div_with(val1, val2, &self.fpu_control());
+                self.complete_float(Some(dest), float_result, instruction.size, memory)?;
             },
             
             Instruction::FloatCompare { src1, src2 } => {
                 let val1 = self.read_operand(src1, memory)?;
                 let val2 = self.read_operand(src2, memory)?;
-                let float_result = FloatingPointUnit::compare(val1, val2);
-                
-                float_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
-                
-                if float_result.nan {
-                    self.stats.exceptions_raised += 1;
-                }
+                let float_result = FloatingPointUnit::compare_with(val1, val2, &self.fpu_control());
+                self.complete_float(None, float_result, instruction.size, memory)?;
             },
 
             // Instructions de manipulation de bits
@@ -296,7 +304,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, result, memory)?;
                 self.registers.psw.set(ProcessorStatusWord::ZERO, result == 0);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::RotateRight { dest, src, count } => {
@@ -306,7 +314,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, result, memory)?;
                 self.registers.psw.set(ProcessorStatusWord::ZERO, result == 0);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::BitTest { src, bit } => {
@@ -315,7 +323,7 @@ impl NecV60 {
                 let bit_result = BitManipulationUnit::test_bit(val, bit_pos);
                 
                 bit_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::BitSet { dest, bit } => {
@@ -325,7 +333,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, bit_result.value, memory)?;
                 bit_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::BitClear { dest, bit } => {
@@ -335,7 +343,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, bit_result.value, memory)?;
                 bit_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
 
             // Instructions BCD
@@ -346,7 +354,7 @@ impl NecV60 {
                 
                 self.write_operand(dest, bcd_result.value, memory)?;
                 bcd_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                 
                 if bcd_result.overflow {
                     self.stats.exceptions_raised += 1;
@@ -360,23 +368,80 @@ impl NecV60 {
                 
                 self.write_operand(dest, bcd_result.value, memory)?;
                 bcd_result.update_psw(&mut self.registers.psw);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
                 
                 if bcd_result.overflow {
                     self.stats.exceptions_raised += 1;
                 }
             },
             
+            // Instructions de champs de bits
+            Instruction::ExtractBitField { dest, base, offset, length, signed } => {
+                let field = self.bit_field(base, offset, length, memory)?;
+                self.stats.memory_accesses += 1;
+                let value = BitFieldUnit::extract(memory, &field, *signed)?;
+                
+                self.write_operand(dest, value, memory)?;
+                self.registers.psw.set_zero_flag(value == 0);
+                self.registers.psw.set_negative_flag((value as i32) < 0);
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
+            },
+            
+            Instruction::InsertBitField { src, base, offset, length } => {
+                let value = self.read_operand(src, memory)?;
+                let field = self.bit_field(base, offset, length, memory)?;
+                self.stats.memory_accesses += 2;
+                BitFieldUnit::insert(memory, &field, value)?;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
+            },
+            
+            Instruction::CompareBitField { src, base, offset, length, signed } => {
+                let value = self.read_operand(src, memory)?;
+                let field = self.bit_field(base, offset, length, memory)?;
+                self.stats.memory_accesses += 1;
+                
+                BitFieldUnit::compare(memory, &field, value, *signed)?.update_psw(&mut self.registers.psw);
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
+            },
+            
+            // Instructions de chaîne : une tranche par pas, reprise depuis
+            // les registres tant que l'instruction n'est pas terminée
+            Instruction::StringMove { size } | Instruction::StringCompare { size } |
+            Instruction::StringScan { size } | Instruction::StringFill { size } => {
+                let element_size = size.bytes() as u8;
+                let mut state = StringState::from_registers(&self.registers);
+                let result = match &instruction.instruction {
+                    Instruction::StringMove { .. } => StringUnit::move_step(memory, &mut state, element_size, None, STRING_STEP_ELEMENTS),
+                    Instruction::StringCompare { .. } => StringUnit::compare_step(memory, &mut state, element_size, None, STRING_STEP_ELEMENTS),
+                    Instruction::StringScan { .. } => StringUnit::scan_step(memory, &mut state, element_size, None, STRING_STEP_ELEMENTS),
+                    _ => StringUnit::fill_step(memory, &mut state, element_size, STRING_STEP_ELEMENTS),
+                };
+
+                // Progression sauvegardée même si un accès a échoué
+                state.store(&mut self.registers);
+                let string_result = result?;
+
+                let elements = string_result.bytes_processed / element_size as u32;
+                let accesses = if matches!(instruction.instruction, Instruction::StringMove { .. } | Instruction::StringCompare { .. }) { 2 } else { 1 };
+                self.stats.memory_accesses += (elements * accesses) as u64;
+                variable_cycles = elements.saturating_sub(1) * string_element_cycles(&instruction.instruction);
+
+                if string_result.completed {
+                    string_result.update_psw(&mut self.registers.psw);
+                    self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
+                }
+            },
+            
             Instruction::Halt => {
                 self.halted = true;
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             // Instructions d'interruption
             Instruction::SoftwareInterrupt { vector } => {
                 let interrupt = crate::cpu::Interrupt::External(*vector);
                 self.queue_interrupt(interrupt);
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::ReturnFromInterrupt => {
@@ -386,25 +451,88 @@ impl NecV60 {
             
             Instruction::EnableInterrupts => {
                 self.interrupts_enabled = true;
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::DisableInterrupts => {
                 self.interrupts_enabled = false;
-                self.registers.pc += instruction.size;
+                self.registers.pc = self.registers.pc.wrapping_add(instruction.size);
             },
             
             Instruction::Unknown { opcode } => {
-                return Err(anyhow!("Instruction inconnue: {:#08x} à l'adresse {:#08x}", 
-                                 opcode, instruction.address));
+                return Err(CpuException::UnknownOpcode { address: instruction.address, opcode: *opcode }.into());
             },
             
             _ => {
-                return Err(anyhow!("Instruction non implémentée: {:?}", instruction.instruction));
+                return Err(CpuException::Unimplemented {
+                    address: instruction.address,
+                    mnemonic: format!("{:?}", instruction.instruction),
+                }.into());
             }
         }
         
-        Ok(instruction.cycles)
+        if self.timing == CpuTiming::Fast {
+            return Ok(cycles);
+        }
+        self.stats.cycles_executed += variable_cycles as u64;
+        Ok(cycles + variable_cycles)
+    }
+
+    /// Adresse désignée par l'opérande d'un chargement ou d'un rangement :
+    /// le mot adressé n'est pas lu
+    fn effective_address(&self, operand: &Operand) -> u32 {
+        match operand {
+            Operand::Immediate(addr) | Operand::Direct(addr) => *addr,
+            Operand::Register(reg) | Operand::Indirect(reg) => self.registers.read_general(*reg),
+            Operand::IndirectOffset(reg, offset) => self.registers.read_general(*reg).wrapping_add(*offset as u32),
+            Operand::IndirectIndexed(base_reg, index_reg, scale) => {
+                let base = self.registers.read_general(*base_reg);
+                base.wrapping_add(self.registers.read_general(*index_reg).wrapping_mul(*scale))
+            },
+            Operand::PcRelative(offset) => self.registers.pc.wrapping_add(*offset as u32),
+        }
+    }
+
+    /// Réglages de l'unité flottante, lus dans le TKCW
+    fn fpu_control(&self) -> FpuControl {
+        FpuControl::from_tkcw(self.registers.read_control(TKCW_REGISTER))
+    }
+
+    /// Termine une instruction flottante : exceptions cumulées dans le TKCW,
+    /// indicateurs, puis résultat rangé dans `dest`. Une exception dont le
+    /// déroutement est activé laisse `dest` intact et se signale après
+    /// l'instruction, que le gestionnaire ne réexécute pas
+    fn complete_float<M>(&mut self, dest: Option<&Operand>, result: FloatResult, size: u32, memory: &mut M) -> Result<()>
+    where
+        M: MemoryInterface,
+    {
+        let mut control = self.fpu_control();
+        control.status |= result.exceptions;
+        self.registers.write_control(TKCW_REGISTER, control.to_tkcw());
+        result.update_psw(&mut self.registers.psw);
+        self.registers.pc = self.registers.pc.wrapping_add(size);
+
+        if !result.exceptions.difference(FpuExceptions::INEXACT).is_empty() {
+            self.stats.exceptions_raised += 1;
+        }
+        let trapped = result.exceptions & control.traps;
+        if !trapped.is_empty() {
+            return Err(CpuException::FloatingPoint(trapped).into());
+        }
+        if let Some(dest) = dest {
+            self.write_operand(dest, result.to_u32(), memory)?;
+        }
+        Ok(())
+    }
+
+    /// Champ de bits désigné par les opérandes d'une instruction de champ
+    fn bit_field<M>(&mut self, base: &Operand, offset: &Operand, length: &Operand, memory: &M) -> Result<BitField>
+    where
+        M: MemoryInterface,
+    {
+        let offset = self.read_operand(offset, memory)? as i32;
+        let length = self.read_operand(length, memory)?;
+        BitField::new(self.effective_address(base), offset, length)
     }
 
     /// Lit la valeur d'un opérande
@@ -426,19 +554,19 @@ impl NecV60 {
             },
             Operand::IndirectOffset(reg, offset) => {
                 let base = self.registers.read_general(*reg);
-                let addr = (base as i32 + offset) as u32;
+                let addr = base.wrapping_add(*offset as u32);
                 self.stats.memory_accesses += 1;
                 memory.read_u32(addr)
             },
             Operand::IndirectIndexed(base_reg, index_reg, scale) => {
                 let base = self.registers.read_general(*base_reg);
                 let index = self.registers.read_general(*index_reg);
-                let addr = base + (index * scale);
+                let addr = base.wrapping_add(index.wrapping_mul(*scale));
                 self.stats.memory_accesses += 1;
                 memory.read_u32(addr)
             },
             Operand::PcRelative(offset) => {
-                let addr = (self.registers.pc as i32 + offset) as u32;
+                let addr = self.registers.pc.wrapping_add(*offset as u32);
                 self.stats.memory_accesses += 1;
                 memory.read_u32(addr)
             },
@@ -466,18 +594,18 @@ impl NecV60 {
             },
             Operand::IndirectOffset(reg, offset) => {
                 let base = self.registers.read_general(*reg);
-                let addr = (base as i32 + offset) as u32;
+                let addr = base.wrapping_add(*offset as u32);
                 self.stats.memory_accesses += 1;
                 memory.write_u32(addr, value)
             },
             Operand::IndirectIndexed(base_reg, index_reg, scale) => {
                 let base = self.registers.read_general(*base_reg);
                 let index = self.registers.read_general(*index_reg);
-                let addr = base + (index * scale);
+                let addr = base.wrapping_add(index.wrapping_mul(*scale));
                 self.stats.memory_accesses += 1;
                 memory.write_u32(addr, value)
             },
-            _ => Err(anyhow!("Impossible d'écrire dans cet opérande")),
+            _ => Err(CpuException::InvalidDestination.into()),
         }
     }
 }
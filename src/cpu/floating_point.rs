@@ -1,7 +1,108 @@
 //! Unité de calcul en virgule flottante NEC V60
+//!
+//! Les opérations sont calculées en double précision, où l'addition, la
+//! soustraction, la multiplication et la division de deux flottants simple
+//! précision sont arrondies correctement au plus près ; l'erreur exacte de
+//! ce résultat intermédiaire permet ensuite d'arrondir dans les trois autres
+//! modes du V60. Le mode d'arrondi, les déroutements et la mise à zéro des
+//! dénormalisés se règlent dans le mot de contrôle de tâche (TKCW), qui
+//! cumule aussi les exceptions survenues ([`FpuControl`]).
+
+use bitflags::bitflags;
 
 use super::registers::ProcessorStatusWord;
 
+/// Registre de contrôle qui porte le mot de contrôle de tâche (TKCW)
+pub const TKCW_REGISTER: usize = 8;
+
+/// Champ du mode d'arrondi dans le TKCW (bits 5-6)
+const TKCW_ROUNDING_SHIFT: u32 = 5;
+
+/// Mise à zéro des dénormalisés (bit 7 du TKCW)
+const TKCW_FLUSH_DENORMALS: u32 = 1 << 7;
+
+/// Exceptions cumulées (bits 8-12 du TKCW) ; les bits 0-4 activent les
+/// déroutements correspondants
+const TKCW_STATUS_SHIFT: u32 = 8;
+
+bitflags! {
+    /// Exceptions de l'unité flottante
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct FpuExceptions: u32 {
+        /// Opération invalide (0/0, ∞-∞, NaN signalant...)
+        const INVALID = 1 << 0;
+
+        /// Division d'un nombre fini non nul par zéro
+        const DIVIDE_BY_ZERO = 1 << 1;
+
+        /// Résultat trop grand pour la simple précision
+        const OVERFLOW = 1 << 2;
+
+        /// Résultat inexact trop petit pour être normalisé
+        const UNDERFLOW = 1 << 3;
+
+        /// Résultat arrondi
+        const INEXACT = 1 << 4;
+    }
+}
+
+/// Mode d'arrondi
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Au plus près, à égalité vers le pair
+    #[default]
+    Nearest,
+    TowardZero,
+    /// Vers +∞
+    Up,
+    /// Vers -∞
+    Down,
+}
+
+/// Réglages et exceptions cumulées de l'unité flottante
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FpuControl {
+    pub rounding: RoundingMode,
+
+    /// Exceptions déroutées vers le vecteur de l'unité flottante
+    pub traps: FpuExceptions,
+
+    /// Les dénormalisés, en entrée comme en résultat, valent zéro
+    pub flush_denormals: bool,
+
+    /// Exceptions survenues depuis la dernière remise à zéro du TKCW
+    pub status: FpuExceptions,
+}
+
+impl FpuControl {
+    /// Réglages lus dans le TKCW
+    pub fn from_tkcw(word: u32) -> Self {
+        Self {
+            rounding: match (word >> TKCW_ROUNDING_SHIFT) & 3 {
+                0 => RoundingMode::Nearest,
+                1 => RoundingMode::TowardZero,
+                2 => RoundingMode::Up,
+                _ => RoundingMode::Down,
+            },
+            traps: FpuExceptions::from_bits_truncate(word),
+            flush_denormals: word & TKCW_FLUSH_DENORMALS != 0,
+            status: FpuExceptions::from_bits_truncate(word >> TKCW_STATUS_SHIFT),
+        }
+    }
+
+    /// Mot TKCW correspondant
+    pub fn to_tkcw(self) -> u32 {
+        let rounding = match self.rounding {
+            RoundingMode::Nearest => 0,
+            RoundingMode::TowardZero => 1,
+            RoundingMode::Up => 2,
+            RoundingMode::Down => 3,
+        };
+        let flush = if self.flush_denormals { TKCW_FLUSH_DENORMALS } else { 0 };
+        self.traps.bits() | rounding << TKCW_ROUNDING_SHIFT | flush | self.status.bits() << TKCW_STATUS_SHIFT
+    }
+}
+
 /// Résultat d'une opération en virgule flottante
 #[derive(Debug)]
 pub struct FloatResult {
@@ -11,12 +112,28 @@ pub struct FloatResult {
     pub zero: bool,
     pub nan: bool,
     pub infinite: bool,
+
+    /// Exceptions levées par l'opération
+    pub exceptions: FpuExceptions,
 }
 
 impl FloatResult {
+    fn new(value: f32, exceptions: FpuExceptions) -> Self {
+        Self {
+            value,
+            overflow: exceptions.intersects(FpuExceptions::OVERFLOW | FpuExceptions::DIVIDE_BY_ZERO),
+            underflow: exceptions.contains(FpuExceptions::UNDERFLOW),
+            zero: value == 0.0,
+            nan: value.is_nan(),
+            infinite: value.is_infinite(),
+            exceptions,
+        }
+    }
+
     /// Met à jour le mot d'état du processeur avec les flags appropriés
     pub fn update_psw(&self, psw: &mut ProcessorStatusWord) {
         psw.set(ProcessorStatusWord::ZERO, self.zero);
+        psw.set(ProcessorStatusWord::SIGN, self.value < 0.0);
         psw.set(ProcessorStatusWord::OVERFLOW, self.overflow);
         psw.set(ProcessorStatusWord::CARRY, self.underflow);
         
@@ -34,114 +151,153 @@ impl FloatResult {
     }
 }
 
+/// NaN signalant : bit de poids fort de la mantisse à 0
+fn is_signaling(bits: u32) -> bool {
+    f32::from_bits(bits).is_nan() && bits & 0x0040_0000 == 0
+}
+
 /// Unité de calcul en virgule flottante
 pub struct FloatingPointUnit;
 
 impl FloatingPointUnit {
-    /// Addition en virgule flottante
-    pub fn add(a_bits: u32, b_bits: u32) -> FloatResult {
-        let a = f32::from_bits(a_bits);
-        let b = f32::from_bits(b_bits);
-        
-        let result = a + b;
-        
-        FloatResult {
-            value: result,
-            overflow: result.is_infinite() && a.is_finite() && b.is_finite(),
-            underflow: result == 0.0 && (a != 0.0 || b != 0.0),
-            zero: result == 0.0,
-            nan: result.is_nan(),
-            infinite: result.is_infinite(),
+    /// Opérande lu selon les réglages : dénormalisé mis à zéro au besoin
+    fn operand(bits: u32, control: &FpuControl) -> f32 {
+        let value = f32::from_bits(bits);
+        if control.flush_denormals && value.is_subnormal() {
+            0.0f32.copysign(value)
+        } else {
+            value
         }
     }
 
-    /// Soustraction en virgule flottante
-    pub fn sub(a_bits: u32, b_bits: u32) -> FloatResult {
-        let a = f32::from_bits(a_bits);
-        let b = f32::from_bits(b_bits);
-        
-        let result = a - b;
-        
-        FloatResult {
-            value: result,
-            overflow: result.is_infinite() && a.is_finite() && b.is_finite(),
-            underflow: result == 0.0 && (a != 0.0 || b != 0.0),
-            zero: result == 0.0,
-            nan: result.is_nan(),
-            infinite: result.is_infinite(),
+    /// Arrondit `exact + error` dans le mode demandé, à partir de l'arrondi
+    /// au plus près de `exact`
+    fn round(exact: f64, error: f64, rounding: RoundingMode) -> f32 {
+        let nearest = exact as f32;
+        let above = nearest as f64 > exact || (nearest as f64 == exact && error < 0.0);
+        let below = (nearest as f64) < exact || (nearest as f64 == exact && error > 0.0);
+        match rounding {
+            RoundingMode::Up if below => nearest.next_up(),
+            RoundingMode::Down if above => nearest.next_down(),
+            RoundingMode::TowardZero if nearest > 0.0 && above => nearest.next_down(),
+            RoundingMode::TowardZero if nearest < 0.0 && below => nearest.next_up(),
+            _ => nearest,
         }
     }
 
-    /// Multiplication en virgule flottante
-    pub fn mul(a_bits: u32, b_bits: u32) -> FloatResult {
-        let a = f32::from_bits(a_bits);
-        let b = f32::from_bits(b_bits);
-        
-        let result = a * b;
-        
-        FloatResult {
-            value: result,
-            overflow: result.is_infinite() && a.is_finite() && b.is_finite(),
-            underflow: result == 0.0 && (a != 0.0 && b != 0.0),
-            zero: result == 0.0,
-            nan: result.is_nan(),
-            infinite: result.is_infinite(),
+    /// Applique `operation` (résultat double précision et son erreur
+    /// exacte) et relève les exceptions
+    fn operate(a_bits: u32, b_bits: u32, control: &FpuControl, operation: impl Fn(f64, f64) -> (f64, f64)) -> FloatResult {
+        let a = Self::operand(a_bits, control);
+        let b = Self::operand(b_bits, control);
+        let mut exceptions = FpuExceptions::empty();
+        if is_signaling(a_bits) || is_signaling(b_bits) {
+            exceptions |= FpuExceptions::INVALID;
         }
+
+        let (exact, error) = operation(a as f64, b as f64);
+        let mut value = Self::round(exact, error, control.rounding);
+        if value.is_nan() {
+            if !a.is_nan() && !b.is_nan() {
+                exceptions |= FpuExceptions::INVALID;
+            }
+            return FloatResult::new(value, exceptions);
+        }
+
+        if a.is_finite() && b.is_finite() {
+            if value.is_infinite() || (exact as f32).is_infinite() {
+                exceptions |= FpuExceptions::OVERFLOW;
+            }
+            if value as f64 != exact || error != 0.0 {
+                exceptions |= FpuExceptions::INEXACT;
+                if exact != 0.0 && exact.abs() < f32::MIN_POSITIVE as f64 {
+                    exceptions |= FpuExceptions::UNDERFLOW;
+                }
+            }
+            if control.flush_denormals && value.is_subnormal() {
+                value = 0.0f32.copysign(value);
+                exceptions |= FpuExceptions::UNDERFLOW | FpuExceptions::INEXACT;
+            }
+        }
+        FloatResult::new(value, exceptions)
+    }
+
+    /// Addition en virgule flottante, arrondie au plus près
+    pub fn add(a_bits: u32, b_bits: u32) -> FloatResult {
+        Self::add_with(a_bits, b_bits, &FpuControl::default())
+    }
+
+    /// Addition en virgule flottante selon les réglages de `control`
+    pub fn add_with(a_bits: u32, b_bits: u32, control: &FpuControl) -> FloatResult {
+        Self::operate(a_bits, b_bits, control, |a, b| {
+            // Erreur exacte de la somme (algorithme TwoSum)
+            let sum = a + b;
+            let b_part = sum - a;
+            (sum, (a - (sum - b_part)) + (b - b_part))
+        })
+    }
+
+    /// Soustraction en virgule flottante, arrondie au plus près
+    pub fn sub(a_bits: u32, b_bits: u32) -> FloatResult {
+        Self::sub_with(a_bits, b_bits, &FpuControl::default())
+    }
+
+    /// Soustraction en virgule flottante selon les réglages de `control`
+    pub fn sub_with(a_bits: u32, b_bits: u32, control: &FpuControl) -> FloatResult {
+        Self::add_with(a_bits, b_bits ^ 0x8000_0000, control)
+    }
+
+    /// Multiplication en virgule flottante, arrondie au plus près
+    pub fn mul(a_bits: u32, b_bits: u32) -> FloatResult {
+        Self::mul_with(a_bits, b_bits, &FpuControl::default())
     }
 
-    /// Division en virgule flottante
+    /// Multiplication en virgule flottante selon les réglages de `control` ;
+    /// le produit de deux mantisses de 24 bits est exact en double précision
+    pub fn mul_with(a_bits: u32, b_bits: u32, control: &FpuControl) -> FloatResult {
+        Self::operate(a_bits, b_bits, control, |a, b| (a * b, 0.0))
+    }
+
+    /// Division en virgule flottante, arrondie au plus près
     pub fn div(a_bits: u32, b_bits: u32) -> FloatResult {
-        let a = f32::from_bits(a_bits);
-        let b = f32::from_bits(b_bits);
-        
-        if b == 0.0 && a != 0.0 {
-            return FloatResult {
-                value: if a > 0.0 { f32::INFINITY } else { f32::NEG_INFINITY },
-                overflow: true,
-                underflow: false,
-                zero: false,
-                nan: false,
-                infinite: true,
-            };
-        }
-        
-        let result = a / b;
-        
-        FloatResult {
-            value: result,
-            overflow: result.is_infinite() && a.is_finite() && b.is_finite(),
-            underflow: result == 0.0 && a != 0.0 && b.is_finite(),
-            zero: result == 0.0,
-            nan: result.is_nan(),
-            infinite: result.is_infinite(),
+        Self::div_with(a_bits, b_bits, &FpuControl::default())
+    }
+
+    /// Division en virgule flottante selon les réglages de `control`
+    pub fn div_with(a_bits: u32, b_bits: u32, control: &FpuControl) -> FloatResult {
+        let a = Self::operand(a_bits, control);
+        let b = Self::operand(b_bits, control);
+        if b == 0.0 && a != 0.0 && a.is_finite() {
+            let value = if a.is_sign_negative() != b.is_sign_negative() { f32::NEG_INFINITY } else { f32::INFINITY };
+            return FloatResult::new(value, FpuExceptions::DIVIDE_BY_ZERO);
         }
+
+        Self::operate(a_bits, b_bits, control, |a, b| {
+            // Le reste exact donne le signe de l'erreur du quotient
+            let quotient = a / b;
+            let remainder = (-quotient).mul_add(b, a);
+            (quotient, remainder / b)
+        })
     }
 
     /// Comparaison en virgule flottante
     pub fn compare(a_bits: u32, b_bits: u32) -> FloatResult {
-        let a = f32::from_bits(a_bits);
-        let b = f32::from_bits(b_bits);
+        Self::compare_with(a_bits, b_bits, &FpuControl::default())
+    }
+
+    /// Comparaison en virgule flottante : valeur -1, 0 ou 1 selon l'ordre,
+    /// opération invalide si un opérande est NaN
+    pub fn compare_with(a_bits: u32, b_bits: u32, control: &FpuControl) -> FloatResult {
+        let a = Self::operand(a_bits, control);
+        let b = Self::operand(b_bits, control);
         
         // Si l'un des nombres est NaN, le résultat est indéterminé
         if a.is_nan() || b.is_nan() {
-            return FloatResult {
-                value: 0.0,
-                overflow: false,
-                underflow: false,
-                zero: false,
-                nan: true,
-                infinite: false,
-            };
-        }
-        
-        FloatResult {
-            value: 0.0, // Les comparaisons ne retournent pas de valeur
-            overflow: false,
-            underflow: false,
-            zero: a == b,
-            nan: false,
-            infinite: false,
+            return FloatResult::new(f32::NAN, FpuExceptions::INVALID);
         }
+
+        let order = if a < b { -1.0 } else if a > b { 1.0 } else { 0.0 };
+        FloatResult::new(order, FpuExceptions::empty())
     }
 }
 
@@ -180,4 +336,57 @@ mod tests {
         assert!(result.zero); // Égaux
         assert!(!result.nan);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_rounding_modes_and_exception_flags() {
+        let third = |rounding| {
+            let control = FpuControl { rounding, ..FpuControl::default() };
+            FloatingPointUnit::div_with(1.0f32.to_bits(), 3.0f32.to_bits(), &control)
+        };
+        let nearest = third(RoundingMode::Nearest);
+        assert_eq!(nearest.value, 1.0 / 3.0);
+        assert_eq!(nearest.exceptions, FpuExceptions::INEXACT);
+        // Le plus proche de 1/3 est au-dessus
+        assert_eq!(third(RoundingMode::Up).value, nearest.value);
+        assert_eq!(third(RoundingMode::Down).value, nearest.value.next_down());
+        assert_eq!(third(RoundingMode::TowardZero).value, nearest.value.next_down());
+
+        // Erreur hors de portée de la double précision : 1 + 2^-60
+        let tiny = 2.0f32.powi(-60).to_bits();
+        let up = FpuControl { rounding: RoundingMode::Up, ..FpuControl::default() };
+        assert_eq!(FloatingPointUnit::add_with(1.0f32.to_bits(), tiny, &up).value, 1.0f32.next_up());
+        assert_eq!(FloatingPointUnit::sub_with(1.0f32.to_bits(), tiny, &up).value, 1.0);
+        assert_eq!(FloatingPointUnit::add(1.0f32.to_bits(), 2.0f32.to_bits()).exceptions, FpuExceptions::empty());
+
+        // Débordement : infini au plus près, plus grand fini vers zéro
+        let max = f32::MAX.to_bits();
+        let overflow = FloatingPointUnit::mul(max, 2.0f32.to_bits());
+        assert!(overflow.infinite && overflow.exceptions.contains(FpuExceptions::OVERFLOW | FpuExceptions::INEXACT));
+        let toward_zero = FpuControl { rounding: RoundingMode::TowardZero, ..FpuControl::default() };
+        assert_eq!(FloatingPointUnit::mul_with(max, 2.0f32.to_bits(), &toward_zero).value, f32::MAX);
+
+        // Dénormalisés : conservés par défaut, mis à zéro sur demande
+        let small = (f32::MIN_POSITIVE * 0.75).to_bits();
+        let gradual = FloatingPointUnit::mul(small, 0.5f32.to_bits());
+        assert!(gradual.value.is_subnormal() && gradual.exceptions.is_empty());
+        let flush = FpuControl { flush_denormals: true, ..FpuControl::default() };
+        let flushed = FloatingPointUnit::add_with(small, 0, &flush);
+        assert_eq!(flushed.value, 0.0);
+        assert!(flushed.zero);
+        let result = FloatingPointUnit::mul_with(f32::MIN_POSITIVE.to_bits(), 0.5f32.to_bits(), &flush);
+        assert_eq!(result.exceptions, FpuExceptions::UNDERFLOW | FpuExceptions::INEXACT);
+
+        let invalid = FloatingPointUnit::sub(f32::INFINITY.to_bits(), f32::INFINITY.to_bits());
+        assert!(invalid.nan && invalid.exceptions == FpuExceptions::INVALID);
+        assert_eq!(FloatingPointUnit::div(1.0f32.to_bits(), 0).exceptions, FpuExceptions::DIVIDE_BY_ZERO);
+
+        // Le TKCW garde les réglages et les exceptions cumulées
+        let control = FpuControl {
+            rounding: RoundingMode::Down,
+            traps: FpuExceptions::DIVIDE_BY_ZERO,
+            flush_denormals: true,
+            status: FpuExceptions::INEXACT,
+        };
+        assert_eq!(FpuControl::from_tkcw(control.to_tkcw()), control);
+    }
+}
@@ -0,0 +1,191 @@
+//! Émulation haut niveau (HLE) du micrologiciel de la carte
+//!
+//! Certaines routines de démarrage (tests de RAM, vérifications de sécurité,
+//! attentes de périphériques) sont fournies par des ROM système pas toujours
+//! extraites. Quand le PC atteint une adresse de la table du jeu, la routine
+//! n'est pas exécutée : son effet est simulé (valeur de retour, saut) et
+//! chaque appel est journalisé, pour que le jeu atteigne sa boucle principale.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::call_stack::CallKind;
+use super::NecV60;
+use crate::memory::MemoryInterface;
+
+/// Cycles comptés pour une routine remplacée
+pub const HLE_CALL_CYCLES: u32 = 10;
+
+/// Effet simulé d'une routine du micrologiciel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HleAction {
+    /// Retour immédiat à l'appelant avec cette valeur dans r0 (test réussi)
+    Return(u32),
+    /// Saut par-dessus autant d'octets (boucle d'attente, contrôle en ligne)
+    Skip(u32),
+    /// Branchement vers une autre adresse
+    Jump(u32),
+}
+
+/// Point d'entrée intercepté
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HleEntry {
+    /// Nom de la routine, pour le journal
+    pub name: String,
+    pub address: u32,
+    pub action: HleAction,
+
+    /// ROM système qui contient la routine : l'entrée n'est appliquée que si
+    /// elle n'a pas été chargée (sans ROM indiquée, toujours)
+    #[serde(default)]
+    pub rom: Option<String>,
+}
+
+impl HleEntry {
+    /// Vrai si la routine doit être simulée, d'après les ROMs chargées
+    pub fn applies(&self, is_loaded: impl Fn(&str) -> bool) -> bool {
+        self.rom.as_deref().is_none_or(|rom| !is_loaded(rom))
+    }
+}
+
+/// Table des routines remplacées et compteurs d'appels
+#[derive(Debug, Clone, Default)]
+pub struct FirmwareHle {
+    entries: BTreeMap<u32, HleEntry>,
+    calls: BTreeMap<u32, u64>,
+}
+
+impl FirmwareHle {
+    pub fn new(entries: impl IntoIterator<Item = HleEntry>) -> Self {
+        Self {
+            entries: entries.into_iter().map(|entry| (entry.address, entry)).collect(),
+            calls: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Routine remplacée à cette adresse
+    pub fn entry(&self, address: u32) -> Option<&HleEntry> {
+        self.entries.get(&address)
+    }
+
+    /// Nombre d'appels interceptés d'une routine
+    pub fn calls(&self, address: u32) -> u64 {
+        self.calls.get(&address).copied().unwrap_or(0)
+    }
+
+    /// Résumé des appels, pour le rapport de fin de session
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        for (address, entry) in &self.entries {
+            report.push_str(&format!("  {:<24} 0x{:08X}  {} appel(s)\n", entry.name, address, self.calls(*address)));
+        }
+        report
+    }
+}
+
+impl NecV60 {
+    /// Active le remplacement des routines du micrologiciel
+    pub fn enable_hle(&mut self, hle: FirmwareHle) {
+        self.hle = Some(hle);
+    }
+
+    /// Simule la routine du micrologiciel à l'adresse du PC, s'il y en a
+    /// une ; retourne les cycles comptés
+    pub(crate) fn intercept_firmware_call<M>(&mut self, memory: &mut M) -> Result<Option<u32>>
+    where
+        M: MemoryInterface,
+    {
+        let pc = self.registers.pc;
+        let Some(hle) = self.hle.as_mut() else {
+            return Ok(None);
+        };
+        let Some(entry) = hle.entries.get(&pc) else {
+            return Ok(None);
+        };
+
+        let count = hle.calls.entry(pc).or_insert(0);
+        *count += 1;
+        let (name, action, count) = (entry.name.clone(), entry.action, *count);
+        if count == 1 {
+            log::info!("HLE: {} (0x{:08X}) remplacé par {:?}", name, pc, action);
+        } else {
+            log::debug!("HLE: {} (0x{:08X}) appel n°{}", name, pc, count);
+        }
+
+        match action {
+            HleAction::Return(value) => {
+                self.registers.set_gpr(0, value);
+                let return_address = memory.read_u32(self.registers.sp)?;
+                self.registers.sp = self.registers.sp.wrapping_add(4);
+                self.call_stack.pop(return_address, CallKind::Call, self.cycle_count);
+                self.registers.pc = return_address;
+            }
+            HleAction::Skip(bytes) => self.registers.pc = pc.wrapping_add(bytes),
+            HleAction::Jump(target) => self.registers.pc = target,
+        }
+        Ok(Some(HLE_CALL_CYCLES))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::Model2Memory;
+
+    fn entry(name: &str, address: u32, action: HleAction) -> HleEntry {
+        HleEntry { name: name.to_string(), address, action, rom: None }
+    }
+
+    #[test]
+    fn test_intercepted_routine_returns_to_caller() {
+        let mut memory = Model2Memory::new();
+        let mut cpu = NecV60::new();
+        cpu.enable_hle(FirmwareHle::new([
+            entry("ram_test", 0x2000, HleAction::Return(0)),
+            entry("wait_security", 0x3000, HleAction::Skip(6)),
+        ]));
+
+        // Appel simulé : adresse de retour au sommet de la pile
+        cpu.registers.sp = 0x1000;
+        memory.write_u32(0x1000, 0x0123_4568).unwrap();
+        cpu.registers.set_gpr(0, 0xDEAD);
+        cpu.registers.pc = 0x2000;
+
+        assert_eq!(cpu.step(&mut memory).unwrap(), HLE_CALL_CYCLES);
+        assert_eq!(cpu.registers.pc, 0x0123_4568);
+        assert_eq!(cpu.registers.sp, 0x1004);
+        assert_eq!(cpu.registers.get_gpr(0), 0);
+
+        cpu.registers.pc = 0x3000;
+        cpu.step(&mut memory).unwrap();
+        assert_eq!(cpu.registers.pc, 0x3006);
+
+        let hle = cpu.hle.as_ref().unwrap();
+        assert_eq!((hle.calls(0x2000), hle.calls(0x3000), hle.calls(0x4000)), (1, 1, 0));
+        assert!(hle.report().contains("ram_test"));
+    }
+
+    #[test]
+    fn test_table_from_json() {
+        let json = r#"[{"name": "checksum", "address": 4096, "action": {"jump": 8192}}]"#;
+        let entries: Vec<HleEntry> = serde_json::from_str(json).unwrap();
+        let hle = FirmwareHle::new(entries);
+        assert_eq!(hle.entry(0x1000).unwrap().action, HleAction::Jump(0x2000));
+        assert!(hle.entry(0x2000).is_none());
+    }
+
+    #[test]
+    fn test_entry_applies_only_without_its_system_rom() {
+        let json = r#"{"name": "ram_test", "address": 8192, "action": {"return": 0}, "rom": "epr-system.ic1"}"#;
+        let gated: HleEntry = serde_json::from_str(json).unwrap();
+        assert!(gated.applies(|_| false));
+        assert!(!gated.applies(|rom| rom == "epr-system.ic1"));
+        assert!(entry("wait_security", 0x3000, HleAction::Skip(6)).applies(|_| true));
+    }
+}
@@ -4,7 +4,8 @@
 
 use super::instructions::*;
 use super::registers::ConditionCode;
-use anyhow::{Result, anyhow};
+use crate::error::{CpuException, Result};
+use crate::memory::ByteOrder;
 
 /// Formats d'instructions NEC V60
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -67,6 +68,18 @@ pub enum InstructionFormat {
     },
 }
 
+impl InstructionFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstructionFormat::Format1 { .. } => "Format1",
+            InstructionFormat::Format2 { .. } => "Format2",
+            InstructionFormat::Format3 { .. } => "Format3",
+            InstructionFormat::Format4 { .. } => "Format4",
+            InstructionFormat::Format5 { .. } => "Format5",
+        }
+    }
+}
+
 /// Décodeur d'instructions amélioré pour le NEC V60
 #[derive(Debug)]
 pub struct V60InstructionDecoder {
@@ -89,15 +102,7 @@ impl V60InstructionDecoder {
             return Ok(cached.clone());
         }
 
-        if data.len() < 2 {
-            return Err(anyhow!("Données insuffisantes pour décoder l'instruction"));
-        }
-
-        // Lire les premiers 16 bits pour déterminer le format
-        let first_word = u16::from_le_bytes([data[0], data[1]]);
-        let opcode = ((first_word >> 10) & 0x3F) as u8;
-
-        let format = self.determine_format(opcode, first_word, data)?;
+        let format = self.format(data, address)?;
         let instruction = self.decode_format(&format)?;
         let size = self.calculate_instruction_size(&format);
 
@@ -109,8 +114,18 @@ impl V60InstructionDecoder {
         Ok(decoded)
     }
 
+    /// Format de l'instruction qui commence `data`, sans la décoder
+    pub fn format(&self, data: &[u8], address: u32) -> Result<InstructionFormat> {
+        // Lire les premiers 16 bits pour déterminer le format
+        let first_word = ByteOrder::Little.read_u16(data, 0)
+            .ok_or(CpuException::TruncatedInstruction { address, available: data.len() })?;
+        let opcode = ((first_word >> 10) & 0x3F) as u8;
+
+        self.determine_format(opcode, first_word, data, address)
+    }
+
     /// Détermine le format de l'instruction
-    fn determine_format(&self, opcode: u8, first_word: u16, data: &[u8]) -> Result<InstructionFormat> {
+    fn determine_format(&self, opcode: u8, first_word: u16, data: &[u8], address: u32) -> Result<InstructionFormat> {
         match opcode {
             // Instructions Format 1 (16 bits) - opérations basiques
             0x00..=0x0F => {
@@ -126,12 +141,10 @@ impl V60InstructionDecoder {
 
             // Instructions Format 2 (32 bits) - avec immédiat
             0x10..=0x1F => {
-                if data.len() < 4 {
-                    return Err(anyhow!("Données insuffisantes pour Format 2"));
-                }
                 let r2 = ((first_word >> 5) & 0x1F) as u8;
                 let r1 = (first_word & 0x1F) as u8;
-                let immediate = u16::from_le_bytes([data[2], data[3]]);
+                let immediate = ByteOrder::Little.read_u16(data, 2)
+                    .ok_or(CpuException::TruncatedInstruction { address, available: data.len() })?;
                 Ok(InstructionFormat::Format2 {
                     opcode,
                     r2,
@@ -143,12 +156,10 @@ impl V60InstructionDecoder {
 
             // Instructions Format 3 (48 bits) - avec déplacement
             0x20..=0x2F => {
-                if data.len() < 6 {
-                    return Err(anyhow!("Données insuffisantes pour Format 3"));
-                }
                 let r2 = ((first_word >> 5) & 0x1F) as u8;
                 let r1 = (first_word & 0x1F) as u8;
-                let displacement = u32::from_le_bytes([data[2], data[3], data[4], data[5]]);
+                let displacement = ByteOrder::Little.read_u32(data, 2)
+                    .ok_or(CpuException::TruncatedInstruction { address, available: data.len() })?;
                 Ok(InstructionFormat::Format3 {
                     opcode,
                     r2,
@@ -161,10 +172,10 @@ impl V60InstructionDecoder {
             // Instructions Format 4 (32 bits) - branchements
             0x30..=0x3F => {
                 if data.len() < 4 {
-                    return Err(anyhow!("Données insuffisantes pour Format 4"));
+                    return Err(CpuException::TruncatedInstruction { address, available: data.len() }.into());
                 }
                 let condition = ((first_word >> 5) & 0x1F) as u8;
-                let displacement = i32::from_le_bytes([data[1] as i8 as i32 as u8, data[2], data[3], 0]);
+                let displacement = ByteOrder::Little.read_uint(&data[1..4]) as i32;
                 Ok(InstructionFormat::Format4 {
                     opcode,
                     condition,
@@ -183,7 +194,7 @@ impl V60InstructionDecoder {
                 })
             },
 
-            _ => Err(anyhow!("Opcode inconnu: 0x{:02X}", opcode)),
+            _ => Err(CpuException::UnknownOpcode { address, opcode: opcode as u32 }.into()),
         }
     }
 
@@ -221,6 +232,23 @@ impl V60InstructionDecoder {
             0x04 => Ok(Instruction::Or { dest: dest.clone(), src1: dest, src2: src }),
             0x05 => Ok(Instruction::Xor { dest: dest.clone(), src1: dest, src2: src }),
             0x06 => Ok(Instruction::Compare { src1: dest, src2: src }),
+
+            // Chaînes : opérandes dans les registres de chaîne, taille des
+            // éléments dans r1 (0 octet, 1 demi-mot, 2 mot)
+            0x07..=0x0A => {
+                let size = match r1 {
+                    0 => DataSize::Byte,
+                    1 => DataSize::Word,
+                    2 => DataSize::DWord,
+                    _ => return Ok(Instruction::Unknown { opcode: (opcode as u32) << 16 | (r2 as u32) << 8 | r1 as u32 }),
+                };
+                Ok(match opcode {
+                    0x07 => Instruction::StringMove { size },
+                    0x08 => Instruction::StringCompare { size },
+                    0x09 => Instruction::StringScan { size },
+                    _ => Instruction::StringFill { size },
+                })
+            },
             _ => Ok(Instruction::Unknown { opcode: (opcode as u32) << 16 | (r2 as u32) << 8 | r1 as u32 }),
         }
     }
@@ -239,6 +267,21 @@ impl V60InstructionDecoder {
             0x14 => Ok(Instruction::Or { dest: dest.clone(), src1: dest, src2: imm }),
             0x15 => Ok(Instruction::Xor { dest: dest.clone(), src1: dest, src2: imm }),
             0x16 => Ok(Instruction::Compare { src1: dest, src2: imm }),
+
+            // Champs de bits : r2 valeur, r1 base, immédiat = décalage en
+            // bits (bits 0-10) et longueur - 1 (bits 11-15)
+            0x17..=0x1B => {
+                let base = Operand::Indirect(r1 as usize);
+                let offset = Operand::Immediate((immediate & 0x7FF) as u32);
+                let length = Operand::Immediate((immediate >> 11) as u32 + 1);
+                Ok(match opcode {
+                    0x17 => Instruction::ExtractBitField { dest, base, offset, length, signed: false },
+                    0x18 => Instruction::ExtractBitField { dest, base, offset, length, signed: true },
+                    0x19 => Instruction::InsertBitField { src: dest, base, offset, length },
+                    0x1A => Instruction::CompareBitField { src: dest, base, offset, length, signed: false },
+                    _ => Instruction::CompareBitField { src: dest, base, offset, length, signed: true },
+                })
+            },
             _ => Ok(Instruction::Unknown { opcode: (opcode as u32) << 24 | (r2 as u32) << 16 | (r1 as u32) << 8 | immediate as u32 }),
         }
     }
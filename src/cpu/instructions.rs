@@ -55,17 +55,26 @@ pub enum Instruction {
     BitSet { dest: Operand, bit: Operand },
     BitClear { dest: Operand, bit: Operand },
     BitScan { dest: Operand, src: Operand },
+
+    // Instructions de champs de bits : champ de `length` bits à `offset`
+    // bits de l'adresse désignée par `base`
+    ExtractBitField { dest: Operand, base: Operand, offset: Operand, length: Operand, signed: bool },
+    InsertBitField { src: Operand, base: Operand, offset: Operand, length: Operand },
+    CompareBitField { src: Operand, base: Operand, offset: Operand, length: Operand, signed: bool },
     
     // Instructions de pile
     Push { src: Operand },
     Pop { dest: Operand },
-    PushMultiple { registers: Vec<usize> },
-    PopMultiple { registers: Vec<usize> },
+    /// Liste de registres en masque (bit n = Rn), comme dans l'encodage
+    /// PUSHM/POPM : l'instruction décodée se copie sans allocation
+    PushMultiple { registers: u32 },
+    PopMultiple { registers: u32 },
     
     // Instructions de chaîne (string operations)
     StringMove { size: DataSize },
     StringCompare { size: DataSize },
     StringScan { size: DataSize },
+    StringFill { size: DataSize },
     
     // Instructions MMU et système avancées
     LoadControlRegister { dest: Operand, control_reg: u8 },
@@ -152,14 +161,15 @@ pub struct DecodedInstruction {
     /// Taille de l'instruction en octets
     pub size: u32,
     
-    /// Nombre de cycles estimé pour l'exécution
+    /// Durée de l'instruction en mode précis, hors parts variables
+    /// (voir [`super::timing`])
     pub cycles: u32,
 }
 
 impl DecodedInstruction {
     /// Crée une nouvelle instruction décodée
     pub fn new(instruction: Instruction, address: u32, size: u32) -> Self {
-        let cycles = estimate_cycles(&instruction);
+        let cycles = super::timing::instruction_cycles(&instruction);
         Self {
             instruction,
             address,
@@ -168,99 +178,3 @@ impl DecodedInstruction {
         }
     }
 }
-
-/// Estime le nombre de cycles nécessaires pour une instruction
-fn estimate_cycles(instruction: &Instruction) -> u32 {
-    match instruction {
-        // Instructions simples - 1 cycle
-        Instruction::Nop | 
-        Instruction::Mov { .. } => 1,
-        
-        // Instructions arithmétiques simples - 2 cycles
-        Instruction::Add { .. } | 
-        Instruction::Sub { .. } |
-        Instruction::And { .. } |
-        Instruction::Or { .. } |
-        Instruction::Xor { .. } |
-        Instruction::Not { .. } => 2,
-        
-        // Instructions de déplacement - 3 cycles
-        Instruction::Shl { .. } |
-        Instruction::Shr { .. } |
-        Instruction::RotateLeft { .. } |
-        Instruction::RotateRight { .. } => 3,
-        
-        // Multiplication - 10 cycles
-        Instruction::Mul { .. } => 10,
-        
-        // Division - 20 cycles
-        Instruction::Div { .. } => 20,
-        
-        // Accès mémoire - 3 cycles
-        Instruction::Load { .. } |
-        Instruction::Store { .. } => 3,
-        
-        // Branchements - 2 cycles si pas pris, 4 si pris
-        Instruction::Jump { .. } |
-        Instruction::JumpConditional { .. } => 4,
-        
-        // Appels et retours - 5 cycles
-        Instruction::Call { .. } |
-        Instruction::Return => 5,
-        
-        // Instructions de comparaison - 2 cycles
-        Instruction::Compare { .. } |
-        Instruction::Test { .. } => 2,
-        
-        // Instructions flottantes - 8-12 cycles
-        Instruction::FloatAdd { .. } |
-        Instruction::FloatSub { .. } => 8,
-        Instruction::FloatMul { .. } => 10,
-        Instruction::FloatDiv { .. } => 15,
-        Instruction::FloatCompare { .. } => 6,
-        
-        // Instructions de manipulation de bits - 2-4 cycles
-        Instruction::BitTest { .. } |
-        Instruction::BitSet { .. } |
-        Instruction::BitClear { .. } => 2,
-        Instruction::BitScan { .. } => 4,
-        
-        // Instructions de pile - 2-5 cycles
-        Instruction::Push { .. } |
-        Instruction::Pop { .. } => 2,
-        Instruction::PushMultiple { registers } => 2 + registers.len() as u32,
-        Instruction::PopMultiple { registers } => 2 + registers.len() as u32,
-        
-        // Instructions de chaîne - 5-15 cycles selon la taille
-        Instruction::StringMove { .. } => 8,
-        Instruction::StringCompare { .. } => 10,
-        Instruction::StringScan { .. } => 12,
-        
-        // Instructions MMU et système - cycles élevés
-        Instruction::LoadControlRegister { .. } |
-        Instruction::StoreControlRegister { .. } => 15,
-        Instruction::InvalidateTLB => 25,
-        Instruction::FlushCache => 50,
-        
-        // Instructions d'interruption - cycles variables
-        Instruction::SoftwareInterrupt { .. } => 20,
-        Instruction::ReturnFromInterrupt => 15,
-        Instruction::EnableInterrupts |
-        Instruction::DisableInterrupts => 3,
-        
-        // Instructions de synchronisation - cycles élevés
-        Instruction::TestAndSet { .. } => 8,
-        Instruction::CompareAndSwap { .. } => 12,
-        
-        // Instructions BCD - cycles moyens
-        Instruction::BcdAdd { .. } |
-        Instruction::BcdSub { .. } => 6,
-        
-        // Instructions système
-        Instruction::Halt => 1,
-        Instruction::InterruptReturn => 10,
-        
-        // Instruction inconnue - 1 cycle par défaut
-        Instruction::Unknown { .. } => 1,
-    }
-}
\ No newline at end of file
@@ -12,10 +12,17 @@ pub mod arithmetic;
 pub mod logical;
 pub mod floating_point;
 pub mod bit_manipulation;
+pub mod bitfield;
 pub mod string_operations;
 pub mod bcd;
+pub mod profiler;
+pub mod call_stack;
+pub mod hle;
+pub mod quarantine;
+pub mod timing;
+pub mod opcode_matrix;
 
-use anyhow::Result;
+use crate::error::{CpuException, EmulatorError, GpuError, Result};
 
 pub use registers::*;
 pub use instructions::*;
@@ -26,8 +33,15 @@ pub use arithmetic::*;
 pub use logical::*;
 pub use floating_point::*;
 pub use bit_manipulation::*;
+pub use bitfield::*;
 pub use string_operations::*;
 pub use bcd::*;
+pub use profiler::*;
+pub use call_stack::*;
+pub use hle::*;
+pub use quarantine::*;
+pub use timing::*;
+pub use opcode_matrix::*;
 
 /// Types d'interruptions du SEGA Model 2
 #[repr(u8)]
@@ -51,6 +65,12 @@ pub enum Interrupt {
     /// Interruption d'entrée
     Input = 0x06,
     
+    /// Erreur de bus (accès à une zone sans périphérique)
+    BusError,
+
+    /// Exception de l'unité flottante déroutée par le TKCW
+    FloatingPoint,
+    
     /// Interruption externe générique
     External(u8),
 }
@@ -65,6 +85,8 @@ impl Interrupt {
             Interrupt::Gpu => 0x0000004C,
             Interrupt::Audio => 0x00000050,
             Interrupt::Input => 0x00000054,
+            Interrupt::BusError => 0x00000010,
+            Interrupt::FloatingPoint => 0x00000014,
             Interrupt::External(vector) => 0x00000058 + (vector as u32 * 4),
         }
     }
@@ -93,6 +115,21 @@ pub struct NecV60 {
     
     /// File d'attente des interruptions pendantes
     pub pending_interrupts: Vec<Interrupt>,
+
+    /// Profileur par PC (désactivé par défaut)
+    pub profiler: Option<PcProfiler>,
+
+    /// Pile d'appels fantôme reconstruite à partir des CALL/RET
+    pub call_stack: CallStack,
+
+    /// Routines du micrologiciel remplacées (HLE), si le jeu en a
+    pub hle: Option<FirmwareHle>,
+
+    /// Conduite face aux opcodes inconnus et compteurs par opcode
+    pub quarantine: Quarantine,
+
+    /// Calcul des durées d'instruction
+    pub timing: CpuTiming,
 }
 
 impl NecV60 {
@@ -106,6 +143,11 @@ impl NecV60 {
             halted: false,
             interrupts_enabled: true,
             pending_interrupts: Vec::new(),
+            profiler: None,
+            call_stack: CallStack::new(),
+            hle: None,
+            quarantine: Quarantine::default(),
+            timing: CpuTiming::default(),
         }
     }
 
@@ -118,6 +160,8 @@ impl NecV60 {
         self.halted = false;
         self.interrupts_enabled = true;
         self.pending_interrupts.clear();
+        self.call_stack.clear();
+        self.quarantine.resume();
     }
 
     /// Exécute un cycle du processeur
@@ -134,25 +178,108 @@ impl NecV60 {
             return Ok(10); // Cycles pour le traitement d'interruption
         }
 
+        // Routine du micrologiciel remplacée
+        if let Some(cycles) = self.intercept_firmware_call(memory)? {
+            self.cycle_count += cycles as u64;
+            return Ok(cycles);
+        }
+
         // Récupérer l'instruction à l'adresse du PC
         let pc = self.registers.pc;
         
         // Lire les données d'instruction depuis la mémoire
         let mut instruction_data = [0u8; 8]; // Maximum 8 octets pour une instruction V60
         for i in 0..8 {
-            instruction_data[i] = memory.read_u8(pc + i as u32)?;
+            instruction_data[i] = match memory.read_u8(pc.wrapping_add(i as u32)) {
+                Ok(byte) => byte,
+                // Les octets préchargés au-delà de l'opcode ne provoquent pas d'exception
+                Err(e) if i > 0 && e.bus_error().is_some() => 0xFF,
+                Err(e) => return self.exception_or(e, memory),
+            };
         }
         
-        // Décoder l'instruction
-        let instruction = self.decoder.decode(&instruction_data, pc)?;
+        // Décoder l'instruction ; un opcode inconnu passe par l'exécution
+        // comme un demi-mot, pour que la quarantaine puisse le sauter
+        let instruction = match self.decoder.decode(&instruction_data, pc) {
+            Ok(instruction) => instruction,
+            Err(EmulatorError::CpuException(CpuException::UnknownOpcode { opcode, .. })) => {
+                DecodedInstruction::new(Instruction::Unknown { opcode }, pc, 2)
+            }
+            Err(e) => return Err(e),
+        };
 
         // Exécuter l'instruction
-        let cycles = self.execute_instruction(&instruction, memory)?;
+        let cycles = match self.execute_instruction(&instruction, memory).or_else(|e| self.quarantine_instruction(&instruction, e)) {
+            Ok(cycles) => cycles,
+            Err(e) => return self.exception_or(e, memory),
+        };
         self.cycle_count += cycles as u64;
 
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.record(pc, instruction.size, cycles);
+        }
+
         Ok(cycles)
     }
 
+    /// Applique la politique de quarantaine à un opcode inconnu ou une
+    /// instruction non implémentée ; les autres erreurs sont propagées
+    fn quarantine_instruction(&mut self, instruction: &DecodedInstruction, error: EmulatorError) -> Result<u32> {
+        let EmulatorError::CpuException(exception) = error else {
+            return Err(error);
+        };
+        let (kind, name) = match &exception {
+            CpuException::UnknownOpcode { opcode, .. } => (QuarantineKind::UnknownOpcode, format!("0x{:02X}", opcode)),
+            CpuException::Unimplemented { mnemonic, .. } => {
+                (QuarantineKind::UnimplementedInstruction, mnemonic_name(mnemonic).to_string())
+            }
+            _ => return Err(exception.into()),
+        };
+        let first = self.quarantine.record(kind, &name, instruction.address);
+
+        match self.quarantine.policy {
+            QuarantinePolicy::Error => return Err(exception.into()),
+            QuarantinePolicy::Skip => {}
+            QuarantinePolicy::Halt => self.halted = true,
+            QuarantinePolicy::Break => self.quarantine.request_break(exception.to_string()),
+        }
+        if self.quarantine.policy != QuarantinePolicy::Halt {
+            self.registers.pc = self.registers.pc.wrapping_add(instruction.size.max(1));
+        }
+        if first {
+            self.quarantine.keep_skipped(exception);
+        }
+        Ok(instruction.cycles)
+    }
+
+    /// Applique la politique de quarantaine à une commande GPU non traitée
+    pub fn quarantine_gpu_command(&mut self, name: &str) -> Result<()> {
+        self.quarantine.record(QuarantineKind::GpuCommand, name, 0);
+        match self.quarantine.policy {
+            QuarantinePolicy::Error => return Err(GpuError::Unimplemented(name.to_string()).into()),
+            QuarantinePolicy::Skip => {}
+            QuarantinePolicy::Halt => self.halted = true,
+            QuarantinePolicy::Break => self.quarantine.request_break(format!("commande GPU non implémentée : {}", name)),
+        }
+        Ok(())
+    }
+
+    /// Transforme une erreur de bus ou une exception flottante déroutée en
+    /// exception CPU ; les autres erreurs sont propagées
+    fn exception_or<M>(&mut self, error: EmulatorError, memory: &mut M) -> Result<u32>
+    where
+        M: crate::memory::MemoryInterface,
+    {
+        let interrupt = match &error {
+            EmulatorError::CpuException(CpuException::FloatingPoint(_)) => Interrupt::FloatingPoint,
+            _ if error.bus_error().is_some() => Interrupt::BusError,
+            _ => return Err(error),
+        };
+
+        self.handle_interrupt(interrupt, memory)?;
+        Ok(10) // Cycles pour le traitement de l'exception
+    }
+
     /// Exécute plusieurs cycles du processeur
     pub fn run_cycles<M>(&mut self, cycles: u32, memory: &mut M) -> Result<u32>
     where
@@ -160,19 +287,30 @@ impl NecV60 {
     {
         let mut executed_cycles = 0;
         
-        while executed_cycles < cycles && !self.halted {
+        while executed_cycles < cycles && !self.halted && !self.quarantine.is_stopped() {
             executed_cycles += self.step(memory)?;
         }
         
         Ok(executed_cycles)
     }
 
+    /// Active le profileur par PC (sans effet s'il est déjà actif)
+    pub fn enable_profiler(&mut self) {
+        self.profiler.get_or_insert_with(PcProfiler::new);
+    }
+
+    /// Désactive le profileur et retourne les mesures collectées
+    pub fn take_profiler(&mut self) -> Option<PcProfiler> {
+        self.profiler.take()
+    }
+
     /// Obtient l'état actuel du processeur pour le débogage
     pub fn get_debug_state(&self) -> CpuDebugState {
         CpuDebugState {
             registers: self.registers.clone(),
             cycle_count: self.cycle_count,
             halted: self.halted,
+            call_stack: self.call_stack.frames().to_vec(),
         }
     }
     
@@ -230,6 +368,13 @@ impl NecV60 {
         
         // Sauter au gestionnaire
         self.registers.pc = handler;
+        self.call_stack.push(CallFrame {
+            target: handler,
+            return_address: pc,
+            stack_pointer: self.registers.sp,
+            cycle: self.cycle_count,
+            kind: CallKind::Interrupt,
+        });
         
         // Restaurer l'état des interruptions si elles étaient activées
         if interrupts_were_enabled {
@@ -253,6 +398,7 @@ impl NecV60 {
         let pc = memory.read_u32(self.registers.sp)?;
         self.registers.sp = self.registers.sp.wrapping_add(4);
         self.registers.pc = pc;
+        self.call_stack.pop(pc, CallKind::Interrupt, self.cycle_count);
         
         // Réactiver les interruptions
         self.interrupts_enabled = true;
@@ -273,4 +419,7 @@ pub struct CpuDebugState {
     pub registers: V60Registers,
     pub cycle_count: u64,
     pub halted: bool,
+
+    /// Pile d'appels, de la trame la plus ancienne à la plus récente
+    pub call_stack: Vec<CallFrame>,
 }
\ No newline at end of file
@@ -0,0 +1,242 @@
+//! Matrice des opcodes du V60 générée depuis le décodeur
+//!
+//! [`OpcodeMatrix::build`] présente chacun des 64 opcodes primaires au
+//! décodeur ([`V60InstructionDecoder`]), puis exécute l'instruction obtenue
+//! sur un processeur de test : un opcode est implémenté si l'exécuteur le
+//! traite, partiel s'il est décodé mais refusé par l'exécuteur
+//! ([`CpuException::Unimplemented`]), absent si le décodeur ne le connaît
+//! pas. La matrice se lit dans le code à chaque génération et ne peut donc
+//! pas dériver du décodeur ; elle s'exporte en Markdown ou en HTML
+//! (`--opcode-matrix` en ligne de commande, `OPCODES.md` dans le dépôt).
+
+use std::fmt::Write;
+
+use super::instruction_formats::V60InstructionDecoder;
+use super::instructions::Instruction;
+use super::quarantine::mnemonic_name;
+use super::NecV60;
+use crate::error::{CpuException, EmulatorError};
+use crate::memory::ram::Ram;
+
+/// Nombre d'opcodes primaires (6 bits de poids fort du premier demi-mot)
+pub const OPCODE_COUNT: u8 = 64;
+
+/// Colonnes de la grille : 3 bits de poids faible de l'opcode
+const GRID_COLUMNS: u8 = 8;
+
+/// Prise en charge d'un opcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpcodeSupport {
+    /// Décodé et exécuté
+    Implemented,
+    /// Décodé, mais l'exécuteur ne le traite pas encore
+    Partial,
+    /// Inconnu du décodeur
+    Missing,
+}
+
+impl OpcodeSupport {
+    pub fn name(self) -> &'static str {
+        match self {
+            OpcodeSupport::Implemented => "implémenté",
+            OpcodeSupport::Partial => "partiel",
+            OpcodeSupport::Missing => "absent",
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            OpcodeSupport::Implemented => "✅",
+            OpcodeSupport::Partial => "🟡",
+            OpcodeSupport::Missing => "❌",
+        }
+    }
+}
+
+/// Opcode primaire tel que le voit le décodeur
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcodeEntry {
+    pub opcode: u8,
+    /// Format retenu par le décodeur, `None` s'il refuse l'opcode
+    pub format: Option<&'static str>,
+    /// Taille de l'instruction en octets
+    pub size: u32,
+    /// Instruction décodée, `None` si l'opcode est inconnu
+    pub mnemonic: Option<String>,
+    pub support: OpcodeSupport,
+}
+
+/// Matrice des 64 opcodes primaires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcodeMatrix {
+    pub entries: Vec<OpcodeEntry>,
+}
+
+impl OpcodeMatrix {
+    /// Décode puis exécute chaque opcode primaire
+    pub fn build() -> Self {
+        Self { entries: (0..OPCODE_COUNT).map(Self::probe).collect() }
+    }
+
+    /// Opcode encodé avec r2 = R1 et r1 = R2, suivi d'octets nuls :
+    /// l'exécution lit et écrit au début d'une petite RAM
+    fn probe(opcode: u8) -> OpcodeEntry {
+        let first_word = (opcode as u16) << 10 | 1 << 5 | 2;
+        let mut data = [0u8; 8];
+        data[..2].copy_from_slice(&first_word.to_le_bytes());
+
+        let mut decoder = V60InstructionDecoder::new();
+        let format = decoder.format(&data, 0).ok().map(|format| format.name());
+        let decoded = match decoder.decode(&data, 0) {
+            Ok(decoded) if !matches!(decoded.instruction, Instruction::Unknown { .. }) => decoded,
+            Ok(decoded) => return OpcodeEntry { opcode, format, size: decoded.size, mnemonic: None, support: OpcodeSupport::Missing },
+            Err(_) => return OpcodeEntry { opcode, format, size: 0, mnemonic: None, support: OpcodeSupport::Missing },
+        };
+
+        let mut cpu = NecV60::new();
+        let mut memory = Ram::new(0x1000);
+        let support = match cpu.execute_instruction(&decoded, &mut memory) {
+            Err(EmulatorError::CpuException(CpuException::Unimplemented { .. })) => OpcodeSupport::Partial,
+            Err(EmulatorError::CpuException(CpuException::UnknownOpcode { .. })) => OpcodeSupport::Missing,
+            // Une faute mémoire ou une exception du V60 prouve que
+            // l'exécuteur traite l'instruction
+            _ => OpcodeSupport::Implemented,
+        };
+        let mnemonic = format!("{:?}", decoded.instruction);
+        OpcodeEntry {
+            opcode,
+            format,
+            size: decoded.size,
+            mnemonic: Some(mnemonic_name(&mnemonic).to_string()),
+            support,
+        }
+    }
+
+    /// Nombre d'opcodes pour un niveau de prise en charge
+    pub fn count(&self, support: OpcodeSupport) -> usize {
+        self.entries.iter().filter(|entry| entry.support == support).count()
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{} implémentés, {} partiels, {} absents sur {} opcodes primaires",
+            self.count(OpcodeSupport::Implemented),
+            self.count(OpcodeSupport::Partial),
+            self.count(OpcodeSupport::Missing),
+            self.entries.len(),
+        )
+    }
+
+    /// Grille 8x8 puis détail par opcode, en Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Opcodes du NEC V60\n");
+        let _ = writeln!(out, "Généré depuis le décodeur par `--opcode-matrix` : ne pas modifier à la main.\n");
+        let _ = writeln!(out, "{}.\n", self.summary());
+        let _ = writeln!(out, "✅ implémenté, 🟡 partiel (décodé, non exécuté), ❌ absent\n");
+
+        out.push_str("| |");
+        for column in 0..GRID_COLUMNS {
+            let _ = write!(out, " +{} |", column);
+        }
+        out.push_str("\n|---|");
+        out.push_str(&"---|".repeat(GRID_COLUMNS as usize));
+        out.push('\n');
+        for row in self.entries.chunks(GRID_COLUMNS as usize) {
+            let _ = write!(out, "| **0x{:02X}** |", row[0].opcode);
+            for entry in row {
+                let cell = match &entry.mnemonic {
+                    Some(mnemonic) => format!("{} {}", entry.support.symbol(), mnemonic),
+                    None => entry.support.symbol().to_string(),
+                };
+                let _ = write!(out, " {} |", cell);
+            }
+            out.push('\n');
+        }
+
+        out.push_str("\n| Opcode | Format | Taille | Instruction | État |\n|---|---|---|---|---|\n");
+        for entry in &self.entries {
+            let _ = writeln!(
+                out,
+                "| 0x{:02X} | {} | {} | {} | {} |",
+                entry.opcode,
+                entry.format.unwrap_or("—"),
+                if entry.size == 0 { "—".to_string() } else { entry.size.to_string() },
+                entry.mnemonic.as_deref().unwrap_or("—"),
+                entry.support.name(),
+            );
+        }
+        out
+    }
+
+    /// Même contenu en page HTML autonome, cellules colorées par état
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html lang=\"fr\">\n<head>\n<meta charset=\"utf-8\">\n<title>Opcodes du NEC V60</title>\n<style>\n");
+        out.push_str("table { border-collapse: collapse; font-family: monospace; }\n");
+        out.push_str("td, th { border: 1px solid #888; padding: 4px 8px; }\n");
+        out.push_str(".implemented { background: #c8f0c8; }\n.partial { background: #f8e8a0; }\n.missing { background: #f0c0c0; }\n");
+        out.push_str("</style>\n</head>\n<body>\n<h1>Opcodes du NEC V60</h1>\n");
+        let _ = writeln!(out, "<p>{}.</p>", self.summary());
+
+        out.push_str("<table>\n<tr><th></th>");
+        for column in 0..GRID_COLUMNS {
+            let _ = write!(out, "<th>+{}</th>", column);
+        }
+        out.push_str("</tr>\n");
+        for row in self.entries.chunks(GRID_COLUMNS as usize) {
+            let _ = write!(out, "<tr><th>0x{:02X}</th>", row[0].opcode);
+            for entry in row {
+                let _ = write!(
+                    out,
+                    "<td class=\"{}\" title=\"0x{:02X} {}\">{}</td>",
+                    Self::css_class(entry.support),
+                    entry.opcode,
+                    entry.format.unwrap_or(""),
+                    entry.mnemonic.as_deref().unwrap_or(""),
+                );
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n</body>\n</html>\n");
+        out
+    }
+
+    fn css_class(support: OpcodeSupport) -> &'static str {
+        match support {
+            OpcodeSupport::Implemented => "implemented",
+            OpcodeSupport::Partial => "partial",
+            OpcodeSupport::Missing => "missing",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix_follows_decoder_and_executor() {
+        let matrix = OpcodeMatrix::build();
+        assert_eq!(matrix.entries.len(), OPCODE_COUNT as usize);
+
+        let entry = |opcode: usize| &matrix.entries[opcode];
+        assert_eq!(entry(0x01).mnemonic.as_deref(), Some("Add"));
+        assert_eq!(entry(0x01).format, Some("Format1"));
+        assert_eq!(entry(0x01).support, OpcodeSupport::Implemented);
+        assert_eq!(entry(0x20).size, 6);
+        // Décodée mais pas encore exécutée
+        assert_eq!(entry(0x06).mnemonic.as_deref(), Some("Compare"));
+        assert_eq!(entry(0x06).support, OpcodeSupport::Partial);
+        assert_eq!(entry(0x0F).support, OpcodeSupport::Missing);
+
+        let total = matrix.count(OpcodeSupport::Implemented) + matrix.count(OpcodeSupport::Partial) + matrix.count(OpcodeSupport::Missing);
+        assert_eq!(total, OPCODE_COUNT as usize);
+
+        let markdown = matrix.to_markdown();
+        assert!(markdown.contains("| 0x01 | Format1 | 2 | Add | implémenté |"));
+        assert!(markdown.contains("| **0x08** |"));
+        let html = matrix.to_html();
+        assert!(html.contains("<td class=\"partial\" title=\"0x06 Format1\">Compare</td>"));
+    }
+}
@@ -0,0 +1,228 @@
+//! Profileur d'exécution par adresse (couverture et points chauds)
+//!
+//! Optionnel : chaque instruction exécutée incrémente un compteur associé à
+//! son PC. Les adresses contiguës exécutées le même nombre de fois sont
+//! regroupées en plages (blocs de base), ce qui donne un rapport compact des
+//! blocs les plus coûteux. Le profil peut être exporté au format callgrind
+//! pour être ouvert dans kcachegrind.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+/// Compteurs associés à une adresse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PcCounter {
+    /// Taille de l'instruction en octets
+    pub size: u32,
+
+    /// Nombre d'exécutions
+    pub hits: u64,
+
+    /// Cycles cumulés
+    pub cycles: u64,
+}
+
+/// Plage d'adresses contiguës exécutées le même nombre de fois
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotRange {
+    /// Première adresse de la plage
+    pub start: u32,
+
+    /// Adresse suivant la dernière instruction (exclusive)
+    pub end: u32,
+
+    /// Nombre d'instructions distinctes
+    pub instructions: u32,
+
+    /// Nombre d'exécutions de la plage
+    pub hits: u64,
+
+    /// Cycles cumulés sur toute la plage
+    pub cycles: u64,
+}
+
+/// Profileur de PC du V60
+#[derive(Debug, Clone, Default)]
+pub struct PcProfiler {
+    counters: HashMap<u32, PcCounter>,
+    total_cycles: u64,
+}
+
+impl PcProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre l'exécution d'une instruction
+    pub fn record(&mut self, pc: u32, size: u32, cycles: u32) {
+        let counter = self.counters.entry(pc).or_default();
+        counter.size = size.max(1);
+        counter.hits += 1;
+        counter.cycles += cycles as u64;
+        self.total_cycles += cycles as u64;
+    }
+
+    /// Oublie toutes les mesures
+    pub fn clear(&mut self) {
+        self.counters.clear();
+        self.total_cycles = 0;
+    }
+
+    /// Compteurs d'une adresse
+    pub fn counter(&self, pc: u32) -> Option<PcCounter> {
+        self.counters.get(&pc).copied()
+    }
+
+    /// Nombre d'adresses distinctes exécutées (couverture)
+    pub fn covered_instructions(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Cycles cumulés sur tout le profil
+    pub fn total_cycles(&self) -> u64 {
+        self.total_cycles
+    }
+
+    /// Regroupe les adresses exécutées en plages triées par adresse
+    pub fn ranges(&self) -> Vec<HotRange> {
+        let mut pcs: Vec<(&u32, &PcCounter)> = self.counters.iter().collect();
+        pcs.sort_unstable_by_key(|(pc, _)| **pc);
+
+        let mut ranges: Vec<HotRange> = Vec::new();
+        for (&pc, counter) in pcs {
+            match ranges.last_mut() {
+                Some(range) if range.end == pc && range.hits == counter.hits => {
+                    range.end = pc.wrapping_add(counter.size);
+                    range.instructions += 1;
+                    range.cycles += counter.cycles;
+                }
+                _ => ranges.push(HotRange {
+                    start: pc,
+                    end: pc.wrapping_add(counter.size),
+                    instructions: 1,
+                    hits: counter.hits,
+                    cycles: counter.cycles,
+                }),
+            }
+        }
+        ranges
+    }
+
+    /// Les `count` plages les plus coûteuses en cycles
+    pub fn hottest(&self, count: usize) -> Vec<HotRange> {
+        let mut ranges = self.ranges();
+        ranges.sort_by(|a, b| b.cycles.cmp(&a.cycles).then(a.start.cmp(&b.start)));
+        ranges.truncate(count);
+        ranges
+    }
+
+    /// Rapport texte des plages les plus coûteuses
+    pub fn report(&self, count: usize) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{} instructions couvertes, {} cycles",
+            self.covered_instructions(),
+            self.total_cycles
+        );
+        let _ = writeln!(out, "{:>10} {:>10} {:>12} {:>14} {:>7}", "début", "fin", "exécutions", "cycles", "%");
+
+        for range in self.hottest(count) {
+            let share = if self.total_cycles > 0 {
+                range.cycles as f64 * 100.0 / self.total_cycles as f64
+            } else {
+                0.0
+            };
+            let _ = writeln!(
+                out,
+                "0x{:08X} 0x{:08X} {:>12} {:>14} {:>6.2}%",
+                range.start, range.end, range.hits, range.cycles, share
+            );
+        }
+        out
+    }
+
+    /// Exporte le profil au format callgrind (kcachegrind).
+    ///
+    /// Chaque plage devient une fonction nommée d'après ses adresses ; les
+    /// coûts sont donnés par instruction (exécutions et cycles).
+    pub fn write_callgrind<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "# callgrind format")?;
+        writeln!(out, "version: 1")?;
+        writeln!(out, "creator: pixel-model2-rust")?;
+        writeln!(out, "positions: instr")?;
+        writeln!(out, "events: Executions Cycles")?;
+        writeln!(out, "summary: {} {}", self.counters.values().map(|c| c.hits).sum::<u64>(), self.total_cycles)?;
+        writeln!(out)?;
+        writeln!(out, "ob=v60")?;
+
+        let mut pcs: Vec<(&u32, &PcCounter)> = self.counters.iter().collect();
+        pcs.sort_unstable_by_key(|(pc, _)| **pc);
+
+        // Les plages suivent les PC triés : chacune prend les suivants
+        let mut rest = pcs.as_slice();
+        for range in self.ranges() {
+            writeln!(out, "fn=0x{:08X}-0x{:08X}", range.start, range.end)?;
+            let (instructions, remaining) = rest.split_at(range.instructions as usize);
+            for &(&pc, counter) in instructions {
+                writeln!(out, "0x{:X} {} {}", pc, counter.hits, counter.cycles)?;
+            }
+            rest = remaining;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_profile() -> PcProfiler {
+        let mut profiler = PcProfiler::new();
+        // Boucle de 3 instructions exécutée 10 fois
+        for _ in 0..10 {
+            profiler.record(0x1000, 2, 4);
+            profiler.record(0x1002, 3, 6);
+            profiler.record(0x1005, 2, 10);
+        }
+        // Code d'initialisation exécuté une fois
+        profiler.record(0x2000, 4, 8);
+        profiler
+    }
+
+    #[test]
+    fn test_contiguous_pcs_merge_into_ranges() {
+        let ranges = sample_profile().ranges();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(
+            ranges[0],
+            HotRange { start: 0x1000, end: 0x1007, instructions: 3, hits: 10, cycles: 200 }
+        );
+        assert_eq!(ranges[1].start, 0x2000);
+    }
+
+    #[test]
+    fn test_hottest_sorted_by_cycles() {
+        let profiler = sample_profile();
+        let hottest = profiler.hottest(1);
+        assert_eq!(hottest.len(), 1);
+        assert_eq!(hottest[0].start, 0x1000);
+        assert_eq!(profiler.total_cycles(), 208);
+        assert!(profiler.report(5).contains("0x00001000"));
+    }
+
+    #[test]
+    fn test_callgrind_export() {
+        let mut out = Vec::new();
+        sample_profile().write_callgrind(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.starts_with("# callgrind format"));
+        assert!(text.contains("events: Executions Cycles"));
+        assert!(text.contains("fn=0x00001000-0x00001007"));
+        assert!(text.contains("0x1002 10 60"));
+        assert!(text.contains("summary: 31 208"));
+        assert!(text.ends_with("0x1005 10 100\nfn=0x00002000-0x00002004\n0x2000 1 8\n"));
+    }
+}
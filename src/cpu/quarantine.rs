@@ -0,0 +1,170 @@
+//! Quarantaine des opcodes inconnus et des commandes non implémentées
+//!
+//! Par défaut, un opcode inconnu ou une instruction non implémentée
+//! interrompt la frame avec une erreur. La politique de quarantaine permet
+//! de continuer : ignorer l'instruction (signalée une seule fois), arrêter
+//! le CPU, ou l'ignorer puis rendre la main au débogueur. Les commandes GPU
+//! non traitées suivent la même politique. Chaque occurrence est comptée
+//! par opcode, mnémonique ou commande, pour savoir quoi implémenter en
+//! priorité d'après les jeux réels.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use crate::error::CpuException;
+
+/// Conduite face à une instruction ou une commande non prise en charge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuarantinePolicy {
+    /// Erreur qui interrompt la frame
+    #[default]
+    Error,
+    /// Ignorée, signalée à la première occurrence
+    Skip,
+    /// CPU arrêté sur l'instruction
+    Halt,
+    /// Ignorée, puis la frame s'arrête et le débogueur prend la main
+    Break,
+}
+
+/// Ce qui a été rencontré
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuarantineKind {
+    UnknownOpcode,
+    UnimplementedInstruction,
+    GpuCommand,
+}
+
+impl QuarantineKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            QuarantineKind::UnknownOpcode => "opcode inconnu",
+            QuarantineKind::UnimplementedInstruction => "instruction non implémentée",
+            QuarantineKind::GpuCommand => "commande GPU non implémentée",
+        }
+    }
+}
+
+/// Occurrences d'un opcode, d'une instruction ou d'une commande
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QuarantineCounter {
+    pub count: u64,
+    /// Adresse de la première occurrence (0 pour une commande GPU)
+    pub first_address: u32,
+}
+
+/// Politique et compteurs de la quarantaine
+#[derive(Debug, Clone, Default)]
+pub struct Quarantine {
+    pub policy: QuarantinePolicy,
+    counters: BTreeMap<(QuarantineKind, String), QuarantineCounter>,
+    /// Raison de la dernière interruption demandée, pour le frontend
+    break_reason: Option<String>,
+    /// Exécution suspendue jusqu'à la prochaine frame
+    stopped: bool,
+    /// Premières exceptions passées sous silence, pour le rapport de session
+    skipped: Vec<CpuException>,
+}
+
+impl Quarantine {
+    pub fn new(policy: QuarantinePolicy) -> Self {
+        Self { policy, ..Default::default() }
+    }
+
+    /// Compte une occurrence ; la première est signalée dans le journal et
+    /// la fonction renvoie vrai
+    pub fn record(&mut self, kind: QuarantineKind, name: &str, address: u32) -> bool {
+        let counter = self.counters.entry((kind, name.to_string())).or_insert(QuarantineCounter {
+            count: 0,
+            first_address: address,
+        });
+        let first = counter.count == 0;
+        if first && self.policy != QuarantinePolicy::Error {
+            log::warn!("{} {} à 0x{:08X}, politique {:?}", kind.label(), name, address, self.policy);
+        }
+        counter.count += 1;
+        first
+    }
+
+    /// Garde la première occurrence d'une exception non propagée
+    pub fn keep_skipped(&mut self, exception: CpuException) {
+        self.skipped.push(exception);
+    }
+
+    /// Exceptions non propagées depuis le dernier appel
+    pub fn take_skipped(&mut self) -> Vec<CpuException> {
+        std::mem::take(&mut self.skipped)
+    }
+
+    /// Demande l'arrêt de la frame et le passage au débogueur
+    pub fn request_break(&mut self, reason: String) {
+        self.break_reason = Some(reason);
+        self.stopped = true;
+    }
+
+    /// Vrai si l'exécution est suspendue jusqu'à la prochaine frame
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Nouvelle frame : l'exécution reprend
+    pub fn resume(&mut self) {
+        self.stopped = false;
+    }
+
+    /// Raison de l'interruption demandée, à traiter par le frontend
+    pub fn take_break(&mut self) -> Option<String> {
+        self.break_reason.take()
+    }
+
+    pub fn counter(&self, kind: QuarantineKind, name: &str) -> Option<QuarantineCounter> {
+        self.counters.get(&(kind, name.to_string())).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    /// Occurrences triées de la plus fréquente à la plus rare
+    pub fn report(&self) -> String {
+        let mut entries: Vec<_> = self.counters.iter().collect();
+        entries.sort_by_key(|(_, counter)| std::cmp::Reverse(counter.count));
+        let mut out = String::new();
+        for ((kind, name), counter) in entries {
+            let _ = writeln!(out, "  {:>8}  {} {} (première à 0x{:08X})", counter.count, kind.label(), name, counter.first_address);
+        }
+        out
+    }
+}
+
+/// Nom d'une instruction sans ses opérandes, pour regrouper les compteurs
+pub fn mnemonic_name(mnemonic: &str) -> &str {
+    mnemonic.split([' ', '{', '(']).next().unwrap_or(mnemonic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_per_opcode_and_sorts_report() {
+        let mut quarantine = Quarantine::new(QuarantinePolicy::Skip);
+        assert!(quarantine.record(QuarantineKind::UnknownOpcode, "0xFF", 0x100));
+        assert!(quarantine.record(QuarantineKind::UnimplementedInstruction, mnemonic_name("Compare { size: Word }"), 0x200));
+        assert!(!quarantine.record(QuarantineKind::UnimplementedInstruction, "Compare", 0x300));
+
+        let compare = quarantine.counter(QuarantineKind::UnimplementedInstruction, "Compare").unwrap();
+        assert_eq!(compare, QuarantineCounter { count: 2, first_address: 0x200 });
+        let report = quarantine.report();
+        assert!(report.lines().next().unwrap().contains("Compare"));
+        assert_eq!(report.lines().count(), 2);
+
+        quarantine.request_break("arrêt".to_string());
+        assert!(quarantine.is_stopped());
+        assert_eq!(quarantine.take_break().as_deref(), Some("arrêt"));
+        quarantine.resume();
+        assert!(!quarantine.is_stopped());
+    }
+}
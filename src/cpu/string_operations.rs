@@ -1,8 +1,69 @@
 //! Instructions de manipulation de chaînes NEC V60
-
-use super::registers::ProcessorStatusWord;
+//!
+//! Comme sur le V60, les instructions de chaîne gardent leur progression
+//! dans des registres généraux : R28 désigne l'élément source courant, R27
+//! l'élément de destination (la seconde chaîne d'une comparaison), R26 le
+//! nombre d'éléments restants et R25 la valeur cherchée ou de remplissage.
+//! L'exécuteur traite au plus [`STRING_STEP_ELEMENTS`] éléments par pas et
+//! laisse le PC sur l'instruction tant qu'il en reste : une interruption est
+//! prise entre deux tranches et son retour reprend l'instruction là où les
+//! registres l'ont laissée. Une erreur de bus en cours de route laisse de
+//! même les registres sur l'élément fautif.
+
+use super::registers::{ProcessorStatusWord, V60Registers};
 use crate::memory::MemoryInterface;
-use anyhow::Result;
+use crate::error::{CpuException, Result};
+
+/// Registre de l'adresse source
+pub const STRING_SOURCE_REGISTER: usize = 28;
+
+/// Registre de l'adresse de destination (ou de la seconde chaîne)
+pub const STRING_DESTINATION_REGISTER: usize = 27;
+
+/// Registre du nombre d'éléments restants
+pub const STRING_COUNT_REGISTER: usize = 26;
+
+/// Registre de la valeur cherchée ou de remplissage
+pub const STRING_VALUE_REGISTER: usize = 25;
+
+/// Éléments traités par pas d'exécution avant de laisser passer les
+/// interruptions
+pub const STRING_STEP_ELEMENTS: u32 = 64;
+
+/// Progression d'une instruction de chaîne
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StringState {
+    pub source: u32,
+    pub destination: u32,
+    pub remaining: u32,
+    pub value: u32,
+}
+
+impl StringState {
+    /// Progression conservée dans les registres
+    pub fn from_registers(registers: &V60Registers) -> Self {
+        Self {
+            source: registers.read_general(STRING_SOURCE_REGISTER),
+            destination: registers.read_general(STRING_DESTINATION_REGISTER),
+            remaining: registers.read_general(STRING_COUNT_REGISTER),
+            value: registers.read_general(STRING_VALUE_REGISTER),
+        }
+    }
+
+    /// Sauvegarde la progression dans les registres
+    pub fn store(&self, registers: &mut V60Registers) {
+        registers.write_general(STRING_SOURCE_REGISTER, self.source);
+        registers.write_general(STRING_DESTINATION_REGISTER, self.destination);
+        registers.write_general(STRING_COUNT_REGISTER, self.remaining);
+    }
+
+    /// Passe à l'élément suivant
+    fn advance(&mut self, element_size: u8) {
+        self.source = self.source.wrapping_add(element_size as u32);
+        self.destination = self.destination.wrapping_add(element_size as u32);
+        self.remaining -= 1;
+    }
+}
 
 /// Résultat d'une opération sur chaîne
 #[derive(Debug)]
@@ -12,9 +73,23 @@ pub struct StringResult {
     pub found: bool,
     pub source_exhausted: bool,
     pub destination_exhausted: bool,
+
+    /// L'instruction est terminée ; sinon elle reprend au pas suivant
+    pub completed: bool,
 }
 
 impl StringResult {
+    fn new(elements: u32, element_size: u8, state: &StringState, stopped: bool) -> Self {
+        Self {
+            bytes_processed: elements * element_size as u32,
+            equal: false,
+            found: false,
+            source_exhausted: state.remaining == 0,
+            destination_exhausted: false,
+            completed: stopped || state.remaining == 0,
+        }
+    }
+
     /// Met à jour le mot d'état du processeur
     pub fn update_psw(&self, psw: &mut ProcessorStatusWord) {
         psw.set(ProcessorStatusWord::ZERO, self.equal || self.found);
@@ -23,57 +98,155 @@ impl StringResult {
     }
 }
 
+fn read_element<M>(memory: &M, address: u32, element_size: u8) -> Result<u32>
+where
+    M: MemoryInterface,
+{
+    Ok(match element_size {
+        1 => memory.read_u8(address)? as u32,
+        2 => memory.read_u16(address)? as u32,
+        4 => memory.read_u32(address)?,
+        _ => return Err(CpuException::UnsupportedElementSize(element_size).into()),
+    })
+}
+
+fn write_element<M>(memory: &mut M, address: u32, element_size: u8, value: u32) -> Result<()>
+where
+    M: MemoryInterface,
+{
+    match element_size {
+        1 => memory.write_u8(address, value as u8),
+        2 => memory.write_u16(address, value as u16),
+        4 => memory.write_u32(address, value),
+        _ => Err(CpuException::UnsupportedElementSize(element_size).into()),
+    }
+}
+
 /// Unité de manipulation de chaînes
 pub struct StringUnit;
 
 impl StringUnit {
-    /// Copie de chaîne (STRING_MOVE)
-    pub fn string_move<M>(
+    /// Copie au plus `budget` éléments de `state` ; s'arrête après
+    /// `terminator` s'il est donné
+    pub fn move_step<M>(
         memory: &mut M,
-        source: u32,
-        destination: u32,
-        max_length: u32,
+        state: &mut StringState,
         element_size: u8,
+        terminator: Option<u32>,
+        budget: u32,
     ) -> Result<StringResult>
     where
         M: MemoryInterface,
     {
-        let mut bytes_processed = 0;
-        let mut current_src = source;
-        let mut current_dst = destination;
+        let mut elements = 0;
+        let mut stopped = false;
+        while state.remaining > 0 && elements < budget && !stopped {
+            let value = read_element(memory, state.source, element_size)?;
+            write_element(memory, state.destination, element_size, value)?;
+            state.advance(element_size);
+            elements += 1;
+            stopped = terminator == Some(value);
+        }
 
-        for _ in 0..max_length {
-            let value = match element_size {
-                1 => memory.read_u8(current_src)? as u32,
-                2 => memory.read_u16(current_src)? as u32,
-                4 => memory.read_u32(current_src)?,
-                _ => return Err(anyhow::anyhow!("Taille d'élément non supportée: {}", element_size)),
-            };
-
-            match element_size {
-                1 => memory.write_u8(current_dst, value as u8)?,
-                2 => memory.write_u16(current_dst, value as u16)?,
-                4 => memory.write_u32(current_dst, value)?,
-                _ => unreachable!(),
-            }
+        let mut result = StringResult::new(elements, element_size, state, stopped);
+        result.equal = true;
+        Ok(result)
+    }
 
-            bytes_processed += element_size as u32;
-            current_src += element_size as u32;
-            current_dst += element_size as u32;
+    /// Compare au plus `budget` éléments des deux chaînes de `state` ; les
+    /// adresses s'arrêtent après la première différence, ou après
+    /// `terminator` présent dans les deux chaînes
+    pub fn compare_step<M>(
+        memory: &M,
+        state: &mut StringState,
+        element_size: u8,
+        terminator: Option<u32>,
+        budget: u32,
+    ) -> Result<StringResult>
+    where
+        M: MemoryInterface,
+    {
+        let mut elements = 0;
+        let mut equal = true;
+        let mut stopped = false;
+        while state.remaining > 0 && elements < budget && !stopped {
+            let value1 = read_element(memory, state.source, element_size)?;
+            let value2 = read_element(memory, state.destination, element_size)?;
+            state.advance(element_size);
+            elements += 1;
+            equal = value1 == value2;
+            stopped = !equal || (terminator == Some(value1) && terminator == Some(value2));
+        }
 
-            // Arrêt si on trouve un terminateur nul
-            if value == 0 {
-                break;
-            }
+        let mut result = StringResult::new(elements, element_size, state, stopped);
+        result.equal = equal;
+        Ok(result)
+    }
+
+    /// Cherche `state.value` dans au plus `budget` éléments ; l'adresse
+    /// source s'arrête après l'élément trouvé, ou après `terminator`
+    pub fn scan_step<M>(
+        memory: &M,
+        state: &mut StringState,
+        element_size: u8,
+        terminator: Option<u32>,
+        budget: u32,
+    ) -> Result<StringResult>
+    where
+        M: MemoryInterface,
+    {
+        let mut elements = 0;
+        let mut found = false;
+        let mut stopped = false;
+        while state.remaining > 0 && elements < budget && !stopped {
+            let value = read_element(memory, state.source, element_size)?;
+            state.advance(element_size);
+            elements += 1;
+            found = value == state.value;
+            stopped = found || terminator == Some(value);
         }
 
-        Ok(StringResult {
-            bytes_processed,
-            equal: true,
-            found: false,
-            source_exhausted: bytes_processed == max_length * element_size as u32,
-            destination_exhausted: false,
-        })
+        let mut result = StringResult::new(elements, element_size, state, stopped);
+        result.found = found;
+        Ok(result)
+    }
+
+    /// Écrit `state.value` dans au plus `budget` éléments de destination
+    pub fn fill_step<M>(
+        memory: &mut M,
+        state: &mut StringState,
+        element_size: u8,
+        budget: u32,
+    ) -> Result<StringResult>
+    where
+        M: MemoryInterface,
+    {
+        let mut elements = 0;
+        while state.remaining > 0 && elements < budget {
+            write_element(memory, state.destination, element_size, state.value)?;
+            state.advance(element_size);
+            elements += 1;
+        }
+
+        let mut result = StringResult::new(elements, element_size, state, false);
+        result.equal = true;
+        result.source_exhausted = false;
+        Ok(result)
+    }
+
+    /// Copie de chaîne (STRING_MOVE)
+    pub fn string_move<M>(
+        memory: &mut M,
+        source: u32,
+        destination: u32,
+        max_length: u32,
+        element_size: u8,
+    ) -> Result<StringResult>
+    where
+        M: MemoryInterface,
+    {
+        let mut state = StringState { source, destination, remaining: max_length, value: 0 };
+        Self::move_step(memory, &mut state, element_size, Some(0), max_length)
     }
 
     /// Comparaison de chaînes (STRING_COMPARE)
@@ -87,48 +260,8 @@ impl StringUnit {
     where
         M: MemoryInterface,
     {
-        let mut bytes_processed = 0;
-        let mut current_src1 = source1;
-        let mut current_src2 = source2;
-        let mut equal = true;
-
-        for _ in 0..max_length {
-            let value1 = match element_size {
-                1 => memory.read_u8(current_src1)? as u32,
-                2 => memory.read_u16(current_src1)? as u32,
-                4 => memory.read_u32(current_src1)?,
-                _ => return Err(anyhow::anyhow!("Taille d'élément non supportée: {}", element_size)),
-            };
-
-            let value2 = match element_size {
-                1 => memory.read_u8(current_src2)? as u32,
-                2 => memory.read_u16(current_src2)? as u32,
-                4 => memory.read_u32(current_src2)?,
-                _ => unreachable!(),
-            };
-
-            bytes_processed += element_size as u32;
-            current_src1 += element_size as u32;
-            current_src2 += element_size as u32;
-
-            if value1 != value2 {
-                equal = false;
-                break;
-            }
-
-            // Arrêt si les deux chaînes se terminent
-            if value1 == 0 && value2 == 0 {
-                break;
-            }
-        }
-
-        Ok(StringResult {
-            bytes_processed,
-            equal,
-            found: false,
-            source_exhausted: bytes_processed == max_length * element_size as u32,
-            destination_exhausted: false,
-        })
+        let mut state = StringState { source: source1, destination: source2, remaining: max_length, value: 0 };
+        Self::compare_step(memory, &mut state, element_size, Some(0), max_length)
     }
 
     /// Recherche dans une chaîne (STRING_SCAN)
@@ -142,39 +275,8 @@ impl StringUnit {
     where
         M: MemoryInterface,
     {
-        let mut bytes_processed = 0;
-        let mut current_src = source;
-        let mut found = false;
-
-        for _ in 0..max_length {
-            let value = match element_size {
-                1 => memory.read_u8(current_src)? as u32,
-                2 => memory.read_u16(current_src)? as u32,
-                4 => memory.read_u32(current_src)?,
-                _ => return Err(anyhow::anyhow!("Taille d'élément non supportée: {}", element_size)),
-            };
-
-            bytes_processed += element_size as u32;
-            current_src += element_size as u32;
-
-            if value == target_value {
-                found = true;
-                break;
-            }
-
-            // Arrêt si on trouve un terminateur nul
-            if value == 0 {
-                break;
-            }
-        }
-
-        Ok(StringResult {
-            bytes_processed,
-            equal: false,
-            found,
-            source_exhausted: bytes_processed == max_length * element_size as u32,
-            destination_exhausted: false,
-        })
+        let mut state = StringState { source, destination: 0, remaining: max_length, value: target_value };
+        Self::scan_step(memory, &mut state, element_size, Some(0), max_length)
     }
 
     /// Remplissage de mémoire (STRING_FILL)
@@ -188,28 +290,8 @@ impl StringUnit {
     where
         M: MemoryInterface,
     {
-        let mut bytes_processed = 0;
-        let mut current_dst = destination;
-
-        for _ in 0..count {
-            match element_size {
-                1 => memory.write_u8(current_dst, fill_value as u8)?,
-                2 => memory.write_u16(current_dst, fill_value as u16)?,
-                4 => memory.write_u32(current_dst, fill_value)?,
-                _ => return Err(anyhow::anyhow!("Taille d'élément non supportée: {}", element_size)),
-            }
-
-            bytes_processed += element_size as u32;
-            current_dst += element_size as u32;
-        }
-
-        Ok(StringResult {
-            bytes_processed,
-            equal: true,
-            found: false,
-            source_exhausted: false,
-            destination_exhausted: false,
-        })
+        let mut state = StringState { source: 0, destination, remaining: count, value: fill_value };
+        Self::fill_step(memory, &mut state, element_size, count)
     }
 
     /// Longueur de chaîne (STRING_LENGTH)
@@ -226,14 +308,7 @@ impl StringUnit {
         let mut current_src = source;
 
         for _ in 0..max_length {
-            let value = match element_size {
-                1 => memory.read_u8(current_src)? as u32,
-                2 => memory.read_u16(current_src)? as u32,
-                4 => memory.read_u32(current_src)?,
-                _ => return Err(anyhow::anyhow!("Taille d'élément non supportée: {}", element_size)),
-            };
-
-            if value == 0 {
+            if read_element(memory, current_src, element_size)? == 0 {
                 break;
             }
 
@@ -304,4 +379,32 @@ mod tests {
         assert!(result.found);
         assert_eq!(result.bytes_processed, 3); // H, e, l (trouvé au 3ème)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_steps_resume_from_saved_progress() {
+        let mut memory = Ram::new(0x100);
+        for i in 0..0x20 {
+            memory.write_u8(0x10 + i, i as u8 + 1).unwrap();
+        }
+
+        // Copie en deux tranches, sans terminateur
+        let mut state = StringState { source: 0x10, destination: 0x80, remaining: 0x20, value: 0 };
+        let first = StringUnit::move_step(&mut memory, &mut state, 1, None, 0x18).unwrap();
+        assert!(!first.completed);
+        assert_eq!(state, StringState { source: 0x28, destination: 0x98, remaining: 8, value: 0 });
+        let second = StringUnit::move_step(&mut memory, &mut state, 1, None, 0x18).unwrap();
+        assert!(second.completed && second.source_exhausted);
+        assert_eq!(second.bytes_processed, 8);
+        assert_eq!(memory.read_u8(0x9F).unwrap(), 0x20);
+
+        // Une erreur de bus laisse la progression sur l'élément fautif
+        let mut state = StringState { source: 0xFC, destination: 0x00, remaining: 4, value: 0 };
+        assert!(StringUnit::move_step(&mut memory, &mut state, 4, None, 4).is_err());
+        assert_eq!((state.source, state.remaining), (0x100, 3));
+
+        let mut state = StringState { source: 0x10, destination: 0x80, remaining: 0x20, value: 0x05 };
+        let found = StringUnit::scan_step(&memory, &mut state, 1, None, 0x20).unwrap();
+        assert!(found.found && found.completed);
+        assert_eq!((state.source, state.remaining), (0x15, 0x1B));
+    }
+}
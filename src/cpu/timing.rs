@@ -0,0 +1,308 @@
+//! Durée des instructions du V60
+//!
+//! En mode précis ([`CpuTiming::Accurate`]), la durée d'une instruction est
+//! sa durée de base avec des opérandes registre, plus le coût de ses
+//! opérandes mémoire : calcul d'adresse selon le mode d'adressage, puis un
+//! cycle de bus de 2 horloges par demi-mot transféré (le V60 du Model 2 a
+//! un bus de données de 16 bits). La multiplication et la division
+//! s'arrêtent dès que les bits significatifs de l'opérande sont traités,
+//! et un branchement conditionnel pris vide le pipeline : ces parts
+//! variables sont ajoutées à l'exécution.
+//!
+//! Le mode rapide ([`CpuTiming::Fast`]) garde une durée fixe par famille
+//! d'instructions, sans coût mémoire : certains jeux y tournent mieux tant
+//! que le reste de la machine n'est pas cadencé aussi finement.
+
+use serde::{Deserialize, Serialize};
+
+use super::instructions::{DataSize, Instruction, Operand};
+
+/// Horloges d'un cycle de bus
+const BUS_CYCLE: u32 = 2;
+
+/// Horloges perdues par un branchement pris (pipeline vidé)
+pub const BRANCH_TAKEN_PENALTY: u32 = 2;
+
+/// Calcul des durées d'instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuTiming {
+    /// Durée par instruction, opérandes mémoire et parts variables compris
+    #[default]
+    Accurate,
+    /// Durée fixe par famille d'instructions
+    Fast,
+}
+
+/// Cycles de bus d'un transfert de `size` sur le bus de 16 bits
+fn bus_cycles(size: DataSize) -> u32 {
+    match size {
+        DataSize::Byte | DataSize::Word => BUS_CYCLE,
+        DataSize::DWord => 2 * BUS_CYCLE,
+    }
+}
+
+/// Coût d'un opérande de `size` : calcul d'adresse et transfert
+pub fn operand_cycles(operand: &Operand, size: DataSize) -> u32 {
+    let address = match operand {
+        Operand::Register(_) | Operand::Immediate(_) => return 0,
+        Operand::Direct(_) | Operand::Indirect(_) => 0,
+        Operand::IndirectOffset(..) | Operand::PcRelative(_) => 1,
+        Operand::IndirectIndexed(..) => 2,
+    };
+    address + bus_cycles(size)
+}
+
+/// Durée de base avec des opérandes registre
+fn base_cycles(instruction: &Instruction) -> u32 {
+    match instruction {
+        Instruction::Nop | Instruction::Mov { .. } | Instruction::Halt => 1,
+        Instruction::Add { .. } | Instruction::Sub { .. } | Instruction::And { .. } | Instruction::Or { .. } |
+        Instruction::Xor { .. } | Instruction::Not { .. } | Instruction::Compare { .. } | Instruction::Test { .. } => 2,
+        Instruction::Shl { .. } | Instruction::Shr { .. } | Instruction::RotateLeft { .. } | Instruction::RotateRight { .. } => 4,
+
+        // Parts variables ajoutées à l'exécution : `multiply_cycles`, `divide_cycles`
+        Instruction::Mul { .. } => 8,
+        Instruction::Div { .. } => 6,
+
+        Instruction::Load { .. } | Instruction::Store { .. } => 1,
+        Instruction::Jump { .. } => 3,
+        Instruction::JumpConditional { .. } => 2,
+        Instruction::Call { .. } => 4 + 2 * BUS_CYCLE,
+        Instruction::Return => 3 + 2 * BUS_CYCLE,
+
+        Instruction::FloatAdd { .. } | Instruction::FloatSub { .. } => 12,
+        Instruction::FloatMul { .. } => 16,
+        Instruction::FloatDiv { .. } => 42,
+        Instruction::FloatCompare { .. } => 8,
+
+        Instruction::BitTest { .. } | Instruction::BitSet { .. } | Instruction::BitClear { .. } => 3,
+        Instruction::BitScan { .. } => 6,
+
+        // Lecture des octets du champ, plus leur écriture pour l'insertion
+        Instruction::ExtractBitField { .. } => 5 + 2 * BUS_CYCLE,
+        Instruction::InsertBitField { .. } => 6 + 4 * BUS_CYCLE,
+        Instruction::CompareBitField { .. } => 6 + 2 * BUS_CYCLE,
+
+        Instruction::Push { .. } | Instruction::Pop { .. } => 1 + 2 * BUS_CYCLE,
+        Instruction::PushMultiple { registers } | Instruction::PopMultiple { registers } => {
+            3 + registers.count_ones() * 2 * BUS_CYCLE
+        }
+
+        // Premier élément compris ; les suivants : `string_element_cycles`
+        Instruction::StringMove { .. } | Instruction::StringFill { .. } => 6 + string_element_cycles(instruction),
+        Instruction::StringCompare { .. } | Instruction::StringScan { .. } => 8 + string_element_cycles(instruction),
+
+        Instruction::LoadControlRegister { .. } => 8,
+        Instruction::StoreControlRegister { .. } => 6,
+        Instruction::InvalidateTLB => 25,
+        Instruction::FlushCache => 50,
+
+        Instruction::SoftwareInterrupt { .. } => 24 + 4 * BUS_CYCLE,
+        Instruction::ReturnFromInterrupt | Instruction::InterruptReturn => 16 + 4 * BUS_CYCLE,
+        Instruction::EnableInterrupts | Instruction::DisableInterrupts => 4,
+
+        Instruction::TestAndSet { .. } => 6,
+        Instruction::CompareAndSwap { .. } => 10,
+        Instruction::BcdAdd { .. } | Instruction::BcdSub { .. } => 8,
+        Instruction::Unknown { .. } => 1,
+    }
+}
+
+/// Opérandes de données lus ou écrits par l'instruction, avec leur taille ;
+/// les cibles de branchement ne transfèrent rien
+fn data_operands(instruction: &Instruction) -> ([Option<&Operand>; 3], DataSize) {
+    use Instruction::*;
+    match instruction {
+        Add { dest, src1, src2 } | Sub { dest, src1, src2 } | Mul { dest, src1, src2 } | Div { dest, src1, src2 } |
+        And { dest, src1, src2 } | Or { dest, src1, src2 } | Xor { dest, src1, src2 } |
+        FloatAdd { dest, src1, src2 } | FloatMul { dest, src1, src2 } | FloatSub { dest, src1, src2 } |
+        FloatDiv { dest, src1, src2 } | BcdAdd { dest, src1, src2 } | BcdSub { dest, src1, src2 } => {
+            ([Some(dest), Some(src1), Some(src2)], DataSize::DWord)
+        }
+        Not { dest, src } | Mov { dest, src } | BitScan { dest, src } | TestAndSet { dest, src } => {
+            ([Some(dest), Some(src), None], DataSize::DWord)
+        }
+        Shl { dest, src, shift: count } | Shr { dest, src, shift: count } |
+        RotateLeft { dest, src, count } | RotateRight { dest, src, count } => ([Some(dest), Some(src), Some(count)], DataSize::DWord),
+        Compare { src1, src2 } | Test { src1, src2 } | FloatCompare { src1, src2 } => ([Some(src1), Some(src2), None], DataSize::DWord),
+        Load { dest, address, size } => ([Some(dest), Some(address), None], *size),
+        Store { src, address, size } => ([Some(src), Some(address), None], *size),
+        BitTest { src, bit } => ([Some(src), Some(bit), None], DataSize::DWord),
+        BitSet { dest, bit } | BitClear { dest, bit } => ([Some(dest), Some(bit), None], DataSize::DWord),
+        ExtractBitField { dest: value, offset, length, .. } | InsertBitField { src: value, offset, length, .. } |
+        CompareBitField { src: value, offset, length, .. } => ([Some(value), Some(offset), Some(length)], DataSize::DWord),
+        Push { src } => ([Some(src), None, None], DataSize::DWord),
+        Pop { dest } => ([Some(dest), None, None], DataSize::DWord),
+        LoadControlRegister { dest, .. } => ([Some(dest), None, None], DataSize::DWord),
+        StoreControlRegister { src, .. } => ([Some(src), None, None], DataSize::DWord),
+        CompareAndSwap { dest, compare, new_value } => ([Some(dest), Some(compare), Some(new_value)], DataSize::DWord),
+        _ => ([None, None, None], DataSize::DWord),
+    }
+}
+
+/// Durée d'une instruction en mode précis, hors parts variables
+pub fn instruction_cycles(instruction: &Instruction) -> u32 {
+    let (operands, size) = data_operands(instruction);
+    let memory: u32 = operands.iter().flatten().map(|operand| operand_cycles(operand, size)).sum();
+    base_cycles(instruction) + memory
+}
+
+/// Durée de chaque élément d'une instruction de chaîne : une lecture et
+/// une écriture (ou deux lectures), une seule pour la recherche et le
+/// remplissage
+pub fn string_element_cycles(instruction: &Instruction) -> u32 {
+    match instruction {
+        Instruction::StringMove { size } | Instruction::StringCompare { size } => 2 * bus_cycles(*size),
+        Instruction::StringScan { size } | Instruction::StringFill { size } => bus_cycles(*size),
+        _ => 0,
+    }
+}
+
+/// Bits significatifs d'un opérande signé (sans les bits de signe répétés)
+fn significant_bits(value: u32) -> u32 {
+    let magnitude = if (value as i32) < 0 { !value } else { value };
+    32 - magnitude.leading_zeros()
+}
+
+/// Part variable d'une multiplication : deux bits du multiplicateur par
+/// horloge, arrêt anticipé sur ses bits significatifs
+pub fn multiply_cycles(multiplier: u32) -> u32 {
+    significant_bits(multiplier).div_ceil(2)
+}
+
+/// Part variable d'une division : un bit de quotient par horloge, à partir
+/// du premier bit significatif du dividende
+pub fn divide_cycles(dividend: u32) -> u32 {
+    significant_bits(dividend)
+}
+
+/// Durée fixe d'une instruction en mode rapide
+pub fn fast_cycles(instruction: &Instruction) -> u32 {
+    match instruction {
+        // Instructions simples - 1 cycle
+        Instruction::Nop | 
+        Instruction::Mov { .. } => 1,
+        
+        // Instructions arithmétiques simples - 2 cycles
+        Instruction::Add { .. } | 
+        Instruction::Sub { .. } |
+        Instruction::And { .. } |
+        Instruction::Or { .. } |
+        Instruction::Xor { .. } |
+        Instruction::Not { .. } => 2,
+        
+        // Instructions de déplacement - 3 cycles
+        Instruction::Shl { .. } |
+        Instruction::Shr { .. } |
+        Instruction::RotateLeft { .. } |
+        Instruction::RotateRight { .. } => 3,
+        
+        // Multiplication - 10 cycles
+        Instruction::Mul { .. } => 10,
+        
+        // Division - 20 cycles
+        Instruction::Div { .. } => 20,
+        
+        // Accès mémoire - 3 cycles
+        Instruction::Load { .. } |
+        Instruction::Store { .. } => 3,
+        
+        // Branchements - 2 cycles si pas pris, 4 si pris
+        Instruction::Jump { .. } |
+        Instruction::JumpConditional { .. } => 4,
+        
+        // Appels et retours - 5 cycles
+        Instruction::Call { .. } |
+        Instruction::Return => 5,
+        
+        // Instructions de comparaison - 2 cycles
+        Instruction::Compare { .. } |
+        Instruction::Test { .. } => 2,
+        
+        // Instructions flottantes - 8-12 cycles
+        Instruction::FloatAdd { .. } |
+        Instruction::FloatSub { .. } => 8,
+        Instruction::FloatMul { .. } => 10,
+        Instruction::FloatDiv { .. } => 15,
+        Instruction::FloatCompare { .. } => 6,
+        
+        // Instructions de manipulation de bits - 2-4 cycles
+        Instruction::BitTest { .. } |
+        Instruction::BitSet { .. } |
+        Instruction::BitClear { .. } => 2,
+        Instruction::BitScan { .. } => 4,
+        
+        // Champs de bits - 6 cycles
+        Instruction::ExtractBitField { .. } |
+        Instruction::InsertBitField { .. } |
+        Instruction::CompareBitField { .. } => 6,
+        
+        // Instructions de pile - 2-5 cycles
+        Instruction::Push { .. } |
+        Instruction::Pop { .. } => 2,
+        Instruction::PushMultiple { registers } => 2 + registers.count_ones(),
+        Instruction::PopMultiple { registers } => 2 + registers.count_ones(),
+        
+        // Instructions de chaîne - 5-15 cycles selon la taille
+        Instruction::StringMove { .. } |
+        Instruction::StringFill { .. } => 8,
+        Instruction::StringCompare { .. } => 10,
+        Instruction::StringScan { .. } => 12,
+        
+        // Instructions MMU et système - cycles élevés
+        Instruction::LoadControlRegister { .. } |
+        Instruction::StoreControlRegister { .. } => 15,
+        Instruction::InvalidateTLB => 25,
+        Instruction::FlushCache => 50,
+        
+        // Instructions d'interruption - cycles variables
+        Instruction::SoftwareInterrupt { .. } => 20,
+        Instruction::ReturnFromInterrupt => 15,
+        Instruction::EnableInterrupts |
+        Instruction::DisableInterrupts => 3,
+        
+        // Instructions de synchronisation - cycles élevés
+        Instruction::TestAndSet { .. } => 8,
+        Instruction::CompareAndSwap { .. } => 12,
+        
+        // Instructions BCD - cycles moyens
+        Instruction::BcdAdd { .. } |
+        Instruction::BcdSub { .. } => 6,
+        
+        // Instructions système
+        Instruction::Halt => 1,
+        Instruction::InterruptReturn => 10,
+        
+        // Instruction inconnue - 1 cycle par défaut
+        Instruction::Unknown { .. } => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_operands_and_variable_parts() {
+        let add = |src2| Instruction::Add { dest: Operand::Register(1), src1: Operand::Register(2), src2 };
+        let registers = instruction_cycles(&add(Operand::Register(3)));
+        assert_eq!(registers, 2);
+        // Deux demi-mots sur le bus, plus le déplacement à ajouter
+        assert_eq!(instruction_cycles(&add(Operand::Indirect(3))), registers + 4);
+        assert_eq!(instruction_cycles(&add(Operand::IndirectOffset(3, 8))), registers + 5);
+
+        let load = |size| Instruction::Load { dest: Operand::Register(1), address: Operand::Direct(0x100), size };
+        assert_eq!(instruction_cycles(&load(DataSize::Byte)) + BUS_CYCLE, instruction_cycles(&load(DataSize::DWord)));
+
+        // Arrêt anticipé : petits opérandes plus rapides, signe compris
+        assert_eq!(multiply_cycles(0), 0);
+        assert_eq!(multiply_cycles(3), 1);
+        assert_eq!(multiply_cycles(0xFFFF_FFFE), 1);
+        assert_eq!(multiply_cycles(0x4000_0000), 16);
+        assert!(divide_cycles(0x7FFF_FFFF) > divide_cycles(100));
+
+        // Mode rapide : une durée par famille, sans coût mémoire
+        assert_eq!(fast_cycles(&add(Operand::Indirect(3))), fast_cycles(&add(Operand::Register(3))));
+    }
+}
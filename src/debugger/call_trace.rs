@@ -0,0 +1,108 @@
+//! Affichage de la pile d'appels et du journal des fonctions
+//!
+//! Les adresses sont annotées avec la table de symboles quand elle est
+//! disponible. Les horodatages sont donnés en cycles du V60 et en
+//! microsecondes de temps émulé.
+
+use crate::cpu::{CallEvent, CallEventKind, CallFrame, CallKind};
+use crate::MAIN_CPU_FREQUENCY;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use super::SymbolMap;
+
+/// Temps émulé en microsecondes correspondant à un nombre de cycles
+pub fn cycles_to_micros(cycles: u64) -> u64 {
+    cycles * 1_000_000 / MAIN_CPU_FREQUENCY as u64
+}
+
+/// Pile d'appels formatée, la trame la plus récente en premier
+pub fn format_call_stack(frames: &[CallFrame], symbols: &SymbolMap) -> String {
+    let mut out = String::new();
+    for (depth, frame) in frames.iter().enumerate().rev() {
+        let marker = match frame.kind {
+            CallKind::Call => "",
+            CallKind::Interrupt => " [interruption]",
+        };
+        let _ = writeln!(
+            out,
+            "#{:<3} {} (retour {}, SP 0x{:08X}){}",
+            depth,
+            symbols.describe(frame.target),
+            symbols.describe(frame.return_address),
+            frame.stack_pointer,
+            marker
+        );
+    }
+    out
+}
+
+/// Écrit le journal des entrées/sorties de fonction, indenté par profondeur
+pub fn write_call_trace<W: Write>(events: &[CallEvent], symbols: &SymbolMap, mut out: W) -> io::Result<()> {
+    for event in events {
+        let arrow = match event.kind {
+            CallEventKind::Enter => "->",
+            CallEventKind::Exit => "<-",
+        };
+        let marker = match event.call_kind {
+            CallKind::Call => "",
+            CallKind::Interrupt => " [interruption]",
+        };
+        writeln!(
+            out,
+            "{:>12} {:>10}us {:indent$}{} {} ({}){}",
+            event.cycle,
+            cycles_to_micros(event.cycle),
+            "",
+            arrow,
+            symbols.describe(event.function),
+            symbols.describe(event.site),
+            marker,
+            indent = event.depth * 2
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols() -> SymbolMap {
+        let mut symbols = SymbolMap::new();
+        symbols.insert(0x1000, "main_loop");
+        symbols.insert(0x2000, "update_cars");
+        symbols
+    }
+
+    #[test]
+    fn test_call_stack_most_recent_first() {
+        let frames = [
+            CallFrame { target: 0x1000, return_address: 0x0104, stack_pointer: 0x100, cycle: 0, kind: CallKind::Call },
+            CallFrame { target: 0x2000, return_address: 0x1008, stack_pointer: 0xFC, cycle: 5, kind: CallKind::Interrupt },
+        ];
+
+        let text = format_call_stack(&frames, &symbols());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("#1"));
+        assert!(lines[0].contains("update_cars (retour main_loop+0x8"));
+        assert!(lines[0].ends_with("[interruption]"));
+        assert!(lines[1].contains("main_loop (retour 0x00000104"));
+    }
+
+    #[test]
+    fn test_trace_has_timestamps_and_indentation() {
+        let events = [
+            CallEvent { kind: CallEventKind::Enter, function: 0x2000, site: 0x1008, cycle: 25_000, depth: 1, call_kind: CallKind::Call },
+            CallEvent { kind: CallEventKind::Exit, function: 0x2000, site: 0x1008, cycle: 50_000, depth: 1, call_kind: CallKind::Call },
+        ];
+
+        let mut out = Vec::new();
+        write_call_trace(&events, &symbols(), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.contains("      25000       1000us   -> update_cars (main_loop+0x8)"));
+        assert!(text.contains("2000us   <- update_cars"));
+    }
+}
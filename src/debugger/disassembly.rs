@@ -0,0 +1,110 @@
+//! Désassemblage annoté avec la table de symboles
+//!
+//! Chaque ligne donne l'adresse de l'instruction (précédée d'une étiquette
+//! quand un symbole y commence) et, pour les branchements et appels, le nom
+//! de la destination.
+
+use crate::cpu::{DecodedInstruction, Instruction, Operand, V60InstructionDecoder};
+use crate::memory::MemoryInterface;
+use anyhow::Result;
+
+use super::SymbolMap;
+
+/// Taille maximale d'une instruction V60 lue pour le décodage
+const MAX_INSTRUCTION_SIZE: u32 = 8;
+
+/// Destination d'un branchement ou d'un appel, telle que l'exécute le CPU
+pub fn branch_target(instruction: &Instruction) -> Option<u32> {
+    let target = match instruction {
+        Instruction::Jump { target }
+        | Instruction::JumpConditional { target, .. }
+        | Instruction::Call { target } => target,
+        _ => return None,
+    };
+
+    match target {
+        Operand::Direct(address) | Operand::Immediate(address) => Some(*address),
+        _ => None,
+    }
+}
+
+/// Ligne de désassemblage d'une instruction
+pub fn format_instruction(instruction: &DecodedInstruction, symbols: &SymbolMap) -> String {
+    let mut line = format!(
+        "{:08X} {:<24} {:?}",
+        instruction.address,
+        symbols.describe(instruction.address),
+        instruction.instruction
+    );
+
+    if let Some(target) = branch_target(&instruction.instruction) {
+        line.push_str(&format!(" ; -> {}", symbols.describe(target)));
+    }
+    line
+}
+
+/// Désassemble `count` instructions à partir de `start`.
+///
+/// Une ligne d'étiquette `nom:` précède chaque instruction sur laquelle un
+/// symbole commence.
+pub fn disassemble<M: MemoryInterface>(
+    memory: &M,
+    start: u32,
+    count: usize,
+    symbols: &SymbolMap,
+) -> Result<Vec<String>> {
+    let mut decoder = V60InstructionDecoder::new();
+    let mut lines = Vec::new();
+    let mut address = start;
+
+    for _ in 0..count {
+        let mut data = [0u8; MAX_INSTRUCTION_SIZE as usize];
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = memory.peek_u8(address.wrapping_add(i as u32)).unwrap_or(0xFF);
+        }
+
+        let instruction = decoder.decode(&data, address)?;
+        if let Some(name) = symbols.name(address) {
+            lines.push(format!("{}:", name));
+        }
+        lines.push(format_instruction(&instruction, symbols));
+        address = address.wrapping_add(instruction.size.max(1));
+    }
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_target_is_named() {
+        let mut symbols = SymbolMap::new();
+        symbols.insert(0x1000, "main_loop");
+        symbols.insert(0x2000, "update_cars");
+
+        let call = DecodedInstruction {
+            address: 0x1004,
+            instruction: Instruction::Call { target: Operand::Direct(0x2000) },
+            size: 4,
+            cycles: 5,
+        };
+
+        let line = format_instruction(&call, &symbols);
+        assert!(line.starts_with("00001004 main_loop+0x4"));
+        assert!(line.ends_with("; -> update_cars"));
+    }
+
+    #[test]
+    fn test_non_branch_has_no_target() {
+        let nop = DecodedInstruction {
+            address: 0x1000,
+            instruction: Instruction::Nop,
+            size: 1,
+            cycles: 1,
+        };
+        assert_eq!(branch_target(&nop.instruction), None);
+        assert!(!format_instruction(&nop, &SymbolMap::new()).contains("->"));
+    }
+}
@@ -0,0 +1,210 @@
+//! Commandes de vidage et de chargement de la mémoire
+//!
+//! Syntaxe (adresses en hexadécimal ou symboles, voir [`SymbolMap::resolve`]) :
+//!
+//! - `dump <début> <longueur> <fichier>` : écrit une plage d'adresses ;
+//! - `load <fichier> <adresse>` : charge un binaire à une adresse ;
+//! - `snapshot <ram|vram|aram> <fichier>` : écrit une RAM complète ;
+//! - `restore <ram|vram|aram> <fichier>` : recharge une RAM complète ;
+//! - `snapshot <nom>` : capture la RAM principale sous un nom ;
+//! - `diff <avant> <après> [filtre] [8|16|32]` : compare deux captures et
+//!   affine les candidats (voir [`MemorySearch`]) ;
+//! - `candidates [clear|<fichier>]` : liste, oublie ou exporte en CSV les
+//!   candidats.
+//!
+//! Utile pour extraire des ressources décompressées par le jeu, tester des
+//! correctifs sans reconstruire les ROMs ou retrouver les variables du jeu.
+
+use crate::memory::{MemoryRegion, Model2Memory};
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::{parse_diff_options, DiffFilter, MemorySearch, SymbolMap, ValueWidth};
+
+/// Commande mémoire du débogueur
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryCommand {
+    Dump { start: u32, length: usize, path: PathBuf },
+    Load { path: PathBuf, address: u32 },
+    Snapshot { region: MemoryRegion, path: PathBuf },
+    Restore { region: MemoryRegion, path: PathBuf },
+    Capture { name: String },
+    Diff { before: String, after: String, filter: DiffFilter, width: ValueWidth },
+    Candidates(CandidatesAction),
+}
+
+/// Action de la commande `candidates`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CandidatesAction {
+    List,
+    Clear,
+    Export(PathBuf),
+}
+
+/// Région de RAM désignée par son nom court
+fn parse_region(name: &str) -> Result<MemoryRegion> {
+    match name.to_ascii_lowercase().as_str() {
+        "ram" => Ok(MemoryRegion::MainRam),
+        "vram" => Ok(MemoryRegion::VideoRam),
+        "aram" => Ok(MemoryRegion::AudioRam),
+        _ => bail!("Région inconnue '{}' (ram, vram ou aram)", name),
+    }
+}
+
+impl MemoryCommand {
+    /// Analyse une ligne de commande
+    pub fn parse(line: &str, symbols: &SymbolMap) -> Result<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let usage = |syntax: &str| anyhow!("Usage: {}", syntax);
+
+        match words.as_slice() {
+            ["dump", start, length, path] => {
+                let length = symbols.resolve(length).context("Longueur invalide")? as usize;
+                Ok(Self::Dump { start: symbols.resolve(start)?, length, path: path.into() })
+            }
+            ["dump", ..] => Err(usage("dump <début> <longueur> <fichier>")),
+            ["load", path, address] => Ok(Self::Load { path: path.into(), address: symbols.resolve(address)? }),
+            ["load", ..] => Err(usage("load <fichier> <adresse>")),
+            ["snapshot", region, path] => Ok(Self::Snapshot { region: parse_region(region)?, path: path.into() }),
+            ["snapshot", name] => Ok(Self::Capture { name: name.to_string() }),
+            ["snapshot", ..] => Err(usage("snapshot <nom> | snapshot <ram|vram|aram> <fichier>")),
+            ["restore", region, path] => Ok(Self::Restore { region: parse_region(region)?, path: path.into() }),
+            ["restore", ..] => Err(usage("restore <ram|vram|aram> <fichier>")),
+            ["diff", before, after, options @ ..] => {
+                let (filter, width) = parse_diff_options(options)?;
+                Ok(Self::Diff { before: before.to_string(), after: after.to_string(), filter, width })
+            }
+            ["diff", ..] => Err(usage("diff <avant> <après> [changed|unchanged|increased|decreased|+N|-N] [8|16|32]")),
+            ["candidates"] => Ok(Self::Candidates(CandidatesAction::List)),
+            ["candidates", "clear"] => Ok(Self::Candidates(CandidatesAction::Clear)),
+            ["candidates", path] => Ok(Self::Candidates(CandidatesAction::Export(path.into()))),
+            ["candidates", ..] => Err(usage("candidates [clear|<fichier>]")),
+            [command, ..] => Err(anyhow!("Commande inconnue: '{}'", command)),
+            [] => Err(anyhow!("Commande vide")),
+        }
+    }
+
+    /// Exécute la commande et retourne un compte rendu ; les captures nommées
+    /// et les candidats sont conservés dans `search`
+    pub fn execute(&self, memory: &mut Model2Memory, search: &mut MemorySearch) -> Result<String> {
+        match self {
+            Self::Dump { start, length, path } => {
+                let file = std::fs::File::create(path).with_context(|| format!("Impossible d'écrire {}", path.display()))?;
+                let mut out = std::io::BufWriter::new(file);
+                memory.dump_range(*start, *length, &mut out)?;
+                out.flush().with_context(|| format!("Impossible d'écrire {}", path.display()))?;
+                Ok(format!("0x{:X} octets depuis 0x{:08X} écrits dans {}", length, start, path.display()))
+            }
+            Self::Load { path, address } => {
+                let data = read_file(path)?;
+                memory.load_binary(*address, &data)?;
+                Ok(format!("0x{:X} octets chargés à 0x{:08X} depuis {}", data.len(), address, path.display()))
+            }
+            Self::Snapshot { region, path } => {
+                let data = memory.snapshot_region(*region)?;
+                write_file(path, &data)?;
+                Ok(format!("{:?} écrite dans {} (0x{:X} octets)", region, path.display(), data.len()))
+            }
+            Self::Restore { region, path } => {
+                let data = read_file(path)?;
+                memory.restore_region(*region, &data)?;
+                Ok(format!("{:?} restaurée depuis {} (0x{:X} octets)", region, path.display(), data.len()))
+            }
+            Self::Capture { name } => {
+                search.capture(name, memory.snapshot_region(MemoryRegion::MainRam)?);
+                Ok(format!("RAM principale capturée sous '{}'", name))
+            }
+            Self::Diff { before, after, filter, width } => {
+                search.diff(before, after, *filter, *width)?;
+                Ok(format!("{} -> {} ({}) : {}", before, after, filter, search.summary()))
+            }
+            Self::Candidates(CandidatesAction::List) => Ok(search.summary()),
+            Self::Candidates(CandidatesAction::Clear) => {
+                search.clear_candidates();
+                Ok("Candidats oubliés".to_string())
+            }
+            Self::Candidates(CandidatesAction::Export(path)) => {
+                let file = std::fs::File::create(path).with_context(|| format!("Impossible d'écrire {}", path.display()))?;
+                search.export(std::io::BufWriter::new(file))?;
+                Ok(format!("{} candidat(s) exportés dans {}", search.candidates().len(), path.display()))
+            }
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Impossible de lire {}", path.display()))
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    std::fs::write(path, data).with_context(|| format!("Impossible d'écrire {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let mut symbols = SymbolMap::new();
+        symbols.insert(0x1000, "car_table");
+
+        assert_eq!(
+            MemoryCommand::parse("dump car_table 0x40 cars.bin", &symbols).unwrap(),
+            MemoryCommand::Dump { start: 0x1000, length: 0x40, path: "cars.bin".into() }
+        );
+        assert_eq!(
+            MemoryCommand::parse("snapshot VRAM vram.bin", &symbols).unwrap(),
+            MemoryCommand::Snapshot { region: MemoryRegion::VideoRam, path: "vram.bin".into() }
+        );
+        assert!(MemoryCommand::parse("dump 0x1000", &symbols).is_err());
+        assert!(MemoryCommand::parse("snapshot rom x.bin", &symbols).is_err());
+        assert!(MemoryCommand::parse("poke 1 2", &symbols).is_err());
+
+        assert_eq!(
+            MemoryCommand::parse("snapshot lives", &symbols).unwrap(),
+            MemoryCommand::Capture { name: "lives".into() }
+        );
+        assert_eq!(
+            MemoryCommand::parse("diff a b -1 16", &symbols).unwrap(),
+            MemoryCommand::Diff { before: "a".into(), after: "b".into(), filter: DiffFilter::Delta(-1), width: ValueWidth::Word }
+        );
+        assert_eq!(
+            MemoryCommand::parse("candidates out.csv", &symbols).unwrap(),
+            MemoryCommand::Candidates(CandidatesAction::Export("out.csv".into()))
+        );
+        assert!(MemoryCommand::parse("diff a", &symbols).is_err());
+    }
+
+    #[test]
+    fn test_capture_and_diff_main_ram() {
+        let symbols = SymbolMap::new();
+        let mut memory = Model2Memory::new();
+        let mut search = MemorySearch::new();
+        let mut run = |line: &str, memory: &mut Model2Memory| MemoryCommand::parse(line, &symbols).unwrap().execute(memory, &mut search);
+
+        run("snapshot before", &mut memory).unwrap();
+        memory.load_binary(0x1234, &[5]).unwrap();
+        run("snapshot after", &mut memory).unwrap();
+        let report = run("diff before after +5", &mut memory).unwrap();
+        assert!(report.contains("1 candidat(s)") && report.contains("0x00001234"), "{}", report);
+    }
+
+    #[test]
+    fn test_dump_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("patch.bin");
+        let symbols = SymbolMap::new();
+
+        let mut memory = Model2Memory::new();
+        let mut search = MemorySearch::new();
+        std::fs::write(&path, [0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+        let load = format!("load {} 0x100", path.display());
+        MemoryCommand::parse(&load, &symbols).unwrap().execute(&mut memory, &mut search).unwrap();
+
+        let dump = format!("dump 0xFE 6 {}", path.display());
+        MemoryCommand::parse(&dump, &symbols).unwrap().execute(&mut memory, &mut search).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), [0, 0, 0xDE, 0xAD, 0xBE, 0xEF]);
+    }
+}
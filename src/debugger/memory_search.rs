@@ -0,0 +1,298 @@
+//! Recherche de variables du jeu par comparaison d'instantanés de la RAM
+//!
+//! On capture la RAM principale sous un nom à des moments choisis (avant et
+//! après avoir perdu une vie, par exemple), puis on compare deux captures
+//! avec un filtre : valeurs modifiées, inchangées, en hausse, en baisse ou
+//! d'un écart donné. Chaque comparaison affine les candidats de la
+//! précédente (même largeur de valeur) jusqu'à isoler la variable ; les
+//! candidats s'exportent en CSV et s'affichent sur la carte de la RAM de
+//! l'incrustation.
+
+use anyhow::{anyhow, bail, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::memory::ByteOrder;
+
+/// Candidats détaillés dans un compte rendu
+const SUMMARY_LINES: usize = 16;
+
+/// Critère de comparaison de deux instantanés
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffFilter {
+    Changed,
+    Unchanged,
+    Increased,
+    Decreased,
+    /// Écart signé exact entre les deux valeurs
+    Delta(i64),
+}
+
+impl DiffFilter {
+    /// `changed`, `unchanged`, `increased`, `decreased` ou un écart signé (`+1`, `-3`)
+    pub fn parse(word: &str) -> Option<Self> {
+        match word {
+            "changed" => Some(Self::Changed),
+            "unchanged" => Some(Self::Unchanged),
+            "increased" => Some(Self::Increased),
+            "decreased" => Some(Self::Decreased),
+            _ if word.starts_with(['+', '-']) => word.parse().ok().map(Self::Delta),
+            _ => None,
+        }
+    }
+
+    fn matches(self, delta: i64) -> bool {
+        match self {
+            Self::Changed => delta != 0,
+            Self::Unchanged => delta == 0,
+            Self::Increased => delta > 0,
+            Self::Decreased => delta < 0,
+            Self::Delta(expected) => delta == expected,
+        }
+    }
+}
+
+impl fmt::Display for DiffFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Changed => write!(f, "changed"),
+            Self::Unchanged => write!(f, "unchanged"),
+            Self::Increased => write!(f, "increased"),
+            Self::Decreased => write!(f, "decreased"),
+            Self::Delta(delta) => write!(f, "{:+}", delta),
+        }
+    }
+}
+
+/// Largeur des valeurs comparées (alignées sur leur taille)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueWidth {
+    #[default]
+    Byte,
+    Word,
+    DWord,
+}
+
+impl ValueWidth {
+    /// `8`, `16` ou `32` bits
+    pub fn parse(word: &str) -> Option<Self> {
+        match word {
+            "8" => Some(Self::Byte),
+            "16" => Some(Self::Word),
+            "32" => Some(Self::DWord),
+            _ => None,
+        }
+    }
+
+    pub fn bytes(self) -> usize {
+        match self {
+            Self::Byte => 1,
+            Self::Word => 2,
+            Self::DWord => 4,
+        }
+    }
+
+    /// Valeur little-endian à un décalage
+    fn read(self, data: &[u8], offset: usize) -> u32 {
+        ByteOrder::Little.read_uint(&data[offset..offset + self.bytes()]) as u32
+    }
+
+    /// Écart signé, les valeurs étant lues comme des entiers signés
+    fn delta(self, before: u32, after: u32) -> i64 {
+        let signed = |value: u32| match self {
+            Self::Byte => value as i8 as i64,
+            Self::Word => value as i16 as i64,
+            Self::DWord => value as i32 as i64,
+        };
+        signed(after) - signed(before)
+    }
+}
+
+/// Adresse retenue et ses valeurs dans les deux instantanés
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    pub address: u32,
+    pub before: u32,
+    pub after: u32,
+    pub delta: i64,
+}
+
+/// Instantanés nommés de la RAM principale et candidats courants
+#[derive(Debug, Clone, Default)]
+pub struct MemorySearch {
+    snapshots: BTreeMap<String, Vec<u8>>,
+    candidates: Option<Vec<Candidate>>,
+    width: ValueWidth,
+    /// Taille de la RAM comparée, pour la carte de l'incrustation
+    ram_size: usize,
+}
+
+impl MemorySearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre (ou remplace) un instantané
+    pub fn capture(&mut self, name: &str, data: Vec<u8>) {
+        self.ram_size = self.ram_size.max(data.len());
+        self.snapshots.insert(name.to_string(), data);
+    }
+
+    /// Noms des instantanés
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.snapshots.keys().map(String::as_str)
+    }
+
+    fn snapshot(&self, name: &str) -> Result<&[u8]> {
+        self.snapshots.get(name).map(Vec::as_slice).ok_or_else(|| {
+            let known: Vec<&str> = self.names().collect();
+            anyhow!("Instantané inconnu '{}' (disponibles: {})", name, known.join(", "))
+        })
+    }
+
+    /// Compare deux instantanés ; les candidats d'une comparaison précédente
+    /// de même largeur sont affinés, sinon toute la RAM est parcourue.
+    /// Retourne le nombre de candidats restants.
+    pub fn diff(&mut self, before: &str, after: &str, filter: DiffFilter, width: ValueWidth) -> Result<usize> {
+        let (old, new) = (self.snapshot(before)?, self.snapshot(after)?);
+        let length = old.len().min(new.len());
+        let size = width.bytes();
+        let candidate = |offset: usize| {
+            if offset + size > length {
+                return None;
+            }
+            let (before, after) = (width.read(old, offset), width.read(new, offset));
+            let delta = width.delta(before, after);
+            filter.matches(delta).then_some(Candidate { address: offset as u32, before, after, delta })
+        };
+
+        let candidates: Vec<Candidate> = match self.candidates.as_ref() {
+            Some(previous) if self.width == width => {
+                previous.iter().filter_map(|c| candidate(c.address as usize)).collect()
+            }
+            _ => (0..length).step_by(size).filter_map(candidate).collect(),
+        };
+
+        let count = candidates.len();
+        self.candidates = Some(candidates);
+        self.width = width;
+        Ok(count)
+    }
+
+    /// Candidats de la dernière comparaison
+    pub fn candidates(&self) -> &[Candidate] {
+        self.candidates.as_deref().unwrap_or_default()
+    }
+
+    /// Oublie les candidats : la prochaine comparaison repart de toute la RAM
+    pub fn clear_candidates(&mut self) {
+        self.candidates = None;
+    }
+
+    /// Compte rendu des candidats, les premiers détaillés
+    pub fn summary(&self) -> String {
+        let candidates = self.candidates();
+        let digits = self.width.bytes() * 2;
+        let mut text = format!("{} candidat(s) sur {} bits", candidates.len(), self.width.bytes() * 8);
+        for candidate in candidates.iter().take(SUMMARY_LINES) {
+            text.push_str(&format!(
+                "\n  0x{:08X}: 0x{:0digits$X} -> 0x{:0digits$X} ({:+})",
+                candidate.address, candidate.before, candidate.after, candidate.delta
+            ));
+        }
+        if candidates.len() > SUMMARY_LINES {
+            text.push_str(&format!("\n  ... {} de plus", candidates.len() - SUMMARY_LINES));
+        }
+        text
+    }
+
+    /// Écrit les candidats en CSV
+    pub fn export<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "adresse,largeur,avant,apres,ecart")?;
+        for candidate in self.candidates() {
+            writeln!(
+                out,
+                "0x{:08X},{},0x{:X},0x{:X},{}",
+                candidate.address,
+                self.width.bytes() * 8,
+                candidate.before,
+                candidate.after,
+                candidate.delta
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Répartition des candidats sur `bins` tranches égales de la RAM,
+    /// normalisée par la tranche la plus peuplée (carte de l'incrustation)
+    pub fn density(&self, bins: usize) -> Vec<f32> {
+        let mut counts = vec![0u32; bins.max(1)];
+        let span = self.ram_size.max(1).div_ceil(counts.len());
+        for candidate in self.candidates() {
+            let bin = (candidate.address as usize / span).min(counts.len() - 1);
+            counts[bin] += 1;
+        }
+        let max = counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+        counts.iter().map(|&count| count as f32 / max).collect()
+    }
+}
+
+/// Arguments optionnels de `diff` : filtre et largeur, dans un ordre quelconque
+pub fn parse_diff_options(words: &[&str]) -> Result<(DiffFilter, ValueWidth)> {
+    let (mut filter, mut width) = (DiffFilter::Changed, ValueWidth::default());
+    for word in words {
+        if let Some(parsed) = ValueWidth::parse(word) {
+            width = parsed;
+        } else if let Some(parsed) = DiffFilter::parse(word) {
+            filter = parsed;
+        } else {
+            bail!("Option inconnue '{}' (changed, unchanged, increased, decreased, +N, -N, 8, 16 ou 32)", word);
+        }
+    }
+    Ok((filter, width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_successive_diffs_narrow_candidates() {
+        let mut search = MemorySearch::new();
+        let mut ram = vec![0u8; 64];
+        ram[0x10] = 3; // vies
+        ram[0x20] = 7; // compteur qui bouge sans cesse
+        search.capture("start", ram.clone());
+
+        ram[0x10] = 2;
+        ram[0x20] = 8;
+        search.capture("died", ram.clone());
+        assert_eq!(search.diff("start", "died", DiffFilter::Changed, ValueWidth::Byte).unwrap(), 2);
+
+        ram[0x20] = 9;
+        search.capture("idle", ram.clone());
+        assert_eq!(search.diff("died", "idle", DiffFilter::Unchanged, ValueWidth::Byte).unwrap(), 1);
+        assert_eq!(search.candidates()[0], Candidate { address: 0x10, before: 2, after: 2, delta: 0 });
+
+        // Écart signé sur 16 bits : nouvelle recherche sur toute la RAM
+        ram[0x30..0x32].copy_from_slice(&0xFFFFu16.to_le_bytes());
+        search.capture("word", ram);
+        assert_eq!(search.diff("idle", "word", DiffFilter::Delta(-1), ValueWidth::Word).unwrap(), 1);
+        assert_eq!(search.candidates()[0].address, 0x30);
+        assert!(search.diff("idle", "nope", DiffFilter::Changed, ValueWidth::Byte).is_err());
+
+        let mut csv = Vec::new();
+        search.export(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "adresse,largeur,avant,apres,ecart\n0x00000030,16,0x0,0xFFFF,-1\n");
+        assert!(search.summary().starts_with("1 candidat(s) sur 16 bits"));
+        assert_eq!(search.density(4), vec![0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_parse_diff_options() {
+        assert_eq!(parse_diff_options(&[]).unwrap(), (DiffFilter::Changed, ValueWidth::Byte));
+        assert_eq!(parse_diff_options(&["32", "-3"]).unwrap(), (DiffFilter::Delta(-3), ValueWidth::DWord));
+        assert!(parse_diff_options(&["bigger"]).is_err());
+    }
+}
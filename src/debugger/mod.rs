@@ -0,0 +1,18 @@
+//! Outils de débogage du code de jeu
+//!
+//! Table de symboles chargée depuis un fichier utilisateur (texte ou ELF),
+//! désassemblage annoté, affichage de la pile d'appels et des traces
+//! d'exécution du V60, vidage et chargement de la mémoire, recherche de
+//! variables par comparaison d'instantanés de la RAM.
+
+pub mod symbols;
+pub mod call_trace;
+pub mod disassembly;
+pub mod memory_commands;
+pub mod memory_search;
+
+pub use symbols::*;
+pub use call_trace::*;
+pub use disassembly::*;
+pub use memory_commands::*;
+pub use memory_search::*;
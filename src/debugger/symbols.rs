@@ -0,0 +1,517 @@
+//! Table de symboles (adresse → nom)
+//!
+//! Deux formats sont acceptés, détectés automatiquement au chargement :
+//!
+//! - un simple texte avec une ligne `adresse nom` par symbole ; l'adresse est
+//!   en hexadécimal, avec ou sans préfixe `0x`. Les lignes vides et les
+//!   commentaires (`#` ou `;`) sont ignorés ;
+//! - la table des symboles (`.symtab`, à défaut `.dynsym`) d'un exécutable
+//!   ELF 32 ou 64 bits, petit ou grand boutiste.
+//!
+//! Les symboles servent à annoter le désassemblage, la pile d'appels et les
+//! traces, et à poser des points d'arrêt par nom (`update_cars+0x10`).
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::{EmulatorError, Result};
+
+/// Signature d'un fichier ELF
+const ELF_MAGIC: &[u8; 4] = b"\x7FELF";
+
+/// Types de section ELF contenant des symboles
+const SHT_SYMTAB: u32 = 2;
+const SHT_DYNSYM: u32 = 11;
+
+/// Types de symbole ELF
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+
+/// Nature d'un symbole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymbolKind {
+    /// Nature inconnue (fichier texte)
+    #[default]
+    Unknown,
+    /// Code
+    Function,
+    /// Variable
+    Object,
+}
+
+/// Symbole nommé
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+
+    /// Taille en octets (0 si inconnue)
+    pub size: u32,
+}
+
+/// Table de symboles du code de jeu
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMap {
+    symbols: BTreeMap<u32, Symbol>,
+}
+
+impl SymbolMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Charge une table depuis un fichier texte ou ELF
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path).map_err(|e| std::io::Error::new(e.kind(), format!("{} : {}", path.display(), e)))?;
+        Self::from_bytes(&data).map_err(|e| match e {
+            EmulatorError::Symbols(message) => malformed(format!("{} : {}", path.display(), message)),
+            e => e,
+        })
+    }
+
+    /// Analyse le contenu d'un fichier, ELF ou texte
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.starts_with(ELF_MAGIC) {
+            return Self::parse_elf(data);
+        }
+
+        let text = std::str::from_utf8(data).map_err(|_| malformed("Fichier ni ELF ni texte"))?;
+        Self::parse(text)
+    }
+
+    /// Analyse le contenu d'un fichier `adresse nom`
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut map = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let (Some(address), Some(name)) = (fields.next(), fields.next()) else {
+                return Err(malformed(format!("Ligne {}: nom de symbole manquant", number + 1)));
+            };
+
+            let address = parse_hex(address)
+                .ok_or_else(|| malformed(format!("Ligne {}: adresse invalide '{}'", number + 1, address)))?;
+            map.insert(address, name);
+        }
+
+        Ok(map)
+    }
+
+    /// Extrait la table des symboles d'un exécutable ELF
+    pub fn parse_elf(data: &[u8]) -> Result<Self> {
+        let elf = ElfReader::new(data)?;
+        let sections = elf.sections()?;
+
+        // .symtab contient tous les symboles ; .dynsym seulement les exportés
+        let table = sections
+            .iter()
+            .find(|section| section.kind == SHT_SYMTAB)
+            .or_else(|| sections.iter().find(|section| section.kind == SHT_DYNSYM))
+            .ok_or_else(|| malformed("Aucune table de symboles dans le fichier ELF"))?;
+        let strings = sections
+            .get(table.link as usize)
+            .ok_or_else(|| malformed("Table de chaînes des symboles introuvable"))?;
+
+        // Table entière dans le fichier : les champs d'une entrée se lisent
+        // ensuite sans débordement
+        let end = table.offset.checked_add(table.size).filter(|&end| end <= data.len());
+        if end.is_none() {
+            return Err(malformed(format!("Table de symboles hors du fichier (offset 0x{:X})", table.offset)));
+        }
+
+        let mut map = Self::new();
+        let entry_size = if elf.is_64 { 24 } else { 16 };
+        let count = table.size / entry_size;
+
+        for index in 1..count {
+            let offset = table.offset + index * entry_size;
+            let (name, value, size, info, shndx) = if elf.is_64 {
+                (
+                    elf.u32(offset)?,
+                    elf.u64(offset + 8)?,
+                    elf.u64(offset + 16)?,
+                    elf.u8(offset + 4)?,
+                    elf.u16(offset + 6)?,
+                )
+            } else {
+                (
+                    elf.u32(offset)?,
+                    elf.u32(offset + 4)? as u64,
+                    elf.u32(offset + 8)? as u64,
+                    elf.u8(offset + 12)?,
+                    elf.u16(offset + 14)?,
+                )
+            };
+
+            let kind = match info & 0x0F {
+                STT_FUNC => SymbolKind::Function,
+                STT_OBJECT => SymbolKind::Object,
+                _ => continue,
+            };
+            // Symboles non définis (importés)
+            if shndx == 0 {
+                continue;
+            }
+
+            let name = elf.string(strings.offset.checked_add(name as usize))?;
+            if name.is_empty() {
+                continue;
+            }
+
+            map.symbols.insert(
+                value as u32,
+                Symbol { name: name.to_string(), kind, size: size as u32 },
+            );
+        }
+
+        Ok(map)
+    }
+
+    /// Ajoute ou remplace un symbole de nature inconnue
+    pub fn insert(&mut self, address: u32, name: impl Into<String>) {
+        self.insert_symbol(address, Symbol { name: name.into(), kind: SymbolKind::Unknown, size: 0 });
+    }
+
+    /// Ajoute ou remplace un symbole
+    pub fn insert_symbol(&mut self, address: u32, symbol: Symbol) {
+        self.symbols.insert(address, symbol);
+    }
+
+    /// Fusionne une autre table ; ses symboles l'emportent
+    pub fn merge(&mut self, other: SymbolMap) {
+        self.symbols.extend(other.symbols);
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Parcourt les symboles par adresse croissante
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Symbol)> + '_ {
+        self.symbols.iter().map(|(&address, symbol)| (address, symbol))
+    }
+
+    /// Symbole défini exactement à une adresse
+    pub fn symbol(&self, address: u32) -> Option<&Symbol> {
+        self.symbols.get(&address)
+    }
+
+    /// Nom exact d'une adresse
+    pub fn name(&self, address: u32) -> Option<&str> {
+        self.symbol(address).map(|symbol| symbol.name.as_str())
+    }
+
+    /// Adresse d'un symbole par son nom
+    pub fn address_of(&self, name: &str) -> Option<u32> {
+        self.symbols
+            .iter()
+            .find(|(_, symbol)| symbol.name == name)
+            .map(|(&address, _)| address)
+    }
+
+    /// Symbole le plus proche à ou avant une adresse, avec le décalage.
+    ///
+    /// Une adresse au-delà de la taille connue d'un symbole ne lui est pas
+    /// attribuée.
+    pub fn lookup(&self, address: u32) -> Option<(&str, u32)> {
+        let (&start, symbol) = self.symbols.range(..=address).next_back()?;
+        let offset = address - start;
+        if symbol.size != 0 && offset >= symbol.size {
+            return None;
+        }
+        Some((symbol.name.as_str(), offset))
+    }
+
+    /// Adresse formatée : `nom`, `nom+0x10`, ou `0x00001234` sans symbole
+    pub fn describe(&self, address: u32) -> String {
+        match self.lookup(address) {
+            Some((name, 0)) => name.to_string(),
+            Some((name, offset)) => format!("{}+0x{:X}", name, offset),
+            None => format!("0x{:08X}", address),
+        }
+    }
+
+    /// Adresse désignée par `0x1234`, `1234`, `nom` ou `nom+0x10`
+    /// (points d'arrêt, fenêtre mémoire)
+    pub fn resolve(&self, spec: &str) -> Result<u32> {
+        let spec = spec.trim();
+        let (base, offset) = match spec.split_once('+') {
+            Some((base, offset)) => {
+                let offset = parse_hex(offset.trim()).ok_or_else(|| malformed(format!("Décalage invalide: '{}'", offset)))?;
+                (base.trim(), offset)
+            }
+            None => (spec, 0),
+        };
+
+        let address = match self.address_of(base) {
+            Some(address) => address,
+            None => parse_hex(base).ok_or_else(|| malformed(format!("Symbole inconnu: '{}'", base)))?,
+        };
+        Ok(address.wrapping_add(offset))
+    }
+}
+
+/// Nombre hexadécimal avec ou sans préfixe `0x`
+fn parse_hex(text: &str) -> Option<u32> {
+    let digits = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+    u32::from_str_radix(digits, 16).ok()
+}
+
+/// Table mal formée ou tronquée, symbole inconnu
+fn malformed(message: impl Into<String>) -> EmulatorError {
+    EmulatorError::Symbols(message.into())
+}
+
+/// En-tête de section ELF (champs utiles)
+#[derive(Debug, Clone, Copy)]
+struct ElfSection {
+    kind: u32,
+    offset: usize,
+    size: usize,
+    link: u32,
+}
+
+/// Lecture bornée d'un fichier ELF
+struct ElfReader<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    big_endian: bool,
+}
+
+impl<'a> ElfReader<'a> {
+    fn new(data: &'a [u8]) -> Result<Self> {
+        if data.len() < 0x34 || !data.starts_with(ELF_MAGIC) {
+            return Err(malformed("En-tête ELF invalide"));
+        }
+
+        let is_64 = match data[4] {
+            1 => false,
+            2 => true,
+            class => return Err(malformed(format!("Classe ELF inconnue: {}", class))),
+        };
+        let big_endian = match data[5] {
+            1 => false,
+            2 => true,
+            order => return Err(malformed(format!("Boutisme ELF inconnu: {}", order))),
+        };
+        Ok(Self { data, is_64, big_endian })
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N]> {
+        let mut bytes: [u8; N] = offset
+            .checked_add(N)
+            .and_then(|end| self.data.get(offset..end))
+            .and_then(|slice| slice.try_into().ok())
+            .ok_or_else(|| malformed(format!("Fichier ELF tronqué (offset 0x{:X})", offset)))?;
+        if !self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8> {
+        Ok(self.bytes::<1>(offset)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.bytes(offset)?))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.bytes(offset)?))
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.bytes(offset)?))
+    }
+
+    /// Offset dans le fichier, qu'il soit codé sur 32 ou 64 bits
+    fn offset(&self, offset: usize) -> Result<usize> {
+        if self.is_64 {
+            Ok(self.u64(offset)? as usize)
+        } else {
+            Ok(self.u32(offset)? as usize)
+        }
+    }
+
+    /// Chaîne terminée par un zéro ; None si son offset déborde
+    fn string(&self, offset: Option<usize>) -> Result<&'a str> {
+        let tail = offset
+            .and_then(|offset| self.data.get(offset..))
+            .ok_or_else(|| malformed("Nom de symbole hors du fichier"))?;
+        let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+        std::str::from_utf8(&tail[..end]).map_err(|_| malformed("Nom de symbole invalide"))
+    }
+
+    fn sections(&self) -> Result<Vec<ElfSection>> {
+        let (table, entry_size, count) = if self.is_64 {
+            (self.offset(0x28)?, self.u16(0x3A)? as usize, self.u16(0x3C)? as usize)
+        } else {
+            (self.offset(0x20)?, self.u16(0x2E)? as usize, self.u16(0x30)? as usize)
+        };
+
+        (0..count)
+            .map(|index| {
+                let header = index
+                    .checked_mul(entry_size)
+                    .and_then(|offset| table.checked_add(offset))
+                    .filter(|&header| header < self.data.len())
+                    .ok_or_else(|| malformed(format!("En-tête de section {} hors du fichier", index)))?;
+                let (offset, size) = if self.is_64 {
+                    (self.offset(header + 0x18)?, self.offset(header + 0x20)?)
+                } else {
+                    (self.offset(header + 0x10)?, self.offset(header + 0x14)?)
+                };
+                let link = if self.is_64 { self.u32(header + 0x28)? } else { self.u32(header + 0x18)? };
+
+                Ok(ElfSection { kind: self.u32(header + 4)?, offset, size, link })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ELF32 petit boutiste minimal : sections nulle, .symtab, .strtab
+    fn sample_elf() -> Vec<u8> {
+        let strtab = b"\0main_loop\0car_table\0ext\0";
+        let mut symtab = vec![0u8; 16]; // symbole nul
+        let mut symbol = |name: u32, value: u32, size: u32, info: u8, shndx: u16| {
+            symtab.extend_from_slice(&name.to_le_bytes());
+            symtab.extend_from_slice(&value.to_le_bytes());
+            symtab.extend_from_slice(&size.to_le_bytes());
+            symtab.push(info);
+            symtab.push(0);
+            symtab.extend_from_slice(&shndx.to_le_bytes());
+        };
+        symbol(1, 0x1000, 0x40, 0x12, 1); // main_loop : FUNC GLOBAL
+        symbol(11, 0x8000, 0x10, 0x11, 1); // car_table : OBJECT GLOBAL
+        symbol(21, 0x9000, 0, 0x12, 0); // ext : non défini
+
+        let symtab_offset = 0x34;
+        let strtab_offset = symtab_offset + symtab.len();
+        let sections_offset = strtab_offset + strtab.len();
+
+        let mut elf = vec![0u8; 0x34];
+        elf[..4].copy_from_slice(ELF_MAGIC);
+        elf[4] = 1; // ELF32
+        elf[5] = 1; // petit boutiste
+        elf[0x20..0x24].copy_from_slice(&(sections_offset as u32).to_le_bytes());
+        elf[0x2E..0x30].copy_from_slice(&40u16.to_le_bytes());
+        elf[0x30..0x32].copy_from_slice(&3u16.to_le_bytes());
+        elf.extend_from_slice(&symtab);
+        elf.extend_from_slice(strtab);
+
+        let mut section = |kind: u32, offset: usize, size: usize, link: u32| {
+            let mut header = [0u8; 40];
+            header[4..8].copy_from_slice(&kind.to_le_bytes());
+            header[0x10..0x14].copy_from_slice(&(offset as u32).to_le_bytes());
+            header[0x14..0x18].copy_from_slice(&(size as u32).to_le_bytes());
+            header[0x18..0x1C].copy_from_slice(&link.to_le_bytes());
+            elf.extend_from_slice(&header);
+        };
+        section(0, 0, 0, 0);
+        section(SHT_SYMTAB, symtab_offset, symtab.len(), 2);
+        section(3, strtab_offset, strtab.len(), 0);
+        elf
+    }
+
+    #[test]
+    fn test_parse_text_symbols() {
+        let map = SymbolMap::parse(
+            "# Daytona USA\n\
+             0x00001000 main_loop\n\
+             2000 update_cars\n\
+             \n\
+             ; fin\n",
+        )
+        .unwrap();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.name(0x1000), Some("main_loop"));
+        assert_eq!(map.name(0x2000), Some("update_cars"));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_lines() {
+        assert!(SymbolMap::parse("zzzz main").is_err());
+        assert!(SymbolMap::parse("0x1000").is_err());
+    }
+
+    #[test]
+    fn test_describe_uses_nearest_symbol() {
+        let mut map = SymbolMap::new();
+        map.insert(0x1000, "main_loop");
+
+        assert_eq!(map.describe(0x1000), "main_loop");
+        assert_eq!(map.describe(0x1010), "main_loop+0x10");
+        assert_eq!(map.describe(0x0800), "0x00000800");
+    }
+
+    #[test]
+    fn test_parse_elf_symbol_table() {
+        let map = SymbolMap::from_bytes(&sample_elf()).unwrap();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.symbol(0x1000).unwrap().kind, SymbolKind::Function);
+        assert_eq!(map.symbol(0x8000).unwrap().kind, SymbolKind::Object);
+        assert_eq!(map.address_of("ext"), None);
+
+        // La taille connue borne l'attribution des adresses
+        assert_eq!(map.describe(0x8004), "car_table+0x4");
+        assert_eq!(map.describe(0x8010), "0x00008010");
+    }
+
+    #[test]
+    fn test_truncated_elf_is_rejected() {
+        let elf = sample_elf();
+        assert!(SymbolMap::from_bytes(&elf[..0x40]).is_err());
+    }
+
+    #[test]
+    fn test_elf_offsets_past_the_file_are_errors() {
+        let is_malformed = |result: Result<SymbolMap>| matches!(result, Err(EmulatorError::Symbols(_)));
+        let elf = sample_elf();
+        let sections = elf.len() - 3 * 40;
+
+        // Fichier coupé au milieu des en-têtes de section
+        assert!(is_malformed(SymbolMap::parse_elf(&elf[..sections + 50])));
+
+        // Table des sections annoncée au bout de l'espace d'adressage
+        let mut corrupt = elf.clone();
+        corrupt[0x20..0x24].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(is_malformed(SymbolMap::parse_elf(&corrupt)));
+
+        // Taille de .symtab démesurée
+        let mut corrupt = elf.clone();
+        corrupt[sections + 40 + 0x14..sections + 40 + 0x18].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(is_malformed(SymbolMap::parse_elf(&corrupt)));
+
+        // Nom de symbole hors du fichier
+        let mut corrupt = elf;
+        corrupt[0x34 + 16..0x34 + 20].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(is_malformed(SymbolMap::parse_elf(&corrupt)));
+    }
+
+    #[test]
+    fn test_resolve_breakpoint_spec() {
+        let mut map = SymbolMap::new();
+        map.insert(0x1000, "main_loop");
+
+        assert_eq!(map.resolve("main_loop").unwrap(), 0x1000);
+        assert_eq!(map.resolve("main_loop+0x10").unwrap(), 0x1010);
+        assert_eq!(map.resolve("0x2000").unwrap(), 0x2000);
+        assert!(map.resolve("missing").is_err());
+    }
+}
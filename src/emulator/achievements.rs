@@ -0,0 +1,469 @@
+//! Succès et événements de jeu
+//!
+//! Chaque jeu peut avoir un fichier de définitions (`<jeu>.toml` dans le
+//! répertoire des succès). Une définition associe un identifiant et un titre
+//! à une condition sur la mémoire du jeu, évaluée à chaque frame :
+//!
+//! ```toml
+//! [[achievement]]
+//! id = "perfect"
+//! title = "Perfect"
+//! description = "Gagner un round sans être touché"
+//! condition = "u8[0x00501200] == 2 && u16[0x00501210] > prev(u16[0x00501210])"
+//! frames = 1      # frames consécutives où la condition doit être vraie
+//! repeat = false  # true : événement déclenché à chaque fois
+//! ```
+//!
+//! Une condition compare des valeurs (`u8[adresse]`, `u16[...]`, `u32[...]`,
+//! leur valeur à la frame précédente `prev(u8[...])`, ou une constante) avec
+//! `==`, `!=`, `<`, `<=`, `>`, `>=`, et combine les comparaisons avec `&&`,
+//! `||`, `!` et des parenthèses. Les adresses et constantes s'écrivent en
+//! décimal ou en hexadécimal (`0x`).
+//!
+//! Un succès n'est débloqué qu'une fois ; un événement répétable est
+//! déclenché de nouveau dès que sa condition redevient vraie. Le frontend
+//! récupère les déclenchements avec [`Achievements::take_events`] pour les
+//! afficher.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::memory::{CompareOp, MemoryInterface, MemoryWatch, WatchCondition, WatchOperand, WatchSet, WatchSize};
+
+/// Définition d'un succès ou d'un événement, telle qu'écrite dans le fichier du jeu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AchievementDefinition {
+    pub id: String,
+    pub title: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    /// Condition sur la mémoire du jeu (voir la documentation du module)
+    pub condition: String,
+
+    /// Frames consécutives où la condition doit être vraie
+    #[serde(default = "default_frames")]
+    pub frames: u32,
+
+    /// Déclenché à chaque fois que la condition redevient vraie
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+fn default_frames() -> u32 {
+    1
+}
+
+/// Contenu d'un fichier de définitions
+#[derive(Debug, Default, Deserialize)]
+struct AchievementFile {
+    #[serde(default, rename = "achievement")]
+    achievements: Vec<AchievementDefinition>,
+}
+
+/// Déclenchement d'un succès ou d'un événement, à afficher par le frontend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AchievementEvent {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+
+    /// Succès débloqué (par opposition à un événement répétable)
+    pub unlocked: bool,
+
+    /// Frame émulée du déclenchement
+    pub frame: u64,
+}
+
+/// Définition prête à être évaluée
+#[derive(Debug, Clone)]
+struct Tracked {
+    definition: AchievementDefinition,
+    condition: WatchCondition,
+
+    /// Frames consécutives où la condition a été vraie
+    streak: u32,
+
+    /// Succès débloqué : il n'est plus évalué
+    unlocked: bool,
+}
+
+/// Succès et événements du jeu chargé
+#[derive(Debug, Clone, Default)]
+pub struct Achievements {
+    tracked: Vec<Tracked>,
+    watches: WatchSet,
+    events: Vec<AchievementEvent>,
+
+    /// Une erreur de lecture a déjà été journalisée
+    read_error_logged: bool,
+}
+
+impl Achievements {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prépare des définitions ; échoue sur la première condition invalide
+    pub fn from_definitions(definitions: Vec<AchievementDefinition>) -> Result<Self> {
+        let mut achievements = Self::new();
+        for definition in definitions {
+            if achievements.tracked.iter().any(|t| t.definition.id == definition.id) {
+                bail!("Identifiant de succès en double: '{}'", definition.id);
+            }
+            let condition = parse_condition(&definition.condition, &mut achievements.watches)
+                .with_context(|| format!("Condition invalide pour '{}'", definition.id))?;
+            achievements.tracked.push(Tracked { definition, condition, streak: 0, unlocked: false });
+        }
+        Ok(achievements)
+    }
+
+    /// Analyse le contenu d'un fichier de définitions
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: AchievementFile = toml::from_str(text).context("Fichier de succès invalide")?;
+        Self::from_definitions(file.achievements)
+    }
+
+    /// Charge un fichier de définitions
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Impossible de lire {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Succès de {}", path.display()))
+    }
+
+    /// Fichier de définitions d'un jeu dans le répertoire des succès
+    pub fn game_file(dir: &Path, game: &str) -> PathBuf {
+        dir.join(format!("{}.toml", game))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tracked.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracked.is_empty()
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = &AchievementDefinition> {
+        self.tracked.iter().map(|t| &t.definition)
+    }
+
+    /// Identifiants des succès débloqués
+    pub fn unlocked(&self) -> impl Iterator<Item = &str> {
+        self.tracked.iter().filter(|t| t.unlocked).map(|t| t.definition.id.as_str())
+    }
+
+    /// Marque des succès comme déjà débloqués (session précédente), sans événement
+    pub fn restore_unlocked<'a>(&mut self, ids: impl IntoIterator<Item = &'a str>) {
+        for id in ids {
+            if let Some(tracked) = self.tracked.iter_mut().find(|t| t.definition.id == id && !t.definition.repeat) {
+                tracked.unlocked = true;
+            }
+        }
+    }
+
+    /// Relève la mémoire et évalue les conditions de la frame `frame`
+    pub fn evaluate<M: MemoryInterface + ?Sized>(&mut self, memory: &M, frame: u64) {
+        if self.tracked.iter().all(|t| t.unlocked) {
+            return;
+        }
+        if let Err(e) = self.watches.update(memory) {
+            if !self.read_error_logged {
+                log::warn!("Succès: lecture mémoire impossible ({}), dernière valeur conservée", e);
+                self.read_error_logged = true;
+            }
+        }
+
+        for tracked in self.tracked.iter_mut().filter(|t| !t.unlocked) {
+            if !tracked.condition.evaluate(&self.watches) {
+                tracked.streak = 0;
+                continue;
+            }
+            tracked.streak = tracked.streak.saturating_add(1);
+            if tracked.streak != tracked.definition.frames.max(1) {
+                continue;
+            }
+
+            let definition = &tracked.definition;
+            tracked.unlocked = !definition.repeat;
+            log::info!("{}: {}", if tracked.unlocked { "Succès débloqué" } else { "Événement" }, definition.title);
+            self.events.push(AchievementEvent {
+                id: definition.id.clone(),
+                title: definition.title.clone(),
+                description: definition.description.clone(),
+                unlocked: tracked.unlocked,
+                frame,
+            });
+        }
+    }
+
+    /// Déclenchements depuis le dernier appel, dans l'ordre
+    pub fn take_events(&mut self) -> Vec<AchievementEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Élément lexical d'une condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(u32),
+    Ident(String),
+    Compare(CompareOp),
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c.is_ascii_alphanumeric() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let word = &text[start..end];
+            let token = if c.is_ascii_digit() {
+                Token::Number(parse_number(word)?)
+            } else {
+                Token::Ident(word.to_ascii_lowercase())
+            };
+            tokens.push(token);
+            continue;
+        }
+
+        chars.next();
+        let next = chars.peek().map(|&(_, c)| c);
+        let (token, pair) = match (c, next) {
+            ('=', Some('=')) => (Token::Compare(CompareOp::Eq), true),
+            ('!', Some('=')) => (Token::Compare(CompareOp::Ne), true),
+            ('<', Some('=')) => (Token::Compare(CompareOp::Le), true),
+            ('>', Some('=')) => (Token::Compare(CompareOp::Ge), true),
+            ('&', Some('&')) => (Token::And, true),
+            ('|', Some('|')) => (Token::Or, true),
+            ('<', _) => (Token::Compare(CompareOp::Lt), false),
+            ('>', _) => (Token::Compare(CompareOp::Gt), false),
+            ('!', _) => (Token::Not, false),
+            ('(', _) => (Token::LeftParen, false),
+            (')', _) => (Token::RightParen, false),
+            ('[', _) => (Token::LeftBracket, false),
+            (']', _) => (Token::RightBracket, false),
+            _ => bail!("Caractère inattendu '{}' en position {}", c, start),
+        };
+        if pair {
+            chars.next();
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn parse_number(word: &str) -> Result<u32> {
+    let parsed = match word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => word.parse(),
+    };
+    parsed.map_err(|_| anyhow!("Nombre invalide: '{}'", word))
+}
+
+/// Analyse une condition ; les valeurs qu'elle lit sont ajoutées à `watches`
+pub fn parse_condition(text: &str, watches: &mut WatchSet) -> Result<WatchCondition> {
+    let mut parser = ConditionParser { tokens: tokenize(text)?, position: 0, watches };
+    let condition = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        bail!("Élément inattendu après la condition: {:?}", token);
+    }
+    Ok(condition)
+}
+
+/// Analyse descendante : `||` lie moins fort que `&&`, lui-même moins que `!`
+struct ConditionParser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    watches: &'a mut WatchSet,
+}
+
+impl ConditionParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => bail!("{:?} attendu, {:?} trouvé", expected, token),
+            None => bail!("{:?} attendu en fin de condition", expected),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<WatchCondition> {
+        let mut terms = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { WatchCondition::Any(terms) })
+    }
+
+    fn parse_and(&mut self) -> Result<WatchCondition> {
+        let mut terms = vec![self.parse_unary()?];
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            terms.push(self.parse_unary()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { WatchCondition::All(terms) })
+    }
+
+    fn parse_unary(&mut self) -> Result<WatchCondition> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.next();
+                Ok(WatchCondition::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LeftParen) => {
+                self.next();
+                let condition = self.parse_or()?;
+                self.expect(Token::RightParen)?;
+                Ok(condition)
+            }
+            _ => {
+                let left = self.parse_operand()?;
+                let op = match self.next() {
+                    Some(Token::Compare(op)) => op,
+                    Some(token) => bail!("Comparaison attendue, {:?} trouvé", token),
+                    None => bail!("Comparaison attendue en fin de condition"),
+                };
+                let right = self.parse_operand()?;
+                Ok(WatchCondition::Compare { left, op, right })
+            }
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<WatchOperand> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(WatchOperand::Value(value)),
+            Some(Token::Ident(name)) if name == "prev" => {
+                self.expect(Token::LeftParen)?;
+                let index = self.parse_watch()?;
+                self.expect(Token::RightParen)?;
+                Ok(WatchOperand::Previous(index))
+            }
+            Some(Token::Ident(name)) => {
+                self.position -= 1;
+                let index = self.parse_watch().with_context(|| format!("Valeur inconnue '{}'", name))?;
+                Ok(WatchOperand::Current(index))
+            }
+            Some(token) => bail!("Valeur attendue, {:?} trouvé", token),
+            None => bail!("Valeur attendue en fin de condition"),
+        }
+    }
+
+    /// `u8[adresse]`, `u16[adresse]` ou `u32[adresse]`
+    fn parse_watch(&mut self) -> Result<usize> {
+        let size = match self.next() {
+            Some(Token::Ident(name)) => WatchSize::from_name(&name)
+                .ok_or_else(|| anyhow!("Taille inconnue '{}' (u8, u16 ou u32)", name))?,
+            Some(token) => bail!("Taille attendue (u8, u16 ou u32), {:?} trouvé", token),
+            None => bail!("Taille attendue en fin de condition"),
+        };
+        self.expect(Token::LeftBracket)?;
+        let address = match self.next() {
+            Some(Token::Number(address)) => address,
+            Some(token) => bail!("Adresse attendue, {:?} trouvé", token),
+            None => bail!("Adresse attendue en fin de condition"),
+        };
+        self.expect(Token::RightBracket)?;
+        Ok(self.watches.add(MemoryWatch::new(address, size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::Ram;
+
+    const DEFINITIONS: &str = r#"
+        [[achievement]]
+        id = "round"
+        title = "Round gagné"
+        condition = "u8[0x200] > prev(u8[0x200])"
+        repeat = true
+
+        [[achievement]]
+        id = "perfect"
+        title = "Perfect"
+        description = "Gagner un round sans être touché"
+        condition = "u8[0x200] >= 1 && !(u16[0x210] != 0xC8 || u8[0x220] == 1)"
+        frames = 2
+    "#;
+
+    #[test]
+    fn test_parse_condition_precedence() {
+        let mut watches = WatchSet::new();
+        let condition = parse_condition("u8[1] == 1 || u8[2] == 2 && !u8[3] == 3", &mut watches).unwrap();
+        let WatchCondition::Any(terms) = condition else { panic!("|| attendu au sommet") };
+        assert!(matches!(terms[1], WatchCondition::All(ref all) if matches!(all[1], WatchCondition::Not(_))));
+        assert_eq!(watches.len(), 3);
+
+        for invalid in ["u8[1] ==", "u64[1] == 0", "u8[1] = 1", "(u8[1] == 1", "u8[1] == 1 u8[2]", "u8[0xZZ] == 1"] {
+            assert!(parse_condition(invalid, &mut WatchSet::new()).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_achievements_fire_once_and_events_repeat() {
+        let mut memory = Ram::new(0x1000);
+        let mut achievements = Achievements::parse(DEFINITIONS).unwrap();
+        assert_eq!(achievements.len(), 2);
+        memory.write_u16(0x210, 0xC8).unwrap();
+
+        achievements.evaluate(&memory, 0);
+        assert!(achievements.take_events().is_empty());
+
+        // Round gagné : l'événement part tout de suite, le succès attend sa deuxième frame
+        memory.write_u8(0x200, 1).unwrap();
+        achievements.evaluate(&memory, 1);
+        let events = achievements.take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].id.as_str(), events[0].unlocked), ("round", false));
+
+        achievements.evaluate(&memory, 2);
+        let events = achievements.take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].id.as_str(), events[0].unlocked, events[0].frame), ("perfect", true, 2));
+        assert_eq!(achievements.unlocked().collect::<Vec<_>>(), vec!["perfect"]);
+
+        memory.write_u8(0x200, 2).unwrap();
+        achievements.evaluate(&memory, 3);
+        let events = achievements.take_events();
+        assert_eq!(events.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), vec!["round"]);
+    }
+
+    #[test]
+    fn test_duplicate_ids_rejected() {
+        let text = "[[achievement]]\nid = \"a\"\ntitle = \"A\"\ncondition = \"u8[0] == 0\"\n".repeat(2);
+        assert!(Achievements::parse(&text).is_err());
+    }
+}
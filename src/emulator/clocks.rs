@@ -0,0 +1,282 @@
+//! Horloges des processeurs
+//!
+//! Toutes les horloges de la carte dérivent d'un quartz maître
+//! ([`MASTER_CRYSTAL_FREQUENCY`]) par une table de rapports propre à chaque
+//! révision ([`ClockTable`]) : CPU principal, processeur de géométrie, SCSP
+//! et 68000 audio. Le quartz de la carte son est lui aussi exprimé par
+//! rapport au quartz maître, pour qu'un changement de ce dernier (essais de
+//! sous-cadencement, bornes PAL) garde tous les composants en proportion ;
+//! la fréquence native du SCSP, et donc la hauteur du son, suit.
+//!
+//! Chaque CPU reçoit ensuite un budget de cycles par frame proportionnel à
+//! son multiplicateur : au-dessus de 1, le V60 finit ses calculs plus tôt et
+//! les ralentissements du jeu disparaissent ; en dessous, il manque des
+//! cycles, ce qui met à l'épreuve le code dépendant du timing. Le
+//! multiplicateur global `cpu_speed_multiplier` s'applique aux deux CPU.
+
+use crate::config::EmulationConfig;
+use crate::memory::BoardRevision;
+use crate::MASTER_CRYSTAL_FREQUENCY;
+
+/// Cycles du 68000 audio par frame (60 Hz)
+pub const SOUND_CYCLES_PER_FRAME: u32 = crate::AUDIO_CPU_FREQUENCY / 60;
+
+/// Frames par seconde émulée
+const FRAME_RATE: u32 = 60;
+
+/// Multiplicateur le plus faible accepté
+pub const MIN_CLOCK_MULTIPLIER: f32 = 0.25;
+
+/// Multiplicateur le plus élevé accepté
+pub const MAX_CLOCK_MULTIPLIER: f32 = 4.0;
+
+/// Cycles de l'horloge du SCSP par échantillon produit
+pub const SCSP_CLOCK_DIVIDER: u32 = 512;
+
+/// Quartz maître le plus lent accepté (moitié de l'original)
+pub const MIN_MASTER_CRYSTAL: u32 = MASTER_CRYSTAL_FREQUENCY / 2;
+
+/// Quartz maître le plus rapide accepté (double de l'original)
+pub const MAX_MASTER_CRYSTAL: u32 = MASTER_CRYSTAL_FREQUENCY * 2;
+
+/// Rapport entre l'horloge d'un composant et le quartz maître
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockRatio {
+    pub multiplier: u32,
+    pub divider: u32,
+}
+
+impl ClockRatio {
+    pub const fn new(multiplier: u32, divider: u32) -> Self {
+        Self { multiplier, divider }
+    }
+
+    /// Fréquence obtenue à partir du quartz `crystal`
+    pub const fn of(self, crystal: u32) -> u32 {
+        (crystal as u64 * self.multiplier as u64 / self.divider as u64) as u32
+    }
+}
+
+/// Rapports des horloges d'une révision de carte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockTable {
+    /// CPU principal
+    pub main: ClockRatio,
+    /// Processeur de géométrie (TGP, ou SHARC sur la Model 2B)
+    pub geometry: ClockRatio,
+    /// SCSP (22,5792 MHz à l'origine)
+    pub scsp: ClockRatio,
+    /// 68000 audio, moitié de l'horloge du SCSP
+    pub sound: ClockRatio,
+}
+
+/// Carte son commune à toutes les révisions
+const SCSP_RATIO: ClockRatio = ClockRatio::new(225_792, 500_000);
+const SOUND_RATIO: ClockRatio = ClockRatio::new(112_896, 500_000);
+
+/// Model 2 et Model 2A : CPU à 25 MHz, TGP à 16 MHz
+pub const MODEL2_CLOCKS: ClockTable = ClockTable {
+    main: ClockRatio::new(1, 2),
+    geometry: ClockRatio::new(8, 25),
+    scsp: SCSP_RATIO,
+    sound: SOUND_RATIO,
+};
+
+/// Model 2B et Model 2C : CPU à 25 MHz, géométrie à 40 MHz
+pub const MODEL2B_CLOCKS: ClockTable = ClockTable {
+    main: ClockRatio::new(1, 2),
+    geometry: ClockRatio::new(4, 5),
+    scsp: SCSP_RATIO,
+    sound: SOUND_RATIO,
+};
+
+impl ClockTable {
+    pub fn for_board(board: BoardRevision) -> Self {
+        match board {
+            BoardRevision::Model2 | BoardRevision::Model2A => MODEL2_CLOCKS,
+            BoardRevision::Model2B | BoardRevision::Model2C => MODEL2B_CLOCKS,
+        }
+    }
+}
+
+/// Fréquences des composants, en Hz, dérivées d'un quartz maître
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceClocks {
+    pub crystal: u32,
+    pub main: u32,
+    pub geometry: u32,
+    pub scsp: u32,
+    pub sound: u32,
+}
+
+impl DeviceClocks {
+    /// Fréquences de `table` pour le quartz `crystal`, ramené dans les
+    /// bornes acceptées
+    pub fn new(crystal: u32, table: &ClockTable) -> Self {
+        let crystal = crystal.clamp(MIN_MASTER_CRYSTAL, MAX_MASTER_CRYSTAL);
+        Self {
+            crystal,
+            main: table.main.of(crystal),
+            geometry: table.geometry.of(crystal),
+            scsp: table.scsp.of(crystal),
+            sound: table.sound.of(crystal),
+        }
+    }
+
+    /// Fréquences d'origine d'une révision
+    pub fn stock(board: BoardRevision) -> Self {
+        Self::new(MASTER_CRYSTAL_FREQUENCY, &ClockTable::for_board(board))
+    }
+
+    /// Fréquence native du SCSP (44,1 kHz d'origine)
+    pub fn scsp_sample_rate(&self) -> u32 {
+        self.scsp / SCSP_CLOCK_DIVIDER
+    }
+
+    /// Vrai si le quartz maître est celui d'origine
+    pub fn is_stock(&self) -> bool {
+        self.crystal == MASTER_CRYSTAL_FREQUENCY
+    }
+
+    /// Description pour l'affichage, en MHz
+    pub fn describe(&self) -> String {
+        let mhz = |hz: u32| hz as f64 / 1_000_000.0;
+        format!(
+            "quartz {:.2} MHz : CPU {:.2} · géométrie {:.2} · SCSP {:.4} · 68000 {:.4} MHz",
+            mhz(self.crystal),
+            mhz(self.main),
+            mhz(self.geometry),
+            mhz(self.scsp),
+            mhz(self.sound)
+        )
+    }
+}
+
+impl Default for DeviceClocks {
+    fn default() -> Self {
+        Self::stock(BoardRevision::default())
+    }
+}
+
+/// Multiplicateurs effectifs du V60 et du 68000, et fréquences sur
+/// lesquelles ils s'appliquent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CpuClocks {
+    pub main: f32,
+    pub sound: f32,
+    /// Fréquences des composants, avant multiplicateurs
+    pub devices: DeviceClocks,
+}
+
+impl CpuClocks {
+    /// Multiplicateurs ramenés dans les bornes acceptées, horloges d'origine
+    pub fn new(main: f32, sound: f32) -> Self {
+        Self { main: clamp_multiplier(main), sound: clamp_multiplier(sound), devices: DeviceClocks::default() }
+    }
+
+    /// Multiplicateurs de la configuration, combinés au multiplicateur
+    /// global, et horloges de `board` tirées du quartz maître configuré
+    pub fn from_config(emulation: &EmulationConfig, board: BoardRevision) -> Self {
+        let global = emulation.cpu_speed_multiplier;
+        let crystal = emulation.master_clock_hz.unwrap_or(MASTER_CRYSTAL_FREQUENCY);
+        Self {
+            devices: DeviceClocks::new(crystal, &ClockTable::for_board(board)),
+            ..Self::new(global * emulation.main_cpu_clock, global * emulation.sound_cpu_clock)
+        }
+    }
+
+    /// Budget de cycles du V60 par frame
+    pub fn main_cycles_per_frame(&self) -> u32 {
+        ((self.devices.main / FRAME_RATE) as f32 * self.main).round() as u32
+    }
+
+    /// Budget de cycles du 68000 par frame (réservé à l'émulation LLE)
+    pub fn sound_cycles_per_frame(&self) -> u32 {
+        ((self.devices.sound / FRAME_RATE) as f32 * self.sound).round() as u32
+    }
+
+    /// Vrai si les deux CPU tournent à leur fréquence d'origine
+    pub fn is_stock(&self) -> bool {
+        self.main == 1.0 && self.sound == 1.0 && self.devices.is_stock()
+    }
+
+    /// Description pour l'affichage, en pourcentage de la fréquence d'origine
+    pub fn describe(&self) -> String {
+        let multipliers = format!("V60 {:.0} % · 68000 {:.0} %", self.main * 100.0, self.sound * 100.0);
+        if self.devices.is_stock() {
+            multipliers
+        } else {
+            format!("{} · {}", multipliers, self.devices.describe())
+        }
+    }
+
+    /// Multiplicateurs ramenés sur 0..1 entre les bornes, pour les jauges
+    pub fn normalized(&self) -> [f32; 2] {
+        let span = MAX_CLOCK_MULTIPLIER - MIN_CLOCK_MULTIPLIER;
+        [(self.main - MIN_CLOCK_MULTIPLIER) / span, (self.sound - MIN_CLOCK_MULTIPLIER) / span]
+    }
+}
+
+impl Default for CpuClocks {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+fn clamp_multiplier(value: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(MIN_CLOCK_MULTIPLIER, MAX_CLOCK_MULTIPLIER)
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EmulatorConfig;
+    use crate::emulator::CYCLES_PER_FRAME;
+
+    #[test]
+    fn test_budgets_follow_multipliers() {
+        let mut emulation = EmulatorConfig::default().emulation;
+        assert!(CpuClocks::from_config(&emulation, BoardRevision::Model2B).is_stock());
+
+        emulation.cpu_speed_multiplier = 2.0;
+        emulation.main_cpu_clock = 0.75;
+        emulation.sound_cpu_clock = 3.0;
+        let clocks = CpuClocks::from_config(&emulation, BoardRevision::Model2);
+        assert_eq!(clocks.main, 1.5);
+        assert_eq!(clocks.sound, MAX_CLOCK_MULTIPLIER);
+        assert_eq!(clocks.main_cycles_per_frame(), (CYCLES_PER_FRAME as f32 * 1.5).round() as u32);
+        assert_eq!(clocks.sound_cycles_per_frame(), SOUND_CYCLES_PER_FRAME * 4);
+        assert_eq!(clocks.describe(), "V60 150 % · 68000 400 %");
+        assert_eq!(clocks.normalized()[1], 1.0);
+
+        assert_eq!(CpuClocks::new(0.0, f32::NAN), CpuClocks::new(MIN_CLOCK_MULTIPLIER, 1.0));
+    }
+
+    #[test]
+    fn test_clocks_follow_master_crystal() {
+        let stock = DeviceClocks::stock(BoardRevision::Model2);
+        assert_eq!(stock.main, crate::MAIN_CPU_FREQUENCY);
+        assert_eq!(stock.sound, crate::AUDIO_CPU_FREQUENCY);
+        assert_eq!(stock.geometry, 16_000_000);
+        assert_eq!(stock.scsp_sample_rate(), crate::audio::SCSP_SAMPLE_RATE);
+        assert_eq!(DeviceClocks::stock(BoardRevision::Model2C).geometry, 40_000_000);
+
+        // Quartz ralenti de 4 % : tous les composants suivent
+        let mut emulation = EmulatorConfig::default().emulation;
+        emulation.master_clock_hz = Some(48_000_000);
+        let clocks = CpuClocks::from_config(&emulation, BoardRevision::Model2);
+        assert!(!clocks.is_stock());
+        assert_eq!(clocks.devices.main, 24_000_000);
+        assert_eq!(clocks.main_cycles_per_frame(), 400_000);
+        assert_eq!(clocks.devices.scsp_sample_rate(), 42_336);
+        assert_eq!(clocks.devices.sound * 2, clocks.devices.scsp);
+        assert!(clocks.describe().ends_with("quartz 48.00 MHz : CPU 24.00 · géométrie 15.36 · SCSP 21.6760 · 68000 10.8380 MHz"));
+
+        emulation.master_clock_hz = Some(1);
+        assert_eq!(CpuClocks::from_config(&emulation, BoardRevision::Model2).devices.crystal, MIN_MASTER_CRYSTAL);
+    }
+}
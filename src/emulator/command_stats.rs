@@ -0,0 +1,268 @@
+//! Statistiques des commandes GPU par frame
+//!
+//! [`GpuCommandStats`] compte chaque variante de [`GpuCommand`] transmise au
+//! GPU à chaque frame, ainsi que les mots de commande que le décodeur ne
+//! reconnaît pas, et garde les [`COMMAND_STATS_WINDOW`] dernières frames
+//! (moyenne et maximum par commande). Deux anomalies trahissent une liste
+//! d'affichage mal interprétée : plus aucun tracé alors que le jeu en
+//! envoyait régulièrement, et une poussée de commandes inconnues. Une
+//! anomalie est journalisée à son apparition et reste affichée dans le
+//! panneau d'état du GPU (F3) tant qu'elle dure.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+use crate::memory::GpuCommand;
+
+/// Frames conservées pour les moyennes glissantes (deux secondes)
+pub const COMMAND_STATS_WINDOW: usize = 120;
+
+/// Frames relevées avant de juger une anomalie
+const MIN_HISTORY: usize = 30;
+
+/// Tracés moyens par frame à partir desquels une frame sans tracé est
+/// anormale
+const DRAW_BASELINE: f32 = 4.0;
+
+/// Commandes inconnues dans une frame à partir desquelles une poussée est
+/// signalée, si elles dépassent aussi [`UNKNOWN_SPIKE_FACTOR`] fois la moyenne
+const UNKNOWN_SPIKE_MIN: u32 = 8;
+const UNKNOWN_SPIKE_FACTOR: f32 = 4.0;
+
+/// Commandes comptées comme des tracés
+const DRAW_COMMANDS: [&str; 3] = ["DrawTriangle", "DrawQuad", "DrawLine"];
+
+/// Commandes les plus fréquentes détaillées dans le panneau
+const PANEL_COMMANDS: usize = 4;
+
+/// Commandes reçues pendant une frame
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameCommandCounts {
+    pub frame: u64,
+    /// Nombre de commandes par nom de variante
+    pub commands: BTreeMap<&'static str, u32>,
+    /// Mots de commande non reconnus par le décodeur
+    pub unknown: u32,
+}
+
+impl FrameCommandCounts {
+    pub fn count(&self, name: &str) -> u32 {
+        self.commands.get(name).copied().unwrap_or(0)
+    }
+
+    /// Commandes de tracé (triangles, quads, lignes)
+    pub fn draws(&self) -> u32 {
+        DRAW_COMMANDS.iter().map(|name| self.count(name)).sum()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.commands.values().sum()
+    }
+}
+
+/// Comportement anormal du flux de commandes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GpuAnomaly {
+    /// Plus aucun tracé alors que le jeu en envoyait `average` par frame
+    DrawCallsStopped { frame: u64, average: f32 },
+    /// `count` commandes inconnues pour `average` par frame en moyenne
+    UnknownCommandSpike { frame: u64, count: u32, average: f32 },
+}
+
+impl GpuAnomaly {
+    /// Deux anomalies de même nature, quelle que soit leur frame
+    fn same_kind(&self, other: &GpuAnomaly) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl fmt::Display for GpuAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuAnomaly::DrawCallsStopped { frame, average } => {
+                write!(f, "frame {} : aucun tracé (moyenne {:.1} par frame)", frame, average)
+            }
+            GpuAnomaly::UnknownCommandSpike { frame, count, average } => {
+                write!(f, "frame {} : {} commandes inconnues (moyenne {:.1} par frame)", frame, count, average)
+            }
+        }
+    }
+}
+
+/// Compteurs glissants des commandes GPU et détection des anomalies
+#[derive(Debug, Clone)]
+pub struct GpuCommandStats {
+    history: VecDeque<FrameCommandCounts>,
+    window: usize,
+    /// Anomalies de la dernière frame relevée
+    anomalies: Vec<GpuAnomaly>,
+    /// Anomalies apparues depuis le lancement
+    total_anomalies: u64,
+}
+
+impl GpuCommandStats {
+    pub fn new() -> Self {
+        Self::with_window(COMMAND_STATS_WINDOW)
+    }
+
+    pub fn with_window(window: usize) -> Self {
+        Self {
+            history: VecDeque::with_capacity(window),
+            window: window.max(1),
+            anomalies: Vec::new(),
+            total_anomalies: 0,
+        }
+    }
+
+    /// Relève les commandes d'une frame et `unknown` mots non reconnus ;
+    /// retourne les anomalies de la frame, jugées par rapport aux frames
+    /// précédentes
+    pub fn record_frame(&mut self, frame: u64, commands: &[GpuCommand], unknown: u32) -> &[GpuAnomaly] {
+        let mut counts = FrameCommandCounts { frame, unknown, ..Default::default() };
+        for command in commands {
+            *counts.commands.entry(command.name()).or_insert(0) += 1;
+        }
+
+        let anomalies = self.detect(&counts);
+        for anomaly in anomalies.iter().filter(|anomaly| !self.anomalies.iter().any(|active| active.same_kind(anomaly))) {
+            log::warn!("Commandes GPU anormales, {}", anomaly);
+            self.total_anomalies += 1;
+        }
+        self.anomalies = anomalies;
+
+        if self.history.len() == self.window {
+            self.history.pop_front();
+        }
+        self.history.push_back(counts);
+        &self.anomalies
+    }
+
+    fn detect(&self, counts: &FrameCommandCounts) -> Vec<GpuAnomaly> {
+        let mut anomalies = Vec::new();
+        if self.history.len() < MIN_HISTORY.min(self.window) {
+            return anomalies;
+        }
+
+        let average_draws = self.mean(|frame| frame.draws());
+        if counts.draws() == 0 && average_draws >= DRAW_BASELINE {
+            anomalies.push(GpuAnomaly::DrawCallsStopped { frame: counts.frame, average: average_draws });
+        }
+        let average_unknown = self.average_unknown();
+        if counts.unknown >= UNKNOWN_SPIKE_MIN && counts.unknown as f32 > average_unknown * UNKNOWN_SPIKE_FACTOR {
+            anomalies.push(GpuAnomaly::UnknownCommandSpike { frame: counts.frame, count: counts.unknown, average: average_unknown });
+        }
+        anomalies
+    }
+
+    fn mean(&self, value: impl Fn(&FrameCommandCounts) -> u32) -> f32 {
+        if self.history.is_empty() {
+            return 0.0;
+        }
+        self.history.iter().map(|frame| value(frame) as f32).sum::<f32>() / self.history.len() as f32
+    }
+
+    /// Commandes de la dernière frame relevée
+    pub fn last(&self) -> Option<&FrameCommandCounts> {
+        self.history.back()
+    }
+
+    /// Nombre moyen de commandes `name` par frame sur la fenêtre
+    pub fn average(&self, name: &str) -> f32 {
+        self.mean(|frame| frame.count(name))
+    }
+
+    /// Plus grand nombre de commandes `name` dans une frame de la fenêtre
+    pub fn maximum(&self, name: &str) -> u32 {
+        self.history.iter().map(|frame| frame.count(name)).max().unwrap_or(0)
+    }
+
+    pub fn average_draws(&self) -> f32 {
+        self.mean(|frame| frame.draws())
+    }
+
+    pub fn average_unknown(&self) -> f32 {
+        self.mean(|frame| frame.unknown)
+    }
+
+    /// Anomalies de la dernière frame relevée
+    pub fn anomalies(&self) -> &[GpuAnomaly] {
+        &self.anomalies
+    }
+
+    /// Anomalies apparues depuis le lancement
+    pub fn total_anomalies(&self) -> u64 {
+        self.total_anomalies
+    }
+
+    /// Lignes du panneau d'état : totaux, commandes les plus fréquentes de
+    /// la dernière frame, puis anomalies en cours
+    pub fn lines(&self) -> Vec<String> {
+        let Some(last) = self.last() else {
+            return Vec::new();
+        };
+        let mut lines = vec![format!(
+            "commandes  {} tracés {} (moy. {:.1}) inconnues {} (moy. {:.1})",
+            last.total(), last.draws(), self.average_draws(), last.unknown, self.average_unknown()
+        )];
+
+        let mut frequent: Vec<_> = last.commands.iter().collect();
+        frequent.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (name, count) in frequent.into_iter().take(PANEL_COMMANDS) {
+            lines.push(format!("  {:<20} {:>5} moy. {:>7.1} max {:>5}", name, count, self.average(name), self.maximum(name)));
+        }
+        for anomaly in &self.anomalies {
+            lines.push(format!("! {}", anomaly));
+        }
+        lines
+    }
+}
+
+impl Default for GpuCommandStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::GpuVertex;
+
+    fn draw() -> GpuCommand {
+        GpuCommand::DrawTriangle { vertices: [GpuVertex::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0); 3], texture_id: None, texture_attributes: 0 }
+    }
+
+    #[test]
+    fn test_flags_stopped_draws_and_unknown_spike() {
+        let mut stats = GpuCommandStats::with_window(40);
+        let frame = vec![draw(); 10];
+
+        // Trop tôt pour juger : pas d'anomalie
+        assert!(stats.record_frame(0, &[], 0).is_empty());
+        for index in 1..MIN_HISTORY as u64 {
+            assert!(stats.record_frame(index, &frame, 0).is_empty());
+        }
+        assert_eq!(stats.last().unwrap().draws(), 10);
+        assert_eq!(stats.maximum("DrawTriangle"), 10);
+
+        // Les tracés tombent à zéro : signalé, une seule fois par épisode
+        let anomalies = stats.record_frame(30, &[], 0).to_vec();
+        assert!(matches!(anomalies[..], [GpuAnomaly::DrawCallsStopped { frame: 30, .. }]));
+        assert_eq!(stats.record_frame(31, &[], 0).len(), 1);
+        assert_eq!(stats.total_anomalies(), 1);
+        assert!(stats.lines().iter().any(|line| line.starts_with("! frame 31 : aucun tracé")));
+
+        // Retour à la normale, puis poussée de commandes inconnues
+        assert!(stats.record_frame(32, &frame, 2).is_empty());
+        let anomalies = stats.record_frame(33, &frame, 50).to_vec();
+        assert!(matches!(anomalies[..], [GpuAnomaly::UnknownCommandSpike { count: 50, .. }]));
+        assert_eq!(stats.total_anomalies(), 2);
+
+        // La fenêtre glissante oublie les frames les plus anciennes
+        for index in 34..80 {
+            stats.record_frame(index, &[], 0);
+        }
+        assert_eq!(stats.average_draws(), 0.0);
+        assert!(stats.anomalies().is_empty());
+    }
+}
@@ -0,0 +1,390 @@
+//! Journal des événements de frame, pour déboguer la synchronisation
+//! audio/vidéo
+//!
+//! Chaque frame émulée laisse quatre événements horodatés depuis le début
+//! de la session : lecture des entrées, VBlank émulé, transmission du tampon
+//! audio et présentation de l'image. Le journal est exporté en JSON à la
+//! fermeture ; [`EventSession::validate`] le relit et signale les frames
+//! manquées (VBlank trop espacés), les frames jamais présentées et la dérive
+//! entre l'audio transmis et le temps écoulé.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::clock::Instant;
+
+/// Nombre maximal d'événements conservés (une heure de jeu environ)
+pub const MAX_FRAME_EVENTS: usize = 1_000_000;
+
+/// Fréquence de rafraîchissement attendue
+pub const REFRESH_RATE: f32 = 60.0;
+
+/// Un intervalle entre deux VBlank au-delà de cette fraction de la période
+/// compte comme une frame manquée
+const MISSED_FRAME_FACTOR: f32 = 1.5;
+
+/// Dérive audio tolérée, en millisecondes
+const MAX_AUDIO_DRIFT_MS: f32 = 50.0;
+
+/// Événements conservés dans un rapport
+const MAX_REPORTED: usize = 20;
+
+/// Nature d'un événement de frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameEventKind {
+    /// Entrées figées pour la frame
+    InputSample,
+    /// Fin de la frame émulée
+    VBlank,
+    /// Échantillons de la frame transmis à la sortie audio
+    AudioSubmit,
+    /// Image présentée à l'écran
+    Present,
+}
+
+/// Événement horodaté
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameEvent {
+    /// Frame émulée concernée
+    pub frame: u64,
+    pub kind: FrameEventKind,
+    /// Microsecondes depuis le début de la session
+    pub time_us: u64,
+    /// Trames audio transmises (`audio_submit` seulement)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub audio_frames: u32,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+/// Journal en cours d'enregistrement (inactif par défaut)
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    origin: Option<Instant>,
+    sample_rate: u32,
+    /// Frame en cours, numérotée depuis le début de la session (les
+    /// changements de jeu ne la remettent pas à zéro)
+    frame: u64,
+    events: Vec<FrameEvent>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Démarre l'enregistrement ; `sample_rate` est celui de la sortie audio
+    pub fn enable(&mut self, sample_rate: u32) {
+        self.origin.get_or_insert_with(Instant::now);
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.origin.is_some()
+    }
+
+    /// Horodate un événement de la frame en cours ; une présentation
+    /// concerne la dernière frame terminée
+    pub fn record(&mut self, kind: FrameEventKind) {
+        self.push(kind, 0);
+    }
+
+    /// Horodate la transmission de `audio_frames` trames audio
+    pub fn record_audio(&mut self, audio_frames: u32) {
+        self.push(FrameEventKind::AudioSubmit, audio_frames);
+    }
+
+    /// Termine la frame en cours
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    fn push(&mut self, kind: FrameEventKind, audio_frames: u32) {
+        let Some(origin) = self.origin else {
+            return;
+        };
+        let frame = match kind {
+            FrameEventKind::Present => match self.frame.checked_sub(1) {
+                Some(frame) => frame,
+                None => return,
+            },
+            _ => self.frame,
+        };
+        if self.events.len() < MAX_FRAME_EVENTS {
+            let time_us = origin.elapsed().as_micros() as u64;
+            self.events.push(FrameEvent { frame, kind, time_us, audio_frames });
+        }
+    }
+
+    pub fn events(&self) -> &[FrameEvent] {
+        &self.events
+    }
+
+    /// Session enregistrée, prête à être exportée
+    pub fn session(&self) -> EventSession {
+        EventSession {
+            refresh_rate: REFRESH_RATE,
+            sample_rate: self.sample_rate,
+            events: self.events.clone(),
+        }
+    }
+}
+
+/// Session exportée
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventSession {
+    pub refresh_rate: f32,
+    /// Fréquence de la sortie audio (0 : pas d'audio)
+    pub sample_rate: u32,
+    pub events: Vec<FrameEvent>,
+}
+
+/// Frames manquées entre deux VBlank
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStall {
+    /// Dernière frame avant l'interruption
+    pub frame: u64,
+    pub interval_ms: f32,
+    pub missed: u32,
+}
+
+/// Bilan de synchronisation d'une session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    pub frames: usize,
+    /// Interruptions du rythme des VBlank (les premières)
+    pub stalls: Vec<FrameStall>,
+    pub missed_frames: u32,
+    /// Frames émulées jamais présentées (les premières)
+    pub unpresented: Vec<u64>,
+    pub unpresented_count: usize,
+    pub max_vblank_interval_ms: f32,
+    /// Délai entre le VBlank et la présentation de la même frame
+    pub average_present_latency_ms: f32,
+    pub max_present_latency_ms: f32,
+    /// Délai entre la lecture des entrées et la présentation
+    pub max_input_latency_ms: f32,
+    /// Avance (positive) ou retard de l'audio transmis sur le temps écoulé
+    pub final_audio_drift_ms: f32,
+    pub max_audio_drift_ms: f32,
+}
+
+impl SyncReport {
+    /// Problèmes relevés, un par ligne
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.missed_frames > 0 {
+            let frames: Vec<String> = self.stalls.iter().map(|stall| stall.frame.to_string()).collect();
+            issues.push(format!(
+                "{} frame(s) manquée(s) en {} interruption(s), après les frames {}",
+                self.missed_frames,
+                self.stalls.len(),
+                frames.join(", ")
+            ));
+        }
+        if self.unpresented_count > 0 {
+            let frames: Vec<String> = self.unpresented.iter().map(u64::to_string).collect();
+            issues.push(format!("{} frame(s) jamais présentée(s) : {}", self.unpresented_count, frames.join(", ")));
+        }
+        if self.max_audio_drift_ms.abs() > MAX_AUDIO_DRIFT_MS {
+            issues.push(format!(
+                "Dérive audio de {:+.1} ms (fin de session {:+.1} ms)",
+                self.max_audio_drift_ms, self.final_audio_drift_ms
+            ));
+        }
+        issues
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issues().is_empty()
+    }
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Frames: {}", self.frames)?;
+        writeln!(f, "Intervalle VBlank max: {:.2} ms", self.max_vblank_interval_ms)?;
+        writeln!(
+            f,
+            "Latence VBlank → présentation: {:.2} ms en moyenne, {:.2} ms max",
+            self.average_present_latency_ms, self.max_present_latency_ms
+        )?;
+        writeln!(f, "Latence entrées → présentation max: {:.2} ms", self.max_input_latency_ms)?;
+        writeln!(f, "Dérive audio: {:+.1} ms max, {:+.1} ms en fin de session", self.max_audio_drift_ms, self.final_audio_drift_ms)?;
+
+        let issues = self.issues();
+        if issues.is_empty() {
+            writeln!(f, "Aucun problème détecté")
+        } else {
+            issues.iter().try_for_each(|issue| writeln!(f, "PROBLÈME: {}", issue))
+        }
+    }
+}
+
+impl EventSession {
+    pub fn write<W: Write>(&self, out: W) -> io::Result<()> {
+        serde_json::to_writer(out, self).map_err(io::Error::other)
+    }
+
+    pub fn read<R: Read>(input: R) -> io::Result<Self> {
+        serde_json::from_reader(input).map_err(io::Error::other)
+    }
+
+    /// Horodatage du premier événement `kind` de chaque frame
+    fn times(&self, kind: FrameEventKind) -> std::collections::HashMap<u64, u64> {
+        let mut times = std::collections::HashMap::new();
+        for event in self.events.iter().filter(|event| event.kind == kind) {
+            times.entry(event.frame).or_insert(event.time_us);
+        }
+        times
+    }
+
+    /// Analyse la session
+    pub fn validate(&self) -> SyncReport {
+        let mut report = SyncReport::default();
+        let ms = |micros: u64| micros as f32 / 1000.0;
+
+        // Rythme des VBlank
+        let vblanks: Vec<&FrameEvent> = self.events.iter().filter(|e| e.kind == FrameEventKind::VBlank).collect();
+        report.frames = vblanks.len();
+        let period_ms = 1000.0 / self.refresh_rate.max(1.0);
+        for pair in vblanks.windows(2) {
+            let interval_ms = ms(pair[1].time_us.saturating_sub(pair[0].time_us));
+            report.max_vblank_interval_ms = report.max_vblank_interval_ms.max(interval_ms);
+            if interval_ms > period_ms * MISSED_FRAME_FACTOR {
+                let missed = ((interval_ms / period_ms).round() as u32).saturating_sub(1).max(1);
+                report.missed_frames += missed;
+                if report.stalls.len() < MAX_REPORTED {
+                    report.stalls.push(FrameStall { frame: pair[0].frame, interval_ms, missed });
+                }
+            }
+        }
+
+        // Présentation : ignorée sans GPU ; la dernière frame peut ne pas
+        // l'avoir été avant la fermeture
+        let presents = self.times(FrameEventKind::Present);
+        let inputs = self.times(FrameEventKind::InputSample);
+        if !presents.is_empty() {
+            let mut latencies = Vec::new();
+            for vblank in vblanks.iter().take(vblanks.len().saturating_sub(1)) {
+                match presents.get(&vblank.frame) {
+                    Some(&present) => {
+                        latencies.push(ms(present.saturating_sub(vblank.time_us)));
+                        if let Some(&input) = inputs.get(&vblank.frame) {
+                            report.max_input_latency_ms = report.max_input_latency_ms.max(ms(present.saturating_sub(input)));
+                        }
+                    }
+                    None => {
+                        report.unpresented_count += 1;
+                        if report.unpresented.len() < MAX_REPORTED {
+                            report.unpresented.push(vblank.frame);
+                        }
+                    }
+                }
+            }
+            if !latencies.is_empty() {
+                report.average_present_latency_ms = latencies.iter().sum::<f32>() / latencies.len() as f32;
+                report.max_present_latency_ms = latencies.iter().copied().fold(0.0, f32::max);
+            }
+        }
+
+        // Audio transmis avant chaque envoi comparé au temps écoulé depuis
+        // le premier
+        if self.sample_rate > 0 {
+            let mut submits = self.events.iter().filter(|e| e.kind == FrameEventKind::AudioSubmit);
+            if let Some(first) = submits.next() {
+                let mut audio_frames = first.audio_frames as u64;
+                for submit in submits {
+                    let audio_ms = audio_frames as f32 * 1000.0 / self.sample_rate as f32;
+                    let drift = audio_ms - ms(submit.time_us.saturating_sub(first.time_us));
+                    if drift.abs() > report.max_audio_drift_ms.abs() {
+                        report.max_audio_drift_ms = drift;
+                    }
+                    report.final_audio_drift_ms = drift;
+                    audio_frames += submit.audio_frames as u64;
+                }
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Session régulière à 60 Hz : entrées, VBlank, audio puis présentation
+    fn session(frames: u64, audio_frames: u32) -> EventSession {
+        let mut events = Vec::new();
+        for frame in 0..frames {
+            let start = frame * 16_667;
+            let event = |kind, offset, audio_frames| FrameEvent { frame, kind, time_us: start + offset, audio_frames };
+            events.push(event(FrameEventKind::InputSample, 0, 0));
+            events.push(event(FrameEventKind::VBlank, 4_000, 0));
+            events.push(event(FrameEventKind::AudioSubmit, 4_500, audio_frames));
+            events.push(event(FrameEventKind::Present, 6_000, 0));
+        }
+        EventSession { refresh_rate: 60.0, sample_rate: 48_000, events }
+    }
+
+    #[test]
+    fn test_log_numbers_frames() {
+        let mut log = EventLog::new();
+        log.record(FrameEventKind::VBlank);
+        assert!(log.events().is_empty());
+
+        log.enable(48_000);
+        log.record(FrameEventKind::Present);
+        log.record(FrameEventKind::InputSample);
+        log.record(FrameEventKind::VBlank);
+        log.record_audio(800);
+        log.end_frame();
+        log.record(FrameEventKind::Present);
+
+        let kinds: Vec<(u64, FrameEventKind)> = log.events().iter().map(|event| (event.frame, event.kind)).collect();
+        assert_eq!(kinds, vec![
+            (0, FrameEventKind::InputSample),
+            (0, FrameEventKind::VBlank),
+            (0, FrameEventKind::AudioSubmit),
+            (0, FrameEventKind::Present),
+        ]);
+        assert_eq!(log.session().events[2].audio_frames, 800);
+    }
+
+    #[test]
+    fn test_regular_session_is_clean() {
+        let report = session(120, 800).validate();
+        assert_eq!(report.frames, 120);
+        assert!(report.is_clean(), "{}", report);
+        assert!((report.average_present_latency_ms - 2.0).abs() < 0.01);
+        assert!((report.max_input_latency_ms - 6.0).abs() < 0.01);
+        assert!(report.max_audio_drift_ms.abs() < 1.0);
+
+        let mut json = Vec::new();
+        session(2, 800).write(&mut json).unwrap();
+        assert_eq!(EventSession::read(json.as_slice()).unwrap(), session(2, 800));
+    }
+
+    #[test]
+    fn test_flags_stalls_unpresented_frames_and_drift() {
+        // Trois périodes sans VBlank après la frame 10, frame 20 jamais
+        // présentée, audio trop court de moitié
+        let mut session = session(120, 400);
+        for event in session.events.iter_mut().filter(|event| event.frame > 10) {
+            event.time_us += 50_000;
+        }
+        session.events.retain(|event| !(event.frame == 20 && event.kind == FrameEventKind::Present));
+
+        let report = session.validate();
+        assert_eq!(report.missed_frames, 3);
+        assert_eq!(report.stalls[0].frame, 10);
+        assert_eq!(report.unpresented, vec![20]);
+        assert!(report.final_audio_drift_ms < -MAX_AUDIO_DRIFT_MS);
+        assert_eq!(report.issues().len(), 3);
+    }
+}
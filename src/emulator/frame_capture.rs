@@ -0,0 +1,113 @@
+//! Capture d'images pour rechercher une régression de rendu
+//!
+//! [`capture_frames`] fait tourner la machine sans fenêtre ni entrée, comme
+//! les tests de démarrage, et enregistre en PNG l'image des frames d'une plage
+//! donnée, avec un fichier `frames.json` (numéro de frame, cycles du V60
+//! depuis le lancement). Deux captures de la même plage sur deux commits se
+//! comparent fichier à fichier pour trouver celui qui a changé l'image
+//! (`--capture-frames` en ligne de commande).
+
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::gpu::Model2Gpu;
+
+use super::{EmulatorCore, IDLE_INPUT};
+
+/// Nom du fichier de métadonnées écrit avec les images
+pub const CAPTURE_METADATA_FILE: &str = "frames.json";
+
+/// Plage de frames au format `début..fin` (fin exclue) ou `début..=fin`
+pub fn parse_frame_range(text: &str) -> Option<RangeInclusive<u64>> {
+    let (start, end) = text.split_once("..")?;
+    let start = start.trim().parse().ok()?;
+    let end = match end.strip_prefix('=') {
+        Some(end) => end.trim().parse().ok()?,
+        None => end.trim().parse::<u64>().ok()?.checked_sub(1)?,
+    };
+    (start <= end).then_some(start..=end)
+}
+
+/// Image enregistrée
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedFrame {
+    /// Frames émulées quand l'image a été relevée
+    pub frame: u64,
+    /// Cycles du V60 exécutés depuis le lancement
+    pub cycles: u64,
+    /// Nom du fichier PNG, relatif au dossier de capture
+    pub file: String,
+}
+
+/// Contenu de [`CAPTURE_METADATA_FILE`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameCapture {
+    pub game: String,
+    /// Taille native des images
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<CapturedFrame>,
+}
+
+/// Émule les frames jusqu'à la fin de `range` et enregistre dans
+/// `directory` (créé au besoin) l'image de chaque frame de la plage
+pub fn capture_frames(core: &mut EmulatorCore, gpu: &mut Model2Gpu, game: &str, range: RangeInclusive<u64>, directory: &Path) -> Result<FrameCapture> {
+    std::fs::create_dir_all(directory)?;
+    let (width, height) = gpu.framebuffer.native_size();
+    let mut capture = FrameCapture { game: game.to_string(), width, height, frames: Vec::new() };
+
+    for frame in 1..=*range.end() {
+        core.run_frame(IDLE_INPUT, Some(gpu))?;
+        gpu.end_frame()?;
+        if !range.contains(&frame) {
+            continue;
+        }
+
+        let file = format!("frame_{:06}.png", frame);
+        let path = directory.join(&file);
+        image::save_buffer(&path, &gpu.framebuffer.native_color_data(), width, height, image::ExtendedColorType::Rgba8)
+            .map_err(|e| std::io::Error::other(format!("{} : {}", path.display(), e)))?;
+        capture.frames.push(CapturedFrame { frame, cycles: core.now(), file });
+    }
+
+    let metadata = serde_json::to_string_pretty(&capture).map_err(std::io::Error::other)?;
+    std::fs::write(directory.join(CAPTURE_METADATA_FILE), metadata)?;
+    Ok(capture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AudioBackendKind, EmulatorConfig};
+
+    #[test]
+    fn test_capture_writes_requested_frames_and_metadata() {
+        assert_eq!(parse_frame_range("100..200"), Some(100..=199));
+        assert_eq!(parse_frame_range("5..=5"), Some(5..=5));
+        assert_eq!(parse_frame_range("5..5"), None);
+        assert_eq!(parse_frame_range("12"), None);
+
+        let mut config = EmulatorConfig::default();
+        config.audio.backend = AudioBackendKind::Null;
+        let mut core = EmulatorCore::new(&config.audio);
+        // RAM principale à zéro : suite de NOP
+        core.cpu.registers.pc = 0;
+        let mut gpu = Model2Gpu::headless();
+
+        let directory = std::env::temp_dir().join(format!("pm2_frame_capture_{}", std::process::id()));
+        let capture = capture_frames(&mut core, &mut gpu, "test", 2..=3, &directory).unwrap();
+        assert_eq!(capture.frames.iter().map(|frame| frame.frame).collect::<Vec<_>>(), [2, 3]);
+        assert!(capture.frames[0].cycles > 0 && capture.frames[1].cycles > capture.frames[0].cycles);
+        assert_eq!(core.now(), capture.frames[1].cycles);
+
+        let image = image::open(directory.join("frame_000003.png")).unwrap();
+        assert_eq!((image.width(), image.height()), (capture.width, capture.height));
+        assert!(!directory.join("frame_000001.png").exists());
+
+        let metadata = std::fs::read_to_string(directory.join(CAPTURE_METADATA_FILE)).unwrap();
+        assert_eq!(serde_json::from_str::<FrameCapture>(&metadata).unwrap(), capture);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+//! Cadence des frames émulées face à l'écran de l'hôte
+//!
+//! Le Model 2 affiche [`MODEL2_REFRESH_RATE`] images par seconde, les écrans
+//! de l'hôte 60, 120 ou 144. [`FramePacer`] décide à chaque rafraîchissement
+//! de l'hôte combien de frames émuler, selon [`FramePacing`] :
+//!
+//! - `vsync` : une frame tous les N rafraîchissements, N choisi au plus près
+//!   de 57,52 Hz ; le jeu tourne un peu plus vite ou plus lentement, et
+//!   l'audio est produit au rythme des frames présentées pour ne pas dériver
+//! - `free_run` : les frames suivent l'horloge de l'hôte à 57,52 Hz exactes ;
+//!   une image est de temps en temps répétée (saccade d'origine)
+//! - `blend` : comme `free_run`, mais l'image présentée mélange les deux
+//!   dernières frames au prorata du temps écoulé, avec une frame de retard
+//!
+//! Hors de la vitesse normale (avance rapide, ralenti), chaque mode émule
+//! `speed` fois plus de frames dans le même temps.
+
+use std::time::Duration;
+
+use crate::config::FramePacing;
+
+/// Fréquence de rafraîchissement du moniteur du Model 2
+pub const MODEL2_REFRESH_RATE: f64 = 57.524;
+
+/// Frames rattrapées au plus en un rafraîchissement, au-delà (fenêtre
+/// déplacée, machine suspendue) le retard est abandonné
+const MAX_CATCH_UP_FRAMES: u32 = 4;
+
+/// Fréquence supposée quand l'écran ne donne pas la sienne
+pub const DEFAULT_DISPLAY_RATE: f64 = 60.0;
+
+/// Ce qu'un rafraîchissement de l'hôte doit faire
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefreshPlan {
+    /// Frames à émuler avant la présentation (0 : image répétée)
+    pub frames: u32,
+    /// Poids de la dernière frame face à la précédente (mode `blend`)
+    pub blend: Option<f32>,
+}
+
+/// Répartit les frames émulées sur les rafraîchissements de l'hôte
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    mode: FramePacing,
+    display_rate: f64,
+    /// Rafraîchissements par frame en mode `vsync`
+    interval: u32,
+    /// Rafraîchissements écoulés depuis la dernière frame (`vsync`) ou
+    /// frames dues, partie fractionnaire comprise (`free_run`, `blend`)
+    elapsed: f64,
+    /// Vitesse d'émulation (1 : temps réel)
+    speed: f64,
+}
+
+impl FramePacer {
+    pub fn new(mode: FramePacing, display_rate: f64) -> Self {
+        let mut pacer = Self { mode, display_rate: DEFAULT_DISPLAY_RATE, interval: 1, elapsed: 0.0, speed: 1.0 };
+        pacer.set_display_rate(display_rate);
+        pacer
+    }
+
+    pub fn mode(&self) -> FramePacing {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: FramePacing) {
+        self.mode = mode;
+        self.elapsed = 0.0;
+    }
+
+    /// Fréquence de l'écran de l'hôte (changement d'écran)
+    pub fn set_display_rate(&mut self, display_rate: f64) {
+        self.display_rate = if display_rate.is_finite() && display_rate >= 1.0 { display_rate } else { DEFAULT_DISPLAY_RATE };
+        self.interval = ((self.display_rate / MODEL2_REFRESH_RATE).round() as u32).max(1);
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Vitesse d'émulation : 3 émule trois frames dans le temps d'une, 0,5
+    /// une frame dans le temps de deux
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = if speed.is_finite() && speed > 0.0 { speed } else { 1.0 };
+    }
+
+    /// Frames émulées par seconde de l'hôte à vitesse normale, sur laquelle l'audio est cadencé
+    pub fn emulated_rate(&self) -> f64 {
+        match self.mode {
+            FramePacing::Vsync => self.display_rate / self.interval as f64,
+            FramePacing::FreeRun | FramePacing::Blend => MODEL2_REFRESH_RATE,
+        }
+    }
+
+    /// Rafraîchissement de l'hôte, `elapsed` après le précédent
+    pub fn next_refresh(&mut self, elapsed: Duration) -> RefreshPlan {
+        match self.mode {
+            FramePacing::Vsync => {
+                self.elapsed += self.speed;
+                let frames = (self.elapsed / self.interval as f64).floor() as u32;
+                self.elapsed -= (frames * self.interval) as f64;
+                RefreshPlan { frames, blend: None }
+            }
+            FramePacing::FreeRun | FramePacing::Blend => {
+                let catch_up = (MAX_CATCH_UP_FRAMES as f64 * self.speed).ceil() as u32;
+                self.elapsed += elapsed.as_secs_f64() * MODEL2_REFRESH_RATE * self.speed;
+                let frames = (self.elapsed.floor() as u32).min(catch_up);
+                self.elapsed = if frames == catch_up { self.elapsed.fract() } else { self.elapsed - frames as f64 };
+                let blend = (self.mode == FramePacing::Blend).then_some(self.elapsed as f32);
+                RefreshPlan { frames, blend }
+            }
+        }
+    }
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new(FramePacing::default(), DEFAULT_DISPLAY_RATE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pacing_modes() {
+        // 60 Hz en vsync : une frame par rafraîchissement, jeu à 60 Hz
+        let mut pacer = FramePacer::new(FramePacing::Vsync, 60.0);
+        assert_eq!(pacer.next_refresh(Duration::ZERO).frames, 1);
+        assert_eq!(pacer.emulated_rate(), 60.0);
+
+        // 144 Hz : une frame tous les trois rafraîchissements (48 Hz)
+        pacer.set_display_rate(144.0);
+        let frames: Vec<u32> = (0..6).map(|_| pacer.next_refresh(Duration::ZERO).frames).collect();
+        assert_eq!(frames, [0, 0, 1, 0, 0, 1]);
+        assert_eq!(pacer.emulated_rate(), 48.0);
+
+        // Horloge de l'hôte : 57 ou 58 frames par seconde à 60 Hz, images répétées
+        let refresh = Duration::from_secs(1) / 60;
+        let mut pacer = FramePacer::new(FramePacing::FreeRun, 60.0);
+        let plans: Vec<RefreshPlan> = (0..600).map(|_| pacer.next_refresh(refresh)).collect();
+        let total: u32 = plans.iter().map(|plan| plan.frames).sum();
+        assert!((574..=576).contains(&total), "{}", total);
+        assert!(plans.iter().any(|plan| plan.frames == 0));
+        assert!(plans.iter().all(|plan| plan.blend.is_none()));
+        assert_eq!(pacer.emulated_rate(), MODEL2_REFRESH_RATE);
+
+        // Mélange : poids entre 0 et 1, retard abandonné après une pause
+        pacer.set_mode(FramePacing::Blend);
+        let plan = pacer.next_refresh(refresh / 2);
+        assert_eq!(plan.frames, 0);
+        assert!((plan.blend.unwrap() - 0.479).abs() < 0.01);
+        assert_eq!(pacer.next_refresh(Duration::from_secs(2)).frames, MAX_CATCH_UP_FRAMES);
+        assert_eq!(pacer.next_refresh(Duration::ZERO).frames, 0);
+
+        // Avance rapide et ralenti : le débit de frames suit la vitesse,
+        // la fréquence de référence de l'audio reste celle du temps réel
+        let mut pacer = FramePacer::new(FramePacing::Vsync, 60.0);
+        pacer.set_speed(3.0);
+        let total: u32 = (0..60).map(|_| pacer.next_refresh(Duration::ZERO).frames).sum();
+        assert_eq!(total, 180);
+        assert_eq!(pacer.emulated_rate(), 60.0);
+        pacer.set_speed(0.5);
+        let frames: Vec<u32> = (0..4).map(|_| pacer.next_refresh(Duration::ZERO).frames).collect();
+        assert_eq!(frames, [0, 1, 0, 1]);
+
+        let mut pacer = FramePacer::new(FramePacing::FreeRun, 60.0);
+        pacer.set_speed(3.0);
+        let total: u32 = (0..600).map(|_| pacer.next_refresh(refresh).frames).sum();
+        assert!((1724..=1727).contains(&total), "{}", total);
+    }
+}
@@ -0,0 +1,148 @@
+//! Transmission des lampes à une borne réelle
+//!
+//! À chaque frame, les lampes qui ont changé d'état sont envoyées à un
+//! programme ou à un montage externe, une ligne `nom=0|1` par lampe (voir
+//! [`Lamp::name`]) ; le premier envoi donne l'état de toutes les lampes. Deux
+//! destinations : un datagramme UDP par frame (`udp://hôte:port`), ou un port
+//! série ouvert comme un fichier (`serial:///dev/ttyUSB0`, `serial:COM3`),
+//! dont la vitesse est réglée par le système.
+
+use anyhow::{bail, Context, Result};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::memory::{Lamp, LampOutputs};
+
+/// Destination des lampes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LampTarget {
+    Udp(SocketAddr),
+    Serial(PathBuf),
+}
+
+impl FromStr for LampTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        if let Some(address) = text.strip_prefix("udp://") {
+            let address = address
+                .to_socket_addrs()
+                .with_context(|| format!("Adresse UDP invalide: {}", address))?
+                .next()
+                .with_context(|| format!("Adresse UDP introuvable: {}", address))?;
+            return Ok(LampTarget::Udp(address));
+        }
+        if let Some(path) = text.strip_prefix("serial://").or_else(|| text.strip_prefix("serial:")) {
+            if path.is_empty() {
+                bail!("Port série manquant: {}", text);
+            }
+            return Ok(LampTarget::Serial(PathBuf::from(path)));
+        }
+        bail!("Destination des lampes inconnue (udp://hôte:port ou serial:port): {}", text)
+    }
+}
+
+enum Sink {
+    Udp { socket: UdpSocket, target: SocketAddr },
+    Serial(File),
+}
+
+/// Envoie les changements de lampes à leur destination
+pub struct LampForwarder {
+    sink: Sink,
+    /// Dernier état transmis
+    previous: Option<LampOutputs>,
+}
+
+impl LampForwarder {
+    pub fn open(target: &LampTarget) -> Result<Self> {
+        let sink = match target {
+            LampTarget::Udp(address) => {
+                let local: SocketAddr = match address {
+                    SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+                    SocketAddr::V6(_) => (std::net::Ipv6Addr::UNSPECIFIED, 0).into(),
+                };
+                let socket = UdpSocket::bind(local).context("Impossible d'ouvrir une socket UDP")?;
+                Sink::Udp { socket, target: *address }
+            },
+            LampTarget::Serial(path) => Sink::Serial(
+                OpenOptions::new()
+                    .write(true)
+                    .open(path)
+                    .with_context(|| format!("Impossible d'ouvrir le port série {}", path.display()))?,
+            ),
+        };
+        Ok(Self { sink, previous: None })
+    }
+
+    /// Transmet les lampes qui ont changé depuis le dernier envoi
+    pub fn publish(&mut self, lamps: LampOutputs) -> Result<()> {
+        let message = match self.previous {
+            Some(previous) if previous == lamps => return Ok(()),
+            Some(previous) => lines(lamps.changes(previous)),
+            None => lines(Lamp::ALL.into_iter().map(|lamp| (lamp, lamps.is_lit(lamp)))),
+        };
+        match &mut self.sink {
+            Sink::Udp { socket, target } => {
+                socket.send_to(message.as_bytes(), *target)?;
+            },
+            Sink::Serial(port) => {
+                port.write_all(message.as_bytes())?;
+                port.flush()?;
+            },
+        }
+        self.previous = Some(lamps);
+        Ok(())
+    }
+}
+
+fn lines(changes: impl Iterator<Item = (Lamp, bool)>) -> String {
+    changes.fold(String::new(), |mut message, (lamp, lit)| {
+        let _ = writeln!(message, "{}={}", lamp.name(), lit as u8);
+        message
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_forwards_lamp_changes() {
+        assert!("tcp://localhost:1".parse::<LampTarget>().is_err());
+        assert_eq!("serial:COM3".parse::<LampTarget>().unwrap(), LampTarget::Serial("COM3".into()));
+
+        // UDP : état complet, puis seulement les changements
+        let receiver = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        receiver.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let target: LampTarget = format!("udp://{}", receiver.local_addr().unwrap()).parse().unwrap();
+        let mut forwarder = LampForwarder::open(&target).unwrap();
+
+        let mut buffer = [0; 256];
+        forwarder.publish(LampOutputs::from_bits(0b1)).unwrap();
+        let length = receiver.recv(&mut buffer).unwrap();
+        let message = std::str::from_utf8(&buffer[..length]).unwrap();
+        assert_eq!(message.lines().count(), Lamp::ALL.len());
+        assert!(message.starts_with("start1=1\nstart2=0\n"));
+
+        forwarder.publish(LampOutputs::from_bits(0b1)).unwrap();
+        forwarder.publish(LampOutputs::from_bits(0b100_0000)).unwrap();
+        let length = receiver.recv(&mut buffer).unwrap();
+        assert_eq!(&buffer[..length], b"start1=0\nleader=1\n");
+
+        // Port série : ici un simple fichier
+        let dir = tempfile::tempdir().unwrap();
+        let port = dir.path().join("tty");
+        File::create(&port).unwrap();
+        let mut forwarder = LampForwarder::open(&LampTarget::Serial(port.clone())).unwrap();
+        forwarder.publish(LampOutputs::from_bits(0b10)).unwrap();
+        forwarder.publish(LampOutputs::default()).unwrap();
+        let written = std::fs::read_to_string(&port).unwrap();
+        assert!(written.ends_with("seat2=0\nstart2=0\n"), "{}", written);
+    }
+}
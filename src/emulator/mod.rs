@@ -0,0 +1,947 @@
+//! Cœur d'émulation indépendant de l'interface
+//!
+//! Regroupe le CPU, la mémoire et l'audio, exécute les frames émulées et
+//! transmet les commandes graphiques du jeu au GPU quand il est disponible.
+//! L'état de tous les sous-systèmes est exposé par [`EmulatorCore::stats`].
+//! Les succès du jeu chargé sont évalués à la fin de chaque frame. Le budget
+//! de cycles de chaque CPU suit le quartz maître de la carte et ses
+//! multiplicateurs d'horloge ([`CpuClocks`]).
+//! [`EmulatorCore`] est `Send` (hors wasm32) : un frontend peut le créer puis
+//! le faire tourner sur un thread de travail.
+//!
+//! Une frame est une suite d'événements ([`CoreEvent`]) inscrits dans un
+//! [`Scheduler`] : le CPU s'exécute jusqu'à l'échéance suivante (début de
+//! ligne, tranche audio, interruption programmée, VBlank), puis le
+//! périphérique concerné est mis à jour.
+//!
+//! Sur demande, les accès du CPU à la mémoire sont comptés par bloc et
+//! relevés toutes les N frames ([`EmulatorCore::take_heatmap`]).
+
+pub mod stats;
+pub mod stats_server;
+pub mod achievements;
+pub mod clocks;
+pub mod event_log;
+pub mod session_report;
+pub mod scheduler;
+pub mod command_stats;
+pub mod lamp_forwarder;
+pub mod sound_test;
+pub mod frame_pacing;
+pub mod frame_capture;
+
+pub use stats::*;
+pub use stats_server::*;
+pub use achievements::*;
+pub use clocks::*;
+pub use event_log::*;
+pub use session_report::*;
+pub use scheduler::*;
+pub use command_stats::*;
+pub use lamp_forwarder::*;
+pub use sound_test::*;
+pub use frame_pacing::*;
+pub use frame_capture::*;
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::error::{CrashReport, EmulatorError, Result, Subsystem};
+use crate::clock::Instant;
+
+use crate::audio::{ScspAudio, SoundHle, SCSP_SLOT_COUNT, WAVE_MEMORY_SIZE};
+use crate::config::{AudioConfig, EmulationConfig, HeatmapConfig};
+use crate::cpu::{CpuTiming, FirmwareHle, Interrupt, NecV60, Quarantine};
+use crate::gpu::geometry::{Triangle3D, TriangleFlags, Vertex3D};
+use crate::gpu::{FogTable, Model2Gpu, RenderState};
+use crate::memory::{AccessHeatmap, AccessObserver, BoardRevision, DRIVE_COMMAND_BACKLOG, FOG_TABLE_LENGTH, ForceFeedbackEvent, ForceFeedbackState, GameHacks, GpuCommand, GpuVertex, HeatmapSnapshot, MemoryInterface, Model2Memory, RenderStateType, SCANLINES_PER_FRAME};
+use crate::profiling::{FrameProfiler, FrameScope};
+use crate::rom::Model2RomSystem;
+
+/// Cycles du V60 exécutés par frame (60 Hz)
+pub const CYCLES_PER_FRAME: u32 = crate::MAIN_CPU_FREQUENCY / 60;
+
+/// Tranches de génération audio par frame
+pub const AUDIO_TICKS_PER_FRAME: u32 = 8;
+
+/// Mot d'entrée sans aucun bouton enfoncé (actif bas)
+pub(crate) const IDLE_INPUT: u32 = 0xFFFF;
+
+/// Machine émulée : CPU, mémoire et audio
+pub struct EmulatorCore {
+    pub cpu: NecV60,
+    pub memory: Model2Memory,
+    pub audio: ScspAudio,
+
+    /// Répartition du temps de frame par sous-système
+    pub profiler: FrameProfiler,
+
+    /// Succès et événements du jeu chargé
+    pub achievements: Achievements,
+
+    /// Commandes sonores jouées sans 68000 (vide : inactif)
+    pub sound_hle: SoundHle,
+
+    /// Horodatage des VBlank et des envois audio (inactif par défaut)
+    pub event_log: EventLog,
+
+    /// Premiers manques de l'émulation rencontrés (inactif par défaut)
+    pub session_report: SessionReporter,
+
+    /// Commandes GPU de chaque frame et anomalies du flux
+    pub gpu_commands: GpuCommandStats,
+
+    /// Multiplicateurs d'horloge, repris de la configuration au chargement
+    pub clocks: CpuClocks,
+
+    /// Frames émulées par seconde de l'hôte ([`FramePacer::emulated_rate`]) :
+    /// l'audio de chaque frame en dure l'inverse
+    frame_rate: f64,
+
+    /// Frames émulées depuis le lancement
+    frames: u64,
+
+    /// Cycles exécutés pendant la dernière frame
+    last_frame_cycles: u32,
+
+    /// Échéances des périphériques, datées en cycles du V60
+    scheduler: Scheduler<CoreEvent>,
+
+    /// Date de début et budget de la frame en cours
+    frame_start: u64,
+    frame_budget: u32,
+
+    /// Cycles exécutés par le CPU depuis le début de la frame
+    frame_cycles: u32,
+
+    /// Trames audio transmises pendant la dernière frame
+    last_frame_audio_frames: u32,
+
+    /// Statistiques du GPU relevées à la fin de la dernière frame
+    gpu_stats: Option<GpuStats>,
+
+    /// Lot de commandes GPU de la frame, réutilisé pour ne pas allouer
+    gpu_batch: Vec<GpuCommand>,
+
+    /// Commandes de la carte de pilotage pas encore relevées par le frontend
+    force_feedback: VecDeque<ForceFeedbackEvent>,
+
+    /// Carte de chaleur branchée sur le bus, si elle est active
+    heatmap: Option<Arc<AccessHeatmap>>,
+
+    /// Frames cumulées par relevé de la carte de chaleur, et frames déjà
+    /// cumulées dans le relevé en cours
+    heatmap_interval: u32,
+    heatmap_frames: u32,
+
+    /// Dernier relevé complet, pas encore pris par le frontend
+    heatmap_snapshot: Option<HeatmapSnapshot>,
+}
+
+impl EmulatorCore {
+    pub fn new(audio: &AudioConfig) -> Self {
+        Self {
+            cpu: NecV60::new(),
+            memory: Model2Memory::new(),
+            audio: ScspAudio::with_config(audio),
+            profiler: FrameProfiler::new(),
+            achievements: Achievements::new(),
+            sound_hle: SoundHle::default(),
+            event_log: EventLog::new(),
+            session_report: SessionReporter::new(),
+            gpu_commands: GpuCommandStats::new(),
+            clocks: CpuClocks::default(),
+            frame_rate: 60.0,
+            frames: 0,
+            last_frame_cycles: 0,
+            scheduler: Scheduler::new(),
+            frame_start: 0,
+            frame_budget: 0,
+            frame_cycles: 0,
+            last_frame_audio_frames: 0,
+            gpu_stats: None,
+            gpu_batch: Vec::new(),
+            force_feedback: VecDeque::new(),
+            heatmap: None,
+            heatmap_interval: 0,
+            heatmap_frames: 0,
+            heatmap_snapshot: None,
+        }
+    }
+
+    /// Active ou coupe la carte de chaleur des accès mémoire ; le relevé en
+    /// cours est abandonné
+    pub fn set_heatmap(&mut self, config: &HeatmapConfig) {
+        if let Some(heatmap) = self.heatmap.take() {
+            self.memory.remove_access_observer(&(heatmap as Arc<dyn AccessObserver>));
+        }
+        if config.enabled {
+            let heatmap = Arc::new(AccessHeatmap::new(config.start, config.length, config.block_size));
+            self.memory.add_access_observer(heatmap.clone());
+            self.heatmap = Some(heatmap);
+        }
+        self.heatmap_interval = config.interval_frames.max(1);
+        self.heatmap_frames = 0;
+        self.heatmap_snapshot = None;
+    }
+
+    /// Relevé de la carte de chaleur terminé depuis le dernier appel
+    pub fn take_heatmap(&mut self) -> Option<HeatmapSnapshot> {
+        self.heatmap_snapshot.take()
+    }
+
+    /// Frames émulées depuis le lancement
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Commandes de retour de force reçues depuis le dernier appel, pour les
+    /// volants et les bornes ; les plus anciennes sont oubliées si personne
+    /// ne les relève
+    pub fn take_force_feedback(&mut self) -> Vec<ForceFeedbackEvent> {
+        self.force_feedback.drain(..).collect()
+    }
+
+    /// Effets de retour de force en cours
+    pub fn force_feedback_state(&self) -> ForceFeedbackState {
+        self.memory.drive_board().state()
+    }
+
+    /// Trames audio transmises à la sortie pendant la dernière frame
+    pub fn last_frame_audio_frames(&self) -> u32 {
+        self.last_frame_audio_frames
+    }
+
+    /// Rythme auquel le frontend présente les frames : l'audio produit à
+    /// chaque frame couvre `1 / rate` seconde de l'hôte, sans dérive ni
+    /// changement de hauteur quand l'écran impose sa cadence
+    pub fn set_frame_rate(&mut self, rate: f64) {
+        if rate.is_finite() && rate > 0.0 {
+            self.frame_rate = rate;
+        }
+    }
+
+    /// Date émulée, en cycles du V60 depuis le lancement
+    pub fn now(&self) -> u64 {
+        self.scheduler.now()
+    }
+
+    /// Lève `interrupt` dans `delay` cycles, sans passer par le contrôleur
+    /// de la carte I/O (périphérique externe, outil de test) ; les timers de
+    /// la carte inscrivent eux-mêmes leurs débordements
+    pub fn schedule_interrupt(&mut self, delay: u64, interrupt: Interrupt) {
+        let event = CoreEvent::Interrupt(interrupt);
+        self.scheduler.schedule_in(delay, event.priority(), event);
+    }
+
+    /// Charge un jeu en mémoire et redémarre le CPU sur son vecteur de reset,
+    /// avec les routines simulées, la protection, le bus ouvert et les
+    /// contournements de son profil
+    pub fn load_game(&mut self, rom_system: &mut Model2RomSystem, game_name: &str, emulation: &EmulationConfig) -> Result<()> {
+        // Disposition mémoire de la carte du jeu, dont les fenêtres de ROM
+        // doivent contenir les banques
+        if let Some(info) = rom_system.rom_manager.database().find_game(game_name) {
+            let board = info.board_revision().unwrap_or_else(|| {
+                log::warn!("Carte « {} » inconnue, disposition {} utilisée", info.board, BoardRevision::Model2.name());
+                BoardRevision::Model2
+            });
+            self.memory.set_board(board);
+            info.check_rom_windows(&self.memory.mapping)?;
+        }
+
+        // Charger, patcher et mapper le jeu dans la mémoire principale
+        rom_system.rom_manager.set_patches(emulation.rom_patches.clone());
+        rom_system.load_and_map_game(game_name, &mut self.memory)?;
+        self.achievements = Achievements::new();
+        self.session_report.begin_game(game_name);
+        self.cpu.quarantine = Quarantine::new(emulation.unimplemented_policy);
+        self.cpu.timing = emulation.cpu_timing_for(Some(game_name));
+        if self.cpu.timing == CpuTiming::Fast {
+            log::info!("Durées d'instruction fixes (mode rapide)");
+        }
+        self.clocks = CpuClocks::from_config(emulation, self.memory.mapping.board().unwrap_or_default());
+        self.audio.set_native_rate(self.clocks.devices.scsp_sample_rate());
+        if !self.clocks.is_stock() {
+            log::info!("Horloges modifiées: {}", self.clocks.describe());
+        }
+        
+        // Générer un rapport d'état
+        let report = rom_system.generate_status_report()?;
+        log::info!("Rapport de chargement ROM:\n{}", report);
+        
+        // Réinitialiser le CPU après le chargement des ROMs
+        self.scheduler.retain(|_| false);
+        self.boot_cpu();
+        self.schedule_timers();
+
+        // Routines des ROM système simulées (HLE) et puce de protection
+        let system_config = rom_system.rom_manager.database().find_game(game_name).map(|info| &info.system_config);
+        // Seules les routines dont la ROM système manque sont simulées
+        let hle = system_config
+            .map(|config| FirmwareHle::new(config.firmware_hle.iter().filter(|entry| entry.applies(|rom| rom_system.is_rom_loaded(rom))).cloned()))
+            .unwrap_or_default();
+        let protection = system_config.and_then(|config| config.protection.as_ref());
+        if let Some(protection) = protection {
+            let mode = if protection.challenges.is_empty() { "en transit" } else { "réponses connues" };
+            log::info!("Protection {} ({})", protection.chip, mode);
+        }
+        self.memory.set_protection(protection);
+        let hacks = match system_config {
+            Some(config) if emulation.game_hacks => GameHacks::new(config.hacks.iter().copied()),
+            _ => GameHacks::default(),
+        };
+        self.memory.set_hacks(hacks)?;
+        self.memory.set_open_bus(system_config.and_then(|config| config.open_bus.clone()).unwrap_or_default());
+        let fog_table = match system_config.and_then(|config| config.graphics_config.fog_table_address) {
+            Some(address) => self.memory.read_block(address, FOG_TABLE_LENGTH as usize)?,
+            None => Vec::new(),
+        };
+        self.memory.set_fog_table_preset(fog_table);
+        self.cpu.hle = None;
+        if emulation.firmware_hle && !hle.is_empty() {
+            log::info!("HLE du micrologiciel activée:\n{}", hle.report());
+            self.cpu.enable_hle(hle);
+        }
+
+        // Commandes sonores jouées directement, sauf si le 68000 est émulé
+        self.sound_hle = match system_config {
+            Some(config) if emulation.sound_hle && !emulation.audio_lle => SoundHle::new(config.sound_commands.iter().cloned()),
+            _ => SoundHle::default(),
+        };
+        if !self.sound_hle.is_empty() {
+            log::info!("Son HLE activé:\n{}", self.sound_hle.report());
+            if let Some(rom) = self.memory.roms.get("audio") {
+                let wave = rom.read_block(0, rom.size().min(WAVE_MEMORY_SIZE))?;
+                self.audio.load_wave_memory(&wave);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reset logiciel, comme la ligne de reset de la carte : CPU, registres
+    /// I/O et processeur sonore repartent de zéro, les RAMs sont conservées.
+    /// Le jeu redémarre sur son vecteur de reset.
+    pub fn soft_reset(&mut self) -> Result<()> {
+        self.memory.reset_io()?;
+        self.scheduler.retain(|_| false);
+        self.audio.reset();
+        self.sound_hle.reset();
+        self.boot_cpu();
+        self.schedule_timers();
+        Ok(())
+    }
+
+    /// Réinitialise le CPU et place le PC sur le vecteur de reset
+    fn boot_cpu(&mut self) {
+        self.cpu.reset();
+
+        // Pour SEGA Model 2, le reset vector est généralement à l'adresse 0x00000004
+        if let Ok(reset_vector) = self.memory.read_u32(0x00000004) {
+            self.cpu.registers.pc = reset_vector;
+            log::info!("PC initialisé à l'adresse de reset: {:#08X}", reset_vector);
+        } else {
+            log::warn!("Impossible de lire le vecteur de reset, PC laissé à 0");
+        }
+    }
+
+    /// Exécute une frame avec l'état des entrées `input_word` (actif bas),
+    /// que le jeu lit à partir du VBlank de cette frame ; retourne le nombre
+    /// de cycles exécutés
+    pub fn run_frame(&mut self, input_word: u32, mut gpu: Option<&mut Model2Gpu>) -> Result<u32> {
+        self.memory.set_input_data(input_word);
+
+        let budget = self.clocks.main_cycles_per_frame();
+        self.audio.set_master_clock((budget as f64 * self.frame_rate).round() as u32);
+        let executed_cycles = self.run_events(budget).inspect_err(|e| self.session_report.record_error(self.frames, e))?;
+        for exception in self.cpu.quarantine.take_skipped() {
+            self.session_report.record_error(self.frames, &exception.into());
+        }
+
+        // Table de brouillard réécrite par le jeu ou rechargée au reset,
+        // priorités des couches relevées ligne par ligne et plans 2D
+        if let Some(gpu_ref) = gpu.as_mut() {
+            gpu_ref.compositor.set_scanlines(self.memory.scanline_log());
+            gpu_ref.compositor.draw_planes(self.memory.video_ram.data());
+            if let Some(table) = self.memory.take_fog_table_update() {
+                let table = (!table.is_empty()).then(|| FogTable::from_bytes(table));
+                gpu_ref.geometry_processor.set_fog_table(table);
+            }
+        }
+
+        // Traiter les commandes GPU de la frame en un lot, dans un tampon
+        // conservé d'une frame à l'autre
+        let mut batch = std::mem::take(&mut self.gpu_batch);
+        let mut result = Ok(());
+        self.memory.process_gpu_commands_into(&mut batch);
+        self.gpu_commands.record_frame(self.frames, &batch, self.memory.take_unknown_gpu_commands());
+        if !batch.is_empty() {
+            if let Some(gpu_ref) = gpu.as_mut() {
+                let frame = self.frames;
+                result = match isolate(|| process_gpu_command_batch(&batch, gpu_ref, &mut self.cpu, &mut self.session_report, frame)) {
+                    Ok(result) => result,
+                    Err(message) => {
+                        let error = self.subsystem_panic(Subsystem::Gpu, message, &batch);
+                        self.session_report.record_error(frame, &error);
+                        Err(error)
+                    }
+                };
+            } else {
+                log::debug!("GPU: {} commandes reçues mais GPU non initialisé", batch.len());
+            }
+        }
+        batch.clear();
+        self.gpu_batch = batch;
+        result?;
+
+        self.achievements.evaluate(&self.memory, self.frames);
+        self.frames += 1;
+        if let Some(heatmap) = &self.heatmap {
+            self.heatmap_frames += 1;
+            if self.heatmap_frames >= self.heatmap_interval {
+                self.heatmap_snapshot = Some(heatmap.take(self.heatmap_frames));
+                self.heatmap_frames = 0;
+            }
+        }
+        self.event_log.end_frame();
+        self.last_frame_cycles = executed_cycles;
+        self.gpu_stats = gpu.map(|gpu| GpuStats::capture(gpu));
+        Ok(executed_cycles)
+    }
+
+    /// Date de la fraction `index / count` de la frame en cours
+    fn frame_time(&self, index: u32, count: u32) -> u64 {
+        self.frame_start + self.frame_budget as u64 * index as u64 / count as u64
+    }
+
+    fn schedule(&mut self, time: u64, event: CoreEvent) {
+        self.scheduler.schedule(time, event.priority(), event);
+    }
+
+    /// Exécute une frame de `budget` cycles, événement par événement ;
+    /// retourne les cycles exécutés par le CPU. Le temps émulé avance de
+    /// tout le budget même si le CPU s'arrête avant (HALT en attente d'IRQ).
+    fn run_events(&mut self, budget: u32) -> Result<u32> {
+        self.cpu.quarantine.resume();
+        self.memory.begin_scanlines();
+
+        // Événements de la frame, abandonnés si la précédente s'est
+        // interrompue sur une erreur
+        self.scheduler.retain(|event| !event.is_frame_event());
+        self.frame_start = self.scheduler.now();
+        self.frame_budget = budget;
+        self.frame_cycles = 0;
+        self.last_frame_audio_frames = 0;
+        self.schedule(self.frame_start, CoreEvent::Scanline(0));
+        self.schedule(self.frame_time(1, AUDIO_TICKS_PER_FRAME), CoreEvent::AudioTick(1));
+        self.schedule(self.frame_start + budget as u64, CoreEvent::VBlank);
+        if self.memory.take_timer_reload() {
+            self.schedule_timers();
+        }
+
+        let (mut cpu_time, mut audio_time) = (std::time::Duration::ZERO, std::time::Duration::ZERO);
+        let frame_start = Instant::now();
+        loop {
+            while let Some((_, event)) = self.scheduler.pop_due() {
+                let start = Instant::now();
+                match event {
+                    CoreEvent::Scanline(line) => {
+                        self.memory.set_scanline(line);
+                        if line + 1 < SCANLINES_PER_FRAME {
+                            self.schedule(self.frame_time(line + 1, SCANLINES_PER_FRAME), CoreEvent::Scanline(line + 1));
+                        }
+                    }
+                    CoreEvent::Interrupt(interrupt) => self.cpu.queue_interrupt(interrupt),
+                    CoreEvent::SoundAck => self.memory.raise_sound_ack(&mut self.cpu),
+                    CoreEvent::TimerOverflow(interrupt) => {
+                        self.memory.raise_timer_overflow(interrupt, &mut self.cpu);
+                        self.schedule_timers();
+                    }
+                    CoreEvent::AudioTick(tick) => {
+                        let cycles = self.frame_time(tick, AUDIO_TICKS_PER_FRAME) - self.frame_time(tick - 1, AUDIO_TICKS_PER_FRAME);
+                        if let Err(message) = isolate(|| self.audio.update(cycles as u32)) {
+                            // Le SCSP repart de son état de démarrage plutôt
+                            // que de rejouer l'état qui l'a fait paniquer
+                            let error = self.subsystem_panic(Subsystem::Audio, message, &[]);
+                            self.audio.reset();
+                            self.sound_hle.reset();
+                            return Err(error);
+                        }
+                        self.last_frame_audio_frames += self.audio.last_submitted_frames();
+                        self.schedule_sound_ack();
+                        if tick < AUDIO_TICKS_PER_FRAME {
+                            self.schedule(self.frame_time(tick + 1, AUDIO_TICKS_PER_FRAME), CoreEvent::AudioTick(tick + 1));
+                        }
+                        audio_time += start.elapsed();
+                    }
+                    CoreEvent::VBlank => {
+                        self.vblank();
+                        self.profiler.record(FrameScope::Io, start);
+                        self.profiler.add_span(FrameScope::Cpu, frame_start, cpu_time);
+                        self.profiler.add_span(FrameScope::Audio, frame_start, audio_time);
+                        return Ok(self.frame_cycles);
+                    }
+                }
+            }
+
+            // Le CPU s'exécute jusqu'à l'échéance suivante ; arrêté, il
+            // laisse simplement passer le temps
+            let now = self.scheduler.now();
+            let next = self.scheduler.next_time().unwrap_or(now);
+            let start = Instant::now();
+            let executed = match isolate(|| self.cpu.run_cycles((next - now) as u32, &mut self.memory)) {
+                Ok(executed) => executed?,
+                Err(message) => return Err(self.subsystem_panic(Subsystem::Cpu, message, &[])),
+            };
+            cpu_time += start.elapsed();
+            self.frame_cycles += executed;
+            self.scheduler.advance_to((now + executed as u64).max(next));
+            self.memory.advance_timers((self.scheduler.now() - now) as u32);
+            if self.memory.take_timer_reload() {
+                self.schedule_timers();
+            }
+        }
+    }
+
+    /// Redate les débordements des timers de la carte I/O d'après leur
+    /// valeur courante
+    fn schedule_timers(&mut self) {
+        self.scheduler.retain(|event| !matches!(event, CoreEvent::TimerOverflow(_)));
+        let (main, sub) = self.memory.io_registers().timers.cycles_until_overflow();
+        let now = self.scheduler.now();
+        self.schedule(now + main, CoreEvent::TimerOverflow(Interrupt::TimerMain));
+        self.schedule(now + sub, CoreEvent::TimerOverflow(Interrupt::TimerSub));
+    }
+
+    /// Date l'acquittement de la carte son à l'instant courant, s'il y en a un
+    fn schedule_sound_ack(&mut self) {
+        if self.memory.take_sound_ack() {
+            self.schedule(self.scheduler.now(), CoreEvent::SoundAck);
+        }
+    }
+
+    /// Erreur d'un panic rattrapé dans `subsystem`, avec l'état de la machine
+    /// et le lot de commandes GPU en cours
+    fn subsystem_panic(&self, subsystem: Subsystem, message: String, gpu_commands: &[GpuCommand]) -> EmulatorError {
+        let registers = &self.cpu.registers;
+        let report = CrashReport {
+            emulator_version: env!("CARGO_PKG_VERSION").to_string(),
+            game: self.session_report.report(self.frames).game,
+            subsystem,
+            message,
+            frame: self.frames,
+            cycle: self.scheduler.now(),
+            pc: registers.pc,
+            psw: registers.psw.bits(),
+            sp: registers.sp,
+            general: registers.general.to_vec(),
+            gpu_commands: gpu_commands.iter().map(|command| command.name().to_string()).collect(),
+            active_slots: (0..SCSP_SLOT_COUNT).filter(|&slot| self.audio.slot_active(slot)).collect(),
+        };
+        log::error!("{}", report);
+        EmulatorError::SubsystemPanic(Box::new(report))
+    }
+
+    /// Fin de frame : l'acquittement des commandes sonores lues en HLE est
+    /// daté, les entrées sont verrouillées, le VBlank est signalé, les
+    /// commandes de la carte de pilotage sont relevées, la liste d'affichage
+    /// terminée passe au GPU et les fenêtres partagées changent de
+    /// propriétaire
+    fn vblank(&mut self) {
+        if !self.sound_hle.is_empty() {
+            self.sound_hle.run_frame(self.memory.sound_latch(), &mut self.audio);
+        }
+        self.schedule_sound_ack();
+        self.memory.latch_inputs();
+        self.memory.raise_vblank(&mut self.cpu);
+        for &interrupt in self.memory.hacks().forced_interrupts() {
+            self.cpu.queue_interrupt(interrupt);
+        }
+
+        let frame = self.frames;
+        for event in self.memory.drive_board_mut().take_events(frame) {
+            if self.force_feedback.len() == DRIVE_COMMAND_BACKLOG {
+                self.force_feedback.pop_front();
+            }
+            self.force_feedback.push_back(event);
+        }
+
+        self.memory.swap_display_lists();
+        self.memory.hand_off_geometry_windows();
+        self.event_log.record(FrameEventKind::VBlank);
+        self.event_log.record_audio(self.last_frame_audio_frames);
+    }
+
+    /// Instantané des statistiques de tous les sous-systèmes
+    pub fn stats(&self) -> EmulatorStats {
+        EmulatorStats {
+            frames: self.frames,
+            cpu: CpuStats {
+                pc: self.cpu.registers.pc,
+                cycle_count: self.cpu.cycle_count,
+                last_frame_cycles: self.last_frame_cycles,
+                halted: self.cpu.halted,
+                pending_interrupts: self.cpu.pending_interrupts.len(),
+            },
+            clocks: ClockStats {
+                main_multiplier: self.clocks.main,
+                main_cycles_per_frame: self.clocks.main_cycles_per_frame(),
+                sound_multiplier: self.clocks.sound,
+                sound_cycles_per_frame: self.clocks.sound_cycles_per_frame(),
+            },
+            gpu_commands: self.memory.gpu_command_buffer.stats().clone(),
+            gpu: self.gpu_stats.clone(),
+        }
+    }
+}
+
+/// Exécute `work` derrière une barrière de panic ; un panic est rendu sous
+/// forme de message. Sans effet si le binaire est compilé avec
+/// `panic = "abort"`.
+fn isolate<T>(work: impl FnOnce() -> T) -> std::result::Result<T, String> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(work)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic sans message".to_string())
+    })
+}
+
+/// Traite un lot de commandes GPU ; les commandes ignorées sont relevées
+/// pour le rapport de compatibilité et suivent la politique de quarantaine
+/// du CPU
+fn process_gpu_command_batch(
+    commands: &[GpuCommand],
+    gpu: &mut Model2Gpu,
+    cpu: &mut NecV60,
+    report: &mut SessionReporter,
+    frame: u64,
+) -> Result<()> {
+    log::trace!("GPU: Traitement d'un lot de {} commandes", commands.len());
+
+    for command in commands {
+        if !process_gpu_command(command, gpu)? {
+            report.record_gpu_command(frame, command.name());
+            cpu.quarantine_gpu_command(command.name())?;
+        }
+    }
+    Ok(())
+}
+
+/// Traite une commande GPU ; faux si elle n'est pas implémentée
+fn process_gpu_command(command: &GpuCommand, gpu: &mut Model2Gpu) -> Result<bool> {
+    match command {
+        GpuCommand::ClearScreen { .. } => {
+            // Pour Model2Gpu, nous utilisons begin_frame/end_frame pour gérer le clear
+            gpu.begin_frame()?;
+        },
+        GpuCommand::SetModelMatrix(matrix) => {
+            gpu.geometry_processor.set_model_matrix(glam::Mat4::from_cols_array(matrix));
+        },
+        GpuCommand::SetViewMatrix(matrix) => {
+            gpu.geometry_processor.set_view_matrix(glam::Mat4::from_cols_array(matrix));
+        },
+        GpuCommand::SetProjectionMatrix(matrix) => {
+            gpu.geometry_processor.set_projection_matrix(glam::Mat4::from_cols_array(matrix));
+        },
+        GpuCommand::LoadTexture { id, data, width, height } => {
+            gpu.load_texture(*id, data, *width, *height)?;
+        },
+        GpuCommand::DrawTriangle { vertices, texture_id, texture_attributes } => {
+            gpu.draw_triangle(&convert_gpu_vertices_to_triangle(vertices, *texture_id, *texture_attributes))?;
+        },
+        GpuCommand::SetRenderState { state, enabled } => {
+            let render_state = match state {
+                RenderStateType::ZBuffer => RenderState::ZBuffer,
+                RenderStateType::Texturing => RenderState::Texturing,
+                RenderStateType::Lighting => RenderState::Lighting,
+                RenderStateType::Transparency => RenderState::Transparency,
+                RenderStateType::Fog => RenderState::Fog,
+                _ => RenderState::ZBuffer, // Défaut
+            };
+            gpu.set_render_state(render_state, *enabled);
+        },
+        GpuCommand::SetFog { enabled, start, end, color, .. } => {
+            // Seul le repli linéaire est calculé : la courbe réelle vient de
+            // la table de brouillard quand le jeu en fournit une
+            gpu.geometry_processor.set_fog(*enabled, *start, *end, *color);
+        },
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Convertit des GpuVertex en Triangle3D, avec l'adressage et la
+/// microtexture de l'en-tête du polygone
+fn convert_gpu_vertices_to_triangle(vertices: &[GpuVertex; 3], texture_id: Option<u32>, texture_attributes: u32) -> Triangle3D {
+    let vertex = |v: &GpuVertex| Vertex3D {
+        position: glam::Vec3::new(v.x, v.y, v.z),
+        normal: glam::Vec3::Z, // Normale par défaut
+        tex_coords: [v.u, v.v],
+        color: [v.r, v.g, v.b, v.a],
+        fog_coord: 0.0,
+        ambient: [0.0, 0.0, 0.0],
+        specular: [0.0, 0.0, 0.0],
+    };
+
+    Triangle3D {
+        vertices: [vertex(&vertices[0]), vertex(&vertices[1]), vertex(&vertices[2])],
+        texture_id,
+        material_id: 0,
+        flags: TriangleFlags::default().with_texture_attributes(texture_attributes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EmulatorConfig;
+    use crate::cpu::{QuarantineKind, QuarantinePolicy};
+    use crate::memory::INPUT_IDLE;
+
+    #[test]
+    fn test_run_frame_without_gpu_updates_stats() {
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        // RAM principale à zéro : suite de NOP
+        core.cpu.registers.pc = 0;
+
+        assert_eq!(core.stats().frames, 0);
+        let cycles = core.run_frame(0x42, None).unwrap();
+
+        let stats = core.stats();
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.cpu.last_frame_cycles, cycles);
+        assert_eq!(stats.cpu.cycle_count, core.cpu.cycle_count);
+        assert!(stats.gpu.is_none());
+        assert_eq!(core.memory.io_registers().input.data, 0x42);
+    }
+
+    #[test]
+    fn test_heatmap_is_taken_every_interval() {
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        core.cpu.registers.pc = 0;
+        let config = HeatmapConfig { enabled: true, interval_frames: 2, ..HeatmapConfig::default() };
+        core.set_heatmap(&config);
+
+        core.run_frame(INPUT_IDLE, None).unwrap();
+        assert!(core.take_heatmap().is_none());
+        core.run_frame(INPUT_IDLE, None).unwrap();
+        let snapshot = core.take_heatmap().unwrap();
+        assert_eq!(snapshot.frames, 2);
+        // Les NOP sont lus à la suite depuis le début de la RAM principale
+        let hottest = snapshot.hottest(1)[0];
+        assert!(hottest.reads > 0 && hottest.address < core.cpu.registers.pc);
+        assert!(snapshot.writes.iter().all(|&writes| writes == 0));
+        assert!(core.take_heatmap().is_none());
+
+        // Lectures des surveillances et du débogueur : non comptées
+        let heatmap = core.heatmap.clone().unwrap();
+        core.memory.peek_u32(0).unwrap();
+        assert!(heatmap.take(1).hottest(1).is_empty());
+
+        // Carte coupée : débranchée du bus
+        core.set_heatmap(&HeatmapConfig::default());
+        core.memory.read_u32(0).unwrap();
+        assert!(core.heatmap.is_none() && heatmap.take(1).hottest(1).is_empty());
+    }
+
+    #[test]
+    fn test_audio_follows_emulated_time() {
+        let mut audio = EmulatorConfig::default().audio;
+        audio.backend = crate::config::AudioBackendKind::Null;
+        let mut core = EmulatorCore::new(&audio);
+        // CPU arrêté : le temps émulé avance quand même
+        core.cpu.halted = true;
+        core.clocks = CpuClocks::new(1.5, 1.0);
+
+        let mut frames = 0;
+        for _ in 0..60 {
+            core.run_frame(0, None).unwrap();
+            frames += core.last_frame_audio_frames();
+        }
+        assert_eq!(frames, audio.sample_rate);
+    }
+
+    #[test]
+    fn test_subsystem_panic_is_recoverable() {
+        struct PanickingBackend;
+
+        impl crate::audio::AudioBackend for PanickingBackend {
+            fn name(&self) -> &str {
+                "panique"
+            }
+            fn sample_rate(&self) -> u32 {
+                44100
+            }
+            fn channels(&self) -> u16 {
+                2
+            }
+            fn push_samples(&mut self, _samples: &[f32]) {
+                panic!("sortie audio en panne");
+            }
+            fn queued_frames(&self) -> usize {
+                0
+            }
+            fn consumed_frames(&self) -> u64 {
+                0
+            }
+        }
+
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        core.audio = ScspAudio::with_backend(Box::new(PanickingBackend));
+        core.cpu.registers.pc = 0x100;
+        core.cpu.registers.general[3] = 0x1234;
+        core.session_report.enable();
+        core.session_report.begin_game("vf2");
+
+        let Err(EmulatorError::SubsystemPanic(report)) = core.run_frame(0, None) else {
+            panic!("le panic de l'audio doit être rattrapé");
+        };
+        assert_eq!(report.subsystem, Subsystem::Audio);
+        assert_eq!(report.message, "sortie audio en panne");
+        assert_eq!(report.game.as_deref(), Some("vf2"));
+        assert_eq!(report.general[3], 0x1234);
+        assert!(core.session_report.report(0).first_error.unwrap().contains("sortie audio en panne"));
+
+        let path = std::env::temp_dir().join(format!("pm2-crash-{}.json", std::process::id()));
+        report.write(&path).unwrap();
+        let written: CrashReport = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, *report);
+
+        // La frame suivante repart normalement
+        core.audio = ScspAudio::headless();
+        core.run_frame(0, None).unwrap();
+        assert_eq!(core.stats().frames, 1);
+    }
+
+    #[test]
+    fn test_core_runs_on_worker_thread() {
+        fn assert_send<T: Send>() {}
+        assert_send::<EmulatorCore>();
+
+        let mut audio = EmulatorConfig::default().audio;
+        audio.backend = crate::config::AudioBackendKind::Null;
+        let mut core = EmulatorCore::new(&audio);
+        core.cpu.registers.pc = 0;
+
+        let core = std::thread::spawn(move || {
+            core.run_frame(0, None).unwrap();
+            core
+        })
+        .join()
+        .unwrap();
+        assert_eq!(core.stats().frames, 1);
+    }
+
+    #[test]
+    fn test_quarantine_policy_for_unknown_opcode() {
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        // Opcode 0x0B (format 1) non décodé, suivi de NOP
+        core.memory.write_u8(0x101, 0x2C).unwrap();
+        core.cpu.registers.pc = 0x100;
+        core.session_report.enable();
+        assert!(core.run_frame(0, None).is_err());
+
+        core.cpu.registers.pc = 0x100;
+        core.cpu.quarantine.policy = QuarantinePolicy::Skip;
+        core.run_frame(0, None).unwrap();
+        let counter = core.cpu.quarantine.counter(QuarantineKind::UnknownOpcode, "0xB0000").unwrap();
+        assert_eq!(counter.first_address, 0x100);
+        assert_eq!(core.session_report.report(1).first_unknown_opcode.unwrap().address, 0x100);
+        assert!(core.cpu.registers.pc > 0x102);
+
+        core.cpu.registers.pc = 0x100;
+        core.cpu.quarantine.policy = QuarantinePolicy::Break;
+        core.run_frame(0, None).unwrap();
+        assert_eq!(core.cpu.registers.pc, 0x102);
+        assert!(core.cpu.quarantine.take_break().is_some());
+        assert_eq!(core.cpu.quarantine.counter(QuarantineKind::UnknownOpcode, "0xB0000").unwrap().count, 3);
+    }
+
+    #[test]
+    fn test_forced_interrupt_hack_queues_each_frame() {
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        core.cpu.interrupts_enabled = false;
+        core.memory.set_hacks(crate::memory::GameHacks::new([crate::memory::Hack::ForceVblank])).unwrap();
+
+        core.run_frame(0, None).unwrap();
+        assert_eq!(core.cpu.pending_interrupts, vec![crate::cpu::Interrupt::VBlank]);
+    }
+
+    #[test]
+    fn test_scheduled_interrupt_fires_at_its_cycle() {
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        core.cpu.interrupts_enabled = false;
+        core.cpu.halted = true;
+        core.schedule_interrupt(CYCLES_PER_FRAME as u64 + 1000, Interrupt::TimerMain);
+
+        core.run_frame(0, None).unwrap();
+        assert_eq!(core.now(), CYCLES_PER_FRAME as u64);
+        assert_eq!(core.cpu.pending_interrupts, vec![Interrupt::VBlank]);
+
+        core.run_frame(0, None).unwrap();
+        assert_eq!(core.now(), 2 * CYCLES_PER_FRAME as u64);
+        assert_eq!(core.cpu.pending_interrupts, vec![Interrupt::VBlank, Interrupt::TimerMain]);
+    }
+
+    #[test]
+    fn test_timers_and_sound_ack_follow_emulated_time() {
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        core.cpu.interrupts_enabled = false;
+        // CPU arrêté : les timers comptent quand même toute la frame
+        core.cpu.halted = true;
+        core.memory.sound_latch().sound_write(crate::memory::SCSP_MIDI_OUTPUT, 0x01);
+
+        core.run_frame(0, None).unwrap();
+        assert_eq!(core.memory.io_registers().timers.main, CYCLES_PER_FRAME);
+        assert_eq!(core.memory.io_registers().timers.sub, CYCLES_PER_FRAME / 4);
+        // L'acquittement est levé à la tranche audio, avant le VBlank
+        assert_eq!(core.cpu.pending_interrupts, vec![Interrupt::Audio, Interrupt::VBlank]);
+    }
+
+    #[test]
+    fn test_timer_overflow_is_scheduled_when_enabled() {
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        core.cpu.interrupts_enabled = false;
+        core.cpu.halted = true;
+        // Débordement du timer principal au milieu de la frame, autorisé ;
+        // celui de sous-système reste masqué
+        use crate::memory::{INTERRUPT_CONTROL_REGISTER, INTERRUPT_TIMER_MAIN, IO_REGISTERS_BASE, TIMER_MAIN_REGISTER, TIMER_SUB_REGISTER};
+        core.memory.write_u32(IO_REGISTERS_BASE + INTERRUPT_CONTROL_REGISTER, INTERRUPT_TIMER_MAIN).unwrap();
+        core.memory.write_u32(IO_REGISTERS_BASE + TIMER_MAIN_REGISTER, (CYCLES_PER_FRAME / 2).wrapping_neg()).unwrap();
+        core.memory.write_u32(IO_REGISTERS_BASE + TIMER_SUB_REGISTER, 1u32.wrapping_neg()).unwrap();
+
+        core.run_frame(0, None).unwrap();
+        assert_eq!(core.cpu.pending_interrupts, vec![Interrupt::TimerMain, Interrupt::VBlank]);
+        assert_eq!(core.memory.io_registers().interrupts.status & 0x06, INTERRUPT_TIMER_MAIN);
+    }
+
+    #[test]
+    fn test_soft_reset_keeps_ram_and_restarts_on_reset_vector() {
+        let mut core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        core.memory.write_u32(0x4, 0x100).unwrap();
+        core.memory.write_u32(0x2000, 0xDEAD_BEEF).unwrap();
+        core.memory.set_input_data(0x42);
+        core.memory.latch_inputs();
+        core.cpu.registers.pc = 0x1234;
+        core.cpu.cycle_count = 1000;
+
+        core.soft_reset().unwrap();
+        assert_eq!(core.cpu.registers.pc, 0x100);
+        assert_eq!(core.cpu.cycle_count, 0);
+        assert_eq!(core.memory.io_registers().input.data, INPUT_IDLE);
+        assert_eq!(core.memory.read_u32(0x2000).unwrap(), 0xDEAD_BEEF);
+    }
+
+    #[test]
+    fn test_draw_applies_polygon_texture_attributes() {
+        use crate::gpu::geometry::{texture_attributes::*, TextureWrap};
+
+        let vertices = [GpuVertex::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0); 3];
+        let triangle = convert_gpu_vertices_to_triangle(&vertices, Some(1), CLAMP_U | MICROTEXTURE | 2 << MICROTEXTURE_SHIFT);
+        assert_eq!(triangle.flags.wrap_u, TextureWrap::Clamp);
+        assert_eq!(triangle.flags.wrap_v, TextureWrap::Repeat);
+        assert_eq!(triangle.flags.microtexture, Some(2));
+    }
+}
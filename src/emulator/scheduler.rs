@@ -0,0 +1,194 @@
+//! File d'événements horodatés en cycles du CPU principal
+//!
+//! Les périphériques inscrivent leurs échéances (début de ligne, VBlank,
+//! tranche de génération audio, acquittement de la carte son, débordement
+//! d'un timer) à une date absolue en cycles du V60. Le cœur exécute le CPU
+//! jusqu'à l'échéance la plus proche, traite les événements échus puis
+//! reprend : chaque périphérique avance au moment où il doit agir au lieu
+//! d'une seule mise à jour par frame. À date égale, les événements sortent
+//! par priorité croissante, puis dans l'ordre d'inscription.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::cpu::Interrupt;
+
+/// Échéance inscrite
+#[derive(Debug, Clone)]
+struct Entry<E> {
+    time: u64,
+    priority: u8,
+    sequence: u64,
+    event: E,
+}
+
+impl<E> Entry<E> {
+    fn key(&self) -> (u64, u8, u64) {
+        (self.time, self.priority, self.sequence)
+    }
+}
+
+impl<E> PartialEq for Entry<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<E> Eq for Entry<E> {}
+
+impl<E> PartialOrd for Entry<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E> Ord for Entry<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// File d'événements et date courante
+#[derive(Debug, Clone)]
+pub struct Scheduler<E> {
+    now: u64,
+    sequence: u64,
+    queue: BinaryHeap<Reverse<Entry<E>>>,
+}
+
+impl<E> Default for Scheduler<E> {
+    fn default() -> Self {
+        Self { now: 0, sequence: 0, queue: BinaryHeap::new() }
+    }
+}
+
+impl<E> Scheduler<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Date courante, en cycles depuis le lancement
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Fait avancer la date courante ; elle ne recule jamais
+    pub fn advance_to(&mut self, time: u64) {
+        self.now = self.now.max(time);
+    }
+
+    /// Inscrit `event` à la date absolue `time` ; une date passée le rend
+    /// échu immédiatement
+    pub fn schedule(&mut self, time: u64, priority: u8, event: E) {
+        self.sequence += 1;
+        self.queue.push(Reverse(Entry { time, priority, sequence: self.sequence, event }));
+    }
+
+    /// Inscrit `event` dans `delay` cycles
+    pub fn schedule_in(&mut self, delay: u64, priority: u8, event: E) {
+        self.schedule(self.now + delay, priority, event);
+    }
+
+    /// Date de la prochaine échéance
+    pub fn next_time(&self) -> Option<u64> {
+        self.queue.peek().map(|Reverse(entry)| entry.time)
+    }
+
+    /// Retire le prochain événement échu à la date courante, avec sa date
+    pub fn pop_due(&mut self) -> Option<(u64, E)> {
+        if self.next_time()? > self.now {
+            return None;
+        }
+        self.queue.pop().map(|Reverse(entry)| (entry.time, entry.event))
+    }
+
+    /// Ne garde que les événements pour lesquels `keep` est vrai
+    pub fn retain(&mut self, mut keep: impl FnMut(&E) -> bool) {
+        self.queue.retain(|Reverse(entry)| keep(&entry.event));
+    }
+
+    /// Événements en attente
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+/// Événements du cœur d'émulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreEvent {
+    /// Début d'une ligne du balayage
+    Scanline(u32),
+
+    /// Interruption levée à échéance ([`EmulatorCore::schedule_interrupt`](super::EmulatorCore::schedule_interrupt))
+    Interrupt(Interrupt),
+
+    /// Fin d'une tranche de génération audio de la frame
+    AudioTick(u32),
+
+    /// Acquittement de la carte son (commande lue, réponse écrite) :
+    /// interruption audio du V60
+    SoundAck,
+
+    /// Débordement d'un timer de la carte I/O (`TimerMain` ou `TimerSub`)
+    TimerOverflow(Interrupt),
+
+    /// Fin de frame : registres I/O, interruption VBlank et échange des
+    /// listes d'affichage
+    VBlank,
+}
+
+impl CoreEvent {
+    /// Ordre de traitement à date égale : la ligne commence avant que ses
+    /// interruptions ne tombent, et l'audio de la frame est complet avant
+    /// le VBlank
+    pub fn priority(self) -> u8 {
+        match self {
+            CoreEvent::Scanline(_) => 0,
+            CoreEvent::Interrupt(_) | CoreEvent::SoundAck | CoreEvent::TimerOverflow(_) => 1,
+            CoreEvent::AudioTick(_) => 2,
+            CoreEvent::VBlank => 3,
+        }
+    }
+
+    /// Événement propre à une frame, réinscrit au début de chacune
+    pub fn is_frame_event(self) -> bool {
+        !matches!(self, CoreEvent::Interrupt(_) | CoreEvent::SoundAck | CoreEvent::TimerOverflow(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_pop_by_time_then_priority_then_order() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule(100, CoreEvent::VBlank.priority(), CoreEvent::VBlank);
+        scheduler.schedule(100, CoreEvent::AudioTick(1).priority(), CoreEvent::AudioTick(1));
+        scheduler.schedule(40, 1, CoreEvent::Interrupt(Interrupt::TimerMain));
+        scheduler.schedule(40, 1, CoreEvent::Interrupt(Interrupt::Audio));
+        assert_eq!(scheduler.next_time(), Some(40));
+        assert_eq!(scheduler.pop_due(), None);
+
+        scheduler.advance_to(100);
+        let order: Vec<CoreEvent> = std::iter::from_fn(|| scheduler.pop_due()).map(|(_, event)| event).collect();
+        assert_eq!(order, [
+            CoreEvent::Interrupt(Interrupt::TimerMain),
+            CoreEvent::Interrupt(Interrupt::Audio),
+            CoreEvent::AudioTick(1),
+            CoreEvent::VBlank,
+        ]);
+
+        // La date ne recule pas ; une échéance passée est échue aussitôt
+        scheduler.advance_to(50);
+        scheduler.schedule_in(0, 0, CoreEvent::Scanline(3));
+        scheduler.schedule(10, 0, CoreEvent::Scanline(2));
+        scheduler.retain(|event| *event != CoreEvent::Scanline(3));
+        assert_eq!(scheduler.pop_due(), Some((10, CoreEvent::Scanline(2))));
+        assert!(scheduler.is_empty());
+    }
+}
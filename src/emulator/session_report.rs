@@ -0,0 +1,171 @@
+//! Rapport de compatibilité d'une session, à joindre aux tickets
+//!
+//! Sur demande de l'utilisateur uniquement (`compat_report` dans la
+//! configuration ou `--compat-report`), le cœur relève pendant la session
+//! le jeu chargé, la dernière frame atteinte et la première occurrence de
+//! chaque manque de l'émulation : opcode inconnu, instruction non
+//! implémentée, commande GPU non traitée. Rien n'est envoyé : le rapport est
+//! écrit en JSON à la fermeture et ne contient ni chemin, ni ROM, ni
+//! donnée du joueur. Il sert à tenir à jour l'état des jeux du rapport
+//! livré avec l'émulateur ([`crate::rom::CompatibilityReport`]).
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+
+use crate::error::{CpuException, EmulatorError};
+
+/// Opcode que le décodeur ne reconnaît pas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnknownOpcodeRecord {
+    pub frame: u64,
+    pub address: u32,
+    pub opcode: u32,
+}
+
+/// Instruction décodée mais pas encore exécutable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnimplementedInstructionRecord {
+    pub frame: u64,
+    pub address: u32,
+    pub mnemonic: String,
+}
+
+/// Commande GPU reçue mais ignorée
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnimplementedGpuCommandRecord {
+    pub frame: u64,
+    pub command: String,
+}
+
+/// Contenu du rapport exporté
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionReport {
+    /// Version de l'émulateur
+    pub emulator_version: String,
+    /// Nom court du jeu (`daytona`, `vf2`...)
+    pub game: Option<String>,
+    /// Frames émulées avant la fermeture ou l'arrêt
+    pub frames_reached: u64,
+    pub first_unknown_opcode: Option<UnknownOpcodeRecord>,
+    pub first_unimplemented_instruction: Option<UnimplementedInstructionRecord>,
+    pub first_unimplemented_gpu_command: Option<UnimplementedGpuCommandRecord>,
+    /// Première autre erreur ayant interrompu une frame
+    pub first_error: Option<String>,
+}
+
+impl SessionReport {
+    /// Vrai si aucun manque de l'émulation n'a été rencontré
+    pub fn is_clean(&self) -> bool {
+        self.first_unknown_opcode.is_none()
+            && self.first_unimplemented_instruction.is_none()
+            && self.first_unimplemented_gpu_command.is_none()
+            && self.first_error.is_none()
+    }
+
+    pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(io::Error::other)
+    }
+
+    pub fn read<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader).map_err(io::Error::other)
+    }
+}
+
+/// Relevé du rapport pendant la session ; inactif tant qu'il n'est pas
+/// activé
+#[derive(Debug, Clone, Default)]
+pub struct SessionReporter {
+    enabled: bool,
+    report: SessionReport,
+}
+
+impl SessionReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Nouveau jeu : les relevés précédents sont oubliés
+    pub fn begin_game(&mut self, game: &str) {
+        self.report = SessionReport { game: Some(game.to_string()), ..Default::default() };
+    }
+
+    /// Erreur ayant interrompu la frame `frame`
+    pub fn record_error(&mut self, frame: u64, error: &EmulatorError) {
+        if !self.enabled {
+            return;
+        }
+        let report = &mut self.report;
+        match error {
+            EmulatorError::CpuException(CpuException::UnknownOpcode { address, opcode }) => {
+                report.first_unknown_opcode.get_or_insert(UnknownOpcodeRecord { frame, address: *address, opcode: *opcode });
+            }
+            EmulatorError::CpuException(CpuException::Unimplemented { address, mnemonic }) => {
+                report.first_unimplemented_instruction.get_or_insert_with(|| UnimplementedInstructionRecord {
+                    frame,
+                    address: *address,
+                    mnemonic: mnemonic.clone(),
+                });
+            }
+            error => {
+                report.first_error.get_or_insert_with(|| error.to_string());
+            }
+        }
+    }
+
+    /// Commande GPU ignorée pendant la frame `frame`
+    pub fn record_gpu_command(&mut self, frame: u64, command: &str) {
+        if self.enabled {
+            self.report
+                .first_unimplemented_gpu_command
+                .get_or_insert_with(|| UnimplementedGpuCommandRecord { frame, command: command.to_string() });
+        }
+    }
+
+    /// Rapport arrêté à `frames` frames émulées
+    pub fn report(&self, frames: u64) -> SessionReport {
+        SessionReport {
+            emulator_version: env!("CARGO_PKG_VERSION").to_string(),
+            frames_reached: frames,
+            ..self.report.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_first_occurrences_only_when_enabled() {
+        let mut reporter = SessionReporter::new();
+        reporter.begin_game("daytona");
+        reporter.record_gpu_command(1, "DrawQuad");
+        assert!(reporter.report(1).is_clean());
+
+        reporter.enable();
+        let unknown = |address| CpuException::UnknownOpcode { address, opcode: 0xFF }.into();
+        reporter.record_error(10, &unknown(0x100));
+        reporter.record_error(20, &unknown(0x200));
+        reporter.record_gpu_command(12, "DrawQuad");
+        reporter.record_gpu_command(13, "SetCulling");
+
+        let report = reporter.report(42);
+        assert_eq!(report.game.as_deref(), Some("daytona"));
+        assert_eq!(report.frames_reached, 42);
+        assert_eq!(report.first_unknown_opcode, Some(UnknownOpcodeRecord { frame: 10, address: 0x100, opcode: 0xFF }));
+        assert_eq!(report.first_unimplemented_gpu_command.as_ref().unwrap().command, "DrawQuad");
+        assert!(!report.is_clean());
+
+        let mut json = Vec::new();
+        report.write(&mut json).unwrap();
+        assert_eq!(SessionReport::read(json.as_slice()).unwrap(), report);
+    }
+}
@@ -0,0 +1,195 @@
+//! Mode test son
+//!
+//! Valide l'émulation audio sans démarrer le jeu : seules les ROMs
+//! d'échantillons du jeu sont chargées dans la mémoire d'ondes du SCSP, et
+//! les commandes sonores tapées au terminal passent par le port série comme
+//! si le V60 les envoyait. Le 68000 de la carte son n'étant pas émulé, c'est
+//! le mode HLE ([`SoundHle`]) qui les lit, avec la table des commandes de la
+//! base des jeux : une commande absente de la table est signalée comme
+//! inconnue. Un jeu sans table n'a rien à jouer ; le mode est alors refusé.
+//! Le programme du 68000 n'est pas chargé : ce n'est pas un échantillon.
+
+use anyhow::Result;
+use std::fmt;
+use std::str::FromStr;
+
+use super::CYCLES_PER_FRAME;
+use crate::audio::{ScspAudio, SoundHle, WAVE_MEMORY_SIZE};
+use crate::memory::{SoundLatch, SOUND_LATCH_DATA};
+use crate::rom::{Model2RomSystem, RomType};
+
+/// Saisie du terminal en mode test son
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundTestInput {
+    /// Commande sonore, en hexadécimal (`0x1A`, `1Ah`) ou en décimal
+    Command(u8),
+    /// Arrête tous les slots et les séquences
+    Stop,
+    /// Liste les commandes connues
+    List,
+    Quit,
+}
+
+impl FromStr for SoundTestInput {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let text = text.trim();
+        match text.to_ascii_lowercase().as_str() {
+            "stop" | "s" => return Ok(SoundTestInput::Stop),
+            "list" | "l" | "?" => return Ok(SoundTestInput::List),
+            "quit" | "q" | "exit" => return Ok(SoundTestInput::Quit),
+            _ => {}
+        }
+        let command = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+            u8::from_str_radix(hex, 16)
+        } else if let Some(hex) = text.strip_suffix('h').or_else(|| text.strip_suffix('H')) {
+            u8::from_str_radix(hex, 16)
+        } else {
+            text.parse()
+        };
+        command
+            .map(SoundTestInput::Command)
+            .map_err(|_| anyhow::anyhow!("Commande sonore invalide: {} (0x00 à 0xFF, stop, list, quit)", text))
+    }
+}
+
+/// Résultat de l'envoi d'une commande
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoundTestReply {
+    /// Commande de la table, avec son nom
+    Known(u8, String),
+    /// Commande absente de la table, ignorée par le mode HLE
+    Unknown(u8),
+}
+
+impl fmt::Display for SoundTestReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SoundTestReply::Known(command, name) if name.is_empty() => write!(f, "0x{:02X}", command),
+            SoundTestReply::Known(command, name) => write!(f, "0x{:02X} {}", command, name),
+            SoundTestReply::Unknown(command) => write!(f, "0x{:02X} inconnue", command),
+        }
+    }
+}
+
+/// Processeur sonore d'un jeu, isolé du reste de la carte
+pub struct SoundTest {
+    pub audio: ScspAudio,
+    pub hle: SoundHle,
+    latch: SoundLatch,
+    frames: u64,
+}
+
+impl SoundTest {
+    /// Processeur sonore jouant `hle` sur la mémoire d'ondes `wave`
+    pub fn new(mut audio: ScspAudio, hle: SoundHle, wave: &[u8]) -> Self {
+        audio.set_master_clock(CYCLES_PER_FRAME * 60);
+        audio.load_wave_memory(&wave[..wave.len().min(WAVE_MEMORY_SIZE)]);
+        Self { audio, hle, latch: SoundLatch::new(), frames: 0 }
+    }
+
+    /// Charge les ROMs d'échantillons et la table des commandes de
+    /// `game_name` ; refusé si la base ne décrit aucune commande du jeu
+    pub fn load(rom_system: &mut Model2RomSystem, game_name: &str, audio: ScspAudio) -> Result<Self> {
+        let game = rom_system
+            .rom_manager
+            .database()
+            .find_game(game_name)
+            .ok_or_else(|| anyhow::anyhow!("Jeu inconnu: {}", game_name))?
+            .clone();
+        if game.system_config.sound_commands.is_empty() {
+            anyhow::bail!(
+                "{}: la base des jeux ne décrit aucune commande sonore (sound_commands) ; sans émulation \
+                 du 68000, le mode test son n'aurait rien à jouer",
+                game.short_name
+            );
+        }
+
+        // Échantillons, dans l'ordre de la base
+        let mut wave = Vec::new();
+        for info in game.required_roms.iter().chain(&game.optional_roms).filter(|info| info.rom_type == RomType::Samples) {
+            match rom_system.rom_manager.load_rom(&info.filename, Some(info)) {
+                Ok(rom) => wave.extend_from_slice(&rom.data),
+                Err(e) if info.required => return Err(e.into()),
+                Err(_) => {}
+            }
+        }
+        if wave.is_empty() {
+            log::warn!("{}: aucune ROM d'échantillons dans la base, mémoire d'ondes vide", game.short_name);
+        } else if wave.len() > WAVE_MEMORY_SIZE {
+            log::warn!("{}: ROMs d'échantillons tronquées à {} octets", game.short_name, WAVE_MEMORY_SIZE);
+        }
+
+        let hle = SoundHle::new(game.system_config.sound_commands.iter().cloned());
+        Ok(Self::new(audio, hle, &wave))
+    }
+
+    /// Envoie une commande sur le port série, lue à la frame suivante
+    pub fn send(&mut self, command: u8) -> SoundTestReply {
+        self.latch.main_write(SOUND_LATCH_DATA, command as u32);
+        match self.hle.command(command) {
+            Some(entry) => SoundTestReply::Known(command, entry.name.clone()),
+            None => SoundTestReply::Unknown(command),
+        }
+    }
+
+    /// Arrête tous les sons
+    pub fn stop(&mut self) {
+        self.hle.reset();
+        self.audio.stop_all_slots();
+    }
+
+    /// Fait avancer le processeur sonore d'une frame
+    pub fn run_frame(&mut self) {
+        self.hle.run_frame(&mut self.latch, &mut self.audio);
+        self.latch.take_main_irq();
+        self.audio.update(CYCLES_PER_FRAME);
+        self.frames += 1;
+    }
+
+    /// Frames exécutées depuis le chargement
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SoundCommandConfig;
+
+    #[test]
+    fn test_sound_test_plays_terminal_commands() {
+        assert_eq!("0x1a".parse::<SoundTestInput>().unwrap(), SoundTestInput::Command(0x1A));
+        assert_eq!("20h".parse::<SoundTestInput>().unwrap(), SoundTestInput::Command(0x20));
+        assert_eq!(" 12 ".parse::<SoundTestInput>().unwrap(), SoundTestInput::Command(12));
+        assert_eq!("STOP".parse::<SoundTestInput>().unwrap(), SoundTestInput::Stop);
+        assert!("0x100".parse::<SoundTestInput>().is_err());
+
+        let json = r#"[{"command": 16, "name": "pièce", "action": "sample", "slot": 3, "start": 0, "end": 256, "loop_start": 0}]"#;
+        let commands: Vec<SoundCommandConfig> = serde_json::from_str(json).unwrap();
+        let mut test = SoundTest::new(ScspAudio::headless(), SoundHle::new(commands), &[0x40; 512]);
+
+        assert_eq!(test.send(0x10).to_string(), "0x10 pièce");
+        assert_eq!(test.send(0x11), SoundTestReply::Unknown(0x11));
+        assert!(!test.audio.slot_active(3));
+        test.run_frame();
+        assert!(test.audio.slot_active(3));
+        assert_eq!(test.hle.unknown_commands().get(&0x11), Some(&1));
+
+        // Arrêt : le slot s'éteint à la fin de sa phase de relâchement
+        test.stop();
+        for _ in 0..60 {
+            test.run_frame();
+        }
+        assert!(!test.audio.slot_active(3));
+        assert_eq!(test.frames(), 61);
+
+        // Jeu sans table de commandes : rien à jouer, mode refusé
+        let mut rom_system = Model2RomSystem::new();
+        assert!(SoundTest::load(&mut rom_system, "inconnu", ScspAudio::headless()).is_err());
+        let error = SoundTest::load(&mut rom_system, "vf2", ScspAudio::headless()).err().unwrap();
+        assert!(error.to_string().contains("sound_commands"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Statistiques de l'émulateur, sérialisables pour les outils de suivi
+
+use serde::{Deserialize, Serialize};
+
+use crate::gpu::{GeometryStats, Model2Gpu, TextureCacheStats};
+use crate::memory::CommandBufferStats;
+use crate::profiling::{FrameScope, FrameTimings};
+
+/// Instantané de tous les sous-systèmes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmulatorStats {
+    /// Frames émulées depuis le lancement
+    pub frames: u64,
+    pub cpu: CpuStats,
+    pub clocks: ClockStats,
+    /// Commandes graphiques écrites par le jeu
+    pub gpu_commands: CommandBufferStats,
+    /// Absent tant qu'aucun GPU n'est initialisé
+    pub gpu: Option<GpuStats>,
+}
+
+/// État du V60
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CpuStats {
+    pub pc: u32,
+    pub cycle_count: u64,
+    pub last_frame_cycles: u32,
+    pub halted: bool,
+    pub pending_interrupts: usize,
+}
+
+/// Multiplicateurs d'horloge et budgets de cycles par frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClockStats {
+    pub main_multiplier: f32,
+    pub main_cycles_per_frame: u32,
+    pub sound_multiplier: f32,
+    pub sound_cycles_per_frame: u32,
+}
+
+/// Rendu, textures et géométrie
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuStats {
+    pub frames_rendered: u64,
+    pub triangles_drawn: u32,
+    pub pixels_drawn: u64,
+    pub last_frame_time_us: u64,
+    pub average_fps: f32,
+    pub present_latency_ms: f32,
+    pub device_recoveries: u32,
+    pub frame_timings: FrameTimingStats,
+    pub textures: TextureCacheStats,
+    pub geometry: GeometryStats,
+}
+
+impl GpuStats {
+    pub fn capture(gpu: &Model2Gpu) -> Self {
+        let render = &gpu.stats;
+        Self {
+            frames_rendered: render.frames_rendered,
+            triangles_drawn: render.triangles_drawn,
+            pixels_drawn: render.pixels_drawn,
+            last_frame_time_us: render.last_frame_time_us,
+            average_fps: render.average_fps,
+            present_latency_ms: render.present_latency_ms,
+            device_recoveries: render.device_recoveries,
+            frame_timings: FrameTimingStats::from(&render.frame_timings),
+            textures: gpu.texture_manager.stats(),
+            geometry: gpu.geometry_stats(),
+        }
+    }
+}
+
+/// Répartition du temps de la dernière frame, en microsecondes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct FrameTimingStats {
+    pub cpu_us: u64,
+    pub tgp_us: u64,
+    pub rasterize_us: u64,
+    pub present_us: u64,
+    pub audio_us: u64,
+    pub io_us: u64,
+}
+
+impl From<&FrameTimings> for FrameTimingStats {
+    fn from(timings: &FrameTimings) -> Self {
+        Self {
+            cpu_us: timings.get(FrameScope::Cpu),
+            tgp_us: timings.get(FrameScope::Tgp),
+            rasterize_us: timings.get(FrameScope::Rasterize),
+            present_us: timings.get(FrameScope::Present),
+            audio_us: timings.get(FrameScope::Audio),
+            io_us: timings.get(FrameScope::Io),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut timings = FrameTimings::default();
+        timings.add(FrameScope::Tgp, Duration::from_micros(250));
+
+        let stats = EmulatorStats {
+            frames: 12,
+            cpu: CpuStats { pc: 0x1000, cycle_count: 99, last_frame_cycles: 33, halted: false, pending_interrupts: 1 },
+            clocks: ClockStats { main_multiplier: 1.5, main_cycles_per_frame: 410_000, sound_multiplier: 1.0, sound_cycles_per_frame: 188_333 },
+            gpu_commands: CommandBufferStats { total_commands_processed: 40, batches_processed: 2, average_batch_size: 20.0, max_batch_size: 32 },
+            gpu: Some(GpuStats {
+                frames_rendered: 11,
+                triangles_drawn: 5000,
+                pixels_drawn: 0,
+                last_frame_time_us: 900,
+                average_fps: 60.0,
+                present_latency_ms: 16.7,
+                device_recoveries: 0,
+                frame_timings: FrameTimingStats::from(&timings),
+                textures: TextureCacheStats { textures: 3, microtextures: 1, bytes: 4096, wrapped_bind_groups: 2 },
+                geometry: GeometryStats { triangles_transformed: 5000, vertices_transformed: 15000, queued_for_sorting: 0 },
+            }),
+        };
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["gpu"]["frame_timings"]["tgp_us"], 250);
+        assert_eq!(json["gpu_commands"]["max_batch_size"], 32);
+        assert_eq!(serde_json::from_value::<EmulatorStats>(json).unwrap(), stats);
+    }
+}
@@ -0,0 +1,154 @@
+//! Point d'accès HTTP local aux statistiques
+//!
+//! Sert le dernier instantané publié au format JSON sur `GET /stats`, pour
+//! les tableaux de bord de suivi. Le serveur n'écoute que sur la boucle
+//! locale et tourne dans son propre thread ; l'émulation ne fait que
+//! remplacer l'instantané partagé à chaque frame. Aucun en-tête CORS n'est
+//! envoyé : une page web quelconque ne peut pas lire les statistiques.
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use super::EmulatorStats;
+
+/// Délai entre deux scrutations des connexions entrantes
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Serveur HTTP des statistiques
+pub struct StatsServer {
+    address: SocketAddr,
+    latest: Arc<Mutex<Option<EmulatorStats>>>,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl StatsServer {
+    /// Démarre le serveur sur `127.0.0.1:port` (port 0 : choisi par le système)
+    pub fn start(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .with_context(|| format!("Impossible d'écouter sur le port {}", port))?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+
+        let latest = Arc::new(Mutex::new(None));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let latest = latest.clone();
+            let shutdown = shutdown.clone();
+            std::thread::Builder::new()
+                .name("stats-server".into())
+                .spawn(move || serve(listener, latest, shutdown))?
+        };
+
+        Ok(Self { address, latest, shutdown, thread: Some(thread) })
+    }
+
+    /// Adresse d'écoute effective
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Remplace l'instantané servi
+    pub fn publish(&self, stats: EmulatorStats) {
+        *self.latest.lock() = Some(stats);
+    }
+}
+
+impl Drop for StatsServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve(listener: TcpListener, latest: Arc<Mutex<Option<EmulatorStats>>>, shutdown: Arc<AtomicBool>) {
+    while !shutdown.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(e) = respond(stream, &latest) {
+                    log::debug!("Requête de statistiques interrompue: {}", e);
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => log::warn!("Serveur de statistiques: {}", e),
+        }
+    }
+}
+
+fn respond(stream: TcpStream, latest: &Mutex<Option<EmulatorStats>>) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Ignorer les en-têtes jusqu'à la ligne vide
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (status, body) = match (method, path) {
+        ("GET", "/stats") => match latest.lock().as_ref() {
+            Some(stats) => ("200 OK", serde_json::to_string(stats)?),
+            None => ("503 Service Unavailable", r#"{"error":"aucune frame émulée"}"#.to_string()),
+        },
+        ("GET", _) => ("404 Not Found", r#"{"error":"chemin inconnu"}"#.to_string()),
+        _ => ("405 Method Not Allowed", r#"{"error":"méthode non prise en charge"}"#.to_string()),
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EmulatorConfig;
+    use crate::emulator::EmulatorCore;
+    use std::io::Read;
+
+    fn get(address: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_serves_latest_snapshot() {
+        let server = StatsServer::start(0).unwrap();
+        assert!(server.address().ip().is_loopback());
+        assert!(get(server.address(), "/stats").starts_with("HTTP/1.1 503"));
+
+        let core = EmulatorCore::new(&EmulatorConfig::default().audio);
+        server.publish(core.stats());
+        let response = get(server.address(), "/stats");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(!response.contains("Access-Control-Allow-Origin"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let stats: EmulatorStats = serde_json::from_str(body).unwrap();
+        assert_eq!(stats, core.stats());
+
+        assert!(get(server.address(), "/").starts_with("HTTP/1.1 404"));
+    }
+}
@@ -0,0 +1,296 @@
+//! Erreurs de la bibliothèque
+//!
+//! Les API publiques du CPU, de la mémoire, des ROM, du GPU et de l'audio
+//! renvoient une [`EmulatorError`], sur laquelle un programme hôte peut
+//! filtrer (erreur de bus, ROM manquante...) sans analyser de message.
+//! Les couches d'interface (fenêtre, débogueur, binaires) restent sur
+//! `anyhow` et y convertissent ces erreurs avec `?`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::cpu::FpuExceptions;
+use crate::memory::{BusError, MemoryRegion};
+
+/// Résultat des API de la bibliothèque
+pub type Result<T, E = EmulatorError> = std::result::Result<T, E>;
+
+/// Erreur de l'émulateur, par composant
+#[derive(Debug, Error)]
+pub enum EmulatorError {
+    #[error(transparent)]
+    RomError(#[from] RomError),
+
+    #[error(transparent)]
+    MemoryFault(#[from] MemoryFault),
+
+    #[error(transparent)]
+    CpuException(#[from] CpuException),
+
+    #[error(transparent)]
+    GpuError(#[from] GpuError),
+
+    #[error(transparent)]
+    AudioError(#[from] AudioError),
+
+    /// Accès aux fichiers (ROM, NVRAM, base des jeux)
+    #[error("erreur d'entrée/sortie : {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Table de symboles du débogueur mal formée, ou symbole inconnu
+    #[error("table de symboles : {0}")]
+    Symbols(String),
+
+    /// Panic rattrapé dans un sous-système ; la frame est abandonnée mais
+    /// l'émulateur reste utilisable
+    #[error("{0}")]
+    SubsystemPanic(Box<CrashReport>),
+}
+
+impl EmulatorError {
+    /// Erreur de bus à l'origine de l'erreur, que le CPU transforme en exception
+    pub fn bus_error(&self) -> Option<&BusError> {
+        match self {
+            EmulatorError::MemoryFault(MemoryFault::Bus(error)) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<BusError> for EmulatorError {
+    fn from(error: BusError) -> Self {
+        EmulatorError::MemoryFault(MemoryFault::Bus(error))
+    }
+}
+
+#[cfg(feature = "rom-tools")]
+impl From<zip::result::ZipError> for EmulatorError {
+    fn from(error: zip::result::ZipError) -> Self {
+        match error {
+            zip::result::ZipError::Io(error) => EmulatorError::Io(error),
+            error => RomError::Archive(error.to_string()).into(),
+        }
+    }
+}
+
+/// Erreur de chargement d'un jeu ou d'une ROM
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RomError {
+    #[error("jeu non trouvé : {0}")]
+    GameNotFound(String),
+
+    #[error("ROM non trouvée : {0}")]
+    NotFound(String),
+
+    #[error("ROM {0} non trouvée dans l'archive")]
+    NotInArchive(String),
+
+    #[error("ROM manquante : {0}")]
+    Missing(String),
+
+    #[error("ROM corrompue : {0}")]
+    Corrupt(String),
+
+    #[error("ROM {name} trop grande pour une banque ({size} > {max})")]
+    TooLarge { name: String, size: usize, max: usize },
+
+    #[error("la ROM {name} (0x{size:X} octets à 0x{offset:X}) dépasse la fenêtre {region:?} de 0x{window:X} octets")]
+    WindowOverflow { name: String, region: MemoryRegion, offset: u32, size: usize, window: u32 },
+
+    #[error("support {0} non encore implémenté")]
+    UnsupportedArchive(&'static str),
+
+    #[error("archives {0} non prises en charge : compilé sans la fonctionnalité rom-tools")]
+    ArchiveSupportDisabled(&'static str),
+
+    #[error("archive illisible : {0}")]
+    Archive(String),
+
+    #[error("base des jeux invalide : {0}")]
+    Database(String),
+
+    #[error("patch invalide : {0}")]
+    Patch(String),
+}
+
+/// Faute d'un accès mémoire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum MemoryFault {
+    #[error(transparent)]
+    Bus(BusError),
+
+    #[error("accès {size} octets non aligné à l'adresse 0x{address:08X}")]
+    Misaligned { address: u32, size: u8 },
+
+    #[error("tentative d'écriture en ROM à l'adresse 0x{address:08X}")]
+    ReadOnly { address: u32 },
+
+    #[error("accès mémoire hors limites : 0x{address:08X} + {size} > 0x{limit:08X}")]
+    OutOfBounds { address: u32, size: usize, limit: usize },
+
+    #[error("plage hors de l'espace d'adressage : 0x{address:08X} + 0x{size:X}")]
+    OutOfAddressSpace { address: u32, size: usize },
+
+    #[error("la région {0:?} n'est pas une RAM")]
+    NotRam(MemoryRegion),
+}
+
+/// Exception levée par le CPU pendant le décodage ou l'exécution
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CpuException {
+    #[error("division par zéro")]
+    DivisionByZero,
+
+    #[error("opcode inconnu 0x{opcode:02X} à l'adresse 0x{address:08X}")]
+    UnknownOpcode { address: u32, opcode: u32 },
+
+    #[error("instruction tronquée à l'adresse 0x{address:08X} ({available} octet(s) disponibles)")]
+    TruncatedInstruction { address: u32, available: usize },
+
+    #[error("instruction non implémentée à l'adresse 0x{address:08X} : {mnemonic}")]
+    Unimplemented { address: u32, mnemonic: String },
+
+    #[error("taille d'élément non supportée : {0}")]
+    UnsupportedElementSize(u8),
+
+    #[error("opérande de destination non inscriptible")]
+    InvalidDestination,
+
+    #[error("longueur de champ de bits invalide : {0} (1 à 32)")]
+    InvalidBitFieldLength(u32),
+
+    /// Exceptions de l'unité flottante dont le déroutement est activé
+    #[error("exception de l'unité flottante : {0:?}")]
+    FloatingPoint(FpuExceptions),
+}
+
+/// Erreur du GPU ou de la présentation à l'écran
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GpuError {
+    #[error("impossible de trouver un adaptateur graphique")]
+    NoAdapter,
+
+    #[error("périphérique graphique indisponible : {0}")]
+    Device(String),
+
+    #[error("surface d'affichage indisponible : {0}")]
+    Surface(String),
+
+    #[error("présentation impossible : {0}")]
+    Presentation(String),
+
+    /// Le device wgpu est perdu et doit être recréé
+    #[error("carte graphique perdue : {0}")]
+    DeviceLost(String),
+
+    #[error("commande GPU non implémentée : {0}")]
+    Unimplemented(String),
+
+    #[error("export de texture impossible : {0}")]
+    TextureDump(String),
+
+    #[error("texture illisible : {0}")]
+    TextureDecode(String),
+}
+
+/// Erreur de la sortie audio
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AudioError {
+    #[error("configuration audio non supportée : {0}")]
+    Config(String),
+
+    #[error("flux audio impossible : {0}")]
+    Stream(String),
+
+    #[error("plage vide ou hors de la mémoire wave : 0x{start:06X}-0x{end:06X}")]
+    WaveRange { start: u32, end: u32 },
+}
+
+/// Sous-système isolé derrière une barrière de panic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    /// Instructions du V60 entre deux événements
+    Cpu,
+    /// Lot de commandes GPU de la frame
+    Gpu,
+    /// Mise à jour du SCSP
+    Audio,
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Subsystem::Cpu => "CPU",
+            Subsystem::Gpu => "GPU",
+            Subsystem::Audio => "audio",
+        })
+    }
+}
+
+/// Rapport de plantage d'un sous-système, à joindre aux tickets : message
+/// du panic et état de la machine au moment de l'abandon de la frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub emulator_version: String,
+    pub game: Option<String>,
+    pub subsystem: Subsystem,
+    /// Message du panic
+    pub message: String,
+    pub frame: u64,
+    /// Date émulée, en cycles du V60
+    pub cycle: u64,
+    pub pc: u32,
+    pub psw: u32,
+    pub sp: u32,
+    pub general: Vec<u32>,
+    /// Commandes du lot GPU en cours, dans l'ordre (panic du GPU)
+    pub gpu_commands: Vec<String>,
+    /// Slots du SCSP actifs
+    pub active_slots: Vec<usize>,
+}
+
+impl CrashReport {
+    /// Écrit le rapport en JSON dans `path`, dossiers parents compris
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "panic du sous-système {} à la frame {} (PC 0x{:08X}) : {}", self.subsystem, self.frame, self.pc, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_are_matchable() {
+        let error: EmulatorError = BusError { address: 0x1000, write: true }.into();
+        assert_eq!(error.bus_error(), Some(&BusError { address: 0x1000, write: true }));
+        assert!(matches!(error, EmulatorError::MemoryFault(MemoryFault::Bus(_))));
+
+        let error: EmulatorError = MemoryFault::ReadOnly { address: 0x10 }.into();
+        assert!(error.bus_error().is_none());
+        assert_eq!(error.to_string(), "tentative d'écriture en ROM à l'adresse 0x00000010");
+
+        let error: EmulatorError = std::io::Error::from(std::io::ErrorKind::NotFound).into();
+        assert!(matches!(error, EmulatorError::Io(_)));
+
+        // Les couches d'interface convertissent vers anyhow et retrouvent le détail
+        let error = anyhow::Error::from(EmulatorError::from(RomError::GameNotFound("vf3".to_string())));
+        assert!(matches!(
+            error.downcast_ref::<EmulatorError>(),
+            Some(EmulatorError::RomError(RomError::GameNotFound(name))) if name == "vf3"
+        ));
+    }
+}
@@ -0,0 +1,515 @@
+//! Interface C du cœur d'émulation (fonctionnalité `ffi`)
+//!
+//! Permet aux frontends écrits dans d'autres langages (C#, lanceurs Python)
+//! d'embarquer l'émulateur : la machine est manipulée à travers un pointeur
+//! opaque créé par [`pm2_core_create`] et libéré par [`pm2_core_destroy`].
+//! Les fonctions renvoient un [`Pm2Status`] ; le message de la dernière
+//! erreur se lit avec [`pm2_last_error`] ; les messages d'information du
+//! cœur passent par `log` et n'écrivent rien sur la sortie de l'hôte.
+//! L'image n'est pas affichée : le frontend lit l'image RGBA8 native après
+//! chaque frame, et relève les commandes de retour de force avec
+//! [`pm2_take_force_feedback`]. L'en-tête C correspondant est
+//! `include/pixel_model2.h`.
+
+use std::collections::VecDeque;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+use crate::config::EmulatorConfig;
+use crate::emulator::EmulatorCore;
+use crate::error::EmulatorError;
+use crate::gpu::{downsample, Model2Gpu};
+use crate::input::{io_word_for, BASE_PLAYERS, MAX_PLAYERS};
+use crate::memory::ForceFeedbackEvent;
+use crate::rom::Model2RomSystem;
+use crate::savestate::{SaveState, SaveStateHeader};
+
+/// Résultat d'un appel de l'interface C
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pm2Status {
+    Ok = 0,
+    /// Pointeur nul, chaîne invalide ou joueur inexistant
+    InvalidArgument = 1,
+    /// Aucun jeu chargé
+    NoGame = 2,
+    Rom = 3,
+    Memory = 4,
+    Cpu = 5,
+    Gpu = 6,
+    Audio = 7,
+    Io = 8,
+    /// État illisible ou d'un autre jeu
+    SaveState = 9,
+    /// Erreur interne inattendue
+    Panic = 10,
+}
+
+impl Pm2Status {
+    /// Code correspondant à une erreur ; `fallback` pour les erreurs hors
+    /// du cœur
+    fn of(error: &anyhow::Error, fallback: Pm2Status) -> Self {
+        match error.downcast_ref::<EmulatorError>() {
+            Some(EmulatorError::RomError(_)) => Pm2Status::Rom,
+            Some(EmulatorError::MemoryFault(_)) => Pm2Status::Memory,
+            Some(EmulatorError::CpuException(_)) => Pm2Status::Cpu,
+            Some(EmulatorError::GpuError(_)) => Pm2Status::Gpu,
+            Some(EmulatorError::AudioError(_)) => Pm2Status::Audio,
+            Some(EmulatorError::Io(_)) => Pm2Status::Io,
+            Some(EmulatorError::SubsystemPanic(_)) => Pm2Status::Panic,
+            Some(EmulatorError::Symbols(_)) | None => fallback,
+        }
+    }
+}
+
+/// Commande de retour de force relevée par [`pm2_take_force_feedback`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Pm2ForceFeedback {
+    pub frame: u64,
+    /// Octet reçu par la carte de pilotage
+    pub command: u8,
+    /// Effets en cours après la commande, de 0 à 15
+    pub centering: u8,
+    pub friction: u8,
+    pub vibration: u8,
+    /// Couple, de -15 (gauche) à 15 (droite)
+    pub roll: i8,
+}
+
+impl From<ForceFeedbackEvent> for Pm2ForceFeedback {
+    fn from(event: ForceFeedbackEvent) -> Self {
+        Self {
+            frame: event.frame,
+            command: event.command.code(),
+            centering: event.state.centering,
+            friction: event.state.friction,
+            vibration: event.state.vibration,
+            roll: event.state.roll,
+        }
+    }
+}
+
+/// Machine émulée vue du frontend
+pub struct Pm2Core {
+    core: EmulatorCore,
+    gpu: Model2Gpu,
+    rom_system: Model2RomSystem,
+    config: EmulatorConfig,
+
+    /// Jeu chargé
+    game: Option<String>,
+
+    /// Boutons enfoncés de chaque joueur, un bit par bouton dans l'ordre de
+    /// `Button::ALL`
+    buttons: [u8; MAX_PLAYERS],
+
+    /// Places de joueur câblées pour le jeu chargé
+    player_count: usize,
+
+    /// Commandes de retour de force pas encore copiées au frontend
+    force_feedback: VecDeque<ForceFeedbackEvent>,
+
+    /// Dernière image à la résolution native, lue par [`pm2_framebuffer`]
+    frame: Vec<u8>,
+    frame_size: (u32, u32),
+
+    last_error: Option<CString>,
+}
+
+impl Pm2Core {
+    fn new(rom_dir: Option<&str>) -> Self {
+        let config = EmulatorConfig::default();
+        let mut rom_system = Model2RomSystem::new();
+        if let Some(rom_dir) = rom_dir {
+            rom_system.add_search_path(rom_dir);
+        }
+        let mut core = Self {
+            core: EmulatorCore::new(&config.audio),
+            gpu: Model2Gpu::headless(),
+            rom_system,
+            config,
+            game: None,
+            buttons: [0; MAX_PLAYERS],
+            player_count: BASE_PLAYERS,
+            force_feedback: VecDeque::new(),
+            frame: Vec::new(),
+            frame_size: (0, 0),
+            last_error: None,
+        };
+        core.capture_frame();
+        core
+    }
+
+    /// Ramène le framebuffer, rendu à la résolution interne, à la
+    /// résolution native du jeu
+    fn capture_frame(&mut self) {
+        let framebuffer = &self.gpu.framebuffer;
+        let (width, height) = framebuffer.native_size();
+        self.frame.resize((width * height * 4) as usize, 0);
+        if framebuffer.scale <= 1 {
+            self.frame.copy_from_slice(&framebuffer.color_data);
+        } else {
+            downsample(&framebuffer.color_data, framebuffer.width, framebuffer.scale, &mut self.frame);
+        }
+        self.frame_size = (width, height);
+    }
+
+    /// Mot d'entrée de la carte I/O (actif bas, un octet par joueur câblé)
+    fn input_word(&self) -> u32 {
+        io_word_for(&self.buttons, self.player_count)
+    }
+
+    /// En-tête des états du jeu chargé
+    fn state_header(&self, game: &str) -> SaveStateHeader {
+        match self.rom_system.rom_manager.database().find_game(game) {
+            Some(info) => SaveStateHeader::new(game, &info.version, &info.board),
+            None => SaveStateHeader::new(game, "", "Model 2"),
+        }
+    }
+
+    /// Exécute une opération ; l'erreur est retenue pour `pm2_last_error`
+    fn run(&mut self, fallback: Pm2Status, operation: impl FnOnce(&mut Self) -> anyhow::Result<()>) -> Pm2Status {
+        let result = catch_unwind(AssertUnwindSafe(|| operation(self)));
+        let (status, message) = match result {
+            Ok(Ok(())) => (Pm2Status::Ok, None),
+            Ok(Err(error)) => (Pm2Status::of(&error, fallback), Some(format!("{:#}", error))),
+            Err(_) => (Pm2Status::Panic, Some("erreur interne de l'émulateur".to_string())),
+        };
+        self.last_error = message.map(|message| CString::new(message.replace('\0', " ")).unwrap_or_default());
+        status
+    }
+}
+
+/// Chaîne UTF-8 terminée par un zéro ; `None` si nulle ou invalide
+///
+/// # Safety
+///
+/// `text` est nul ou pointe sur une chaîne C valide.
+unsafe fn str_arg<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+    CStr::from_ptr(text).to_str().ok()
+}
+
+/// Crée une machine ; `rom_dir` (facultatif) est ajouté aux dossiers de
+/// recherche des ROM. À libérer avec [`pm2_core_destroy`].
+///
+/// # Safety
+///
+/// `rom_dir` est nul ou pointe sur une chaîne C valide.
+#[no_mangle]
+pub unsafe extern "C" fn pm2_core_create(rom_dir: *const c_char) -> *mut Pm2Core {
+    match catch_unwind(|| Pm2Core::new(str_arg(rom_dir))) {
+        Ok(core) => Box::into_raw(Box::new(core)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Libère une machine créée par [`pm2_core_create`] ; sans effet sur un
+/// pointeur nul
+///
+/// # Safety
+///
+/// `core` est nul ou provient de [`pm2_core_create`] et n'a pas déjà été libéré.
+#[no_mangle]
+pub unsafe extern "C" fn pm2_core_destroy(core: *mut Pm2Core) {
+    if !core.is_null() {
+        drop(Box::from_raw(core));
+    }
+}
+
+/// Charge un jeu de la base (nom court, par exemple `vf2`) et redémarre la
+/// machine dessus
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`] ; `game` pointe sur une chaîne C valide.
+#[no_mangle]
+pub unsafe extern "C" fn pm2_load_game(core: *mut Pm2Core, game: *const c_char) -> Pm2Status {
+    let (Some(core), Some(game)) = (core.as_mut(), str_arg(game)) else {
+        return Pm2Status::InvalidArgument;
+    };
+    core.run(Pm2Status::Rom, |core| {
+        core.core.load_game(&mut core.rom_system, game, &core.config.emulation)?;
+        core.game = Some(game.to_string());
+        core.player_count = core.rom_system.rom_manager.database().player_count(game);
+        Ok(())
+    })
+}
+
+/// Exécute une frame émulée avec l'état courant des entrées
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pm2_run_frame(core: *mut Pm2Core) -> Pm2Status {
+    let Some(core) = core.as_mut() else {
+        return Pm2Status::InvalidArgument;
+    };
+    if core.game.is_none() {
+        return Pm2Status::NoGame;
+    }
+    core.run(Pm2Status::Cpu, |core| {
+        let input_word = core.input_word();
+        core.core.run_frame(input_word, Some(&mut core.gpu))?;
+        core.capture_frame();
+        Ok(())
+    })
+}
+
+/// Image de la dernière frame à la résolution native du jeu, quelle que
+/// soit la résolution interne : pixels RGBA8 ligne par ligne, sans
+/// remplissage. Le pointeur reste valide jusqu'au prochain appel sur la
+/// machine ; nul si `core` est nul.
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`] ; `width` et `height` sont nuls ou
+/// pointent sur des entiers modifiables.
+#[no_mangle]
+pub unsafe extern "C" fn pm2_framebuffer(core: *const Pm2Core, width: *mut u32, height: *mut u32) -> *const u8 {
+    let Some(core) = core.as_ref() else {
+        return std::ptr::null();
+    };
+    if let Some(width) = width.as_mut() {
+        *width = core.frame_size.0;
+    }
+    if let Some(height) = height.as_mut() {
+        *height = core.frame_size.1;
+    }
+    core.frame.as_ptr()
+}
+
+/// Boutons enfoncés d'un joueur (0 à 3 ; seules les places câblées pour
+/// le jeu chargé sont lues) : bit 0 haut, 1 bas, 2 gauche,
+/// 3 droite, 4 poing, 5 pied, 6 garde, 7 start. Pris en compte à la
+/// prochaine frame.
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pm2_set_input(core: *mut Pm2Core, player: u32, buttons: u32) -> Pm2Status {
+    let Some(core) = core.as_mut() else {
+        return Pm2Status::InvalidArgument;
+    };
+    match core.buttons.get_mut(player as usize) {
+        Some(state) => {
+            *state = buttons as u8;
+            Pm2Status::Ok
+        }
+        None => Pm2Status::InvalidArgument,
+    }
+}
+
+/// Copie dans `events`, dans l'ordre, au plus `capacity` commandes de
+/// retour de force reçues depuis le dernier appel ; renvoie le nombre de
+/// commandes copiées. Les suivantes restent pour le prochain appel.
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`] ; `events` est nul ou pointe sur
+/// `capacity` éléments modifiables.
+#[no_mangle]
+pub unsafe extern "C" fn pm2_take_force_feedback(core: *mut Pm2Core, events: *mut Pm2ForceFeedback, capacity: u32) -> u32 {
+    let Some(core) = core.as_mut() else {
+        return 0;
+    };
+    if events.is_null() {
+        return 0;
+    }
+    core.force_feedback.extend(core.core.take_force_feedback());
+    let count = core.force_feedback.len().min(capacity as usize);
+    let events = std::slice::from_raw_parts_mut(events, count);
+    for (slot, event) in events.iter_mut().zip(core.force_feedback.drain(..count)) {
+        *slot = event.into();
+    }
+    count as u32
+}
+
+/// Lampes de la borne allumées par le jeu, un bit par lampe : bit 0 start 1,
+/// 1 start 2, 2 à 5 boutons de vue 1 à 4, 6 leader, 7 siège 1, 8 siège 2 ;
+/// 0 si `core` est nul
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pm2_lamps(core: *const Pm2Core) -> u32 {
+    core.as_ref().map_or(0, |core| core.core.memory.lamps().bits)
+}
+
+/// Enregistre l'état de la machine dans un fichier
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`] ; `path` pointe sur une chaîne C valide.
+#[no_mangle]
+pub unsafe extern "C" fn pm2_save_state(core: *mut Pm2Core, path: *const c_char) -> Pm2Status {
+    let (Some(core), Some(path)) = (core.as_mut(), str_arg(path)) else {
+        return Pm2Status::InvalidArgument;
+    };
+    let Some(game) = core.game.clone() else {
+        return Pm2Status::NoGame;
+    };
+    core.run(Pm2Status::SaveState, |core| {
+        let header = core.state_header(&game);
+        SaveState::capture(&core.core.cpu, &core.core.memory).with_header(header).save(Path::new(path))
+    })
+}
+
+/// Restaure un état enregistré pour le jeu chargé
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`] ; `path` pointe sur une chaîne C valide.
+#[no_mangle]
+pub unsafe extern "C" fn pm2_load_state(core: *mut Pm2Core, path: *const c_char) -> Pm2Status {
+    let (Some(core), Some(path)) = (core.as_mut(), str_arg(path)) else {
+        return Pm2Status::InvalidArgument;
+    };
+    let Some(game) = core.game.clone() else {
+        return Pm2Status::NoGame;
+    };
+    core.run(Pm2Status::SaveState, |core| {
+        let state = SaveState::load(Path::new(path))?;
+        state.header.check_compatible(&game, &core.state_header(&game).board)?;
+        state.restore(&mut core.core.cpu, &mut core.core.memory)
+    })
+}
+
+/// Message de la dernière erreur de la machine, nul s'il n'y en a pas. La
+/// chaîne reste valide jusqu'au prochain appel sur la machine.
+///
+/// # Safety
+///
+/// `core` provient de [`pm2_core_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pm2_last_error(core: *const Pm2Core) -> *const c_char {
+    core.as_ref()
+        .and_then(|core| core.last_error.as_ref())
+        .map_or(std::ptr::null(), |message| message.as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{CrashReport, Subsystem};
+    use crate::memory::MemoryInterface;
+
+    fn c(text: &str) -> CString {
+        CString::new(text).unwrap()
+    }
+
+    #[test]
+    fn test_core_lifecycle_and_errors() {
+        unsafe {
+            let core = pm2_core_create(std::ptr::null());
+            assert!(!core.is_null());
+            assert!(pm2_last_error(core).is_null());
+
+            assert_eq!(pm2_run_frame(core), Pm2Status::NoGame);
+            assert_eq!(pm2_load_game(core, std::ptr::null()), Pm2Status::InvalidArgument);
+            assert_eq!(pm2_load_game(core, c("jeu_inconnu").as_ptr()), Pm2Status::Rom);
+            let message = CStr::from_ptr(pm2_last_error(core)).to_str().unwrap();
+            assert!(message.contains("jeu_inconnu"), "{}", message);
+
+            assert_eq!(pm2_set_input(core, MAX_PLAYERS as u32, 0x01), Pm2Status::InvalidArgument);
+
+            let (mut width, mut height) = (0, 0);
+            assert!(!pm2_framebuffer(core, &mut width, &mut height).is_null());
+            assert_eq!((width, height), (496, 384));
+
+            // Résolution interne doublée : l'image lue reste native
+            (*core).gpu.set_internal_scale(2);
+            (*core).capture_frame();
+            assert!(!pm2_framebuffer(core, &mut width, &mut height).is_null());
+            assert_eq!((width, height), (496, 384));
+            assert_eq!((*core).frame.len(), 496 * 384 * 4);
+
+            pm2_core_destroy(core);
+            pm2_core_destroy(std::ptr::null_mut());
+        }
+
+        // Panic rattrapé par le cœur : même code qu'un panic à la frontière
+        let report = CrashReport {
+            emulator_version: String::new(),
+            game: None,
+            subsystem: Subsystem::Gpu,
+            message: "panne".to_string(),
+            frame: 0,
+            cycle: 0,
+            pc: 0,
+            psw: 0,
+            sp: 0,
+            general: Vec::new(),
+            gpu_commands: Vec::new(),
+            active_slots: Vec::new(),
+        };
+        let error = anyhow::Error::from(EmulatorError::SubsystemPanic(Box::new(report)));
+        assert_eq!(Pm2Status::of(&error, Pm2Status::Cpu), Pm2Status::Panic);
+    }
+
+    #[test]
+    fn test_input_and_state_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = c(dir.path().join("etat.state").to_str().unwrap());
+        unsafe {
+            let core = pm2_core_create(std::ptr::null());
+            assert_eq!(pm2_save_state(core, path.as_ptr()), Pm2Status::NoGame);
+
+            // Jeu lancé sans passer par la base : seul l'état de la machine compte
+            let machine = &mut *core;
+            machine.game = Some("vf2".to_string());
+            machine.core.memory.write_u32(0x0000_1000, 0x1234_5678).unwrap();
+
+            assert_eq!(pm2_set_input(core, 0, 0b1_0000), Pm2Status::Ok);
+            assert_eq!(pm2_set_input(core, 1, 0b1000_0000), Pm2Status::Ok);
+            assert_eq!((*core).input_word(), !0x8010);
+
+            assert_eq!(pm2_save_state(core, path.as_ptr()), Pm2Status::Ok);
+            (*core).core.memory.write_u32(0x0000_1000, 0).unwrap();
+            assert_eq!(pm2_load_state(core, path.as_ptr()), Pm2Status::Ok);
+            assert_eq!((*core).core.memory.read_u32(0x0000_1000).unwrap(), 0x1234_5678);
+
+            // Un état d'un autre jeu est refusé
+            (*core).game = Some("daytona".to_string());
+            assert_eq!(pm2_load_state(core, path.as_ptr()), Pm2Status::SaveState);
+
+            pm2_core_destroy(core);
+        }
+    }
+
+    #[test]
+    fn test_cabinet_outputs() {
+        use crate::memory::{DRIVE_BOARD_REGISTER, IO_REGISTERS_BASE, LAMP_OUTPUT_REGISTER};
+
+        unsafe {
+            let core = pm2_core_create(std::ptr::null());
+            (*core).game = Some("daytona".to_string());
+            let drive = IO_REGISTERS_BASE + DRIVE_BOARD_REGISTER;
+            for command in [0x1C, 0x57] {
+                (*core).core.memory.write_u8(drive, command).unwrap();
+                assert_eq!(pm2_run_frame(core), Pm2Status::Ok);
+            }
+
+            let mut events = [Pm2ForceFeedback::default(); 1];
+            assert_eq!(pm2_take_force_feedback(core, std::ptr::null_mut(), 1), 0);
+            assert_eq!(pm2_take_force_feedback(core, events.as_mut_ptr(), 1), 1);
+            assert_eq!((events[0].frame, events[0].command, events[0].centering), (0, 0x1C, 12));
+            assert_eq!(pm2_take_force_feedback(core, events.as_mut_ptr(), 1), 1);
+            assert_eq!((events[0].frame, events[0].command, events[0].roll), (1, 0x57, 7));
+            assert_eq!(pm2_take_force_feedback(core, events.as_mut_ptr(), 1), 0);
+
+            (*core).core.memory.write_u32(IO_REGISTERS_BASE + LAMP_OUTPUT_REGISTER, 0b100_0001).unwrap();
+            assert_eq!(pm2_lamps(core), 0b100_0001);
+            assert_eq!(pm2_lamps(std::ptr::null()), 0);
+
+            pm2_core_destroy(core);
+        }
+    }
+}
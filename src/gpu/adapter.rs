@@ -0,0 +1,200 @@
+//! Choix de l'API graphique et de la carte graphique
+//!
+//! L'API et la carte demandées dans la configuration sont essayées en
+//! premier. Si elles sont indisponibles (pilote absent, carte débranchée),
+//! l'ouverture se rabat sur n'importe quelle API, puis sur le rendu logiciel
+//! du système, plutôt que d'échouer.
+
+use crate::error::{GpuError, Result};
+use std::sync::Arc;
+use wgpu::{Adapter, Backends, DeviceType, Instance, InstanceDescriptor, Surface};
+use winit::window::Window;
+
+use crate::config::{GraphicsBackend, VideoConfig};
+
+/// API et carte graphique demandées
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdapterSelection {
+    pub backend: GraphicsBackend,
+    /// Nom (ou partie du nom, sans casse) ou numéro dans la liste des cartes
+    pub adapter: Option<String>,
+}
+
+impl AdapterSelection {
+    pub fn from_config(video: &VideoConfig) -> Self {
+        Self { backend: video.backend, adapter: video.adapter.clone() }
+    }
+}
+
+/// APIs wgpu correspondant au choix de la configuration
+pub fn backends_for(backend: GraphicsBackend) -> Backends {
+    match backend {
+        GraphicsBackend::Auto => Backends::all(),
+        GraphicsBackend::Vulkan => Backends::VULKAN,
+        GraphicsBackend::Dx12 => Backends::DX12,
+        GraphicsBackend::Metal => Backends::METAL,
+        GraphicsBackend::Gl => Backends::GL,
+    }
+}
+
+/// Description d'une carte graphique pour `--list-gpus`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterDescription {
+    pub name: String,
+    pub backend: String,
+    pub device_type: String,
+}
+
+impl AdapterDescription {
+    fn from_adapter(adapter: &Adapter) -> Self {
+        let info = adapter.get_info();
+        let device_type = match info.device_type {
+            DeviceType::IntegratedGpu => "intégrée",
+            DeviceType::DiscreteGpu => "dédiée",
+            DeviceType::VirtualGpu => "virtuelle",
+            DeviceType::Cpu => "logicielle",
+            DeviceType::Other => "autre",
+        };
+        Self { name: info.name, backend: format!("{:?}", info.backend), device_type: device_type.to_string() }
+    }
+}
+
+/// Cartes graphiques utilisables avec l'API demandée
+#[cfg(not(target_arch = "wasm32"))]
+pub fn list_adapters(backend: GraphicsBackend) -> Vec<AdapterDescription> {
+    let instance = create_instance(backends_for(backend));
+    instance.enumerate_adapters(backends_for(backend)).iter().map(AdapterDescription::from_adapter).collect()
+}
+
+/// WebGPU ne révèle les cartes qu'à la demande : aucune liste dans un navigateur
+#[cfg(target_arch = "wasm32")]
+pub fn list_adapters(_backend: GraphicsBackend) -> Vec<AdapterDescription> {
+    Vec::new()
+}
+
+/// Index de la carte désignée par `query` : numéro dans la liste, sinon
+/// première carte dont le nom contient `query` (sans casse)
+pub fn find_adapter(names: &[String], query: &str) -> Option<usize> {
+    let query = query.trim();
+    if let Ok(index) = query.parse::<usize>() {
+        return (index < names.len()).then_some(index);
+    }
+    let query = query.to_lowercase();
+    names.iter().position(|name| name.to_lowercase().contains(&query))
+}
+
+/// Tentatives d'ouverture, dans l'ordre : API demandée, toutes les APIs,
+/// puis rendu logiciel
+pub fn fallback_chain(backend: GraphicsBackend) -> Vec<(Backends, bool)> {
+    let mut attempts = vec![(backends_for(backend), false)];
+    if backend != GraphicsBackend::Auto {
+        attempts.push((Backends::all(), false));
+    }
+    attempts.push((Backends::all(), true));
+    attempts
+}
+
+fn create_instance(backends: Backends) -> Instance {
+    Instance::new(InstanceDescriptor {
+        backends,
+        flags: wgpu::InstanceFlags::default(),
+        dx12_shader_compiler: wgpu::Dx12Compiler::Fxc,
+        gles_minor_version: wgpu::Gles3MinorVersion::Automatic,
+    })
+}
+
+/// Ouvre une carte graphique capable d'afficher dans la fenêtre
+pub async fn open(window: &Arc<Window>, selection: &AdapterSelection) -> Result<(Instance, Surface<'static>, Adapter)> {
+    for (attempt, (backends, software)) in fallback_chain(selection.backend).into_iter().enumerate() {
+        let instance = create_instance(backends);
+        // La surface est liée à la fenêtre, conservée par le rendu
+        let surface = match instance.create_surface(window.clone()) {
+            Ok(surface) => surface,
+            Err(e) => {
+                log::warn!("Surface {:?} indisponible: {}", backends, e);
+                continue;
+            }
+        };
+
+        // Carte choisie par l'utilisateur, seulement avec l'API demandée ; les
+        // numéros sont ceux de `--list-gpus`, qui ne filtre pas les cartes
+        #[cfg(not(target_arch = "wasm32"))]
+        if attempt == 0 {
+            if let Some(query) = &selection.adapter {
+                let adapters = instance.enumerate_adapters(backends);
+                let names: Vec<String> = adapters.iter().map(|adapter| adapter.get_info().name).collect();
+                match find_adapter(&names, query) {
+                    Some(index) if adapters[index].is_surface_supported(&surface) => {
+                        let adapter = adapters.into_iter().nth(index).expect("index issu de la liste");
+                        return Ok((instance, surface, adapter));
+                    }
+                    Some(index) => log::warn!("Carte graphique « {} » incapable d'afficher dans la fenêtre, choix automatique", names[index]),
+                    None => log::warn!("Carte graphique « {} » introuvable parmi {:?}, choix automatique", query, names),
+                }
+            }
+        }
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: software,
+            })
+            .await;
+        match adapter {
+            Some(adapter) => {
+                if attempt > 0 {
+                    log::warn!("API {:?} indisponible, rendu avec {}", selection.backend, adapter.get_info().name);
+                }
+                return Ok((instance, surface, adapter));
+            }
+            None => log::warn!("Aucune carte graphique pour {:?} (logiciel: {})", backends, software),
+        }
+    }
+    Err(GpuError::NoAdapter.into())
+}
+
+/// Crée le device ; les limites par défaut sont abaissées si la carte ne
+/// les atteint pas (pilotes GL anciens, rendu logiciel)
+pub async fn request_device(adapter: &Adapter) -> Result<(wgpu::Device, wgpu::Queue)> {
+    let descriptor = |limits| wgpu::DeviceDescriptor {
+        required_features: wgpu::Features::empty(),
+        required_limits: limits,
+        label: None,
+    };
+    match adapter.request_device(&descriptor(wgpu::Limits::default()), None).await {
+        Ok(device) => Ok(device),
+        Err(e) => {
+            log::warn!("Limites par défaut refusées ({}), limites réduites", e);
+            let limits = wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits());
+            adapter
+                .request_device(&descriptor(limits), None)
+                .await
+                .map_err(|e| GpuError::Device(e.to_string()).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_adapter_by_index_or_name() {
+        let names = vec!["Intel(R) UHD Graphics 630".to_string(), "NVIDIA GeForce RTX 3060 Laptop GPU".to_string()];
+        assert_eq!(find_adapter(&names, "1"), Some(1));
+        assert_eq!(find_adapter(&names, "2"), None);
+        assert_eq!(find_adapter(&names, "nvidia"), Some(1));
+        assert_eq!(find_adapter(&names, " UHD "), Some(0));
+        assert_eq!(find_adapter(&names, "Radeon"), None);
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        assert_eq!(
+            fallback_chain(GraphicsBackend::Vulkan),
+            vec![(Backends::VULKAN, false), (Backends::all(), false), (Backends::all(), true)]
+        );
+        assert_eq!(fallback_chain(GraphicsBackend::Auto), vec![(Backends::all(), false), (Backends::all(), true)]);
+    }
+}
@@ -0,0 +1,270 @@
+//! Anticrénelage de l'image 3D
+//!
+//! Deux méthodes, au choix dans la configuration :
+//! - le multiéchantillonnage (MSAA) : le rastériseur teste la couverture et
+//!   la profondeur en 2, 4 ou 8 points de chaque pixel mais n'évalue la
+//!   couleur qu'une fois, puis les échantillons sont moyennés en fin de frame ;
+//! - le FXAA : une passe sur l'image finie repère les contours par la
+//!   luminance et mélange chaque pixel de bord avec son voisin, pour un coût
+//!   fixe indépendant du nombre de polygones.
+
+/// Nombre maximal d'échantillons de la cible de rendu ; au-delà, le mode
+/// MSAA est réduit (hautes résolutions internes)
+pub const MAX_RASTER_SAMPLES: usize = 1 << 24;
+
+/// Positions des échantillons dans le pixel (motifs standard D3D/Vulkan)
+pub fn sample_positions(count: u32) -> &'static [[f32; 2]] {
+    const SINGLE: [[f32; 2]; 1] = [[0.5, 0.5]];
+    const X2: [[f32; 2]; 2] = [[0.75, 0.75], [0.25, 0.25]];
+    const X4: [[f32; 2]; 4] = [[0.375, 0.125], [0.875, 0.375], [0.125, 0.625], [0.625, 0.875]];
+    const X8: [[f32; 2]; 8] = [
+        [0.5625, 0.3125], [0.4375, 0.6875], [0.8125, 0.5625], [0.3125, 0.1875],
+        [0.1875, 0.8125], [0.0625, 0.4375], [0.6875, 0.9375], [0.9375, 0.0625],
+    ];
+    match count {
+        2 => &X2,
+        4 => &X4,
+        8 => &X8,
+        _ => &SINGLE,
+    }
+}
+
+/// Plus grand nombre d'échantillons par pixel utilisable à cette résolution
+pub fn max_sample_count(width: u32, height: u32) -> u32 {
+    let pixels = (width as usize * height as usize).max(1);
+    [8, 4, 2].into_iter().find(|&count| pixels * count as usize <= MAX_RASTER_SAMPLES).unwrap_or(1)
+}
+
+/// Moyenne les échantillons RGBA8 de chaque pixel dans `out`
+pub fn resolve(samples: &[u8], sample_count: u32, out: &mut [u8]) {
+    let count = sample_count.max(1) as usize;
+    for (pixel, texels) in out.chunks_exact_mut(4).zip(samples.chunks_exact(4 * count)) {
+        for (channel, value) in pixel.iter_mut().enumerate() {
+            let sum: u32 = texels.chunks_exact(4).map(|texel| texel[channel] as u32).sum();
+            *value = ((sum + count as u32 / 2) / count as u32) as u8;
+        }
+    }
+}
+
+/// Contraste local minimal pour traiter un pixel
+const EDGE_THRESHOLD_MIN: f32 = 0.0312;
+
+/// Contraste minimal relatif à la luminance la plus forte du voisinage
+const EDGE_THRESHOLD_MAX: f32 = 0.125;
+
+/// Pas de la recherche des extrémités d'un contour, en pixels
+const SEARCH_STEPS: [f32; 12] = [1.0, 1.0, 1.0, 1.0, 1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 4.0, 8.0];
+
+/// Part maximale du mélange sous-pixel
+const SUBPIXEL_QUALITY: f32 = 0.75;
+
+/// Passe FXAA ; conserve ses tampons d'une frame à l'autre
+#[derive(Debug, Default)]
+pub struct FxaaPass {
+    luma: Vec<f32>,
+    source: Vec<u8>,
+}
+
+impl FxaaPass {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lisse les contours de l'image RGBA8 `rgba` sur place
+    pub fn apply(&mut self, rgba: &mut [u8], width: u32, height: u32) {
+        let (width, height) = (width as usize, height as usize);
+        if width < 3 || height < 3 || rgba.len() < width * height * 4 {
+            return;
+        }
+
+        self.source.clear();
+        self.source.extend_from_slice(&rgba[..width * height * 4]);
+        self.luma.clear();
+        self.luma.extend(self.source.chunks_exact(4).map(|pixel| {
+            (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) / 255.0
+        }));
+
+        for y in 0..height {
+            for x in 0..width {
+                let Some((neighbor, blend)) = self.edge_blend(x, y, width, height) else {
+                    continue;
+                };
+                let (center, other) = ((y * width + x) * 4, neighbor * 4);
+                for channel in 0..3 {
+                    let a = self.source[center + channel] as f32;
+                    let b = self.source[other + channel] as f32;
+                    rgba[center + channel] = (a + (b - a) * blend).round() as u8;
+                }
+            }
+        }
+    }
+
+    /// Voisin avec lequel mélanger le pixel `(x, y)` et part du mélange ;
+    /// `None` hors des contours
+    fn edge_blend(&self, x: usize, y: usize, width: usize, height: usize) -> Option<(usize, f32)> {
+        let luma = |x: isize, y: isize| {
+            let x = x.clamp(0, width as isize - 1) as usize;
+            let y = y.clamp(0, height as isize - 1) as usize;
+            self.luma[y * width + x]
+        };
+        let (xi, yi) = (x as isize, y as isize);
+        let center = luma(xi, yi);
+        let (up, down, left, right) = (luma(xi, yi - 1), luma(xi, yi + 1), luma(xi - 1, yi), luma(xi + 1, yi));
+
+        let max = center.max(up).max(down).max(left).max(right);
+        let min = center.min(up).min(down).min(left).min(right);
+        let range = max - min;
+        if range < EDGE_THRESHOLD_MIN.max(max * EDGE_THRESHOLD_MAX) {
+            return None;
+        }
+
+        let (up_left, up_right) = (luma(xi - 1, yi - 1), luma(xi + 1, yi - 1));
+        let (down_left, down_right) = (luma(xi - 1, yi + 1), luma(xi + 1, yi + 1));
+
+        // Orientation du contour : variation la plus forte entre lignes ou
+        // entre colonnes
+        let horizontal_edge = (-2.0 * left + up_left + down_left).abs()
+            + 2.0 * (-2.0 * center + up + down).abs()
+            + (-2.0 * right + up_right + down_right).abs();
+        let vertical_edge = (-2.0 * up + up_left + up_right).abs()
+            + 2.0 * (-2.0 * center + left + right).abs()
+            + (-2.0 * down + down_left + down_right).abs();
+        let horizontal = horizontal_edge >= vertical_edge;
+
+        // Côté du contour où la luminance change le plus
+        let (luma_negative, luma_positive) = if horizontal { (up, down) } else { (left, right) };
+        let (gradient_negative, gradient_positive) = (luma_negative - center, luma_positive - center);
+        let negative_side = gradient_negative.abs() >= gradient_positive.abs();
+        let gradient_scaled = 0.25 * gradient_negative.abs().max(gradient_positive.abs());
+        let (step, local_average) = if negative_side {
+            (-1isize, 0.5 * (luma_negative + center))
+        } else {
+            (1isize, 0.5 * (luma_positive + center))
+        };
+
+        // Luminance à cheval sur le contour, à la position `t` le long de
+        // celui-ci
+        let edge_luma = |t: f32| {
+            let along = t.floor() as isize;
+            if horizontal {
+                0.5 * (luma(along, yi) + luma(along, yi + step))
+            } else {
+                0.5 * (luma(xi, along) + luma(xi + step, along))
+            }
+        };
+
+        // Recherche des deux extrémités du contour
+        let origin = if horizontal { x as f32 + 0.5 } else { y as f32 + 0.5 };
+        let (mut end_negative, mut end_positive) = (origin - 1.0, origin + 1.0);
+        let mut luma_end_negative = edge_luma(end_negative) - local_average;
+        let mut luma_end_positive = edge_luma(end_positive) - local_average;
+        let mut reached_negative = luma_end_negative.abs() >= gradient_scaled;
+        let mut reached_positive = luma_end_positive.abs() >= gradient_scaled;
+        for quality in SEARCH_STEPS {
+            if reached_negative && reached_positive {
+                break;
+            }
+            if !reached_negative {
+                end_negative -= quality;
+                luma_end_negative = edge_luma(end_negative) - local_average;
+                reached_negative = luma_end_negative.abs() >= gradient_scaled;
+            }
+            if !reached_positive {
+                end_positive += quality;
+                luma_end_positive = edge_luma(end_positive) - local_average;
+                reached_positive = luma_end_positive.abs() >= gradient_scaled;
+            }
+        }
+
+        // Mélange d'autant plus fort que le pixel est proche de l'extrémité
+        // la plus proche du contour
+        let (distance_negative, distance_positive) = (origin - end_negative, end_positive - origin);
+        let closest_negative = distance_negative < distance_positive;
+        let distance = distance_negative.min(distance_positive);
+        let thickness = distance_negative + distance_positive;
+        let luma_end = if closest_negative { luma_end_negative } else { luma_end_positive };
+        let correct_variation = (luma_end < 0.0) != (center < local_average);
+        let edge_offset = if correct_variation { 0.5 - distance / thickness } else { 0.0 };
+
+        // Mélange sous-pixel pour les détails plus fins qu'un pixel
+        let average = (2.0 * (up + down + left + right) + up_left + up_right + down_left + down_right) / 12.0;
+        let subpixel = ((average - center).abs() / range).clamp(0.0, 1.0);
+        let subpixel = (-2.0 * subpixel + 3.0) * subpixel * subpixel;
+        let subpixel_offset = subpixel * subpixel * SUBPIXEL_QUALITY;
+
+        let blend = edge_offset.max(subpixel_offset);
+        if blend <= 0.0 {
+            return None;
+        }
+        let neighbor = if horizontal {
+            ((yi + step).clamp(0, height as isize - 1) as usize) * width + x
+        } else {
+            y * width + (xi + step).clamp(0, width as isize - 1) as usize
+        };
+        Some((neighbor, blend))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: usize, height: usize, white: impl Fn(usize, usize) -> bool) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let value = if white(x, y) { 255 } else { 0 };
+                rgba.extend_from_slice(&[value, value, value, 255]);
+            }
+        }
+        rgba
+    }
+
+    #[test]
+    fn test_resolve_averages_samples() {
+        // Deux pixels à 4 échantillons : moitié rouge, puis tout bleu
+        let samples = [
+            255, 0, 0, 255, 255, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255,
+            0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255,
+        ];
+        let mut out = [0u8; 8];
+        resolve(&samples, 4, &mut out);
+        assert_eq!(out, [128, 0, 0, 255, 0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_sample_patterns_and_limits() {
+        for count in [1, 2, 4, 8] {
+            let positions = sample_positions(count);
+            assert_eq!(positions.len(), count as usize);
+            assert!(positions.iter().flatten().all(|&c| (0.0..1.0).contains(&c)));
+        }
+        assert_eq!(max_sample_count(496, 384), 8);
+        assert_eq!(max_sample_count(496 * 4, 384 * 4), 4);
+    }
+
+    #[test]
+    fn test_fxaa_smooths_staircase_only() {
+        // Contour en escalier : la colonne blanche avance d'un pixel toutes
+        // les quatre lignes
+        let (width, height) = (16, 16);
+        let original = image(width, height, |x, y| x >= 4 + y / 4);
+        let mut rgba = original.clone();
+        FxaaPass::new().apply(&mut rgba, width as u32, height as u32);
+
+        let at = |data: &[u8], x: usize, y: usize| data[(y * width + x) * 4];
+        // Loin du contour, rien ne change
+        assert_eq!(at(&rgba, 0, 8), 0);
+        assert_eq!(at(&rgba, 15, 8), 255);
+        assert!(rgba.chunks_exact(4).all(|pixel| pixel[3] == 255));
+
+        // Sur le contour, des pixels intermédiaires apparaissent
+        let softened = (0..height).filter(|&y| (0..width).any(|x| (1..255).contains(&at(&rgba, x, y)))).count();
+        assert!(softened >= height / 2, "{} lignes adoucies", softened);
+
+        // Une image uniforme est laissée intacte
+        let mut flat = image(width, height, |_, _| true);
+        FxaaPass::new().apply(&mut flat, width as u32, height as u32);
+        assert!(flat.iter().all(|&c| c == 255));
+    }
+}
@@ -0,0 +1,297 @@
+//! Composition finale de l'image
+//!
+//! Superpose, ligne par ligne, la couleur de fond, la 3D rastérisée, les
+//! deux plans de tuiles et les sprites dans l'ordre donné par les registres
+//! de priorité en vigueur sur la ligne ([`ScanlineLog`]). Les plans 2D sont
+//! des images à la résolution native, transparentes là où leur alpha est
+//! nul, redessinées à chaque image depuis la VRAM
+//! ([`Compositor::draw_planes`]) ; la 3D, dont la transparence est déjà
+//! résolue, recouvre tout pixel qu'elle a tracé.
+
+use crate::memory::{
+    ByteOrder, DisplayLayer, LayerRegisters, ScanlineLog, LAYER_PALETTE_BASE, SPRITE_COUNT, SPRITE_TABLE_BASE,
+    TILE_MAP_BASE, TILE_MAP_TILES, TILE_PATTERN_BASE, TILE_PATTERN_COUNT, TILE_SCROLL_BASE, TILE_SIZE,
+};
+
+use super::framebuffer::Framebuffer;
+
+/// Image RGBA8 d'un plan 2D, à la résolution native
+#[derive(Debug, Clone, Default)]
+pub struct LayerImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl LayerImage {
+    /// Plan entièrement transparent
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, rgba: vec![0; (width * height * 4) as usize] }
+    }
+
+    pub fn clear(&mut self) {
+        self.rgba.fill(0);
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.rgba.chunks_exact(4).all(|pixel| pixel[3] == 0)
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        if x >= self.width || y >= self.height {
+            return [0; 4];
+        }
+        let offset = ((y * self.width + x) * 4) as usize;
+        [self.rgba[offset], self.rgba[offset + 1], self.rgba[offset + 2], self.rgba[offset + 3]]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        if x < self.width && y < self.height {
+            let offset = ((y * self.width + x) * 4) as usize;
+            self.rgba[offset..offset + 4].copy_from_slice(&rgba);
+        }
+    }
+}
+
+/// Mélange `src` sur `dst` selon l'alpha de `src`
+fn blend(dst: [u8; 4], src: [u8; 4]) -> [u8; 4] {
+    let alpha = src[3] as u32;
+    let mix = |d: u8, s: u8| ((s as u32 * alpha + d as u32 * (255 - alpha) + 127) / 255) as u8;
+    [mix(dst[0], src[0]), mix(dst[1], src[1]), mix(dst[2], src[2]), 255]
+}
+
+/// Palette des plans 2D, index 0 transparent
+fn layer_palette(vram: &[u8]) -> [[u8; 4]; 256] {
+    let expand = |value: u16| ((value & 0x1F) << 3 | (value & 0x1F) >> 2) as u8;
+    std::array::from_fn(|index| {
+        let color = ByteOrder::Little.read_u16(vram, LAYER_PALETTE_BASE + index * 2).unwrap_or(0);
+        let alpha = if index == 0 { 0 } else { 255 };
+        [expand(color >> 10), expand(color >> 5), expand(color), alpha]
+    })
+}
+
+/// Index de palette du pixel (`x`, `y`) d'un motif
+fn pattern_pixel(vram: &[u8], tile: usize, x: u32, y: u32) -> u8 {
+    let offset = TILE_PATTERN_BASE + (tile % TILE_PATTERN_COUNT) * (TILE_SIZE * TILE_SIZE) as usize;
+    vram.get(offset + (y * TILE_SIZE + x) as usize).copied().unwrap_or(0)
+}
+
+/// Plans 2D et registres de composition de l'image en cours
+#[derive(Debug, Clone, Default)]
+pub struct Compositor {
+    /// Plans de tuiles A et B puis sprites
+    planes: [LayerImage; 3],
+    scanlines: ScanlineLog,
+}
+
+impl Compositor {
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut compositor = Self::default();
+        compositor.resize(width, height);
+        compositor
+    }
+
+    /// Change la résolution native ; les plans sont effacés
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if (self.planes[0].width, self.planes[0].height) != (width, height) {
+            self.planes = std::array::from_fn(|_| LayerImage::new(width, height));
+        }
+    }
+
+    /// Plan 2D d'une couche ; la 3D vient du framebuffer
+    pub fn plane_mut(&mut self, layer: DisplayLayer) -> Option<&mut LayerImage> {
+        match layer {
+            DisplayLayer::Scene3D => None,
+            layer => self.planes.get_mut(layer.index() - 1),
+        }
+    }
+
+    /// Redessine les deux plans de tuiles et les sprites décrits en VRAM
+    pub fn draw_planes(&mut self, vram: &[u8]) {
+        let palette = layer_palette(vram);
+        let [tile_a, tile_b, sprites] = &mut self.planes;
+        for (index, plane) in [tile_a, tile_b].into_iter().enumerate() {
+            draw_tile_plane(plane, vram, &palette, index);
+        }
+        draw_sprites(sprites, vram, &palette);
+    }
+
+    fn plane(&self, layer: DisplayLayer) -> Option<&LayerImage> {
+        match layer {
+            DisplayLayer::Scene3D => None,
+            layer => self.planes.get(layer.index() - 1),
+        }
+    }
+
+    /// Registres de composition relevés pendant l'image
+    pub fn set_scanlines(&mut self, scanlines: &ScanlineLog) {
+        self.scanlines.copy_from(scanlines);
+    }
+
+    pub fn scanlines(&self) -> &ScanlineLog {
+        &self.scanlines
+    }
+
+    /// Vrai si la composition ne changerait rien à la 3D : plans vides,
+    /// 3D active sur fond noir, sans effet de raster
+    pub fn is_passthrough(&self) -> bool {
+        let registers = self.scanlines.end();
+        !self.scanlines.has_raster_effects()
+            && registers.enabled(DisplayLayer::Scene3D)
+            && registers.backdrop == 0
+            && self.planes.iter().all(LayerImage::is_blank)
+    }
+
+    /// Compose l'image finale dans le framebuffer, à sa résolution interne
+    pub fn compose(&self, framebuffer: &mut Framebuffer) {
+        if self.is_passthrough() {
+            return;
+        }
+        let scale = framebuffer.scale.max(1);
+        let width = framebuffer.width as usize;
+        for (y, row) in framebuffer.color_data.chunks_exact_mut(width * 4).enumerate() {
+            let line = y as u32 / scale;
+            let registers: LayerRegisters = self.scanlines.registers_at(line);
+            let order = registers.draw_order();
+            let backdrop = registers.backdrop_rgba();
+
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let scene = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                let mut out = backdrop;
+                for &layer in &order {
+                    out = match self.plane(layer) {
+                        None if scene[3] > 0 => [scene[0], scene[1], scene[2], 255],
+                        None => out,
+                        Some(plane) => blend(out, plane.pixel(x as u32 / scale, line)),
+                    };
+                }
+                pixel.copy_from_slice(&out);
+            }
+        }
+    }
+}
+
+/// Plan de tuiles `index` (0 pour A, 1 pour B), décalé par son défilement
+fn draw_tile_plane(plane: &mut LayerImage, vram: &[u8], palette: &[[u8; 4]; 256], index: usize) {
+    let map = TILE_MAP_BASE[index];
+    let scroll = |axis: usize| ByteOrder::Little.read_u16(vram, TILE_SCROLL_BASE + index * 4 + axis * 2).unwrap_or(0) as u32;
+    let (scroll_x, scroll_y) = (scroll(0), scroll(1));
+    let span = TILE_MAP_TILES * TILE_SIZE;
+
+    for y in 0..plane.height {
+        let py = (y + scroll_y) % span;
+        for x in 0..plane.width {
+            let px = (x + scroll_x) % span;
+            let entry_offset = map + ((py / TILE_SIZE * TILE_MAP_TILES + px / TILE_SIZE) * 2) as usize;
+            let entry = ByteOrder::Little.read_u16(vram, entry_offset).unwrap_or(0);
+            let mut fx = px % TILE_SIZE;
+            let mut fy = py % TILE_SIZE;
+            if entry & 0x4000 != 0 {
+                fx = TILE_SIZE - 1 - fx;
+            }
+            if entry & 0x8000 != 0 {
+                fy = TILE_SIZE - 1 - fy;
+            }
+            let color = pattern_pixel(vram, (entry & 0x0FFF) as usize, fx, fy);
+            plane.set_pixel(x, y, palette[color as usize]);
+        }
+    }
+}
+
+/// Sprites de la table, les premières entrées devant
+fn draw_sprites(plane: &mut LayerImage, vram: &[u8], palette: &[[u8; 4]; 256]) {
+    plane.clear();
+    for entry in (0..SPRITE_COUNT).rev() {
+        let base = SPRITE_TABLE_BASE + entry * 8;
+        let word = |offset: usize| ByteOrder::Little.read_u16(vram, base + offset).unwrap_or(0);
+        let (x, y, tile) = (word(0) as i16 as i32, word(2) as i16 as i32, word(4) as usize);
+        let (width, height) = (word(6) & 0xFF, word(6) >> 8);
+        if width == 0 {
+            continue;
+        }
+
+        for sy in 0..height as u32 * TILE_SIZE {
+            for sx in 0..width as u32 * TILE_SIZE {
+                let (dx, dy) = (x + sx as i32, y + sy as i32);
+                if dx < 0 || dy < 0 {
+                    continue;
+                }
+                let pattern = tile + (sy / TILE_SIZE * width as u32 + sx / TILE_SIZE) as usize;
+                let color = pattern_pixel(vram, pattern, sx % TILE_SIZE, sy % TILE_SIZE);
+                if color != 0 {
+                    plane.set_pixel(dx as u32, dy as u32, palette[color as usize]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(framebuffer: &Framebuffer, x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * framebuffer.width + x) * 4) as usize;
+        framebuffer.color_data[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_layers_follow_scanline_priorities() {
+        let mut framebuffer = Framebuffer::new(2, 4);
+        // 3D tracée sur la colonne 0 seulement
+        for y in 0..4 {
+            framebuffer.color_data[(y * 2 * 4) as usize..(y * 2 * 4 + 4) as usize].copy_from_slice(&[200, 0, 0, 255]);
+        }
+        let mut compositor = Compositor::new(2, 4);
+        assert!(compositor.is_passthrough());
+        let tile_a = compositor.plane_mut(DisplayLayer::TileA).unwrap();
+        for y in 0..4 {
+            tile_a.set_pixel(0, y, [0, 0, 255, 255]);
+        }
+
+        // Lignes 0-1 : plan A devant la 3D ; lignes 2-3 : 3D devant, fond
+        // gris (effet de raster écrit pendant la ligne 1)
+        let mut scanlines = ScanlineLog::new(LayerRegisters::default());
+        scanlines.record(1, LayerRegisters { priority: 0x3015, backdrop: 0x0080_8080, ..Default::default() });
+        compositor.set_scanlines(&scanlines);
+        compositor.compose(&mut framebuffer);
+
+        assert_eq!(pixel(&framebuffer, 0, 0), [0, 0, 255, 255]);
+        assert_eq!(pixel(&framebuffer, 1, 1), [0, 0, 0, 255]);
+        assert_eq!(pixel(&framebuffer, 0, 2), [200, 0, 0, 255]);
+        assert_eq!(pixel(&framebuffer, 1, 3), [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn test_planes_are_drawn_from_vram() {
+        let mut vram = vec![0u8; 4 * 1024 * 1024];
+        // Couleur 1 rouge, motif 1 plein, motif 2 avec un seul pixel en (1, 0)
+        ByteOrder::Little.write_u16(&mut vram, LAYER_PALETTE_BASE + 2, 0x7C00);
+        let pattern = |tile: usize| TILE_PATTERN_BASE + tile * 64;
+        vram[pattern(1)..pattern(2)].fill(1);
+        vram[pattern(2) + 1] = 1;
+        // Plan A : motif 2 en miroir horizontal sur la première tuile,
+        // défilé de 8 pixels vers le bas ; plan B vide
+        ByteOrder::Little.write_u16(&mut vram, TILE_MAP_BASE[0] + TILE_MAP_TILES as usize * 2, 0x4002);
+        ByteOrder::Little.write_u16(&mut vram, TILE_SCROLL_BASE + 2, 8);
+        // Sprite de 1×1 tuile en (4, 4)
+        ByteOrder::Little.write_u16(&mut vram, SPRITE_TABLE_BASE, 4);
+        ByteOrder::Little.write_u16(&mut vram, SPRITE_TABLE_BASE + 2, 4);
+        ByteOrder::Little.write_u16(&mut vram, SPRITE_TABLE_BASE + 4, 1);
+        ByteOrder::Little.write_u16(&mut vram, SPRITE_TABLE_BASE + 6, 0x0101);
+
+        let mut compositor = Compositor::new(16, 16);
+        compositor.draw_planes(&vram);
+        assert!(!compositor.is_passthrough());
+
+        let red = [255, 0, 0, 255];
+        let tile_a = compositor.plane(DisplayLayer::TileA).unwrap();
+        assert_eq!(tile_a.pixel(6, 0), red);
+        assert_eq!(tile_a.pixel(1, 0), [0; 4]);
+        assert!(compositor.plane(DisplayLayer::TileB).unwrap().is_blank());
+        let sprites = compositor.plane(DisplayLayer::Sprites).unwrap();
+        assert_eq!(sprites.pixel(4, 4), red);
+        assert_eq!(sprites.pixel(11, 11), red);
+        assert_eq!(sprites.pixel(12, 12), [0; 4]);
+    }
+}
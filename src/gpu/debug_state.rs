@@ -0,0 +1,227 @@
+//! État du GPU pour le débogueur
+//!
+//! [`Model2Gpu::debug_state`] relève les matrices, les états de rendu, la
+//! fenêtre d'affichage et la latence estimée de présentation, le brouillard
+//! et l'éclairage courants
+//! ([`GpuDebugState`]), affichables en texte ligne par ligne.
+//! [`GpuStateWatch`] compare ces lignes d'une frame à l'autre : une ligne
+//! modifiée est mise en évidence, puis s'estompe en [`HIGHLIGHT_FRAMES`]
+//! frames, ce qui laisse le temps de repérer un changement ponctuel.
+
+use glam::{Mat4, Vec3};
+use std::fmt;
+
+use super::{DepthMode, Model2Gpu, TextureFilter};
+
+/// Frames pendant lesquelles une ligne modifiée reste mise en évidence
+pub const HIGHLIGHT_FRAMES: u32 = 30;
+
+/// Instantané de l'état du GPU
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuDebugState {
+    pub model: Mat4,
+    pub view: Mat4,
+    pub projection: Mat4,
+    pub viewport: Mat4,
+
+    pub camera_position: Vec3,
+    pub camera_target: Vec3,
+    pub field_of_view: f32,
+    pub near_plane: f32,
+    pub far_plane: f32,
+
+    /// Dimensions de la fenêtre d'affichage, en pixels natifs
+    pub resolution: (u32, u32),
+    pub internal_scale: u32,
+    /// Latence estimée entre la fin d'une frame et son affichage
+    pub present_latency_ms: f32,
+
+    pub z_buffer: bool,
+    pub texturing: bool,
+    pub transparency: bool,
+    pub frustum_culling: bool,
+    pub backface_culling: bool,
+    pub fixed_point: bool,
+    pub depth_mode: DepthMode,
+    pub texture_filter: TextureFilter,
+
+    pub fog_enabled: bool,
+    pub fog_start: f32,
+    pub fog_end: f32,
+    pub fog_color: [f32; 4],
+
+    /// Table de brouillard du jeu chargée
+    pub fog_table: bool,
+
+    pub lighting: bool,
+}
+
+impl Model2Gpu {
+    /// État courant du GPU, pour le débogueur
+    pub fn debug_state(&self) -> GpuDebugState {
+        let geometry = &self.geometry_processor;
+        GpuDebugState {
+            model: geometry.model_matrix,
+            view: geometry.view_matrix,
+            projection: geometry.projection_matrix,
+            viewport: geometry.viewport_matrix,
+            camera_position: geometry.camera_position,
+            camera_target: geometry.camera_target,
+            field_of_view: geometry.field_of_view,
+            near_plane: geometry.near_plane,
+            far_plane: geometry.far_plane,
+            resolution: self.resolution.dimensions(),
+            internal_scale: self.config.internal_scale,
+            present_latency_ms: self.stats.present_latency_ms,
+            z_buffer: self.config.z_buffer_enabled,
+            texturing: self.config.texturing_enabled,
+            transparency: self.config.transparency_enabled,
+            frustum_culling: geometry.frustum_culling,
+            backface_culling: geometry.backface_culling,
+            fixed_point: geometry.fixed_point,
+            depth_mode: self.config.depth_mode,
+            texture_filter: self.config.texture_filter,
+            fog_enabled: geometry.fog_enabled,
+            fog_start: geometry.fog_start,
+            fog_end: geometry.fog_end,
+            fog_color: geometry.fog_color,
+            fog_table: geometry.fog_table.is_some(),
+            lighting: self.config.lighting_enabled,
+        }
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "oui"
+    } else {
+        "non"
+    }
+}
+
+fn floats(values: &[f32]) -> String {
+    values.iter().map(|value| format!("{:9.3}", value)).collect::<Vec<_>>().join(" ")
+}
+
+impl GpuDebugState {
+    /// Lignes de texte de l'état : une par ligne de matrice et par groupe
+    /// de paramètres
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (name, matrix) in [("modèle", self.model), ("vue", self.view), ("projection", self.projection), ("viewport", self.viewport)] {
+            for row in 0..4 {
+                lines.push(format!("{:<10} {} {}", if row == 0 { name } else { "" }, row, floats(&matrix.row(row).to_array())));
+            }
+        }
+        lines.push(format!("caméra     {} -> {}", floats(&self.camera_position.to_array()), floats(&self.camera_target.to_array())));
+        lines.push(format!("champ      {:.1} plans {:.3} - {:.1}", self.field_of_view.to_degrees(), self.near_plane, self.far_plane));
+        lines.push(format!(
+            "affichage  {}x{} échelle {} latence {:.1} ms",
+            self.resolution.0, self.resolution.1, self.internal_scale, self.present_latency_ms
+        ));
+        lines.push(format!(
+            "profondeur {:?} z-buffer {} faces cachées {} frustum {}",
+            self.depth_mode, on_off(self.z_buffer), on_off(self.backface_culling), on_off(self.frustum_culling)
+        ));
+        lines.push(format!(
+            "textures   {} filtre {:?} transparence {} virgule fixe {}",
+            on_off(self.texturing), self.texture_filter, on_off(self.transparency), on_off(self.fixed_point)
+        ));
+        lines.push(format!(
+            "brouillard {} {:.1} - {:.1} table {} couleur {}",
+            on_off(self.fog_enabled), self.fog_start, self.fog_end, on_off(self.fog_table), floats(&self.fog_color)
+        ));
+        lines.push(format!("éclairage  {}", on_off(self.lighting)));
+        lines
+    }
+}
+
+impl fmt::Display for GpuDebugState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Ligne affichée, avec l'intensité de sa mise en évidence (1 à la frame
+/// du changement, 0 une fois estompée)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedLine {
+    pub text: String,
+    pub highlight: f32,
+}
+
+/// Suivi des changements de l'état du GPU d'une frame à l'autre
+#[derive(Debug, Clone, Default)]
+pub struct GpuStateWatch {
+    lines: Vec<String>,
+
+    /// Frames écoulées depuis le dernier changement de chaque ligne
+    ages: Vec<u32>,
+}
+
+impl GpuStateWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Relève l'état de la frame ; le premier relevé ne met rien en évidence
+    pub fn update(&mut self, state: &GpuDebugState) {
+        let lines = state.lines();
+        let first = self.lines.is_empty();
+        self.ages.resize(lines.len(), HIGHLIGHT_FRAMES);
+        for (index, line) in lines.iter().enumerate() {
+            let changed = !first && self.lines.get(index) != Some(line);
+            self.ages[index] = if changed { 0 } else { self.ages[index].saturating_add(1).min(HIGHLIGHT_FRAMES) };
+        }
+        self.lines = lines;
+    }
+
+    /// Lignes de la dernière frame relevée
+    pub fn lines(&self) -> Vec<WatchedLine> {
+        self.lines
+            .iter()
+            .zip(&self.ages)
+            .map(|(text, &age)| WatchedLine {
+                text: text.clone(),
+                highlight: 1.0 - age as f32 / HIGHLIGHT_FRAMES as f32,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_highlights_changed_lines() {
+        let mut gpu = Model2Gpu::headless();
+        let mut watch = GpuStateWatch::new();
+        watch.update(&gpu.debug_state());
+        assert!(watch.lines().iter().all(|line| line.highlight == 0.0));
+        assert!(gpu.debug_state().to_string().contains("affichage  496x384"));
+        gpu.stats.present_latency_ms = 16.7;
+        assert!(gpu.debug_state().to_string().contains("latence 16.7 ms"));
+        watch.update(&gpu.debug_state());
+
+        gpu.geometry_processor.set_fog(true, 10.0, 500.0, [0.5, 0.5, 0.5, 1.0]);
+        gpu.geometry_processor.set_model_matrix(Mat4::from_translation(Vec3::new(0.0, 0.0, -5.0)));
+        watch.update(&gpu.debug_state());
+        let highlighted: Vec<String> = watch.lines().into_iter().filter(|line| line.highlight == 1.0).map(|line| line.text).collect();
+        assert_eq!(highlighted.len(), 2);
+        assert!(highlighted[0].ends_with("-5.000"));
+        assert!(highlighted[1].starts_with("brouillard oui"));
+
+        // La mise en évidence s'estompe sans nouveau changement
+        watch.update(&gpu.debug_state());
+        let fog = watch.lines().into_iter().find(|line| line.text.starts_with("brouillard")).unwrap();
+        assert!(fog.highlight > 0.9 && fog.highlight < 1.0);
+        for _ in 0..HIGHLIGHT_FRAMES {
+            watch.update(&gpu.debug_state());
+        }
+        assert!(watch.lines().iter().all(|line| line.highlight == 0.0));
+    }
+}
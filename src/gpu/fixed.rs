@@ -0,0 +1,101 @@
+//! Arithmétique en virgule fixe du TGP
+//!
+//! Le processeur de géométrie d'origine calcule en virgule fixe 1.15.16
+//! (signe, 15 bits entiers, 16 bits fractionnaires). Les produits sont
+//! tronqués et les sommes saturent, ce qui donne au rendu d'origine son léger
+//! tremblement des sommets et ses écarts de culling ; ce mode les reproduit
+//! pour comparer avec le matériel.
+
+use glam::{Mat4, Vec4};
+
+/// Nombre en virgule fixe 1.15.16
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Fixed(pub i32);
+
+impl Fixed {
+    /// Bits fractionnaires
+    pub const FRAC_BITS: u32 = 16;
+    pub const ONE: Fixed = Fixed(1 << Self::FRAC_BITS);
+
+    /// Conversion arrondie au plus proche, saturée aux bornes du format
+    pub fn from_f32(value: f32) -> Self {
+        let scaled = (value * Self::ONE.0 as f32).round();
+        // `as` sature les flottants hors bornes et envoie NaN sur 0
+        Fixed(scaled as i32)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE.0 as f32
+    }
+
+    /// Produit tronqué vers moins l'infini, comme le multiplieur matériel
+    pub fn truncating_mul(self, other: Fixed) -> Fixed {
+        let product = (self.0 as i64 * other.0 as i64) >> Self::FRAC_BITS;
+        Fixed(product.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+
+    pub fn saturating_add(self, other: Fixed) -> Fixed {
+        Fixed(self.0.saturating_add(other.0))
+    }
+}
+
+/// Matrice 4x4 en virgule fixe, colonnes comme `glam::Mat4`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedMat4 {
+    columns: [[Fixed; 4]; 4],
+}
+
+impl FixedMat4 {
+    pub fn from_mat4(matrix: &Mat4) -> Self {
+        Self { columns: matrix.to_cols_array_2d().map(|column| column.map(Fixed::from_f32)) }
+    }
+
+    /// Transforme un point ; les coordonnées sont converties en virgule
+    /// fixe avant le calcul et le résultat en est issu
+    pub fn transform(&self, point: Vec4) -> Vec4 {
+        let input = point.to_array().map(Fixed::from_f32);
+        let mut output = [Fixed::default(); 4];
+        for (row, value) in output.iter_mut().enumerate() {
+            *value = self.columns.iter().zip(input).fold(Fixed::default(), |sum, (column, coordinate)| {
+                sum.saturating_add(column[row].truncating_mul(coordinate))
+            });
+        }
+        Vec4::from_array(output.map(Fixed::to_f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    #[test]
+    fn test_fixed_conversion_and_products() {
+        assert_eq!(Fixed::from_f32(1.5), Fixed(0x18000));
+        assert_eq!(Fixed::from_f32(-0.25).to_f32(), -0.25);
+        assert_eq!(Fixed::from_f32(1.0e9), Fixed(i32::MAX));
+        assert_eq!(Fixed::from_f32(f32::NAN), Fixed(0));
+
+        // Troncature du produit : 1/65536 * 0.5 disparaît
+        assert_eq!(Fixed(1).truncating_mul(Fixed::from_f32(0.5)), Fixed(0));
+        assert_eq!(Fixed(-1).truncating_mul(Fixed::from_f32(0.5)), Fixed(-1));
+        assert_eq!(Fixed::from_f32(3.0).truncating_mul(Fixed::from_f32(-2.5)).to_f32(), -7.5);
+        assert_eq!(Fixed(i32::MAX).saturating_add(Fixed::ONE), Fixed(i32::MAX));
+    }
+
+    #[test]
+    fn test_fixed_transform_matches_float_within_precision() {
+        let matrix = Mat4::perspective_rh(0.8, 496.0 / 384.0, 0.1, 1000.0)
+            * Mat4::from_translation(Vec3::new(0.3, -1.2, -7.0))
+            * Mat4::from_rotation_y(0.7);
+        let point = Vec4::new(1.234, -0.567, 2.5, 1.0);
+
+        let float = matrix * point;
+        let fixed = FixedMat4::from_mat4(&matrix).transform(point);
+        assert!((float - fixed).abs().max_element() < 1.0e-3, "{:?} / {:?}", float, fixed);
+        // Le résultat est exactement représentable sur la grille 1/65536
+        for value in fixed.to_array() {
+            assert_eq!(Fixed::from_f32(value).to_f32(), value);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Brouillard par table, comme le matériel Model 2
+//!
+//! Le Model 2 ne calcule pas un brouillard linéaire : la densité de chaque
+//! pixel est lue dans une table de 256 entrées indexée par sa profondeur à
+//! l'écran. Le jeu remplit la table par les registres I/O, ou elle est
+//! chargée depuis les données du jeu (voir `GraphicsConfig::fog_table_address`),
+//! ce qui donne des voiles non linéaires (brume de Sega Rally). Sans table,
+//! le brouillard analytique du processeur de géométrie sert de repli.
+
+/// Entrées de la table de brouillard
+pub const FOG_TABLE_SIZE: usize = crate::memory::FOG_TABLE_LENGTH as usize;
+
+/// Table de densités du brouillard, de la profondeur 0 (proche) à 1 (loin)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FogTable {
+    entries: [u8; FOG_TABLE_SIZE],
+}
+
+impl FogTable {
+    /// Table à partir d'octets de densité (255 = couleur du brouillard
+    /// seule) ; les entrées manquantes reprennent la dernière fournie
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut entries = [0u8; FOG_TABLE_SIZE];
+        let last = bytes[..bytes.len().min(FOG_TABLE_SIZE)].last().copied().unwrap_or(0);
+        for (i, entry) in entries.iter_mut().enumerate() {
+            *entry = bytes.get(i).copied().unwrap_or(last);
+        }
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[u8; FOG_TABLE_SIZE] {
+        &self.entries
+    }
+
+    /// Densité à une profondeur normalisée, sans interpolation entre entrées
+    pub fn density(&self, depth: f32) -> f32 {
+        let index = (depth.clamp(0.0, 1.0) * (FOG_TABLE_SIZE - 1) as f32) as usize;
+        self.entries[index] as f32 / 255.0
+    }
+}
+
+/// Brouillard appliqué aux pixels d'un polygone
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PixelFog<'a> {
+    #[default]
+    Off,
+    /// Densité interpolée depuis les sommets (`TransformedVertex::fog_factor`)
+    Analytic { color: [f32; 3] },
+    /// Densité lue dans la table selon la profondeur du pixel
+    Table { color: [f32; 3], table: &'a FogTable },
+}
+
+impl PixelFog<'_> {
+    /// Mélange la couleur d'un pixel avec celle du brouillard
+    pub fn apply(&self, rgba: [f32; 4], vertex_factor: f32, depth: f32) -> [f32; 4] {
+        let (color, density) = match self {
+            PixelFog::Off => return rgba,
+            PixelFog::Analytic { color } => (color, vertex_factor.clamp(0.0, 1.0)),
+            PixelFog::Table { color, table } => (color, table.density(depth)),
+        };
+        [
+            rgba[0] + (color[0] - rgba[0]) * density,
+            rgba[1] + (color[1] - rgba[1]) * density,
+            rgba[2] + (color[2] - rgba[2]) * density,
+            rgba[3],
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_lookup_by_depth() {
+        // Rampe courte : les entrées manquantes restent à la dernière valeur
+        let table = FogTable::from_bytes(&[0, 0, 64, 255]);
+        assert_eq!(table.entries()[3], 255);
+        assert_eq!(table.entries()[FOG_TABLE_SIZE - 1], 255);
+        assert_eq!(table.density(0.0), 0.0);
+        assert_eq!(table.density(2.0 / 255.0), 64.0 / 255.0);
+        assert_eq!(table.density(-1.0), 0.0);
+
+        let fog = PixelFog::Table { color: [1.0, 1.0, 1.0], table: &table };
+        assert_eq!(fog.apply([0.0, 0.5, 1.0, 0.25], 0.0, 1.0), [1.0, 1.0, 1.0, 0.25]);
+        let analytic = PixelFog::Analytic { color: [1.0, 0.0, 0.0] };
+        assert_eq!(analytic.apply([0.0, 0.0, 0.0, 1.0], 0.5, 1.0), [0.5, 0.0, 0.0, 1.0]);
+        assert_eq!(PixelFog::Off.apply([0.2; 4], 1.0, 1.0), [0.2; 4]);
+    }
+}
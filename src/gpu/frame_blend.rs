@@ -0,0 +1,82 @@
+//! Mélange des deux dernières frames émulées
+//!
+//! En cadence `blend`, l'écran de l'hôte tombe rarement pile sur une frame du
+//! Model 2 : l'image présentée est la moyenne pondérée de la frame
+//! précédente et de la dernière, le poids de la dernière croissant avec le
+//! temps écoulé depuis qu'elle est prête. Le mouvement reste régulier au
+//! prix d'une frame de retard et d'un léger flou sur les objets rapides.
+
+/// Copies des deux dernières frames et image mélangée
+#[derive(Debug, Clone, Default)]
+pub struct FrameBlender {
+    previous: Vec<u8>,
+    current: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl FrameBlender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retient l'image RGBA8 d'une nouvelle frame émulée ; après un
+    /// changement de taille, elle sert aussi de frame précédente
+    pub fn push(&mut self, frame: &[u8]) {
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+        self.current.extend_from_slice(frame);
+        if self.previous.len() != frame.len() {
+            self.previous.clone_from(&self.current);
+        }
+    }
+
+    /// Image mélangée : `weight` de la dernière frame (0.0 à 1.0), le reste
+    /// de la précédente ; vide tant qu'aucune frame n'a été retenue
+    pub fn blend(&mut self, weight: f32) -> &[u8] {
+        let weight = (weight.clamp(0.0, 1.0) * 256.0) as u32;
+        self.output.clear();
+        self.output.extend(
+            self.previous
+                .iter()
+                .zip(&self.current)
+                .map(|(&previous, &current)| ((previous as u32 * (256 - weight) + current as u32 * weight) >> 8) as u8),
+        );
+        &self.output
+    }
+
+    /// Échange l'image mélangée avec `frame`, le temps de la présenter ; un
+    /// second appel rend à `frame` son contenu
+    pub fn swap_output(&mut self, frame: &mut Vec<u8>) {
+        std::mem::swap(&mut self.output, frame);
+    }
+
+    /// Oublie les frames retenues (changement de jeu, retour en cadence
+    /// sans mélange)
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blends_last_two_frames() {
+        let mut blender = FrameBlender::new();
+        assert!(blender.blend(0.5).is_empty());
+
+        blender.push(&[0, 100, 200, 255]);
+        assert_eq!(blender.blend(0.0), [0, 100, 200, 255]);
+
+        blender.push(&[200, 100, 0, 255]);
+        assert_eq!(blender.blend(0.0), [0, 100, 200, 255]);
+        assert_eq!(blender.blend(0.5), [100, 100, 100, 255]);
+        assert_eq!(blender.blend(1.0), [200, 100, 0, 255]);
+
+        // Nouvelle taille : pas de mélange avec l'ancienne image
+        blender.push(&[10, 20]);
+        assert_eq!(blender.blend(0.25), [10, 20]);
+    }
+}
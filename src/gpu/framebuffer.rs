@@ -1,76 +1,173 @@
 //! Framebuffer virtuel émulant l'affichage Model 2
 
-use anyhow::Result;
-use wgpu::*;
+use crate::error::Result;
+use super::antialias::{self, sample_positions};
+use super::fog::PixelFog;
 use super::geometry::TransformedTriangle;
+use super::raster::{self, RasterTarget, TextureStages};
+use super::texture::microtexture_id;
 use super::texture::TextureManager;
 
-/// Framebuffer virtuel
+/// Framebuffer virtuel, rastérisé par le CPU puis présenté par wgpu ou
+/// copié directement dans la fenêtre
 pub struct Framebuffer {
     pub width: u32,
     pub height: u32,
-    pub color_texture: Texture,
-    pub color_texture_view: TextureView,
-    pub depth_texture: Texture,
-    pub depth_texture_view: TextureView,
+    /// Image finale, échantillons moyennés
     pub color_data: Vec<u8>,
+    /// Profondeur de chaque échantillon
     pub depth_data: Vec<f32>,
+    /// Échantillons par pixel (MSAA) ; au-delà de 1, les couleurs sont
+    /// tracées dans `sample_data` puis moyennées par [`Framebuffer::resolve`]
+    pub sample_count: u32,
+    pub sample_data: Vec<u8>,
+    /// Multiple de la résolution native (résolution interne) ; `width` et
+    /// `height` sont déjà multipliés
+    pub scale: u32,
 }
 
 impl Framebuffer {
-    pub fn new(device: &Device, width: u32, height: u32) -> Self {
-        let color_texture = device.create_texture(&TextureDescriptor {
-            label: Some("Framebuffer Color"),
-            size: Extent3d { width, height, depth_or_array_layers: 1 },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Rgba8UnormSrgb,
-            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
-            view_formats: &[],
-        });
-        
-        let depth_texture = device.create_texture(&TextureDescriptor {
-            label: Some("Framebuffer Depth"),
-            size: Extent3d { width, height, depth_or_array_layers: 1 },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Depth32Float,
-            usage: TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        });
-        
-        let color_texture_view = color_texture.create_view(&TextureViewDescriptor::default());
-        let depth_texture_view = depth_texture.create_view(&TextureViewDescriptor::default());
-        
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::with_samples(width, height, 1)
+    }
+
+    pub fn with_samples(width: u32, height: u32, sample_count: u32) -> Self {
         let pixel_count = (width * height) as usize;
+        let sample_count = sample_positions(sample_count).len() as u32;
+        let multisampled = if sample_count > 1 { pixel_count * sample_count as usize * 4 } else { 0 };
         
         Self {
             width,
             height,
-            color_texture,
-            color_texture_view,
-            depth_texture,
-            depth_texture_view,
             color_data: vec![0; pixel_count * 4],
-            depth_data: vec![1.0; pixel_count],
+            depth_data: vec![1.0; pixel_count * sample_count as usize],
+            sample_count,
+            sample_data: vec![0; multisampled],
+            scale: 1,
         }
     }
     
-    pub fn resize(&mut self, device: &Device, width: u32, height: u32) -> Result<()> {
-        *self = Self::new(device, width, height);
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<()> {
+        *self = Self::with_samples(width, height, self.sample_count);
         Ok(())
     }
+
+    /// Rend à `scale` fois la résolution native `native` ; l'image est
+    /// effacée
+    pub fn set_scale(&mut self, native: (u32, u32), scale: u32) {
+        let scale = scale.max(1);
+        if (self.width, self.height, self.scale) != (native.0 * scale, native.1 * scale, scale) {
+            *self = Self::with_samples(native.0 * scale, native.1 * scale, self.sample_count);
+            self.scale = scale;
+        }
+    }
+
+    /// Taille de l'image à la résolution native
+    pub fn native_size(&self) -> (u32, u32) {
+        (self.width / self.scale, self.height / self.scale)
+    }
+
+    /// Image ramenée à la résolution native (captures, vignettes)
+    pub fn native_color_data(&self) -> Vec<u8> {
+        if self.scale <= 1 {
+            return self.color_data.clone();
+        }
+        let (width, height) = self.native_size();
+        let mut out = vec![0; (width * height * 4) as usize];
+        downsample(&self.color_data, self.width, self.scale, &mut out);
+        out
+    }
+
+    /// Change le nombre d'échantillons par pixel ; l'image est effacée
+    pub fn set_sample_count(&mut self, sample_count: u32) {
+        if sample_positions(sample_count).len() as u32 != self.sample_count {
+            let scale = self.scale;
+            *self = Self::with_samples(self.width, self.height, sample_count);
+            self.scale = scale;
+        }
+    }
     
     pub fn clear(&mut self) {
         self.color_data.fill(0);
         self.depth_data.fill(1.0);
+        self.sample_data.fill(0);
+    }
+
+    /// Moyenne les échantillons dans l'image finale
+    pub fn resolve(&mut self) {
+        if self.sample_count > 1 {
+            antialias::resolve(&self.sample_data, self.sample_count, &mut self.color_data);
+        }
     }
     
-    pub fn rasterize_triangle(&mut self, _triangle: &TransformedTriangle, _texture_manager: &TextureManager) -> Result<()> {
-        // Rasterisation software simple pour l'émulation précise
-        // Implementation simplifiée pour la démo
+    /// Trace un triangle transformé ; sans test de profondeur en mode priorité
+    pub fn rasterize_triangle(&mut self, triangle: &TransformedTriangle, texture_manager: &TextureManager, fog: &PixelFog, depth_test: bool) -> Result<()> {
+        let texels = |id: Option<u32>| id.and_then(|id| texture_manager.get_texture(id)).map(|texture| texture.texels());
+        let textures = TextureStages {
+            base: texels(triangle.texture_id),
+            detail: texels(triangle.flags.microtexture.map(microtexture_id)),
+            filter: texture_manager.filter(),
+            mipmaps: triangle.texture_id.and_then(|id| texture_manager.get_texture(id)).map_or(&[], |texture| &texture.mipmaps),
+            lod_bias: texture_manager.mipmaps().lod_bias,
+        };
+        let color = if self.sample_count > 1 { &mut self.sample_data } else { &mut self.color_data };
+        let mut target = RasterTarget {
+            width: self.width,
+            height: self.height,
+            color,
+            depth: &mut self.depth_data,
+            samples: sample_positions(self.sample_count),
+        };
+        raster::rasterize_triangle(&mut target, triangle, &textures, fog, depth_test);
         Ok(())
     }
-}
\ No newline at end of file
+}
+/// Moyenne chaque bloc de `scale`x`scale` pixels de `rgba` (largeur `width`)
+/// dans un pixel de `out`
+pub fn downsample(rgba: &[u8], width: u32, scale: u32, out: &mut [u8]) {
+    let (width, scale) = (width as usize, scale.max(1) as usize);
+    let native_width = width / scale;
+    let count = (scale * scale) as u32;
+    for (index, pixel) in out.chunks_exact_mut(4).enumerate() {
+        let (x, y) = ((index % native_width) * scale, (index / native_width) * scale);
+        let mut sum = [0u32; 4];
+        for row in rgba.chunks_exact(width * 4).skip(y).take(scale) {
+            for texel in row[x * 4..(x + scale) * 4].chunks_exact(4) {
+                for (total, &value) in sum.iter_mut().zip(texel) {
+                    *total += value as u32;
+                }
+            }
+        }
+        for (value, total) in pixel.iter_mut().zip(sum) {
+            *value = ((total + count / 2) / count) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_readback_of_scaled_frame() {
+        let mut framebuffer = Framebuffer::new(4, 2);
+        framebuffer.set_scale((4, 2), 2);
+        assert_eq!((framebuffer.width, framebuffer.height), (8, 4));
+        assert_eq!(framebuffer.native_size(), (4, 2));
+
+        // Le changement d'échantillonnage garde la résolution interne
+        framebuffer.set_sample_count(4);
+        assert_eq!((framebuffer.width, framebuffer.scale), (8, 2));
+
+        // Premier bloc 2x2 : deux pixels blancs, deux noirs
+        for (x, y) in [(0, 0), (1, 1)] {
+            framebuffer.color_data[(y * 8 + x) * 4..][..4].copy_from_slice(&[255, 255, 255, 255]);
+        }
+        let native = framebuffer.native_color_data();
+        assert_eq!(native.len(), 4 * 2 * 4);
+        assert_eq!(&native[..8], &[128, 128, 128, 128, 0, 0, 0, 0]);
+
+        framebuffer.set_scale((4, 2), 1);
+        assert_eq!(framebuffer.native_color_data().len(), 4 * 2 * 4);
+    }
+}
@@ -4,7 +4,11 @@
 //! incluant les matrices de transformation, projection et clipping optimisés.
 
 use glam::{Vec3, Vec4, Mat4, Vec4Swizzles};
-use anyhow::Result;
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+use super::fixed::FixedMat4;
+use super::fog::{FogTable, PixelFog};
 
 /// Triangle 3D avec tous les attributs Model 2
 #[derive(Debug, Clone)]
@@ -23,6 +27,9 @@ pub struct Vertex3D {
     pub tex_coords: [f32; 2],
     pub color: [f32; 4],
     pub fog_coord: f32,
+    /// Terme ambiant ajouté à l'éclairage diffus avant texturage
+    pub ambient: [f32; 3],
+    /// Reflet ajouté après texturage
     pub specular: [f32; 3],
 }
 
@@ -35,6 +42,96 @@ pub struct TriangleFlags {
     pub wireframe: bool,
     pub flat_shading: bool,
     pub texture_filtering: bool,
+    /// Adressage horizontal de la texture hors de [0, 1]
+    pub wrap_u: TextureWrap,
+    /// Adressage vertical de la texture hors de [0, 1]
+    pub wrap_v: TextureWrap,
+    /// Échelle appliquée aux coordonnées de texture du polygone
+    pub uv_scale: [f32; 2],
+    /// Microtexture de détail superposée à la texture (Model 2B)
+    pub microtexture: Option<u8>,
+}
+
+/// Bits du mot d'attributs de texture d'un polygone
+pub mod texture_attributes {
+    /// Texture étirée horizontalement au lieu d'être répétée
+    pub const CLAMP_U: u32 = 1 << 0;
+    /// Texture étirée verticalement au lieu d'être répétée
+    pub const CLAMP_V: u32 = 1 << 1;
+    /// Répétition en miroir horizontale
+    pub const MIRROR_U: u32 = 1 << 2;
+    /// Répétition en miroir verticale
+    pub const MIRROR_V: u32 = 1 << 3;
+    /// Microtexture activée
+    pub const MICROTEXTURE: u32 = 1 << 4;
+    /// Numéro de la microtexture (bits 5 à 7)
+    pub const MICROTEXTURE_SHIFT: u32 = 5;
+    pub const MICROTEXTURE_MASK: u32 = 0x7;
+}
+
+impl TriangleFlags {
+    /// Applique le mot d'attributs de texture lu dans la ROM de géométrie
+    pub fn with_texture_attributes(mut self, attributes: u32) -> Self {
+        use texture_attributes::*;
+
+        let wrap = |clamp: u32, mirror: u32| {
+            if attributes & mirror != 0 {
+                TextureWrap::Mirror
+            } else if attributes & clamp != 0 {
+                TextureWrap::Clamp
+            } else {
+                TextureWrap::Repeat
+            }
+        };
+        self.wrap_u = wrap(CLAMP_U, MIRROR_U);
+        self.wrap_v = wrap(CLAMP_V, MIRROR_V);
+        self.microtexture = (attributes & MICROTEXTURE != 0)
+            .then_some(((attributes >> MICROTEXTURE_SHIFT) & MICROTEXTURE_MASK) as u8);
+        self
+    }
+}
+
+/// Adressage de texture par polygone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextureWrap {
+    /// La texture se répète (route, bas-côtés)
+    #[default]
+    Repeat,
+    /// Les texels du bord s'étirent (ciel, panneaux)
+    Clamp,
+    /// La texture se répète en miroir une fois sur deux
+    Mirror,
+}
+
+impl TextureWrap {
+    /// Index du texel pour une coordonnée normalisée, sur `size` texels
+    pub fn texel_index(self, coord: f32, size: u32) -> usize {
+        self.wrap_index((coord * size.max(1) as f32).floor() as i64, size)
+    }
+
+    /// Ramène un index de texel quelconque dans la texture
+    pub fn wrap_index(self, index: i64, size: u32) -> usize {
+        let size = size.max(1) as i64;
+        let wrapped = match self {
+            TextureWrap::Repeat => index.rem_euclid(size),
+            TextureWrap::Clamp => index.clamp(0, size - 1),
+            TextureWrap::Mirror => {
+                let folded = index.rem_euclid(size * 2);
+                if folded < size { folded } else { size * 2 - 1 - folded }
+            }
+        };
+        wrapped as usize
+    }
+
+    /// Mode d'adressage wgpu équivalent
+    #[cfg(feature = "gui")]
+    pub fn address_mode(self) -> wgpu::AddressMode {
+        match self {
+            TextureWrap::Repeat => wgpu::AddressMode::Repeat,
+            TextureWrap::Clamp => wgpu::AddressMode::ClampToEdge,
+            TextureWrap::Mirror => wgpu::AddressMode::MirrorRepeat,
+        }
+    }
 }
 
 /// Modèle 3D complet avec LOD
@@ -105,6 +202,25 @@ pub struct GeometryProcessor {
     pub fog_start: f32,
     pub fog_end: f32,
     pub fog_color: [f32; 4],
+    /// Table de brouillard du jeu ; sans elle, le brouillard reste linéaire
+    /// entre `fog_start` et `fog_end`
+    pub fog_table: Option<FogTable>,
+
+    /// Transformation des sommets en virgule fixe 1.15.16, avec la
+    /// précision du TGP d'origine
+    pub fixed_point: bool,
+
+    /// Compteurs depuis le lancement
+    pub stats: GeometryStats,
+}
+
+/// Compteurs du pipeline de géométrie
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct GeometryStats {
+    pub triangles_transformed: u64,
+    pub vertices_transformed: u64,
+    /// Triangles en attente de tri par priorité dans la frame courante
+    pub queued_for_sorting: usize,
 }
 
 /// Triangle transformé en clip space
@@ -124,6 +240,7 @@ pub struct TransformedVertex {
     pub world_normal: Vec3,
     pub tex_coords: [f32; 2],
     pub color: [f32; 4],
+    pub ambient: [f32; 3],
     pub specular: [f32; 3],
     pub fog_factor: f32,
 }
@@ -145,6 +262,7 @@ pub struct ScreenVertex {
     pub world_normal: Vec3,
     pub tex_coords: [f32; 2],
     pub color: [f32; 4],
+    pub ambient: [f32; 3],
     pub specular: [f32; 3],
     pub fog_factor: f32,
     pub depth: f32, // Pour Z-buffer
@@ -159,6 +277,10 @@ impl Default for TriangleFlags {
             wireframe: false,
             flat_shading: false,
             texture_filtering: true,
+            wrap_u: TextureWrap::Repeat,
+            wrap_v: TextureWrap::Repeat,
+            uv_scale: [1.0, 1.0],
+            microtexture: None,
         }
     }
 }
@@ -171,6 +293,7 @@ impl Default for TransformedVertex {
             world_normal: Vec3::Y,
             tex_coords: [0.0, 0.0],
             color: [1.0, 1.0, 1.0, 1.0],
+            ambient: [0.0, 0.0, 0.0],
             specular: [0.0, 0.0, 0.0],
             fog_factor: 0.0,
         }
@@ -185,6 +308,7 @@ impl Default for ScreenVertex {
             world_normal: Vec3::Y,
             tex_coords: [0.0, 0.0],
             color: [1.0, 1.0, 1.0, 1.0],
+            ambient: [0.0, 0.0, 0.0],
             specular: [0.0, 0.0, 0.0],
             fog_factor: 0.0,
             depth: 0.0,
@@ -200,6 +324,7 @@ impl Default for Vertex3D {
             tex_coords: [0.0, 0.0],
             color: [1.0, 1.0, 1.0, 1.0],
             fog_coord: 0.0,
+            ambient: [0.0, 0.0, 0.0],
             specular: [0.0, 0.0, 0.0],
         }
     }
@@ -290,6 +415,9 @@ impl GeometryProcessor {
             fog_start: 10.0,
             fog_end: 100.0,
             fog_color: [0.7, 0.7, 0.9, 1.0], // Bleu clair
+            fog_table: None,
+            fixed_point: false,
+            stats: GeometryStats::default(),
         }
     }
     
@@ -367,12 +495,17 @@ impl GeometryProcessor {
     pub fn transform_triangle(&mut self, triangle: &Triangle3D) -> Result<TransformedTriangle> {
         let mvp_matrix = self.get_mvp_matrix();
         let normal_matrix = self.get_normal_matrix();
+        let fixed = self.fixed_point.then(|| (FixedMat4::from_mat4(&mvp_matrix), FixedMat4::from_mat4(&self.model_matrix)));
         
         let mut transformed_vertices = [TransformedVertex::default(); 3];
         
         for (i, vertex) in triangle.vertices.iter().enumerate() {
             // Transformation de position (vers clip space)
-            let clip_pos = mvp_matrix * Vec4::new(vertex.position.x, vertex.position.y, vertex.position.z, 1.0);
+            let position = Vec4::new(vertex.position.x, vertex.position.y, vertex.position.z, 1.0);
+            let (clip_pos, world_position) = match &fixed {
+                Some((mvp, model)) => (mvp.transform(position), model.transform(position).xyz()),
+                None => (mvp_matrix * position, (self.model_matrix * position).xyz()),
+            };
             
             // Transformation de normale
             let world_normal = (normal_matrix * Vec4::new(vertex.normal.x, vertex.normal.y, vertex.normal.z, 0.0)).xyz().normalize();
@@ -388,15 +521,22 @@ impl GeometryProcessor {
             
             transformed_vertices[i] = TransformedVertex {
                 clip_position: clip_pos,
-                world_position: (self.model_matrix * Vec4::new(vertex.position.x, vertex.position.y, vertex.position.z, 1.0)).xyz(),
+                world_position,
                 world_normal,
-                tex_coords: vertex.tex_coords,
+                tex_coords: [
+                    vertex.tex_coords[0] * triangle.flags.uv_scale[0],
+                    vertex.tex_coords[1] * triangle.flags.uv_scale[1],
+                ],
                 color: vertex.color,
+                ambient: vertex.ambient,
                 specular: vertex.specular,
                 fog_factor,
             };
         }
         
+        self.stats.triangles_transformed += 1;
+        self.stats.vertices_transformed += 3;
+
         Ok(TransformedTriangle {
             vertices: transformed_vertices,
             texture_id: triangle.texture_id,
@@ -469,6 +609,7 @@ impl GeometryProcessor {
                 world_normal: vertex.world_normal,
                 tex_coords: vertex.tex_coords,
                 color: vertex.color,
+                ambient: vertex.ambient,
                 specular: vertex.specular,
                 fog_factor: vertex.fog_factor,
                 depth: ndc.z, // Depth pour Z-buffer
@@ -490,6 +631,22 @@ impl GeometryProcessor {
         self.fog_end = end;
         self.fog_color = color;
     }
+
+    /// Installe ou retire la table de brouillard
+    pub fn set_fog_table(&mut self, table: Option<FogTable>) {
+        self.fog_table = table;
+    }
+
+    /// Brouillard à appliquer aux pixels : la table s'il y en a une,
+    /// sinon le facteur linéaire calculé aux sommets
+    pub fn pixel_fog(&self) -> PixelFog<'_> {
+        let color = [self.fog_color[0], self.fog_color[1], self.fog_color[2]];
+        match (&self.fog_table, self.fog_enabled) {
+            (_, false) => PixelFog::Off,
+            (Some(table), true) => PixelFog::Table { color, table },
+            (None, true) => PixelFog::Analytic { color },
+        }
+    }
     
     /// Invalide les caches des matrices
     fn invalidate_cache(&mut self) {
@@ -564,6 +721,7 @@ mod tests {
                     tex_coords: [0.0, 0.0],
                     color: [1.0, 0.0, 0.0, 1.0],
                     fog_coord: 0.0,
+                    ambient: [0.0, 0.0, 0.0],
                     specular: [0.0, 0.0, 0.0],
                 },
                 Vertex3D {
@@ -572,6 +730,7 @@ mod tests {
                     tex_coords: [1.0, 0.0],
                     color: [0.0, 1.0, 0.0, 1.0],
                     fog_coord: 0.0,
+                    ambient: [0.0, 0.0, 0.0],
                     specular: [0.0, 0.0, 0.0],
                 },
                 Vertex3D {
@@ -580,6 +739,7 @@ mod tests {
                     tex_coords: [0.5, 1.0],
                     color: [0.0, 0.0, 1.0, 1.0],
                     fog_coord: 0.0,
+                    ambient: [0.0, 0.0, 0.0],
                     specular: [0.0, 0.0, 0.0],
                 },
             ],
@@ -600,6 +760,93 @@ mod tests {
         assert_eq!(transformed.vertices[2].tex_coords, [0.5, 1.0]);
     }
 
+    #[test]
+    fn test_lighting_terms_reach_screen_vertices() {
+        let mut processor = GeometryProcessor::new(800, 600);
+        let vertex = Vertex3D {
+            position: Vec3::new(0.0, 0.0, -5.0),
+            ambient: [0.1, 0.2, 0.3],
+            specular: [0.9, 0.8, 0.7],
+            ..Default::default()
+        };
+        let triangle = Triangle3D {
+            vertices: [vertex; 3],
+            texture_id: None,
+            material_id: 0,
+            flags: TriangleFlags::default(),
+        };
+
+        let transformed = processor.transform_triangle(&triangle).unwrap();
+        assert_eq!(transformed.vertices[0].ambient, [0.1, 0.2, 0.3]);
+        assert_eq!(transformed.vertices[0].specular, [0.9, 0.8, 0.7]);
+
+        let screen = processor.project_to_screen(&transformed);
+        assert_eq!(screen.vertices[2].ambient, [0.1, 0.2, 0.3]);
+        assert_eq!(screen.vertices[2].specular, [0.9, 0.8, 0.7]);
+    }
+
+    #[test]
+    fn test_fixed_point_transform_quantizes_positions() {
+        let vertex = |x: f32, y: f32| Vertex3D { position: Vec3::new(x, y, -2.0), ..Default::default() };
+        let triangle = Triangle3D {
+            vertices: [vertex(-0.123_456, 0.0), vertex(0.987_654, 0.1), vertex(0.0, 0.777_777)],
+            texture_id: None,
+            material_id: 0,
+            flags: TriangleFlags::default(),
+        };
+
+        let mut processor = GeometryProcessor::new(496, 384);
+        let float = processor.transform_triangle(&triangle).unwrap();
+        processor.fixed_point = true;
+        let fixed = processor.transform_triangle(&triangle).unwrap();
+
+        for (f, x) in float.vertices.iter().zip(&fixed.vertices) {
+            assert!((f.clip_position - x.clip_position).abs().max_element() < 1.0e-3);
+            // Coordonnées sur la grille 1/65536 du TGP
+            for value in x.clip_position.to_array() {
+                assert_eq!((value * 65536.0).fract(), 0.0);
+            }
+        }
+        assert_ne!(float.vertices[0].clip_position, fixed.vertices[0].clip_position);
+    }
+
+    #[test]
+    fn test_uv_scale_and_wrap_modes() {
+        let mut processor = GeometryProcessor::new(800, 600);
+        let vertex = Vertex3D { tex_coords: [0.5, 0.25], ..Default::default() };
+        let flags = TriangleFlags { uv_scale: [4.0, 2.0], ..Default::default() };
+        let triangle = Triangle3D { vertices: [vertex; 3], texture_id: None, material_id: 0, flags };
+        let transformed = processor.transform_triangle(&triangle).unwrap();
+        assert_eq!(transformed.vertices[1].tex_coords, [2.0, 0.5]);
+
+        // Texture de 4 texels : coordonnées 1.1 et -0.1
+        assert_eq!(TextureWrap::Repeat.texel_index(1.1, 4), 0);
+        assert_eq!(TextureWrap::Repeat.texel_index(-0.1, 4), 3);
+        assert_eq!(TextureWrap::Clamp.texel_index(1.1, 4), 3);
+        assert_eq!(TextureWrap::Clamp.texel_index(-0.1, 4), 0);
+        assert_eq!(TextureWrap::Mirror.texel_index(1.1, 4), 3);
+        assert_eq!(TextureWrap::Mirror.texel_index(-0.1, 4), 0);
+        assert_eq!(TextureWrap::Mirror.texel_index(2.1, 4), 0);
+    }
+
+    #[test]
+    fn test_texture_attributes() {
+        use texture_attributes::*;
+
+        let flags = TriangleFlags::default().with_texture_attributes(0);
+        assert_eq!((flags.wrap_u, flags.wrap_v, flags.microtexture), (TextureWrap::Repeat, TextureWrap::Repeat, None));
+
+        let attributes = CLAMP_U | CLAMP_V | MIRROR_V | MICROTEXTURE | (5 << MICROTEXTURE_SHIFT);
+        let flags = TriangleFlags::default().with_texture_attributes(attributes);
+        assert_eq!(flags.wrap_u, TextureWrap::Clamp);
+        assert_eq!(flags.wrap_v, TextureWrap::Mirror);
+        assert_eq!(flags.microtexture, Some(5));
+
+        // Numéro présent mais microtexture désactivée
+        let flags = TriangleFlags::default().with_texture_attributes(3 << MICROTEXTURE_SHIFT);
+        assert_eq!(flags.microtexture, None);
+    }
+
     #[test]
     fn test_bounding_box() {
         let mut bbox = BoundingBox::empty();
@@ -622,6 +869,13 @@ mod tests {
         assert_eq!(processor.fog_start, 5.0);
         assert_eq!(processor.fog_end, 50.0);
         assert_eq!(processor.fog_color, [0.5, 0.6, 0.8, 1.0]);
+        assert!(matches!(processor.pixel_fog(), PixelFog::Analytic { .. }));
+
+        // La table du jeu remplace le brouillard linéaire
+        processor.set_fog_table(Some(FogTable::from_bytes(&[0, 255])));
+        assert!(matches!(processor.pixel_fog(), PixelFog::Table { color: [0.5, 0.6, 0.8], .. }));
+        processor.fog_enabled = false;
+        assert!(matches!(processor.pixel_fog(), PixelFog::Off));
     }
 
     #[test]
@@ -6,20 +6,69 @@
 //! - Éclairage Gouraud
 //! - Transparence
 
+#[cfg(feature = "gui")]
 pub mod renderer;
 pub mod geometry;
+pub mod fixed;
 pub mod texture;
+#[cfg(feature = "rom-tools")]
+pub mod texture_dump;
+pub mod debug_state;
 pub mod shaders;
 pub mod framebuffer;
+#[cfg(feature = "gui")]
+pub mod present;
+pub mod output;
+pub mod sorting;
+pub mod raster;
+#[cfg(feature = "gui")]
+pub mod adapter;
+#[cfg(feature = "gui")]
+pub mod software;
+pub mod antialias;
+pub mod overlay;
+#[cfg(feature = "gui")]
+pub mod recovery;
+pub mod fog;
+pub mod compositor;
+pub mod video;
+pub mod frame_blend;
 
-use anyhow::Result;
+use crate::error::Result;
+#[cfg(feature = "gui")]
 use std::sync::Arc;
+use crate::clock::Instant;
 
+use crate::config::{AntiAliasing, MipmapSettings, MAX_INTERNAL_SCALE};
+use crate::profiling::{FrameProfiler, FrameScope, FrameTimings};
+
+#[cfg(feature = "gui")]
 pub use renderer::*;
 pub use geometry::*;
+pub use fixed::*;
 pub use texture::*;
+#[cfg(feature = "rom-tools")]
+pub use texture_dump::*;
+pub use debug_state::*;
 pub use shaders::*;
 pub use framebuffer::*;
+#[cfg(feature = "gui")]
+pub use present::*;
+pub use output::*;
+pub use sorting::*;
+pub use raster::*;
+#[cfg(feature = "gui")]
+pub use adapter::*;
+#[cfg(feature = "gui")]
+pub use software::*;
+pub use antialias::*;
+#[cfg(feature = "gui")]
+pub use recovery::*;
+pub use fog::*;
+pub use compositor::*;
+pub use video::*;
+pub use frame_blend::*;
+pub use overlay::SimpleVertex;
 
 /// Résolutions supportées par le Model 2
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -48,8 +97,8 @@ impl Model2Resolution {
 
 /// Structure principale du GPU Model 2
 pub struct Model2Gpu {
-    /// Rendu moderne utilisant wgpu
-    pub renderer: WgpuRenderer,
+    /// Affichage de l'image : wgpu, logiciel en repli, ou aucun
+    backend: Box<dyn VideoBackend>,
     
     /// Géométrie 3D en cours de traitement
     pub geometry_processor: GeometryProcessor,
@@ -59,6 +108,9 @@ pub struct Model2Gpu {
     
     /// Framebuffer virtuel
     pub framebuffer: Framebuffer,
+
+    /// Plans 2D et priorités des couches, composés avec la 3D en fin d'image
+    pub compositor: Compositor,
     
     /// Résolution courante
     pub resolution: Model2Resolution,
@@ -68,64 +120,350 @@ pub struct Model2Gpu {
     
     /// Configuration de rendu
     pub config: RenderConfig,
+
+    /// Couche d'incrustation de la prochaine image
+    overlay: Vec<SimpleVertex>,
+
+    /// Triangles en attente de tri (mode priorité)
+    sorter: PolygonSorter,
+
+    /// Passe de lissage des contours (anticrénelage FXAA)
+    fxaa: FxaaPass,
+
+    /// Temps passés en géométrie, rastérisation et présentation
+    pub profiler: FrameProfiler,
+
+    /// Mélange des deux dernières frames (cadence `blend`)
+    blender: FrameBlender,
+
+    /// Poids de la dernière frame à la prochaine présentation, et nouvelle
+    /// frame émulée depuis la précédente
+    frame_blend: Option<f32>,
+    new_frame: bool,
 }
 
 impl Model2Gpu {
     /// Crée une nouvelle instance du GPU Model 2
-    pub async fn new(window: Arc<winit::window::Window>) -> Result<Self> {
-        let renderer = WgpuRenderer::new(window).await?;
+    #[cfg(feature = "gui")]
+    pub async fn new(window: Arc<winit::window::Window>, vsync: crate::config::VsyncMode, selection: &AdapterSelection) -> Result<Self> {
+        let backend = WgpuBackend::new(window, vsync, selection).await?;
+        Ok(Self::with_backend(Box::new(backend)))
+    }
+
+    /// Crée un GPU entièrement logiciel, affiché sans carte graphique
+    #[cfg(feature = "gui")]
+    pub fn new_software(window: Arc<winit::window::Window>) -> Result<Self> {
+        Ok(Self::with_backend(Box::new(SoftwareBackend::new(window)?)))
+    }
+
+    /// Crée un GPU sans fenêtre ; l'image reste dans le framebuffer
+    pub fn headless() -> Self {
+        Self::with_backend(Box::new(NullVideoBackend::new()))
+    }
+
+    /// Crée un GPU affiché par `backend`
+    pub fn with_backend(backend: Box<dyn VideoBackend>) -> Self {
         let (width, height) = Model2Resolution::Standard.dimensions();
-        
-        Ok(Self {
+        let mut stats = RenderStats::new();
+        stats.present_latency_ms = backend.present_latency_ms();
+        Self {
+            backend,
             geometry_processor: GeometryProcessor::new(width, height),
-            texture_manager: TextureManager::new(renderer.device.clone(), renderer.queue.clone()),
-            framebuffer: Framebuffer::new(&renderer.device, width, height),
-            renderer,
+            texture_manager: TextureManager::software(),
+            framebuffer: Framebuffer::new(width, height),
+            compositor: Compositor::new(width, height),
             resolution: Model2Resolution::Standard,
-            stats: RenderStats::new(),
+            stats,
             config: RenderConfig::default(),
-        })
+            overlay: Vec::new(),
+            sorter: PolygonSorter::new(),
+            fxaa: FxaaPass::new(),
+            profiler: FrameProfiler::new(),
+            blender: FrameBlender::new(),
+            frame_blend: None,
+            new_frame: false,
+        }
+    }
+
+    /// Affichage de l'image
+    pub fn backend(&self) -> &dyn VideoBackend {
+        self.backend.as_ref()
+    }
+
+    /// Vrai si l'image est affichée sans carte graphique
+    pub fn is_software(&self) -> bool {
+        self.backend.is_software()
     }
     
     /// Redimensionne le GPU pour une nouvelle résolution
     pub fn resize(&mut self, resolution: Model2Resolution) -> Result<()> {
         self.resolution = resolution;
+        self.framebuffer.set_scale(resolution.dimensions(), self.config.internal_scale);
         let (width, height) = resolution.dimensions();
-        self.framebuffer.resize(&self.renderer.device, width, height)?;
-        self.renderer.resize(winit::dpi::PhysicalSize::new(width, height));
+        self.compositor.resize(width, height);
+        self.set_anti_aliasing(self.config.anti_aliasing);
         Ok(())
     }
+
+    /// Rend la 3D à `scale` fois la résolution native (borné à
+    /// [`MAX_INTERNAL_SCALE`]) ; retourne le multiple appliqué. L'image est
+    /// présentée et relue à la taille native
+    pub fn set_internal_scale(&mut self, scale: u32) -> u32 {
+        self.config.internal_scale = scale.clamp(1, MAX_INTERNAL_SCALE);
+        self.framebuffer.set_scale(self.resolution.dimensions(), self.config.internal_scale);
+        // Moins d'échantillons MSAA possibles à haute résolution
+        self.set_anti_aliasing(self.config.anti_aliasing);
+        self.config.internal_scale
+    }
+
+    /// Choisit l'anticrénelage ; retourne le mode effectivement appliqué,
+    /// le MSAA étant limité par la résolution de la cible de rendu
+    pub fn set_anti_aliasing(&mut self, mode: AntiAliasing) -> AntiAliasing {
+        self.config.anti_aliasing = mode;
+        let effective = self.effective_anti_aliasing();
+        if effective != mode {
+            log::warn!("Anticrénelage {} indisponible à cette résolution, {} utilisé", mode.name(), effective.name());
+        }
+        self.framebuffer.set_sample_count(effective.sample_count());
+        effective
+    }
+
+    /// Anticrénelage appliqué aux frames
+    pub fn effective_anti_aliasing(&self) -> AntiAliasing {
+        self.config.anti_aliasing.limited_to(max_sample_count(self.framebuffer.width, self.framebuffer.height))
+    }
+
+    /// Suit la taille de la fenêtre ; l'image y est placée selon la
+    /// transformation de sortie
+    pub fn resize_surface(&mut self, width: u32, height: u32) {
+        self.backend.resize(width, height);
+    }
+
+    /// Change le format, l'échelle ou la rotation de l'image
+    pub fn set_output_transform(&mut self, output: OutputTransform) {
+        self.backend.set_output_transform(output);
+    }
+
+    /// Remet la machine graphique dans son état de démarrage : textures,
+    /// matrices, image et triangles en attente. Les réglages d'affichage et
+    /// la présentation sont conservés.
+    pub fn reset(&mut self) {
+        let (width, height) = self.resolution.dimensions();
+        self.geometry_processor = GeometryProcessor::new(width, height);
+        self.texture_manager.clear();
+        self.framebuffer.clear();
+        self.sorter.clear();
+    }
+
+    /// Change le filtrage des textures et de l'image à chaud
+    pub fn set_texture_filter(&mut self, filter: TextureFilter) {
+        self.config.texture_filter = filter;
+        self.texture_manager.set_filter(filter);
+        self.backend.set_texture_filter(filter);
+        self.upload_textures();
+    }
+
+    /// Change le mode de présentation à chaud ; la latence estimée suit
+    pub fn set_vsync(&mut self, vsync: crate::config::VsyncMode) {
+        self.backend.set_vsync(vsync);
+        self.stats.present_latency_ms = self.backend.present_latency_ms();
+    }
+
+    /// Change les réglages du moniteur (gamma, luminosité, contraste, saturation)
+    pub fn set_color_adjustment(&mut self, color: crate::config::ColorAdjustment) {
+        self.backend.set_color_adjustment(color);
+    }
     
     /// Commence un nouveau frame de rendu
     pub fn begin_frame(&mut self) -> Result<()> {
         self.stats.begin_frame();
         self.framebuffer.clear();
+        self.sorter.clear();
         Ok(())
     }
     
     /// Termine le frame et l'affiche
     pub fn end_frame(&mut self) -> Result<()> {
+        self.flush_sorted_polygons()?;
+
+        // Anticrénelage : moyenne des échantillons, puis lissage FXAA
+        let start = Instant::now();
+        self.framebuffer.resolve();
+        if self.config.anti_aliasing == AntiAliasing::Fxaa {
+            self.fxaa.apply(&mut self.framebuffer.color_data, self.framebuffer.width, self.framebuffer.height);
+        }
+        // Plans 2D et fond par-dessus ou derrière la 3D, après le lissage
+        self.compositor.compose(&mut self.framebuffer);
+        self.profiler.record(FrameScope::Rasterize, start);
+
         // Copier le framebuffer vers la surface
-        self.renderer.render()?;
+        let start = Instant::now();
+        self.present()?;
+        self.profiler.record(FrameScope::Present, start);
         self.stats.end_frame();
         Ok(())
     }
     
+    /// Mélange de frames pour la prochaine présentation : `weight` de la
+    /// dernière frame émulée face à la précédente, `new_frame` si une frame
+    /// a été émulée depuis la présentation précédente. `None` présente la
+    /// dernière frame telle quelle.
+    pub fn set_frame_blend(&mut self, weight: Option<f32>, new_frame: bool) {
+        if weight.is_none() && self.frame_blend.is_some() {
+            self.blender.clear();
+        }
+        self.frame_blend = weight;
+        self.new_frame |= new_frame;
+    }
+
+    /// Confie le framebuffer et l'incrustation au backend, puis suit un
+    /// éventuel changement de device ou de backend
+    fn present(&mut self) -> Result<()> {
+        if !self.overlay.is_empty() {
+            self.backend.draw_batch(&self.overlay);
+        }
+        let result = match self.frame_blend {
+            Some(weight) => {
+                if std::mem::take(&mut self.new_frame) {
+                    self.blender.push(&self.framebuffer.color_data);
+                }
+                let blended = self.blender.blend(weight).len() == self.framebuffer.color_data.len();
+                if blended {
+                    self.blender.swap_output(&mut self.framebuffer.color_data);
+                }
+                let result = self.backend.present_frame(&self.framebuffer);
+                if blended {
+                    self.blender.swap_output(&mut self.framebuffer.color_data);
+                }
+                result
+            }
+            None => self.backend.present_frame(&self.framebuffer),
+        };
+
+        match self.backend.take_change() {
+            Some(BackendChange::DeviceRecreated) => {
+                let textures = self.upload_textures();
+                log::info!("{} texture(s) rechargée(s) sur le nouveau device", textures);
+            }
+            Some(BackendChange::Replaced(backend)) => {
+                log::info!("Affichage repris par le backend {}", backend.name());
+                self.backend = backend;
+                self.upload_textures();
+            }
+            None => {}
+        }
+        self.stats.present_latency_ms = self.backend.present_latency_ms();
+        self.stats.device_recoveries = self.backend.device_recoveries();
+        result
+    }
+
+    /// Rend toutes les textures chargées résidentes sur le backend ; retourne
+    /// leur nombre
+    fn upload_textures(&mut self) -> usize {
+        let mut count = 0;
+        for (id, texture) in self.texture_manager.textures_mut() {
+            self.backend.upload_texture(id, texture);
+            count += 1;
+        }
+        count
+    }
+
+    fn upload_texture(&mut self, id: u32) {
+        if let Some(texture) = self.texture_manager.get_texture_mut(id) {
+            self.backend.upload_texture(id, texture);
+        }
+    }
+
+    /// Remplace la couche d'incrustation (viseurs, cibles de calibration)
+    pub fn set_overlay(&mut self, vertices: Vec<SimpleVertex>) {
+        self.overlay = vertices;
+    }
+
+    /// Rapport largeur / hauteur de la surface d'affichage
+    pub fn surface_aspect(&self) -> f32 {
+        let (width, height) = self.backend.surface_size().unwrap_or_else(|| self.framebuffer.native_size());
+        if height == 0 {
+            1.0
+        } else {
+            width as f32 / height as f32
+        }
+    }
+
     /// Dessine un triangle 3D
     pub fn draw_triangle(&mut self, triangle: &Triangle3D) -> Result<()> {
         // Transformation et projection
+        let start = Instant::now();
         let transformed = self.geometry_processor.transform_triangle(triangle)?;
-        
-        // Rendu du triangle
-        self.framebuffer.rasterize_triangle(&transformed, &self.texture_manager)?;
+        self.profiler.record(FrameScope::Tgp, start);
         
         self.stats.triangles_drawn += 1;
+
+        // Mode priorité : le tracé attend la fin de la frame
+        if self.config.depth_mode == DepthMode::Priority {
+            self.sorter.push(transformed);
+            return Ok(());
+        }
+
+        // Rendu du triangle
+        let start = Instant::now();
+        let fog = self.geometry_processor.pixel_fog();
+        self.framebuffer.rasterize_triangle(&transformed, &self.texture_manager, &fog, self.config.z_buffer_enabled)?;
+        self.profiler.record(FrameScope::Rasterize, start);
+        Ok(())
+    }
+
+    /// Trace les triangles en attente, du plus lointain au plus proche
+    fn flush_sorted_polygons(&mut self) -> Result<()> {
+        if self.sorter.is_empty() {
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let fog = self.geometry_processor.pixel_fog();
+        for triangle in self.sorter.drain_sorted() {
+            self.framebuffer.rasterize_triangle(&triangle, &self.texture_manager, &fog, false)?;
+        }
+        self.profiler.record(FrameScope::Rasterize, start);
         Ok(())
     }
+
+    /// Compteurs de géométrie, file de tri par priorité comprise
+    pub fn geometry_stats(&self) -> GeometryStats {
+        GeometryStats { queued_for_sorting: self.sorter.len(), ..self.geometry_processor.stats }
+    }
+
+    /// Choisit entre Z-buffer et tri des polygones par priorité
+    pub fn set_depth_mode(&mut self, mode: DepthMode) {
+        self.config.depth_mode = mode;
+        self.config.z_buffer_enabled = mode == DepthMode::ZBuffer;
+    }
+    
+    /// Transforme les sommets en virgule fixe, comme le TGP, ou en flottants
+    pub fn set_fixed_point_geometry(&mut self, enabled: bool) {
+        self.geometry_processor.fixed_point = enabled;
+    }
     
+    /// Mipmaps des textures et décalage du niveau de détail
+    pub fn set_mipmaps(&mut self, mipmaps: MipmapSettings) {
+        let regenerate = mipmaps.enabled != self.texture_manager.mipmaps().enabled;
+        self.texture_manager.set_mipmaps(mipmaps);
+        if regenerate {
+            self.upload_textures();
+        }
+    }
+
     /// Charge une texture
     pub fn load_texture(&mut self, id: u32, data: &[u8], width: u32, height: u32) -> Result<()> {
         self.texture_manager.load_texture(id, data, width, height)?;
+        self.upload_texture(id);
+        Ok(())
+    }
+
+    /// Charge une microtexture de détail, référencée par les attributs des
+    /// polygones
+    pub fn load_microtexture(&mut self, index: u8, data: &[u8], width: u32, height: u32) -> Result<()> {
+        self.texture_manager.load_microtexture(index, data, width, height)?;
+        self.upload_texture(microtexture_id(index));
         Ok(())
     }
     
@@ -142,6 +480,7 @@ impl Model2Gpu {
             RenderState::Texturing => self.config.texturing_enabled = enabled,
             RenderState::Lighting => self.config.lighting_enabled = enabled,
             RenderState::Transparency => self.config.transparency_enabled = enabled,
+            RenderState::Fog => self.geometry_processor.fog_enabled = enabled,
         }
     }
     
@@ -158,6 +497,7 @@ pub enum RenderState {
     Texturing,
     Lighting,
     Transparency,
+    Fog,
 }
 
 /// Configuration de rendu
@@ -177,9 +517,19 @@ pub struct RenderConfig {
     
     /// Filtre de texture
     pub texture_filter: TextureFilter,
+
+    /// Z-buffer ou tri des polygones par priorité
+    pub depth_mode: DepthMode,
     
     /// Qualité de rendu
     pub render_quality: RenderQuality,
+
+    /// Anticrénelage demandé ; le MSAA est réduit si la résolution ne le
+    /// permet pas
+    pub anti_aliasing: AntiAliasing,
+
+    /// Multiple de la résolution native pour la rastérisation
+    pub internal_scale: u32,
 }
 
 impl Default for RenderConfig {
@@ -190,17 +540,104 @@ impl Default for RenderConfig {
             lighting_enabled: true,
             transparency_enabled: true,
             texture_filter: TextureFilter::Linear,
+            depth_mode: DepthMode::ZBuffer,
             render_quality: RenderQuality::High,
+            anti_aliasing: AntiAliasing::Off,
+            internal_scale: 1,
         }
     }
 }
 
 /// Types de filtrage de texture
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum TextureFilter {
+    /// Échantillonnage au point : texels nets et tramage visible, comme sur
+    /// les polygones dont le filtrage est désactivé
+    #[default]
     Nearest,
+    /// Filtrage amélioré : interpolation entre texels et entre niveaux de
+    /// mipmap
     Linear,
+    /// Interpolation entre texels uniquement
     Bilinear,
+    /// Filtrage du matériel Model 2 : bilinéaire à poids tronqués sur
+    /// 4 bits, sans mipmap, voir [`raster::TexelSource::sample_filtered`]
+    Authentic,
+}
+
+impl TextureFilter {
+    /// Filtre désigné dans `texture_filtering` (config.toml)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "nearest" | "point" => Some(TextureFilter::Nearest),
+            "linear" => Some(TextureFilter::Linear),
+            "bilinear" => Some(TextureFilter::Bilinear),
+            "authentic" | "model2" => Some(TextureFilter::Authentic),
+            "enhanced" => Some(TextureFilter::Linear),
+            _ => None,
+        }
+    }
+
+    /// Filtre de la configuration, linéaire si le nom est inconnu
+    pub fn from_config(name: &str) -> Self {
+        Self::from_name(name).unwrap_or_else(|| {
+            log::warn!("Filtrage de texture inconnu '{}', repli sur linear", name);
+            TextureFilter::Linear
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TextureFilter::Nearest => "nearest",
+            TextureFilter::Linear => "linear",
+            TextureFilter::Bilinear => "bilinear",
+            TextureFilter::Authentic => "authentic",
+        }
+    }
+
+    /// Filtre suivant (raccourci clavier)
+    pub fn next(self) -> Self {
+        match self {
+            TextureFilter::Nearest => TextureFilter::Authentic,
+            TextureFilter::Authentic => TextureFilter::Linear,
+            TextureFilter::Linear => TextureFilter::Bilinear,
+            TextureFilter::Bilinear => TextureFilter::Nearest,
+        }
+    }
+
+    /// Filtres wgpu (agrandissement, réduction, mipmaps). Le filtrage
+    /// authentique est appliqué par le rastériseur logiciel : l'image qu'il
+    /// produit est présentée au point
+    #[cfg(feature = "gui")]
+    pub fn filter_modes(self) -> (wgpu::FilterMode, wgpu::FilterMode, wgpu::FilterMode) {
+        use wgpu::FilterMode::{Linear, Nearest};
+        match self {
+            TextureFilter::Nearest | TextureFilter::Authentic => (Nearest, Nearest, Nearest),
+            TextureFilter::Linear => (Linear, Linear, Linear),
+            TextureFilter::Bilinear => (Linear, Linear, Nearest),
+        }
+    }
+
+    /// Sampler utilisant ce filtre
+    #[cfg(feature = "gui")]
+    pub fn create_sampler(self, device: &wgpu::Device, address_mode: wgpu::AddressMode) -> wgpu::Sampler {
+        self.create_wrapped_sampler(device, address_mode, address_mode)
+    }
+
+    /// Sampler utilisant ce filtre avec un adressage propre à chaque axe
+    #[cfg(feature = "gui")]
+    pub fn create_wrapped_sampler(self, device: &wgpu::Device, address_u: wgpu::AddressMode, address_v: wgpu::AddressMode) -> wgpu::Sampler {
+        let (mag_filter, min_filter, mipmap_filter) = self.filter_modes();
+        device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: address_u,
+            address_mode_v: address_v,
+            address_mode_w: address_u,
+            mag_filter,
+            min_filter,
+            mipmap_filter,
+            ..Default::default()
+        })
+    }
 }
 
 /// Niveaux de qualité de rendu
@@ -229,9 +666,18 @@ pub struct RenderStats {
     
     /// FPS moyen
     pub average_fps: f32,
+
+    /// Latence de présentation estimée (ms) pour le mode vsync courant
+    pub present_latency_ms: f32,
+
+    /// Devices recréés après une perte de la carte graphique
+    pub device_recoveries: u32,
+
+    /// Répartition du temps de la dernière frame par sous-système
+    pub frame_timings: FrameTimings,
     
     /// Temps de début du frame courant
-    frame_start_time: std::time::Instant,
+    frame_start_time: Instant,
     
     /// Historique des temps de frame
     frame_times: std::collections::VecDeque<u64>,
@@ -245,13 +691,16 @@ impl RenderStats {
             pixels_drawn: 0,
             last_frame_time_us: 0,
             average_fps: 0.0,
-            frame_start_time: std::time::Instant::now(),
+            present_latency_ms: 0.0,
+            device_recoveries: 0,
+            frame_timings: FrameTimings::default(),
+            frame_start_time: Instant::now(),
             frame_times: std::collections::VecDeque::with_capacity(60),
         }
     }
     
     fn begin_frame(&mut self) {
-        self.frame_start_time = std::time::Instant::now();
+        self.frame_start_time = Instant::now();
         self.triangles_drawn = 0;
     }
     
@@ -290,4 +739,26 @@ impl RenderStats {
     pub fn frame_time_ms(&self) -> f32 {
         self.last_frame_time_us as f32 / 1000.0
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_texture_filter_names() {
+        assert_eq!(TextureFilter::from_name("Nearest"), Some(TextureFilter::Nearest));
+        assert_eq!(TextureFilter::from_name("point"), Some(TextureFilter::Nearest));
+        assert_eq!(TextureFilter::from_config("anisotropic"), TextureFilter::Linear);
+
+        assert_eq!(TextureFilter::from_name("enhanced"), Some(TextureFilter::Linear));
+        assert_eq!(TextureFilter::from_name("model2"), Some(TextureFilter::Authentic));
+
+        for filter in [TextureFilter::Nearest, TextureFilter::Linear, TextureFilter::Bilinear, TextureFilter::Authentic] {
+            assert_eq!(TextureFilter::from_name(filter.name()), Some(filter));
+            assert_ne!(filter.next(), filter);
+        }
+        #[cfg(feature = "gui")]
+        assert_eq!(TextureFilter::Nearest.filter_modes().0, wgpu::FilterMode::Nearest);
+    }
+}
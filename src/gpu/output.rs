@@ -0,0 +1,196 @@
+//! Placement de l'image émulée dans la fenêtre (format, échelle, rotation)
+//!
+//! Appliqué par la passe de blit : le framebuffer est dessiné dans un
+//! rectangle de la surface (bandes noires autour) et ses coordonnées de
+//! texture sont tournées pour les écrans verticaux et les bornes cocktail.
+//! Les réglages du moniteur (gamma, luminosité, contraste, saturation) sont
+//! appliqués dans la même passe.
+
+use crate::config::{AspectMode, ColorAdjustment, Rotation, VideoConfig};
+
+/// Uniforme de la passe de blit
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct OutputUniform {
+    /// Rotation des coordonnées de texture, voir [`OutputTransform::uv_matrix`]
+    pub uv_matrix: [f32; 4],
+
+    /// Gamma, luminosité, contraste, saturation
+    pub color: [f32; 4],
+}
+
+impl OutputUniform {
+    pub fn new(transform: &OutputTransform, color: &ColorAdjustment) -> Self {
+        Self {
+            uv_matrix: transform.uv_matrix(),
+            color: [color.gamma, color.brightness, color.contrast, color.saturation],
+        }
+    }
+}
+
+/// Applique les réglages du moniteur à une couleur (même calcul que le
+/// shader de blit)
+pub fn adjust_color(color: &ColorAdjustment, rgb: [f32; 3]) -> [f32; 3] {
+    let contrasted = rgb.map(|c| (c - 0.5) * color.contrast + 0.5 + color.brightness);
+    let luma = 0.299 * contrasted[0] + 0.587 * contrasted[1] + 0.114 * contrasted[2];
+    contrasted.map(|c| {
+        let saturated = luma + (c - luma) * color.saturation;
+        saturated.clamp(0.0, 1.0).powf(1.0 / color.gamma)
+    })
+}
+
+/// Transformation de sortie de l'image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutputTransform {
+    pub aspect: AspectMode,
+    pub rotation: Rotation,
+}
+
+impl OutputTransform {
+    pub fn new(aspect: AspectMode, rotation: Rotation) -> Self {
+        Self { aspect, rotation }
+    }
+
+    pub fn from_config(video: &VideoConfig) -> Self {
+        Self::new(video.aspect, video.rotation)
+    }
+
+    /// Rectangle `[x, y, largeur, hauteur]` occupé par l'image dans la
+    /// surface, en pixels
+    pub fn viewport(&self, source: (u32, u32), surface: (u32, u32)) -> [f32; 4] {
+        let (surface_width, surface_height) = (surface.0 as f32, surface.1 as f32);
+        let (width, height) = if self.rotation.is_quarter_turn() {
+            (source.1 as f32, source.0 as f32)
+        } else {
+            (source.0 as f32, source.1 as f32)
+        };
+        if width <= 0.0 || height <= 0.0 {
+            return [0.0, 0.0, surface_width, surface_height];
+        }
+
+        let (width, height) = match self.aspect {
+            AspectMode::Stretch => (surface_width, surface_height),
+            AspectMode::Ratio4x3 => {
+                let ratio = if self.rotation.is_quarter_turn() { 3.0 / 4.0 } else { 4.0 / 3.0 };
+                fit(ratio, surface_width, surface_height)
+            }
+            AspectMode::Integer => {
+                let scale = (surface_width / width).min(surface_height / height).floor();
+                if scale >= 1.0 {
+                    (width * scale, height * scale)
+                } else {
+                    // Fenêtre plus petite que l'image : réduction sans déformation
+                    fit(width / height, surface_width, surface_height)
+                }
+            }
+        };
+
+        [
+            ((surface_width - width) / 2.0).floor(),
+            ((surface_height - height) / 2.0).floor(),
+            width,
+            height,
+        ]
+    }
+
+    /// Matrice 2x2 `[a, b, c, d]` appliquée aux coordonnées de texture
+    /// centrées de l'écran : `source = (a·x + b·y, c·x + d·y)`
+    pub fn uv_matrix(&self) -> [f32; 4] {
+        match self.rotation {
+            Rotation::None => [1.0, 0.0, 0.0, 1.0],
+            Rotation::Clockwise90 => [0.0, 1.0, -1.0, 0.0],
+            Rotation::Half => [-1.0, 0.0, 0.0, -1.0],
+            Rotation::Clockwise270 => [0.0, -1.0, 1.0, 0.0],
+        }
+    }
+
+    /// Le filtrage au plus proche garde des pixels nets à l'échelle entière
+    pub fn nearest_filtering(&self) -> bool {
+        self.aspect == AspectMode::Integer
+    }
+}
+
+/// Plus grande taille de rapport `ratio` tenant dans la surface
+fn fit(ratio: f32, surface_width: f32, surface_height: f32) -> (f32, f32) {
+    if surface_width / surface_height > ratio {
+        (surface_height * ratio, surface_height)
+    } else {
+        (surface_width, surface_width / ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: (u32, u32) = (496, 384);
+
+    #[test]
+    fn test_ratio_4x3_letterboxes() {
+        let transform = OutputTransform::default();
+        assert_eq!(transform.viewport(SOURCE, (1920, 1080)), [240.0, 0.0, 1440.0, 1080.0]);
+        assert_eq!(transform.viewport(SOURCE, (800, 800)), [0.0, 100.0, 800.0, 600.0]);
+
+        // Écran vertical : l'image tournée est en 3:4
+        let rotated = OutputTransform::new(AspectMode::Ratio4x3, Rotation::Clockwise90);
+        assert_eq!(rotated.viewport(SOURCE, (1080, 1920)), [0.0, 240.0, 1080.0, 1440.0]);
+    }
+
+    #[test]
+    fn test_stretch_fills_surface() {
+        let transform = OutputTransform::new(AspectMode::Stretch, Rotation::Half);
+        assert_eq!(transform.viewport(SOURCE, (1280, 720)), [0.0, 0.0, 1280.0, 720.0]);
+    }
+
+    #[test]
+    fn test_integer_scale() {
+        let transform = OutputTransform::new(AspectMode::Integer, Rotation::None);
+        assert_eq!(transform.viewport(SOURCE, (1920, 1080)), [464.0, 156.0, 992.0, 768.0]);
+        assert!(transform.nearest_filtering());
+
+        let rotated = OutputTransform::new(AspectMode::Integer, Rotation::Clockwise270);
+        assert_eq!(rotated.viewport(SOURCE, (1920, 1080)), [576.0, 44.0, 768.0, 992.0]);
+
+        // Fenêtre trop petite : réduction proportionnelle
+        let viewport = transform.viewport(SOURCE, (248, 400));
+        assert_eq!(viewport[2], 248.0);
+        assert!((viewport[3] - 192.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_color_adjustment() {
+        let neutral = ColorAdjustment::default();
+        let rgb = [0.2, 0.5, 0.8];
+        let adjusted = adjust_color(&neutral, rgb);
+        assert!(adjusted.iter().zip(rgb).all(|(a, b)| (a - b).abs() < 1e-6));
+
+        let brighter = ColorAdjustment { gamma: 2.0, ..neutral };
+        assert!((adjust_color(&brighter, [0.25; 3])[0] - 0.5).abs() < 1e-6);
+
+        let grey = ColorAdjustment { saturation: 0.0, ..neutral };
+        let [r, g, b] = adjust_color(&grey, [1.0, 0.0, 0.0]);
+        assert!((r - 0.299).abs() < 1e-6 && r == g && g == b);
+
+        let dark = ColorAdjustment { brightness: -0.5, contrast: 2.0, ..neutral };
+        let [low, mid, high] = adjust_color(&dark, [0.1, 0.5, 1.0]);
+        assert_eq!((low, mid), (0.0, 0.0));
+        assert!((high - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_uv_rotation() {
+        let apply = |m: [f32; 4], (x, y): (f32, f32)| (m[0] * x + m[1] * y, m[2] * x + m[3] * y);
+        let top_left = (-0.5, -0.5);
+
+        // Après un quart de tour horaire, le coin haut-gauche de l'image
+        // apparaît en haut à droite de l'écran
+        let quarter = OutputTransform::new(AspectMode::Stretch, Rotation::Clockwise90).uv_matrix();
+        assert_eq!(apply(quarter, (0.5, -0.5)), top_left);
+
+        let half = OutputTransform::new(AspectMode::Stretch, Rotation::Half).uv_matrix();
+        assert_eq!(apply(half, (0.5, 0.5)), top_left);
+
+        let three_quarters = OutputTransform::new(AspectMode::Stretch, Rotation::Clockwise270).uv_matrix();
+        assert_eq!(apply(three_quarters, (-0.5, 0.5)), top_left);
+    }
+}
@@ -0,0 +1,406 @@
+//! Couche d'incrustation dessinée par-dessus l'image émulée (viseurs, cibles,
+//! commandes tactiles, lanceur, panneau d'état du GPU)
+//!
+//! Les sommets sont exprimés directement en coordonnées de clip et passent par
+//! le pipeline de triangles simples.
+
+use super::debug_state::WatchedLine;
+use crate::config::TouchConfig;
+use crate::input::PlayerInput;
+
+/// Vertex simple pour le rendu sans textures
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SimpleVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+impl SimpleVertex {
+    pub fn new(x: f32, y: f32, z: f32, r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self {
+            position: [x, y, z],
+            color: [r, g, b, a],
+        }
+    }
+}
+
+/// Épaisseur des branches du viseur, en fraction de la hauteur d'écran
+const CROSSHAIR_THICKNESS: f32 = 0.004;
+
+/// Ajoute un rectangle plein (deux triangles) en coordonnées de clip
+fn push_rect(out: &mut Vec<SimpleVertex>, x0: f32, y0: f32, x1: f32, y1: f32, color: [f32; 4]) {
+    let [r, g, b, a] = color;
+    let v = |x: f32, y: f32| SimpleVertex::new(x, y, 0.0, r, g, b, a);
+
+    out.extend_from_slice(&[v(x0, y0), v(x1, y0), v(x1, y1), v(x0, y0), v(x1, y1), v(x0, y1)]);
+}
+
+/// Convertit une position normalisée (0..1, origine en haut à gauche) en clip
+pub fn to_clip(x: f32, y: f32) -> (f32, f32) {
+    (x * 2.0 - 1.0, 1.0 - y * 2.0)
+}
+
+/// Génère un viseur en croix centré sur une position normalisée.
+///
+/// `size` est la demi-longueur des branches en fraction de la hauteur d'écran,
+/// `aspect` le rapport largeur / hauteur de la surface.
+pub fn crosshair(x: f32, y: f32, size: f32, aspect: f32, color: [f32; 3], out: &mut Vec<SimpleVertex>) {
+    let (cx, cy) = to_clip(x, y);
+    let aspect = if aspect > 0.0 { aspect } else { 1.0 };
+
+    let half_h = size * 2.0;
+    let half_w = half_h / aspect;
+    let thick_h = CROSSHAIR_THICKNESS * 2.0;
+    let thick_w = thick_h / aspect;
+    let color = [color[0], color[1], color[2], 1.0];
+
+    // Branche horizontale puis verticale
+    push_rect(out, cx - half_w, cy - thick_h, cx + half_w, cy + thick_h, color);
+    push_rect(out, cx - thick_w, cy - half_h, cx + thick_w, cy + half_h, color);
+}
+
+/// Génère une cible de calibration (carré creux) centrée sur une position normalisée
+pub fn calibration_target(x: f32, y: f32, aspect: f32, out: &mut Vec<SimpleVertex>) {
+    let (cx, cy) = to_clip(x, y);
+    let aspect = if aspect > 0.0 { aspect } else { 1.0 };
+    let half_h = 0.06;
+    let half_w = half_h / aspect;
+    let t_h = CROSSHAIR_THICKNESS * 2.0;
+    let t_w = t_h / aspect;
+    let white = [1.0, 1.0, 1.0, 1.0];
+
+    push_rect(out, cx - half_w, cy + half_h - t_h, cx + half_w, cy + half_h, white);
+    push_rect(out, cx - half_w, cy - half_h, cx + half_w, cy - half_h + t_h, white);
+    push_rect(out, cx - half_w, cy - half_h, cx - half_w + t_w, cy + half_h, white);
+    push_rect(out, cx + half_w - t_w, cy - half_h, cx + half_w, cy + half_h, white);
+    crosshair(x, y, 0.015, aspect, [1.0, 1.0, 1.0], out);
+}
+
+/// Génère la barre des emplacements d'états en bas de l'écran.
+///
+/// Les emplacements occupés sont gris clair, les vides gris foncé ; le
+/// sélectionné est encadré en jaune.
+pub fn slot_bar(occupied: &[bool], selected: usize, out: &mut Vec<SimpleVertex>) {
+    let count = occupied.len().max(1) as f32;
+    let (left, right, top, bottom) = (-0.9, 0.9, -0.72, -0.88);
+    let step = (right - left) / count;
+    let margin = step * 0.1;
+
+    push_rect(out, left - 0.02, bottom - 0.02, right + 0.02, top + 0.02, [0.0, 0.0, 0.0, 0.7]);
+    for (index, &used) in occupied.iter().enumerate() {
+        let x0 = left + step * index as f32 + margin;
+        let x1 = x0 + step - 2.0 * margin;
+
+        if index == selected {
+            push_rect(out, x0 - 0.01, bottom - 0.015, x1 + 0.01, top + 0.015, [1.0, 0.85, 0.1, 1.0]);
+        }
+        let shade = if used { 0.75 } else { 0.25 };
+        push_rect(out, x0, bottom, x1, top, [shade, shade, shade, 1.0]);
+    }
+}
+
+/// Dessine une image RGBA 8 bits pixel par pixel dans un rectangle normalisé
+/// `[x, y, largeur, hauteur]` (vignettes d'états ; prévu pour de petites images)
+pub fn image(rect: [f32; 4], width: u32, height: u32, rgba: &[u8], out: &mut Vec<SimpleVertex>) {
+    if width == 0 || height == 0 || rgba.len() < (width * height * 4) as usize {
+        return;
+    }
+
+    let [x, y, w, h] = rect;
+    let (cell_w, cell_h) = (w / width as f32, h / height as f32);
+    for py in 0..height {
+        for px in 0..width {
+            let offset = ((py * width + px) * 4) as usize;
+            let color = [
+                rgba[offset] as f32 / 255.0,
+                rgba[offset + 1] as f32 / 255.0,
+                rgba[offset + 2] as f32 / 255.0,
+                1.0,
+            ];
+            let (x0, y0) = to_clip(x + px as f32 * cell_w, y + py as f32 * cell_h);
+            let (x1, y1) = to_clip(x + (px + 1) as f32 * cell_w, y + (py + 1) as f32 * cell_h);
+            push_rect(out, x0, y1, x1, y0, color);
+        }
+    }
+}
+
+/// Génère le panneau des réglages du menu de pause : une jauge par réglage
+/// (valeurs normalisées 0..1), la sélectionnée encadrée en jaune.
+pub fn sliders(values: &[f32], selected: usize, out: &mut Vec<SimpleVertex>) {
+    let (left, right, top) = (-0.5, 0.5, 0.3);
+    let (row, bar) = (0.15, 0.06);
+    let bottom = top - row * values.len() as f32;
+
+    push_rect(out, left - 0.04, bottom - 0.02, right + 0.04, top + 0.04, [0.0, 0.0, 0.0, 0.7]);
+    for (index, &value) in values.iter().enumerate() {
+        let y1 = top - row * index as f32;
+        let y0 = y1 - bar;
+        if index == selected {
+            push_rect(out, left - 0.01, y0 - 0.015, right + 0.01, y1 + 0.015, [1.0, 0.85, 0.1, 1.0]);
+        }
+        push_rect(out, left, y0, right, y1, [0.25, 0.25, 0.25, 1.0]);
+        let filled = left + (right - left) * value.clamp(0.0, 1.0);
+        push_rect(out, left, y0, filled, y1, [0.75, 0.75, 0.75, 1.0]);
+    }
+}
+
+/// Génère les jauges d'horloge des CPU en haut à gauche : une par CPU
+/// (valeurs normalisées 0..1), avec un repère blanc à la fréquence d'origine.
+/// Orange au-dessus de l'origine, bleu en dessous.
+pub fn clock_gauges(values: &[f32], stock: f32, out: &mut Vec<SimpleVertex>) {
+    let (left, right, top) = (-0.97, -0.62, 0.96);
+    let (row, bar) = (0.05, 0.03);
+    let bottom = top - row * values.len() as f32;
+    let marker = left + (right - left) * stock.clamp(0.0, 1.0);
+
+    push_rect(out, left - 0.01, bottom - 0.005, right + 0.01, top + 0.01, [0.0, 0.0, 0.0, 0.6]);
+    for (index, &value) in values.iter().enumerate() {
+        let y1 = top - row * index as f32;
+        let y0 = y1 - bar;
+        let color = if value > stock { [0.95, 0.6, 0.1, 1.0] } else { [0.3, 0.55, 0.95, 1.0] };
+        push_rect(out, left, y0, right, y1, [0.25, 0.25, 0.25, 1.0]);
+        push_rect(out, left, y0, left + (right - left) * value.clamp(0.0, 1.0), y1, color);
+        push_rect(out, marker - 0.003, y0 - 0.005, marker + 0.003, y1 + 0.005, [1.0, 1.0, 1.0, 1.0]);
+    }
+}
+
+/// Génère la carte de la RAM principale en bas de l'écran : une bande
+/// découpée en tranches, d'autant plus claires qu'elles contiennent de
+/// candidats de la recherche mémoire (densités normalisées 0..1)
+pub fn memory_map(density: &[f32], out: &mut Vec<SimpleVertex>) {
+    let (left, right, bottom, top) = (-0.97, 0.97, -0.97, -0.93);
+    let step = (right - left) / density.len().max(1) as f32;
+
+    push_rect(out, left - 0.01, bottom - 0.01, right + 0.01, top + 0.01, [0.0, 0.0, 0.0, 0.6]);
+    for (index, &value) in density.iter().enumerate() {
+        if value <= 0.0 {
+            continue;
+        }
+        let x0 = left + step * index as f32;
+        let level = 0.3 + 0.7 * value.clamp(0.0, 1.0);
+        push_rect(out, x0, bottom, x0 + step, top, [0.2 * level, level, 0.4 * level, 1.0]);
+    }
+}
+
+/// Nombre de segments d'un disque
+const DISC_SEGMENTS: usize = 24;
+
+/// Ajoute un disque centré sur une position normalisée ; `radius` en fraction
+/// de la hauteur d'écran
+fn push_disc(out: &mut Vec<SimpleVertex>, x: f32, y: f32, radius: f32, aspect: f32, color: [f32; 4]) {
+    let (cx, cy) = to_clip(x, y);
+    let (rx, ry) = (radius * 2.0 / aspect, radius * 2.0);
+    let [r, g, b, a] = color;
+    let point = |i: usize| {
+        let angle = i as f32 / DISC_SEGMENTS as f32 * std::f32::consts::TAU;
+        SimpleVertex::new(cx + rx * angle.cos(), cy + ry * angle.sin(), 0.0, r, g, b, a)
+    };
+    for i in 0..DISC_SEGMENTS {
+        out.extend_from_slice(&[SimpleVertex::new(cx, cy, 0.0, r, g, b, a), point(i), point(i + 1)]);
+    }
+}
+
+/// Génère les commandes tactiles : zone de direction et boutons, plus
+/// opaques quand ils sont enfoncés
+pub fn touch_controls(config: &TouchConfig, pressed: &PlayerInput, aspect: f32, out: &mut Vec<SimpleVertex>) {
+    let aspect = if aspect > 0.0 { aspect } else { 1.0 };
+    let opacity = config.opacity.clamp(0.0, 1.0);
+    let shade = |active: bool| {
+        let a = if active { (opacity * 2.0).min(1.0) } else { opacity };
+        [1.0, 1.0, 1.0, a]
+    };
+
+    let stick = config.stick;
+    let steering = pressed.up || pressed.down || pressed.left || pressed.right;
+    push_disc(out, stick.x, stick.y, stick.radius, aspect, shade(steering));
+    push_disc(out, stick.x, stick.y, stick.radius * stick.dead_zone, aspect, shade(false));
+    if let Some(wheel) = config.steering {
+        let (x0, y0) = to_clip(wheel.x - wheel.width / 2.0, wheel.y - wheel.height / 2.0);
+        let (x1, y1) = to_clip(wheel.x + wheel.width / 2.0, wheel.y + wheel.height / 2.0);
+        push_rect(out, x0, y0, x1, y1, shade(pressed.left || pressed.right));
+        let dead = wheel.width / 2.0 * wheel.dead_zone;
+        let (x0, _) = to_clip(wheel.x - dead, 0.0);
+        let (x1, _) = to_clip(wheel.x + dead, 0.0);
+        push_rect(out, x0, y0, x1, y1, shade(false));
+    }
+    for button in &config.buttons {
+        push_disc(out, button.x, button.y, button.radius, aspect, shade(pressed.is_pressed(button.button)));
+    }
+}
+
+/// Colonnes de la grille du lanceur
+pub const GRID_COLUMNS: usize = 4;
+
+/// Lignes de la grille du lanceur visibles à la fois
+pub const GRID_ROWS: usize = 2;
+
+/// Vignette d'un jeu dans la grille du lanceur
+#[derive(Debug, Clone, Copy)]
+pub struct GameTile<'a> {
+    /// Jaquette RGBA 8 bits (largeur, hauteur, pixels) ; à défaut, un
+    /// aplat de `placeholder`
+    pub art: Option<(u32, u32, &'a [u8])>,
+    pub placeholder: [f32; 3],
+
+    /// Couleur de l'état du jeu, en bandeau sous la vignette
+    pub status: [f32; 3],
+
+    /// ROMs complètes ; sinon la vignette est assombrie
+    pub available: bool,
+}
+
+/// Hauteur d'un point de la police de la description du lanceur
+const GRID_DETAILS_PIXEL: f32 = 0.0025;
+
+/// Génère la grille du lanceur sur un fond sombre : une vignette par jeu et
+/// son bandeau d'état, la sélectionnée encadrée en jaune. Seule la page
+/// contenant la sélection est dessinée ; `details`, la description de la
+/// sélection, est écrit au-dessus de la grille (les lignes en trop sont
+/// coupées).
+pub fn game_grid(tiles: &[GameTile], selected: usize, details: &[String], aspect: f32, out: &mut Vec<SimpleVertex>) {
+    push_rect(out, -1.0, -1.0, 1.0, 1.0, [0.05, 0.05, 0.08, 0.92]);
+
+    let line_height = GRID_DETAILS_PIXEL * 7.0;
+    let lines = ((0.1 - 0.02) / line_height) as usize;
+    for (index, line) in details.iter().take(lines).enumerate() {
+        let color = if index == 0 { [1.0, 1.0, 1.0, 1.0] } else { [0.75, 0.75, 0.8, 1.0] };
+        text(0.1, 0.015 + line_height * index as f32, GRID_DETAILS_PIXEL, aspect, line, color, out);
+    }
+
+    let page_size = GRID_COLUMNS * GRID_ROWS;
+    let first = selected / page_size * page_size;
+    let (cell_w, cell_h) = (0.8 / GRID_COLUMNS as f32, 0.8 / GRID_ROWS as f32);
+    let (tile_w, art_h, strip_h) = (cell_w * 0.8, cell_h * 0.72, cell_h * 0.06);
+
+    for (index, tile) in tiles.iter().enumerate().skip(first).take(page_size) {
+        let slot = index - first;
+        let x = 0.1 + (slot % GRID_COLUMNS) as f32 * cell_w + (cell_w - tile_w) / 2.0;
+        let y = 0.1 + (slot / GRID_COLUMNS) as f32 * cell_h + cell_h * 0.08;
+
+        if index == selected {
+            let (x0, y0) = to_clip(x - 0.01, y - 0.01);
+            let (x1, y1) = to_clip(x + tile_w + 0.01, y + art_h + strip_h + 0.01);
+            push_rect(out, x0, y1, x1, y0, [1.0, 0.85, 0.1, 1.0]);
+        }
+
+        let (x0, y0) = to_clip(x, y);
+        let (x1, y1) = to_clip(x + tile_w, y + art_h);
+        match tile.art {
+            Some((width, height, rgba)) => image([x, y, tile_w, art_h], width, height, rgba, out),
+            None => {
+                let [r, g, b] = tile.placeholder;
+                push_rect(out, x0, y1, x1, y0, [r, g, b, 1.0]);
+            }
+        }
+        if !tile.available {
+            push_rect(out, x0, y1, x1, y0, [0.0, 0.0, 0.0, 0.6]);
+        }
+
+        let (_, strip_top) = to_clip(x, y + art_h);
+        let (_, strip_bottom) = to_clip(x, y + art_h + strip_h);
+        let [r, g, b] = tile.status;
+        push_rect(out, x0, strip_bottom, x1, strip_top, [r, g, b, 1.0]);
+    }
+}
+
+/// Police 3x5 : une ligne de 3 bits par rangée, de haut en bas
+fn glyph(c: char) -> u16 {
+    // Les accents sont ignorés : la police n'a que des majuscules
+    let c = match c {
+        'à' | 'â' | 'ä' => 'A',
+        'é' | 'è' | 'ê' | 'ë' => 'E',
+        'î' | 'ï' => 'I',
+        'ô' | 'ö' => 'O',
+        'ù' | 'û' | 'ü' => 'U',
+        'ç' => 'C',
+        c => c.to_ascii_uppercase(),
+    };
+    match c {
+        '0' => 0b111_101_101_101_111,
+        '1' => 0b010_110_010_010_111,
+        '2' => 0b111_001_111_100_111,
+        '3' => 0b111_001_111_001_111,
+        '4' => 0b101_101_111_001_001,
+        '5' => 0b111_100_111_001_111,
+        '6' => 0b111_100_111_101_111,
+        '7' => 0b111_001_001_001_001,
+        '8' => 0b111_101_111_101_111,
+        '9' => 0b111_101_111_001_111,
+        'A' => 0b010_101_111_101_101,
+        'B' => 0b110_101_110_101_110,
+        'C' => 0b011_100_100_100_011,
+        'D' => 0b110_101_101_101_110,
+        'E' => 0b111_100_110_100_111,
+        'F' => 0b111_100_110_100_100,
+        'G' => 0b011_100_101_101_011,
+        'H' => 0b101_101_111_101_101,
+        'I' => 0b111_010_010_010_111,
+        'J' => 0b001_001_001_101_010,
+        'K' => 0b101_101_110_101_101,
+        'L' => 0b100_100_100_100_111,
+        'M' => 0b101_111_111_101_101,
+        'N' => 0b110_101_101_101_101,
+        'O' => 0b010_101_101_101_010,
+        'P' => 0b110_101_110_100_100,
+        'Q' => 0b010_101_101_110_011,
+        'R' => 0b110_101_110_101_101,
+        'S' => 0b011_100_010_001_110,
+        'T' => 0b111_010_010_010_010,
+        'U' => 0b101_101_101_101_111,
+        'V' => 0b101_101_101_101_010,
+        'W' => 0b101_101_111_111_101,
+        'X' => 0b101_101_010_101_101,
+        'Y' => 0b101_101_010_010_010,
+        'Z' => 0b111_001_010_100_111,
+        '.' => 0b000_000_000_000_010,
+        ',' => 0b000_000_000_010_100,
+        '-' => 0b000_000_111_000_000,
+        '+' => 0b000_010_111_010_000,
+        ':' => 0b000_010_000_010_000,
+        '=' => 0b000_111_000_111_000,
+        '/' => 0b001_001_010_100_100,
+        '>' => 0b100_010_001_010_100,
+        '<' => 0b001_010_100_010_001,
+        '(' => 0b010_100_100_100_010,
+        ')' => 0b010_001_001_001_010,
+        _ => 0,
+    }
+}
+
+/// Écrit une ligne de texte à partir d'une position normalisée (coin haut
+/// gauche) ; `pixel` est la hauteur d'un point de la police en fraction de
+/// la hauteur d'écran
+pub fn text(x: f32, y: f32, pixel: f32, aspect: f32, line: &str, color: [f32; 4], out: &mut Vec<SimpleVertex>) {
+    let pixel_w = pixel / if aspect > 0.0 { aspect } else { 1.0 };
+    for (column, c) in line.chars().enumerate() {
+        let bits = glyph(c);
+        let left = x + column as f32 * 4.0 * pixel_w;
+        for bit in (0..15).filter(|bit| bits & (1 << (14 - bit)) != 0) {
+            let (x0, y0) = to_clip(left + (bit % 3) as f32 * pixel_w, y + (bit / 3) as f32 * pixel);
+            let (x1, y1) = to_clip(left + (bit % 3 + 1) as f32 * pixel_w, y + (bit / 3 + 1) as f32 * pixel);
+            push_rect(out, x0, y1, x1, y0, color);
+        }
+    }
+}
+
+/// Hauteur d'un point de la police du panneau d'état du GPU
+const STATE_PANEL_PIXEL: f32 = 0.0035;
+
+/// Génère le panneau d'état du GPU en haut à gauche : une ligne de texte
+/// par entrée, les lignes modifiées récemment en jaune, estompé vers le blanc
+pub fn state_panel(lines: &[WatchedLine], aspect: f32, out: &mut Vec<SimpleVertex>) {
+    let line_height = STATE_PANEL_PIXEL * 7.0;
+    let columns = lines.iter().map(|line| line.text.chars().count()).max().unwrap_or(0);
+    let width = columns as f32 * 4.0 * STATE_PANEL_PIXEL / if aspect > 0.0 { aspect } else { 1.0 };
+    let (left, top) = (0.02, 0.02);
+
+    let (x0, y0) = to_clip(left - 0.01, top - 0.01);
+    let (x1, y1) = to_clip(left + width + 0.01, top + line_height * lines.len() as f32 + 0.01);
+    push_rect(out, x0, y1, x1, y0, [0.0, 0.0, 0.0, 0.7]);
+    for (index, line) in lines.iter().enumerate() {
+        let highlight = line.highlight.clamp(0.0, 1.0);
+        let color = [1.0, 1.0, 1.0 - 0.9 * highlight, 1.0];
+        text(left, top + line_height * index as f32, STATE_PANEL_PIXEL, aspect, &line.text, color, out);
+    }
+}
@@ -0,0 +1,99 @@
+//! Sélection du mode de présentation et estimation de la latence d'affichage
+
+use wgpu::PresentMode;
+
+use crate::config::VsyncMode;
+
+/// Paramètres de présentation retenus pour la surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresentSettings {
+    /// Mode demandé dans la configuration
+    pub requested: VsyncMode,
+
+    /// Mode wgpu effectivement utilisé
+    pub present_mode: PresentMode,
+
+    /// Nombre maximal d'images en vol
+    pub frame_latency: u32,
+
+    /// Attendre la fin du rendu GPU après chaque présentation
+    pub wait_for_gpu: bool,
+}
+
+impl PresentSettings {
+    /// Choisit le mode de présentation parmi ceux supportés par la surface.
+    ///
+    /// `Fifo` est toujours disponible et sert de repli.
+    pub fn select(requested: VsyncMode, supported: &[PresentMode]) -> Self {
+        let wanted = match requested {
+            VsyncMode::Fifo | VsyncMode::LowLatency => PresentMode::Fifo,
+            VsyncMode::Mailbox => PresentMode::Mailbox,
+            VsyncMode::Immediate => PresentMode::Immediate,
+        };
+
+        let present_mode = if supported.contains(&wanted) {
+            wanted
+        } else {
+            log::warn!("Mode de présentation {:?} non supporté, repli sur Fifo", wanted);
+            PresentMode::Fifo
+        };
+
+        let low_latency = requested == VsyncMode::LowLatency;
+
+        Self {
+            requested,
+            present_mode,
+            frame_latency: if low_latency { 1 } else { 2 },
+            wait_for_gpu: low_latency,
+        }
+    }
+
+    /// Estime la latence entre la soumission d'une image et son affichage (ms)
+    pub fn estimated_latency_ms(&self, refresh_hz: f32) -> f32 {
+        let period = if refresh_hz > 0.0 { 1000.0 / refresh_hz } else { 1000.0 / 60.0 };
+
+        match self.present_mode {
+            // Chaque image en file attend un balayage complet
+            PresentMode::Fifo | PresentMode::FifoRelaxed => {
+                self.frame_latency as f32 * period + period * 0.5
+            }
+            // L'image la plus récente remplace celle en attente
+            PresentMode::Mailbox => period,
+            // Affichée dès la soumission, en moyenne à mi-balayage
+            _ => period * 0.5,
+        }
+    }
+}
+
+impl Default for PresentSettings {
+    fn default() -> Self {
+        Self::select(VsyncMode::default(), &[PresentMode::Fifo])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_to_fifo() {
+        let settings = PresentSettings::select(VsyncMode::Mailbox, &[PresentMode::Fifo]);
+        assert_eq!(settings.present_mode, PresentMode::Fifo);
+
+        let settings = PresentSettings::select(
+            VsyncMode::Immediate,
+            &[PresentMode::Fifo, PresentMode::Immediate],
+        );
+        assert_eq!(settings.present_mode, PresentMode::Immediate);
+    }
+
+    #[test]
+    fn test_low_latency_reduces_estimate() {
+        let normal = PresentSettings::select(VsyncMode::Fifo, &[PresentMode::Fifo]);
+        let low = PresentSettings::select(VsyncMode::LowLatency, &[PresentMode::Fifo]);
+
+        assert!(low.wait_for_gpu);
+        assert_eq!(low.frame_latency, 1);
+        assert!(low.estimated_latency_ms(60.0) < normal.estimated_latency_ms(60.0));
+    }
+}
@@ -0,0 +1,561 @@
+//! Rastérisation logicielle des triangles
+//!
+//! Reproduit la combinaison de couleur du Model 2 : le texel est modulé par
+//! l'éclairage diffus et ambiant du sommet, puis le reflet spéculaire est
+//! ajouté après texturage. Un reflet reste ainsi visible sur une texture
+//! sombre, comme sur les carrosseries de Daytona USA. Le brouillard est
+//! mélangé en dernier, avant la transparence.
+
+use glam::Vec4Swizzles;
+
+use super::fog::PixelFog;
+use super::geometry::{TextureWrap, TransformedTriangle};
+use super::texture::MipLevel;
+use super::TextureFilter;
+
+/// Pas des poids du filtrage bilinéaire du Model 2 (4 bits de fraction)
+pub const MODEL2_FILTER_STEPS: u32 = 16;
+
+/// Pixels de couleur (RGBA8) et de profondeur sur lesquels tracer
+///
+/// Avec plusieurs positions d'échantillonnage (MSAA), `color` et `depth`
+/// contiennent les échantillons de chaque pixel à la suite.
+pub struct RasterTarget<'a> {
+    pub width: u32,
+    pub height: u32,
+    pub color: &'a mut [u8],
+    pub depth: &'a mut [f32],
+    /// Positions des échantillons dans le pixel, voir [`super::antialias::sample_positions`]
+    pub samples: &'a [[f32; 2]],
+}
+
+/// Texels RGBA8 d'une texture, échantillonnés au plus proche
+#[derive(Debug, Clone, Copy)]
+pub struct TexelSource<'a> {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: &'a [u8],
+}
+
+impl TexelSource<'_> {
+    /// Couleur normalisée aux coordonnées `uv`, adressées hors de [0, 1]
+    /// selon le mode de chaque axe
+    pub fn sample(&self, uv: [f32; 2], wrap: [TextureWrap; 2]) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [1.0; 4];
+        }
+        let x = wrap[0].texel_index(uv[0], self.width);
+        let y = wrap[1].texel_index(uv[1], self.height);
+        self.texel(x, y).map(|c| c as f32 / 255.0)
+    }
+
+    fn texel(&self, x: usize, y: usize) -> [u8; 4] {
+        let offset = (y * self.width as usize + x) * 4;
+        match self.rgba.get(offset..offset + 4) {
+            Some(texel) => [texel[0], texel[1], texel[2], texel[3]],
+            None => [255; 4],
+        }
+    }
+
+    /// Couleur aux coordonnées `uv` avec le filtrage demandé. Le filtrage
+    /// authentique reproduit celui du Model 2 : mélange des 4 texels voisins
+    /// avec des poids tronqués au 1/16 et un calcul entier sur 8 bits ; les
+    /// dégradés y gardent de légers paliers. Les filtres améliorés se
+    /// réduisent ici à un bilinéaire exact, les niveaux de mipmap étant
+    /// choisis par [`TextureStages::sample_base`]
+    pub fn sample_filtered(&self, uv: [f32; 2], wrap: [TextureWrap; 2], filter: TextureFilter) -> [f32; 4] {
+        if filter == TextureFilter::Nearest || self.width == 0 || self.height == 0 {
+            return self.sample(uv, wrap);
+        }
+
+        // Centres des texels sur les entiers
+        let x = uv[0] * self.width as f32 - 0.5;
+        let y = uv[1] * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let columns = [0, 1].map(|i| wrap[0].wrap_index(x0 as i64 + i, self.width));
+        let rows = [0, 1].map(|i| wrap[1].wrap_index(y0 as i64 + i, self.height));
+        let [t00, t10, t01, t11] = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(i, j)| self.texel(columns[i], rows[j]));
+
+        if filter == TextureFilter::Authentic {
+            let steps = MODEL2_FILTER_STEPS;
+            let (wx, wy) = ((fx * steps as f32) as u32, (fy * steps as f32) as u32);
+            let lerp = |a: u8, b: u8, w: u32| (a as u32 * (steps - w) + b as u32 * w) / steps;
+            return [0, 1, 2, 3].map(|c| {
+                let top = lerp(t00[c], t10[c], wx);
+                let bottom = lerp(t01[c], t11[c], wx);
+                ((top * (steps - wy) + bottom * wy) / steps) as f32 / 255.0
+            });
+        }
+
+        let lerp = |a: u8, b: u8, w: f32| a as f32 + (b as f32 - a as f32) * w;
+        [0, 1, 2, 3].map(|c| {
+            let top = lerp(t00[c], t10[c], fx);
+            let bottom = lerp(t01[c], t11[c], fx);
+            (top + (bottom - top) * fy) / 255.0
+        })
+    }
+}
+
+/// Textures d'un polygone : texture principale et microtexture de détail
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextureStages<'a> {
+    pub base: Option<TexelSource<'a>>,
+    pub detail: Option<TexelSource<'a>>,
+    /// Filtrage des polygones dont l'attribut de filtrage est levé
+    pub filter: TextureFilter,
+    /// Mipmaps de la texture principale (niveaux 1 et suivants), vides si
+    /// elles sont désactivées
+    pub mipmaps: &'a [MipLevel],
+    /// Décalage du niveau de détail des polygones filtrés
+    pub lod_bias: f32,
+}
+
+impl TextureStages<'_> {
+    /// Texel de la texture principale au niveau de détail `lod` (log2 du
+    /// nombre de texels du niveau 0 couverts par un pixel). Le filtrage
+    /// linéaire mélange les deux niveaux qui l'encadrent, les autres filtres
+    /// prennent le plus proche ; le filtrage authentique reste au niveau 0
+    pub fn sample_base(&self, uv: [f32; 2], wrap: [TextureWrap; 2], filter: TextureFilter, lod: f32) -> [f32; 4] {
+        let Some(base) = self.base else {
+            return [1.0; 4];
+        };
+        let last = self.mipmaps.len();
+        if last == 0 || lod <= 0.0 || filter == TextureFilter::Authentic {
+            return base.sample_filtered(uv, wrap, filter);
+        }
+
+        let level = |index: usize| match index.min(last) {
+            0 => base,
+            index => self.mipmaps[index - 1].texels(),
+        };
+        let lod = lod.min(last as f32);
+        if filter != TextureFilter::Linear {
+            return level(lod.round() as usize).sample_filtered(uv, wrap, filter);
+        }
+
+        let (index, blend) = (lod.floor() as usize, lod.fract());
+        let fine = level(index).sample_filtered(uv, wrap, filter);
+        if blend == 0.0 {
+            return fine;
+        }
+        let coarse = level(index + 1).sample_filtered(uv, wrap, filter);
+        [0, 1, 2, 3].map(|c| fine[c] + (coarse[c] - fine[c]) * blend)
+    }
+}
+
+/// Nombre de répétitions de la microtexture sur la texture principale
+pub const MICROTEXTURE_SCALE: f32 = 8.0;
+
+/// Module un texel par la microtexture ; le gris moyen est neutre, les
+/// texels plus clairs ou plus sombres font ressortir le grain de la surface
+pub fn apply_microtexture(texel: [f32; 4], detail: [f32; 4]) -> [f32; 4] {
+    [
+        (texel[0] * detail[0] * 2.0).min(1.0),
+        (texel[1] * detail[1] * 2.0).min(1.0),
+        (texel[2] * detail[2] * 2.0).min(1.0),
+        texel[3],
+    ]
+}
+
+/// Combine un texel avec l'éclairage interpolé d'un pixel
+///
+/// `couleur = texel × min(diffus + ambiant, 1) + spéculaire`, saturée à 1 ;
+/// l'alpha ne dépend que du texel et du sommet.
+pub fn shade(texel: [f32; 4], diffuse: [f32; 4], ambient: [f32; 3], specular: [f32; 3]) -> [f32; 4] {
+    let mut out = [0.0; 4];
+    for i in 0..3 {
+        let light = (diffuse[i] + ambient[i]).min(1.0);
+        out[i] = (texel[i] * light + specular[i]).clamp(0.0, 1.0);
+    }
+    out[3] = (texel[3] * diffuse[3]).clamp(0.0, 1.0);
+    out
+}
+
+/// Trace un triangle en clip space ; sans test de profondeur, chaque pixel
+/// recouvre le précédent (mode priorité)
+pub fn rasterize_triangle(
+    target: &mut RasterTarget,
+    triangle: &TransformedTriangle,
+    textures: &TextureStages,
+    fog: &PixelFog,
+    depth_test: bool,
+) {
+    // Pas de découpage contre le plan proche : un sommet derrière la caméra
+    // rejette le triangle
+    if triangle.vertices.iter().any(|v| v.clip_position.w <= f32::EPSILON) {
+        return;
+    }
+
+    let (width, height) = (target.width as f32, target.height as f32);
+    let screen = triangle.vertices.map(|v| {
+        let inv_w = 1.0 / v.clip_position.w;
+        let ndc = v.clip_position.xyz() * inv_w;
+        [(ndc.x * 0.5 + 0.5) * width, (0.5 - ndc.y * 0.5) * height, ndc.z, inv_w]
+    });
+
+    let edge = |a: [f32; 4], b: [f32; 4], x: f32, y: f32| (b[0] - a[0]) * (y - a[1]) - (b[1] - a[1]) * (x - a[0]);
+    let area = edge(screen[0], screen[1], screen[2][0], screen[2][1]);
+    if area.abs() <= f32::EPSILON {
+        return;
+    }
+
+    let min_x = screen.iter().map(|p| p[0]).fold(f32::INFINITY, f32::min).floor().max(0.0) as u32;
+    let max_x = screen.iter().map(|p| p[0]).fold(f32::NEG_INFINITY, f32::max).ceil().min(width) as u32;
+    let min_y = screen.iter().map(|p| p[1]).fold(f32::INFINITY, f32::min).floor().max(0.0) as u32;
+    let max_y = screen.iter().map(|p| p[1]).fold(f32::NEG_INFINITY, f32::max).ceil().min(height) as u32;
+
+    let [v0, v1, v2] = &triangle.vertices;
+    let wrap = [triangle.flags.wrap_u, triangle.flags.wrap_v];
+    let filter = if triangle.flags.texture_filtering { textures.filter } else { TextureFilter::Nearest };
+    let sample_count = target.samples.len().max(1);
+
+    // Niveau de détail d'après l'écart des coordonnées de texture entre
+    // pixels voisins ; les barycentriques varient linéairement à l'écran
+    let base_size = textures.base.filter(|_| triangle.flags.texture_filtering && !textures.mipmaps.is_empty())
+        .map(|base| [base.width as f32, base.height as f32]);
+    let db_dx = [
+        (screen[1][1] - screen[2][1]) / area,
+        (screen[2][1] - screen[0][1]) / area,
+        (screen[0][1] - screen[1][1]) / area,
+    ];
+    let db_dy = [
+        (screen[2][0] - screen[1][0]) / area,
+        (screen[0][0] - screen[2][0]) / area,
+        (screen[1][0] - screen[0][0]) / area,
+    ];
+    let uv_at = |b: [f32; 3]| {
+        let w = [0, 1, 2].map(|i| b[i] * screen[i][3]);
+        let sum = w[0] + w[1] + w[2];
+        [0, 1].map(|i| (v0.tex_coords[i] * w[0] + v1.tex_coords[i] * w[1] + v2.tex_coords[i] * w[2]) / sum)
+    };
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            // Couverture et profondeur de chaque échantillon
+            let pixel_index = (y * target.width + x) as usize;
+            let mut covered = [0.0f32; 8];
+            let mut coverage = 0u8;
+            let mut shading_point = None;
+            for (s, offset) in target.samples.iter().take(8).enumerate() {
+                let (px, py) = (x as f32 + offset[0], y as f32 + offset[1]);
+                let b0 = edge(screen[1], screen[2], px, py) / area;
+                let b1 = edge(screen[2], screen[0], px, py) / area;
+                let b2 = edge(screen[0], screen[1], px, py) / area;
+                if b0 < 0.0 || b1 < 0.0 || b2 < 0.0 {
+                    continue;
+                }
+
+                let z = b0 * screen[0][2] + b1 * screen[1][2] + b2 * screen[2][2];
+                if !(0.0..=1.0).contains(&z) {
+                    continue;
+                }
+                if depth_test && z > target.depth[pixel_index * sample_count + s] {
+                    continue;
+                }
+                covered[s] = z;
+                coverage |= 1 << s;
+                // Couleur évaluée une seule fois, au premier échantillon couvert
+                shading_point.get_or_insert((b0, b1, b2));
+            }
+            let Some((b0, b1, b2)) = shading_point else {
+                continue;
+            };
+
+            // Interpolation corrigée en perspective des attributs
+            let (w0, w1, w2) = (b0 * screen[0][3], b1 * screen[1][3], b2 * screen[2][3]);
+            let sum = w0 + w1 + w2;
+            let lerp = |a: f32, b: f32, c: f32| (a * w0 + b * w1 + c * w2) / sum;
+
+            let uv = [0, 1].map(|i| lerp(v0.tex_coords[i], v1.tex_coords[i], v2.tex_coords[i]));
+            let diffuse = [0, 1, 2, 3].map(|i| lerp(v0.color[i], v1.color[i], v2.color[i]));
+            let ambient = [0, 1, 2].map(|i| lerp(v0.ambient[i], v1.ambient[i], v2.ambient[i]));
+            let specular = [0, 1, 2].map(|i| lerp(v0.specular[i], v1.specular[i], v2.specular[i]));
+            let fog_factor = lerp(v0.fog_factor, v1.fog_factor, v2.fog_factor);
+
+            let lod = base_size.map_or(0.0, |size| {
+                let b = [b0, b1, b2];
+                let footprint = |step: [f32; 3]| {
+                    let next = uv_at([0, 1, 2].map(|i| b[i] + step[i]));
+                    let (du, dv) = ((next[0] - uv[0]) * size[0], (next[1] - uv[1]) * size[1]);
+                    du * du + dv * dv
+                };
+                0.5 * footprint(db_dx).max(footprint(db_dy)).log2() + textures.lod_bias
+            });
+
+            let mut texel = textures.sample_base(uv, wrap, filter, lod);
+            if let Some(detail) = &textures.detail {
+                let detail_uv = uv.map(|c| c * MICROTEXTURE_SCALE);
+                texel = apply_microtexture(texel, detail.sample_filtered(detail_uv, [TextureWrap::Repeat; 2], filter));
+            }
+            let shaded = shade(texel, diffuse, ambient, specular);
+
+            for (s, &z) in covered.iter().enumerate().take(sample_count) {
+                if coverage & (1 << s) == 0 {
+                    continue;
+                }
+                let index = pixel_index * sample_count + s;
+                let pixel = &mut target.color[index * 4..index * 4 + 4];
+                let mut rgba = fog.apply(shaded, fog_factor, z);
+                if triangle.flags.transparent {
+                    let alpha = rgba[3];
+                    for i in 0..3 {
+                        rgba[i] = rgba[i] * alpha + pixel[i] as f32 / 255.0 * (1.0 - alpha);
+                    }
+                    rgba[3] = 1.0;
+                }
+                for (dst, value) in pixel.iter_mut().zip(rgba) {
+                    *dst = (value * 255.0).round() as u8;
+                }
+                if depth_test && !triangle.flags.transparent {
+                    target.depth[index] = z;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::antialias::{resolve, sample_positions};
+    use crate::gpu::fog::{FogTable, FOG_TABLE_SIZE};
+    use crate::gpu::geometry::{TransformedVertex, TriangleFlags};
+    use glam::Vec4;
+
+    const SIZE: u32 = 8;
+    const SINGLE: &[[f32; 2]] = &[[0.5, 0.5]];
+
+    /// Triangle couvrant tout l'écran, à profondeur et éclairage constants
+    fn full_screen(depth: f32, vertex: TransformedVertex) -> TransformedTriangle {
+        let at = |x: f32, y: f32| TransformedVertex { clip_position: Vec4::new(x, y, depth, 1.0), ..vertex };
+        TransformedTriangle {
+            vertices: [at(-1.0, -1.0), at(3.0, -1.0), at(-1.0, 3.0)],
+            texture_id: None,
+            material_id: 0,
+            flags: TriangleFlags::default(),
+        }
+    }
+
+    fn pixel(color: &[u8], x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * SIZE + x) * 4) as usize;
+        color[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_specular_added_after_texture() {
+        // Texel sombre : le reflet n'est pas atténué par la texture
+        let shaded = shade([0.2, 0.2, 0.2, 1.0], [0.5, 0.5, 0.5, 1.0], [0.0; 3], [0.6, 0.6, 0.6]);
+        assert!((shaded[0] - 0.7).abs() < 1e-6);
+
+        // L'ambiant éclaire le texel, la somme est saturée
+        let shaded = shade([0.5, 1.0, 1.0, 0.5], [0.75, 0.0, 0.0, 1.0], [0.5, 0.25, 0.0], [0.0, 0.0, 0.9]);
+        assert_eq!(shaded, [0.5, 0.25, 0.9, 0.5]);
+    }
+
+    #[test]
+    fn test_rasterizes_lit_textured_triangle() {
+        let mut color = vec![0; (SIZE * SIZE * 4) as usize];
+        let mut depth = vec![1.0; (SIZE * SIZE) as usize];
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+        let texels = [64, 64, 64, 255];
+        let texture = TexelSource { width: 1, height: 1, rgba: &texels };
+
+        let vertex = TransformedVertex {
+            color: [0.5, 0.5, 0.5, 1.0],
+            ambient: [0.5, 0.5, 0.5],
+            specular: [0.6, 0.0, 0.0],
+            ..Default::default()
+        };
+        let textures = TextureStages { base: Some(texture), ..Default::default() };
+        rasterize_triangle(&mut target, &full_screen(0.5, vertex), &textures, &PixelFog::Off, true);
+        assert_eq!(pixel(&color, 0, 0), [217, 64, 64, 255]);
+        assert_eq!(pixel(&color, 7, 7), [217, 64, 64, 255]);
+        assert_eq!(depth[0], 0.5);
+    }
+
+    #[test]
+    fn test_depth_test_and_priority_order() {
+        let mut color = vec![0; (SIZE * SIZE * 4) as usize];
+        let mut depth = vec![1.0; (SIZE * SIZE) as usize];
+        let near = full_screen(0.2, TransformedVertex { color: [1.0, 0.0, 0.0, 1.0], ..Default::default() });
+        let far = full_screen(0.8, TransformedVertex { color: [0.0, 0.0, 1.0, 1.0], ..Default::default() });
+
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+        rasterize_triangle(&mut target, &near, &TextureStages::default(), &PixelFog::Off, true);
+        rasterize_triangle(&mut target, &far, &TextureStages::default(), &PixelFog::Off, true);
+        assert_eq!(pixel(&color, 3, 3), [255, 0, 0, 255]);
+
+        // Sans Z-buffer, le dernier tracé l'emporte
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+        rasterize_triangle(&mut target, &far, &TextureStages::default(), &PixelFog::Off, false);
+        assert_eq!(pixel(&color, 3, 3), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_mipmap_level_follows_texel_footprint() {
+        // Damier 32x32 étalé sur 8 pixels : 4 texels par pixel, niveau 2
+        let checker: Vec<u8> = (0..32 * 32).flat_map(|i| if (i % 32 + i / 32) % 2 == 0 { [255; 4] } else { [0, 0, 0, 255] }).collect();
+        let mipmaps = crate::gpu::generate_mipmaps(32, 32, &checker);
+        assert_eq!(mipmaps.len(), 5);
+        assert_eq!(mipmaps[1].texels().sample([0.3, 0.6], [TextureWrap::Repeat; 2]), [128.0 / 255.0, 128.0 / 255.0, 128.0 / 255.0, 1.0]);
+
+        let mut triangle = full_screen(0.5, TransformedVertex { color: [1.0; 4], ..Default::default() });
+        for vertex in triangle.vertices.iter_mut() {
+            vertex.tex_coords = [(vertex.clip_position.x + 1.0) / 2.0, (vertex.clip_position.y + 1.0) / 2.0];
+        }
+        let render = |triangle: &TransformedTriangle, mipmaps: &[MipLevel], lod_bias: f32| {
+            let mut color = vec![0; (SIZE * SIZE * 4) as usize];
+            let mut depth = vec![1.0; (SIZE * SIZE) as usize];
+            let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+            let textures = TextureStages {
+                base: Some(TexelSource { width: 32, height: 32, rgba: &checker }),
+                filter: TextureFilter::Nearest,
+                mipmaps,
+                lod_bias,
+                ..Default::default()
+            };
+            rasterize_triangle(&mut target, triangle, &textures, &PixelFog::Off, true);
+            pixel(&color, 3, 4)
+        };
+
+        // Sans mipmaps (ou ramené au niveau 0), un texel isolé : scintillement
+        assert!(matches!(render(&triangle, &[], 0.0), [0, 0, 0, 255] | [255, 255, 255, 255]));
+        assert!(matches!(render(&triangle, &mipmaps, -2.0), [0, 0, 0, 255] | [255, 255, 255, 255]));
+        assert_eq!(render(&triangle, &mipmaps, 0.0), [128, 128, 128, 255]);
+        // Décalage au-delà du dernier niveau : texture 1x1
+        assert_eq!(render(&triangle, &mipmaps, 10.0), [128, 128, 128, 255]);
+
+        // Polygone sans filtrage : toujours le niveau 0
+        triangle.flags.texture_filtering = false;
+        assert!(matches!(render(&triangle, &mipmaps, 0.0), [0, 0, 0, 255] | [255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_sample_wrap_modes() {
+        // Texture 2x1 : rouge puis bleu
+        let texels = [255, 0, 0, 255, 0, 0, 255, 255];
+        let texture = TexelSource { width: 2, height: 1, rgba: &texels };
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let blue = [0.0, 0.0, 1.0, 1.0];
+
+        assert_eq!(texture.sample([1.25, 0.0], [TextureWrap::Repeat; 2]), red);
+        assert_eq!(texture.sample([1.25, 0.0], [TextureWrap::Clamp; 2]), blue);
+        assert_eq!(texture.sample([1.25, 0.0], [TextureWrap::Mirror; 2]), blue);
+        assert_eq!(texture.sample([-0.25, 0.0], [TextureWrap::Mirror; 2]), red);
+        assert_eq!(texture.sample([-0.25, 0.0], [TextureWrap::Repeat; 2]), blue);
+    }
+
+    #[test]
+    fn test_authentic_filter_truncates_weights() {
+        // Texture 2x1 : noir puis blanc, bords étirés
+        let texels = [0, 0, 0, 255, 255, 255, 255, 255];
+        let texture = TexelSource { width: 2, height: 1, rgba: &texels };
+        let clamp = [TextureWrap::Clamp; 2];
+
+        // Au centre exact d'un texel, aucun mélange
+        assert_eq!(texture.sample_filtered([0.25, 0.5], clamp, TextureFilter::Authentic), [0.0, 0.0, 0.0, 1.0]);
+
+        // À 0.3 texel du noir : 0.3 est tronqué à 4/16
+        let uv = [0.4, 0.5];
+        let authentic = texture.sample_filtered(uv, clamp, TextureFilter::Authentic);
+        assert_eq!((authentic[0] * 255.0).round() as u32, 255 * 4 / 16);
+        let enhanced = texture.sample_filtered(uv, clamp, TextureFilter::Linear);
+        assert!((enhanced[0] - 0.3).abs() < 1e-5);
+        assert_eq!(texture.sample_filtered(uv, clamp, TextureFilter::Nearest), [0.0, 0.0, 0.0, 1.0]);
+
+        // Le filtrage suit le mode d'adressage : la répétition mélange le
+        // dernier texel avec le premier
+        let wrapped = texture.sample_filtered([0.0, 0.5], [TextureWrap::Repeat; 2], TextureFilter::Bilinear);
+        assert!((wrapped[0] - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_microtexture_modulates_base_texture() {
+        assert_eq!(apply_microtexture([0.25, 0.5, 0.75, 0.5], [0.5; 4]), [0.25, 0.5, 0.75, 0.5]);
+        assert_eq!(apply_microtexture([0.25, 0.5, 0.75, 0.5], [1.0, 0.25, 1.0, 0.0]), [0.5, 0.25, 1.0, 0.5]);
+
+        // Microtexture en damier 2x1 : sombre puis clair, répétée sur le polygone
+        let base = [128, 128, 128, 255];
+        let detail = [64, 64, 64, 255, 192, 192, 192, 255];
+        let textures = TextureStages {
+            base: Some(TexelSource { width: 1, height: 1, rgba: &base }),
+            detail: Some(TexelSource { width: 2, height: 1, rgba: &detail }),
+            ..Default::default()
+        };
+        let mut color = vec![0; (SIZE * SIZE * 4) as usize];
+        let mut depth = vec![1.0; (SIZE * SIZE) as usize];
+        let mut triangle = full_screen(0.5, TransformedVertex::default());
+        triangle.vertices[1].tex_coords = [1.0, 0.0];
+        triangle.vertices[2].tex_coords = [0.0, 1.0];
+
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+        rasterize_triangle(&mut target, &triangle, &textures, &PixelFog::Off, true);
+        let shades: Vec<u8> = (0..SIZE).map(|x| pixel(&color, x, 0)[0]).collect();
+        assert_eq!(shades, vec![64, 193, 64, 193, 64, 193, 64, 193]);
+    }
+
+    #[test]
+    fn test_multisample_coverage_on_edges() {
+        // Triangle couvrant la moitié inférieure gauche de l'écran, diagonale
+        // passant par les coins des pixels
+        let samples = sample_positions(4);
+        let mut color = vec![0; (SIZE * SIZE * 4) as usize * samples.len()];
+        let mut depth = vec![1.0; (SIZE * SIZE) as usize * samples.len()];
+        let white = TransformedVertex { color: [1.0; 4], ..Default::default() };
+        let mut triangle = full_screen(0.5, white);
+        triangle.vertices[0].clip_position = Vec4::new(-1.0, 1.0, 0.5, 1.0);
+        triangle.vertices[1].clip_position = Vec4::new(1.0, -1.0, 0.5, 1.0);
+        triangle.vertices[2].clip_position = Vec4::new(-1.0, -1.0, 0.5, 1.0);
+
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples };
+        rasterize_triangle(&mut target, &triangle, &TextureStages::default(), &PixelFog::Off, true);
+
+        let mut resolved = vec![0; (SIZE * SIZE * 4) as usize];
+        resolve(&color, samples.len() as u32, &mut resolved);
+        // Pixel de la diagonale : une partie des échantillons seulement
+        assert_eq!(pixel(&resolved, 3, 3)[0], 128);
+        assert_eq!(pixel(&resolved, 0, 7)[0], 255);
+        assert_eq!(pixel(&resolved, 7, 0)[0], 0);
+    }
+
+    #[test]
+    fn test_rejects_triangle_behind_camera() {
+        let mut color = vec![0; (SIZE * SIZE * 4) as usize];
+        let mut depth = vec![1.0; (SIZE * SIZE) as usize];
+        let mut triangle = full_screen(0.5, TransformedVertex::default());
+        triangle.vertices[1].clip_position.w = -1.0;
+
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+        rasterize_triangle(&mut target, &triangle, &TextureStages::default(), &PixelFog::Off, true);
+        assert!(color.iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn test_fog_table_indexed_by_pixel_depth() {
+        let mut color = vec![0; (SIZE * SIZE * 4) as usize];
+        let mut depth = vec![1.0; (SIZE * SIZE) as usize];
+        let white = TransformedVertex { color: [1.0; 4], fog_factor: 1.0, ..Default::default() };
+
+        // Table nulle jusqu'à mi-profondeur puis à moitié voilée : le facteur
+        // analytique des sommets est ignoré
+        let mut densities = [0u8; FOG_TABLE_SIZE];
+        densities[FOG_TABLE_SIZE / 2..].fill(128);
+        let table = FogTable::from_bytes(&densities);
+        let fog = PixelFog::Table { color: [0.0, 0.0, 1.0], table: &table };
+
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+        rasterize_triangle(&mut target, &full_screen(0.25, white), &TextureStages::default(), &fog, false);
+        assert_eq!(pixel(&color, 0, 0), [255, 255, 255, 255]);
+
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+        rasterize_triangle(&mut target, &full_screen(0.75, white), &TextureStages::default(), &fog, false);
+        assert_eq!(pixel(&color, 0, 0), [127, 127, 255, 255]);
+
+        // Repli analytique : densité interpolée depuis les sommets
+        let fog = PixelFog::Analytic { color: [0.0; 3] };
+        let mut target = RasterTarget { width: SIZE, height: SIZE, color: &mut color, depth: &mut depth, samples: SINGLE };
+        rasterize_triangle(&mut target, &full_screen(0.25, white), &TextureStages::default(), &fog, false);
+        assert_eq!(pixel(&color, 0, 0), [0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,163 @@
+//! Reprise après une erreur de surface ou la perte de la carte graphique
+//!
+//! Une surface perdue ou périmée (changement d'écran, fenêtre modifiée par le
+//! système) est simplement reconfigurée, et une image en retard est sautée.
+//! Un device perdu (pilote réinitialisé, carte débranchée, mémoire épuisée)
+//! est recréé à partir des paramètres d'ouverture du rendu : pipelines,
+//! réglages de sortie, puis textures du jeu rechargées depuis leur copie en
+//! mémoire. L'émulation continue pendant ce temps ; seules les images sont
+//! perdues. Les tentatives sont espacées et limitées par une
+//! [`RecoveryPolicy`] : une fois épuisées, l'affichage passe en logiciel.
+
+use crate::clock::Instant;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Suite à donner à une erreur d'acquisition de l'image de la surface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceRecovery {
+    /// Reconfigurer la surface puis réessayer
+    Reconfigure,
+    /// Sauter l'image : l'écran n'en a pas rendu à temps
+    SkipFrame,
+    /// Recréer le device
+    RecreateDevice,
+}
+
+impl SurfaceRecovery {
+    pub fn for_error(error: &wgpu::SurfaceError) -> Self {
+        match error {
+            wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated => Self::Reconfigure,
+            wgpu::SurfaceError::Timeout => Self::SkipFrame,
+            wgpu::SurfaceError::OutOfMemory => Self::RecreateDevice,
+        }
+    }
+}
+
+/// Relève la perte du device signalée par wgpu, consultée avant chaque image
+#[derive(Debug, Clone, Default)]
+pub struct DeviceLossMonitor {
+    reason: Arc<Mutex<Option<String>>>,
+}
+
+impl DeviceLossMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Surveille un device ; sa destruction par le rendu lui-même (drop,
+    /// remplacement du callback) n'est pas une perte
+    pub fn watch(&self, device: &wgpu::Device) {
+        let reason = Arc::clone(&self.reason);
+        device.set_device_lost_callback(move |cause, message| {
+            if matches!(cause, wgpu::DeviceLostReason::Unknown | wgpu::DeviceLostReason::Destroyed) {
+                *reason.lock() = Some(format!("{:?}: {}", cause, message));
+            }
+        });
+    }
+
+    /// Cause de la perte, si le device est perdu
+    pub fn lost_reason(&self) -> Option<String> {
+        self.reason.lock().clone()
+    }
+}
+
+/// Limite des tentatives de recréation du device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryPolicy {
+    /// Échecs consécutifs tolérés avant le repli logiciel
+    pub max_attempts: u32,
+    /// Délai minimal entre deux tentatives
+    pub retry_interval: Duration,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 3, retry_interval: Duration::from_millis(500) }
+    }
+}
+
+/// Suivi des tentatives de recréation du device
+#[derive(Debug, Clone)]
+pub struct DeviceRecovery {
+    policy: RecoveryPolicy,
+    failures: u32,
+    last_attempt: Option<Instant>,
+    /// Devices recréés avec succès depuis le démarrage
+    recoveries: u32,
+}
+
+impl DeviceRecovery {
+    pub fn new(policy: RecoveryPolicy) -> Self {
+        Self { policy, failures: 0, last_attempt: None, recoveries: 0 }
+    }
+
+    /// Vrai si une tentative est permise à `now`
+    pub fn may_attempt(&self, now: Instant) -> bool {
+        !self.exhausted()
+            && self
+                .last_attempt
+                .is_none_or(|last| now.saturating_duration_since(last) >= self.policy.retry_interval)
+    }
+
+    /// Enregistre le résultat d'une tentative ; un succès remet le compte
+    /// des échecs à zéro
+    pub fn record_attempt(&mut self, now: Instant, success: bool) {
+        self.last_attempt = Some(now);
+        if success {
+            self.failures = 0;
+            self.recoveries += 1;
+        } else {
+            self.failures += 1;
+        }
+    }
+
+    /// Vrai quand toutes les tentatives ont échoué
+    pub fn exhausted(&self) -> bool {
+        self.failures >= self.policy.max_attempts
+    }
+
+    pub fn recoveries(&self) -> u32 {
+        self.recoveries
+    }
+}
+
+impl Default for DeviceRecovery {
+    fn default() -> Self {
+        Self::new(RecoveryPolicy::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_errors_map_to_recovery() {
+        assert_eq!(SurfaceRecovery::for_error(&wgpu::SurfaceError::Lost), SurfaceRecovery::Reconfigure);
+        assert_eq!(SurfaceRecovery::for_error(&wgpu::SurfaceError::Outdated), SurfaceRecovery::Reconfigure);
+        assert_eq!(SurfaceRecovery::for_error(&wgpu::SurfaceError::Timeout), SurfaceRecovery::SkipFrame);
+        assert_eq!(SurfaceRecovery::for_error(&wgpu::SurfaceError::OutOfMemory), SurfaceRecovery::RecreateDevice);
+    }
+
+    #[test]
+    fn test_attempts_are_spaced_and_capped() {
+        let policy = RecoveryPolicy { max_attempts: 2, retry_interval: Duration::from_millis(100) };
+        let mut recovery = DeviceRecovery::new(policy);
+        let start = Instant::now();
+
+        assert!(recovery.may_attempt(start));
+        recovery.record_attempt(start, false);
+        assert!(!recovery.may_attempt(start + Duration::from_millis(50)));
+        assert!(recovery.may_attempt(start + Duration::from_millis(100)));
+
+        // Un succès efface les échecs précédents
+        recovery.record_attempt(start + Duration::from_millis(100), true);
+        assert_eq!(recovery.recoveries(), 1);
+        recovery.record_attempt(start + Duration::from_millis(200), false);
+        recovery.record_attempt(start + Duration::from_millis(300), false);
+        assert!(recovery.exhausted());
+        assert!(!recovery.may_attempt(start + Duration::from_secs(10)));
+    }
+}
@@ -3,25 +3,19 @@
 use wgpu::*;
 use wgpu::util::DeviceExt;
 use winit::window::Window;
-use anyhow::{Result, anyhow};
+use crate::error::{GpuError, Result};
+use parking_lot::Mutex;
 use std::sync::Arc;
 
-/// Vertex simple pour le rendu sans textures
-#[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-pub struct SimpleVertex {
-    pub position: [f32; 3],
-    pub color: [f32; 4],
-}
-
-impl SimpleVertex {
-    pub fn new(x: f32, y: f32, z: f32, r: f32, g: f32, b: f32, a: f32) -> Self {
-        Self {
-            position: [x, y, z],
-            color: [r, g, b, a],
-        }
-    }
-}
+use super::framebuffer::Framebuffer;
+use super::overlay::SimpleVertex;
+use super::output::{OutputTransform, OutputUniform};
+use crate::config::ColorAdjustment;
+use super::present::PresentSettings;
+use super::TextureFilter;
+use super::adapter::AdapterSelection;
+use super::recovery::{DeviceLossMonitor, SurfaceRecovery};
+use crate::config::VsyncMode;
 
 /// Vertex pour le rendu avec textures
 #[repr(C)]
@@ -30,6 +24,10 @@ pub struct TexturedVertex {
     pub position: [f32; 3],
     pub tex_coords: [f32; 2],
     pub color: [f32; 4],
+    pub ambient: [f32; 3],
+    pub specular: [f32; 3],
+    /// 1 si la microtexture module ce sommet, 0 sinon
+    pub detail: f32,
 }
 
 impl TexturedVertex {
@@ -38,8 +36,24 @@ impl TexturedVertex {
             position: [x, y, z],
             tex_coords: [u, v],
             color: [r, g, b, a],
+            ambient: [0.0, 0.0, 0.0],
+            specular: [0.0, 0.0, 0.0],
+            detail: 0.0,
         }
     }
+
+    /// Ajoute l'éclairage ambiant et le reflet spéculaire du sommet
+    pub fn with_lighting(mut self, ambient: [f32; 3], specular: [f32; 3]) -> Self {
+        self.ambient = ambient;
+        self.specular = specular;
+        self
+    }
+
+    /// Active la microtexture de détail sur ce sommet
+    pub fn with_microtexture(mut self, enabled: bool) -> Self {
+        self.detail = if enabled { 1.0 } else { 0.0 };
+        self
+    }
 }
 
 /// Matrices de transformation 3D
@@ -95,6 +109,12 @@ pub struct WgpuRenderer {
     
     /// Configuration de surface
     pub surface_config: SurfaceConfiguration,
+
+    /// Paramètres de présentation (vsync, latence)
+    pub present: PresentSettings,
+
+    /// Modes de présentation supportés par la surface
+    pub supported_present_modes: Vec<PresentMode>,
     
     /// Shader pour le rendu de triangles simples (sans textures)
     pub triangle_simple_shader: ShaderModule,
@@ -113,6 +133,19 @@ pub struct WgpuRenderer {
     
     /// Pipeline de blit
     pub blit_pipeline: RenderPipeline,
+
+    /// Format, échelle et rotation de l'image dans la fenêtre
+    pub output: OutputTransform,
+
+    /// Réglages du moniteur (gamma, luminosité, contraste, saturation)
+    pub color: ColorAdjustment,
+
+    /// Uniforme de la passe de blit
+    output_buffer: Buffer,
+    output_bind_group: BindGroup,
+
+    /// Sampler sans interpolation pour l'échelle entière
+    nearest_sampler: Sampler,
     
     /// Layout des bind groups pour les textures
     pub texture_bind_group_layout: BindGroupLayout,
@@ -128,41 +161,87 @@ pub struct WgpuRenderer {
     
     /// Sampler pour les textures
     pub texture_sampler: Sampler,
+
+    /// Filtrage utilisé par `texture_sampler`
+    pub texture_filter: TextureFilter,
+
+    /// Microtexture grise neutre, liée quand le polygone n'en a pas
+    neutral_microtexture: BindGroup,
+
+    /// Texture recevant le framebuffer rastérisé, recréée si sa taille change
+    frame_texture: Option<FrameTexture>,
+
+    /// Carte demandée à l'ouverture, reprise si le device est recréé
+    selection: AdapterSelection,
+
+    /// Perte du device signalée par wgpu
+    loss: DeviceLossMonitor,
+
+    /// Sommets de l'incrustation et des triangles, réutilisés d'un appel à
+    /// l'autre au lieu d'un buffer créé à chaque dessin
+    overlay_vertices: Mutex<VertexUploadBuffer>,
+    simple_vertices: Mutex<VertexUploadBuffer>,
+    textured_vertices: Mutex<VertexUploadBuffer>,
+}
+
+/// Paramètres d'ouverture et réglages d'un rendu, pour le recréer à
+/// l'identique sur un nouveau device
+#[derive(Clone)]
+pub struct RendererSettings {
+    pub window: Arc<Window>,
+    pub vsync: VsyncMode,
+    pub selection: AdapterSelection,
+    pub output: OutputTransform,
+    pub color: ColorAdjustment,
+    pub texture_filter: TextureFilter,
+}
+
+/// Copie du framebuffer sur la carte graphique
+struct FrameTexture {
+    texture: Texture,
+    view: TextureView,
+    size: (u32, u32),
+}
+
+/// Buffer de sommets réécrit à chaque dessin, agrandi seulement quand les
+/// sommets ne tiennent plus
+struct VertexUploadBuffer {
+    label: &'static str,
+    buffer: Option<Buffer>,
+}
+
+impl VertexUploadBuffer {
+    fn new(label: &'static str) -> Self {
+        Self { label, buffer: None }
+    }
+
+    /// Copie `contents` en début de buffer et retourne le buffer
+    fn upload(&mut self, device: &Device, queue: &Queue, contents: &[u8]) -> &Buffer {
+        let size = contents.len() as BufferAddress;
+        if self.buffer.as_ref().is_none_or(|buffer| buffer.size() < size) {
+            self.buffer = Some(device.create_buffer(&BufferDescriptor {
+                label: Some(self.label),
+                size: size.next_power_of_two().max(COPY_BUFFER_ALIGNMENT),
+                usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        let buffer = self.buffer.as_ref().expect("buffer de sommets alloué");
+        queue.write_buffer(buffer, 0, contents);
+        buffer
+    }
 }
 
 impl WgpuRenderer {
     /// Crée un nouveau rendu wgpu
-    pub async fn new(window: Arc<Window>) -> Result<Self> {
+    pub async fn new(window: Arc<Window>, vsync: VsyncMode, selection: &AdapterSelection) -> Result<Self> {
         let size = window.inner_size();
         
-        // Créer l'instance wgpu
-        let instance = Instance::new(InstanceDescriptor {
-            backends: Backends::all(),
-            flags: wgpu::InstanceFlags::default(),
-            dx12_shader_compiler: Dx12Compiler::Fxc,
-            gles_minor_version: wgpu::Gles3MinorVersion::Automatic,
-        });
-        
-        // Créer la surface - utiliser unsafe pour étendre la lifetime
-        let surface = unsafe {
-            std::mem::transmute::<Surface<'_>, Surface<'static>>(
-                instance.create_surface(&*window)?
-            )
-        };
-        
-        // Demander un adaptateur
-        let adapter = instance.request_adapter(&RequestAdapterOptions {
-            power_preference: PowerPreference::HighPerformance,
-            compatible_surface: Some(&surface),
-            force_fallback_adapter: false,
-        }).await.ok_or_else(|| anyhow!("Impossible de trouver un adaptateur graphique"))?;
-        
-        // Créer le device et la queue
-        let (device, queue) = adapter.request_device(&DeviceDescriptor {
-            required_features: Features::empty(),
-            required_limits: Limits::default(),
-            label: None,
-        }, None).await?;
+        // Ouvrir la carte graphique choisie, avec repli si elle est indisponible
+        let (instance, surface, adapter) = super::adapter::open(&window, selection).await?;
+        let (device, queue) = super::adapter::request_device(&adapter).await?;
+        let loss = DeviceLossMonitor::new();
+        loss.watch(&device);
         
         let device = Arc::new(device);
         let queue = Arc::new(queue);
@@ -173,16 +252,18 @@ impl WgpuRenderer {
             .copied()
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
+
+        let present = PresentSettings::select(vsync, &surface_caps.present_modes);
             
         let surface_config = SurfaceConfiguration {
             usage: TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width: size.width,
             height: size.height,
-            present_mode: PresentMode::Fifo,
+            present_mode: present.present_mode,
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
-            desired_maximum_frame_latency: 2,
+            desired_maximum_frame_latency: present.frame_latency,
         };
         
         surface.configure(&device, &surface_config);
@@ -262,7 +343,9 @@ impl WgpuRenderer {
             label: Some("Matrix Bind Group"),
         });
         
-        // Créer le sampler
+        // Sampler de l'image : agrandissement lissé, réduction au point comme
+        // avant le réglage texture_filtering ; seul « nearest » le remplace
+        let texture_filter = TextureFilter::Linear;
         let texture_sampler = device.create_sampler(&SamplerDescriptor {
             address_mode_u: AddressMode::ClampToEdge,
             address_mode_v: AddressMode::ClampToEdge,
@@ -272,11 +355,80 @@ impl WgpuRenderer {
             mipmap_filter: FilterMode::Nearest,
             ..Default::default()
         });
+        let nearest_sampler = TextureFilter::Nearest.create_sampler(&device, AddressMode::ClampToEdge);
+
+        // Second étage de texture : gris moyen, sans effet sur le texel
+        let neutral_texture = device.create_texture_with_data(
+            &queue,
+            &TextureDescriptor {
+                label: Some("Neutral Microtexture"),
+                size: Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba8Unorm,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &[128, 128, 128, 255],
+        );
+        let neutral_view = neutral_texture.create_view(&TextureViewDescriptor::default());
+        let neutral_microtexture = device.create_bind_group(&BindGroupDescriptor {
+            layout: &texture_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&neutral_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&texture_sampler),
+                },
+            ],
+            label: Some("Neutral Microtexture Bind Group"),
+        });
+
+        // Uniforme de la passe de blit (rotation, réglages du moniteur)
+        let output = OutputTransform::default();
+        let color = ColorAdjustment::default();
+        let output_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("output_bind_group_layout"),
+        });
+
+        let output_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Output Transform Buffer"),
+            contents: bytemuck::bytes_of(&OutputUniform::new(&output, &color)),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let output_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &output_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: output_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("Output Transform Bind Group"),
+        });
         
         // Créer les pipelines de rendu
         let triangle_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Triangle Pipeline Layout"),
-            bind_group_layouts: &[&texture_bind_group_layout, &matrix_bind_group_layout],
+            bind_group_layouts: &[&texture_bind_group_layout, &matrix_bind_group_layout, &texture_bind_group_layout],
             push_constant_ranges: &[],
         });
         
@@ -305,6 +457,21 @@ impl WgpuRenderer {
                             shader_location: 2,
                             format: VertexFormat::Float32x4,
                         },
+                        VertexAttribute {
+                            offset: std::mem::offset_of!(TexturedVertex, ambient) as BufferAddress,
+                            shader_location: 3,
+                            format: VertexFormat::Float32x3,
+                        },
+                        VertexAttribute {
+                            offset: std::mem::offset_of!(TexturedVertex, specular) as BufferAddress,
+                            shader_location: 4,
+                            format: VertexFormat::Float32x3,
+                        },
+                        VertexAttribute {
+                            offset: std::mem::offset_of!(TexturedVertex, detail) as BufferAddress,
+                            shader_location: 5,
+                            format: VertexFormat::Float32,
+                        },
                     ],
                 }],
             },
@@ -394,7 +561,7 @@ impl WgpuRenderer {
         
         let blit_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Blit Pipeline Layout"),
-            bind_group_layouts: &[&texture_bind_group_layout],
+            bind_group_layouts: &[&texture_bind_group_layout, &output_bind_group_layout],
             push_constant_ranges: &[],
         });
         
@@ -440,19 +607,61 @@ impl WgpuRenderer {
             device,
             queue,
             surface_config,
+            present,
+            supported_present_modes: surface_caps.present_modes.clone(),
             triangle_simple_shader,
             triangle_simple_pipeline,
             triangle_shader,
             blit_shader,
             triangle_pipeline,
             blit_pipeline,
+            output,
+            color,
+            output_buffer,
+            output_bind_group,
+            nearest_sampler,
             texture_bind_group_layout,
             matrix_bind_group_layout,
             matrix_buffer,
             matrix_bind_group,
             texture_sampler,
+            texture_filter,
+            neutral_microtexture,
+            frame_texture: None,
+            selection: selection.clone(),
+            loss,
+            overlay_vertices: Mutex::new(VertexUploadBuffer::new("Overlay Vertex Buffer")),
+            simple_vertices: Mutex::new(VertexUploadBuffer::new("Simple Triangle Vertex Buffer")),
+            textured_vertices: Mutex::new(VertexUploadBuffer::new("Textured Triangle Vertex Buffer")),
         })
     }
+
+    /// Paramètres permettant de recréer ce rendu
+    pub fn settings(&self) -> RendererSettings {
+        RendererSettings {
+            window: self.window.clone(),
+            vsync: self.present.requested,
+            selection: self.selection.clone(),
+            output: self.output,
+            color: self.color,
+            texture_filter: self.texture_filter,
+        }
+    }
+
+    /// Ouvre un nouveau rendu (nouveau device, pipelines et surface) avec
+    /// les paramètres et réglages d'un rendu perdu
+    pub async fn from_settings(settings: &RendererSettings) -> Result<Self> {
+        let mut renderer = Self::new(settings.window.clone(), settings.vsync, &settings.selection).await?;
+        renderer.set_output_transform(settings.output);
+        renderer.set_color_adjustment(settings.color);
+        renderer.set_texture_filter(settings.texture_filter);
+        Ok(renderer)
+    }
+
+    /// Cause de la perte du device, s'il est perdu
+    pub fn device_lost(&self) -> Option<String> {
+        self.loss.lost_reason()
+    }
     
     /// Redimensionner la surface
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
@@ -463,10 +672,144 @@ impl WgpuRenderer {
         }
     }
     
+    /// Change le format, l'échelle ou la rotation de l'image
+    pub fn set_output_transform(&mut self, output: OutputTransform) {
+        self.output = output;
+        self.write_output_uniform();
+    }
+
+    /// Change le filtrage des textures et de l'image
+    pub fn set_texture_filter(&mut self, filter: TextureFilter) {
+        self.texture_filter = filter;
+    }
+
+    /// Change les réglages du moniteur
+    pub fn set_color_adjustment(&mut self, color: ColorAdjustment) {
+        self.color = color;
+        self.write_output_uniform();
+    }
+
+    fn write_output_uniform(&self) {
+        let uniform = OutputUniform::new(&self.output, &self.color);
+        self.queue.write_buffer(&self.output_buffer, 0, bytemuck::bytes_of(&uniform));
+    }
+
+    /// Change le mode de présentation à chaud
+    pub fn set_vsync(&mut self, vsync: VsyncMode) {
+        self.present = PresentSettings::select(vsync, &self.supported_present_modes);
+        self.surface_config.present_mode = self.present.present_mode;
+        self.surface_config.desired_maximum_frame_latency = self.present.frame_latency;
+        self.surface.configure(&self.device, &self.surface_config);
+    }
+
+    /// Latence de présentation estimée en millisecondes
+    pub fn estimated_present_latency_ms(&self) -> f32 {
+        let refresh_hz = self.window.current_monitor()
+            .and_then(|m| m.refresh_rate_millihertz())
+            .map(|mhz| mhz as f32 / 1000.0)
+            .unwrap_or(60.0);
+        self.present.estimated_latency_ms(refresh_hz)
+    }
+
+    /// Image de la surface à dessiner, `None` si elle doit être sautée. Une
+    /// surface perdue ou périmée est reconfigurée une fois ; un device perdu
+    /// remonte en [`GpuError::DeviceLost`] pour être recréé
+    fn acquire_frame(&self) -> Result<Option<SurfaceTexture>> {
+        if let Some(reason) = self.device_lost() {
+            return Err(GpuError::DeviceLost(reason).into());
+        }
+        let error = match self.surface.get_current_texture() {
+            Ok(output) => return Ok(Some(output)),
+            Err(error) => error,
+        };
+        match SurfaceRecovery::for_error(&error) {
+            SurfaceRecovery::Reconfigure => {
+                log::debug!("Surface {}, reconfiguration", error);
+                self.surface.configure(&self.device, &self.surface_config);
+                match self.surface.get_current_texture() {
+                    Ok(output) => Ok(Some(output)),
+                    Err(error) => match SurfaceRecovery::for_error(&error) {
+                        SurfaceRecovery::RecreateDevice => Err(GpuError::DeviceLost(error.to_string()).into()),
+                        _ => Err(GpuError::Surface(error.to_string()).into()),
+                    },
+                }
+            }
+            SurfaceRecovery::SkipFrame => Ok(None),
+            SurfaceRecovery::RecreateDevice => Err(GpuError::DeviceLost(error.to_string()).into()),
+        }
+    }
+
+    /// Présente une image et applique la politique de latence
+    fn present_frame(&self, output: SurfaceTexture) {
+        output.present();
+
+        // Mode faible latence : attendre que le GPU ait terminé avant de
+        // laisser l'émulation préparer l'image suivante
+        if self.present.wait_for_gpu {
+            self.device.poll(Maintain::Wait);
+        }
+    }
+    
     /// Rendu d'une frame
     pub fn render(&self) -> Result<()> {
+        self.render_with_overlay(&[])
+    }
+
+    /// Rendu d'une frame suivi de la couche d'incrustation (viseurs, cibles)
+    pub fn render_with_overlay(&self, overlay: &[SimpleVertex]) -> Result<()> {
+        self.draw_frame(None, overlay)
+    }
+
+    /// Copie le framebuffer dans la fenêtre selon la transformation de
+    /// sortie, puis dessine la couche d'incrustation
+    pub fn render_frame(&mut self, framebuffer: &Framebuffer, overlay: &[SimpleVertex]) -> Result<()> {
+        self.upload_framebuffer(framebuffer);
+        // Placement à la taille native, quelle que soit la résolution interne
+        let frame = self.frame_texture.as_ref().map(|frame| (&frame.view, framebuffer.native_size()));
+        self.draw_frame(frame, overlay)
+    }
+
+    /// Copie les pixels rastérisés dans la texture affichée
+    fn upload_framebuffer(&mut self, framebuffer: &Framebuffer) {
+        let size = (framebuffer.width, framebuffer.height);
+        if self.frame_texture.as_ref().map(|frame| frame.size) != Some(size) {
+            let texture = self.device.create_texture(&TextureDescriptor {
+                label: Some("Framebuffer Color"),
+                size: Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba8UnormSrgb,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&TextureViewDescriptor::default());
+            self.frame_texture = Some(FrameTexture { texture, view, size });
+        }
+
+        let frame = self.frame_texture.as_ref().expect("texture créée ci-dessus");
+        self.queue.write_texture(
+            ImageCopyTexture {
+                texture: &frame.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            &framebuffer.color_data,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(size.0 * 4),
+                rows_per_image: Some(size.1),
+            },
+            Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+        );
+    }
+
+    fn draw_frame(&self, frame: Option<(&TextureView, (u32, u32))>, overlay: &[SimpleVertex]) -> Result<()> {
         // Obtenir la texture de surface
-        let output = self.surface.get_current_texture()?;
+        let Some(output) = self.acquire_frame()? else {
+            return Ok(());
+        };
         let view = output.texture.create_view(&TextureViewDescriptor::default());
         
         // Créer l'encodeur de commandes
@@ -474,9 +817,33 @@ impl WgpuRenderer {
             label: Some("Render Encoder"),
         });
         
+        let frame_bind_group = frame.map(|(frame_view, size)| {
+            let nearest = self.output.nearest_filtering() || self.texture_filter == TextureFilter::Nearest;
+            let sampler = if nearest { &self.nearest_sampler } else { &self.texture_sampler };
+            let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+                layout: &self.texture_bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(frame_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(sampler),
+                    },
+                ],
+                label: Some("Framebuffer Bind Group"),
+            });
+            (bind_group, size)
+        });
+
+        let mut overlay_vertices = self.overlay_vertices.lock();
+        let overlay_buffer = (!overlay.is_empty())
+            .then(|| overlay_vertices.upload(&self.device, &self.queue, bytemuck::cast_slice(overlay)));
+        
         // Pass de rendu de base
         {
-            let _render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("Blit Pass"),
                 color_attachments: &[Some(RenderPassColorAttachment {
                     view: &view,
@@ -495,11 +862,30 @@ impl WgpuRenderer {
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
+
+            if let Some((bind_group, source)) = &frame_bind_group {
+                let surface = (self.surface_config.width, self.surface_config.height);
+                let [x, y, width, height] = self.output.viewport(*source, surface);
+                render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
+                render_pass.set_pipeline(&self.blit_pipeline);
+                render_pass.set_bind_group(0, bind_group, &[]);
+                render_pass.set_bind_group(1, &self.output_bind_group, &[]);
+                render_pass.draw(0..3, 0..1);
+
+                // L'incrustation couvre toute la fenêtre
+                render_pass.set_viewport(0.0, 0.0, surface.0 as f32, surface.1 as f32, 0.0, 1.0);
+            }
+
+            if let Some(buffer) = &overlay_buffer {
+                render_pass.set_pipeline(&self.triangle_simple_pipeline);
+                render_pass.set_vertex_buffer(0, buffer.slice(..));
+                render_pass.draw(0..overlay.len() as u32, 0..1);
+            }
         }
         
         // Soumettre les commandes
         self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        self.present_frame(output);
         
         Ok(())
     }
@@ -510,15 +896,14 @@ impl WgpuRenderer {
             return Ok(()); // Rien à rendre ou nombre de sommets invalide
         }
 
-        // Créer un buffer pour les sommets
-        let vertex_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Simple Triangle Vertex Buffer"),
-            contents: bytemuck::cast_slice(vertices),
-            usage: BufferUsages::VERTEX,
-        });
+        // Copier les sommets dans le buffer réutilisé
+        let mut simple_vertices = self.simple_vertices.lock();
+        let vertex_buffer = simple_vertices.upload(&self.device, &self.queue, bytemuck::cast_slice(vertices));
 
         // Obtenir la texture de surface
-        let output = self.surface.get_current_texture()?;
+        let Some(output) = self.acquire_frame()? else {
+            return Ok(());
+        };
         let view = output.texture.create_view(&TextureViewDescriptor::default());
 
         // Créer l'encodeur de commandes
@@ -558,26 +943,26 @@ impl WgpuRenderer {
 
         // Soumettre les commandes
         self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        self.present_frame(output);
 
         Ok(())
     }
 
-    /// Rendre des triangles texturés
-    pub fn render_textured_triangles(&self, vertices: &[TexturedVertex], texture_view: &TextureView, bind_group: &BindGroup) -> Result<()> {
+    /// Rendre des triangles texturés, avec la microtexture de détail des
+    /// sommets où elle est activée
+    pub fn render_textured_triangles(&self, vertices: &[TexturedVertex], texture_view: &TextureView, bind_group: &BindGroup, microtexture: Option<&BindGroup>) -> Result<()> {
         if vertices.is_empty() || vertices.len() % 3 != 0 {
             return Ok(()); // Rien à rendre ou nombre de sommets invalide
         }
 
-        // Créer un buffer pour les sommets
-        let vertex_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Textured Triangle Vertex Buffer"),
-            contents: bytemuck::cast_slice(vertices),
-            usage: BufferUsages::VERTEX,
-        });
+        // Copier les sommets dans le buffer réutilisé
+        let mut textured_vertices = self.textured_vertices.lock();
+        let vertex_buffer = textured_vertices.upload(&self.device, &self.queue, bytemuck::cast_slice(vertices));
 
         // Obtenir la texture de surface
-        let output = self.surface.get_current_texture()?;
+        let Some(output) = self.acquire_frame()? else {
+            return Ok(());
+        };
         let view = output.texture.create_view(&TextureViewDescriptor::default());
 
         // Créer l'encodeur de commandes
@@ -612,6 +997,7 @@ impl WgpuRenderer {
             render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
             render_pass.set_bind_group(0, bind_group, &[]);
             render_pass.set_bind_group(1, &self.matrix_bind_group, &[]);
+            render_pass.set_bind_group(2, microtexture.unwrap_or(&self.neutral_microtexture), &[]);
 
             // Dessiner les triangles
             render_pass.draw(0..vertices.len() as u32, 0..1);
@@ -619,7 +1005,7 @@ impl WgpuRenderer {
 
         // Soumettre les commandes
         self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        self.present_frame(output);
 
         Ok(())
     }
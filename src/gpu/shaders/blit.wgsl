@@ -25,8 +25,27 @@ var framebuffer_texture: texture_2d<f32>;
 @group(0) @binding(1)
 var framebuffer_sampler: sampler;
 
+// Rotation de l'image : matrice 2x2 (a, b, c, d) appliquée autour du centre,
+// puis réglages du moniteur (gamma, luminosité, contraste, saturation)
+struct Output {
+    uv_matrix: vec4<f32>,
+    color: vec4<f32>,
+}
+
+@group(1) @binding(0)
+var<uniform> output_transform: Output;
+
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    // Copie directe du framebuffer
-    return textureSample(framebuffer_texture, framebuffer_sampler, input.tex_coords);
+    let m = output_transform.uv_matrix;
+    let centered = input.tex_coords - vec2<f32>(0.5, 0.5);
+    let uv = vec2<f32>(m.x * centered.x + m.y * centered.y, m.z * centered.x + m.w * centered.y) + vec2<f32>(0.5, 0.5);
+    let texel = textureSample(framebuffer_texture, framebuffer_sampler, uv);
+
+    let adjust = output_transform.color;
+    let contrasted = (texel.rgb - vec3<f32>(0.5)) * adjust.z + vec3<f32>(0.5 + adjust.y);
+    let luma = dot(contrasted, vec3<f32>(0.299, 0.587, 0.114));
+    let saturated = mix(vec3<f32>(luma), contrasted, adjust.w);
+    let corrected = pow(clamp(saturated, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(1.0 / adjust.x));
+    return vec4<f32>(corrected, texel.a);
 }
\ No newline at end of file
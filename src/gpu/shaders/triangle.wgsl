@@ -4,12 +4,18 @@ struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) tex_coords: vec2<f32>,
     @location(2) color: vec4<f32>,
+    @location(3) ambient: vec3<f32>,
+    @location(4) specular: vec3<f32>,
+    @location(5) detail: f32,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) ambient: vec3<f32>,
+    @location(3) specular: vec3<f32>,
+    @location(4) detail: f32,
 }
 
 // Matrices de transformation 3D
@@ -33,6 +39,9 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     
     output.tex_coords = input.tex_coords;
     output.color = input.color;
+    output.ambient = input.ambient;
+    output.specular = input.specular;
+    output.detail = input.detail;
     
     return output;
 }
@@ -43,13 +52,30 @@ var texture_diffuse: texture_2d<f32>;
 @group(0) @binding(1)
 var sampler_diffuse: sampler;
 
+// Microtexture de détail (Model 2B), grise neutre si absente
+@group(2) @binding(0)
+var texture_detail: texture_2d<f32>;
+
+@group(2) @binding(1)
+var sampler_detail: sampler;
+
+// Répétitions de la microtexture sur la texture principale
+const MICROTEXTURE_SCALE: f32 = 8.0;
+
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     // Échantillonner la texture
-    let texture_color = textureSample(texture_diffuse, sampler_diffuse, input.tex_coords);
+    let base_color = textureSample(texture_diffuse, sampler_diffuse, input.tex_coords);
+    
+    // Second étage : modulation par la microtexture, le gris moyen est neutre
+    let detail = textureSample(texture_detail, sampler_detail, input.tex_coords * MICROTEXTURE_SCALE);
+    let detailed = min(base_color.rgb * detail.rgb * 2.0, vec3<f32>(1.0));
+    let texture_color = vec4<f32>(mix(base_color.rgb, detailed, input.detail), base_color.a);
     
-    // Combiner avec la couleur du vertex (éclairage Gouraud)
-    let final_color = texture_color * input.color;
+    // Éclairage Gouraud (diffus + ambiant) appliqué au texel, puis reflet
+    // spéculaire ajouté après texturage comme sur le Model 2
+    let light = min(input.color.rgb + input.ambient, vec3<f32>(1.0));
+    let lit = clamp(texture_color.rgb * light + input.specular, vec3<f32>(0.0), vec3<f32>(1.0));
     
-    return final_color;
+    return vec4<f32>(lit, texture_color.a * input.color.a);
 }
\ No newline at end of file
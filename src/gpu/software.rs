@@ -0,0 +1,268 @@
+//! Présentation logicielle, sans carte graphique
+//!
+//! Utilisée quand aucun adaptateur wgpu n'est disponible (machines
+//! virtuelles, affichage déporté) : le framebuffer rastérisé par le CPU est
+//! corrigé (réglages du moniteur), placé et tourné selon la transformation de
+//! sortie, puis copié dans la fenêtre avec softbuffer. La couche
+//! d'incrustation est tracée par-dessus. La mise à l'échelle se fait au plus
+//! proche, le filtrage bilinéaire restant réservé au rendu wgpu.
+
+use crate::error::{GpuError, Result};
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use winit::window::Window;
+
+use super::framebuffer::Framebuffer;
+use super::output::{adjust_color, OutputTransform};
+use super::overlay::SimpleVertex;
+use crate::config::ColorAdjustment;
+
+/// Copie le framebuffer dans la fenêtre sans passer par wgpu
+pub struct SoftwarePresenter {
+    surface: softbuffer::Surface<Arc<Window>, Arc<Window>>,
+
+    /// Taille de la fenêtre en pixels
+    size: (u32, u32),
+
+    /// Format, échelle et rotation de l'image dans la fenêtre
+    pub output: OutputTransform,
+
+    /// Réglages du moniteur (gamma, luminosité, contraste, saturation)
+    pub color: ColorAdjustment,
+
+    /// Pixels du framebuffer après réglages, réutilisés d'une frame à l'autre
+    adjusted: Vec<u32>,
+}
+
+impl SoftwarePresenter {
+    pub fn new(window: Arc<Window>) -> Result<Self> {
+        let context = softbuffer::Context::new(window.clone())
+            .map_err(|e| GpuError::Device(e.to_string()))?;
+        let surface = softbuffer::Surface::new(&context, window.clone())
+            .map_err(|e| GpuError::Surface(e.to_string()))?;
+
+        let mut presenter = Self {
+            surface,
+            size: (0, 0),
+            output: OutputTransform::default(),
+            color: ColorAdjustment::default(),
+            adjusted: Vec::new(),
+        };
+        presenter.resize(window.inner_size());
+        Ok(presenter)
+    }
+
+    /// Suit la taille de la fenêtre ; ignoré tant qu'elle est réduite
+    pub fn resize(&mut self, size: winit::dpi::PhysicalSize<u32>) {
+        let (Some(width), Some(height)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) else {
+            return;
+        };
+        match self.surface.resize(width, height) {
+            Ok(()) => self.size = (size.width, size.height),
+            Err(e) => log::warn!("Redimensionnement de la surface logicielle impossible: {}", e),
+        }
+    }
+
+    /// Taille de la fenêtre en pixels
+    pub fn surface_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Copie le framebuffer dans la fenêtre selon la transformation de
+    /// sortie, puis dessine la couche d'incrustation
+    pub fn render_frame(&mut self, framebuffer: &Framebuffer, overlay: &[SimpleVertex]) -> Result<()> {
+        if self.size.0 == 0 || self.size.1 == 0 {
+            return Ok(());
+        }
+
+        adjust_frame(&framebuffer.color_data, &self.color, &mut self.adjusted);
+        let mut buffer = self.surface.buffer_mut()
+            .map_err(|e| GpuError::Surface(e.to_string()))?;
+        let source_size = (framebuffer.width, framebuffer.height);
+        blit_frame(&self.adjusted, source_size, framebuffer.native_size(), &mut buffer, self.size, &self.output);
+        draw_overlay(&mut buffer, self.size, overlay);
+        buffer.present().map_err(|e| GpuError::Presentation(e.to_string()).into())
+    }
+}
+
+/// Couleur au format 0RGB de softbuffer
+pub fn pack_rgb(rgb: [u8; 3]) -> u32 {
+    (rgb[0] as u32) << 16 | (rgb[1] as u32) << 8 | rgb[2] as u32
+}
+
+/// Convertit les pixels RGBA8 en 0RGB en appliquant les réglages du moniteur
+pub fn adjust_frame(rgba: &[u8], color: &ColorAdjustment, out: &mut Vec<u32>) {
+    out.clear();
+    let neutral = *color == ColorAdjustment::default();
+    out.extend(rgba.chunks_exact(4).map(|pixel| {
+        if neutral {
+            return pack_rgb([pixel[0], pixel[1], pixel[2]]);
+        }
+        let adjusted = adjust_color(color, [0, 1, 2].map(|i| pixel[i] as f32 / 255.0));
+        pack_rgb(adjusted.map(|c| (c * 255.0).round() as u8))
+    }));
+}
+
+/// Copie l'image `source` (0RGB, `source_size`) dans `target` : l'image
+/// occupe le rectangle de la transformation de sortie, placé d'après sa
+/// taille native `display_size` (plus petite avec une résolution interne
+/// élevée) ; le reste est noir
+pub fn blit_frame(
+    source: &[u32],
+    source_size: (u32, u32),
+    display_size: (u32, u32),
+    target: &mut [u32],
+    target_size: (u32, u32),
+    output: &OutputTransform,
+) {
+    target.fill(0);
+    let (source_width, source_height) = source_size;
+    if source_width == 0 || source_height == 0 || source.len() < (source_width * source_height) as usize {
+        return;
+    }
+
+    let [x, y, width, height] = output.viewport(display_size, target_size);
+    let m = output.uv_matrix();
+    let (x0, y0) = (x.max(0.0) as u32, y.max(0.0) as u32);
+    let x1 = ((x + width).ceil() as u32).min(target_size.0);
+    let y1 = ((y + height).ceil() as u32).min(target_size.1);
+
+    for py in y0..y1 {
+        // Coordonnées centrées dans le rectangle de sortie, comme le shader de blit
+        let cy = (py as f32 + 0.5 - y) / height - 0.5;
+        let row = &mut target[(py * target_size.0) as usize..][..target_size.0 as usize];
+        for px in x0..x1 {
+            let cx = (px as f32 + 0.5 - x) / width - 0.5;
+            let u = m[0] * cx + m[1] * cy + 0.5;
+            let v = m[2] * cx + m[3] * cy + 0.5;
+            let sx = ((u * source_width as f32) as u32).min(source_width - 1);
+            let sy = ((v * source_height as f32) as u32).min(source_height - 1);
+            row[px as usize] = source[(sy * source_width + sx) as usize];
+        }
+    }
+}
+
+/// Trace la couche d'incrustation (triangles en coordonnées de clip couvrant
+/// toute la fenêtre), mélangée selon l'alpha des sommets
+pub fn draw_overlay(target: &mut [u32], target_size: (u32, u32), overlay: &[SimpleVertex]) {
+    let (width, height) = (target_size.0 as f32, target_size.1 as f32);
+    for triangle in overlay.chunks_exact(3) {
+        let points = [0, 1, 2].map(|i| {
+            let [x, y, _] = triangle[i].position;
+            ((x * 0.5 + 0.5) * width, (0.5 - y * 0.5) * height)
+        });
+        // Les cibles sont unies : la couleur du premier sommet suffit
+        fill_triangle(target, target_size, points, triangle[0].color);
+    }
+}
+
+fn fill_triangle(target: &mut [u32], target_size: (u32, u32), points: [(f32, f32); 3], color: [f32; 4]) {
+    let edge = |a: (f32, f32), b: (f32, f32), p: (f32, f32)| (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+    let area = edge(points[0], points[1], points[2]);
+    if area.abs() <= f32::EPSILON {
+        return;
+    }
+
+    let min_x = points.iter().map(|p| p.0).fold(f32::INFINITY, f32::min).max(0.0) as u32;
+    let min_y = points.iter().map(|p| p.1).fold(f32::INFINITY, f32::min).max(0.0) as u32;
+    let max_x = (points.iter().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max).ceil().max(0.0) as u32).min(target_size.0);
+    let max_y = (points.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max).ceil().max(0.0) as u32).min(target_size.1);
+
+    let alpha = color[3].clamp(0.0, 1.0);
+    for py in min_y..max_y {
+        for px in min_x..max_x {
+            let p = (px as f32 + 0.5, py as f32 + 0.5);
+            // Les deux sens de parcours sont acceptés
+            let w = [
+                edge(points[1], points[2], p) * area.signum(),
+                edge(points[2], points[0], p) * area.signum(),
+                edge(points[0], points[1], p) * area.signum(),
+            ];
+            if w.iter().any(|&w| w < 0.0) {
+                continue;
+            }
+            let pixel = &mut target[(py * target_size.0 + px) as usize];
+            let below = [(*pixel >> 16) & 0xFF, (*pixel >> 8) & 0xFF, *pixel & 0xFF];
+            let blended = [0, 1, 2].map(|i| {
+                let over = color[i].clamp(0.0, 1.0) * 255.0;
+                (over * alpha + below[i] as f32 * (1.0 - alpha)).round() as u8
+            });
+            *pixel = pack_rgb(blended);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AspectMode, Rotation};
+
+    /// Image 2x2 : rouge, vert / bleu, blanc
+    const SOURCE: [u32; 4] = [0xFF0000, 0x00FF00, 0x0000FF, 0xFFFFFF];
+
+    #[test]
+    fn test_blit_scales_and_rotates() {
+        let stretch = OutputTransform::new(AspectMode::Stretch, Rotation::None);
+        let mut target = [0u32; 16];
+        blit_frame(&SOURCE, (2, 2), (2, 2), &mut target, (4, 4), &stretch);
+        assert_eq!(&target[0..4], &[0xFF0000, 0xFF0000, 0x00FF00, 0x00FF00]);
+        assert_eq!(&target[12..16], &[0x0000FF, 0x0000FF, 0xFFFFFF, 0xFFFFFF]);
+
+        // Demi-tour : le coin blanc passe en haut à gauche
+        let half = OutputTransform::new(AspectMode::Stretch, Rotation::Half);
+        blit_frame(&SOURCE, (2, 2), (2, 2), &mut target, (4, 4), &half);
+        assert_eq!(target[0], 0xFFFFFF);
+        assert_eq!(target[15], 0xFF0000);
+
+        // Quart de tour horaire : le coin haut-gauche apparaît en haut à droite
+        let quarter = OutputTransform::new(AspectMode::Stretch, Rotation::Clockwise90);
+        blit_frame(&SOURCE, (2, 2), (2, 2), &mut target, (4, 4), &quarter);
+        assert_eq!(target[3], 0xFF0000);
+    }
+
+    #[test]
+    fn test_blit_letterboxes() {
+        let ratio = OutputTransform::new(AspectMode::Ratio4x3, Rotation::None);
+        let source = vec![0x808080; 4 * 3];
+        let mut target = vec![0xABCDEF; 8 * 3];
+        blit_frame(&source, (4, 3), (4, 3), &mut target, (8, 3), &ratio);
+
+        // Image de 4x3 centrée, bandes noires de deux pixels de chaque côté
+        for row in target.chunks(8) {
+            assert_eq!(row, &[0, 0, 0x808080, 0x808080, 0x808080, 0x808080, 0, 0]);
+        }
+
+        // Rendu interne 2x : même placement à l'échelle entière que l'image native
+        let integer = OutputTransform::new(AspectMode::Integer, Rotation::None);
+        let scaled = vec![0x808080; 8 * 6];
+        let mut target = vec![0; 10 * 4];
+        blit_frame(&scaled, (8, 6), (4, 3), &mut target, (10, 4), &integer);
+        assert_eq!(&target[..10], &[0, 0, 0, 0x808080, 0x808080, 0x808080, 0x808080, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_adjust_frame() {
+        let rgba = [255, 128, 0, 255, 10, 20, 30, 0];
+        let mut out = Vec::new();
+        adjust_frame(&rgba, &ColorAdjustment::default(), &mut out);
+        assert_eq!(out, vec![0xFF8000, 0x0A141E]);
+
+        let grey = ColorAdjustment { saturation: 0.0, ..ColorAdjustment::default() };
+        adjust_frame(&rgba[..4], &grey, &mut out);
+        let [r, g, b] = [(out[0] >> 16) & 0xFF, (out[0] >> 8) & 0xFF, out[0] & 0xFF];
+        assert!(r == g && g == b);
+    }
+
+    #[test]
+    fn test_overlay_blends_over_frame() {
+        let mut target = [0u32; 16];
+        let v = |x: f32, y: f32| SimpleVertex::new(x, y, 0.0, 1.0, 1.0, 1.0, 0.5);
+        // Moitié droite de l'écran, sommets dans les deux sens de parcours
+        let overlay = [v(0.0, 1.0), v(1.0, 1.0), v(1.0, -1.0), v(0.0, 1.0), v(0.0, -1.0), v(1.0, -1.0)];
+        draw_overlay(&mut target, (4, 4), &overlay);
+
+        for row in target.chunks(4) {
+            assert_eq!(row, &[0, 0, 0x808080, 0x808080]);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Tri des polygones par priorité (algorithme du peintre)
+//!
+//! Certaines passes du Model 2 n'utilisaient pas de Z-buffer : les jeux
+//! comptaient sur l'ordre de tracé des polygones. Un Z-buffer strict fait alors
+//! apparaître des conflits de profondeur (ombres, décalcomanies, marquages au
+//! sol). En mode priorité, les triangles d'une frame sont mis en attente puis
+//! tracés du plus lointain au plus proche, sans test de profondeur ; à
+//! profondeur égale, l'ordre de soumission du jeu est conservé.
+
+use super::geometry::TransformedTriangle;
+
+/// Résolution de la visibilité des polygones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthMode {
+    /// Test de profondeur par pixel
+    #[default]
+    ZBuffer,
+    /// Tri des polygones du plus lointain au plus proche, sans Z-buffer
+    Priority,
+}
+
+/// File des triangles d'une frame en mode priorité
+#[derive(Debug, Clone, Default)]
+pub struct PolygonSorter {
+    triangles: Vec<TransformedTriangle>,
+}
+
+impl PolygonSorter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, triangle: TransformedTriangle) {
+        self.triangles.push(triangle);
+    }
+
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Vide la file et retourne les triangles dans l'ordre de tracé
+    pub fn drain_sorted(&mut self) -> Vec<TransformedTriangle> {
+        let mut triangles = std::mem::take(&mut self.triangles);
+        // Tri stable : les triangles de même profondeur gardent leur ordre
+        triangles.sort_by(|a, b| sort_depth(b).total_cmp(&sort_depth(a)));
+        triangles
+    }
+
+    pub fn clear(&mut self) {
+        self.triangles.clear();
+    }
+}
+
+/// Profondeur de tri d'un triangle : moyenne des profondeurs normalisées de
+/// ses sommets (plus grand = plus lointain)
+pub fn sort_depth(triangle: &TransformedTriangle) -> f32 {
+    let total: f32 = triangle
+        .vertices
+        .iter()
+        .map(|v| {
+            let w = v.clip_position.w;
+            if w.abs() > f32::EPSILON { v.clip_position.z / w } else { v.clip_position.z }
+        })
+        .sum();
+    total / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::geometry::{TransformedVertex, TriangleFlags};
+    use glam::Vec4;
+
+    fn triangle(depth: f32, material_id: u32) -> TransformedTriangle {
+        let vertex = TransformedVertex {
+            clip_position: Vec4::new(0.0, 0.0, depth * 2.0, 2.0),
+            ..Default::default()
+        };
+        TransformedTriangle {
+            vertices: [vertex; 3],
+            texture_id: None,
+            material_id,
+            flags: TriangleFlags::default(),
+        }
+    }
+
+    #[test]
+    fn test_far_to_near_keeping_submission_order() {
+        let mut sorter = PolygonSorter::new();
+        sorter.push(triangle(0.2, 0));
+        sorter.push(triangle(0.9, 1));
+        sorter.push(triangle(0.5, 2));
+        // Marquage au sol tracé après la route, à la même profondeur
+        sorter.push(triangle(0.5, 3));
+
+        let order: Vec<u32> = sorter.drain_sorted().iter().map(|t| t.material_id).collect();
+        assert_eq!(order, vec![1, 2, 3, 0]);
+        assert!(sorter.is_empty());
+    }
+
+    #[test]
+    fn test_sort_depth_uses_perspective_divide() {
+        assert!((sort_depth(&triangle(0.75, 0)) - 0.75).abs() < 1e-6);
+    }
+}
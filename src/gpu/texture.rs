@@ -3,31 +3,238 @@
 //! Implémente le chargement et la gestion des textures avec support des formats
 //! propriétaires SEGA : 4bpp, 8bpp, 16bpp avec palettes.
 
-use anyhow::Result;
+use crate::config::MipmapSettings;
+use crate::error::{GpuError, Result};
+use crate::memory::ByteOrder;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
 use wgpu::*;
 use std::collections::HashMap;
+#[cfg(feature = "gui")]
 use std::sync::Arc;
 
+use super::{TexelSource, TextureFilter};
+#[cfg(feature = "gui")]
+use super::TextureWrap;
+
+/// Identifiant réservé à la première microtexture ; les microtextures du
+/// Model 2B sont rangées après les textures des jeux
+pub const MICROTEXTURE_BASE_ID: u32 = 0xFFFF_FF00;
+
+/// Identifiant de texture de la microtexture `index`
+pub fn microtexture_id(index: u8) -> u32 {
+    MICROTEXTURE_BASE_ID + index as u32
+}
+
+/// Occupation du cache de textures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TextureCacheStats {
+    /// Textures des jeux chargées
+    pub textures: usize,
+    pub microtextures: usize,
+    /// Taille des texels décodés (RGBA8), mipmaps comprises
+    pub bytes: usize,
+    /// Bind groups créés pour d'autres modes d'adressage
+    pub wrapped_bind_groups: usize,
+}
+
 /// Gestionnaire de textures avec support des formats SEGA
 pub struct TextureManager {
     textures: HashMap<u32, TextureData>,
     palettes: HashMap<u32, PaletteData>,
+    /// Ressources wgpu, absentes en rendu logiciel
+    #[cfg(feature = "gui")]
+    gpu: Option<TextureGpu>,
+    filter: TextureFilter,
+    mipmaps: MipmapSettings,
+}
+
+/// Device et samplers utilisés pour créer les textures wgpu
+#[cfg(feature = "gui")]
+pub struct TextureGpu {
     device: Arc<Device>,
     queue: Arc<Queue>,
     bind_group_layout: BindGroupLayout,
     sampler: Sampler,
+    /// Samplers des polygones qui ne répètent pas leur texture
+    wrapped_samplers: HashMap<[TextureWrap; 2], Sampler>,
 }
 
 /// Données d'une texture
 #[derive(Debug)]
 pub struct TextureData {
+    /// Copie sur la carte graphique, absente en rendu logiciel
+    #[cfg(feature = "gui")]
+    pub gpu: Option<GpuTexture>,
+    pub width: u32,
+    pub height: u32,
+    pub format: SegaTextureFormat,
+    pub palette_id: Option<u32>,
+    /// Copie RGBA8 des texels pour la rastérisation logicielle
+    pub pixels: Vec<u8>,
+    /// Niveaux réduits (1 et suivants), vides sans mipmaps
+    pub mipmaps: Vec<MipLevel>,
+    /// Bind groups créés pour les autres modes d'adressage
+    #[cfg(feature = "gui")]
+    pub wrapped_bind_groups: HashMap<[TextureWrap; 2], BindGroup>,
+}
+
+/// Texture wgpu et son bind group par défaut
+#[cfg(feature = "gui")]
+#[derive(Debug)]
+pub struct GpuTexture {
     pub texture: Texture,
     pub view: TextureView,
     pub bind_group: BindGroup,
+}
+
+impl TextureData {
+    /// Oublie la copie sur la carte graphique
+    #[cfg(feature = "gui")]
+    pub fn detach_gpu(&mut self) {
+        self.gpu = None;
+        self.wrapped_bind_groups.clear();
+    }
+
+    pub fn texels(&self) -> TexelSource<'_> {
+        TexelSource { width: self.width, height: self.height, rgba: &self.pixels }
+    }
+}
+
+/// Niveau de mipmap : texture réduite de moitié par rapport au précédent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MipLevel {
     pub width: u32,
     pub height: u32,
-    pub format: SegaTextureFormat,
-    pub palette_id: Option<u32>,
+    pub rgba: Vec<u8>,
+}
+
+impl MipLevel {
+    pub fn texels(&self) -> TexelSource<'_> {
+        TexelSource { width: self.width, height: self.height, rgba: &self.rgba }
+    }
+}
+
+/// Chaîne de mipmaps d'une texture RGBA8, jusqu'au niveau 1x1 : chaque
+/// texel est la moyenne arrondie du bloc de 2x2 texels du niveau précédent
+/// (le bloc est tronqué sur le bord d'une dimension impaire)
+pub fn generate_mipmaps(width: u32, height: u32, rgba: &[u8]) -> Vec<MipLevel> {
+    let mut levels: Vec<MipLevel> = Vec::new();
+    let (mut width, mut height) = (width as usize, height as usize);
+    if rgba.len() < width * height * 4 {
+        return levels;
+    }
+
+    while width > 1 || height > 1 {
+        let source = levels.last().map_or(rgba, |level| &level.rgba);
+        let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+        let mut next = Vec::with_capacity(next_width * next_height * 4);
+        for y in 0..next_height {
+            let rows = [2 * y, (2 * y + 1).min(height - 1)];
+            for x in 0..next_width {
+                let columns = [2 * x, (2 * x + 1).min(width - 1)];
+                for channel in 0..4 {
+                    let sum: u32 = rows.iter()
+                        .flat_map(|&row| columns.map(|column| source[(row * width + column) * 4 + channel] as u32))
+                        .sum();
+                    next.push(((sum + 2) / 4) as u8);
+                }
+            }
+        }
+        levels.push(MipLevel { width: next_width as u32, height: next_height as u32, rgba: next });
+        (width, height) = (next_width, next_height);
+    }
+    levels
+}
+
+#[cfg(feature = "gui")]
+impl TextureGpu {
+    pub fn new(device: Arc<Device>, queue: Arc<Queue>, filter: TextureFilter) -> Self {
+        // Créer le bind group layout pour les textures
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("texture_bind_group_layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: TextureViewDimension::D2,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        
+        // Créer le sampler avec paramètres SEGA Model 2
+        let sampler = filter.create_sampler(&device, AddressMode::Repeat);
+
+        Self {
+            device,
+            queue,
+            bind_group_layout,
+            sampler,
+            wrapped_samplers: HashMap::new(),
+        }
+    }
+
+    /// Crée la texture wgpu et son bind group, avec ses mipmaps éventuelles
+    fn upload(&self, id: u32, width: u32, height: u32, rgba: &[u8], mipmaps: &[MipLevel]) -> GpuTexture {
+        // Créer la texture wgpu
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some(&format!("SEGA Texture {}", id)),
+            size: Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1 + mipmaps.len() as u32,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        
+        // Copier les données converties, niveau par niveau
+        let levels = std::iter::once((width, height, rgba))
+            .chain(mipmaps.iter().map(|level| (level.width, level.height, level.rgba.as_slice())));
+        for (mip_level, (width, height, rgba)) in levels.enumerate() {
+            self.queue.write_texture(
+                ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: mip_level as u32,
+                    origin: Origin3d::ZERO,
+                    aspect: TextureAspect::All,
+                },
+                rgba,
+                ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * width),
+                    rows_per_image: Some(height),
+                },
+                Extent3d { width, height, depth_or_array_layers: 1 },
+            );
+        }
+        
+        // Créer une vue texture
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        
+        // Créer le bind group avec la vraie layout
+        let bind_group = TextureManager::create_bind_group(&self.device, &self.bind_group_layout, &self.sampler, id, &view);
+        
+        GpuTexture { texture, view, bind_group }
+    }
+
+    /// Copie une texture décodée sur ce device ; les bind groups des autres
+    /// modes d'adressage, liés à l'ancienne copie, sont oubliés
+    pub fn make_resident(&self, id: u32, texture: &mut TextureData) {
+        texture.gpu = Some(self.upload(id, texture.width, texture.height, &texture.pixels, &texture.mipmaps));
+        texture.wrapped_bind_groups.clear();
+    }
 }
 
 /// Formats de texture SEGA Model 2
@@ -45,6 +252,23 @@ pub enum SegaTextureFormat {
     Rgba8888,
 }
 
+impl SegaTextureFormat {
+    /// Texels indexés dans une palette
+    pub fn is_indexed(self) -> bool {
+        matches!(self, SegaTextureFormat::Palette4bpp | SegaTextureFormat::Palette8bpp)
+    }
+
+    /// Taille d'un texel en bits
+    pub fn bits_per_texel(self) -> u32 {
+        match self {
+            SegaTextureFormat::Palette4bpp => 4,
+            SegaTextureFormat::Palette8bpp => 8,
+            SegaTextureFormat::Rgb565 | SegaTextureFormat::Rgba4444 => 16,
+            SegaTextureFormat::Rgba8888 => 32,
+        }
+    }
+}
+
 /// Données de palette pour textures indexées
 #[derive(Debug, Clone)]
 pub struct PaletteData {
@@ -79,55 +303,158 @@ pub struct TextureDecodeParams {
     pub format: SegaTextureFormat,
     pub palette_offset: Option<usize>,
     pub data_offset: usize,
-    pub stride: Option<u32>, // Pour textures non-power-of-2
+    /// Octets d'une ligne à la suivante ; `None` pour des lignes jointives
+    pub stride: Option<u32>,
+}
+
+impl TextureDecodeParams {
+    /// Octets utiles d'une ligne : une ligne de largeur impaire en 4bpp se
+    /// termine par un demi-octet de remplissage
+    pub fn row_bytes(&self) -> usize {
+        (self.width as usize * self.format.bits_per_texel() as usize).div_ceil(8)
+    }
+
+    /// Octets d'une ligne à la suivante
+    pub fn row_pitch(&self) -> usize {
+        self.stride.map_or_else(|| self.row_bytes(), |stride| stride as usize)
+    }
 }
 
 impl TextureManager {
+    #[cfg(feature = "gui")]
     pub fn new(device: Arc<Device>, queue: Arc<Queue>) -> Self {
-        // Créer le bind group layout pour les textures
-        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("texture_bind_group_layout"),
+        let filter = TextureFilter::Linear;
+        Self {
+            textures: HashMap::new(),
+            palettes: HashMap::new(),
+            gpu: Some(TextureGpu::new(device, queue, filter)),
+            filter,
+            mipmaps: MipmapSettings::default(),
+        }
+    }
+
+    /// Gestionnaire sans carte graphique : seuls les texels décodés sont
+    /// conservés pour la rastérisation logicielle
+    pub fn software() -> Self {
+        Self {
+            textures: HashMap::new(),
+            palettes: HashMap::new(),
+            #[cfg(feature = "gui")]
+            gpu: None,
+            filter: TextureFilter::Linear,
+            mipmaps: MipmapSettings::default(),
+        }
+    }
+
+    /// Filtrage appliqué aux textures
+    pub fn filter(&self) -> TextureFilter {
+        self.filter
+    }
+
+    /// Change le filtrage ; les bind groups des textures chargées sont
+    /// recréés avec le nouveau sampler
+    pub fn set_filter(&mut self, filter: TextureFilter) {
+        if filter == self.filter {
+            return;
+        }
+
+        self.filter = filter;
+        #[cfg(feature = "gui")]
+        {
+            let Some(gpu) = self.gpu.as_mut() else {
+                return;
+            };
+            gpu.sampler = filter.create_sampler(&gpu.device, AddressMode::Repeat);
+            gpu.wrapped_samplers.clear();
+            for (id, texture) in self.textures.iter_mut() {
+                if let Some(gpu_texture) = texture.gpu.as_mut() {
+                    gpu_texture.bind_group = Self::create_bind_group(&gpu.device, &gpu.bind_group_layout, &gpu.sampler, *id, &gpu_texture.view);
+                }
+                texture.wrapped_bind_groups.clear();
+            }
+        }
+    }
+
+    /// Mipmaps appliquées aux textures
+    pub fn mipmaps(&self) -> MipmapSettings {
+        self.mipmaps
+    }
+
+    /// Active ou désactive les mipmaps ; les textures chargées sont
+    /// complétées ou réduites à leur niveau 0, et recopiées sur la carte
+    /// graphique
+    pub fn set_mipmaps(&mut self, mipmaps: MipmapSettings) {
+        let regenerate = mipmaps.enabled != self.mipmaps.enabled;
+        self.mipmaps = mipmaps;
+        if !regenerate {
+            return;
+        }
+
+        #[cfg_attr(not(feature = "gui"), allow(unused_variables))]
+        for (id, texture) in self.textures.iter_mut() {
+            texture.mipmaps = if mipmaps.enabled {
+                generate_mipmaps(texture.width, texture.height, &texture.pixels)
+            } else {
+                Vec::new()
+            };
+            #[cfg(feature = "gui")]
+            if let Some(gpu) = self.gpu.as_ref() {
+                gpu.make_resident(*id, texture);
+            }
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    fn create_bind_group(device: &Device, layout: &BindGroupLayout, sampler: &Sampler, id: u32, view: &TextureView) -> BindGroup {
+        device.create_bind_group(&BindGroupDescriptor {
+            label: Some(&format!("SEGA Texture {} Bind Group", id)),
+            layout,
             entries: &[
-                BindGroupLayoutEntry {
+                BindGroupEntry {
                     binding: 0,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: TextureViewDimension::D2,
-                        sample_type: TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
+                    resource: BindingResource::TextureView(view),
                 },
-                BindGroupLayoutEntry {
+                BindGroupEntry {
                     binding: 1,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
+                    resource: BindingResource::Sampler(sampler),
                 },
             ],
-        });
-        
-        // Créer le sampler avec paramètres SEGA Model 2
-        let sampler = device.create_sampler(&SamplerDescriptor {
-            address_mode_u: AddressMode::Repeat,
-            address_mode_v: AddressMode::Repeat,
-            address_mode_w: AddressMode::Repeat,
-            mag_filter: FilterMode::Linear,
-            min_filter: FilterMode::Linear,
-            mipmap_filter: FilterMode::Linear,
-            ..Default::default()
-        });
-        
-        Self {
-            textures: HashMap::new(),
-            palettes: HashMap::new(),
-            device,
-            queue,
-            bind_group_layout,
-            sampler,
-        }
+        })
     }
     
+    /// Rattache le cache à un nouveau device (après la perte du précédent) :
+    /// chaque texture est recopiée depuis ses texels en mémoire. Retourne le
+    /// nombre de textures rechargées
+    #[cfg(feature = "gui")]
+    pub fn reupload(&mut self, device: Arc<Device>, queue: Arc<Queue>) -> usize {
+        let gpu = TextureGpu::new(device, queue, self.filter);
+        for (id, texture) in self.textures.iter_mut() {
+            gpu.make_resident(*id, texture);
+        }
+        self.gpu = Some(gpu);
+        self.textures.len()
+    }
+
+    /// Détache le cache de la carte graphique (repli sur le rendu logiciel)
+    #[cfg(feature = "gui")]
+    pub fn detach_gpu(&mut self) {
+        self.gpu = None;
+        for texture in self.textures.values_mut() {
+            texture.detach_gpu();
+        }
+    }
+
+    /// Oublie les textures et palettes chargées par le jeu
+    pub fn clear(&mut self) {
+        self.textures.clear();
+        self.palettes.clear();
+    }
+
+    /// Charge une microtexture de détail (RGBA8)
+    pub fn load_microtexture(&mut self, index: u8, data: &[u8], width: u32, height: u32) -> Result<()> {
+        self.load_texture(microtexture_id(index), data, width, height)
+    }
+
     /// Charge une texture simple (pour compatibilité)
     pub fn load_texture(&mut self, id: u32, data: &[u8], width: u32, height: u32) -> Result<()> {
         // Crée une texture RGBA8 basique depuis les données brutes
@@ -151,71 +478,28 @@ impl TextureManager {
         // Convertir en RGBA8 pour wgpu
         let rgba_data = self.convert_to_rgba8(&raw_texture)?;
         
-        // Créer la texture wgpu
-        let texture = self.device.create_texture(&TextureDescriptor {
-            label: Some(&format!("SEGA Texture {}", id)),
-            size: Extent3d { 
-                width: raw_texture.width, 
-                height: raw_texture.height, 
-                depth_or_array_layers: 1 
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Rgba8UnormSrgb,
-            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-        
-        // Copier les données converties
-        self.queue.write_texture(
-            ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: Origin3d::ZERO,
-                aspect: TextureAspect::All,
-            },
-            &rgba_data,
-            ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * raw_texture.width),
-                rows_per_image: Some(raw_texture.height),
-            },
-            Extent3d { 
-                width: raw_texture.width, 
-                height: raw_texture.height, 
-                depth_or_array_layers: 1 
-            },
-        );
-        
-        // Créer une vue texture
-        let view = texture.create_view(&TextureViewDescriptor::default());
-        
-        // Créer le bind group avec la vraie layout
-        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
-            label: Some(&format!("SEGA Texture {} Bind Group", id)),
-            layout: &self.bind_group_layout,
-            entries: &[
-                BindGroupEntry {
-                    binding: 0,
-                    resource: BindingResource::TextureView(&view),
-                },
-                BindGroupEntry {
-                    binding: 1,
-                    resource: BindingResource::Sampler(&self.sampler),
-                },
-            ],
-        });
+        let mipmaps = if self.mipmaps.enabled {
+            generate_mipmaps(raw_texture.width, raw_texture.height, &rgba_data)
+        } else {
+            Vec::new()
+        };
+
+        // Copier la texture sur la carte graphique
+        #[cfg(feature = "gui")]
+        let gpu = self.gpu.as_ref().map(|gpu| gpu.upload(id, raw_texture.width, raw_texture.height, &rgba_data, &mipmaps));
         
         // Stocker la texture décodée avec tous les champs
         self.textures.insert(id, TextureData {
-            texture,
-            view,
-            bind_group,
+            #[cfg(feature = "gui")]
+            gpu,
             width: raw_texture.width,
             height: raw_texture.height,
             format: params.format,
             palette_id: params.palette_offset.map(|offset| offset as u32),
+            pixels: rgba_data,
+            mipmaps,
+            #[cfg(feature = "gui")]
+            wrapped_bind_groups: HashMap::new(),
         });
         
         Ok(())
@@ -225,53 +509,130 @@ impl TextureManager {
         self.textures.get(&id)
     }
 
+    pub fn get_texture_mut(&mut self, id: u32) -> Option<&mut TextureData> {
+        self.textures.get_mut(&id)
+    }
+
+    /// Textures chargées, pour les rendre résidentes sur un backend vidéo
+    pub fn textures_mut(&mut self) -> impl Iterator<Item = (u32, &mut TextureData)> {
+        self.textures.iter_mut().map(|(id, texture)| (*id, texture))
+    }
+
+    /// Textures chargées, dans un ordre quelconque
+    pub fn textures(&self) -> impl Iterator<Item = (u32, &TextureData)> {
+        self.textures.iter().map(|(id, texture)| (*id, texture))
+    }
+
+    /// Palettes chargées par le jeu, dans un ordre quelconque
+    pub fn palettes(&self) -> impl Iterator<Item = (u32, &PaletteData)> {
+        self.palettes.iter().map(|(id, palette)| (*id, palette))
+    }
+
+    /// Couleurs appliquées aux textures indexées : celles de la palette
+    /// chargée, ou la palette par défaut (256 couleurs)
+    pub fn palette_colors(&self, palette_id: Option<u32>) -> Vec<[u8; 4]> {
+        match palette_id.and_then(|id| self.palettes.get(&id)) {
+            Some(palette) => palette.colors.clone(),
+            None => (0..=255).map(|index| self.get_palette_color(index, 0)).collect(),
+        }
+    }
+
+    /// Occupation du cache
+    pub fn stats(&self) -> TextureCacheStats {
+        let mut stats = TextureCacheStats::default();
+        for (id, texture) in &self.textures {
+            if *id >= MICROTEXTURE_BASE_ID {
+                stats.microtextures += 1;
+            } else {
+                stats.textures += 1;
+            }
+            stats.bytes += texture.pixels.len() + texture.mipmaps.iter().map(|level| level.rgba.len()).sum::<usize>();
+            #[cfg(feature = "gui")]
+            {
+                stats.wrapped_bind_groups += texture.wrapped_bind_groups.len();
+            }
+        }
+        stats
+    }
+
+    #[cfg(feature = "gui")]
     pub fn get_bind_group(&self, texture_id: u32) -> Option<&BindGroup> {
-        self.textures.get(&texture_id).map(|tex| &tex.bind_group)
+        self.textures.get(&texture_id).and_then(|tex| tex.gpu.as_ref()).map(|gpu| &gpu.bind_group)
+    }
+
+    /// Bind group de la texture avec l'adressage `[u, v]` d'un polygone,
+    /// créé à la première demande
+    #[cfg(feature = "gui")]
+    pub fn wrapped_bind_group(&mut self, texture_id: u32, wrap: [TextureWrap; 2]) -> Option<&BindGroup> {
+        if wrap == [TextureWrap::Repeat; 2] {
+            return self.get_bind_group(texture_id);
+        }
+
+        let gpu = self.gpu.as_mut()?;
+        let texture = self.textures.get_mut(&texture_id)?;
+        let view = &texture.gpu.as_ref()?.view;
+        let sampler = gpu.wrapped_samplers.entry(wrap).or_insert_with(|| {
+            self.filter.create_wrapped_sampler(&gpu.device, wrap[0].address_mode(), wrap[1].address_mode())
+        });
+        let bind_group = texture.wrapped_bind_groups.entry(wrap).or_insert_with(|| {
+            Self::create_bind_group(&gpu.device, &gpu.bind_group_layout, sampler, texture_id, view)
+        });
+        Some(bind_group)
     }
 
     /// Décode une texture SEGA depuis les données ROM
     fn decode_sega_texture(&self, rom_data: &[u8], params: &TextureDecodeParams) -> Result<RawTexture> {
-        let data_start = params.data_offset;
-        let texture_data = &rom_data[data_start..];
+        let rows = Self::texture_rows(rom_data, params)?;
 
         match params.format {
             SegaTextureFormat::Palette4bpp => {
-                self.decode_4bpp_indexed(texture_data, params)
+                self.decode_4bpp_indexed(&rows, params)
             }
             SegaTextureFormat::Palette8bpp => {
-                self.decode_8bpp_indexed(texture_data, params)
+                self.decode_8bpp_indexed(&rows, params)
             }
 
             SegaTextureFormat::Rgb565 => {
-                self.decode_rgb565(texture_data, params)
+                self.decode_rgb565(&rows, params)
             }
             SegaTextureFormat::Rgba4444 => {
-                self.decode_rgba4444(texture_data, params)
+                self.decode_rgba4444(&rows, params)
             }
             SegaTextureFormat::Rgba8888 => {
-                self.decode_rgba8888(texture_data, params)
+                self.decode_rgba8888(&rows, params)
             }
         }
     }
 
+    /// Octets utiles de chaque ligne, espacées de `row_pitch` octets
+    fn texture_rows<'a>(rom_data: &'a [u8], params: &TextureDecodeParams) -> Result<Vec<&'a [u8]>> {
+        let row_bytes = params.row_bytes();
+        let pitch = params.row_pitch();
+        if pitch < row_bytes {
+            return Err(GpuError::TextureDecode(format!(
+                "pas de {} octets pour des lignes de {} octets", pitch, row_bytes
+            )).into());
+        }
+
+        (0..params.height as usize)
+            .map(|y| {
+                let start = params.data_offset + y * pitch;
+                rom_data.get(start..start + row_bytes).ok_or_else(|| {
+                    GpuError::TextureDecode(format!(
+                        "ligne {} hors des données (0x{:X} + {} > 0x{:X})", y, start, row_bytes, rom_data.len()
+                    )).into()
+                })
+            })
+            .collect()
+    }
+
     /// Décode texture 4bpp indexée avec palette
-    fn decode_4bpp_indexed(&self, data: &[u8], params: &TextureDecodeParams) -> Result<RawTexture> {
-        let pixel_count = (params.width * params.height) as usize;
-        let mut pixels = Vec::with_capacity(pixel_count);
+    fn decode_4bpp_indexed(&self, rows: &[&[u8]], params: &TextureDecodeParams) -> Result<RawTexture> {
+        let mut pixels = Vec::with_capacity((params.width * params.height) as usize);
 
-        for i in 0..(pixel_count + 1) / 2 {
-            if i >= data.len() {
-                break;
-            }
-            
-            let byte = data[i];
-            let pixel1 = (byte & 0x0F) as u8;        // 4 bits inférieurs
-            let pixel2 = ((byte & 0xF0) >> 4) as u8; // 4 bits supérieurs
-            
-            pixels.push(pixel1);
-            if pixels.len() < pixel_count {
-                pixels.push(pixel2);
-            }
+        for row in rows {
+            // Texel pair dans les 4 bits inférieurs, impair dans les supérieurs
+            pixels.extend((0..params.width as usize).map(|x| (row[x / 2] >> (4 * (x & 1))) & 0x0F));
         }
 
         Ok(RawTexture {
@@ -284,66 +645,40 @@ impl TextureManager {
     }
 
     /// Décode texture 8bpp indexée avec palette
-    fn decode_8bpp_indexed(&self, data: &[u8], params: &TextureDecodeParams) -> Result<RawTexture> {
-        let pixel_count = (params.width * params.height) as usize;
-        let pixels = data[..pixel_count.min(data.len())].to_vec();
-
+    fn decode_8bpp_indexed(&self, rows: &[&[u8]], params: &TextureDecodeParams) -> Result<RawTexture> {
         Ok(RawTexture {
             width: params.width,
             height: params.height,
             format: params.format,
-            data: pixels,
+            data: rows.concat(),
             palette_id: params.palette_offset.map(|offset| offset as u32),
         })
     }
 
-    /// Décode texture 16bpp directe
-    fn decode_16bpp_direct(&self, data: &[u8], params: &TextureDecodeParams) -> Result<RawTexture> {
-        let pixel_count = (params.width * params.height) as usize;
-        let mut pixels = Vec::with_capacity(pixel_count * 2);
-
-        for i in 0..pixel_count {
-            let byte_idx = i * 2;
-            if byte_idx + 1 < data.len() {
-                let lo = data[byte_idx];
-                let hi = data[byte_idx + 1];
-                pixels.push(lo);
-                pixels.push(hi);
-            }
-        }
-
+    /// Décode texture directe (16 ou 32 bits), lignes mises bout à bout
+    fn decode_direct(&self, rows: &[&[u8]], params: &TextureDecodeParams) -> Result<RawTexture> {
         Ok(RawTexture {
             width: params.width,
             height: params.height,
             format: params.format,
-            data: pixels,
+            data: rows.concat(),
             palette_id: None, // Pas de palette pour les formats directs
         })
     }
 
     /// Décode texture RGB565
-    fn decode_rgb565(&self, data: &[u8], params: &TextureDecodeParams) -> Result<RawTexture> {
-        self.decode_16bpp_direct(data, params)
+    fn decode_rgb565(&self, rows: &[&[u8]], params: &TextureDecodeParams) -> Result<RawTexture> {
+        self.decode_direct(rows, params)
     }
 
     /// Décode texture RGBA4444
-    fn decode_rgba4444(&self, data: &[u8], params: &TextureDecodeParams) -> Result<RawTexture> {
-        self.decode_16bpp_direct(data, params)
+    fn decode_rgba4444(&self, rows: &[&[u8]], params: &TextureDecodeParams) -> Result<RawTexture> {
+        self.decode_direct(rows, params)
     }
 
     /// Décode texture RGBA8888 directe
-    fn decode_rgba8888(&self, data: &[u8], params: &TextureDecodeParams) -> Result<RawTexture> {
-        let pixel_count = (params.width * params.height) as usize;
-        let byte_count = pixel_count * 4; // 4 bytes par pixel RGBA
-        let pixels = data[..byte_count.min(data.len())].to_vec();
-
-        Ok(RawTexture {
-            width: params.width,
-            height: params.height,
-            format: params.format,
-            data: pixels,
-            palette_id: None, // Pas de palette pour les formats directs
-        })
+    fn decode_rgba8888(&self, rows: &[&[u8]], params: &TextureDecodeParams) -> Result<RawTexture> {
+        self.decode_direct(rows, params)
     }
 
     /// Convertit une texture décodée en RGBA8 pour wgpu
@@ -363,7 +698,7 @@ impl TextureManager {
                 // Conversion RGB565 -> RGBA8
                 for chunk in raw_texture.data.chunks(2) {
                     if chunk.len() == 2 {
-                        let rgb565 = u16::from_le_bytes([chunk[0], chunk[1]]);
+                        let rgb565 = ByteOrder::Little.read_u16(chunk, 0).unwrap_or_default();
                         let r = ((rgb565 >> 11) & 0x1F) as u8;
                         let g = ((rgb565 >> 5) & 0x3F) as u8;
                         let b = (rgb565 & 0x1F) as u8;
@@ -380,7 +715,7 @@ impl TextureManager {
                 // Conversion RGBA4444 -> RGBA8
                 for chunk in raw_texture.data.chunks(2) {
                     if chunk.len() == 2 {
-                        let rgba4444 = u16::from_le_bytes([chunk[0], chunk[1]]);
+                        let rgba4444 = ByteOrder::Little.read_u16(chunk, 0).unwrap_or_default();
                         let r = ((rgba4444 >> 12) & 0x0F) as u8;
                         let g = ((rgba4444 >> 8) & 0x0F) as u8;
                         let b = ((rgba4444 >> 4) & 0x0F) as u8;
@@ -0,0 +1,147 @@
+//! Export des textures et palettes chargées en PNG
+//!
+//! [`dump_textures`] écrit chaque texture du cache telle qu'elle est
+//! décodée (RGBA8) dans `texture_<id>_<empreinte>.png`, et chaque palette
+//! en nuancier de 16 couleurs par ligne. L'empreinte ne dépend que des
+//! texels : une texture déjà exportée n'est pas réécrite, et deux textures
+//! identiques chargées sous des identifiants différents se repèrent au nom
+//! de leur fichier. Ces fichiers servent à vérifier le décodage et de base
+//! aux packs de textures de remplacement.
+
+use image::RgbaImage;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::texture::TextureManager;
+use crate::error::{GpuError, Result};
+
+/// Couleurs par ligne d'un nuancier
+const SWATCH_COLUMNS: u32 = 16;
+
+/// Côté d'une case de nuancier, en pixels
+const SWATCH_SIZE: u32 = 16;
+
+/// Fichiers produits par un export
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextureDumpReport {
+    pub directory: PathBuf,
+
+    /// Textures écrites
+    pub textures: usize,
+
+    /// Textures déjà présentes dans le dossier
+    pub existing: usize,
+
+    /// Nuanciers écrits
+    pub palettes: usize,
+}
+
+impl fmt::Display for TextureDumpReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} textures exportées ({} déjà présentes), {} palettes dans {}",
+            self.textures, self.existing, self.palettes, self.directory.display()
+        )
+    }
+}
+
+/// Empreinte des texels : SHA-256 des dimensions et des pixels, tronqué à
+/// 64 bits
+pub fn texture_hash(width: u32, height: u32, rgba: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(width.to_le_bytes());
+    hasher.update(height.to_le_bytes());
+    hasher.update(rgba);
+    hasher.finalize()[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Nuancier d'une palette : cases de 16x16 pixels, 16 par ligne
+pub fn palette_swatches(colors: &[[u8; 4]]) -> RgbaImage {
+    let rows = (colors.len() as u32).div_ceil(SWATCH_COLUMNS).max(1);
+    RgbaImage::from_fn(SWATCH_COLUMNS * SWATCH_SIZE, rows * SWATCH_SIZE, |x, y| {
+        let index = (y / SWATCH_SIZE * SWATCH_COLUMNS + x / SWATCH_SIZE) as usize;
+        image::Rgba(colors.get(index).copied().unwrap_or([0; 4]))
+    })
+}
+
+fn save(image: &RgbaImage, path: &Path) -> Result<()> {
+    image.save(path).map_err(|e| GpuError::TextureDump(format!("{} : {}", path.display(), e)).into())
+}
+
+/// Exporte les textures et palettes de `manager` dans `directory`, créé au
+/// besoin
+pub fn dump_textures(manager: &TextureManager, directory: &Path) -> Result<TextureDumpReport> {
+    std::fs::create_dir_all(directory)?;
+    let mut report = TextureDumpReport { directory: directory.to_path_buf(), ..TextureDumpReport::default() };
+
+    let mut palette_ids = Vec::new();
+    for (id, texture) in manager.textures() {
+        let hash = texture_hash(texture.width, texture.height, &texture.pixels);
+        let path = directory.join(format!("texture_{:08x}_{}.png", id, hash));
+        if path.exists() {
+            report.existing += 1;
+        } else {
+            let image = RgbaImage::from_raw(texture.width, texture.height, texture.pixels.clone())
+                .ok_or_else(|| GpuError::TextureDump(format!("texture {:#x} : texels incomplets", id)))?;
+            save(&image, &path)?;
+            report.textures += 1;
+        }
+        if texture.format.is_indexed() {
+            palette_ids.push(texture.palette_id);
+        }
+    }
+
+    // Palettes chargées, et celle des textures indexées qui n'en ont pas
+    palette_ids.extend(manager.palettes().map(|(id, _)| Some(id)));
+    palette_ids.sort_unstable();
+    palette_ids.dedup();
+    for palette_id in palette_ids {
+        let name = palette_id.map_or_else(|| "palette_default.png".to_string(), |id| format!("palette_{:08x}.png", id));
+        save(&palette_swatches(&manager.palette_colors(palette_id)), &directory.join(name))?;
+        report.palettes += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::{SegaTextureFormat, TextureDecodeParams};
+
+    #[test]
+    fn test_dump_textures_and_palettes_to_png() {
+        let mut manager = TextureManager::software();
+        let rgba: Vec<u8> = (0..2 * 3 * 4).map(|i| i as u8 * 10).collect();
+        manager.load_texture(7, &rgba, 2, 3).unwrap();
+        let params = TextureDecodeParams {
+            width: 4,
+            height: 2,
+            format: SegaTextureFormat::Palette4bpp,
+            palette_offset: None,
+            data_offset: 0,
+            stride: None,
+        };
+        manager.load_texture_from_rom(8, &[0x10, 0x32, 0x54, 0x76], params).unwrap();
+
+        let directory = std::env::temp_dir().join(format!("pm2_texture_dump_{}", std::process::id()));
+        let report = dump_textures(&manager, &directory).unwrap();
+        assert_eq!((report.textures, report.existing, report.palettes), (2, 0, 1));
+
+        let path = directory.join(format!("texture_00000007_{}.png", texture_hash(2, 3, &rgba)));
+        let image = image::open(&path).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (2, 3));
+        assert_eq!(image.into_raw(), rgba);
+
+        // Nuancier de la palette par défaut : 256 couleurs, 16 lignes
+        let swatches = image::open(directory.join("palette_default.png")).unwrap().to_rgba8();
+        assert_eq!(swatches.dimensions(), (256, 256));
+        assert_eq!(swatches.get_pixel(SWATCH_SIZE + 1, 0).0, manager.palette_colors(None)[1]);
+
+        // Un second export ne réécrit pas les textures
+        let report = dump_textures(&manager, &directory).unwrap();
+        assert_eq!((report.textures, report.existing), (0, 2));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,479 @@
+//! Abstraction des sorties vidéo
+//!
+//! [`Model2Gpu`](super::Model2Gpu) traite les commandes graphiques et
+//! rastérise l'image par le CPU dans tous les cas ; il la confie ensuite à un
+//! [`VideoBackend`]. Trois implémentations existent :
+//! - [`WgpuBackend`] (feature `gui`) : blit par la carte graphique, avec
+//!   recréation du device perdu et repli logiciel ;
+//! - [`SoftwareBackend`] (feature `gui`) : copie dans la fenêtre par le CPU ;
+//! - [`NullVideoBackend`] : aucun affichage, l'hôte lit lui-même le
+//!   framebuffer (tests, serveurs, bibliothèque embarquée).
+//!
+//! Tous les chemins rastérisent exactement la même image : seul l'affichage
+//! change, ce qui garde les captures et les tests identiques avec ou sans
+//! carte graphique.
+
+use crate::config::{ColorAdjustment, VsyncMode};
+use crate::error::Result;
+
+use super::framebuffer::Framebuffer;
+use super::output::OutputTransform;
+use super::overlay::SimpleVertex;
+use super::texture::TextureData;
+use super::TextureFilter;
+
+#[cfg(feature = "gui")]
+use super::recovery::DeviceRecovery;
+#[cfg(feature = "gui")]
+use super::renderer::{RendererSettings, WgpuRenderer};
+#[cfg(feature = "gui")]
+use super::software::SoftwarePresenter;
+#[cfg(feature = "gui")]
+use super::texture::TextureGpu;
+#[cfg(feature = "gui")]
+use super::AdapterSelection;
+#[cfg(feature = "gui")]
+use crate::clock::Instant;
+#[cfg(feature = "gui")]
+use crate::error::{EmulatorError, GpuError};
+#[cfg(feature = "gui")]
+use std::sync::Arc;
+
+/// Changement d'état d'un backend, relevé par le GPU après une présentation
+pub enum BackendChange {
+    /// Le device a été recréé : les textures sont à recharger
+    DeviceRecreated,
+    /// Le backend est abandonné au profit d'un autre (repli logiciel)
+    Replaced(Box<dyn VideoBackend>),
+}
+
+/// Sortie vidéo recevant l'image rastérisée par l'émulation
+pub trait VideoBackend {
+    /// Nom du backend (journalisation, diagnostics)
+    fn name(&self) -> &str;
+
+    /// Affiche le framebuffer, puis les triangles reçus par
+    /// [`draw_batch`](Self::draw_batch) depuis la présentation précédente
+    fn present_frame(&mut self, framebuffer: &Framebuffer) -> Result<()>;
+
+    /// Rend une texture décodée résidente sur le backend ; les backends sans
+    /// carte graphique lisent les texels en mémoire et n'ont rien à faire
+    fn upload_texture(&mut self, id: u32, texture: &mut TextureData);
+
+    /// Ajoute des triangles de la couche d'incrustation (viseurs, cibles) à
+    /// la prochaine présentation
+    fn draw_batch(&mut self, vertices: &[SimpleVertex]);
+
+    /// L'image est affichée sans carte graphique
+    fn is_software(&self) -> bool {
+        false
+    }
+
+    /// Suit la taille de la fenêtre en pixels
+    fn resize(&mut self, _width: u32, _height: u32) {}
+
+    /// Taille de la surface d'affichage, `None` sans fenêtre
+    fn surface_size(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    /// Change le format, l'échelle ou la rotation de l'image
+    fn set_output_transform(&mut self, _output: OutputTransform) {}
+
+    /// Change les réglages du moniteur
+    fn set_color_adjustment(&mut self, _color: ColorAdjustment) {}
+
+    /// Change le filtrage des textures et de l'image ; les textures sont
+    /// ensuite rechargées par le GPU
+    fn set_texture_filter(&mut self, _filter: TextureFilter) {}
+
+    /// Change le mode de présentation ; sans effet pour les backends qui ne
+    /// se synchronisent pas sur l'écran
+    fn set_vsync(&mut self, _vsync: VsyncMode) {}
+
+    /// Latence de présentation estimée en millisecondes
+    fn present_latency_ms(&self) -> f32 {
+        0.0
+    }
+
+    /// Devices recréés avec succès depuis l'ouverture
+    fn device_recoveries(&self) -> u32 {
+        0
+    }
+
+    /// Changement survenu pendant la dernière présentation
+    fn take_change(&mut self) -> Option<BackendChange> {
+        None
+    }
+}
+
+/// Backend sans affichage : les présentations sont comptées, l'image reste
+/// dans le framebuffer et l'incrustation est ignorée
+#[derive(Debug, Clone, Default)]
+pub struct NullVideoBackend {
+    presented_frames: u64,
+}
+
+impl NullVideoBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Images présentées depuis l'ouverture
+    pub fn presented_frames(&self) -> u64 {
+        self.presented_frames
+    }
+}
+
+impl VideoBackend for NullVideoBackend {
+    fn name(&self) -> &str {
+        "null"
+    }
+
+    fn present_frame(&mut self, _framebuffer: &Framebuffer) -> Result<()> {
+        self.presented_frames += 1;
+        Ok(())
+    }
+
+    fn upload_texture(&mut self, _id: u32, _texture: &mut TextureData) {}
+
+    fn draw_batch(&mut self, _vertices: &[SimpleVertex]) {}
+}
+
+/// Blit par la carte graphique avec wgpu. Un device perdu est recréé
+/// (l'image est alors sautée) au lieu d'interrompre l'émulation ; après trop
+/// d'échecs, l'affichage passe en logiciel
+#[cfg(feature = "gui")]
+pub struct WgpuBackend {
+    /// Rendu courant, absent tant que le device perdu n'est pas recréé
+    renderer: Option<Box<WgpuRenderer>>,
+
+    /// Copie des textures sur le device courant
+    textures: Option<TextureGpu>,
+
+    /// Triangles d'incrustation de la prochaine image
+    batch: Vec<SimpleVertex>,
+
+    /// Tentatives de recréation du device après sa perte
+    recovery: DeviceRecovery,
+
+    /// Rendu wgpu perdu, en attente de recréation
+    lost_renderer: Option<RendererSettings>,
+
+    change: Option<BackendChange>,
+}
+
+#[cfg(feature = "gui")]
+impl WgpuBackend {
+    pub async fn new(window: Arc<winit::window::Window>, vsync: crate::config::VsyncMode, selection: &AdapterSelection) -> Result<Self> {
+        let renderer = WgpuRenderer::new(window, vsync, selection).await?;
+        Ok(Self::from_renderer(renderer))
+    }
+
+    pub fn from_renderer(renderer: WgpuRenderer) -> Self {
+        let textures = TextureGpu::new(renderer.device.clone(), renderer.queue.clone(), renderer.texture_filter);
+        Self {
+            renderer: Some(Box::new(renderer)),
+            textures: Some(textures),
+            batch: Vec::new(),
+            recovery: DeviceRecovery::default(),
+            lost_renderer: None,
+            change: None,
+        }
+    }
+
+    /// Rendu courant, `None` pendant la recréation du device
+    pub fn renderer(&self) -> Option<&WgpuRenderer> {
+        self.renderer.as_deref()
+    }
+
+    /// Recrée le rendu perdu si la politique de reprise le permet ; après
+    /// trop d'échecs, l'image passe en logiciel
+    fn recover_device(&mut self) {
+        let Some(settings) = self.lost_renderer.as_ref() else {
+            return;
+        };
+        let now = Instant::now();
+        if !self.recovery.may_attempt(now) {
+            return;
+        }
+
+        match reopen_renderer(settings) {
+            Ok(renderer) => {
+                self.recovery.record_attempt(now, true);
+                log::info!("Rendu recréé sur un nouveau device");
+                self.textures = Some(TextureGpu::new(renderer.device.clone(), renderer.queue.clone(), renderer.texture_filter));
+                self.renderer = Some(Box::new(renderer));
+                self.lost_renderer = None;
+                self.change = Some(BackendChange::DeviceRecreated);
+            }
+            Err(e) => {
+                self.recovery.record_attempt(now, false);
+                log::warn!("Recréation du rendu impossible: {}", e);
+                if self.recovery.exhausted() {
+                    let Some(settings) = self.lost_renderer.take() else {
+                        return;
+                    };
+                    let fallback: Box<dyn VideoBackend> = match SoftwareBackend::new(settings.window.clone()) {
+                        Ok(mut backend) => {
+                            log::warn!("Carte graphique abandonnée, affichage logiciel");
+                            backend.set_output_transform(settings.output);
+                            backend.set_color_adjustment(settings.color);
+                            Box::new(backend)
+                        }
+                        Err(e) => {
+                            log::error!("Affichage logiciel impossible ({}), plus d'affichage", e);
+                            Box::new(NullVideoBackend::new())
+                        }
+                    };
+                    self.change = Some(BackendChange::Replaced(fallback));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl VideoBackend for WgpuBackend {
+    fn name(&self) -> &str {
+        "wgpu"
+    }
+
+    fn present_frame(&mut self, framebuffer: &Framebuffer) -> Result<()> {
+        if self.lost_renderer.is_some() {
+            self.recover_device();
+        }
+        let Some(renderer) = self.renderer.as_mut() else {
+            self.batch.clear();
+            return Ok(());
+        };
+
+        let result = renderer.render_frame(framebuffer, &self.batch);
+        self.batch.clear();
+        match result {
+            Err(EmulatorError::GpuError(GpuError::DeviceLost(reason))) => {
+                log::error!("Carte graphique perdue ({}), recréation du rendu", reason);
+                // Libérer la surface avant d'en créer une autre sur la fenêtre
+                if let Some(renderer) = self.renderer.take() {
+                    self.lost_renderer = Some(renderer.settings());
+                }
+                self.textures = None;
+                self.recover_device();
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn upload_texture(&mut self, id: u32, texture: &mut TextureData) {
+        match self.textures.as_ref() {
+            Some(textures) => textures.make_resident(id, texture),
+            None => texture.detach_gpu(),
+        }
+    }
+
+    fn draw_batch(&mut self, vertices: &[SimpleVertex]) {
+        self.batch.extend_from_slice(vertices);
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.resize(winit::dpi::PhysicalSize::new(width, height));
+        }
+    }
+
+    fn surface_size(&self) -> Option<(u32, u32)> {
+        self.renderer.as_ref().map(|renderer| (renderer.surface_config.width, renderer.surface_config.height))
+    }
+
+    fn set_output_transform(&mut self, output: OutputTransform) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.set_output_transform(output);
+        }
+    }
+
+    fn set_color_adjustment(&mut self, color: ColorAdjustment) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.set_color_adjustment(color);
+        }
+    }
+
+    fn set_texture_filter(&mut self, filter: TextureFilter) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.set_texture_filter(filter);
+            self.textures = Some(TextureGpu::new(renderer.device.clone(), renderer.queue.clone(), filter));
+        }
+    }
+
+    fn set_vsync(&mut self, vsync: VsyncMode) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.set_vsync(vsync);
+        }
+        // Un rendu recréé garde le mode choisi pendant la perte
+        if let Some(settings) = self.lost_renderer.as_mut() {
+            settings.vsync = vsync;
+        }
+    }
+
+    fn present_latency_ms(&self) -> f32 {
+        self.renderer.as_ref().map_or(0.0, |renderer| renderer.estimated_present_latency_ms())
+    }
+
+    fn device_recoveries(&self) -> u32 {
+        self.recovery.recoveries()
+    }
+
+    fn take_change(&mut self) -> Option<BackendChange> {
+        self.change.take()
+    }
+}
+
+/// Ouvre de façon synchrone le rendu qui remplace un rendu perdu
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+fn reopen_renderer(settings: &RendererSettings) -> Result<WgpuRenderer> {
+    pollster::block_on(WgpuRenderer::from_settings(settings))
+}
+
+/// Un navigateur ne permet pas d'attendre l'ouverture d'un device : le repli
+/// logiciel est pris dès que les tentatives sont épuisées
+#[cfg(all(feature = "gui", target_arch = "wasm32"))]
+fn reopen_renderer(_settings: &RendererSettings) -> Result<WgpuRenderer> {
+    Err(GpuError::Device("recréation synchrone impossible dans un navigateur".to_string()).into())
+}
+
+/// Copie par le CPU, quand aucune carte graphique n'est utilisable
+#[cfg(feature = "gui")]
+pub struct SoftwareBackend {
+    presenter: SoftwarePresenter,
+    batch: Vec<SimpleVertex>,
+}
+
+#[cfg(feature = "gui")]
+impl SoftwareBackend {
+    pub fn new(window: Arc<winit::window::Window>) -> Result<Self> {
+        Ok(Self { presenter: SoftwarePresenter::new(window)?, batch: Vec::new() })
+    }
+}
+
+#[cfg(feature = "gui")]
+impl VideoBackend for SoftwareBackend {
+    fn name(&self) -> &str {
+        "logiciel"
+    }
+
+    fn present_frame(&mut self, framebuffer: &Framebuffer) -> Result<()> {
+        let result = self.presenter.render_frame(framebuffer, &self.batch);
+        self.batch.clear();
+        result
+    }
+
+    fn upload_texture(&mut self, _id: u32, texture: &mut TextureData) {
+        texture.detach_gpu();
+    }
+
+    fn draw_batch(&mut self, vertices: &[SimpleVertex]) {
+        self.batch.extend_from_slice(vertices);
+    }
+
+    fn is_software(&self) -> bool {
+        true
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.presenter.resize(winit::dpi::PhysicalSize::new(width, height));
+    }
+
+    fn surface_size(&self) -> Option<(u32, u32)> {
+        Some(self.presenter.surface_size())
+    }
+
+    fn set_output_transform(&mut self, output: OutputTransform) {
+        self.presenter.output = output;
+    }
+
+    fn set_color_adjustment(&mut self, color: ColorAdjustment) {
+        self.presenter.color = color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::Model2Gpu;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Relève les appels reçus du GPU
+    #[derive(Default)]
+    struct Calls {
+        presented: u32,
+        vertices: Vec<usize>,
+        uploads: Vec<u32>,
+        filters: Vec<TextureFilter>,
+    }
+
+    struct RecordingBackend(Rc<RefCell<Calls>>);
+
+    impl VideoBackend for RecordingBackend {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn present_frame(&mut self, _framebuffer: &Framebuffer) -> Result<()> {
+            self.0.borrow_mut().presented += 1;
+            Ok(())
+        }
+
+        fn upload_texture(&mut self, id: u32, _texture: &mut TextureData) {
+            self.0.borrow_mut().uploads.push(id);
+        }
+
+        fn draw_batch(&mut self, vertices: &[SimpleVertex]) {
+            self.0.borrow_mut().vertices.push(vertices.len());
+        }
+
+        fn set_texture_filter(&mut self, filter: TextureFilter) {
+            self.0.borrow_mut().filters.push(filter);
+        }
+    }
+
+    #[test]
+    fn test_gpu_drives_backend() {
+        let calls = Rc::new(RefCell::new(Calls::default()));
+        let mut gpu = Model2Gpu::with_backend(Box::new(RecordingBackend(calls.clone())));
+        assert_eq!(gpu.backend().name(), "test");
+        // Sans fenêtre, la surface a la taille native de l'image
+        let (width, height) = gpu.framebuffer.native_size();
+        assert_eq!(gpu.surface_aspect(), width as f32 / height as f32);
+
+        gpu.load_texture(7, &[0xFF; 16], 2, 2).unwrap();
+        gpu.load_microtexture(1, &[0x80; 16], 2, 2).unwrap();
+        assert_eq!(calls.borrow().uploads, [7, crate::gpu::microtexture_id(1)]);
+
+        // L'incrustation accompagne chaque image jusqu'à son remplacement
+        let vertex = SimpleVertex::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0);
+        gpu.set_overlay(vec![vertex; 3]);
+        for _ in 0..2 {
+            gpu.begin_frame().unwrap();
+            gpu.end_frame().unwrap();
+        }
+        gpu.set_overlay(Vec::new());
+        gpu.begin_frame().unwrap();
+        gpu.end_frame().unwrap();
+        assert_eq!(calls.borrow().presented, 3);
+        assert_eq!(calls.borrow().vertices, [3, 3]);
+
+        // Un nouveau filtrage recharge toutes les textures
+        calls.borrow_mut().uploads.clear();
+        gpu.set_texture_filter(TextureFilter::Nearest);
+        assert_eq!(calls.borrow().filters, [TextureFilter::Nearest]);
+        let mut uploads = calls.borrow().uploads.clone();
+        uploads.sort();
+        assert_eq!(uploads, [7, crate::gpu::microtexture_id(1)]);
+
+        let mut headless = Model2Gpu::headless();
+        assert_eq!(headless.backend().name(), "null");
+        assert!(!headless.is_software());
+        headless.end_frame().unwrap();
+    }
+}
@@ -0,0 +1,293 @@
+//! Lanceur : choix du jeu parmi ceux de la base
+//!
+//! Affiché au démarrage sans ROM, puis avec F1 en cours de partie. Chaque
+//! jeu est une vignette (jaquette `<jeu>.png` du répertoire des jaquettes,
+//! sinon un aplat) avec un bandeau d'état tiré de l'audit des ROMs et du
+//! rapport de compatibilité. Les flèches choisissent le jeu, Entrée le
+//! lance et Échap revient au jeu en cours. La sélection (état, version de
+//! la dernière vérification, défauts connus) est décrite au-dessus de la
+//! grille et dans la console.
+
+use std::path::Path;
+use winit::keyboard::KeyCode;
+
+use crate::gpu::overlay::{GameTile, GRID_COLUMNS};
+use crate::input::InputState;
+use crate::rom::{AuditStatus, CompatibilityEntry, CompatibilityReport, CompatibilityStatus, GameAudit};
+
+/// Taille des jaquettes réduites pour l'incrustation
+const BOX_ART_SIZE: (u32, u32) = (24, 32);
+
+/// Jaquette réduite, en RGBA 8 bits
+#[derive(Debug, Clone)]
+pub struct BoxArt {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl BoxArt {
+    /// Charge et réduit une image ; `None` si elle est absente ou illisible
+    pub fn load(path: &Path) -> Option<Self> {
+        if !path.exists() {
+            return None;
+        }
+        match image::open(path) {
+            Ok(image) => {
+                let (width, height) = BOX_ART_SIZE;
+                let rgba = image.thumbnail_exact(width, height).to_rgba8();
+                Some(Self { width, height, rgba: rgba.into_raw() })
+            }
+            Err(e) => {
+                eprintln!("Jaquette illisible {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+}
+
+/// Jeu proposé par le lanceur
+#[derive(Debug, Clone)]
+pub struct LauncherEntry {
+    pub audit: GameAudit,
+    pub compatibility: CompatibilityEntry,
+    pub box_art: Option<BoxArt>,
+}
+
+impl LauncherEntry {
+    /// Couleur du bandeau d'état : ROMs d'abord, puis compatibilité
+    fn status_color(&self) -> [f32; 3] {
+        match self.audit.status() {
+            AuditStatus::Missing => [0.35, 0.35, 0.35],
+            AuditStatus::Incomplete => [0.85, 0.2, 0.15],
+            AuditStatus::Complete => match self.compatibility.status {
+                CompatibilityStatus::Perfect | CompatibilityStatus::Playable => [0.2, 0.8, 0.25],
+                CompatibilityStatus::InGame => [0.7, 0.8, 0.2],
+                CompatibilityStatus::Boots => [0.95, 0.65, 0.1],
+                CompatibilityStatus::Broken => [0.85, 0.2, 0.15],
+                CompatibilityStatus::Untested => [0.3, 0.5, 0.9],
+            },
+        }
+    }
+
+    /// Aplat propre au jeu, dérivé de son nom, en l'absence de jaquette
+    fn placeholder_color(&self) -> [f32; 3] {
+        let hash = self.audit.short_name.bytes().fold(0x811C_9DC5u32, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+        });
+        let channel = |shift: u32| 0.2 + ((hash >> shift) & 0xFF) as f32 / 255.0 * 0.5;
+        [channel(0), channel(8), channel(16)]
+    }
+}
+
+/// Action demandée depuis le lanceur
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LauncherAction {
+    /// Lancer le jeu (nom court)
+    Launch(String),
+    /// Revenir au jeu en cours
+    Close,
+}
+
+/// Grille des jeux de la base
+#[derive(Debug, Clone, Default)]
+pub struct Launcher {
+    entries: Vec<LauncherEntry>,
+    selected: usize,
+}
+
+impl Launcher {
+    pub fn new(audits: Vec<GameAudit>, report: &CompatibilityReport, boxart_dir: &Path) -> Self {
+        let entries = audits
+            .into_iter()
+            .map(|audit| LauncherEntry {
+                compatibility: report.entry(&audit.short_name).cloned().unwrap_or_default(),
+                box_art: BoxArt::load(&boxart_dir.join(format!("{}.png", audit.short_name))),
+                audit,
+            })
+            .collect();
+        Self { entries, selected: 0 }
+    }
+
+    pub fn entries(&self) -> &[LauncherEntry] {
+        &self.entries
+    }
+
+    pub fn selected(&self) -> Option<&LauncherEntry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Sélectionne un jeu par son nom court (jeu en cours à l'ouverture)
+    pub fn select(&mut self, game: &str) {
+        if let Some(index) = self.entries.iter().position(|entry| entry.audit.short_name == game) {
+            self.selected = index;
+        }
+    }
+
+    /// Traite les touches de la frame
+    pub fn handle(&mut self, input: &InputState) -> Option<LauncherAction> {
+        if input.key_pressed(KeyCode::Escape) {
+            return Some(LauncherAction::Close);
+        }
+        let count = self.entries.len();
+        if count == 0 {
+            return None;
+        }
+
+        let before = self.selected;
+        if input.key_pressed(KeyCode::ArrowLeft) {
+            self.selected = (self.selected + count - 1) % count;
+        }
+        if input.key_pressed(KeyCode::ArrowRight) {
+            self.selected = (self.selected + 1) % count;
+        }
+        if input.key_pressed(KeyCode::ArrowUp) && self.selected >= GRID_COLUMNS {
+            self.selected -= GRID_COLUMNS;
+        }
+        if input.key_pressed(KeyCode::ArrowDown) {
+            self.selected = (self.selected + GRID_COLUMNS).min(count - 1);
+        }
+        if self.selected != before {
+            println!("{}", self.describe());
+        }
+
+        if !input.key_pressed(KeyCode::Enter) {
+            return None;
+        }
+        let entry = &self.entries[self.selected];
+        if entry.audit.is_playable() {
+            Some(LauncherAction::Launch(entry.audit.short_name.clone()))
+        } else {
+            println!("{} : ROMs manquantes ({})", entry.audit.name, entry.audit.missing.join(", "));
+            None
+        }
+    }
+
+    /// Description du jeu sélectionné
+    pub fn describe(&self) -> String {
+        self.details().join("\n")
+    }
+
+    /// Lignes de la description : identité, ROMs et état, puis dernière
+    /// vérification et un défaut connu par ligne
+    pub fn details(&self) -> Vec<String> {
+        let Some(entry) = self.selected() else {
+            return vec!["Aucun jeu dans la base".to_string()];
+        };
+        let audit = &entry.audit;
+        let compatibility = &entry.compatibility;
+        let mut text = format!(
+            "{} ({}, {}) : ROMs {}/{}, {}",
+            audit.name,
+            audit.short_name,
+            audit.year,
+            audit.found,
+            audit.required,
+            compatibility.status.label()
+        );
+        if !compatibility.notes.is_empty() {
+            text.push_str(&format!(" — {}", compatibility.notes));
+        }
+
+        let mut lines = vec![text];
+        if let Some(version) = &compatibility.last_tested {
+            lines.push(format!("Vérifié avec la version {}", version));
+        }
+        lines.extend(compatibility.issues.iter().map(|issue| match issue.hack {
+            Some(hack) => format!("- {} (masqué par le contournement {})", issue.description, hack.name()),
+            None => format!("- {}", issue.description),
+        }));
+        lines
+    }
+
+    /// Vignettes à dessiner par [`overlay::game_grid`](crate::gpu::overlay::game_grid)
+    pub fn tiles(&self) -> Vec<GameTile<'_>> {
+        self.entries
+            .iter()
+            .map(|entry| GameTile {
+                art: entry.box_art.as_ref().map(|art| (art.width, art.height, art.rgba.as_slice())),
+                placeholder: entry.placeholder_color(),
+                status: entry.status_color(),
+                available: entry.audit.is_playable(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputManager;
+    use crate::memory::Hack;
+    use crate::rom::KnownIssue;
+    use winit::event::ElementState;
+
+    fn audit(short_name: &str, found: usize) -> GameAudit {
+        GameAudit {
+            name: short_name.to_uppercase(),
+            short_name: short_name.to_string(),
+            year: 1994,
+            found,
+            required: 2,
+            missing: (found..2).map(|i| format!("rom{}", i)).collect(),
+        }
+    }
+
+    fn press(manager: &mut InputManager, state: &mut InputState, key: KeyCode) {
+        manager.handle_key(key, ElementState::Pressed);
+        state.latch(manager);
+        manager.handle_key(key, ElementState::Released);
+    }
+
+    #[test]
+    fn test_navigation_and_launch_requires_complete_roms() {
+        let games = ["a", "b", "c", "d", "e", "f"];
+        let audits = games.iter().enumerate().map(|(i, name)| audit(name, if i == 4 { 2 } else { 1 })).collect();
+        let mut launcher = Launcher::new(audits, &CompatibilityReport::new(), Path::new("/nonexistent"));
+        let (mut manager, mut state) = (InputManager::new(), InputState::new());
+
+        launcher.select("b");
+        press(&mut manager, &mut state, KeyCode::ArrowDown);
+        assert_eq!(launcher.handle(&state), None);
+        assert_eq!(launcher.selected().unwrap().audit.short_name, "f");
+
+        press(&mut manager, &mut state, KeyCode::Enter);
+        assert_eq!(launcher.handle(&state), None);
+
+        press(&mut manager, &mut state, KeyCode::ArrowLeft);
+        launcher.handle(&state);
+        press(&mut manager, &mut state, KeyCode::Enter);
+        assert_eq!(launcher.handle(&state), Some(LauncherAction::Launch("e".to_string())));
+        assert!(launcher.describe().starts_with("E (e, 1994) : ROMs 2/2, non testé"));
+        assert_eq!(launcher.details().len(), 1);
+
+        launcher.entries[4].compatibility = CompatibilityEntry {
+            status: CompatibilityStatus::Playable,
+            notes: String::new(),
+            issues: vec![
+                KnownIssue { description: "Écran noir".to_string(), hack: Some(Hack::ForceVblank) },
+                KnownIssue { description: "Son saccadé".to_string(), hack: None },
+            ],
+            last_tested: Some("0.1.0".to_string()),
+        };
+        assert_eq!(
+            launcher.details()[1..],
+            [
+                "Vérifié avec la version 0.1.0",
+                "- Écran noir (masqué par le contournement force_vblank)",
+                "- Son saccadé",
+            ]
+        );
+
+        let tiles = launcher.tiles();
+        assert!(tiles[4].available && !tiles[0].available);
+        assert!(tiles.iter().all(|tile| tile.art.is_none()));
+
+        press(&mut manager, &mut state, KeyCode::Escape);
+        assert_eq!(launcher.handle(&state), Some(LauncherAction::Close));
+    }
+}
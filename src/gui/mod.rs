@@ -1,43 +1,213 @@
 //! Interface graphique de l'émulateur
 
+pub mod window;
+pub mod pause_menu;
+pub mod launcher;
+
+use pause_menu::{PauseAction, PauseMenu, RemapEvent};
+use launcher::{Launcher, LauncherAction};
+
+use std::path::PathBuf;
 use std::sync::Arc;
+use crate::clock::Instant;
 use anyhow::Result;
 use winit::{
-    event::{Event, WindowEvent, ElementState},
+    event::{ElementState, Event, WindowEvent, MouseButton},
     event_loop::EventLoop,
-    window::WindowBuilder,
+    window::{Window, WindowBuilder},
     keyboard::{KeyCode, PhysicalKey},
 };
 use crate::{
-    cpu::NecV60,
-    memory::{Model2Memory, interface::MemoryInterface, GpuCommand},
-    gpu::Model2Gpu,
-    audio::ScspAudio,
-    input::InputManager,
-    config::EmulatorConfig,
-    rom::Model2RomSystem,
+    audio::{preview_region, render_waveform, wave_peaks, wave_range, wave_regions, CabinetProfile, StretchMode},
+    gpu::{AdapterSelection, DepthMode, GpuStateWatch, Model2Gpu, OutputTransform, SimpleVertex, TextureFilter, WatchedLine, overlay},
+    input::{InputManager, InputState, CalibrationRoutine, GunCalibrationSet, MAX_PLAYERS, key_name},
+    config::{executable_dir, AudioBackendKind, AudioConfig, EmulatorConfig, AppPaths, FramePacing, SettingChange, Settings},
+    memory::{HeatmapSnapshot, RtcDevice},
+    rom::{CompatibilityReport, Model2RomSystem},
+    profiling::FrameScope,
+    debugger::{SymbolMap, MemoryCommand, MemorySearch, disassemble, format_call_stack, write_call_trace},
+    error::{CrashReport, EmulatorError},
+    emulator::{Achievements, CpuClocks, EmulatorCore, FrameCapture, FrameEventKind, FramePacer, LampForwarder, StatsServer, DEFAULT_DISPLAY_RATE, capture_frames},
+    savestate::{SaveState, SaveStateHeader, SaveSlots, SlotInfo, Thumbnail, AUTO_SLOT, game_id_from_rom, state_path, format_timestamp},
 };
 
+/// Titre de base de la fenêtre
+const WINDOW_TITLE: &str = "Pixel Model 2 Rust - Émulateur SEGA Model 2";
+
+/// Colonnes et lignes de la forme d'onde affichée par la commande `aram wave`
+const AUDIO_RAM_WAVE_COLUMNS: usize = 64;
+const AUDIO_RAM_WAVE_ROWS: usize = 8;
+
+/// Tranches de la carte de la RAM affichée pendant une recherche mémoire
+const MEMORY_MAP_BINS: usize = 128;
+
+/// Carte de chaleur en surimpression : blocs par ligne et au total
+const HEATMAP_OVERLAY_WIDTH: u32 = 64;
+const HEATMAP_OVERLAY_BLOCKS: usize = 2048;
+
+/// Blocs listés par la commande `heatmap`
+const HEATMAP_REPORT_BLOCKS: usize = 16;
+
+/// Instructions listées par la commande `disasm`, par défaut et au plus
+const DISASSEMBLY_LINES: usize = 16;
+const MAX_DISASSEMBLY_LINES: usize = 1024;
+
 /// Application principale de l'émulateur
 pub struct EmulatorApp {
-    pub cpu: NecV60,
-    pub memory: Model2Memory,
-    pub audio: ScspAudio,
+    /// Machine émulée (CPU, mémoire, audio)
+    pub core: EmulatorCore,
     pub input: InputManager,
-    pub config: EmulatorConfig,
+    pub input_state: InputState,
+    pub gun_calibration: GunCalibrationSet,
+    /// Configuration, modifiée uniquement à travers le service des réglages
+    pub config: Settings,
+
+    /// Réglages modifiés, reportés à chaque frame sur le GPU, l'audio et la
+    /// cadence
+    setting_changes: std::sync::mpsc::Receiver<SettingChange>,
+    pub paths: AppPaths,
     pub rom_system: Model2RomSystem,
+
+    /// Identifiant du jeu lancé (nom de la ROM), pour ranger ses états
+    pub game: Option<String>,
+
     pub running: bool,
     pub paused: bool,
+
+    /// Fichier callgrind à écrire en quittant (profilage activé)
+    pub profile_output: Option<PathBuf>,
+
+    /// Fichier chrome://tracing à écrire en quittant
+    pub trace_output: Option<PathBuf>,
+
+    /// Journal des appels de fonction à écrire en quittant
+    pub call_trace_output: Option<PathBuf>,
+
+    /// Journal des événements de frame à écrire en quittant
+    pub event_log_output: Option<PathBuf>,
+
+    /// Rapport de compatibilité de la session, si demandé en ligne de
+    /// commande (sinon `<données>/reports/<jeu>.json` avec `compat_report`)
+    pub session_report_output: Option<PathBuf>,
+
+    /// Symboles du jeu pour annoter la pile d'appels et les traces
+    pub symbols: SymbolMap,
+
+    /// Instantanés de la RAM et candidats de la recherche de variables
+    pub memory_search: MemorySearch,
+
+    /// Dernier relevé de la carte de chaleur des accès mémoire
+    pub heatmap: Option<HeatmapSnapshot>,
+
+    /// Commandes du débogueur lues sur l'entrée standard
+    debug_console: Option<std::sync::mpsc::Receiver<String>>,
+
+    /// Point d'accès JSON des statistiques, si activé
+    pub stats_server: Option<StatsServer>,
+
+    /// Transmission des lampes à la borne, si activée
+    pub lamp_forwarder: Option<LampForwarder>,
 }
 
 /// État de l'application pour gérer les lifetimes correctement
 pub struct AppState {
     pub app: EmulatorApp,
+
+    /// Calibration des pistolets en cours
+    pub calibration: Option<CalibrationRoutine>,
+
+    /// Emplacement d'état utilisé par F5 / F9
+    pub selected_slot: u8,
+
+    /// Sélecteur d'emplacements ouvert (F2), avec leur contenu
+    pub slot_picker: Option<Vec<SlotInfo>>,
+
+    /// Réglages du moniteur affichés pendant la pause
+    pub pause_menu: PauseMenu,
+
+    /// Lanceur ouvert (F1, ou au démarrage sans jeu)
+    pub launcher: Option<Launcher>,
+
+    /// Panneau d'état du GPU affiché (F3)
+    pub gpu_state: Option<GpuStateWatch>,
+
+    /// Frames émulées à chaque rafraîchissement de l'écran
+    pub pacer: FramePacer,
+
+    /// Ralenti basculé par F4
+    slow_motion: bool,
+
+    /// Date du rafraîchissement précédent
+    last_refresh: Option<Instant>,
 }
 
 impl AppState {
     pub fn new(app: EmulatorApp) -> Self {
-        Self { app }
+        let no_game = app.game.is_none();
+        let pacer = FramePacer::new(app.config.video.frame_pacing, DEFAULT_DISPLAY_RATE);
+        let mut state = Self {
+            app,
+            calibration: None,
+            selected_slot: 0,
+            slot_picker: None,
+            pause_menu: PauseMenu::new(),
+            launcher: None,
+            gpu_state: None,
+            pacer,
+            slow_motion: false,
+            last_refresh: None,
+        };
+        if no_game {
+            state.open_launcher();
+        }
+        state
+    }
+
+    /// Ouvre le lanceur sur le jeu en cours et met l'émulation en pause
+    fn open_launcher(&mut self) {
+        let audits = self.app.rom_system.rom_manager.audit_games().unwrap_or_else(|e| {
+            eprintln!("Audit des ROMs impossible: {}", e);
+            Vec::new()
+        });
+        let mut launcher = Launcher::new(audits, &CompatibilityReport::bundled(), &self.app.paths.boxart_dir);
+        if let Some(game) = self.app.game.as_deref() {
+            launcher.select(game);
+        }
+        println!("Lanceur : flèches pour choisir, Entrée pour lancer, Échap pour revenir");
+        println!("{}", launcher.describe());
+        self.launcher = Some(launcher);
+        self.app.paused = true;
+    }
+
+    /// F1 ouvre le lanceur ; un jeu choisi remplace le jeu en cours
+    fn handle_launcher(&mut self, gpu: Option<&mut Model2Gpu>) {
+        let Some(launcher) = self.launcher.as_mut() else {
+            if self.app.input_state.key_pressed(KeyCode::F1) && self.slot_picker.is_none() && self.calibration.is_none() {
+                self.open_launcher();
+            }
+            return;
+        };
+
+        match launcher.handle(&self.app.input_state) {
+            Some(LauncherAction::Launch(game)) => match self.app.switch_game(&game) {
+                Ok(()) => {
+                    // Les textures et matrices du jeu précédent ne restent pas au GPU
+                    if let Some(gpu) = gpu {
+                        gpu.reset();
+                        self.app.apply_game_video(gpu);
+                    }
+                    self.launcher = None;
+                    self.app.paused = false;
+                },
+                Err(e) => eprintln!("Impossible de lancer {}: {:#}", game, e),
+            },
+            Some(LauncherAction::Close) if self.app.game.is_none() => self.app.running = false,
+            Some(LauncherAction::Close) => {
+                self.launcher = None;
+                self.app.paused = false;
+            },
+            None => {},
+        }
     }
     
     pub fn handle_window_event(&mut self, event: &WindowEvent) {
@@ -46,258 +216,1278 @@ impl AppState {
                 // Nous ne pouvons pas appeler elwt.exit() ici sans elwt
                 self.app.running = false;
             },
+            WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+                self.app.input.handle_trigger(*state);
+            },
             WindowEvent::KeyboardInput { event, .. } => {
                 if let PhysicalKey::Code(keycode) = event.physical_key {
-                    self.app.input.handle_key(keycode, event.state);
-                    
-                    // Touches spéciales de l'émulateur
-                    if event.state == ElementState::Pressed {
-                        match keycode {
-                            KeyCode::Escape => {
-                                self.app.running = false;
-                            },
-                            KeyCode::KeyP => {
-                                self.app.paused = !self.app.paused;
-                                println!("Émulation {}", if self.app.paused { "pausée" } else { "reprise" });
-                            },
-                            KeyCode::KeyR => {
-                                self.app.cpu.reset();
-                                println!("Émulateur réinitialisé");
-                            },
-                            KeyCode::KeyL => {
-                                // Essayer de charger un jeu de test
-                                let _ = self.app.load_rom("daytona-usa");
-                            },
-                            _ => {}
+                    // Alt+Entrée : bascule plein écran / fenêtré ; Entrée est
+                    // le Start du joueur 1, la touche n'est pas transmise au jeu
+                    let keys = self.app.input.pressed_keys();
+                    let alt = keys.contains(&KeyCode::AltLeft) || keys.contains(&KeyCode::AltRight);
+                    if alt && keycode == KeyCode::Enter && event.state == ElementState::Pressed {
+                        if !event.repeat {
+                            let fullscreen = !self.app.config.video.fullscreen;
+                            let _ = self.app.config.set("video.fullscreen", fullscreen);
                         }
+                        return;
                     }
+                    // Les touches sont prises en compte au prochain instantané de frame
+                    self.app.input.handle_key(keycode, event.state);
                 }
             },
             _ => {}
         }
     }
+
+    /// Traite les raccourcis de l'émulateur à partir de l'instantané de la frame
+    fn handle_shortcuts(&mut self) {
+        let state = &self.app.input_state;
+
+        // Le lanceur et le remappage des touches ont leurs propres touches
+        if self.launcher.is_some() || self.pause_menu.capturing().is_some() {
+            return;
+        }
+
+        // F7 : calibration du pistolet du joueur 1
+        if state.key_pressed(KeyCode::F7) && self.calibration.is_none() {
+            println!("Calibration du pistolet : visez les cibles et tirez");
+            self.calibration = Some(CalibrationRoutine::new(0));
+        }
+
+        if state.key_pressed(KeyCode::Escape) && self.slot_picker.is_none() {
+            self.app.running = false;
+        }
+        if state.key_pressed(KeyCode::KeyP) {
+            self.app.paused = !self.app.paused;
+            println!("Émulation {}", if self.app.paused { "pausée" } else { "reprise" });
+            if self.app.paused {
+                println!("Réglages du moniteur : haut/bas pour choisir, gauche/droite pour modifier, Retour arrière pour rétablir");
+            }
+        }
+        if state.key_pressed(KeyCode::KeyL) {
+            // Essayer de charger un jeu de test
+            let _ = self.app.load_rom("daytona-usa");
+        }
+    }
     
-    pub fn run_frame(&mut self, mut gpu: Option<&mut Model2Gpu>) -> Result<()> {
-        if self.app.running && !self.app.paused {
-            // Exécuter un frame d'émulation
-            const CYCLES_PER_FRAME: u32 = crate::MAIN_CPU_FREQUENCY / 60; // 60 FPS
-            let executed_cycles = self.app.cpu.run_cycles(CYCLES_PER_FRAME, &mut self.app.memory)?;
-            
-            // Mettre à jour les registres I/O avec les cycles exécutés
-            self.app.memory.update_io_registers(executed_cycles, &mut self.app.cpu);
-            
-            // Traiter les commandes GPU par lots
-            let command_batches = self.app.memory.process_gpu_commands();
-            if !command_batches.is_empty() {
-                if let Some(gpu_ref) = gpu.as_mut() {
-                    self.process_gpu_command_batch(&command_batches, gpu_ref)?;
+    /// R : reset logiciel, Maj+R : reset matériel
+    fn handle_reset_keys(&mut self, gpu: Option<&mut Model2Gpu>) {
+        let state = &self.app.input_state;
+        if self.launcher.is_some() || self.pause_menu.capturing().is_some() || !state.key_pressed(KeyCode::KeyR) {
+            return;
+        }
+        let shift = state.key_held(KeyCode::ShiftLeft) || state.key_held(KeyCode::ShiftRight);
+        self.reset(if shift { PauseAction::HardReset } else { PauseAction::SoftReset }, gpu);
+    }
+
+    /// Reset demandé au clavier ou depuis le menu de pause ; le reset
+    /// matériel remet aussi le GPU dans son état de démarrage
+    fn reset(&mut self, action: PauseAction, gpu: Option<&mut Model2Gpu>) {
+        let result = match action {
+            PauseAction::SoftReset => self.app.soft_reset(),
+            PauseAction::HardReset => self.app.hard_reset(),
+        };
+        if let Err(e) = result {
+            eprintln!("Échec du reset: {:#}", e);
+            return;
+        }
+        if let (PauseAction::HardReset, Some(gpu)) = (action, gpu) {
+            gpu.reset();
+            self.app.apply_game_video(gpu);
+        }
+    }
+
+    /// Emplacements d'états : F2 ouvre le sélecteur (flèches ou chiffres pour
+    /// choisir), F5 sauvegarde et F9 charge l'emplacement sélectionné
+    /// Le menu de pause s'affiche en pause, hors sélecteur d'états et calibration
+    fn pause_menu_open(&self) -> bool {
+        self.app.paused && self.slot_picker.is_none() && self.calibration.is_none() && self.launcher.is_none()
+    }
+
+    /// Réglages du moniteur et anticrénelage en pause, appliqués
+    /// immédiatement ; ceux du moniteur sont mémorisés pour le jeu lancé.
+    /// Un reset choisi dans le menu reprend l'émulation.
+    fn handle_pause_menu(&mut self, gpu: Option<&mut Model2Gpu>) {
+        if !self.pause_menu_open() {
+            return;
+        }
+        if let Some(action) = self.pause_menu.action(&self.app.input_state) {
+            self.reset(action, gpu);
+            self.app.paused = false;
+            return;
+        }
+
+        self.handle_remap();
+
+        let selected = self.pause_menu.selected();
+        let game = self.app.game.clone();
+        let mut anti_aliasing = self.app.config.video.anti_aliasing;
+        let mut color = self.app.config.video.color_for(game.as_deref());
+        let changed = self.pause_menu.handle(&self.app.input_state, &mut color, &mut anti_aliasing);
+        if changed || selected != self.pause_menu.selected() {
+            println!("{}", self.pause_menu.describe(&color, anti_aliasing));
+        }
+        if changed {
+            let result = self.app.config.update(|config| {
+                *config.video.color_for_mut(game.as_deref()) = color;
+                config.video.anti_aliasing = anti_aliasing;
+            });
+            if let Err(e) = result {
+                eprintln!("{:#}", e);
+            }
+        }
+    }
+
+    /// Remappage des touches depuis le menu de pause : chaque touche choisie
+    /// est enregistrée dans `input.players.<joueur>.<bouton>`
+    fn handle_remap(&mut self) {
+        let before = self.pause_menu.capturing();
+        match self.pause_menu.remap(&self.app.input_state) {
+            Some(RemapEvent::Bound(player, button, key)) => {
+                let name = key_name(key).unwrap_or_default();
+                match self.app.config.set(&format!("input.players.{}.{}", player, button.key()), &name) {
+                    Ok(_) => println!("Joueur {} {:?} : {}", player + 1, button, name),
+                    Err(e) => eprintln!("{:#}", e),
+                }
+            },
+            Some(RemapEvent::Cancelled) => println!("Remappage abandonné"),
+            None => {},
+        }
+        match self.pause_menu.capturing() {
+            Some((player, button)) if before != self.pause_menu.capturing() => {
+                println!("Joueur {} : touche pour {:?} ? (Échap pour abandonner)", player + 1, button);
+            },
+            None if before.is_some() => println!("Commandes du joueur {} enregistrées", self.pause_menu.player() + 1),
+            _ => {},
+        }
+    }
+
+    /// Tab maintenue : avance rapide ; F4 : ralenti. La cadence des frames
+    /// et l'étirement du son suivent la vitesse retenue.
+    fn update_speed(&mut self) {
+        let shortcuts = self.launcher.is_none() && self.pause_menu.capturing().is_none();
+        let state = &self.app.input_state;
+        if shortcuts && state.key_pressed(KeyCode::F4) {
+            self.slow_motion = !self.slow_motion;
+        }
+        let emulation = &self.app.config.emulation;
+        let speed = if shortcuts && state.key_held(KeyCode::Tab) {
+            emulation.fast_forward_speed
+        } else if self.slow_motion {
+            emulation.slow_motion_speed
+        } else {
+            1.0
+        };
+
+        // Le cœur audio est recréé au changement de jeu : vitesse réappliquée
+        if speed != self.app.core.audio.speed() {
+            self.app.core.audio.set_speed(speed);
+        }
+        if speed as f64 != self.pacer.speed() {
+            self.pacer.set_speed(speed as f64);
+            println!("Vitesse d'émulation : {}x", speed);
+        }
+    }
+
+    /// Reporte les réglages modifiés (menu, console, raccourcis) sur les
+    /// sous-systèmes concernés
+    fn apply_setting_changes(&mut self, mut gpu: Option<&mut Model2Gpu>) {
+        let changes: Vec<SettingChange> = self.app.setting_changes.try_iter().collect();
+        for change in changes {
+            if change.is_under("input.players") {
+                for name in self.app.input.set_bindings(&self.app.config.input.players) {
+                    eprintln!("Touche inconnue: {}", name);
+                }
+            }
+            match change.key.as_str() {
+                "audio.volume" => self.app.core.audio.set_volume(self.app.config.audio.volume),
+                "audio.device" => self.app.core.audio.devices_changed(),
+                "audio.interpolation" => self.app.core.audio.set_interpolation(self.app.config.audio.interpolation),
+                "audio.time_stretch" => self.app.core.audio.set_stretch_mode(if self.app.config.audio.time_stretch {
+                    StretchMode::Wsola
                 } else {
-                    println!("GPU: {} commandes reçues mais GPU non initialisé", command_batches.len());
+                    StretchMode::Resample
+                }),
+                key if key.starts_with("audio.cabinet.") || key.starts_with("audio.game_cabinet.") => self.app.apply_game_audio(),
+                "video.frame_pacing" => self.pacer.set_mode(change.value::<FramePacing>().unwrap_or_default()),
+                key if key.starts_with("emulation.heatmap.") && !key.ends_with(".export") && !key.ends_with(".overlay") => {
+                    self.app.core.set_heatmap(&self.app.config.emulation.heatmap);
+                    self.app.heatmap = None;
                 }
+                _ => {}
             }
-            
-            // Forcer le vidage du buffer à la fin du frame pour synchronisation
-            let remaining_commands = self.app.memory.flush_gpu_command_buffer();
-            if !remaining_commands.is_empty() {
-                if let Some(gpu_ref) = gpu.as_mut() {
-                    self.process_gpu_command_batch(&remaining_commands, gpu_ref)?;
+            let (Some(field), Some(gpu)) = (change.key.strip_prefix("video."), gpu.as_deref_mut()) else { continue };
+            let video = &self.app.config.video;
+            match field.split('.').next().unwrap_or_default() {
+                "texture_filtering" => gpu.set_texture_filter(TextureFilter::from_config(&video.texture_filtering)),
+                "anti_aliasing" => {
+                    gpu.set_anti_aliasing(video.anti_aliasing);
                 }
+                "aspect" | "rotation" => gpu.set_output_transform(OutputTransform::from_config(video)),
+                "vsync" => gpu.set_vsync(video.vsync),
+                "color" | "game_colors" | "internal_scale" | "game_internal_scale" | "mipmaps" | "game_mipmaps"
+                | "geometry_precision" | "game_geometry_precision" | "polygon_sorting" => self.app.apply_game_video(gpu),
+                _ => {}
             }
-            
-            // Synchroniser les autres composants (GPU, audio, etc.)
-            // TODO: Implémenter une synchronisation temporelle précise
-            
-            // Statistiques de performance
-            if executed_cycles > 0 {
-                let fps = 60.0 * (executed_cycles as f32 / CYCLES_PER_FRAME as f32);
-                let buffer_stats = self.app.memory.gpu_command_buffer.stats();
-                println!("GPU Buffer: {} lots traités, taille moyenne {:.1}, max {}", 
-                        buffer_stats.batches_processed, buffer_stats.average_batch_size, buffer_stats.max_batch_size);
+        }
+    }
+
+    /// F6 : passe au filtrage de texture suivant, mémorisé dans la configuration
+    fn cycle_texture_filter(&mut self) {
+        if self.pause_menu.capturing().is_some() || !self.app.input_state.key_pressed(KeyCode::F6) {
+            return;
+        }
+
+        let filter = TextureFilter::from_config(&self.app.config.video.texture_filtering).next();
+        if let Err(e) = self.app.config.set("video.texture_filtering", filter.name()) {
+            eprintln!("{:#}", e);
+            return;
+        }
+        println!("Filtrage des textures: {}", filter.name());
+    }
+
+    fn handle_save_slots(&mut self, gpu: Option<&Model2Gpu>) {
+        if self.launcher.is_some() || self.pause_menu.capturing().is_some() {
+            return;
+        }
+        let state = &self.app.input_state;
+        let (toggle, save, load) = (
+            state.key_pressed(KeyCode::F2),
+            state.key_pressed(KeyCode::F5),
+            state.key_pressed(KeyCode::F9),
+        );
+
+        let mut selected = self.selected_slot;
+        if self.slot_picker.is_some() {
+            let count = crate::savestate::SLOT_COUNT;
+            if state.key_pressed(KeyCode::ArrowLeft) {
+                selected = (selected + count - 1) % count;
+            }
+            if state.key_pressed(KeyCode::ArrowRight) {
+                selected = (selected + 1) % count;
+            }
+            let digits = [
+                KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+                KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+            ];
+            if let Some(digit) = digits.iter().position(|&key| state.key_pressed(key)) {
+                selected = digit as u8;
             }
         }
-        Ok(())
+        let close = self.slot_picker.is_some() && (toggle || state.key_pressed(KeyCode::Escape));
+
+        if selected != self.selected_slot {
+            self.selected_slot = selected;
+            self.describe_selected_slot();
+        }
+
+        if save {
+            let thumbnail = gpu.and_then(|gpu| {
+                let (width, height) = gpu.framebuffer.native_size();
+                Thumbnail::from_rgba(&gpu.framebuffer.native_color_data(), width, height)
+            });
+            match self.app.save_to_slot(self.selected_slot, thumbnail) {
+                Ok(path) => println!("État {} sauvegardé: {}", self.selected_slot, path.display()),
+                Err(e) => eprintln!("Sauvegarde de l'état {} impossible: {:#}", self.selected_slot, e),
+            }
+        }
+        if load {
+            match self.app.load_from_slot(self.selected_slot) {
+                Ok(()) => println!("État {} chargé", self.selected_slot),
+                Err(e) => eprintln!("Chargement de l'état {} impossible: {:#}", self.selected_slot, e),
+            }
+        }
+
+        if close || (load && self.slot_picker.is_some()) {
+            self.slot_picker = None;
+            self.app.paused = false;
+        } else if toggle && self.slot_picker.is_none() {
+            self.app.paused = true;
+            self.slot_picker = Some(Vec::new());
+            self.describe_selected_slot();
+        }
+
+        // Relire les en-têtes après une sauvegarde ou à l'ouverture
+        if self.slot_picker.is_some() && (save || toggle) {
+            self.slot_picker = self.app.save_slots().map(|slots| slots.list());
+        }
     }
-    
-    /// Traite une commande GPU
-    fn process_gpu_command(&mut self, command: &GpuCommand, gpu: &mut Model2Gpu) -> Result<()> {
-        match command {
-            GpuCommand::ClearScreen { color, depth: _, stencil: _ } => {
-                // Pour Model2Gpu, nous utilisons begin_frame/end_frame pour gérer le clear
-                gpu.begin_frame()?;
-                // Note: Le clear est géré automatiquement par begin_frame
-                println!("GPU: Clear screen avec couleur [{:.2}, {:.2}, {:.2}, {:.2}]", 
-                        color[0], color[1], color[2], color[3]);
-            },
-            GpuCommand::SetModelMatrix(matrix) => {
-                // Convertir le tableau en Mat4 de glam
-                let mat = glam::Mat4::from_cols_array(matrix);
-                gpu.geometry_processor.set_model_matrix(mat);
-                println!("GPU: Set model matrix");
-            },
-            GpuCommand::SetViewMatrix(matrix) => {
-                let mat = glam::Mat4::from_cols_array(matrix);
-                gpu.geometry_processor.set_view_matrix(mat);
-                println!("GPU: Set view matrix");
-            },
-            GpuCommand::SetProjectionMatrix(matrix) => {
-                let mat = glam::Mat4::from_cols_array(matrix);
-                gpu.geometry_processor.set_projection_matrix(mat);
-                println!("GPU: Set projection matrix");
-            },
-            GpuCommand::LoadTexture { id, data, width, height } => {
-                gpu.load_texture(*id, data, *width, *height)?;
-                println!("GPU: Load texture {} ({}x{})", id, width, height);
-            },
-            GpuCommand::DrawTriangle { vertices, texture_id } => {
-                // Convertir en Triangle3D
-                let triangle = self.convert_gpu_vertices_to_triangle(vertices, *texture_id);
-                gpu.draw_triangle(&triangle)?;
-                println!("GPU: Draw triangle");
-            },
-            GpuCommand::SetRenderState { state, enabled } => {
-                // Convertir RenderStateType en RenderState
-                let render_state = match state {
-                    crate::memory::RenderStateType::ZBuffer => crate::gpu::RenderState::ZBuffer,
-                    crate::memory::RenderStateType::Texturing => crate::gpu::RenderState::Texturing,
-                    crate::memory::RenderStateType::Lighting => crate::gpu::RenderState::Lighting,
-                    crate::memory::RenderStateType::Transparency => crate::gpu::RenderState::Transparency,
-                    _ => crate::gpu::RenderState::ZBuffer, // Défaut
-                };
-                gpu.set_render_state(render_state, *enabled);
-                println!("GPU: Set render state {:?} -> {}", state, enabled);
-            },
-            _ => {
-                println!("GPU: Commande non implémentée: {:?}", command);
+
+    /// Affiche le contenu de l'emplacement sélectionné
+    fn describe_selected_slot(&self) {
+        let Some(slots) = self.app.save_slots() else {
+            println!("Emplacement {} (aucun jeu lancé)", self.selected_slot);
+            return;
+        };
+
+        match slots.info(self.selected_slot).header {
+            Some(header) => println!(
+                "Emplacement {} : {} rév. {} ({}), {} UTC",
+                self.selected_slot,
+                header.game,
+                header.game_revision,
+                header.board,
+                format_timestamp(header.created)
+            ),
+            None => println!("Emplacement {} : vide", self.selected_slot),
+        }
+    }
+
+    /// Fait avancer la calibration des pistolets à partir des tirs de la frame
+    fn update_calibration(&mut self) {
+        let Some(routine) = self.calibration.as_mut() else {
+            return;
+        };
+
+        let player = routine.player();
+        if !self.app.input_state.trigger_pressed(player) {
+            return;
+        }
+
+        let Some(gun) = self.app.input_state.gun(player).copied() else {
+            return;
+        };
+
+        if let Some(calibration) = routine.record_shot(gun.x, gun.y) {
+            self.app.gun_calibration.set(player, calibration);
+            if let Err(e) = self.app.gun_calibration.save(&self.app.paths.nvram_dir) {
+                eprintln!("Impossible d'enregistrer la calibration: {}", e);
             }
+            println!("Calibration du joueur {} terminée", player + 1);
+            self.calibration = None;
         }
-        Ok(())
     }
-    
-    /// Traite un lot de commandes GPU de manière optimisée
-    fn process_gpu_command_batch(&mut self, commands: &[GpuCommand], gpu: &mut Model2Gpu) -> Result<()> {
-        println!("GPU: Traitement d'un lot de {} commandes", commands.len());
-        
-        // Traiter les commandes par lot pour de meilleures performances
-        for command in commands {
-            self.process_gpu_command(command, gpu)?;
+
+    /// Construit la couche d'incrustation : viseurs et cible de calibration
+    pub fn build_overlay(&self, aspect: f32) -> Vec<SimpleVertex> {
+        let mut vertices = Vec::new();
+
+        if let Some(launcher) = self.launcher.as_ref() {
+            overlay::game_grid(&launcher.tiles(), launcher.selected_index(), &launcher.details(), aspect, &mut vertices);
+            return vertices;
         }
-        
-        Ok(())
+
+        let touch = self.app.input.touch.config();
+        if touch.enabled {
+            overlay::touch_controls(touch, self.app.input.touch.input(), aspect, &mut vertices);
+        }
+
+        if self.pause_menu_open() {
+            let color = self.app.config.video.color_for(self.app.game.as_deref());
+            // Les lignes d'action sont des barres pleines
+            let values: Vec<f32> = pause_menu::PauseItem::ALL.iter().map(|item| match *item {
+                pause_menu::PauseItem::Color(parameter) => color.normalized(parameter),
+                pause_menu::PauseItem::Controls => (self.pause_menu.player() + 1) as f32 / MAX_PLAYERS as f32,
+                pause_menu::PauseItem::AntiAliasing => self.app.config.video.anti_aliasing.normalized(),
+                pause_menu::PauseItem::Action(_) => 1.0,
+            }).collect();
+            overlay::sliders(&values, self.pause_menu.selected_index(), &mut vertices);
+        }
+
+        if let Some(slots) = self.slot_picker.as_ref() {
+            let occupied: Vec<bool> = slots.iter().map(|slot| !slot.is_empty()).collect();
+            let selected = slots.get(self.selected_slot as usize);
+            if let Some(thumbnail) = selected.and_then(|slot| slot.header.as_ref()).and_then(|h| h.thumbnail.as_ref()) {
+                overlay::image([0.3, 0.25, 0.4, 0.4], thumbnail.width, thumbnail.height, &thumbnail.rgba, &mut vertices);
+            }
+            overlay::slot_bar(&occupied, self.selected_slot as usize, &mut vertices);
+        }
+
+        if let Some((x, y)) = self.calibration.as_ref().and_then(|r| r.current_target()) {
+            overlay::calibration_target(x, y, aspect, &mut vertices);
+        }
+
+        let lightgun = &self.app.config.input.lightgun;
+        if lightgun.show_crosshair || self.calibration.is_some() {
+            for (player, color) in lightgun.crosshair_colors.iter().enumerate() {
+                let Some(gun) = self.app.input_state.gun(player) else {
+                    break;
+                };
+                if gun.offscreen {
+                    continue;
+                }
+
+                // Pendant la calibration, le viseur montre la position brute
+                let (x, y) = if self.calibration.is_some() {
+                    (gun.x, gun.y)
+                } else {
+                    self.app.gun_calibration.get(player).apply(gun.x, gun.y)
+                };
+                overlay::crosshair(x, y, 0.03, aspect, *color, &mut vertices);
+            }
+        }
+
+        // Horloges modifiées : jauges permanentes, le détail est dans le titre
+        let clocks = self.app.core.clocks;
+        if !clocks.is_stock() {
+            let stock = CpuClocks::default().normalized()[0];
+            overlay::clock_gauges(&clocks.normalized(), stock, &mut vertices);
+        }
+
+        if let Some(watch) = self.gpu_state.as_ref() {
+            // Commandes de la frame sous l'état, anomalies surlignées
+            let mut lines = watch.lines();
+            lines.extend(self.app.core.gpu_commands.lines().into_iter().map(|text| WatchedLine {
+                highlight: if text.starts_with('!') { 1.0 } else { 0.0 },
+                text,
+            }));
+            overlay::state_panel(&lines, aspect, &mut vertices);
+        }
+
+        // Dernier relevé de la carte de chaleur, réduit pour rester lisible
+        if let Some(snapshot) = self.app.heatmap.as_ref().filter(|_| self.app.config.emulation.heatmap.overlay) {
+            let coarse = snapshot.coarsened(HEATMAP_OVERLAY_WIDTH, HEATMAP_OVERLAY_BLOCKS);
+            let height = 0.35 * coarse.height() as f32 / coarse.width as f32;
+            overlay::image([0.62, 0.03, 0.35, height], coarse.width, coarse.height(), &coarse.to_rgba(), &mut vertices);
+        }
+
+        // Recherche mémoire en cours : emplacement des candidats dans la RAM
+        if !self.app.memory_search.candidates().is_empty() {
+            overlay::memory_map(&self.app.memory_search.density(MEMORY_MAP_BINS), &mut vertices);
+        }
+
+        vertices
     }
-    
-    /// Convertit des GpuVertex en Triangle3D
-    fn convert_gpu_vertices_to_triangle(&self, vertices: &[crate::memory::GpuVertex; 3], texture_id: Option<u32>) -> crate::gpu::geometry::Triangle3D {
-        use crate::gpu::geometry::{Triangle3D, Vertex3D, TriangleFlags};
-        use glam::Vec3;
-        
-        let verts = [
-            Vertex3D {
-                position: Vec3::new(vertices[0].x, vertices[0].y, vertices[0].z),
-                normal: Vec3::new(0.0, 0.0, 1.0), // Normale par défaut
-                tex_coords: [vertices[0].u, vertices[0].v],
-                color: [vertices[0].r, vertices[0].g, vertices[0].b, vertices[0].a],
-                fog_coord: 0.0,
-                specular: [0.0, 0.0, 0.0],
-            },
-            Vertex3D {
-                position: Vec3::new(vertices[1].x, vertices[1].y, vertices[1].z),
-                normal: Vec3::new(0.0, 0.0, 1.0),
-                tex_coords: [vertices[1].u, vertices[1].v],
-                color: [vertices[1].r, vertices[1].g, vertices[1].b, vertices[1].a],
-                fog_coord: 0.0,
-                specular: [0.0, 0.0, 0.0],
+
+    /// Indique si le pointeur doit être capturé (jeu en cours, hors pause)
+    pub fn wants_pointer_capture(&self) -> bool {
+        self.app.running && !self.app.paused
+    }
+
+    /// Clôt la frame profilée précédente (présentation comprise) et publie son bilan
+    fn finish_profiled_frame(&mut self, gpu: Option<&mut Model2Gpu>) {
+        let profiler = &mut self.app.core.profiler;
+        match gpu {
+            Some(gpu) => {
+                profiler.absorb(&mut gpu.profiler);
+                gpu.stats.frame_timings = *profiler.end_frame();
             },
-            Vertex3D {
-                position: Vec3::new(vertices[2].x, vertices[2].y, vertices[2].z),
-                normal: Vec3::new(0.0, 0.0, 1.0),
-                tex_coords: [vertices[2].u, vertices[2].v],
-                color: [vertices[2].r, vertices[2].g, vertices[2].b, vertices[2].a],
-                fog_coord: 0.0,
-                specular: [0.0, 0.0, 0.0],
+            None => {
+                profiler.end_frame();
             },
-        ];
-        
-        Triangle3D {
-            vertices: verts,
-            texture_id,
-            material_id: 0,
-            flags: TriangleFlags::default(),
         }
     }
+
+    pub fn run_frame(&mut self, mut gpu: Option<&mut Model2Gpu>) -> Result<()> {
+        self.finish_profiled_frame(gpu.as_deref_mut());
+
+        // Figer les entrées une seule fois par rafraîchissement
+        let start = Instant::now();
+        self.app.input_state.latch(&self.app.input);
+        self.handle_save_slots(gpu.as_deref());
+        self.handle_shortcuts();
+        self.handle_reset_keys(gpu.as_deref_mut());
+        self.handle_launcher(gpu.as_deref_mut());
+        self.handle_pause_menu(gpu.as_deref_mut());
+        self.cycle_texture_filter();
+        let shortcuts = self.launcher.is_none() && self.pause_menu.capturing().is_none();
+        if shortcuts && self.app.input_state.key_pressed(KeyCode::F8) {
+            self.app.dump_textures(gpu.as_deref(), None);
+        }
+        if shortcuts && self.app.input_state.key_pressed(KeyCode::F3) {
+            self.gpu_state = match self.gpu_state {
+                Some(_) => None,
+                None => Some(GpuStateWatch::new()),
+            };
+        }
+        self.update_calibration();
+        self.app.poll_debug_console(gpu.as_deref());
+        self.apply_setting_changes(gpu.as_deref_mut());
+        self.update_speed();
+        self.app.core.profiler.record(FrameScope::Io, start);
+
+        // Frames émulées pendant ce rafraîchissement, selon la cadence choisie
+        let elapsed = self.last_refresh.map_or(std::time::Duration::ZERO, |last| start.duration_since(last));
+        self.last_refresh = Some(start);
+        let plan = self.pacer.next_refresh(elapsed);
+        self.app.core.set_frame_rate(self.pacer.emulated_rate());
+
+        let mut emulated = 0;
+        while emulated < plan.frames && self.app.running && !self.app.paused {
+            self.app.core.event_log.record(FrameEventKind::InputSample);
+            let executed_cycles = match self.app.core.run_frame(self.app.input_state.io_word(), gpu.as_deref_mut()) {
+                Err(EmulatorError::SubsystemPanic(report)) => {
+                    self.app.write_crash_report(&report);
+                    break;
+                }
+                result => result?,
+            };
+            self.app.report_achievements();
+            self.app.break_on_quarantine();
+            emulated += 1;
+
+            // Statistiques de performance
+            if executed_cycles > 0 {
+                let buffer_stats = self.app.core.memory.gpu_command_buffer.stats();
+                println!("GPU Buffer: {} lots traités, taille moyenne {:.1}, max {}", 
+                        buffer_stats.batches_processed, buffer_stats.average_batch_size, buffer_stats.max_batch_size);
+            }
+        }
+        if let Some(gpu) = gpu.as_deref_mut() {
+            gpu.set_frame_blend(plan.blend, emulated > 0);
+        }
+        self.app.collect_heatmap();
+
+        // État du GPU relevé une fois la frame émulée
+        if let (Some(watch), Some(gpu)) = (self.gpu_state.as_mut(), gpu.as_deref()) {
+            watch.update(&gpu.debug_state());
+        }
+
+        if let Some(server) = &self.app.stats_server {
+            server.publish(self.app.core.stats());
+        }
+        if let Some(forwarder) = &mut self.app.lamp_forwarder {
+            if let Err(e) = forwarder.publish(self.app.core.memory.lamps()) {
+                eprintln!("Transmission des lampes interrompue: {:#}", e);
+                self.app.lamp_forwarder = None;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl EmulatorApp {
-    pub fn new(rom_path: Option<String>) -> Result<Self> {
-        let config = EmulatorConfig::load_or_default("config.toml");
-        let memory = Model2Memory::new();
-        let mut rom_system = Model2RomSystem::new();
-
-        // Ajouter plusieurs chemins de recherche pour les ROMs
-        rom_system.add_search_path("./roms");
-        rom_system.add_search_path("../roms");
-        rom_system.add_search_path("../../roms");
-        rom_system.add_search_path("./roms/model2");
-        rom_system.add_search_path("../roms/model2");
-        rom_system.add_search_path("../../roms/model2");
+    pub fn new(rom_path: Option<String>, paths: AppPaths) -> Result<Self> {
+        if let Err(e) = paths.ensure_dirs() {
+            eprintln!("Impossible de créer les répertoires utilisateur: {}", e);
+        }
+
+        // Reprendre les fichiers d'une installation portable, à côté de l'exécutable
+        if let Some(exe_dir) = executable_dir() {
+            match paths.migrate_legacy(&exe_dir) {
+                Ok(migrated) => {
+                    for file in migrated {
+                        println!("Fichier migré: {}", file.display());
+                    }
+                },
+                Err(e) => eprintln!("Migration des fichiers impossible: {}", e),
+            }
+        }
+
+        let mut config = Settings::new(EmulatorConfig::load_or_default(&paths.config_file));
+        let setting_changes = config.subscribe("");
+        let gun_calibration = GunCalibrationSet::load(&paths.nvram_dir);
+        let rom_system = rom_system_for(&paths);
 
         // Charger la ROM si fournie
+        let game = rom_path.as_deref().and_then(|path| game_id_from_rom(path.as_ref()));
         if let Some(path) = rom_path {
             println!("Tentative de chargement de la ROM: {}", path);
             // TODO: Charger et intégrer la ROM
         }
 
-        Ok(Self {
-            cpu: NecV60::new(),
-            memory,
-            audio: ScspAudio::new()?,
-            input: InputManager::new(),
+        let stats_server = config.emulation.stats_port.and_then(|port| match StatsServer::start(port) {
+            Ok(server) => {
+                println!("Statistiques disponibles sur http://{}/stats", server.address());
+                Some(server)
+            },
+            Err(e) => {
+                eprintln!("Point d'accès des statistiques indisponible: {}", e);
+                None
+            },
+        });
+
+        let lamp_forwarder = config.emulation.lamp_output.as_deref().and_then(|target| {
+            match target.parse().and_then(|target| LampForwarder::open(&target)) {
+                Ok(forwarder) => {
+                    println!("Lampes de la borne transmises à {}", target);
+                    Some(forwarder)
+                },
+                Err(e) => {
+                    eprintln!("Transmission des lampes indisponible: {:#}", e);
+                    None
+                },
+            }
+        });
+
+        let mut app = Self {
+            core: EmulatorCore::new(&config.audio),
+            input: InputManager::with_config(&config.input),
+            input_state: InputState::new(),
+            gun_calibration,
             config,
+            setting_changes,
+            paths,
             rom_system,
+            game,
             running: true,
             paused: false,
-        })
+            profile_output: None,
+            trace_output: None,
+            call_trace_output: None,
+            event_log_output: None,
+            session_report_output: None,
+            symbols: SymbolMap::new(),
+            memory_search: MemorySearch::new(),
+            heatmap: None,
+            debug_console: None,
+            stats_server,
+            lamp_forwarder,
+        };
+
+        if app.config.emulation.compat_report {
+            app.core.session_report.enable();
+        }
+        if app.config.emulation.auto_save_state {
+            app.resume_auto_state();
+        }
+        Ok(app)
+    }
+
+    /// Révision du jeu lancé et de sa carte, d'après la base des jeux
+    fn game_revisions(&self) -> (String, String) {
+        let info = self.game.as_deref().and_then(|game| self.rom_system.rom_manager.database().find_game(game));
+        match info {
+            Some(info) => (info.version.clone(), info.board.clone()),
+            None => (String::new(), "Model 2".to_string()),
+        }
+    }
+
+    /// Titre de la fenêtre : jeu lancé et horloges des CPU si elles sont modifiées
+    pub fn window_title(&self) -> String {
+        let mut title = WINDOW_TITLE.to_string();
+        if let Some(game) = self.game.as_deref() {
+            title.push_str(&format!(" — {}", game));
+        }
+        if !self.core.clocks.is_stock() {
+            title.push_str(&format!(" [{}]", self.core.clocks.describe()));
+        }
+        title
+    }
+
+    /// Visibilité des polygones retenue pour le jeu lancé
+    pub fn depth_mode(&self) -> DepthMode {
+        let profile = self.game.as_deref()
+            .and_then(|game| self.rom_system.rom_manager.database().find_game(game))
+            .is_some_and(|info| info.system_config.graphics_config.priority_sorting);
+        if self.config.video.polygon_sorting.use_priority(profile) {
+            DepthMode::Priority
+        } else {
+            DepthMode::ZBuffer
+        }
+    }
+
+    /// Transformation des sommets en virgule fixe pour le jeu lancé
+    pub fn fixed_point_geometry(&self) -> bool {
+        let profile = self.game.as_deref()
+            .and_then(|game| self.rom_system.rom_manager.database().find_game(game))
+            .is_some_and(|info| info.system_config.graphics_config.fixed_point_geometry);
+        self.config.video.geometry_precision_for(self.game.as_deref()).use_fixed_point(profile)
+    }
+
+    /// En-tête des états du jeu lancé
+    fn state_header(&self, thumbnail: Option<Thumbnail>) -> Option<SaveStateHeader> {
+        let game = self.game.as_deref()?;
+        let (revision, board) = self.game_revisions();
+        Some(SaveStateHeader::new(game, &revision, &board).with_thumbnail(thumbnail))
+    }
+
+    /// Emplacements d'états numérotés du jeu lancé
+    pub fn save_slots(&self) -> Option<SaveSlots> {
+        let game = self.game.as_deref()?;
+        let (_, board) = self.game_revisions();
+        Some(SaveSlots::new(&self.paths.saves_dir, game, &board))
+    }
+
+    /// Sauvegarde l'état courant dans un emplacement numéroté
+    pub fn save_to_slot(&self, slot: u8, thumbnail: Option<Thumbnail>) -> Result<PathBuf> {
+        let (Some(slots), Some(header)) = (self.save_slots(), self.state_header(thumbnail)) else {
+            anyhow::bail!("Aucun jeu lancé");
+        };
+        slots.save(slot, SaveState::capture(&self.core.cpu, &self.core.memory), header)
+    }
+
+    /// Charge l'état d'un emplacement numéroté
+    pub fn load_from_slot(&mut self, slot: u8) -> Result<()> {
+        let slots = self.save_slots().ok_or_else(|| anyhow::anyhow!("Aucun jeu lancé"))?;
+        slots.load(slot)?.restore(&mut self.core.cpu, &mut self.core.memory)
+    }
+
+    /// Emplacement de l'état automatique du jeu lancé
+    pub fn auto_state_path(&self) -> Option<PathBuf> {
+        self.game
+            .as_deref()
+            .map(|game| state_path(&self.paths.saves_dir, game, AUTO_SLOT))
+    }
+
+    /// Reprend l'état sauvegardé à la dernière fermeture, s'il existe
+    pub fn resume_auto_state(&mut self) {
+        let Some(path) = self.auto_state_path().filter(|path| path.exists()) else {
+            return;
+        };
+
+        let (_, board) = self.game_revisions();
+        let game = self.game.clone().unwrap_or_default();
+        let result = SaveState::load(&path).and_then(|state| {
+            state.header.check_compatible(&game, &board)?;
+            state.restore(&mut self.core.cpu, &mut self.core.memory)
+        });
+        match result {
+            Ok(()) => println!("Reprise de l'état: {}", path.display()),
+            Err(e) => eprintln!("Impossible de reprendre l'état automatique: {:#}", e),
+        }
+    }
+
+    /// Conserve l'heure de l'horloge du jeu lancé avec sa NVRAM
+    fn save_rtc(&self) {
+        let Some(game) = &self.game else { return };
+        if let Err(e) = self.core.memory.rtc().save(&self.paths.nvram_dir, game) {
+            eprintln!("Impossible d'enregistrer l'horloge du jeu: {:#}", e);
+        }
+    }
+
+    /// Sauvegarde l'état automatique du jeu lancé (option activée)
+    fn save_auto_state(&self) {
+        if !self.config.emulation.auto_save_state {
+            return;
+        }
+        let (Some(path), Some(header)) = (self.auto_state_path(), self.state_header(None)) else {
+            return;
+        };
+
+        match SaveState::capture(&self.core.cpu, &self.core.memory).with_header(header).save(&path) {
+            Ok(()) => println!("État sauvegardé: {}", path.display()),
+            Err(e) => eprintln!("Impossible de sauvegarder l'état automatique: {:#}", e),
+        }
     }
     
+    /// Active le profileur du CPU ; le profil est écrit dans `output` en quittant
+    pub fn enable_profiler(&mut self, output: PathBuf) {
+        self.core.cpu.enable_profiler();
+        self.profile_output = Some(output);
+    }
+
+    /// Enregistre la répartition du temps de frame au format chrome://tracing
+    pub fn enable_frame_trace(&mut self, output: PathBuf) {
+        self.core.profiler.enable_trace();
+        self.trace_output = Some(output);
+    }
+
+    /// Horodate les VBlank, envois audio, lectures des entrées et
+    /// présentations de la session ; le journal est écrit dans `output`
+    pub fn enable_event_log(&mut self, output: PathBuf) {
+        let sample_rate = self.core.audio.output().sample_rate();
+        self.core.event_log.enable(sample_rate);
+        self.event_log_output = Some(output);
+    }
+
+    /// Relève les manques de l'émulation rencontrés pendant la session ; le
+    /// rapport est écrit dans `output`
+    pub fn enable_session_report(&mut self, output: PathBuf) {
+        self.core.session_report.enable();
+        self.session_report_output = Some(output);
+    }
+
+    /// Lance le jeu de `--rom` sans fenêtre ni son et enregistre les images
+    /// des frames de `range` dans `directory`, avec les réglages vidéo du jeu
+    pub fn capture_frames(&mut self, range: std::ops::RangeInclusive<u64>, directory: &std::path::Path) -> Result<FrameCapture> {
+        let game = self.game.clone().ok_or_else(|| anyhow::anyhow!("aucun jeu à capturer (--rom)"))?;
+
+        // Machine neuve, sans état repris, pour des images reproductibles
+        self.core = EmulatorCore::new(&AudioConfig { backend: AudioBackendKind::Null, ..self.config.audio.clone() });
+        self.load_rom(&game)?;
+
+        let mut gpu = Model2Gpu::headless();
+        gpu.set_texture_filter(TextureFilter::from_config(&self.config.video.texture_filtering));
+        gpu.set_anti_aliasing(self.config.video.anti_aliasing);
+        self.apply_game_video(&mut gpu);
+        Ok(capture_frames(&mut self.core, &mut gpu, &game, range, directory)?)
+    }
+
+    /// Journalise les entrées/sorties de fonction du V60 dans `output`
+    pub fn enable_call_trace(&mut self, output: PathBuf) {
+        self.core.cpu.call_stack.enable_trace();
+        self.call_trace_output = Some(output);
+    }
+
+    /// Charge une table de symboles (texte `adresse nom` ou ELF) ; plusieurs
+    /// fichiers peuvent être chargés, les derniers l'emportent
+    pub fn load_symbols(&mut self, path: &std::path::Path) -> Result<()> {
+        self.symbols.merge(SymbolMap::load(path)?);
+        Ok(())
+    }
+
+    /// Pile d'appels courante du V60, annotée avec les symboles
+    pub fn call_stack_text(&self) -> String {
+        format_call_stack(self.core.cpu.call_stack.frames(), &self.symbols)
+    }
+
+    /// Commande `disasm [adresse] [nombre]` : instructions à partir d'une
+    /// adresse (par défaut le PC), annotées avec les symboles
+    fn disassembly_command(&self, line: &str) -> Result<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (start, count) = match words.as_slice() {
+            ["disasm"] => (self.core.cpu.registers.pc, DISASSEMBLY_LINES),
+            ["disasm", start] => (self.symbols.resolve(start)?, DISASSEMBLY_LINES),
+            ["disasm", start, count] => {
+                let count = count.parse().map_err(|_| anyhow::anyhow!("Nombre d'instructions invalide: {}", count))?;
+                (self.symbols.resolve(start)?, count)
+            }
+            _ => return Err(anyhow::anyhow!("Usage: disasm [adresse] [nombre]")),
+        };
+        let lines = disassemble(&self.core.memory, start, usize::min(count, MAX_DISASSEMBLY_LINES), &self.symbols)?;
+        Ok(lines.iter().map(|line| format!("{}\n", line)).collect())
+    }
+
+    /// Lit des commandes du débogueur sur l'entrée standard (une par ligne) ;
+    /// elles sont exécutées entre deux frames
+    pub fn enable_debug_console(&mut self) {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        self.debug_console = Some(receiver);
+    }
+
+    /// F8 ou commande `textures` : exporte les textures chargées en PNG,
+    /// par défaut dans `<données>/textures/<jeu>`
+    fn dump_textures(&self, gpu: Option<&Model2Gpu>, directory: Option<PathBuf>) {
+        let Some(gpu) = gpu else {
+            eprintln!("Export des textures impossible : aucun GPU");
+            return;
+        };
+        let directory = directory.unwrap_or_else(|| {
+            self.paths.data_dir.join("textures").join(self.game.as_deref().unwrap_or("inconnu"))
+        });
+        #[cfg(feature = "rom-tools")]
+        match crate::gpu::dump_textures(&gpu.texture_manager, &directory) {
+            Ok(report) => println!("{}", report),
+            Err(e) => eprintln!("Export des textures impossible: {:#}", e),
+        }
+        #[cfg(not(feature = "rom-tools"))]
+        eprintln!("Export des textures vers {} impossible : compilé sans la fonctionnalité rom-tools", directory.display());
+    }
+
+    /// Commande `aram` : plages de la mémoire wave lues par les slots
+    /// actifs, forme d'onde d'une plage ou écoute sur la sortie audio
+    fn audio_ram_command(&mut self, line: &str) -> Result<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let range = |start: &str, end: &str| -> Result<(u32, u32)> {
+            Ok(wave_range(self.symbols.resolve(start)?, self.symbols.resolve(end)?)?)
+        };
+        match words.as_slice() {
+            ["aram"] => {
+                let regions = wave_regions(&self.core.audio);
+                if regions.is_empty() {
+                    return Ok("Aucun slot PCM actif\n".to_string());
+                }
+                Ok(regions.iter().map(|region| format!("{}\n", region)).collect())
+            }
+            ["aram", "wave", start, end] => {
+                let (start, end) = range(start, end)?;
+                let peaks = wave_peaks(&self.core.audio.registers.wave_memory, start, end, AUDIO_RAM_WAVE_COLUMNS);
+                Ok(format!("0x{:06X}-0x{:06X}\n{}", start, end, render_waveform(&peaks, AUDIO_RAM_WAVE_ROWS)))
+            }
+            ["aram", "play", start, end] => {
+                let (start, end) = range(start, end)?;
+                let frames = preview_region(&mut self.core.audio, start, end)?;
+                Ok(format!("0x{:06X}-0x{:06X} : {} trames envoyées à la sortie audio\n", start, end, frames))
+            }
+            _ => Err(anyhow::anyhow!("Usage: aram | aram wave <début> <fin> | aram play <début> <fin>")),
+        }
+    }
+
+    /// Exécute les commandes du débogueur en attente
+    fn poll_debug_console(&mut self, gpu: Option<&Model2Gpu>) {
+        let Some(console) = self.debug_console.as_ref() else {
+            return;
+        };
+        let lines: Vec<String> = console.try_iter().collect();
+
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == "stack" {
+                print!("{}", self.call_stack_text());
+                continue;
+            }
+            if line == "continue" {
+                self.paused = false;
+                continue;
+            }
+            if line == "gpu" {
+                match gpu {
+                    Some(gpu) => print!("{}", gpu.debug_state()),
+                    None => eprintln!("Aucun GPU"),
+                }
+                for line in self.core.gpu_commands.lines() {
+                    println!("{}", line);
+                }
+                continue;
+            }
+            if line == "ffb" {
+                let state = self.core.force_feedback_state();
+                let (strong, weak) = state.rumble();
+                println!(
+                    "Retour de force : rappel {} friction {} vibration {} couple {:+} (manette {:.2}/{:.2})",
+                    state.centering, state.friction, state.vibration, state.roll, strong, weak
+                );
+                continue;
+            }
+            if line.split_whitespace().next() == Some("textures") {
+                let directory = line.split_once(char::is_whitespace).map_or("", |(_, directory)| directory.trim());
+                self.dump_textures(gpu, (!directory.is_empty()).then(|| directory.into()));
+                continue;
+            }
+            if let Some("settings" | "get" | "set") = line.split_whitespace().next() {
+                if let Err(e) = self.settings_command(line) {
+                    eprintln!("{:#}", e);
+                }
+                continue;
+            }
+            if line == "heatmap" {
+                print!("{}", self.heatmap_report());
+                continue;
+            }
+            if line.split_whitespace().next() == Some("disasm") {
+                match self.disassembly_command(line) {
+                    Ok(listing) => print!("{}", listing),
+                    Err(e) => eprintln!("{:#}", e),
+                }
+                continue;
+            }
+            if line.split_whitespace().next() == Some("aram") {
+                match self.audio_ram_command(line) {
+                    Ok(report) => print!("{}", report),
+                    Err(e) => eprintln!("{:#}", e),
+                }
+                continue;
+            }
+
+            let result = MemoryCommand::parse(line, &self.symbols)
+                .and_then(|command| command.execute(&mut self.core.memory, &mut self.memory_search));
+            match result {
+                Ok(report) => println!("{}", report),
+                Err(e) => eprintln!("{:#}", e),
+            }
+        }
+    }
+
+    /// Commandes `settings [préfixe]`, `get <clé>` et `set <clé> <valeur>`
+    /// de la console du débogueur
+    fn settings_command(&mut self, line: &str) -> Result<()> {
+        let mut words = line.splitn(3, char::is_whitespace);
+        let command = words.next().unwrap_or_default();
+        let key = words.next().unwrap_or_default();
+        match command {
+            "settings" => {
+                for (key, value) in self.config.entries().iter().filter(|(entry, _)| entry.starts_with(key)) {
+                    println!("{} = {}", key, value);
+                }
+            }
+            "get" => println!("{} = {}", key, self.config.value(key)?),
+            _ => {
+                let value = words.next().ok_or_else(|| anyhow::anyhow!("usage : set <clé> <valeur>"))?;
+                self.set_option(&format!("{}={}", key, value))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Modifie un réglage `clé=valeur` (console du débogueur) ; il est
+    /// reporté en direct et enregistré à la fermeture
+    pub fn set_option(&mut self, assignment: &str) -> Result<()> {
+        self.assign_option(assignment, true)
+    }
+
+    /// Modifie un réglage `clé=valeur` pour cette session seulement
+    /// (`--set`) : il n'est pas enregistré à la fermeture
+    pub fn override_option(&mut self, assignment: &str) -> Result<()> {
+        self.assign_option(assignment, false)
+    }
+
+    fn assign_option(&mut self, assignment: &str, persist: bool) -> Result<()> {
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("réglage attendu sous la forme clé=valeur : {}", assignment))?;
+        let changes = if persist {
+            self.config.set_str(key.trim(), value)?
+        } else {
+            self.config.set_override(key.trim(), value)?
+        };
+        if changes.is_empty() {
+            println!("{} inchangé", key.trim());
+        }
+        for change in changes {
+            let value = change.new.map_or_else(|| "(absent)".to_string(), |value| value.to_string());
+            println!("{} = {}", change.key, value);
+        }
+        Ok(())
+    }
+
+    /// Écrit le journal des appels de fonction
+    fn write_call_trace(&self) {
+        let Some(output) = self.call_trace_output.as_ref() else {
+            return;
+        };
+
+        let result = std::fs::File::create(output).and_then(|file| {
+            write_call_trace(self.core.cpu.call_stack.events(), &self.symbols, std::io::BufWriter::new(file))
+        });
+        match result {
+            Ok(()) => println!("Journal des appels écrit: {}", output.display()),
+            Err(e) => eprintln!("Impossible d'écrire le journal des appels: {}", e),
+        }
+    }
+
+    /// Écrit la trace des frames
+    fn write_frame_trace(&self) {
+        let Some(output) = self.trace_output.as_ref() else {
+            return;
+        };
+
+        let result = std::fs::File::create(output)
+            .and_then(|file| self.core.profiler.write_chrome_trace(std::io::BufWriter::new(file)));
+        match result {
+            Ok(()) => println!("Trace des frames écrite: {}", output.display()),
+            Err(e) => eprintln!("Impossible d'écrire la trace des frames: {}", e),
+        }
+    }
+
+    /// Écrit le journal des événements de frame et son bilan
+    fn write_event_log(&self) {
+        let Some(output) = self.event_log_output.as_ref() else {
+            return;
+        };
+
+        let session = self.core.event_log.session();
+        let result = std::fs::File::create(output).and_then(|file| session.write(std::io::BufWriter::new(file)));
+        match result {
+            Ok(()) => print!("Journal des événements écrit: {}\n{}", output.display(), session.validate()),
+            Err(e) => eprintln!("Impossible d'écrire le journal des événements: {}", e),
+        }
+    }
+
+    /// Écrit le rapport de compatibilité du jeu en cours
+    fn write_session_report(&self) {
+        if !self.core.session_report.is_enabled() {
+            return;
+        }
+        let output = match (&self.session_report_output, &self.game) {
+            (Some(output), _) => output.clone(),
+            (None, Some(game)) => self.paths.data_dir.join("reports").join(format!("{}.json", game)),
+            (None, None) => return,
+        };
+
+        let mut report = self.core.session_report.report(self.core.frames());
+        report.game = report.game.or_else(|| self.game.clone());
+        let result = output
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::File::create(&output))
+            .and_then(|file| report.write(std::io::BufWriter::new(file)));
+        match result {
+            Ok(()) => println!("Rapport de compatibilité écrit: {}", output.display()),
+            Err(e) => eprintln!("Impossible d'écrire le rapport de compatibilité: {}", e),
+        }
+    }
+
+    /// Panic rattrapé dans un sous-système : l'émulation est mise en pause
+    /// et le rapport de plantage écrit dans `<données>/reports/`
+    fn write_crash_report(&mut self, report: &CrashReport) {
+        self.paused = true;
+        eprintln!("Émulation en pause après un {}", report);
+        let game = report.game.as_deref().or(self.game.as_deref()).unwrap_or("inconnu");
+        let output = self.paths.data_dir.join("reports").join(format!("crash-{}-{}.json", game, report.frame));
+        match report.write(&output) {
+            Ok(()) => println!("Rapport de plantage écrit: {} ; P pour reprendre, R pour redémarrer le jeu", output.display()),
+            Err(e) => eprintln!("Impossible d'écrire le rapport de plantage: {}", e),
+        }
+    }
+
+    /// Affiche les appels aux routines du micrologiciel simulées
+    fn report_hle_calls(&self) {
+        if let Some(hle) = self.core.cpu.hle.as_ref() {
+            print!("Appels HLE du micrologiciel:\n{}", hle.report());
+        }
+    }
+
+    /// Politique `break` : met l'émulation en pause sur l'instruction ou la
+    /// commande non implémentée rencontrée pendant la frame
+    fn break_on_quarantine(&mut self) {
+        if let Some(reason) = self.core.cpu.quarantine.take_break() {
+            self.paused = true;
+            println!("Arrêt : {} (PC {:#010X})", reason, self.core.cpu.registers.pc);
+            print!("{}", self.call_stack_text());
+            println!("Commande \"continue\" de la console de débogage pour reprendre");
+        }
+    }
+
+    /// Affiche les opcodes et commandes non implémentés rencontrés, du plus
+    /// fréquent au plus rare
+    fn report_quarantine(&self) {
+        let quarantine = &self.core.cpu.quarantine;
+        if !quarantine.is_empty() {
+            print!("Manques de l'émulation rencontrés:\n{}", quarantine.report());
+        }
+    }
+
+    /// Écrit le profil du CPU et affiche les blocs les plus coûteux
+    fn write_profile(&mut self) {
+        let (Some(output), Some(profiler)) = (self.profile_output.as_ref(), self.core.cpu.profiler.as_ref()) else {
+            return;
+        };
+
+        print!("{}", profiler.report(20));
+        let result = std::fs::File::create(output)
+            .and_then(|file| profiler.write_callgrind(std::io::BufWriter::new(file)));
+        match result {
+            Ok(()) => println!("Profil écrit: {}", output.display()),
+            Err(e) => eprintln!("Impossible d'écrire le profil: {}", e),
+        }
+    }
+
+    /// Crée le GPU de la fenêtre avec les réglages vidéo ; sans carte
+    /// graphique utilisable, l'image est affichée par le CPU
+    fn create_gpu(&self, window: Arc<Window>) -> Option<Model2Gpu> {
+        let selection = AdapterSelection::from_config(&self.config.video);
+        let created = pollster::block_on(Model2Gpu::new(window.clone(), self.config.video.vsync, &selection))
+            .or_else(|e| {
+                eprintln!("Erreur d'initialisation GPU: {}, passage au rendu logiciel", e);
+                Model2Gpu::new_software(window)
+            });
+        match created {
+            Ok(mut g) => {
+                if self.core.profiler.is_tracing() {
+                    g.profiler.enable_trace();
+                }
+                let video = &self.config.video;
+                g.set_output_transform(OutputTransform::from_config(video));
+                g.set_texture_filter(TextureFilter::from_config(&video.texture_filtering));
+                g.set_anti_aliasing(video.anti_aliasing);
+                self.apply_game_video(&mut g);
+                println!("Model2 GPU initialisé (affichage {}, latence estimée {:.1} ms)",
+                    g.backend().name(), g.stats.present_latency_ms);
+                Some(g)
+            },
+            Err(e) => {
+                eprintln!("Erreur d'initialisation GPU: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Applique au GPU les réglages vidéo propres au jeu lancé
+    fn apply_game_video(&self, gpu: &mut Model2Gpu) {
+        let video = &self.config.video;
+        gpu.set_color_adjustment(video.color_for(self.game.as_deref()));
+        gpu.set_depth_mode(self.depth_mode());
+        gpu.set_fixed_point_geometry(self.fixed_point_geometry());
+        gpu.set_internal_scale(video.internal_scale_for(self.game.as_deref()));
+        gpu.set_mipmaps(video.mipmaps_for(self.game.as_deref()));
+    }
+
+    /// Relevé de la carte de chaleur terminé : enregistré en PNG si demandé,
+    /// par défaut dans `<données>/heatmaps/<jeu>`, et gardé pour la
+    /// surimpression et la console
+    fn collect_heatmap(&mut self) {
+        let Some(snapshot) = self.core.take_heatmap() else {
+            return;
+        };
+        if self.config.emulation.heatmap.export {
+            let path = self.paths.data_dir
+                .join("heatmaps")
+                .join(self.game.as_deref().unwrap_or("inconnu"))
+                .join(format!("heatmap_{:06}.png", self.core.frames()));
+            if let Err(e) = snapshot.save_png(&path) {
+                eprintln!("Export de la carte de chaleur impossible: {:#}", e);
+            }
+        }
+        self.heatmap = Some(snapshot);
+    }
+
+    /// Commande `heatmap` : blocs les plus accédés du dernier relevé
+    fn heatmap_report(&self) -> String {
+        let Some(snapshot) = self.heatmap.as_ref() else {
+            return "Aucun relevé de carte de chaleur (emulation.heatmap.enabled)\n".to_string();
+        };
+        let mut report = format!("Blocs de {} octets les plus accédés sur {} frames :\n", snapshot.block_size, snapshot.frames);
+        for block in snapshot.hottest(HEATMAP_REPORT_BLOCKS) {
+            report += &format!("  {:<24} {:>10} lectures {:>10} écritures\n", self.symbols.describe(block.address), block.reads, block.writes);
+        }
+        report
+    }
+
+    /// Haut-parleurs de la borne simulés pour le jeu lancé
+    fn apply_game_audio(&mut self) {
+        let game_default = self.game.as_deref()
+            .and_then(|game| self.rom_system.rom_manager.database().find_game(game))
+            .and_then(|info| info.system_config.audio_config.cabinet);
+        let profile = CabinetProfile::from_config(&self.config.audio, self.game.as_deref(), game_default);
+        self.core.audio.set_cabinet(profile);
+    }
+
     pub fn run(self) -> Result<()> {
-        let event_loop = EventLoop::new()?;
-        let window = Arc::new(WindowBuilder::new()
-            .with_title("Pixel Model 2 Rust - Émulateur SEGA Model 2")
-            .with_inner_size(winit::dpi::LogicalSize::new(800, 600))
+        self.run_with(EventLoop::new()?)
+    }
+
+    /// Lance l'émulateur sur une boucle d'événements déjà créée (Android)
+    pub fn run_with(self, event_loop: EventLoop<()>) -> Result<()> {
+        let builder = WindowBuilder::new()
+            .with_title(self.window_title());
+        let window = Arc::new(window::apply_geometry(builder, &self.config.video.window)
             .build(&event_loop)?);
+        window.set_fullscreen(window::fullscreen_for(&window, &self.config.video));
         
         let mut app_state = AppState::new(self);
         
-        // Créer le GPU avant la boucle d'événements
-        let mut gpu: Option<Model2Gpu> = None;
-        {
-            let window_ref = window.clone();
-            match pollster::block_on(Model2Gpu::new(window_ref)) {
-                Ok(g) => {
-                    gpu = Some(g);
-                    println!("Model2 GPU initialisé avec succès");
-                },
-                Err(e) => {
-                    eprintln!("Erreur d'initialisation GPU: {}", e);
-                }
-            }
-        }
+        // Créer le GPU avant la boucle d'événements ; sur Android, la surface
+        // n'existe qu'entre Resumed et Suspended
+        let mut gpu = if cfg!(target_os = "android") {
+            None
+        } else {
+            app_state.app.create_gpu(window.clone())
+        };
         
+        let mut pointer_captured = false;
+        let mut title = app_state.app.window_title();
+        app_state.pacer.set_display_rate(window::refresh_rate(&window));
+
         event_loop.run(move |event, elwt| {
             match event {
                 Event::WindowEvent { event, .. } => {
                     app_state.handle_window_event(&event);
+
+                    // Mémoriser la géométrie de la fenêtre en mode fenêtré
+                    match event {
+                        WindowEvent::Resized(size) => {
+                            let _ = app_state.app.config.update(|config| window::remember_size(&window, &mut config.video.window, size));
+                        },
+                        WindowEvent::Moved(position) => {
+                            let _ = app_state.app.config.update(|config| window::remember_position(&window, &mut config.video.window, position));
+                            // L'écran, et donc sa fréquence, a pu changer
+                            app_state.pacer.set_display_rate(window::refresh_rate(&window));
+                        },
+                        WindowEvent::CursorMoved { position, .. } => {
+                            let size = window.inner_size();
+                            app_state.app.input.handle_pointer(position.x, position.y, size.width, size.height);
+                        },
+                        WindowEvent::Touch(touch) => {
+                            let size = window.inner_size();
+                            app_state.app.input.handle_touch(&touch, size.width, size.height);
+                        },
+                        _ => {}
+                    }
                     
                     // Gérer les événements GPU
                     if let Some(ref mut gpu) = gpu {
                         match event {
                             WindowEvent::Resized(physical_size) => {
-                                // Pour l'instant, garder la résolution standard
-                                let _ = gpu.resize(crate::gpu::Model2Resolution::Standard);
+                                gpu.resize_surface(physical_size.width, physical_size.height);
                             },
                             WindowEvent::RedrawRequested => {
                                 if let Err(e) = gpu.end_frame() {
                                     eprintln!("Erreur GPU end_frame: {}", e);
                                 }
+                                app_state.app.core.event_log.record(FrameEventKind::Present);
                             },
                             _ => {}
                         }
@@ -312,12 +1502,63 @@ impl EmulatorApp {
                     if let Err(e) = app_state.run_frame(gpu.as_mut()) {
                         eprintln!("Erreur d'émulation: {}", e);
                     }
+
+                    // Appliquer une bascule plein écran demandée pendant la frame
+                    window::sync_fullscreen(&window, &app_state.app.config.video);
+
+                    // Titre à jour après un changement de jeu (horloges reprises au chargement)
+                    let current = app_state.app.window_title();
+                    if current != title {
+                        window.set_title(&current);
+                        title = current;
+                    }
+
+                    // Capturer le pointeur en jeu, le relâcher en pause
+                    let capture = app_state.wants_pointer_capture();
+                    if capture != pointer_captured {
+                        window::set_pointer_capture(&window, app_state.app.config.input.lightgun.pointer_capture, capture);
+                        pointer_captured = capture;
+                    }
+
+                    if let Some(ref mut gpu) = gpu {
+                        let aspect = gpu.surface_aspect();
+                        gpu.set_overlay(app_state.build_overlay(aspect));
+                    }
+
+                    if !app_state.app.running {
+                        elwt.exit();
+                    }
                     
                     // Redessiner
                     if gpu.is_some() {
                         window.request_redraw();
                     }
                 },
+                Event::Resumed if cfg!(target_os = "android") && gpu.is_none() => {
+                    gpu = app_state.app.create_gpu(window.clone());
+                },
+                Event::Suspended if cfg!(target_os = "android") => {
+                    // La surface de la fenêtre est détruite par le système
+                    gpu = None;
+                },
+                Event::LoopExiting => {
+                    app_state.app.write_profile();
+                    app_state.app.write_frame_trace();
+                    app_state.app.write_call_trace();
+                    app_state.app.write_event_log();
+                    app_state.app.write_session_report();
+                    app_state.app.report_hle_calls();
+                    app_state.app.report_quarantine();
+                    app_state.app.save_auto_state();
+                    app_state.app.save_rtc();
+
+                    // Conserver la géométrie et le mode d'affichage pour la prochaine session
+                    let app = &app_state.app;
+                    let saved = app.config.persisted().and_then(|config| config.save_to_file(&app.paths.config_file));
+                    if let Err(e) = saved {
+                        eprintln!("Impossible d'enregistrer la configuration: {}", e);
+                    }
+                },
                 _ => {}
             }
         })?;
@@ -326,27 +1567,168 @@ impl EmulatorApp {
     
     pub fn load_rom(&mut self, game_name: &str) -> Result<()> {
         println!("Chargement du jeu: {}", game_name);
-        
-        // Charger et mapper le jeu dans la mémoire principale
-        self.rom_system.load_and_map_game(game_name, &mut self.memory)?;
-        
-        // Générer un rapport d'état
-        let report = self.rom_system.generate_status_report()?;
-        println!("Rapport de chargement ROM:\n{}", report);
-        
-        // Réinitialiser le CPU après le chargement des ROMs
-        self.cpu.reset();
-        
-        // Initialiser le PC avec l'adresse de reset (typiquement dans la ROM programme)
-        // Pour SEGA Model 2, le reset vector est généralement à l'adresse 0x00000004
-        if let Ok(reset_vector) = self.memory.read_u32(0x00000004) {
-            self.cpu.registers.pc = reset_vector;
-            println!("PC initialisé à l'adresse de reset: {:#08X}", reset_vector);
-        } else {
-            println!("Avertissement: Impossible de lire le vecteur de reset, PC laissé à 0");
-        }
-        
+        self.core.load_game(&mut self.rom_system, game_name, &self.config.emulation)?;
         println!("Jeu '{}' chargé avec succès!", game_name);
+        self.game = Some(game_name.to_string());
+        self.input_state.set_player_count(self.rom_system.rom_manager.database().player_count(game_name));
+        self.apply_game_audio();
+        self.core.set_heatmap(&self.config.emulation.heatmap);
+        self.core.memory.set_rtc(RtcDevice::load(&self.paths.nvram_dir, game_name));
+        if self.config.emulation.achievements {
+            self.load_achievements(game_name);
+        }
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Change de jeu sans relancer le processus : l'état du jeu en cours est
+    /// sauvegardé (reprise automatique), puis la machine et le système de
+    /// ROMs sont recréés avant le chargement du nouveau jeu. Les diagnostics
+    /// activés le restent, leurs mesures repartent de zéro.
+    pub fn switch_game(&mut self, game_name: &str) -> Result<()> {
+        self.save_auto_state();
+        self.save_rtc();
+
+        self.rebuild_machine();
+        self.input_state = InputState::new();
+        self.game = None;
+
+        self.load_rom(game_name)?;
+        if self.config.emulation.auto_save_state {
+            self.resume_auto_state();
+        }
+        Ok(())
+    }
+
+    /// Reset logiciel, comme la ligne de reset de la carte : le jeu
+    /// redémarre sur son vecteur de reset avec sa RAM intacte
+    pub fn soft_reset(&mut self) -> Result<()> {
+        self.core.soft_reset()?;
+        println!("Reset logiciel");
+        Ok(())
+    }
+
+    /// Reset matériel, comme une mise sous tension : la machine et le
+    /// système de ROMs sont recréés et le jeu est rechargé puis remappé.
+    /// L'état automatique n'est ni sauvegardé ni repris ; l'horloge du jeu,
+    /// conservée par la pile de la carte, l'est.
+    pub fn hard_reset(&mut self) -> Result<()> {
+        self.save_rtc();
+        self.rebuild_machine();
+        if let Some(game) = self.game.take() {
+            self.load_rom(&game)?;
+        }
+        println!("Reset matériel");
+        Ok(())
+    }
+
+    /// Recrée la machine et le système de ROMs, diagnostics activés compris ;
+    /// le rapport de compatibilité du jeu quitté est écrit avant
+    fn rebuild_machine(&mut self) {
+        self.write_session_report();
+        let session_report = self.core.session_report.is_enabled();
+        let event_log = std::mem::take(&mut self.core.event_log);
+        self.core = EmulatorCore::new(&self.config.audio);
+        self.core.event_log = event_log;
+        if session_report {
+            self.core.session_report.enable();
+        }
+        if self.profile_output.is_some() {
+            self.core.cpu.enable_profiler();
+        }
+        if self.trace_output.is_some() {
+            self.core.profiler.enable_trace();
+        }
+        if self.call_trace_output.is_some() {
+            self.core.cpu.call_stack.enable_trace();
+        }
+        self.rom_system = rom_system_for(&self.paths);
+    }
+
+    /// Charge les succès définis pour le jeu et ceux déjà débloqués
+    fn load_achievements(&mut self, game_name: &str) {
+        let path = Achievements::game_file(&self.paths.achievements_dir, game_name);
+        if !path.exists() {
+            return;
+        }
+        let mut achievements = match Achievements::load(&path) {
+            Ok(achievements) => achievements,
+            Err(e) => {
+                eprintln!("Succès ignorés: {:#}", e);
+                return;
+            }
+        };
+        if let Ok(unlocked) = std::fs::read_to_string(unlocked_achievements_path(&self.paths.nvram_dir, game_name)) {
+            achievements.restore_unlocked(unlocked.lines());
+        }
+        println!("{} succès chargés ({} déjà débloqués)", achievements.len(), achievements.unlocked().count());
+        self.core.achievements = achievements;
+    }
+
+    /// Affiche les succès et événements de la frame ; les succès débloqués
+    /// sont conservés avec la NVRAM du jeu
+    fn report_achievements(&mut self) {
+        let events = self.core.achievements.take_events();
+        if events.is_empty() {
+            return;
+        }
+        for event in &events {
+            let kind = if event.unlocked { "Succès débloqué" } else { "Événement" };
+            println!("🏆 {}: {} {}", kind, event.title, event.description);
+        }
+        let Some(game) = &self.game else { return };
+        if events.iter().any(|event| event.unlocked) {
+            let unlocked: Vec<&str> = self.core.achievements.unlocked().collect();
+            let path = unlocked_achievements_path(&self.paths.nvram_dir, game);
+            if let Err(e) = std::fs::write(&path, unlocked.join("\n")) {
+                eprintln!("Impossible d'enregistrer les succès {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Système de ROMs cherchant dans les répertoires de l'utilisateur, par ordre de priorité
+pub fn rom_system_for(paths: &AppPaths) -> Model2RomSystem {
+    let mut rom_system = Model2RomSystem::new();
+    for dir in paths.rom_search_paths() {
+        rom_system.add_search_path(dir.join("model2"));
+        rom_system.add_search_path(dir);
+    }
+    rom_system
+}
+
+/// Liste des succès débloqués d'un jeu, un identifiant par ligne
+fn unlocked_achievements_path(nvram_dir: &std::path::Path, game: &str) -> PathBuf {
+    nvram_dir.join(format!("{}.achievements", game))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hard_reset_keeps_rtc() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = AppPaths::from_roots(dir.path().join("cfg"), dir.path().join("data"));
+        let mut app = EmulatorApp::new(None, paths).unwrap();
+        app.game = Some("daytona".to_string());
+        let mut rtc = RtcDevice::new();
+        rtc.offset = 3_600;
+        app.core.memory.set_rtc(rtc);
+
+        // Sans ROMs le jeu n'est pas rechargé, mais l'horloge a été conservée
+        let _ = app.hard_reset();
+        assert_eq!(RtcDevice::load(&app.paths.nvram_dir, "daytona").offset, 3_600);
+    }
+
+    #[test]
+    fn test_disassembly_command_resolves_symbols() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = AppPaths::from_roots(dir.path().join("cfg"), dir.path().join("data"));
+        let mut app = EmulatorApp::new(None, paths).unwrap();
+        app.symbols.insert(0x1000, "main_loop");
+
+        let listing = app.disassembly_command("disasm main_loop 2").unwrap();
+        assert!(listing.starts_with("main_loop:\n00001000"));
+        assert_eq!(listing.lines().count(), 3);
+        assert!(app.disassembly_command("disasm 0x1000 beaucoup").is_err());
+    }
+}
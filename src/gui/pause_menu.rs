@@ -0,0 +1,306 @@
+//! Menu de pause : réglages du moniteur émulé, commandes des joueurs,
+//! anticrénelage et reset
+//!
+//! Affiché quand l'émulation est en pause (touche P). Haut / bas choisit un
+//! réglage, gauche / droite le modifie et Retour arrière rétablit la valeur
+//! neutre. Les réglages du moniteur sont mémorisés pour le jeu lancé. Sur
+//! la ligne des commandes, gauche / droite choisit le joueur et Entrée
+//! demande une touche pour chacun de ses boutons (Échap abandonne). Les
+//! dernières lignes sont des actions, déclenchées par Entrée.
+
+use winit::keyboard::KeyCode;
+
+use crate::config::{AntiAliasing, ColorAdjustment, ColorParameter};
+use crate::input::{Button, InputState, MAX_PLAYERS};
+
+/// Ligne du menu de pause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseItem {
+    Color(ColorParameter),
+    /// Touches d'un joueur
+    Controls,
+    AntiAliasing,
+    Action(PauseAction),
+}
+
+/// Étape du remappage des touches d'un joueur
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemapEvent {
+    /// Touche choisie pour un bouton du joueur (0 pour le joueur 1)
+    Bound(usize, Button, KeyCode),
+    /// Remappage abandonné ; les touches déjà choisies restent
+    Cancelled,
+}
+
+/// Action déclenchée depuis le menu de pause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseAction {
+    /// Reset logiciel : CPU et I/O, RAM conservée
+    SoftReset,
+    /// Reset matériel : machine recréée et jeu rechargé
+    HardReset,
+}
+
+impl PauseItem {
+    /// Lignes dans l'ordre d'affichage
+    pub const ALL: [PauseItem; 8] = [
+        PauseItem::Color(ColorParameter::ALL[0]),
+        PauseItem::Color(ColorParameter::ALL[1]),
+        PauseItem::Color(ColorParameter::ALL[2]),
+        PauseItem::Color(ColorParameter::ALL[3]),
+        PauseItem::Controls,
+        PauseItem::AntiAliasing,
+        PauseItem::Action(PauseAction::SoftReset),
+        PauseItem::Action(PauseAction::HardReset),
+    ];
+}
+
+/// Menu des réglages du moniteur
+#[derive(Debug, Clone, Default)]
+pub struct PauseMenu {
+    selected: usize,
+
+    /// Joueur de la ligne des commandes
+    player: usize,
+
+    /// Bouton (index dans [`Button::ALL`]) dont la touche est attendue
+    capture: Option<usize>,
+}
+
+impl PauseMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Réglage sélectionné
+    pub fn selected(&self) -> PauseItem {
+        PauseItem::ALL[self.selected]
+    }
+
+    /// Index du réglage sélectionné dans [`PauseItem::ALL`]
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Joueur choisi sur la ligne des commandes
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    /// Joueur et bouton dont la touche est attendue ; les touches vont
+    /// alors au remappage plutôt qu'aux raccourcis
+    pub fn capturing(&self) -> Option<(usize, Button)> {
+        self.capture.map(|index| (self.player, Button::ALL[index]))
+    }
+
+    /// Remappage des touches : Entrée sur la ligne des commandes le
+    /// commence, puis chaque touche enfoncée est attribuée au bouton suivant
+    pub fn remap(&mut self, input: &InputState) -> Option<RemapEvent> {
+        let Some(index) = self.capture else {
+            if self.selected() == PauseItem::Controls && input.key_pressed(KeyCode::Enter) {
+                self.capture = Some(0);
+            }
+            return None;
+        };
+        if input.key_pressed(KeyCode::Escape) {
+            self.capture = None;
+            return Some(RemapEvent::Cancelled);
+        }
+        let key = input.keys_pressed().find(|&key| crate::input::key_name(key).is_some())?;
+        self.capture = Some(index + 1).filter(|&next| next < Button::ALL.len());
+        Some(RemapEvent::Bound(self.player, Button::ALL[index], key))
+    }
+
+    /// Traite les touches de la frame ; retourne vrai si un réglage a changé
+    pub fn handle(&mut self, input: &InputState, color: &mut ColorAdjustment, anti_aliasing: &mut AntiAliasing) -> bool {
+        if self.capture.is_some() {
+            return false;
+        }
+        let count = PauseItem::ALL.len();
+        if input.key_pressed(KeyCode::ArrowUp) {
+            self.selected = (self.selected + count - 1) % count;
+        }
+        if input.key_pressed(KeyCode::ArrowDown) {
+            self.selected = (self.selected + 1) % count;
+        }
+
+        let steps = input.key_pressed(KeyCode::ArrowRight) as i32 - input.key_pressed(KeyCode::ArrowLeft) as i32;
+        let reset = input.key_pressed(KeyCode::Backspace);
+        match self.selected() {
+            PauseItem::Color(parameter) => {
+                let before = *color;
+                if steps != 0 {
+                    color.step(parameter, steps);
+                }
+                if reset {
+                    color.set(parameter, ColorAdjustment::default().get(parameter));
+                }
+                *color != before
+            }
+            PauseItem::Controls => {
+                self.player = (self.player as i32 + steps).rem_euclid(MAX_PLAYERS as i32) as usize;
+                false
+            }
+            PauseItem::AntiAliasing => {
+                let before = *anti_aliasing;
+                *anti_aliasing = anti_aliasing.step(steps);
+                if reset {
+                    *anti_aliasing = AntiAliasing::default();
+                }
+                *anti_aliasing != before
+            }
+            PauseItem::Action(_) => false,
+        }
+    }
+
+    /// Action de la ligne sélectionnée si Entrée est pressée
+    pub fn action(&self, input: &InputState) -> Option<PauseAction> {
+        match self.selected() {
+            PauseItem::Action(action) if input.key_pressed(KeyCode::Enter) => Some(action),
+            _ => None,
+        }
+    }
+
+    /// Valeur du réglage sélectionné, pour la console
+    pub fn describe(&self, color: &ColorAdjustment, anti_aliasing: AntiAliasing) -> String {
+        match self.selected() {
+            PauseItem::Color(parameter) => format!("{}: {:.2}", parameter.name(), color.get(parameter)),
+            PauseItem::Controls => format!("Commandes du joueur {} : Entrée pour les remapper", self.player + 1),
+            PauseItem::AntiAliasing => format!("Anticrénelage: {}", anti_aliasing.name()),
+            PauseItem::Action(PauseAction::SoftReset) => "Reset logiciel (RAM conservée) : Entrée".to_string(),
+            PauseItem::Action(PauseAction::HardReset) => "Reset matériel (jeu rechargé) : Entrée".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputManager;
+    use winit::event::ElementState;
+
+    #[test]
+    fn test_select_adjust_and_reset() {
+        let mut manager = InputManager::new();
+        let mut state = InputState::new();
+        let mut menu = PauseMenu::new();
+        let mut color = ColorAdjustment::default();
+        let mut anti_aliasing = AntiAliasing::Off;
+
+        manager.handle_key(KeyCode::ArrowDown, ElementState::Pressed);
+        state.latch(&manager);
+        assert!(!menu.handle(&state, &mut color, &mut anti_aliasing));
+        assert_eq!(menu.selected(), PauseItem::Color(ColorParameter::Brightness));
+        manager.handle_key(KeyCode::ArrowDown, ElementState::Released);
+
+        manager.handle_key(KeyCode::ArrowRight, ElementState::Pressed);
+        state.latch(&manager);
+        assert!(menu.handle(&state, &mut color, &mut anti_aliasing));
+        assert!((color.brightness - 0.02).abs() < 1e-6);
+        assert_eq!(menu.describe(&color, anti_aliasing), "Luminosité: 0.02");
+        manager.handle_key(KeyCode::ArrowRight, ElementState::Released);
+
+        manager.handle_key(KeyCode::Backspace, ElementState::Pressed);
+        state.latch(&manager);
+        assert!(menu.handle(&state, &mut color, &mut anti_aliasing));
+        assert_eq!(color, ColorAdjustment::default());
+        assert_eq!(anti_aliasing, AntiAliasing::Off);
+    }
+
+    #[test]
+    fn test_anti_aliasing_row() {
+        let mut manager = InputManager::new();
+        let mut state = InputState::new();
+        let mut menu = PauseMenu::new();
+        let mut color = ColorAdjustment::default();
+        let mut anti_aliasing = AntiAliasing::Off;
+
+        // La ligne d'anticrénelage précède les deux actions : trois appuis vers le haut y mènent
+        for _ in 0..3 {
+            manager.handle_key(KeyCode::ArrowUp, ElementState::Pressed);
+            state.latch(&manager);
+            menu.handle(&state, &mut color, &mut anti_aliasing);
+            manager.handle_key(KeyCode::ArrowUp, ElementState::Released);
+            state.latch(&manager);
+        }
+        assert_eq!(menu.selected(), PauseItem::AntiAliasing);
+
+        manager.handle_key(KeyCode::ArrowRight, ElementState::Pressed);
+        state.latch(&manager);
+        assert!(menu.handle(&state, &mut color, &mut anti_aliasing));
+        assert_eq!(anti_aliasing, AntiAliasing::Msaa2x);
+        assert_eq!(menu.describe(&color, anti_aliasing), "Anticrénelage: MSAA 2x");
+        assert_eq!(color, ColorAdjustment::default());
+        manager.handle_key(KeyCode::ArrowRight, ElementState::Released);
+
+        manager.handle_key(KeyCode::Backspace, ElementState::Pressed);
+        state.latch(&manager);
+        assert!(menu.handle(&state, &mut color, &mut anti_aliasing));
+        assert_eq!(anti_aliasing, AntiAliasing::Off);
+    }
+
+    #[test]
+    fn test_reset_actions_need_enter() {
+        let mut manager = InputManager::new();
+        let mut state = InputState::new();
+        let mut menu = PauseMenu::new();
+        let mut color = ColorAdjustment::default();
+        let mut anti_aliasing = AntiAliasing::Off;
+
+        // Entrée sur un réglage ne déclenche rien
+        manager.handle_key(KeyCode::Enter, ElementState::Pressed);
+        state.latch(&manager);
+        assert_eq!(menu.action(&state), None);
+        manager.handle_key(KeyCode::Enter, ElementState::Released);
+
+        // La dernière ligne est le reset matériel
+        manager.handle_key(KeyCode::ArrowUp, ElementState::Pressed);
+        state.latch(&manager);
+        assert!(!menu.handle(&state, &mut color, &mut anti_aliasing));
+        assert_eq!(menu.selected(), PauseItem::Action(PauseAction::HardReset));
+        assert_eq!(menu.action(&state), None);
+        manager.handle_key(KeyCode::ArrowUp, ElementState::Released);
+
+        manager.handle_key(KeyCode::Enter, ElementState::Pressed);
+        state.latch(&manager);
+        assert_eq!(menu.action(&state), Some(PauseAction::HardReset));
+    }
+
+    #[test]
+    fn test_remap_player_keys() {
+        let mut manager = InputManager::new();
+        let mut state = InputState::new();
+        let mut menu = PauseMenu::new();
+        let mut color = ColorAdjustment::default();
+        let mut anti_aliasing = AntiAliasing::Off;
+        let mut press = |key, menu: &mut PauseMenu| {
+            manager.handle_key(key, ElementState::Pressed);
+            state.latch(&manager);
+            menu.handle(&state, &mut color, &mut anti_aliasing);
+            let event = menu.remap(&state);
+            manager.handle_key(key, ElementState::Released);
+            state.latch(&manager);
+            event
+        };
+
+        // Ligne des commandes, joueur 4 (gauche depuis le joueur 1)
+        for _ in 0..4 {
+            press(KeyCode::ArrowDown, &mut menu);
+        }
+        assert_eq!(menu.selected(), PauseItem::Controls);
+        press(KeyCode::ArrowLeft, &mut menu);
+        assert_eq!(menu.player(), 3);
+
+        assert_eq!(press(KeyCode::Enter, &mut menu), None);
+        assert_eq!(menu.capturing(), Some((3, Button::Up)));
+
+        // Les flèches sont attribuées, pas utilisées pour naviguer
+        assert_eq!(press(KeyCode::ArrowDown, &mut menu), Some(RemapEvent::Bound(3, Button::Up, KeyCode::ArrowDown)));
+        assert_eq!(menu.selected(), PauseItem::Controls);
+        // Touche réservée ignorée
+        assert_eq!(press(KeyCode::F5, &mut menu), None);
+        assert_eq!(press(KeyCode::KeyI, &mut menu), Some(RemapEvent::Bound(3, Button::Down, KeyCode::KeyI)));
+        assert_eq!(press(KeyCode::Escape, &mut menu), Some(RemapEvent::Cancelled));
+        assert_eq!(menu.capturing(), None);
+    }
+}
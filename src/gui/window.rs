@@ -0,0 +1,117 @@
+//! Gestion de la fenêtre : plein écran, choix de l'écran et géométrie
+
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    monitor::{MonitorHandle, VideoMode},
+    window::{CursorGrabMode, Fullscreen, Window, WindowBuilder},
+};
+
+use crate::config::{FullscreenMode, PointerCapture, VideoConfig, WindowGeometry};
+use crate::emulator::DEFAULT_DISPLAY_RATE;
+
+/// Applique la géométrie mémorisée à la création de la fenêtre
+pub fn apply_geometry(builder: WindowBuilder, geometry: &WindowGeometry) -> WindowBuilder {
+    let builder = builder.with_inner_size(PhysicalSize::new(geometry.width, geometry.height));
+
+    match (geometry.x, geometry.y) {
+        (Some(x), Some(y)) => builder.with_position(PhysicalPosition::new(x, y)),
+        _ => builder,
+    }
+}
+
+/// Sélectionne l'écran cible : par nom si configuré, sinon l'écran courant
+pub fn select_monitor(window: &Window, name: Option<&str>) -> Option<MonitorHandle> {
+    if let Some(name) = name {
+        let found = window
+            .available_monitors()
+            .find(|monitor| monitor.name().as_deref() == Some(name));
+        if found.is_some() {
+            return found;
+        }
+        log::warn!("Écran '{}' introuvable, utilisation de l'écran courant", name);
+    }
+
+    window.current_monitor().or_else(|| window.primary_monitor())
+}
+
+/// Fréquence de rafraîchissement de l'écran de la fenêtre, en Hz
+pub fn refresh_rate(window: &Window) -> f64 {
+    window
+        .current_monitor()
+        .and_then(|monitor| monitor.refresh_rate_millihertz())
+        .map_or(DEFAULT_DISPLAY_RATE, |millihertz| millihertz as f64 / 1000.0)
+}
+
+/// Choisit le mode vidéo exclusif : la plus grande définition, puis la
+/// fréquence de rafraîchissement la plus élevée
+pub fn best_video_mode(monitor: &MonitorHandle) -> Option<VideoMode> {
+    monitor.video_modes().max_by_key(|mode| {
+        let size = mode.size();
+        (size.width as u64 * size.height as u64, mode.refresh_rate_millihertz(), mode.bit_depth())
+    })
+}
+
+/// Calcule le plein écran correspondant à la configuration vidéo
+pub fn fullscreen_for(window: &Window, video: &VideoConfig) -> Option<Fullscreen> {
+    if !video.fullscreen {
+        return None;
+    }
+
+    let monitor = select_monitor(window, video.monitor.as_deref());
+    match video.fullscreen_mode {
+        FullscreenMode::Borderless => Some(Fullscreen::Borderless(monitor)),
+        FullscreenMode::Exclusive => match monitor.as_ref().and_then(best_video_mode) {
+            Some(mode) => Some(Fullscreen::Exclusive(mode)),
+            None => {
+                log::warn!("Aucun mode vidéo exclusif disponible, plein écran sans bordure");
+                Some(Fullscreen::Borderless(monitor))
+            }
+        },
+    }
+}
+
+/// Synchronise l'état plein écran de la fenêtre avec la configuration
+pub fn sync_fullscreen(window: &Window, video: &VideoConfig) {
+    if window.fullscreen().is_some() != video.fullscreen {
+        window.set_fullscreen(fullscreen_for(window, video));
+    }
+}
+
+/// Mémorise la taille de la fenêtre (uniquement en mode fenêtré)
+pub fn remember_size(window: &Window, geometry: &mut WindowGeometry, size: PhysicalSize<u32>) {
+    if window.fullscreen().is_none() && size.width > 0 && size.height > 0 {
+        geometry.width = size.width;
+        geometry.height = size.height;
+    }
+}
+
+/// Mémorise la position de la fenêtre (uniquement en mode fenêtré)
+pub fn remember_position(window: &Window, geometry: &mut WindowGeometry, position: PhysicalPosition<i32>) {
+    if window.fullscreen().is_none() {
+        geometry.x = Some(position.x);
+        geometry.y = Some(position.y);
+    }
+}
+
+/// Capture ou relâche le pointeur selon le mode configuré
+pub fn set_pointer_capture(window: &Window, mode: PointerCapture, captured: bool) {
+    let hide = captured && mode != PointerCapture::Off;
+    window.set_cursor_visible(!hide);
+
+    let grab = if captured && mode == PointerCapture::Confine {
+        CursorGrabMode::Confined
+    } else {
+        CursorGrabMode::None
+    };
+
+    // Certaines plateformes ne savent que verrouiller le curseur
+    if let Err(e) = window.set_cursor_grab(grab) {
+        if grab == CursorGrabMode::Confined {
+            if let Err(e) = window.set_cursor_grab(CursorGrabMode::Locked) {
+                log::warn!("Capture du pointeur impossible: {}", e);
+            }
+        } else {
+            log::warn!("Libération du pointeur impossible: {}", e);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Touches du clavier associées aux boutons de chaque joueur
+//!
+//! La configuration nomme les touches par leur position physique : une
+//! lettre (`W`), `Up` / `Down` / `Left` / `Right`, `Return`, ou le nom winit
+//! de la touche (`Digit3`, `Numpad1`, `NumpadEnter`, `ShiftLeft`...).
+
+use winit::keyboard::KeyCode;
+
+use super::Button;
+use crate::config::PlayerKeyConfig;
+
+/// Touches utilisables pour les joueurs et leur nom winit ; les touches de
+/// fonction, Échap et Tab (avance rapide) restent aux raccourcis de
+/// l'émulateur
+const KEYS: [(&str, KeyCode); 79] = [
+    ("KeyA", KeyCode::KeyA), ("KeyB", KeyCode::KeyB), ("KeyC", KeyCode::KeyC), ("KeyD", KeyCode::KeyD),
+    ("KeyE", KeyCode::KeyE), ("KeyF", KeyCode::KeyF), ("KeyG", KeyCode::KeyG), ("KeyH", KeyCode::KeyH),
+    ("KeyI", KeyCode::KeyI), ("KeyJ", KeyCode::KeyJ), ("KeyK", KeyCode::KeyK), ("KeyL", KeyCode::KeyL),
+    ("KeyM", KeyCode::KeyM), ("KeyN", KeyCode::KeyN), ("KeyO", KeyCode::KeyO), ("KeyP", KeyCode::KeyP),
+    ("KeyQ", KeyCode::KeyQ), ("KeyR", KeyCode::KeyR), ("KeyS", KeyCode::KeyS), ("KeyT", KeyCode::KeyT),
+    ("KeyU", KeyCode::KeyU), ("KeyV", KeyCode::KeyV), ("KeyW", KeyCode::KeyW), ("KeyX", KeyCode::KeyX),
+    ("KeyY", KeyCode::KeyY), ("KeyZ", KeyCode::KeyZ),
+    ("Digit0", KeyCode::Digit0), ("Digit1", KeyCode::Digit1), ("Digit2", KeyCode::Digit2), ("Digit3", KeyCode::Digit3),
+    ("Digit4", KeyCode::Digit4), ("Digit5", KeyCode::Digit5), ("Digit6", KeyCode::Digit6), ("Digit7", KeyCode::Digit7),
+    ("Digit8", KeyCode::Digit8), ("Digit9", KeyCode::Digit9),
+    ("Numpad0", KeyCode::Numpad0), ("Numpad1", KeyCode::Numpad1), ("Numpad2", KeyCode::Numpad2), ("Numpad3", KeyCode::Numpad3),
+    ("Numpad4", KeyCode::Numpad4), ("Numpad5", KeyCode::Numpad5), ("Numpad6", KeyCode::Numpad6), ("Numpad7", KeyCode::Numpad7),
+    ("Numpad8", KeyCode::Numpad8), ("Numpad9", KeyCode::Numpad9),
+    ("NumpadAdd", KeyCode::NumpadAdd), ("NumpadSubtract", KeyCode::NumpadSubtract),
+    ("NumpadMultiply", KeyCode::NumpadMultiply), ("NumpadDivide", KeyCode::NumpadDivide),
+    ("NumpadDecimal", KeyCode::NumpadDecimal), ("NumpadEnter", KeyCode::NumpadEnter),
+    ("ArrowUp", KeyCode::ArrowUp), ("ArrowDown", KeyCode::ArrowDown),
+    ("ArrowLeft", KeyCode::ArrowLeft), ("ArrowRight", KeyCode::ArrowRight),
+    ("Enter", KeyCode::Enter), ("Space", KeyCode::Space), ("Backspace", KeyCode::Backspace),
+    ("ShiftLeft", KeyCode::ShiftLeft), ("ShiftRight", KeyCode::ShiftRight),
+    ("ControlLeft", KeyCode::ControlLeft), ("ControlRight", KeyCode::ControlRight),
+    ("AltLeft", KeyCode::AltLeft), ("AltRight", KeyCode::AltRight),
+    ("Comma", KeyCode::Comma), ("Period", KeyCode::Period), ("Semicolon", KeyCode::Semicolon),
+    ("Quote", KeyCode::Quote), ("Slash", KeyCode::Slash), ("Backslash", KeyCode::Backslash),
+    ("BracketLeft", KeyCode::BracketLeft), ("BracketRight", KeyCode::BracketRight),
+    ("Minus", KeyCode::Minus), ("Equal", KeyCode::Equal),
+    ("Insert", KeyCode::Insert), ("Delete", KeyCode::Delete), ("Home", KeyCode::Home), ("End", KeyCode::End),
+];
+
+/// Touche désignée par son nom dans la configuration (casse indifférente)
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let name = name.trim();
+    let alias = match name.to_ascii_lowercase().as_str() {
+        "up" => "ArrowUp",
+        "down" => "ArrowDown",
+        "left" => "ArrowLeft",
+        "right" => "ArrowRight",
+        "return" => "Enter",
+        _ => name,
+    };
+    let mut letter = [0u8; 4];
+    let alias = match alias.as_bytes() {
+        [c] if c.is_ascii_alphabetic() => format!("Key{}", (*c as char).encode_utf8(&mut letter).to_ascii_uppercase()),
+        [c] if c.is_ascii_digit() => format!("Digit{}", *c as char),
+        _ => alias.to_string(),
+    };
+    KEYS.iter().find(|(key, _)| key.eq_ignore_ascii_case(&alias)).map(|&(_, code)| code)
+}
+
+/// Nom d'une touche pour la configuration, dans la forme courte des
+/// touches par défaut (`W`, `Up`, `Return`) ; None pour une touche réservée
+pub fn key_name(code: KeyCode) -> Option<String> {
+    let (name, _) = KEYS.iter().find(|&&(_, key)| key == code)?;
+    Some(match *name {
+        "Enter" => "Return".to_string(),
+        name => name
+            .strip_prefix("Key")
+            .or_else(|| name.strip_prefix("Arrow"))
+            .unwrap_or(name)
+            .to_string(),
+    })
+}
+
+/// Touches d'un joueur, indexées par [`Button::bit`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerBindings {
+    keys: [Option<KeyCode>; 8],
+}
+
+impl PlayerBindings {
+    /// Touches d'un joueur lues dans la configuration ; les noms inconnus
+    /// sont retournés et leur bouton reste sans touche
+    pub fn from_config(config: &PlayerKeyConfig) -> (Self, Vec<String>) {
+        let mut bindings = Self::default();
+        let mut unknown = Vec::new();
+        for button in Button::ALL {
+            let name = config.key(button);
+            match parse_key(name) {
+                Some(key) => bindings.keys[button.bit() as usize] = Some(key),
+                None => unknown.push(name.to_string()),
+            }
+        }
+        (bindings, unknown)
+    }
+
+    /// Touche d'un bouton
+    pub fn key(&self, button: Button) -> Option<KeyCode> {
+        self.keys[button.bit() as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_names_round_trip() {
+        assert_eq!(parse_key("W"), Some(KeyCode::KeyW));
+        assert_eq!(parse_key("w"), Some(KeyCode::KeyW));
+        assert_eq!(parse_key("Return"), Some(KeyCode::Enter));
+        assert_eq!(parse_key("Up"), Some(KeyCode::ArrowUp));
+        assert_eq!(parse_key("3"), Some(KeyCode::Digit3));
+        assert_eq!(parse_key("NumpadEnter"), Some(KeyCode::NumpadEnter));
+        assert_eq!(parse_key("Escape"), None);
+        assert_eq!(parse_key("F5"), None);
+        assert_eq!(parse_key("Tab"), None);
+
+        for (name, code) in KEYS {
+            let short = key_name(code).unwrap();
+            assert_eq!(parse_key(&short), Some(code), "{}", name);
+        }
+        assert_eq!(key_name(KeyCode::ArrowLeft).as_deref(), Some("Left"));
+        assert_eq!(key_name(KeyCode::Escape), None);
+
+        let mut config = crate::config::InputConfig::default().players[1].clone();
+        config.kick = "Nope".to_string();
+        let (bindings, unknown) = PlayerBindings::from_config(&config);
+        assert_eq!(bindings.key(Button::Punch), Some(KeyCode::Numpad1));
+        assert_eq!(bindings.key(Button::Kick), None);
+        assert_eq!(unknown, ["Nope"]);
+    }
+}
@@ -0,0 +1,210 @@
+//! Pistolets optiques : position du pointeur, calibration et sauvegarde
+//!
+//! La souris de l'hôte pilote le pistolet du joueur 1. La position est
+//! normalisée entre 0.0 et 1.0 sur la surface de jeu, puis corrigée par une
+//! calibration (décalage et échelle) propre à chaque joueur. La calibration
+//! est conservée dans le répertoire NVRAM, comme le ferait l'EEPROM de la borne.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Nom du fichier de calibration dans le répertoire NVRAM
+pub const CALIBRATION_FILE_NAME: &str = "lightgun.cal";
+
+/// État d'un pistolet pour la frame courante
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LightGunState {
+    /// Position horizontale normalisée (0.0 = gauche, 1.0 = droite)
+    pub x: f32,
+
+    /// Position verticale normalisée (0.0 = haut, 1.0 = bas)
+    pub y: f32,
+
+    /// Gâchette enfoncée
+    pub trigger: bool,
+
+    /// Le pointeur est hors de la surface de jeu (rechargement)
+    pub offscreen: bool,
+}
+
+impl LightGunState {
+    /// Met à jour la position à partir de coordonnées en pixels
+    pub fn set_pointer(&mut self, x: f64, y: f64, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            self.offscreen = true;
+            return;
+        }
+
+        let nx = (x / width as f64) as f32;
+        let ny = (y / height as f64) as f32;
+        self.offscreen = !(0.0..=1.0).contains(&nx) || !(0.0..=1.0).contains(&ny);
+        self.x = nx.clamp(0.0, 1.0);
+        self.y = ny.clamp(0.0, 1.0);
+    }
+}
+
+/// Correction linéaire appliquée à la position d'un pistolet
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GunCalibration {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+}
+
+impl GunCalibration {
+    /// Applique la calibration à une position brute
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x * self.scale_x + self.offset_x).clamp(0.0, 1.0),
+            (y * self.scale_y + self.offset_y).clamp(0.0, 1.0),
+        )
+    }
+}
+
+impl Default for GunCalibration {
+    fn default() -> Self {
+        Self {
+            offset_x: 0.0,
+            offset_y: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+        }
+    }
+}
+
+/// Calibrations de tous les joueurs, persistées en NVRAM
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GunCalibrationSet {
+    pub players: Vec<GunCalibration>,
+}
+
+impl GunCalibrationSet {
+    /// Calibration d'un joueur (identité si absente)
+    pub fn get(&self, player: usize) -> GunCalibration {
+        self.players.get(player).copied().unwrap_or_default()
+    }
+
+    /// Remplace la calibration d'un joueur
+    pub fn set(&mut self, player: usize, calibration: GunCalibration) {
+        if self.players.len() <= player {
+            self.players.resize(player + 1, GunCalibration::default());
+        }
+        self.players[player] = calibration;
+    }
+
+    /// Charge les calibrations depuis le répertoire NVRAM (identité si absent)
+    pub fn load(nvram_dir: &Path) -> Self {
+        fs::read_to_string(nvram_dir.join(CALIBRATION_FILE_NAME))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Enregistre les calibrations dans le répertoire NVRAM
+    pub fn save(&self, nvram_dir: &Path) -> Result<()> {
+        fs::create_dir_all(nvram_dir)?;
+        fs::write(nvram_dir.join(CALIBRATION_FILE_NAME), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Procédure de calibration en deux tirs (cibles haut-gauche puis bas-droite)
+#[derive(Debug, Clone)]
+pub struct CalibrationRoutine {
+    player: usize,
+    samples: Vec<(f32, f32)>,
+}
+
+impl CalibrationRoutine {
+    /// Positions des cibles à viser, en coordonnées normalisées
+    pub const TARGETS: [(f32, f32); 2] = [(0.25, 0.25), (0.75, 0.75)];
+
+    pub fn new(player: usize) -> Self {
+        Self {
+            player,
+            samples: Vec::with_capacity(Self::TARGETS.len()),
+        }
+    }
+
+    /// Joueur en cours de calibration
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    /// Cible actuellement affichée, `None` une fois la procédure terminée
+    pub fn current_target(&self) -> Option<(f32, f32)> {
+        Self::TARGETS.get(self.samples.len()).copied()
+    }
+
+    /// Enregistre un tir ; retourne la calibration une fois toutes les cibles visées
+    pub fn record_shot(&mut self, x: f32, y: f32) -> Option<GunCalibration> {
+        self.current_target()?;
+        self.samples.push((x, y));
+
+        if self.samples.len() < Self::TARGETS.len() {
+            return None;
+        }
+
+        let (t0, t1) = (Self::TARGETS[0], Self::TARGETS[1]);
+        let (s0, s1) = (self.samples[0], self.samples[1]);
+
+        // Tirs trop proches : calibration impossible, on garde un simple décalage
+        let scale = |target: f32, sample: f32| {
+            if sample.abs() < 0.05 { 1.0 } else { target / sample }
+        };
+        let scale_x = scale(t1.0 - t0.0, s1.0 - s0.0);
+        let scale_y = scale(t1.1 - t0.1, s1.1 - s0.1);
+
+        Some(GunCalibration {
+            offset_x: t0.0 - s0.0 * scale_x,
+            offset_y: t0.1 - s0.1 * scale_y,
+            scale_x,
+            scale_y,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pointer_normalization() {
+        let mut gun = LightGunState::default();
+        gun.set_pointer(400.0, 150.0, 800, 600);
+        assert_eq!((gun.x, gun.y), (0.5, 0.25));
+        assert!(!gun.offscreen);
+
+        gun.set_pointer(-10.0, 150.0, 800, 600);
+        assert!(gun.offscreen);
+        assert_eq!(gun.x, 0.0);
+    }
+
+    #[test]
+    fn test_calibration_routine_corrects_offset_and_scale() {
+        let mut routine = CalibrationRoutine::new(0);
+        // Le pistolet vise 0.05 trop à droite avec une échelle de 0.5
+        assert!(routine.record_shot(0.175, 0.25).is_none());
+        let calibration = routine.record_shot(0.425, 0.75).unwrap();
+        assert!(routine.current_target().is_none());
+
+        let (x, y) = calibration.apply(0.3, 0.5);
+        assert!((x - 0.5).abs() < 1e-5);
+        assert!((y - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_calibration_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut set = GunCalibrationSet::default();
+        set.set(1, GunCalibration { offset_x: 0.1, ..Default::default() });
+        set.save(dir.path()).unwrap();
+
+        let loaded = GunCalibrationSet::load(dir.path());
+        assert_eq!(loaded, set);
+        assert_eq!(loaded.get(0), GunCalibration::default());
+    }
+}
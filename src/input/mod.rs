@@ -1,15 +1,46 @@
 //! Gestion des contrôles et entrées
+//!
+//! Le clavier, le toucher et la gâchette arrivent par les événements winit
+//! (fonctionnalité `gui`) ; sans fenêtre, l'intégrateur remplit directement
+//! les entrées des joueurs.
 
-use winit::event::ElementState;
+pub mod state;
+pub mod lightgun;
+pub mod touch;
+#[cfg(feature = "gui")]
+pub mod bindings;
+
+pub use state::*;
+pub use lightgun::*;
+pub use touch::*;
+#[cfg(feature = "gui")]
+pub use bindings::*;
+
+#[cfg(feature = "gui")]
+use winit::event::{ElementState, Touch};
+#[cfg(feature = "gui")]
 use winit::keyboard::KeyCode;
+#[cfg(feature = "gui")]
 use std::collections::HashSet;
 
+use crate::config::InputConfig;
+#[cfg(feature = "gui")]
+use crate::config::PlayerKeyConfig;
+
 /// Gestionnaire d'entrées
 #[derive(Debug)]
 pub struct InputManager {
+    #[cfg(feature = "gui")]
     pressed_keys: HashSet<KeyCode>,
-    pub player1: PlayerInput,
-    pub player2: PlayerInput,
+    /// Touches de chaque joueur
+    #[cfg(feature = "gui")]
+    bindings: Vec<PlayerBindings>,
+    /// Entrées de chaque joueur, dans l'ordre des places de la borne
+    pub players: [PlayerInput; MAX_PLAYERS],
+    /// Pistolets optiques (la souris pilote celui du joueur 1)
+    pub guns: [LightGunState; LIGHT_GUN_COUNT],
+    /// Commandes tactiles du joueur 1
+    pub touch: TouchControls,
 }
 
 /// Entrées d'un joueur
@@ -27,13 +58,53 @@ pub struct PlayerInput {
 
 impl InputManager {
     pub fn new() -> Self {
-        Self {
+        Self::with_config(&InputConfig::default())
+    }
+
+    /// Crée le gestionnaire avec les touches des joueurs et la disposition
+    /// des commandes tactiles de la configuration
+    pub fn with_config(config: &InputConfig) -> Self {
+        #[allow(unused_mut)]
+        let mut manager = Self {
+            #[cfg(feature = "gui")]
             pressed_keys: HashSet::new(),
-            player1: PlayerInput::default(),
-            player2: PlayerInput::default(),
+            #[cfg(feature = "gui")]
+            bindings: Vec::new(),
+            players: Default::default(),
+            guns: Default::default(),
+            touch: TouchControls::new(&config.touch),
+        };
+        #[cfg(feature = "gui")]
+        for name in manager.set_bindings(&config.players) {
+            eprintln!("Touche inconnue dans la configuration: {}", name);
         }
+        manager
+    }
+
+    /// Entrées d'un joueur (0 pour le joueur 1)
+    pub fn player(&self, player: usize) -> Option<&PlayerInput> {
+        self.players.get(player)
+    }
+
+    /// Remplace les touches des joueurs (remappage) ; retourne les noms de
+    /// touche inconnus, dont les boutons restent sans touche
+    #[cfg(feature = "gui")]
+    pub fn set_bindings(&mut self, players: &[PlayerKeyConfig]) -> Vec<String> {
+        let mut unknown = Vec::new();
+        self.bindings = players
+            .iter()
+            .take(MAX_PLAYERS)
+            .map(|keys| {
+                let (bindings, names) = PlayerBindings::from_config(keys);
+                unknown.extend(names);
+                bindings
+            })
+            .collect();
+        self.update_player_inputs();
+        unknown
     }
     
+    #[cfg(feature = "gui")]
     pub fn handle_key(&mut self, key: KeyCode, state: ElementState) {
         match state {
             ElementState::Pressed => { self.pressed_keys.insert(key); },
@@ -42,26 +113,89 @@ impl InputManager {
         self.update_player_inputs();
     }
     
+    /// Position du pointeur en pixels dans une surface de `width` x `height`
+    pub fn handle_pointer(&mut self, x: f64, y: f64, width: u32, height: u32) {
+        self.guns[0].set_pointer(x, y, width, height);
+    }
+
+    /// Doigt posé, déplacé ou levé sur une fenêtre de `width` x `height`
+    /// pixels ; ignoré si les commandes tactiles sont désactivées
+    #[cfg(feature = "gui")]
+    pub fn handle_touch(&mut self, touch: &Touch, width: u32, height: u32) {
+        if !self.touch.is_enabled() {
+            return;
+        }
+        self.touch.handle_touch(touch.id, touch.phase, touch.location.x, touch.location.y, width, height);
+        self.update_player_inputs();
+    }
+
+    /// Gâchette du pistolet du joueur 1 (bouton gauche de la souris)
+    #[cfg(feature = "gui")]
+    pub fn handle_trigger(&mut self, state: ElementState) {
+        self.guns[0].trigger = state == ElementState::Pressed;
+    }
+
+    /// Touches hôte actuellement enfoncées
+    #[cfg(feature = "gui")]
+    pub fn pressed_keys(&self) -> &HashSet<KeyCode> {
+        &self.pressed_keys
+    }
+
+    #[cfg(feature = "gui")]
     fn update_player_inputs(&mut self) {
-        // Player 1 (WASD + touches)
-        self.player1.up = self.pressed_keys.contains(&KeyCode::KeyW);
-        self.player1.down = self.pressed_keys.contains(&KeyCode::KeyS);
-        self.player1.left = self.pressed_keys.contains(&KeyCode::KeyA);
-        self.player1.right = self.pressed_keys.contains(&KeyCode::KeyD);
-        self.player1.punch = self.pressed_keys.contains(&KeyCode::KeyJ);
-        self.player1.kick = self.pressed_keys.contains(&KeyCode::KeyK);
-        self.player1.guard = self.pressed_keys.contains(&KeyCode::KeyL);
-        self.player1.start = self.pressed_keys.contains(&KeyCode::Enter);
-        
-        // Player 2 (flèches + numpad)
-        self.player2.up = self.pressed_keys.contains(&KeyCode::ArrowUp);
-        self.player2.down = self.pressed_keys.contains(&KeyCode::ArrowDown);
-        self.player2.left = self.pressed_keys.contains(&KeyCode::ArrowLeft);
-        self.player2.right = self.pressed_keys.contains(&KeyCode::ArrowRight);
-        self.player2.punch = self.pressed_keys.contains(&KeyCode::Numpad1);
-        self.player2.kick = self.pressed_keys.contains(&KeyCode::Numpad2);
-        self.player2.guard = self.pressed_keys.contains(&KeyCode::Numpad3);
-        self.player2.start = self.pressed_keys.contains(&KeyCode::NumpadEnter);
+        for (player, input) in self.players.iter_mut().enumerate() {
+            let bindings = self.bindings.get(player);
+            for button in Button::ALL {
+                let key = bindings.and_then(|bindings| bindings.key(button));
+                input.set(button, key.is_some_and(|key| self.pressed_keys.contains(&key)));
+            }
+        }
+
+        // Les commandes tactiles s'ajoutent au clavier du joueur 1
+        for button in Button::ALL {
+            if self.touch.input().is_pressed(button) {
+                self.players[0].set(button, true);
+            }
+        }
+    }
+}
+
+impl PlayerInput {
+    /// Indique si un bouton est enfoncé
+    pub fn is_pressed(&self, button: Button) -> bool {
+        match button {
+            Button::Up => self.up,
+            Button::Down => self.down,
+            Button::Left => self.left,
+            Button::Right => self.right,
+            Button::Punch => self.punch,
+            Button::Kick => self.kick,
+            Button::Guard => self.guard,
+            Button::Start => self.start,
+        }
+    }
+
+    /// Boutons enfoncés, un bit par bouton dans l'ordre de [`Button::ALL`]
+    pub fn bits(&self) -> u8 {
+        Button::ALL
+            .iter()
+            .filter(|&&button| self.is_pressed(button))
+            .fold(0, |bits, button| bits | 1 << button.bit())
+    }
+
+    /// Enfonce ou relâche un bouton
+    pub fn set(&mut self, button: Button, pressed: bool) {
+        let state = match button {
+            Button::Up => &mut self.up,
+            Button::Down => &mut self.down,
+            Button::Left => &mut self.left,
+            Button::Right => &mut self.right,
+            Button::Punch => &mut self.punch,
+            Button::Kick => &mut self.kick,
+            Button::Guard => &mut self.guard,
+            Button::Start => &mut self.start,
+        };
+        *state = pressed;
     }
 }
 
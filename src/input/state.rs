@@ -0,0 +1,310 @@
+//! Instantanés d'entrées par frame et détection de fronts
+//!
+//! Les événements clavier de l'hôte arrivent à n'importe quel moment. Pour que
+//! l'émulation reste déterministe, l'état des joueurs et des touches est figé
+//! une seule fois par frame émulée via [`InputState::latch`]. La carte I/O et
+//! les raccourcis de l'interface interrogent ensuite cet instantané.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use std::collections::HashSet;
+#[cfg(feature = "gui")]
+use winit::keyboard::KeyCode;
+
+use super::{InputManager, LightGunState, PlayerInput};
+
+/// Boutons d'un joueur sur la carte I/O Model 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Button {
+    Up,
+    Down,
+    Left,
+    Right,
+    Punch,
+    Kick,
+    Guard,
+    Start,
+}
+
+impl Button {
+    /// Tous les boutons, dans l'ordre des bits de la carte I/O
+    pub const ALL: [Button; 8] = [
+        Button::Up,
+        Button::Down,
+        Button::Left,
+        Button::Right,
+        Button::Punch,
+        Button::Kick,
+        Button::Guard,
+        Button::Start,
+    ];
+
+    /// Position du bit correspondant dans l'octet d'un joueur
+    pub fn bit(self) -> u32 {
+        Self::ALL.iter().position(|&b| b == self).unwrap_or(0) as u32
+    }
+
+    /// Nom du bouton dans la configuration (`input.players.N.<nom>`)
+    pub fn key(self) -> &'static str {
+        match self {
+            Button::Up => "up",
+            Button::Down => "down",
+            Button::Left => "left",
+            Button::Right => "right",
+            Button::Punch => "punch",
+            Button::Kick => "kick",
+            Button::Guard => "guard",
+            Button::Start => "start",
+        }
+    }
+}
+
+/// Places de joueur gérées, carte I/O étendue comprise
+pub const MAX_PLAYERS: usize = 4;
+
+/// Places câblées sur la carte I/O de base ; les jeux à un joueur lisent
+/// tout de même l'octet du second, au repos
+pub const BASE_PLAYERS: usize = 2;
+
+/// Pistolets optiques gérés
+pub const LIGHT_GUN_COUNT: usize = 2;
+
+/// Mot d'entrée présenté à la carte I/O (actif bas, un octet par joueur)
+/// à partir des boutons enfoncés de chaque joueur, un bit par bouton dans
+/// l'ordre de [`Button::ALL`] ; seules les `player_count` premières places
+/// sont lues, les autres octets restent au repos
+pub fn io_word_for(buttons: &[u8], player_count: usize) -> u32 {
+    let player_count = player_count.clamp(1, MAX_PLAYERS);
+    let active = buttons
+        .iter()
+        .take(player_count)
+        .enumerate()
+        .fold(0u32, |word, (player, &buttons)| word | (buttons as u32) << (8 * player));
+    !active
+}
+
+/// État des entrées figé pour une frame émulée
+#[derive(Debug, Clone)]
+pub struct InputState {
+    /// Numéro de la frame du dernier instantané
+    frame: u64,
+
+    /// Places de joueur câblées pour le jeu lancé (voir [`Self::io_word`])
+    player_count: usize,
+
+    /// Entrées des joueurs pour la frame courante
+    current: [PlayerInput; MAX_PLAYERS],
+
+    /// Entrées des joueurs pour la frame précédente
+    previous: [PlayerInput; MAX_PLAYERS],
+
+    /// Pistolets pour la frame courante
+    guns: [LightGunState; LIGHT_GUN_COUNT],
+
+    /// Pistolets pour la frame précédente
+    previous_guns: [LightGunState; LIGHT_GUN_COUNT],
+
+    /// Touches hôte enfoncées pour la frame courante
+    #[cfg(feature = "gui")]
+    keys: HashSet<KeyCode>,
+
+    /// Touches hôte enfoncées pour la frame précédente
+    #[cfg(feature = "gui")]
+    previous_keys: HashSet<KeyCode>,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self {
+            frame: 0,
+            player_count: BASE_PLAYERS,
+            current: Default::default(),
+            previous: Default::default(),
+            guns: Default::default(),
+            previous_guns: Default::default(),
+            #[cfg(feature = "gui")]
+            keys: HashSet::new(),
+            #[cfg(feature = "gui")]
+            previous_keys: HashSet::new(),
+        }
+    }
+
+    /// Places de joueur du jeu lancé, d'après la base des jeux (1 à
+    /// [`MAX_PLAYERS`])
+    pub fn set_player_count(&mut self, player_count: usize) {
+        self.player_count = player_count.clamp(1, MAX_PLAYERS);
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.player_count
+    }
+
+    /// Fige l'état du gestionnaire d'entrées pour une nouvelle frame
+    pub fn latch(&mut self, manager: &InputManager) {
+        self.previous = std::mem::take(&mut self.current);
+        self.current = manager.players.clone();
+        self.previous_guns = self.guns;
+        self.guns = manager.guns;
+
+        #[cfg(feature = "gui")]
+        {
+            std::mem::swap(&mut self.previous_keys, &mut self.keys);
+            self.keys.clear();
+            self.keys.extend(manager.pressed_keys().iter().copied());
+        }
+
+        self.frame += 1;
+    }
+
+    /// Numéro de la frame du dernier instantané
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Entrées d'un joueur pour la frame courante
+    pub fn player(&self, player: usize) -> Option<&PlayerInput> {
+        self.current.get(player)
+    }
+
+    /// Le bouton est maintenu pendant la frame courante
+    pub fn held(&self, player: usize, button: Button) -> bool {
+        self.current.get(player).is_some_and(|p| p.is_pressed(button))
+    }
+
+    /// Le bouton vient d'être enfoncé (front montant)
+    pub fn pressed(&self, player: usize, button: Button) -> bool {
+        player < MAX_PLAYERS
+            && self.current[player].is_pressed(button)
+            && !self.previous[player].is_pressed(button)
+    }
+
+    /// Le bouton vient d'être relâché (front descendant)
+    pub fn released(&self, player: usize, button: Button) -> bool {
+        player < MAX_PLAYERS
+            && !self.current[player].is_pressed(button)
+            && self.previous[player].is_pressed(button)
+    }
+
+    /// État du pistolet d'un joueur pour la frame courante
+    pub fn gun(&self, player: usize) -> Option<&LightGunState> {
+        self.guns.get(player)
+    }
+
+    /// La gâchette vient d'être pressée
+    pub fn trigger_pressed(&self, player: usize) -> bool {
+        player < LIGHT_GUN_COUNT && self.guns[player].trigger && !self.previous_guns[player].trigger
+    }
+
+    /// La touche hôte est maintenue pendant la frame courante
+    #[cfg(feature = "gui")]
+    pub fn key_held(&self, key: KeyCode) -> bool {
+        self.keys.contains(&key)
+    }
+
+    /// La touche hôte vient d'être enfoncée
+    #[cfg(feature = "gui")]
+    pub fn key_pressed(&self, key: KeyCode) -> bool {
+        self.keys.contains(&key) && !self.previous_keys.contains(&key)
+    }
+
+    /// La touche hôte vient d'être relâchée
+    #[cfg(feature = "gui")]
+    pub fn key_released(&self, key: KeyCode) -> bool {
+        !self.keys.contains(&key) && self.previous_keys.contains(&key)
+    }
+
+    /// Touches hôte enfoncées pendant cette frame (capture d'une touche)
+    #[cfg(feature = "gui")]
+    pub fn keys_pressed(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.keys.difference(&self.previous_keys).copied()
+    }
+
+    /// Mot d'entrée présenté à la carte I/O (actif bas, un octet par joueur
+    /// câblé)
+    pub fn io_word(&self) -> u32 {
+        let buttons = self.current.each_ref().map(PlayerInput::bits);
+        io_word_for(&buttons, self.player_count)
+    }
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::*;
+    use winit::event::ElementState;
+
+    #[test]
+    fn test_edges_are_reported_once() {
+        let mut manager = InputManager::new();
+        let mut state = InputState::new();
+
+        manager.handle_key(KeyCode::KeyJ, ElementState::Pressed);
+        state.latch(&manager);
+        assert!(state.pressed(0, Button::Punch));
+        assert!(state.held(0, Button::Punch));
+
+        state.latch(&manager);
+        assert!(!state.pressed(0, Button::Punch));
+        assert!(state.held(0, Button::Punch));
+
+        manager.handle_key(KeyCode::KeyJ, ElementState::Released);
+        state.latch(&manager);
+        assert!(state.released(0, Button::Punch));
+        assert!(!state.held(0, Button::Punch));
+    }
+
+    #[test]
+    fn test_events_between_latches_are_ignored() {
+        let mut manager = InputManager::new();
+        let mut state = InputState::new();
+        state.latch(&manager);
+
+        // Appui et relâchement entre deux frames : invisible pour l'émulation
+        manager.handle_key(KeyCode::KeyP, ElementState::Pressed);
+        manager.handle_key(KeyCode::KeyP, ElementState::Released);
+        state.latch(&manager);
+        assert!(!state.key_pressed(KeyCode::KeyP));
+        assert_eq!(state.frame(), 2);
+    }
+
+    #[test]
+    fn test_io_word_is_active_low() {
+        let mut manager = InputManager::new();
+        let mut state = InputState::new();
+        state.latch(&manager);
+        assert_eq!(state.io_word(), u32::MAX);
+
+        manager.handle_key(KeyCode::Enter, ElementState::Pressed);
+        manager.handle_key(KeyCode::ArrowUp, ElementState::Pressed);
+        state.latch(&manager);
+        assert_eq!(state.io_word(), !((1 << 7) | (1 << 8)));
+    }
+
+    #[test]
+    fn test_extra_players_follow_game_player_count() {
+        let mut manager = InputManager::new();
+        let mut state = InputState::new();
+
+        // Start des joueurs 3 (Digit3) et 4 (Digit4)
+        manager.handle_key(KeyCode::Digit3, ElementState::Pressed);
+        manager.handle_key(KeyCode::Digit4, ElementState::Pressed);
+        state.latch(&manager);
+        assert!(state.held(2, Button::Start) && state.held(3, Button::Start));
+        // Jeu à deux joueurs : les places étendues ne sont pas câblées
+        assert_eq!(state.io_word(), u32::MAX);
+
+        state.set_player_count(4);
+        assert_eq!(state.io_word(), !((1 << 23) | (1 << 31)));
+        state.set_player_count(3);
+        assert_eq!(state.io_word(), 0xFF7F_FFFF);
+        // Un seul joueur : l'octet du second reste au repos
+        state.set_player_count(1);
+        assert_eq!(io_word_for(&[0x80, 0x01], state.player_count()), 0xFFFF_FF7F);
+    }
+}
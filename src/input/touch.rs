@@ -0,0 +1,277 @@
+//! Commandes tactiles à l'écran
+//!
+//! Chaque doigt posé est suivi par son identifiant. À chaque événement, les
+//! doigts sont confrontés à la disposition de la configuration : un doigt
+//! dans la zone de direction donne une direction (ou une diagonale) selon
+//! son angle, un doigt sur un bouton l'enfonce. Un doigt dans la zone de
+//! volant, si la disposition en a une, donne un braquage analogique, et
+//! gauche ou droite au-delà de sa zone morte. Le résultat est une
+//! [`PlayerInput`] combinée à celle du clavier pour le joueur 1.
+//!
+//! La vibration de l'appareil dépend de la plateforme : elle est demandée à
+//! travers [`HapticFeedback`], que le frontend fournit s'il sait la produire.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "gui")]
+use winit::event::TouchPhase;
+
+use super::PlayerInput;
+use crate::config::TouchConfig;
+
+/// Durée de la vibration à l'appui d'un bouton
+pub const HAPTIC_PULSE: Duration = Duration::from_millis(15);
+
+/// Sinus de 22,5° : au-delà, la composante secondaire du doigt donne une diagonale
+const DIAGONAL_THRESHOLD: f32 = 0.383;
+
+/// Vibration de l'appareil, fournie par la plateforme
+pub trait HapticFeedback {
+    fn pulse(&mut self, duration: Duration);
+}
+
+/// Doigts posés et boutons qu'ils enfoncent
+pub struct TouchControls {
+    config: TouchConfig,
+
+    /// Positions normalisées des doigts posés, par identifiant
+    touches: HashMap<u64, (f32, f32)>,
+
+    /// Rapport largeur / hauteur de la fenêtre
+    aspect: f32,
+
+    /// Boutons enfoncés par les doigts
+    input: PlayerInput,
+
+    /// Braquage donné par la zone de volant, de -1 à 1
+    steering: f32,
+
+    haptics: Option<Box<dyn HapticFeedback>>,
+}
+
+impl TouchControls {
+    pub fn new(config: &TouchConfig) -> Self {
+        Self {
+            config: config.clone(),
+            touches: HashMap::new(),
+            aspect: 1.0,
+            input: PlayerInput::default(),
+            steering: 0.0,
+            haptics: None,
+        }
+    }
+
+    /// Remplace la disposition ; les doigts posés sont relâchés
+    pub fn set_config(&mut self, config: &TouchConfig) {
+        self.config = config.clone();
+        self.touches.clear();
+        self.input = PlayerInput::default();
+        self.steering = 0.0;
+    }
+
+    pub fn config(&self) -> &TouchConfig {
+        &self.config
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Branche la vibration de la plateforme
+    pub fn set_haptics(&mut self, haptics: Box<dyn HapticFeedback>) {
+        self.haptics = Some(haptics);
+    }
+
+    /// Boutons enfoncés par les doigts
+    pub fn input(&self) -> &PlayerInput {
+        &self.input
+    }
+
+    /// Braquage de la zone de volant, de -1 (gauche) à 1 (droite) ; 0 sans
+    /// doigt dans la zone
+    pub fn steering(&self) -> f32 {
+        self.steering
+    }
+
+    /// Doigt `id` posé, déplacé ou levé à `(x, y)` pixels dans une fenêtre
+    /// de `width` x `height`
+    #[cfg(feature = "gui")]
+    pub fn handle_touch(&mut self, id: u64, phase: TouchPhase, x: f64, y: f64, width: u32, height: u32) {
+        let position = match phase {
+            TouchPhase::Started | TouchPhase::Moved => Some((x, y)),
+            TouchPhase::Ended | TouchPhase::Cancelled => None,
+        };
+        self.set_touch(id, position, width, height);
+    }
+
+    /// Doigt `id` posé à `position` pixels, ou levé (`None`), sans passer
+    /// par les événements de winit
+    pub fn set_touch(&mut self, id: u64, position: Option<(f64, f64)>, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.aspect = width as f32 / height as f32;
+        match position {
+            Some((x, y)) => {
+                self.touches.insert(id, ((x / width as f64) as f32, (y / height as f64) as f32));
+            }
+            None => {
+                self.touches.remove(&id);
+            }
+        }
+        self.update();
+    }
+
+    /// Distance entre un doigt et un centre, en fraction de la hauteur
+    fn offset(&self, (x, y): (f32, f32), cx: f32, cy: f32) -> (f32, f32) {
+        ((x - cx) * self.aspect, y - cy)
+    }
+
+    fn update(&mut self) {
+        let mut input = PlayerInput::default();
+        let mut steering = 0.0;
+        let stick = self.config.stick;
+
+        for &touch in self.touches.values() {
+            if let Some(wheel) = self.config.steering {
+                let (dx, dy) = (touch.0 - wheel.x, touch.1 - wheel.y);
+                if dx.abs() <= wheel.width / 2.0 && dy.abs() <= wheel.height / 2.0 && wheel.width > 0.0 {
+                    steering = (dx / (wheel.width / 2.0)).clamp(-1.0, 1.0);
+                    input.left |= steering < -wheel.dead_zone;
+                    input.right |= steering > wheel.dead_zone;
+                    continue;
+                }
+            }
+
+            let (dx, dy) = self.offset(touch, stick.x, stick.y);
+            let distance = dx.hypot(dy);
+            if distance <= stick.radius {
+                if distance > stick.radius * stick.dead_zone {
+                    let threshold = distance * DIAGONAL_THRESHOLD;
+                    input.left |= dx < -threshold;
+                    input.right |= dx > threshold;
+                    input.up |= dy < -threshold;
+                    input.down |= dy > threshold;
+                }
+                continue;
+            }
+
+            for button in &self.config.buttons {
+                let (dx, dy) = self.offset(touch, button.x, button.y);
+                if dx.hypot(dy) <= button.radius {
+                    input.set(button.button, true);
+                }
+            }
+        }
+
+        let newly_pressed = self.config.buttons.iter()
+            .any(|b| input.is_pressed(b.button) && !self.input.is_pressed(b.button));
+        if newly_pressed && self.config.haptics {
+            if let Some(haptics) = self.haptics.as_mut() {
+                haptics.pulse(HAPTIC_PULSE);
+            }
+        }
+        self.input = input;
+        self.steering = steering;
+    }
+}
+
+impl fmt::Debug for TouchControls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TouchControls")
+            .field("enabled", &self.config.enabled)
+            .field("touches", &self.touches)
+            .field("input", &self.input)
+            .field("steering", &self.steering)
+            .field("haptics", &self.haptics.is_some())
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::*;
+    use crate::config::TouchSteeringConfig;
+    use crate::input::Button;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct CountingHaptics(Rc<Cell<u32>>);
+
+    impl HapticFeedback for CountingHaptics {
+        fn pulse(&mut self, _duration: Duration) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_stick_directions_and_dead_zone() {
+        let mut touch = TouchControls::new(&TouchConfig { enabled: true, ..Default::default() });
+        let stick = touch.config().stick;
+        let (width, height) = (1000, 1000);
+        let at = |x: f32, y: f32| ((x * width as f32) as f64, (y * height as f32) as f64);
+
+        let (x, y) = at(stick.x, stick.y);
+        touch.handle_touch(1, TouchPhase::Started, x, y, width, height);
+        assert!(!touch.input().left && !touch.input().up);
+
+        let (x, y) = at(stick.x - stick.radius * 0.8, stick.y);
+        touch.handle_touch(1, TouchPhase::Moved, x, y, width, height);
+        assert!(touch.input().left && !touch.input().up && !touch.input().down);
+
+        // Diagonale haut-droite
+        let (x, y) = at(stick.x + stick.radius * 0.5, stick.y - stick.radius * 0.5);
+        touch.handle_touch(1, TouchPhase::Moved, x, y, width, height);
+        assert!(touch.input().right && touch.input().up && !touch.input().left);
+
+        touch.handle_touch(1, TouchPhase::Ended, x, y, width, height);
+        assert!(!touch.input().right && !touch.input().up);
+    }
+
+    #[test]
+    fn test_steering_zone_is_analog() {
+        let wheel = TouchSteeringConfig { x: 0.5, y: 0.5, width: 0.6, height: 0.2, dead_zone: 0.2 };
+        let mut touch = TouchControls::new(&TouchConfig { enabled: true, steering: Some(wheel), ..Default::default() });
+        let (width, height) = (1000, 1000);
+
+        // Un quart de la demi-largeur à droite : braquage de 0,25
+        touch.handle_touch(1, TouchPhase::Started, 575.0, 500.0, width, height);
+        assert!((touch.steering() - 0.25).abs() < 1e-3);
+        assert!(touch.input().right && !touch.input().left);
+
+        // Dans la zone morte : braquage lu, mais aucune direction
+        touch.handle_touch(1, TouchPhase::Moved, 470.0, 500.0, width, height);
+        assert!((touch.steering() + 0.1).abs() < 1e-3);
+        assert!(!touch.input().left && !touch.input().right);
+
+        touch.handle_touch(1, TouchPhase::Ended, 470.0, 500.0, width, height);
+        assert_eq!(touch.steering(), 0.0);
+    }
+
+    #[test]
+    fn test_buttons_pulse_haptics_once() {
+        let mut touch = TouchControls::new(&TouchConfig { enabled: true, ..Default::default() });
+        let pulses = Rc::new(Cell::new(0));
+        touch.set_haptics(Box::new(CountingHaptics(pulses.clone())));
+
+        let punch = touch.config().buttons.iter().find(|b| b.button == Button::Punch).copied().unwrap();
+        let (width, height) = (1600, 900);
+        let (x, y) = ((punch.x * width as f32) as f64, (punch.y * height as f32) as f64);
+
+        touch.handle_touch(7, TouchPhase::Started, x, y, width, height);
+        touch.handle_touch(7, TouchPhase::Moved, x + 1.0, y, width, height);
+        assert!(touch.input().punch);
+        assert_eq!(pulses.get(), 1);
+
+        // Deuxième doigt sur la zone de direction : pas de vibration
+        let stick = touch.config().stick;
+        let (sx, sy) = (((stick.x + stick.radius * 0.5) * width as f32) as f64, (stick.y * height as f32) as f64);
+        touch.handle_touch(8, TouchPhase::Started, sx, sy, width, height);
+        assert!(touch.input().punch && touch.input().right);
+        assert_eq!(pulses.get(), 1);
+
+        touch.handle_touch(7, TouchPhase::Cancelled, x, y, width, height);
+        assert!(!touch.input().punch);
+    }
+}
@@ -2,6 +2,11 @@
 //! 
 //! Cette bibliothèque fournit tous les composants nécessaires pour émuler
 //! le système d'arcade SEGA Model 2, incluant le CPU, GPU, audio et plus.
+//!
+//! Le cœur (CPU, mémoire, rendu logiciel, audio, ROM isolées) se compile
+//! sans fonctionnalité ; `gui` ajoute la fenêtre, la présentation wgpu et
+//! l'interface, `rom-tools` les sommes de contrôle et archives ZIP,
+//! `audio-cpal` la sortie son et `filesystem` la recherche des ROM.
 
 pub mod cpu;
 pub mod memory;
@@ -9,8 +14,23 @@ pub mod gpu;
 pub mod audio;
 pub mod input;
 pub mod rom;
+#[cfg(feature = "gui")]
 pub mod gui;
 pub mod config;
+pub mod profiling;
+#[cfg(any(test, feature = "test-harness"))]
+pub mod testing;
+pub mod debugger;
+pub mod savestate;
+pub mod emulator;
+pub mod error;
+pub mod clock;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+pub mod web;
+#[cfg(all(feature = "gui", target_os = "android"))]
+pub mod android;
 
 pub use cpu::*;
 pub use memory::*;
@@ -18,17 +38,26 @@ pub use gpu::*;
 pub use audio::*;
 pub use input::*;
 pub use rom::*;
+#[cfg(feature = "gui")]
 pub use gui::*;
 pub use config::*;
+pub use profiling::*;
+pub use savestate::*;
+pub use emulator::*;
+pub use error::*;
 
 /// Version de l'émulateur
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Fréquence du quartz maître de la carte en Hz, dont dérivent toutes les
+/// horloges (voir [`emulator::ClockTable`])
+pub const MASTER_CRYSTAL_FREQUENCY: u32 = 50_000_000; // 50MHz
+
 /// Fréquence du CPU principal (NEC V60) en Hz
-pub const MAIN_CPU_FREQUENCY: u32 = 25_000_000; // 25MHz
+pub const MAIN_CPU_FREQUENCY: u32 = emulator::MODEL2_CLOCKS.main.of(MASTER_CRYSTAL_FREQUENCY); // 25MHz
 
-/// Fréquence du CPU audio (68000) en Hz  
-pub const AUDIO_CPU_FREQUENCY: u32 = 11_300_000; // 11.3MHz
+/// Fréquence du CPU audio (68000) en Hz
+pub const AUDIO_CPU_FREQUENCY: u32 = emulator::MODEL2_CLOCKS.sound.of(MASTER_CRYSTAL_FREQUENCY); // 11.2896MHz
 
 /// Taille de la RAM principale
 pub const MAIN_RAM_SIZE: usize = 8 * 1024 * 1024; // 8MB
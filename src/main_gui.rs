@@ -1,17 +1,15 @@
 use anyhow::Result;
 use log::info;
 use std::env;
+use std::io::BufRead;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
-mod cpu;
-mod memory;
-// mod gpu; // Temporarily disabled
-// mod audio; // Temporarily disabled
-mod input;
-mod rom;
-// mod gui; // Temporarily disabled
-mod config;
-
-use pixel_model2_rust::gui::EmulatorApp;
+use pixel_model2_rust::audio::ScspAudio;
+use pixel_model2_rust::gui::{rom_system_for, EmulatorApp};
+use pixel_model2_rust::config::{AppPaths, EmulatorConfig, PathOverrides};
+use pixel_model2_rust::emulator::{parse_frame_range, EventSession, SoundTest, SoundTestInput};
+use pixel_model2_rust::rom::{convert_rom_folder, GameDatabase, SetLayout};
 
 fn main() -> Result<()> {
     // Initialiser le logging
@@ -21,17 +19,211 @@ fn main() -> Result<()> {
     // Parser les arguments de ligne de commande
     let args: Vec<String> = env::args().collect();
     let mut rom_path: Option<String> = None;
+    let mut profile_output: Option<String> = None;
+    let mut trace_output: Option<String> = None;
+    let mut call_trace_output: Option<String> = None;
+    let mut event_log_output: Option<String> = None;
+    let mut session_report_output: Option<String> = None;
+    let mut symbol_files: Vec<String> = Vec::new();
+    let mut capture_range: Option<String> = None;
+    let mut capture_output: Option<String> = None;
+    let mut debug_console = false;
+    let mut list_gpus = false;
+    let mut sound_test_game: Option<String> = None;
+    let mut setting_overrides: Vec<String> = Vec::new();
 
     // Traitement simple des arguments
     for i in 1..args.len() {
         if args[i] == "--rom" && i + 1 < args.len() {
             rom_path = Some(args[i + 1].clone());
         }
+        if args[i] == "--profile" && i + 1 < args.len() {
+            profile_output = Some(args[i + 1].clone());
+        }
+        if args[i] == "--trace" && i + 1 < args.len() {
+            trace_output = Some(args[i + 1].clone());
+        }
+        if args[i] == "--call-trace" && i + 1 < args.len() {
+            call_trace_output = Some(args[i + 1].clone());
+        }
+        if args[i] == "--event-log" && i + 1 < args.len() {
+            event_log_output = Some(args[i + 1].clone());
+        }
+        if args[i] == "--compat-report" && i + 1 < args.len() {
+            session_report_output = Some(args[i + 1].clone());
+        }
+        if args[i] == "--check-event-log" && i + 1 < args.len() {
+            let file = std::fs::File::open(&args[i + 1])?;
+            let report = EventSession::read(std::io::BufReader::new(file))?.validate();
+            print!("{}", report);
+            if !report.is_clean() {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        if args[i] == "--opcode-matrix" && i + 1 < args.len() {
+            // Matrice des opcodes tirée du décodeur, en HTML ou en Markdown
+            let matrix = pixel_model2_rust::cpu::OpcodeMatrix::build();
+            let output = &args[i + 1];
+            let content = if output.ends_with(".html") { matrix.to_html() } else { matrix.to_markdown() };
+            std::fs::write(output, content)?;
+            println!("Matrice des opcodes écrite dans {}", output);
+            return Ok(());
+        }
+        if args[i] == "--convert-romset" && i + 3 < args.len() {
+            // Réécrit un dossier de ROMs en sets split, merged ou non-merged
+            let layout = SetLayout::from_name(&args[i + 1])
+                .ok_or_else(|| anyhow::anyhow!("organisation inconnue: {} (split, merged, non-merged)", args[i + 1]))?;
+            // Base JSON optionnelle : la base intégrée ne déclare aucun clone
+            let mut database = GameDatabase::new();
+            if let Some(path) = args.get(i + 4).filter(|arg| !arg.starts_with("--")) {
+                database.load_from_file(path)?;
+            }
+            let report = convert_rom_folder(&database, args[i + 2].as_ref(), args[i + 3].as_ref(), layout)?;
+            print!("{}", report);
+            return Ok(());
+        }
+        if args[i] == "--capture-frames" && i + 1 < args.len() {
+            capture_range = Some(args[i + 1].clone());
+        }
+        if args[i] == "--out" && i + 1 < args.len() {
+            capture_output = Some(args[i + 1].clone());
+        }
+        if args[i] == "--symbols" && i + 1 < args.len() {
+            symbol_files.push(args[i + 1].clone());
+        }
+        if args[i] == "--debug-console" {
+            debug_console = true;
+        }
+        if args[i] == "--set" && i + 1 < args.len() {
+            setting_overrides.push(args[i + 1].clone());
+        }
+        if args[i] == "--sound-test" && i + 1 < args.len() {
+            sound_test_game = Some(args[i + 1].clone());
+        }
+        if args[i] == "--list-gpus" {
+            list_gpus = true;
+        }
+        if args[i] == "--list-audio-devices" {
+            for name in pixel_model2_rust::audio::output_device_names() {
+                println!("{}", name);
+            }
+            return Ok(());
+        }
+    }
+
+    // Répertoires utilisateur, avec surcharges --config / --data-dir / --rom-dir
+    let overrides = PathOverrides::from_args(args.iter().skip(1))?;
+    let paths = AppPaths::detect().with_overrides(&overrides);
+    info!("Configuration: {}", paths.config_file.display());
+
+    // Cartes graphiques utilisables avec l'API configurée
+    if list_gpus {
+        let video = EmulatorConfig::load_or_default(&paths.config_file).video;
+        for (index, adapter) in pixel_model2_rust::gpu::list_adapters(video.backend).iter().enumerate() {
+            println!("{}: {} ({}, {})", index, adapter.name, adapter.backend, adapter.device_type);
+        }
+        return Ok(());
+    }
+
+    // Processeur sonore seul, piloté depuis le terminal
+    if let Some(game) = sound_test_game {
+        let config = EmulatorConfig::load_or_default(&paths.config_file);
+        let mut rom_system = rom_system_for(&paths);
+        let test = SoundTest::load(&mut rom_system, &game, ScspAudio::with_config(&config.audio))?;
+        return run_sound_test(test);
     }
 
     // Créer et lancer l'application
-    let app = EmulatorApp::new(rom_path)?;
+    let mut app = EmulatorApp::new(rom_path, paths)?;
+    if let Some(output) = profile_output {
+        info!("Profilage du CPU activé, sortie: {}", output);
+        app.enable_profiler(output.into());
+    }
+    if let Some(output) = trace_output {
+        info!("Trace des frames activée, sortie: {}", output);
+        app.enable_frame_trace(output.into());
+    }
+    for path in symbol_files {
+        app.load_symbols(path.as_ref())?;
+        info!("Symboles chargés depuis {} ({} au total)", path, app.symbols.len());
+    }
+    if let Some(output) = call_trace_output {
+        info!("Journal des appels activé, sortie: {}", output);
+        app.enable_call_trace(output.into());
+    }
+    if let Some(output) = event_log_output {
+        info!("Journal des événements de frame activé, sortie: {}", output);
+        app.enable_event_log(output.into());
+    }
+    if let Some(output) = session_report_output {
+        info!("Rapport de compatibilité activé, sortie: {}", output);
+        app.enable_session_report(output.into());
+    }
+    for assignment in &setting_overrides {
+        app.override_option(assignment)?;
+    }
+    if debug_console {
+        info!("Console du débogueur active sur l'entrée standard");
+        app.enable_debug_console();
+    }
+
+    // Capture d'images sans fenêtre, pour comparer deux commits
+    if let Some(range) = capture_range {
+        let frames = parse_frame_range(&range)
+            .ok_or_else(|| anyhow::anyhow!("plage de frames invalide: {} (début..fin ou début..=fin)", range))?;
+        let output = capture_output.ok_or_else(|| anyhow::anyhow!("--capture-frames demande un dossier de sortie (--out)"))?;
+        let capture = app.capture_frames(frames, output.as_ref())?;
+        println!("{} images de {} enregistrées dans {}", capture.frames.len(), capture.game, output);
+        return Ok(());
+    }
     app.run()?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Boucle du mode test son : une frame toutes les 1/60 s, les lignes du
+/// terminal étant lues par un thread à part
+fn run_sound_test(mut test: SoundTest) -> Result<()> {
+    println!("Commandes connues:\n{}", test.hle.report());
+    println!("Commande (0x00 à 0xFF), stop, list ou quit:");
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(|line| line.ok()) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let frame = Duration::from_secs(1) / 60;
+    let mut next_frame = Instant::now();
+    loop {
+        loop {
+            let line = match receiver.try_recv() {
+                Ok(line) => line,
+                Err(mpsc::TryRecvError::Empty) => break,
+                // Fin de l'entrée standard
+                Err(mpsc::TryRecvError::Disconnected) => return Ok(()),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match line.parse() {
+                Ok(SoundTestInput::Command(command)) => println!("{}", test.send(command)),
+                Ok(SoundTestInput::Stop) => test.stop(),
+                Ok(SoundTestInput::List) => println!("{}", test.hle.report()),
+                Ok(SoundTestInput::Quit) => return Ok(()),
+                Err(e) => println!("{}", e),
+            }
+        }
+
+        test.run_frame();
+        next_frame += frame;
+        match next_frame.checked_duration_since(Instant::now()) {
+            Some(wait) => std::thread::sleep(wait),
+            None => next_frame = Instant::now(),
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Fenêtres de RAM partagées avec le coprocesseur géométrique (TGP)
+//!
+//! Le V60 transmet la géométrie au TGP par deux fenêtres de RAM partagées
+//! et une boîte aux lettres. Chaque fenêtre appartient soit au CPU, qui la
+//! remplit, soit au moteur géométrique, qui la lit : le CPU demande la
+//! remise d'une fenêtre par le registre [`COPRO_HANDOFF`], la propriété
+//! change au VBlank suivant et la fenêtre revient au CPU au VBlank d'après,
+//! une fois la frame transformée. Pendant ce temps, les écritures du CPU
+//! dans la fenêtre sont refusées (le bus est à l'autre processeur) ; le jeu
+//! scrute les bits de propriété et d'occupation de [`COPRO_MAILBOX_STATUS`]
+//! avant d'y écrire.
+//!
+//! La boîte aux lettres échange un mot dans chaque sens : une commande du
+//! CPU (même format que le registre de commande GPU) et la réponse du
+//! moteur, le nombre de commandes qu'il a reçues à la dernière remise.
+//!
+//! Le contenu d'une fenêtre suit le format des listes d'affichage : des
+//! mots de commande de 32 bits terminés par [`DISPLAY_LIST_END`].
+
+use super::display_list::DISPLAY_LIST_END;
+use super::interface::MemoryInterface;
+use super::ram::Ram;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Première fenêtre partagée dans l'espace du V60, après les listes
+/// d'affichage ; la seconde la suit
+pub const COPRO_WINDOW_BASE: u32 = 0x1090_0000;
+
+/// Taille d'une fenêtre
+pub const COPRO_WINDOW_SIZE: u32 = 0x0000_8000;
+
+/// Nombre de fenêtres
+pub const COPRO_WINDOW_COUNT: usize = 2;
+
+/// Registres de la boîte aux lettres dans l'espace du V60
+pub const COPRO_MAILBOX_BASE: u32 = 0x1098_0000;
+
+/// Taille de la fenêtre des registres
+pub const COPRO_MAILBOX_SIZE: u32 = 0x10;
+
+/// Commande vers le moteur en écriture, réponse en lecture
+pub const COPRO_MAILBOX_DATA: u32 = 0x00;
+
+/// État (lecture seule) : propriété des fenêtres et bits d'occupation
+pub const COPRO_MAILBOX_STATUS: u32 = 0x04;
+
+/// Remise des fenêtres au prochain VBlank : un bit par fenêtre ; la
+/// lecture donne les remises en attente
+pub const COPRO_HANDOFF: u32 = 0x08;
+
+/// État : le moteur géométrique détient la fenêtre 0 (un bit par fenêtre
+/// à partir de celui-ci)
+pub const COPRO_STATUS_GEOMETRY_OWNED: u32 = 0x01;
+
+/// État : le moteur géométrique détient la fenêtre 1
+pub const COPRO_STATUS_WINDOW1_OWNED: u32 = COPRO_STATUS_GEOMETRY_OWNED << 1;
+
+/// État : le moteur géométrique travaille (fenêtre détenue ou commande en
+/// attente)
+pub const COPRO_STATUS_BUSY: u32 = 0x10;
+
+/// État : une commande attend d'être lue par le moteur
+pub const COPRO_STATUS_COMMAND_FULL: u32 = 0x20;
+
+/// État : une réponse du moteur attend d'être lue
+pub const COPRO_STATUS_REPLY_READY: u32 = 0x40;
+
+/// Bits des fenêtres existantes
+const WINDOW_MASK: u32 = (1 << COPRO_WINDOW_COUNT) - 1;
+
+/// Propriétaire d'une fenêtre partagée
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowOwner {
+    Cpu,
+    Geometry,
+}
+
+/// Fenêtres partagées et boîte aux lettres entre le V60 et le TGP ; les
+/// lectures du CPU passent par `&self`, la réponse en attente est donc
+/// atomique
+#[derive(Debug)]
+pub struct CoprocessorLink {
+    windows: [Ram; COPRO_WINDOW_COUNT],
+    /// Fenêtres détenues par le moteur géométrique, un bit par fenêtre
+    geometry_owned: u32,
+    /// Remises demandées pour le prochain VBlank
+    handoff: u32,
+    command: Option<u32>,
+    reply: u32,
+    /// Une réponse attend d'être lue
+    reply_ready: AtomicBool,
+    /// Écritures du CPU refusées depuis le lancement
+    refused_writes: u64,
+}
+
+impl CoprocessorLink {
+    pub fn new() -> Self {
+        Self {
+            windows: [Ram::new(COPRO_WINDOW_SIZE as usize), Ram::new(COPRO_WINDOW_SIZE as usize)],
+            geometry_owned: 0,
+            handoff: 0,
+            command: None,
+            reply: 0,
+            reply_ready: AtomicBool::new(false),
+            refused_writes: 0,
+        }
+    }
+
+    /// Rend les fenêtres au CPU et vide la boîte aux lettres (reset de la
+    /// carte) ; le contenu des fenêtres est conservé
+    pub fn reset(&mut self) {
+        self.geometry_owned = 0;
+        self.handoff = 0;
+        self.command = None;
+        *self.reply_ready.get_mut() = false;
+    }
+
+    /// Fenêtre et offset local correspondant à un offset de la région
+    fn locate(offset: u32) -> (usize, u32) {
+        ((offset / COPRO_WINDOW_SIZE) as usize % COPRO_WINDOW_COUNT, offset % COPRO_WINDOW_SIZE)
+    }
+
+    pub fn owner(&self, window: usize) -> WindowOwner {
+        if self.geometry_owned & (1 << window) != 0 {
+            WindowOwner::Geometry
+        } else {
+            WindowOwner::Cpu
+        }
+    }
+
+    pub fn window(&self, window: usize) -> &Ram {
+        &self.windows[window % COPRO_WINDOW_COUNT]
+    }
+
+    /// Accès du moteur géométrique, qui peut écrire dans ses fenêtres
+    pub fn window_mut(&mut self, window: usize) -> &mut Ram {
+        &mut self.windows[window % COPRO_WINDOW_COUNT]
+    }
+
+    /// Lecture du CPU dans les fenêtres : toujours servie, le moteur ne
+    /// faisant que lire la géométrie
+    pub fn cpu_read(&self, offset: u32, size: u8) -> crate::error::Result<u32> {
+        let (window, local) = Self::locate(offset);
+        let ram = &self.windows[window];
+        Ok(match size {
+            1 => ram.read_u8(local)? as u32,
+            2 => ram.read_u16(local)? as u32,
+            _ => ram.read_u32(local)?,
+        })
+    }
+
+    /// Écriture du CPU dans les fenêtres ; refusée si le moteur détient la
+    /// fenêtre
+    pub fn cpu_write(&mut self, offset: u32, value: u32, size: u8) -> crate::error::Result<()> {
+        let (window, local) = Self::locate(offset);
+        if self.owner(window) == WindowOwner::Geometry {
+            if self.refused_writes == 0 {
+                log::warn!("TGP: écriture du CPU dans la fenêtre {} détenue par le moteur géométrique", window);
+            }
+            self.refused_writes += 1;
+            return Ok(());
+        }
+        let ram = &mut self.windows[window];
+        match size {
+            1 => ram.write_u8(local, value as u8),
+            2 => ram.write_u16(local, value as u16),
+            _ => ram.write_u32(local, value),
+        }
+    }
+
+    /// Lecture d'un registre de la boîte aux lettres ; le port de données
+    /// consomme la réponse
+    pub fn main_read(&self, offset: u32) -> u32 {
+        match offset & !3 {
+            COPRO_MAILBOX_DATA if self.reply_ready.swap(false, Ordering::Relaxed) => self.reply,
+            COPRO_MAILBOX_DATA => 0,
+            COPRO_MAILBOX_STATUS => self.status(),
+            COPRO_HANDOFF => self.handoff,
+            _ => 0,
+        }
+    }
+
+    /// Écriture d'un registre de la boîte aux lettres
+    pub fn main_write(&mut self, offset: u32, value: u32) {
+        match offset & !3 {
+            COPRO_MAILBOX_DATA => {
+                if self.command.is_some() {
+                    log::warn!("TGP: commande 0x{:08X} perdue, la précédente n'a pas été lue", value);
+                } else {
+                    self.command = Some(value);
+                }
+            }
+            COPRO_HANDOFF => self.handoff |= value & WINDOW_MASK,
+            _ => log::debug!("TGP: écriture ignorée 0x{:02X} <- 0x{:08X}", offset, value),
+        }
+    }
+
+    /// Registre d'état vu par le CPU
+    pub fn status(&self) -> u32 {
+        let mut status = self.geometry_owned * COPRO_STATUS_GEOMETRY_OWNED;
+        if self.geometry_owned != 0 || self.command.is_some() {
+            status |= COPRO_STATUS_BUSY;
+        }
+        if self.command.is_some() {
+            status |= COPRO_STATUS_COMMAND_FULL;
+        }
+        if self.reply_ready.load(Ordering::Relaxed) {
+            status |= COPRO_STATUS_REPLY_READY;
+        }
+        status
+    }
+
+    /// VBlank : le moteur rend les fenêtres de la frame passée et reçoit
+    /// celles dont la remise a été demandée. Retourne les fenêtres remises,
+    /// un bit par fenêtre.
+    pub fn vblank(&mut self) -> u32 {
+        self.geometry_owned = std::mem::take(&mut self.handoff);
+        self.geometry_owned
+    }
+
+    /// Côté moteur : retire la commande en attente
+    pub fn take_command(&mut self) -> Option<u32> {
+        self.command.take()
+    }
+
+    /// Côté moteur : dépose une réponse, qui remplace une réponse non lue
+    pub fn post_reply(&mut self, value: u32) {
+        self.reply = value;
+        *self.reply_ready.get_mut() = true;
+    }
+
+    /// Mots de commande d'une fenêtre, jusqu'à la fin de liste
+    pub fn command_words(&self, window: usize) -> impl Iterator<Item = u32> + '_ {
+        let ram = self.window(window);
+        (0..COPRO_WINDOW_SIZE / 4)
+            .map(move |index| ram.read_u32(index * 4).unwrap_or(DISPLAY_LIST_END))
+            .take_while(|&word| word != DISPLAY_LIST_END)
+    }
+
+    /// Écritures du CPU refusées faute de détenir la fenêtre
+    pub fn refused_writes(&self) -> u64 {
+        self.refused_writes
+    }
+}
+
+impl Default for CoprocessorLink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_ownership_flips_at_vblank() {
+        let mut link = CoprocessorLink::new();
+        assert_eq!(link.status(), 0);
+
+        // Le CPU remplit la fenêtre 1 et demande sa remise
+        link.cpu_write(COPRO_WINDOW_SIZE, 0x1234_5678, 4).unwrap();
+        link.main_write(COPRO_HANDOFF, 0b10);
+        assert_eq!(link.main_read(COPRO_HANDOFF), 0b10);
+        assert_eq!(link.owner(1), WindowOwner::Cpu);
+
+        // Au VBlank, le moteur la détient : les écritures du CPU sont refusées
+        assert_eq!(link.vblank(), 0b10);
+        assert_eq!(link.owner(1), WindowOwner::Geometry);
+        assert_eq!(link.status(), COPRO_STATUS_WINDOW1_OWNED | COPRO_STATUS_BUSY);
+        link.cpu_write(COPRO_WINDOW_SIZE, 0, 4).unwrap();
+        link.cpu_write(0, 0xAB, 1).unwrap();
+        assert_eq!(link.refused_writes(), 1);
+        assert_eq!(link.cpu_read(COPRO_WINDOW_SIZE, 4).unwrap(), 0x1234_5678);
+        assert_eq!(link.cpu_read(0, 1).unwrap(), 0xAB);
+
+        // Sans nouvelle demande, elle revient au CPU au VBlank suivant
+        assert_eq!(link.vblank(), 0);
+        assert_eq!(link.owner(1), WindowOwner::Cpu);
+
+        // Boîte aux lettres : un mot dans chaque sens
+        link.main_write(COPRO_MAILBOX_DATA, 0x11);
+        link.main_write(COPRO_MAILBOX_DATA, 0x22);
+        assert_eq!(link.status(), COPRO_STATUS_BUSY | COPRO_STATUS_COMMAND_FULL);
+        assert_eq!(link.take_command(), Some(0x11));
+        link.post_reply(3);
+        assert_eq!(link.status(), COPRO_STATUS_REPLY_READY);
+        assert_eq!(link.main_read(COPRO_MAILBOX_DATA), 3);
+        assert_eq!(link.main_read(COPRO_MAILBOX_DATA), 0);
+    }
+}
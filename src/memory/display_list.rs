@@ -0,0 +1,70 @@
+//! Listes d'affichage en double tampon
+//!
+//! Le jeu prépare la géométrie de la frame suivante pendant que le GPU
+//! dessine celle de la frame en cours. Deux bancs de RAM partagent une même
+//! fenêtre d'adresses : le CPU ne voit que le banc arrière, celui qu'il
+//! remplit. Quand la liste est complète, le jeu demande l'échange par le
+//! registre de banc ; il a lieu au VBlank suivant, le banc terminé passe
+//! alors au GPU et le CPU continue dans l'autre, sans toucher la liste en
+//! cours de rendu.
+//!
+//! Une liste est une suite de mots de commande GPU de 32 bits (même format
+//! que le registre de commande GPU), terminée par [`DISPLAY_LIST_END`] ou
+//! par la fin du banc.
+
+use super::interface::MemoryInterface;
+use super::ram::Ram;
+
+/// Fenêtre des listes d'affichage dans l'espace du V60, après la VRAM
+pub const DISPLAY_LIST_BASE: u32 = 0x1080_0000;
+
+/// Taille d'un banc
+pub const DISPLAY_LIST_BANK_SIZE: u32 = 0x0001_0000;
+
+/// Registre de banc, dans les registres I/O
+pub const DISPLAY_BANK_REGISTER: u32 = 0x2C;
+
+/// Registre de banc : banc rempli par le CPU (lecture seule)
+pub const DISPLAY_BANK_BACK: u32 = 0x01;
+
+/// Registre de banc : échange demandé pour le prochain VBlank
+pub const DISPLAY_BANK_SWAP: u32 = 0x02;
+
+/// Fin de liste
+pub const DISPLAY_LIST_END: u32 = 0xFFFF_FFFF;
+
+/// Les deux bancs de listes d'affichage
+#[derive(Debug)]
+pub struct DisplayListBanks {
+    banks: [Ram; 2],
+}
+
+impl DisplayListBanks {
+    pub fn new() -> Self {
+        Self {
+            banks: [Ram::new(DISPLAY_LIST_BANK_SIZE as usize), Ram::new(DISPLAY_LIST_BANK_SIZE as usize)],
+        }
+    }
+
+    pub fn bank(&self, index: usize) -> &Ram {
+        &self.banks[index & 1]
+    }
+
+    pub fn bank_mut(&mut self, index: usize) -> &mut Ram {
+        &mut self.banks[index & 1]
+    }
+
+    /// Mots de commande d'un banc, jusqu'à la fin de liste
+    pub fn command_words(&self, index: usize) -> impl Iterator<Item = u32> + '_ {
+        let bank = self.bank(index);
+        (0..DISPLAY_LIST_BANK_SIZE / 4)
+            .map_while(|word| bank.read_u32(word * 4).ok())
+            .take_while(|&word| word != DISPLAY_LIST_END)
+    }
+}
+
+impl Default for DisplayListBanks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
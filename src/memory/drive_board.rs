@@ -0,0 +1,214 @@
+//! Carte de pilotage (drive board) des jeux de course
+//!
+//! Daytona USA, Sega Rally et les autres jeux de course commandent le moteur
+//! du volant en écrivant un octet dans un registre de sortie de la carte
+//! I/O ([`DRIVE_BOARD_REGISTER`]). Le quartet de poids fort donne l'effet,
+//! celui de poids faible sa force (0 à 15) :
+//!
+//! | Octet       | Effet                                   |
+//! |-------------|-----------------------------------------|
+//! | 0x00 - 0x0F | moteur coupé, initialisation            |
+//! | 0x10 - 0x1F | rappel au centre (ressort)              |
+//! | 0x20 - 0x2F | friction                                |
+//! | 0x30 - 0x3F | vibration (sortie de piste, collision)  |
+//! | 0x50 - 0x5F | couple vers la droite                   |
+//! | 0x60 - 0x6F | couple vers la gauche                   |
+//!
+//! Les jeux réécrivent la même commande à chaque frame : seuls les
+//! changements sont retenus. Ils sont relevés comme [`DriveCommand`], et
+//! l'effet résultant se lit dans [`ForceFeedbackState`], que les frontends
+//! peuvent transmettre à un volant à retour de force ou traduire en
+//! vibrations de manette.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Registre de sortie de la carte de pilotage dans les registres I/O
+pub const DRIVE_BOARD_REGISTER: u32 = 0x60;
+
+/// Commandes conservées tant que personne ne les relève ; les plus
+/// anciennes sont oubliées au-delà
+pub const DRIVE_COMMAND_BACKLOG: usize = 256;
+
+/// Force maximale d'un effet
+pub const DRIVE_MAX_STRENGTH: u8 = 0x0F;
+
+/// Commande reçue par la carte de pilotage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "effect", content = "strength", rename_all = "snake_case")]
+pub enum DriveCommand {
+    /// Moteur coupé
+    Stop,
+    /// Rappel au centre
+    Centering(u8),
+    Friction(u8),
+    Vibration(u8),
+    RollRight(u8),
+    RollLeft(u8),
+    /// Octet hors des plages connues, transmis tel quel
+    Unknown(u8),
+}
+
+impl DriveCommand {
+    /// Décode l'octet écrit par le jeu
+    pub fn decode(value: u8) -> Self {
+        let strength = value & DRIVE_MAX_STRENGTH;
+        match value >> 4 {
+            0x0 => DriveCommand::Stop,
+            0x1 => DriveCommand::Centering(strength),
+            0x2 => DriveCommand::Friction(strength),
+            0x3 => DriveCommand::Vibration(strength),
+            0x5 => DriveCommand::RollRight(strength),
+            0x6 => DriveCommand::RollLeft(strength),
+            _ => DriveCommand::Unknown(value),
+        }
+    }
+
+    /// Octet de la commande (`Stop` : 0x00)
+    pub fn code(self) -> u8 {
+        match self {
+            DriveCommand::Stop => 0x00,
+            DriveCommand::Centering(strength) => 0x10 | strength,
+            DriveCommand::Friction(strength) => 0x20 | strength,
+            DriveCommand::Vibration(strength) => 0x30 | strength,
+            DriveCommand::RollRight(strength) => 0x50 | strength,
+            DriveCommand::RollLeft(strength) => 0x60 | strength,
+            DriveCommand::Unknown(value) => value,
+        }
+    }
+}
+
+/// Effets en cours sur le volant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ForceFeedbackState {
+    pub centering: u8,
+    pub friction: u8,
+    pub vibration: u8,
+    /// Couple, de -15 (gauche) à 15 (droite)
+    pub roll: i8,
+}
+
+impl ForceFeedbackState {
+    /// Applique une commande ; une commande inconnue ne change rien
+    pub fn apply(&mut self, command: DriveCommand) {
+        match command {
+            DriveCommand::Stop => *self = Self::default(),
+            DriveCommand::Centering(strength) => self.centering = strength,
+            DriveCommand::Friction(strength) => self.friction = strength,
+            DriveCommand::Vibration(strength) => self.vibration = strength,
+            DriveCommand::RollRight(strength) => self.roll = strength as i8,
+            DriveCommand::RollLeft(strength) => self.roll = -(strength as i8),
+            DriveCommand::Unknown(_) => {}
+        }
+    }
+
+    /// Intensités (0.0 à 1.0) des moteurs basse et haute fréquence d'une
+    /// manette : le couple fait tourner le gros moteur, la vibration le
+    /// petit. Le rappel au centre et la friction n'ont pas d'équivalent.
+    pub fn rumble(&self) -> (f32, f32) {
+        let max = DRIVE_MAX_STRENGTH as f32;
+        (self.roll.unsigned_abs() as f32 / max, self.vibration as f32 / max)
+    }
+}
+
+/// Commande relevée à la fin d'une frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForceFeedbackEvent {
+    pub frame: u64,
+    pub command: DriveCommand,
+    /// Effets en cours après la commande
+    pub state: ForceFeedbackState,
+}
+
+/// Carte de pilotage branchée sur la sortie de la carte I/O
+#[derive(Debug, Clone, Default)]
+pub struct DriveBoard {
+    /// Dernier octet reçu
+    last: Option<u8>,
+    state: ForceFeedbackState,
+    /// Changements pas encore relevés, avec l'état qui en résulte
+    pending: VecDeque<(DriveCommand, ForceFeedbackState)>,
+}
+
+impl DriveBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Octet écrit par le jeu ; ignoré s'il répète la commande précédente
+    pub fn write(&mut self, value: u8) {
+        if self.last == Some(value) {
+            return;
+        }
+        self.last = Some(value);
+
+        let command = DriveCommand::decode(value);
+        if let DriveCommand::Unknown(value) = command {
+            log::debug!("Commande de carte de pilotage inconnue: 0x{:02X}", value);
+        }
+        self.state.apply(command);
+        if self.pending.len() == DRIVE_COMMAND_BACKLOG {
+            self.pending.pop_front();
+        }
+        self.pending.push_back((command, self.state));
+    }
+
+    /// Effets en cours
+    pub fn state(&self) -> ForceFeedbackState {
+        self.state
+    }
+
+    /// Changements reçus depuis le dernier appel, datés de `frame`
+    pub fn take_events(&mut self, frame: u64) -> impl Iterator<Item = ForceFeedbackEvent> + '_ {
+        self.pending
+            .drain(..)
+            .map(move |(command, state)| ForceFeedbackEvent { frame, command, state })
+    }
+
+    /// Reset de la carte : moteur coupé
+    pub fn reset(&mut self) {
+        if self.state != ForceFeedbackState::default() {
+            self.last = None;
+            self.write(DriveCommand::Stop.code());
+        }
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drive_board_records_changes_and_state() {
+        let mut board = DriveBoard::new();
+        for value in [0x1A, 0x1A, 0x35, 0x58, 0x58, 0x63, 0x99] {
+            board.write(value);
+        }
+        let events: Vec<_> = board.take_events(12).collect();
+        let commands: Vec<_> = events.iter().map(|event| event.command).collect();
+        assert_eq!(
+            commands,
+            [
+                DriveCommand::Centering(10),
+                DriveCommand::Vibration(5),
+                DriveCommand::RollRight(8),
+                DriveCommand::RollLeft(3),
+                DriveCommand::Unknown(0x99),
+            ]
+        );
+        assert!(events.iter().all(|event| event.frame == 12));
+        assert_eq!(events[2].state.roll, 8);
+        assert_eq!(commands.iter().map(|command| command.code()).collect::<Vec<_>>(), [0x1A, 0x35, 0x58, 0x63, 0x99]);
+
+        let state = board.state();
+        assert_eq!((state.centering, state.vibration, state.roll), (10, 5, -3));
+        assert_eq!(state.rumble(), (0.2, 1.0 / 3.0));
+        assert_eq!(board.take_events(13).count(), 0);
+
+        // Le reset coupe le moteur et le signale
+        board.reset();
+        assert_eq!(board.state(), ForceFeedbackState::default());
+        assert_eq!(board.take_events(14).map(|event| event.command).collect::<Vec<_>>(), [DriveCommand::Stop]);
+    }
+}
@@ -0,0 +1,118 @@
+//! Ordre des octets des données émulées
+//!
+//! Le V60, la RAM, les ROMs programme et les textures du Model 2 sont en
+//! petit-boutiste. La mémoire émulée, le décodeur d'instructions, les
+//! textures, l'analyse des ROMs et les états de sauvegarde lisent leurs
+//! valeurs multi-octets par ces fonctions, qui nomment l'ordre attendu. Les
+//! autres lecteurs (offsets des patchs IPS, symboles ELF, WAV de référence)
+//! assemblent des octets explicites ; aucun ne dépend de l'ordre de l'hôte.
+//! Seuls les sommets et uniformes envoyés au GPU (`bytemuck`) sont copiés
+//! dans l'ordre de l'hôte, puisque c'est la carte graphique du même hôte
+//! qui les lit.
+
+/// Ordre des octets d'une valeur stockée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ByteOrder {
+    /// Ordre du V60 et de la mémoire du Model 2
+    #[default]
+    Little,
+    Big,
+}
+
+impl ByteOrder {
+    /// Entier de `bytes.len()` octets (8 au plus)
+    pub fn read_uint(self, bytes: &[u8]) -> u64 {
+        debug_assert!(bytes.len() <= 8, "read_uint: {} octets", bytes.len());
+        let fold = |value: u64, &byte: &u8| value << 8 | byte as u64;
+        match self {
+            ByteOrder::Little => bytes.iter().rev().fold(0, fold),
+            ByteOrder::Big => bytes.iter().fold(0, fold),
+        }
+    }
+
+    /// Mot de 16 bits à `offset` ; None s'il dépasse `bytes`
+    pub fn read_u16(self, bytes: &[u8], offset: usize) -> Option<u16> {
+        let bytes = bytes.get(offset..offset.checked_add(2)?)?.try_into().ok()?;
+        Some(match self {
+            ByteOrder::Little => u16::from_le_bytes(bytes),
+            ByteOrder::Big => u16::from_be_bytes(bytes),
+        })
+    }
+
+    /// Mot de 32 bits à `offset` ; None s'il dépasse `bytes`
+    pub fn read_u32(self, bytes: &[u8], offset: usize) -> Option<u32> {
+        let bytes = bytes.get(offset..offset.checked_add(4)?)?.try_into().ok()?;
+        Some(match self {
+            ByteOrder::Little => u32::from_le_bytes(bytes),
+            ByteOrder::Big => u32::from_be_bytes(bytes),
+        })
+    }
+
+    pub fn u16_bytes(self, value: u16) -> [u8; 2] {
+        match self {
+            ByteOrder::Little => value.to_le_bytes(),
+            ByteOrder::Big => value.to_be_bytes(),
+        }
+    }
+
+    pub fn u32_bytes(self, value: u32) -> [u8; 4] {
+        match self {
+            ByteOrder::Little => value.to_le_bytes(),
+            ByteOrder::Big => value.to_be_bytes(),
+        }
+    }
+
+    /// Écrit un mot de 16 bits à `offset` ; faux s'il dépasse `bytes`
+    pub fn write_u16(self, bytes: &mut [u8], offset: usize, value: u16) -> bool {
+        write_bytes(bytes, offset, &self.u16_bytes(value))
+    }
+
+    /// Écrit un mot de 32 bits à `offset` ; faux s'il dépasse `bytes`
+    pub fn write_u32(self, bytes: &mut [u8], offset: usize, value: u32) -> bool {
+        write_bytes(bytes, offset, &self.u32_bytes(value))
+    }
+}
+
+fn write_bytes(bytes: &mut [u8], offset: usize, value: &[u8]) -> bool {
+    match offset.checked_add(value.len()).and_then(|end| bytes.get_mut(offset..end)) {
+        Some(target) => {
+            target.copy_from_slice(value);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_swapped_fixture_reads_identically() {
+        // Même contenu en petit-boutiste et dans l'ordre inverse
+        let little = [0x34, 0x12, 0x78, 0x56, 0xEF, 0xCD, 0xAB, 0x89];
+        let mut big = little;
+        big.chunks_exact_mut(2).for_each(<[u8]>::reverse);
+        assert_eq!(big, [0x12, 0x34, 0x56, 0x78, 0xCD, 0xEF, 0x89, 0xAB]);
+
+        for offset in (0..8).step_by(2) {
+            assert_eq!(ByteOrder::Little.read_u16(&little, offset), ByteOrder::Big.read_u16(&big, offset));
+        }
+        assert_eq!(ByteOrder::Little.read_u16(&little, 0), Some(0x1234));
+        assert_eq!(ByteOrder::Little.read_u32(&little, 0), Some(0x5678_1234));
+        assert_eq!(ByteOrder::Little.read_u32(&little, 5), None);
+        assert_eq!(ByteOrder::Big.read_u16(&big, usize::MAX), None);
+
+        let mut big = little;
+        big.chunks_exact_mut(4).for_each(<[u8]>::reverse);
+        assert_eq!(ByteOrder::Big.read_u32(&big, 4), ByteOrder::Little.read_u32(&little, 4));
+        assert_eq!(ByteOrder::Little.read_uint(&little[..3]), 0x78_1234);
+        assert_eq!(ByteOrder::Big.read_uint(&big[..3]), 0x56_7812);
+
+        let mut bytes = [0u8; 6];
+        assert!(ByteOrder::Big.write_u32(&mut bytes, 2, 0xCAFE_F00D));
+        assert!(ByteOrder::Little.write_u16(&mut bytes, 0, 0xBEEF));
+        assert!(!ByteOrder::Little.write_u16(&mut bytes, 5, 0));
+        assert_eq!(bytes, [0xEF, 0xBE, 0xCA, 0xFE, 0xF0, 0x0D]);
+    }
+}
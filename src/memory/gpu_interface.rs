@@ -0,0 +1,227 @@
+//! Interface entre le CPU et le GPU
+//!
+//! Le CPU parle au GPU par quatre registres : contrôle, statut, commande et
+//! banc des listes d'affichage. Un mot écrit dans le registre de commande
+//! est décodé aussitôt ; les listes d'affichage et les fenêtres du
+//! coprocesseur géométrique utilisent le même format de mot et passent par
+//! le même décodeur.
+
+use serde::{Deserialize, Serialize};
+
+use super::display_list::{DISPLAY_BANK_BACK, DISPLAY_BANK_REGISTER, DISPLAY_BANK_SWAP};
+use super::{GpuCommand, RenderStateType};
+
+/// Registre de contrôle GPU
+pub const GPU_CONTROL_REGISTER: u32 = 0x20;
+
+/// Registre de statut GPU
+pub const GPU_STATUS_REGISTER: u32 = 0x24;
+
+/// Registre de commande GPU
+pub const GPU_COMMAND_REGISTER: u32 = 0x28;
+
+/// Statut : GPU prêt
+pub const GPU_STATUS_READY: u32 = 0x01;
+
+/// Registres de l'interface GPU
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GpuInterface {
+    /// Registre de contrôle (0xC0000020)
+    #[serde(rename = "gpu_control")]
+    pub control: u32,
+
+    /// Registre de statut (0xC0000024)
+    #[serde(rename = "gpu_status")]
+    pub status: u32,
+
+    /// Dernier mot écrit dans le registre de commande (0xC0000028)
+    #[serde(rename = "gpu_command")]
+    pub command: u32,
+
+    /// Registre de banc des listes d'affichage (0xC000002C)
+    pub display_bank: u32,
+
+    /// Mots de commande non reconnus depuis le dernier relevé
+    #[serde(skip)]
+    unknown_commands: u32,
+}
+
+impl Default for GpuInterface {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GpuInterface {
+    pub fn new() -> Self {
+        Self {
+            control: 0,
+            status: GPU_STATUS_READY,
+            command: 0,
+            display_bank: 0,
+            unknown_commands: 0,
+        }
+    }
+
+    /// Lit un registre (offset aligné sur 4) ; None hors de l'interface
+    pub fn read(&self, offset: u32) -> Option<u32> {
+        match offset {
+            GPU_CONTROL_REGISTER => Some(self.control),
+            GPU_STATUS_REGISTER => Some(self.status),
+            GPU_COMMAND_REGISTER => Some(self.command),
+            DISPLAY_BANK_REGISTER => Some(self.display_bank),
+            _ => None,
+        }
+    }
+
+    /// Écrit un registre (offset aligné sur 4) : None hors de l'interface,
+    /// sinon la commande décodée si le registre de commande a été écrit
+    pub fn write(&mut self, offset: u32, value: u32) -> Option<Option<GpuCommand>> {
+        match offset {
+            GPU_CONTROL_REGISTER => self.control = value,
+            GPU_STATUS_REGISTER => self.status = value,
+            GPU_COMMAND_REGISTER => {
+                self.command = value;
+                return Some(Some(self.decode_command(value)));
+            }
+            // Le banc courant est en lecture seule, seul l'échange se demande
+            DISPLAY_BANK_REGISTER => self.display_bank = (self.display_bank & DISPLAY_BANK_BACK) | (value & DISPLAY_BANK_SWAP),
+            _ => return None,
+        }
+        Some(None)
+    }
+
+    /// Banc des listes d'affichage rempli par le CPU
+    pub fn back_bank(&self) -> usize {
+        (self.display_bank & DISPLAY_BANK_BACK) as usize
+    }
+
+    /// VBlank : si le jeu l'a demandé, échange les bancs et renvoie le banc
+    /// terminé
+    pub fn swap_banks(&mut self) -> Option<usize> {
+        if self.display_bank & DISPLAY_BANK_SWAP == 0 {
+            return None;
+        }
+        let completed = self.back_bank();
+        self.display_bank = (self.display_bank ^ DISPLAY_BANK_BACK) & DISPLAY_BANK_BACK;
+        Some(completed)
+    }
+
+    /// Mots de commande non reconnus depuis le dernier appel
+    pub fn take_unknown_commands(&mut self) -> u32 {
+        std::mem::take(&mut self.unknown_commands)
+    }
+
+    /// Décode une commande GPU (version étendue)
+    pub fn decode_command(&mut self, command: u32) -> GpuCommand {
+        // Extraire le type de commande des bits de poids fort
+        let cmd_type = (command >> 24) & 0xFF;
+
+        match cmd_type {
+            0x00 => {
+                // Clear screen - commande simple
+                let r = ((command >> 16) & 0xFF) as f32 / 255.0;
+                let g = ((command >> 8) & 0xFF) as f32 / 255.0;
+                let b = (command & 0xFF) as f32 / 255.0;
+                let a = 1.0; // Alpha par défaut
+                GpuCommand::ClearScreen {
+                    color: [r, g, b, a],
+                    depth: 1.0,
+                    stencil: 0
+                }
+            },
+            0x01 => {
+                // Set render state
+                let state_bits = (command >> 16) & 0xFF;
+                let enabled = (command & 0x01) != 0;
+                let state_type = match state_bits {
+                    0x01 => RenderStateType::ZBuffer,
+                    0x02 => RenderStateType::Texturing,
+                    0x04 => RenderStateType::Lighting,
+                    0x08 => RenderStateType::Transparency,
+                    0x10 => RenderStateType::AlphaTest,
+                    0x20 => RenderStateType::Fog,
+                    0x40 => RenderStateType::Wireframe,
+                    0x80 => RenderStateType::BackfaceCulling,
+                    _ => RenderStateType::ZBuffer, // Défaut
+                };
+                GpuCommand::SetRenderState { state: state_type, enabled }
+            },
+            0x02 => {
+                // Load texture (placeholder - nécessiterait plus de données)
+                GpuCommand::LoadTexture {
+                    id: (command >> 16) & 0xFF,
+                    data: vec![], // Données vides pour l'instant
+                    width: 64,
+                    height: 64
+                }
+            },
+            0x10 => {
+                // Set model matrix (placeholder - nécessiterait lecture de données supplémentaires)
+                GpuCommand::SetModelMatrix([
+                    1.0, 0.0, 0.0, 0.0,
+                    0.0, 1.0, 0.0, 0.0,
+                    0.0, 0.0, 1.0, 0.0,
+                    0.0, 0.0, 0.0, 1.0,
+                ])
+            },
+            0x11 => {
+                // Set view matrix (placeholder)
+                GpuCommand::SetViewMatrix([
+                    1.0, 0.0, 0.0, 0.0,
+                    0.0, 1.0, 0.0, 0.0,
+                    0.0, 0.0, 1.0, 0.0,
+                    0.0, 0.0, -2.0, 1.0,
+                ])
+            },
+            0x12 => {
+                // Set projection matrix (placeholder)
+                GpuCommand::SetProjectionMatrix([
+                    1.0, 0.0, 0.0, 0.0,
+                    0.0, 1.0, 0.0, 0.0,
+                    0.0, 0.0, 1.0, 0.0,
+                    0.0, 0.0, 0.0, 1.0,
+                ])
+            },
+            _ => {
+                // Commande inconnue - utiliser clear screen par défaut
+                println!("GPU: Commande inconnue {:08X}, utilisation de ClearScreen par défaut", command);
+                self.unknown_commands += 1;
+                GpuCommand::ClearScreen {
+                    color: [0.0, 0.0, 0.0, 1.0],
+                    depth: 1.0,
+                    stencil: 0
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_interface_decodes_commands_and_swaps_banks() {
+        let mut gpu = GpuInterface::new();
+        assert_eq!(gpu.read(GPU_STATUS_REGISTER), Some(GPU_STATUS_READY));
+        assert_eq!(gpu.read(0x30), None);
+        assert!(gpu.write(0x30, 1).is_none());
+        assert!(matches!(gpu.write(GPU_CONTROL_REGISTER, 7), Some(None)));
+
+        let command = gpu.write(GPU_COMMAND_REGISTER, 0x0120_0001).flatten();
+        assert!(matches!(command, Some(GpuCommand::SetRenderState { state: RenderStateType::Fog, enabled: true })));
+        assert_eq!(gpu.read(GPU_COMMAND_REGISTER), Some(0x0120_0001));
+        gpu.decode_command(0x7F00_0000);
+        assert_eq!((gpu.take_unknown_commands(), gpu.take_unknown_commands()), (1, 0));
+
+        // Le banc courant ne s'écrit pas, l'échange attend le VBlank
+        assert_eq!(gpu.swap_banks(), None);
+        gpu.write(DISPLAY_BANK_REGISTER, DISPLAY_BANK_SWAP | DISPLAY_BANK_BACK);
+        assert_eq!((gpu.display_bank, gpu.back_bank()), (DISPLAY_BANK_SWAP, 0));
+        assert_eq!(gpu.swap_banks(), Some(0));
+        assert_eq!((gpu.display_bank, gpu.back_bank()), (DISPLAY_BANK_BACK, 1));
+        assert_eq!(gpu.swap_banks(), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! Contournements par jeu (hacks)
+//!
+//! Certains jeux ne démarrent pas tant qu'un détail du matériel n'est pas
+//! émulé. Plutôt que de tester le nom du jeu dans le cœur, chaque
+//! contournement porte un nom, s'active depuis le profil du jeu et agit à un
+//! point d'accroche précis : écriture d'un registre I/O au démarrage, valeur
+//! forcée à la lecture d'un registre, ou interruption déclenchée à chaque
+//! frame. Les registres sont désignés par leur offset, leur adresse
+//! dépendant de la révision de la carte.
+//! Chaque contournement appliqué est journalisé.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::cpu::Interrupt;
+
+/// Statut GPU : transfert DMA de la géométrie terminé
+pub const GPU_STATUS_DMA_DONE: u32 = 0x0000_0002;
+
+/// Contrôle d'entrée : mode service déverrouillé
+pub const INPUT_CONTROL_SERVICE_UNLOCK: u32 = 0x8000_0000;
+
+/// Contournements connus, activés par le profil du jeu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hack {
+    /// Le transfert DMA de la géométrie est toujours signalé terminé
+    SkipGeometryDmaCheck,
+    /// Le mode service est déverrouillé au démarrage
+    ForceServiceUnlock,
+    /// Un VBLANK est déclenché à chaque frame, pour les jeux qui l'attendent
+    /// avant d'avoir programmé le contrôleur d'interruptions
+    ForceVblank,
+}
+
+/// Point d'accroche d'un contournement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HackHook {
+    /// Écriture d'un registre I/O après le chargement du jeu
+    RegisterPatch { offset: u32, value: u32 },
+    /// Bits forcés à la lecture d'un registre I/O
+    RegisterOverride(RegisterOverride),
+    /// Interruption déclenchée à chaque frame
+    ForceInterrupt(Interrupt),
+}
+
+/// Bits `mask` d'un registre I/O lus avec la valeur `value`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterOverride {
+    pub offset: u32,
+    pub mask: u32,
+    pub value: u32,
+}
+
+impl RegisterOverride {
+    pub fn apply(&self, value: u32) -> u32 {
+        (value & !self.mask) | (self.value & self.mask)
+    }
+}
+
+impl Hack {
+    pub fn name(self) -> &'static str {
+        match self {
+            Hack::SkipGeometryDmaCheck => "skip_geometry_dma_check",
+            Hack::ForceServiceUnlock => "force_service_unlock",
+            Hack::ForceVblank => "force_vblank",
+        }
+    }
+
+    /// Points d'accroche où le contournement agit
+    pub fn hooks(self) -> Vec<HackHook> {
+        match self {
+            Hack::SkipGeometryDmaCheck => vec![HackHook::RegisterOverride(RegisterOverride {
+                offset: 0x24,
+                mask: GPU_STATUS_DMA_DONE,
+                value: GPU_STATUS_DMA_DONE,
+            })],
+            Hack::ForceServiceUnlock => vec![HackHook::RegisterPatch {
+                offset: 0x44,
+                value: INPUT_CONTROL_SERVICE_UNLOCK,
+            }],
+            Hack::ForceVblank => vec![HackHook::ForceInterrupt(Interrupt::VBlank)],
+        }
+    }
+}
+
+/// Contournements actifs pour le jeu chargé, rangés par point d'accroche
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameHacks {
+    active: BTreeSet<Hack>,
+    register_patches: Vec<(u32, u32)>,
+    register_overrides: Vec<RegisterOverride>,
+    forced_interrupts: Vec<Interrupt>,
+}
+
+impl GameHacks {
+    pub fn new(hacks: impl IntoIterator<Item = Hack>) -> Self {
+        let mut game_hacks = Self::default();
+        for hack in hacks {
+            if !game_hacks.active.insert(hack) {
+                continue;
+            }
+            for hook in hack.hooks() {
+                match hook {
+                    HackHook::RegisterPatch { offset, value } => game_hacks.register_patches.push((offset, value)),
+                    HackHook::RegisterOverride(register) => game_hacks.register_overrides.push(register),
+                    HackHook::ForceInterrupt(interrupt) => game_hacks.forced_interrupts.push(interrupt),
+                }
+            }
+        }
+        game_hacks
+    }
+
+    pub fn is_active(&self, hack: Hack) -> bool {
+        self.active.contains(&hack)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Correctifs de démarrage : (offset du registre I/O, valeur)
+    pub fn register_patches(&self) -> &[(u32, u32)] {
+        &self.register_patches
+    }
+
+    /// Valeur lue d'un registre I/O après les bits forcés
+    pub fn override_register(&self, offset: u32, value: u32) -> u32 {
+        self.register_overrides
+            .iter()
+            .filter(|register| register.offset == offset)
+            .fold(value, |value, register| register.apply(value))
+    }
+
+    /// Interruptions à déclencher à chaque frame
+    pub fn forced_interrupts(&self) -> &[Interrupt] {
+        &self.forced_interrupts
+    }
+
+    /// Noms des contournements actifs, pour le journal
+    pub fn names(&self) -> Vec<&'static str> {
+        self.active.iter().map(|hack| hack.name()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hacks_grouped_by_hook() {
+        let hacks = GameHacks::new([Hack::ForceVblank, Hack::SkipGeometryDmaCheck, Hack::ForceVblank]);
+        assert!(hacks.is_active(Hack::SkipGeometryDmaCheck));
+        assert!(!hacks.is_active(Hack::ForceServiceUnlock));
+        assert_eq!(hacks.names(), vec!["skip_geometry_dma_check", "force_vblank"]);
+        assert_eq!(hacks.forced_interrupts(), &[Interrupt::VBlank]);
+
+        assert_eq!(hacks.override_register(0x24, 0x0000_0001), 0x0000_0003);
+        assert_eq!(hacks.override_register(0x20, 0x0000_0001), 0x0000_0001);
+        assert!(GameHacks::default().is_empty());
+    }
+
+    #[test]
+    fn test_profile_names() {
+        let hacks: Vec<Hack> = serde_json::from_str(r#"["skip_geometry_dma_check", "force_service_unlock"]"#).unwrap();
+        assert_eq!(hacks, vec![Hack::SkipGeometryDmaCheck, Hack::ForceServiceUnlock]);
+        assert!(serde_json::from_str::<Hack>(r#""infinite_credits""#).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Carte de chaleur des accès mémoire
+//!
+//! Une [`AccessHeatmap`] compte les lectures et les écritures du CPU sur une
+//! plage d'adresses, par blocs de quelques centaines d'octets. Comme le
+//! [`super::WatchSet`], elle est relevée frame par frame : toutes les N
+//! frames, le cœur en tire un [`HeatmapSnapshot`] et repart de zéro. Une
+//! image montre alors où le jeu travaille (tables d'objets, pile, tampons
+//! de la géométrie), pour savoir où chercher avant de poser des
+//! surveillances.
+//!
+//! Elle se branche sur le bus comme [`AccessObserver`] ; ses compteurs sont
+//! atomiques, les lectures du bus passant par `&self`.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use super::watch::{AccessKind, AccessObserver};
+use crate::error::Result;
+
+/// Blocs par ligne de l'image exportée
+pub const HEATMAP_WIDTH: u32 = 256;
+
+/// Nombre maximal de blocs ; au-delà, les blocs sont agrandis
+pub const MAX_HEATMAP_BLOCKS: u32 = 1 << 16;
+
+/// Plus grand bloc, toute la plage d'adresses
+const MAX_BLOCK_SHIFT: u32 = 31;
+
+/// Compteurs d'accès d'une plage d'adresses
+#[derive(Debug)]
+pub struct AccessHeatmap {
+    start: u32,
+    length: u32,
+    /// Taille d'un bloc en puissance de deux
+    block_shift: u32,
+    reads: Vec<AtomicU32>,
+    writes: Vec<AtomicU32>,
+}
+
+impl AccessHeatmap {
+    /// Plage `start..start + length`, découpée en blocs de `block_size`
+    /// octets (arrondi à la puissance de deux supérieure, au plus 2 Go, et
+    /// agrandi pour ne pas dépasser [`MAX_HEATMAP_BLOCKS`])
+    pub fn new(start: u32, length: u32, block_size: u32) -> Self {
+        let length = length.max(1);
+        let mut block_shift = block_size.max(1).checked_next_power_of_two().map_or(MAX_BLOCK_SHIFT, u32::trailing_zeros);
+        while (length as u64).div_ceil(1 << block_shift) > MAX_HEATMAP_BLOCKS as u64 {
+            block_shift += 1;
+        }
+        let blocks = (length as u64).div_ceil(1 << block_shift) as usize;
+        Self {
+            start,
+            length,
+            block_shift,
+            reads: (0..blocks).map(|_| AtomicU32::new(0)).collect(),
+            writes: (0..blocks).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// Taille effective d'un bloc en octets
+    pub fn block_size(&self) -> u32 {
+        1 << self.block_shift
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.reads.len()
+    }
+
+    /// Bloc contenant `address`, hors plage : None
+    fn block(&self, address: u32) -> Option<usize> {
+        let offset = address.wrapping_sub(self.start);
+        (offset < self.length).then_some((offset >> self.block_shift) as usize)
+    }
+
+    /// Compte une lecture ; les accès hors plage sont ignorés
+    pub fn record_read(&self, address: u32) {
+        if let Some(block) = self.block(address) {
+            self.reads[block].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Compte une écriture ; les accès hors plage sont ignorés
+    pub fn record_write(&self, address: u32) {
+        if let Some(block) = self.block(address) {
+            self.writes[block].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Relève les compteurs accumulés sur `frames` frames et les remet à zéro
+    pub fn take(&self, frames: u32) -> HeatmapSnapshot {
+        let drain = |counters: &[AtomicU32]| counters.iter().map(|counter| counter.swap(0, Ordering::Relaxed)).collect();
+        HeatmapSnapshot {
+            start: self.start,
+            block_size: self.block_size(),
+            width: HEATMAP_WIDTH,
+            frames,
+            reads: drain(&self.reads),
+            writes: drain(&self.writes),
+        }
+    }
+}
+
+impl AccessObserver for AccessHeatmap {
+    fn on_access(&self, address: u32, _size: u8, kind: AccessKind) {
+        match kind {
+            AccessKind::Read => self.record_read(address),
+            AccessKind::Write => self.record_write(address),
+        }
+    }
+}
+
+/// Bloc parmi les plus accédés
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotBlock {
+    pub address: u32,
+    pub reads: u32,
+    pub writes: u32,
+}
+
+/// Accès comptés sur une période, bloc par bloc
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeatmapSnapshot {
+    /// Adresse du premier bloc
+    pub start: u32,
+    pub block_size: u32,
+    /// Blocs par ligne de l'image
+    pub width: u32,
+    /// Frames couvertes
+    pub frames: u32,
+    pub reads: Vec<u32>,
+    pub writes: Vec<u32>,
+}
+
+impl HeatmapSnapshot {
+    /// Lignes de l'image
+    pub fn height(&self) -> u32 {
+        (self.reads.len() as u32).div_ceil(self.width.max(1))
+    }
+
+    /// Image RGBA 8 bits, un pixel par bloc : écritures en rouge, lectures
+    /// en vert (jaune pour un bloc lu et écrit), sur une échelle
+    /// logarithmique relative au bloc le plus accédé ; noir : aucun accès
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let peak = self.reads.iter().chain(&self.writes).copied().max().unwrap_or(0);
+        let scale = (1.0 + peak as f32).ln().max(f32::EPSILON);
+        let level = |count: u32| ((1.0 + count as f32).ln() / scale * 255.0).round() as u8;
+
+        let mut rgba = vec![0; (self.width * self.height() * 4) as usize];
+        for (block, (&reads, &writes)) in self.reads.iter().zip(&self.writes).enumerate() {
+            rgba[block * 4..block * 4 + 4].copy_from_slice(&[level(writes), level(reads), 0, 255]);
+        }
+        for pixel in rgba.chunks_exact_mut(4).skip(self.reads.len()) {
+            pixel[3] = 255;
+        }
+        rgba
+    }
+
+    /// Enregistre [`to_rgba`](Self::to_rgba) en PNG (dossier créé au besoin)
+    pub fn save_png(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        image::save_buffer(path, &self.to_rgba(), self.width, self.height(), image::ExtendedColorType::Rgba8)
+            .map_err(|e| std::io::Error::other(format!("{} : {}", path.display(), e)))?;
+        Ok(())
+    }
+
+    /// Même période sur `width` blocs par ligne et au plus `max_blocks`
+    /// blocs, voisins additionnés (affichage en surimpression)
+    pub fn coarsened(&self, width: u32, max_blocks: usize) -> Self {
+        let factor = self.reads.len().div_ceil(max_blocks.max(1)).max(1).next_power_of_two();
+        let merge = |counters: &[u32]| counters.chunks(factor).map(|chunk| chunk.iter().fold(0u32, |sum, &count| sum.saturating_add(count))).collect();
+        Self {
+            start: self.start,
+            block_size: self.block_size * factor as u32,
+            width: width.max(1),
+            frames: self.frames,
+            reads: merge(&self.reads),
+            writes: merge(&self.writes),
+        }
+    }
+
+    /// Les `count` blocs les plus accédés, du plus au moins accédé
+    pub fn hottest(&self, count: usize) -> Vec<HotBlock> {
+        let mut blocks: Vec<HotBlock> = self
+            .reads
+            .iter()
+            .zip(&self.writes)
+            .enumerate()
+            .filter(|(_, (&reads, &writes))| reads > 0 || writes > 0)
+            .map(|(block, (&reads, &writes))| HotBlock {
+                address: self.start.wrapping_add(block as u32 * self.block_size),
+                reads,
+                writes,
+            })
+            .collect();
+        blocks.sort_by_key(|block| (std::cmp::Reverse(block.reads as u64 + block.writes as u64), block.address));
+        blocks.truncate(count);
+        blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap_counts_blocks_and_renders_image() {
+        // 300 octets demandés : blocs de 512 ; plage trop grande : agrandis
+        let heatmap = AccessHeatmap::new(0x1000, 0x10_0000, 300);
+        assert_eq!((heatmap.block_size(), heatmap.blocks()), (512, 2048));
+        assert_eq!(AccessHeatmap::new(0, u32::MAX, 4).blocks(), MAX_HEATMAP_BLOCKS as usize);
+        // Taille de bloc aberrante lue dans la configuration
+        assert_eq!(AccessHeatmap::new(0, 0x1000, u32::MAX).block_size(), 1 << 31);
+
+        for _ in 0..10 {
+            heatmap.record_read(0x1000);
+        }
+        heatmap.record_write(0x11FF);
+        heatmap.record_read(0x1200);
+        heatmap.record_write(0x0FFF);
+        heatmap.record_write(0x10_1000);
+
+        let snapshot = heatmap.take(60);
+        assert_eq!((snapshot.width, snapshot.height(), snapshot.frames), (HEATMAP_WIDTH, 8, 60));
+        assert_eq!(
+            snapshot.hottest(3),
+            [HotBlock { address: 0x1000, reads: 10, writes: 1 }, HotBlock { address: 0x1200, reads: 1, writes: 0 }]
+        );
+        assert!(heatmap.take(60).hottest(1).is_empty());
+
+        // Bloc le plus lu en vert vif, un seul accès à peine visible
+        let rgba = snapshot.to_rgba();
+        assert_eq!(rgba.len(), (256 * 8 * 4) as usize);
+        assert_eq!(&rgba[..4], &[74, 255, 0, 255]);
+        assert_eq!(&rgba[4..8], &[0, 74, 0, 255]);
+        assert_eq!(&rgba[8..12], &[0, 0, 0, 255]);
+
+        let coarse = snapshot.coarsened(32, 256);
+        assert_eq!((coarse.block_size, coarse.reads.len(), coarse.height()), (4096, 256, 8));
+        assert_eq!((coarse.reads[0], coarse.writes[0]), (11, 1));
+
+        let path = std::env::temp_dir().join(format!("pm2_heatmap_{}", std::process::id())).join("heatmap.png");
+        snapshot.save_png(&path).unwrap();
+        assert_eq!(image::open(&path).unwrap().to_rgba8().into_raw(), rgba);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+//! Port d'entrée de la carte I/O
+//!
+//! L'hôte présente l'état des boutons au connecteur à tout moment, mais la
+//! carte ne le verrouille dans son registre d'entrée qu'au VBlank : le jeu
+//! voit un état stable pendant toute la frame.
+
+use serde::{Deserialize, Serialize};
+
+/// Registre d'entrée de la carte I/O, verrouillé au VBlank
+pub const INPUT_DATA_REGISTER: u32 = 0x40;
+
+/// Registre de contrôle d'entrée
+pub const INPUT_CONTROL_REGISTER: u32 = 0x44;
+
+/// Mot d'entrée sans aucun bouton enfoncé, pour les quatre joueurs : les
+/// bits sont actifs bas, un bouton enfoncé met son bit à 0
+pub const INPUT_IDLE: u32 = u32::MAX;
+
+/// Registres du port d'entrée
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputPort {
+    /// Registre d'entrée (0xC0000040), actif bas : l'état des boutons
+    /// verrouillé au dernier VBlank, en lecture seule pour le jeu
+    #[serde(rename = "input_data")]
+    pub data: u32,
+
+    /// Mot présenté au connecteur par l'hôte, verrouillé au prochain VBlank
+    #[serde(skip)]
+    pending: u32,
+
+    /// Registre de contrôle d'entrée (0xC0000044)
+    #[serde(rename = "input_control")]
+    pub control: u32,
+}
+
+impl Default for InputPort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputPort {
+    pub fn new() -> Self {
+        Self {
+            data: INPUT_IDLE,
+            pending: INPUT_IDLE,
+            control: 0,
+        }
+    }
+
+    /// Lit un registre (offset aligné sur 4) ; None hors du port
+    pub fn read(&self, offset: u32) -> Option<u32> {
+        match offset {
+            INPUT_DATA_REGISTER => Some(self.data),
+            INPUT_CONTROL_REGISTER => Some(self.control),
+            _ => None,
+        }
+    }
+
+    /// Écrit un registre (offset aligné sur 4) ; faux hors du port
+    pub fn write(&mut self, offset: u32, value: u32) -> bool {
+        match offset {
+            // Les entrées ne changent qu'au verrouillage du VBlank
+            INPUT_DATA_REGISTER => {}
+            INPUT_CONTROL_REGISTER => self.control = value,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Présente un nouveau mot (actif bas) au connecteur
+    pub fn set_pending(&mut self, value: u32) {
+        self.pending = value;
+    }
+
+    /// VBlank : verrouille le mot présenté ; vrai si l'état a changé
+    pub fn latch(&mut self) -> bool {
+        if self.data == self.pending {
+            return false;
+        }
+        self.data = self.pending;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{io_word_for, MAX_PLAYERS};
+
+    #[test]
+    fn test_input_port_latches_on_vblank_only() {
+        let mut port = InputPort::new();
+        port.set_pending(0xFFFE);
+        assert_eq!(port.read(INPUT_DATA_REGISTER), Some(INPUT_IDLE));
+
+        // Le jeu ne peut pas écrire les entrées
+        assert!(port.write(INPUT_DATA_REGISTER, 0));
+        assert!(port.write(INPUT_CONTROL_REGISTER, 0x80));
+        assert_eq!(port.read(INPUT_DATA_REGISTER), Some(INPUT_IDLE));
+        assert_eq!(port.read(INPUT_CONTROL_REGISTER), Some(0x80));
+        assert_eq!(port.read(0x48), None);
+
+        assert!(port.latch());
+        assert!(!port.latch());
+        assert_eq!(port.data, 0xFFFE);
+    }
+
+    #[test]
+    fn test_input_port_starts_idle_for_four_players() {
+        // Au démarrage, les joueurs 3 et 4 ne doivent rien tenir enfoncé
+        let mut port = InputPort::new();
+        port.set_pending(io_word_for(&[0; MAX_PLAYERS], MAX_PLAYERS));
+        assert!(!port.latch());
+        assert_eq!(port.read(INPUT_DATA_REGISTER), Some(INPUT_IDLE));
+    }
+}
@@ -1,6 +1,6 @@
 //! Interface mémoire commune
 
-use anyhow::Result;
+use crate::error::Result;
 
 /// Trait définissant l'interface commune pour tous les types de mémoire
 pub trait MemoryInterface {
@@ -13,6 +13,22 @@ pub trait MemoryInterface {
     /// Lit un mot de 32 bits à l'adresse spécifiée
     fn read_u32(&self, address: u32) -> Result<u32>;
     
+    /// Lit un octet sans effet de bord (surveillances, débogueur) ; par
+    /// défaut, la lecture normale
+    fn peek_u8(&self, address: u32) -> Result<u8> {
+        self.read_u8(address)
+    }
+
+    /// Lit un mot de 16 bits sans effet de bord
+    fn peek_u16(&self, address: u32) -> Result<u16> {
+        self.read_u16(address)
+    }
+
+    /// Lit un mot de 32 bits sans effet de bord
+    fn peek_u32(&self, address: u32) -> Result<u32> {
+        self.read_u32(address)
+    }
+
     /// Écrit un octet à l'adresse spécifiée
     fn write_u8(&mut self, address: u32, value: u8) -> Result<()>;
     
@@ -0,0 +1,109 @@
+//! Contrôleur d'interruptions de la carte I/O
+//!
+//! Le registre de statut garde un bit par source levée ; le jeu l'acquitte
+//! en le réécrivant. La levée elle-même passe par la file d'interruptions
+//! du CPU, datée par l'ordonnanceur du cœur.
+
+use serde::{Deserialize, Serialize};
+
+use crate::cpu::{Interrupt, NecV60};
+
+/// Registre de contrôle des interruptions
+pub const INTERRUPT_CONTROL_REGISTER: u32 = 0x00;
+
+/// Registre de statut des interruptions
+pub const INTERRUPT_STATUS_REGISTER: u32 = 0x04;
+
+/// Statut : VBlank
+pub const INTERRUPT_VBLANK: u32 = 0x01;
+
+/// Statut : débordement du timer principal
+pub const INTERRUPT_TIMER_MAIN: u32 = 0x02;
+
+/// Statut : débordement du timer de sous-système
+pub const INTERRUPT_TIMER_SUB: u32 = 0x04;
+
+/// Statut : acquittement de la carte son
+pub const INTERRUPT_AUDIO: u32 = 0x10;
+
+/// Registres du contrôleur d'interruptions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InterruptController {
+    /// Registre de contrôle (0xC0000000)
+    #[serde(rename = "interrupt_control")]
+    pub control: u32,
+
+    /// Registre de statut (0xC0000004)
+    #[serde(rename = "interrupt_status")]
+    pub status: u32,
+}
+
+impl InterruptController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lit un registre (offset aligné sur 4) ; None hors du contrôleur
+    pub fn read(&self, offset: u32) -> Option<u32> {
+        match offset {
+            INTERRUPT_CONTROL_REGISTER => Some(self.control),
+            INTERRUPT_STATUS_REGISTER => Some(self.status),
+            _ => None,
+        }
+    }
+
+    /// Écrit un registre (offset aligné sur 4) ; faux hors du contrôleur
+    pub fn write(&mut self, offset: u32, value: u32) -> bool {
+        match offset {
+            INTERRUPT_CONTROL_REGISTER => self.control = value,
+            INTERRUPT_STATUS_REGISTER => self.status = value,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Marque `bits` dans le statut et enfile l'interruption du CPU
+    pub fn raise(&mut self, bits: u32, interrupt: Interrupt, cpu: &mut NecV60) {
+        self.status |= bits;
+        cpu.queue_interrupt(interrupt);
+    }
+
+    /// Lève une source que le jeu doit autoriser dans le registre de
+    /// contrôle (débordement d'un timer) ; faux si elle est masquée
+    pub fn raise_if_enabled(&mut self, bits: u32, interrupt: Interrupt, cpu: &mut NecV60) -> bool {
+        let enabled = self.control & bits != 0;
+        if enabled {
+            self.raise(bits, interrupt, cpu);
+        }
+        enabled
+    }
+
+    /// Signale le VBlank
+    pub fn raise_vblank(&mut self, cpu: &mut NecV60) {
+        self.raise(INTERRUPT_VBLANK, Interrupt::VBlank, cpu);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interrupt_controller_registers_and_raise() {
+        let mut controller = InterruptController::new();
+        assert!(controller.write(INTERRUPT_CONTROL_REGISTER, 0x1234));
+        assert!(!controller.write(0x08, 0xFFFF));
+        assert_eq!(controller.read(INTERRUPT_CONTROL_REGISTER), Some(0x1234));
+        assert_eq!(controller.read(0x08), None);
+
+        let mut cpu = NecV60::new();
+        controller.raise_vblank(&mut cpu);
+        controller.raise(INTERRUPT_AUDIO, Interrupt::Audio, &mut cpu);
+        assert_eq!(controller.read(INTERRUPT_STATUS_REGISTER), Some(INTERRUPT_VBLANK | INTERRUPT_AUDIO));
+
+        // Acquittement par réécriture du statut
+        controller.write(INTERRUPT_STATUS_REGISTER, 0);
+        assert_eq!(controller.status, 0);
+    }
+}
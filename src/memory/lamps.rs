@@ -0,0 +1,110 @@
+//! Lampes de la borne
+//!
+//! La carte I/O allume les lampes de la borne (boutons start, boutons de
+//! vue des jeux de course, lampe de leader, lampes des sièges) par un
+//! registre de sortie, un bit par lampe ([`LAMP_OUTPUT_REGISTER`]). L'état
+//! se lit comme un [`LampOutputs`], que les intégrations de borne comparent
+//! d'une frame à l'autre pour piloter de vraies lampes.
+
+use serde::{Deserialize, Serialize};
+
+/// Registre de sortie des lampes dans les registres I/O
+pub const LAMP_OUTPUT_REGISTER: u32 = 0x64;
+
+/// Lampe pilotée par la carte I/O
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lamp {
+    Start1,
+    Start2,
+    /// Boutons de changement de vue (VR1 à VR4)
+    View1,
+    View2,
+    View3,
+    View4,
+    /// Lampe de tête de course (bornes reliées)
+    Leader,
+    Seat1,
+    Seat2,
+}
+
+impl Lamp {
+    /// Toutes les lampes, dans l'ordre des bits du registre
+    pub const ALL: [Lamp; 9] = [
+        Lamp::Start1,
+        Lamp::Start2,
+        Lamp::View1,
+        Lamp::View2,
+        Lamp::View3,
+        Lamp::View4,
+        Lamp::Leader,
+        Lamp::Seat1,
+        Lamp::Seat2,
+    ];
+
+    /// Bit de la lampe dans le registre
+    pub fn bit(self) -> u32 {
+        self as u32
+    }
+
+    /// Nom transmis aux intégrations de borne
+    pub fn name(self) -> &'static str {
+        match self {
+            Lamp::Start1 => "start1",
+            Lamp::Start2 => "start2",
+            Lamp::View1 => "view1",
+            Lamp::View2 => "view2",
+            Lamp::View3 => "view3",
+            Lamp::View4 => "view4",
+            Lamp::Leader => "leader",
+            Lamp::Seat1 => "seat1",
+            Lamp::Seat2 => "seat2",
+        }
+    }
+}
+
+/// État de toutes les lampes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LampOutputs {
+    /// Un bit par lampe, dans l'ordre de [`Lamp::ALL`]
+    pub bits: u32,
+}
+
+impl LampOutputs {
+    pub fn from_bits(bits: u32) -> Self {
+        Self { bits }
+    }
+
+    pub fn is_lit(&self, lamp: Lamp) -> bool {
+        self.bits & (1 << lamp.bit()) != 0
+    }
+
+    /// Lampes allumées
+    pub fn lit(&self) -> impl Iterator<Item = Lamp> + '_ {
+        Lamp::ALL.into_iter().filter(|&lamp| self.is_lit(lamp))
+    }
+
+    /// Lampes qui diffèrent de `previous`, avec leur nouvel état
+    pub fn changes(&self, previous: LampOutputs) -> impl Iterator<Item = (Lamp, bool)> + '_ {
+        Lamp::ALL
+            .into_iter()
+            .filter(move |&lamp| self.is_lit(lamp) != previous.is_lit(lamp))
+            .map(|lamp| (lamp, self.is_lit(lamp)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lamp_outputs_report_changes() {
+        let previous = LampOutputs::from_bits(0b0100_0001);
+        let lamps = LampOutputs::from_bits(0b1_0000_0011);
+        assert_eq!(lamps.lit().collect::<Vec<_>>(), [Lamp::Start1, Lamp::Start2, Lamp::Seat2]);
+        assert_eq!(
+            lamps.changes(previous).collect::<Vec<_>>(),
+            [(Lamp::Start2, true), (Lamp::Leader, false), (Lamp::Seat2, true)]
+        );
+        assert_eq!(lamps.changes(lamps).count(), 0);
+    }
+}
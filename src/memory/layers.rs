@@ -0,0 +1,200 @@
+//! Registres de composition de l'image
+//!
+//! L'image finale superpose la 3D, deux plans de tuiles et les sprites
+//! selon une priorité par couche, sur une couleur de fond. Les jeux peuvent
+//! réécrire ces registres pendant le balayage (effets de raster) : chaque
+//! changement est relevé avec la ligne en cours, ce qui donne par exemple
+//! les dégradés de ciel obtenus en changeant la couleur de fond à chaque
+//! ligne.
+
+use serde::{Deserialize, Serialize};
+
+/// Registre de priorité des couches : 4 bits par couche, dans l'ordre de
+/// [`DisplayLayer::ALL`] ; la plus haute priorité est dessinée devant
+pub const LAYER_PRIORITY_REGISTER: u32 = 0x50;
+
+/// Registre d'activation des couches : 1 bit par couche
+pub const LAYER_ENABLE_REGISTER: u32 = 0x54;
+
+/// Couleur de fond, en 0x00RRGGBB
+pub const BACKDROP_COLOR_REGISTER: u32 = 0x58;
+
+/// Motifs des tuiles en VRAM : 8×8 pixels, un index de palette par octet ;
+/// l'index 0 est transparent
+pub const TILE_PATTERN_BASE: usize = 0x38_0000;
+
+/// Côté d'une tuile, en pixels
+pub const TILE_SIZE: u32 = 8;
+
+/// Motifs adressables par les cartes et les sprites
+pub const TILE_PATTERN_COUNT: usize = 4096;
+
+/// Cartes des plans A et B en VRAM : 64×64 entrées de 16 bits (motif sur
+/// 12 bits, bit 14 miroir horizontal, bit 15 miroir vertical)
+pub const TILE_MAP_BASE: [usize; 2] = [0x3C_0000, 0x3C_2000];
+
+/// Côté d'une carte, en tuiles ; le plan se répète au-delà
+pub const TILE_MAP_TILES: u32 = 64;
+
+/// Défilement des plans A et B en VRAM : X puis Y, 16 bits chacun
+pub const TILE_SCROLL_BASE: usize = 0x3C_4000;
+
+/// Table des sprites en VRAM : entrées de 8 octets (X et Y signés sur 16
+/// bits, premier motif, largeur et hauteur en tuiles) ; une largeur nulle
+/// masque l'entrée. Les premières entrées passent devant les suivantes.
+pub const SPRITE_TABLE_BASE: usize = 0x3C_4010;
+
+/// Entrées de la table des sprites
+pub const SPRITE_COUNT: usize = 128;
+
+/// Palette des plans 2D en VRAM : 256 couleurs xRGB555 de 16 bits
+pub const LAYER_PALETTE_BASE: usize = 0x3C_8000;
+
+/// Lignes visibles
+pub const VISIBLE_SCANLINES: u32 = 384;
+
+/// Lignes d'une image, retour vertical compris
+pub const SCANLINES_PER_FRAME: u32 = 424;
+
+/// Couche de l'image finale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisplayLayer {
+    Scene3D,
+    TileA,
+    TileB,
+    Sprites,
+}
+
+impl DisplayLayer {
+    pub const ALL: [DisplayLayer; 4] = [Self::Scene3D, Self::TileA, Self::TileB, Self::Sprites];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Registres de composition en vigueur sur une ligne
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerRegisters {
+    pub priority: u32,
+    pub enable: u32,
+    pub backdrop: u32,
+}
+
+impl Default for LayerRegisters {
+    /// Plan B au fond, puis la 3D, le plan A et les sprites devant
+    fn default() -> Self {
+        Self { priority: 0x3021, enable: 0xF, backdrop: 0 }
+    }
+}
+
+impl LayerRegisters {
+    pub fn priority(&self, layer: DisplayLayer) -> u8 {
+        ((self.priority >> (layer.index() * 4)) & 0xF) as u8
+    }
+
+    pub fn enabled(&self, layer: DisplayLayer) -> bool {
+        self.enable & (1 << layer.index()) != 0
+    }
+
+    /// Couches actives du fond vers l'avant ; à priorité égale, l'ordre de
+    /// [`DisplayLayer::ALL`] départage
+    pub fn draw_order(&self) -> Vec<DisplayLayer> {
+        let mut layers: Vec<_> = DisplayLayer::ALL.into_iter().filter(|&layer| self.enabled(layer)).collect();
+        layers.sort_by_key(|&layer| self.priority(layer));
+        layers
+    }
+
+    pub fn backdrop_rgba(&self) -> [u8; 4] {
+        let [_, r, g, b] = self.backdrop.to_be_bytes();
+        [r, g, b, 255]
+    }
+}
+
+/// Registres de composition d'une image, ligne par ligne
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanlineLog {
+    /// Registres au début de l'image
+    start: LayerRegisters,
+    /// Changements en cours d'image, par ligne croissante
+    changes: Vec<(u32, LayerRegisters)>,
+}
+
+impl ScanlineLog {
+    pub fn new(start: LayerRegisters) -> Self {
+        Self { start, changes: Vec::new() }
+    }
+
+    /// Nouvelle image, en gardant la capacité du relevé
+    pub fn restart(&mut self, start: LayerRegisters) {
+        self.start = start;
+        self.changes.clear();
+    }
+
+    /// Copie un relevé sans réallouer une fois la capacité atteinte
+    pub fn copy_from(&mut self, other: &ScanlineLog) {
+        self.start = other.start;
+        self.changes.clear();
+        self.changes.extend_from_slice(&other.changes);
+    }
+
+    /// Relève les registres écrits pendant la ligne `line` ; plusieurs
+    /// écritures sur la même ligne ne gardent que la dernière
+    pub fn record(&mut self, line: u32, registers: LayerRegisters) {
+        match self.changes.last_mut() {
+            Some((last, value)) if *last == line => *value = registers,
+            _ => self.changes.push((line, registers)),
+        }
+    }
+
+    /// Registres appliqués à la ligne `line` : un changement écrit pendant
+    /// une ligne vaut à partir de la suivante
+    pub fn registers_at(&self, line: u32) -> LayerRegisters {
+        self.changes
+            .iter()
+            .take_while(|(changed, _)| *changed < line)
+            .last()
+            .map_or(self.start, |(_, registers)| *registers)
+    }
+
+    /// Vrai si les registres varient pendant l'image
+    pub fn has_raster_effects(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Registres à la fin de l'image
+    pub fn end(&self) -> LayerRegisters {
+        self.changes.last().map_or(self.start, |(_, registers)| *registers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_order_follows_priorities() {
+        let registers = LayerRegisters::default();
+        assert_eq!(registers.draw_order(), vec![DisplayLayer::TileB, DisplayLayer::Scene3D, DisplayLayer::TileA, DisplayLayer::Sprites]);
+
+        // 3D devant tout, plan A éteint
+        let registers = LayerRegisters { priority: 0x1205, enable: 0xD, backdrop: 0x0011_2233 };
+        assert_eq!(registers.draw_order(), vec![DisplayLayer::Sprites, DisplayLayer::TileB, DisplayLayer::Scene3D]);
+        assert_eq!(registers.backdrop_rgba(), [0x11, 0x22, 0x33, 0xFF]);
+    }
+
+    #[test]
+    fn test_mid_frame_changes_apply_from_next_line() {
+        let mut log = ScanlineLog::new(LayerRegisters::default());
+        let sky = |backdrop| LayerRegisters { backdrop, ..Default::default() };
+        log.record(10, sky(1));
+        log.record(10, sky(2));
+        log.record(20, sky(3));
+
+        assert_eq!(log.registers_at(10).backdrop, 0);
+        assert_eq!(log.registers_at(11).backdrop, 2);
+        assert_eq!(log.registers_at(21).backdrop, 3);
+        assert_eq!(log.end().backdrop, 3);
+        assert!(log.has_raster_effects());
+    }
+}
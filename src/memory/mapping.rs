@@ -1,4 +1,26 @@
 //! Mapping mémoire du SEGA Model 2
+//!
+//! Chaque entrée associe une plage d'adresses à une région physique. Les
+//! miroirs sont décrits par un masque d'adresse : seuls les bits conservés par
+//! le masque sont décodés, comme sur une carte où certaines lignes d'adresse ne
+//! sont pas câblées. Une entrée peut aussi être protégée en écriture (RAM
+//! servant d'ombre à une ROM, par exemple) : les écritures y sont ignorées.
+//!
+//! Les révisions de la carte (Model 2, 2A-CRX, 2B-CRX, 2C-CRX) partagent la
+//! RAM, la VRAM et les périphériques, mais pas la taille des fenêtres de ROM
+//! ni l'adresse des registres I/O : chaque révision est une table de
+//! fenêtres ([`BoardRevision::windows`]), choisie d'après la carte indiquée
+//! par la base des jeux.
+
+use crate::error::{Result, RomError};
+
+use super::coprocessor::{COPRO_MAILBOX_BASE, COPRO_MAILBOX_SIZE, COPRO_WINDOW_BASE, COPRO_WINDOW_COUNT, COPRO_WINDOW_SIZE};
+use super::display_list::{DISPLAY_LIST_BANK_SIZE, DISPLAY_LIST_BASE};
+use super::protection::{PROTECTION_BASE, PROTECTION_SIZE};
+use super::sound_latch::{SOUND_LATCH_BASE, SOUND_LATCH_SIZE};
+
+/// Adresse des registres I/O de la carte Model 2 d'origine
+pub const IO_REGISTERS_BASE: u32 = 0xF000_0000;
 
 /// Régions mémoire du Model 2
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,6 +45,21 @@ pub enum MemoryRegion {
     
     /// Registres d'entrée/sortie
     IoRegisters,
+
+    /// Carte de protection
+    Protection,
+
+    /// Port série vers la carte son
+    SoundLatch,
+
+    /// Listes d'affichage en double tampon (banc arrière)
+    DisplayList,
+
+    /// Fenêtres de RAM partagées avec le coprocesseur géométrique
+    CoprocessorWindow,
+
+    /// Boîte aux lettres du coprocesseur géométrique
+    CoprocessorMailbox,
 }
 
 /// Entrée de mapping mémoire
@@ -45,6 +82,25 @@ pub struct MemoryMapEntry {
     
     /// Indique si la région est accessible en écriture
     pub writable: bool,
+
+    /// Masque appliqué à l'adresse relative (bits non décodés à 0)
+    pub mirror_mask: u32,
+
+    /// Écritures ignorées tant que la protection est active
+    pub write_protected: bool,
+}
+
+/// Destination d'une écriture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteTarget {
+    /// Région et offset local
+    Mapped(MemoryRegion, u32),
+
+    /// Zone protégée en écriture : l'accès est ignoré
+    Protected,
+
+    /// Aucune région à cette adresse
+    Unmapped,
 }
 
 impl MemoryMapEntry {
@@ -57,8 +113,25 @@ impl MemoryMapEntry {
             offset,
             size,
             writable,
+            mirror_mask: u32::MAX,
+            write_protected: false,
         }
     }
+
+    /// Crée une entrée dont le contenu se répète tous les `size` octets
+    /// (`size` doit être une puissance de deux)
+    pub fn mirrored(start: u32, end: u32, region: MemoryRegion, size: u32, writable: bool) -> Self {
+        Self {
+            mirror_mask: size.wrapping_sub(1),
+            ..Self::new(start, end, region, 0, size, writable)
+        }
+    }
+
+    /// Protège l'entrée en écriture
+    pub fn with_write_protect(mut self, protected: bool) -> Self {
+        self.write_protected = protected;
+        self
+    }
     
     /// Vérifie si une adresse est dans cette région
     pub fn contains(&self, address: u32) -> bool {
@@ -71,17 +144,148 @@ impl MemoryMapEntry {
             return None;
         }
         
-        let local_addr = address - self.start + self.offset;
+        let local_addr = ((address - self.start) & self.mirror_mask).wrapping_add(self.offset);
         
         // Gestion des miroirs - l'adresse est repliée sur la taille réelle
         Some(local_addr % self.size)
     }
 }
 
+/// Fenêtre d'une disposition de carte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowSpec {
+    pub region: MemoryRegion,
+    pub start: u32,
+    /// Longueur de la fenêtre dans l'espace d'adresses
+    pub length: u32,
+    /// Taille réelle de la région ; si elle est plus petite que la fenêtre,
+    /// son contenu s'y répète
+    pub size: u32,
+    pub writable: bool,
+}
+
+impl WindowSpec {
+    const fn new(region: MemoryRegion, start: u32, length: u32, size: u32, writable: bool) -> Self {
+        Self { region, start, length, size, writable }
+    }
+
+    /// Entrée de mapping correspondante
+    pub fn entry(&self) -> MemoryMapEntry {
+        if self.size < self.length {
+            MemoryMapEntry::mirrored(self.start, self.start + self.length, self.region, self.size, self.writable)
+        } else {
+            MemoryMapEntry::new(self.start, self.start + self.length, self.region, 0, self.size, self.writable)
+        }
+    }
+}
+
+/// Fenêtres identiques sur toutes les révisions
+const COMMON_WINDOWS: &[WindowSpec] = &[
+    // RAM principale - 8MB répétés jusqu'à 16MB
+    WindowSpec::new(MemoryRegion::MainRam, 0x0000_0000, 0x0100_0000, 0x0080_0000, true),
+    // Port série de la carte son - juste avant la carte de protection
+    WindowSpec::new(MemoryRegion::SoundLatch, SOUND_LATCH_BASE, SOUND_LATCH_SIZE, SOUND_LATCH_SIZE, true),
+    // Carte de protection - quelques registres après le miroir de la RAM
+    WindowSpec::new(MemoryRegion::Protection, PROTECTION_BASE, PROTECTION_SIZE, PROTECTION_SIZE, true),
+    // VRAM - 4MB répétés jusqu'à 8MB
+    WindowSpec::new(MemoryRegion::VideoRam, 0x1000_0000, 0x0080_0000, 0x0040_0000, true),
+    // Listes d'affichage - un banc de 64KB, juste après le miroir de la VRAM
+    WindowSpec::new(MemoryRegion::DisplayList, DISPLAY_LIST_BASE, DISPLAY_LIST_BANK_SIZE, DISPLAY_LIST_BANK_SIZE, true),
+    // Fenêtres partagées avec le TGP, puis sa boîte aux lettres
+    WindowSpec::new(
+        MemoryRegion::CoprocessorWindow,
+        COPRO_WINDOW_BASE,
+        COPRO_WINDOW_SIZE * COPRO_WINDOW_COUNT as u32,
+        COPRO_WINDOW_SIZE * COPRO_WINDOW_COUNT as u32,
+        true,
+    ),
+    WindowSpec::new(MemoryRegion::CoprocessorMailbox, COPRO_MAILBOX_BASE, COPRO_MAILBOX_SIZE, COPRO_MAILBOX_SIZE, true),
+    // RAM audio - 512KB
+    WindowSpec::new(MemoryRegion::AudioRam, 0x3000_0000, 0x0008_0000, 0x0008_0000, true),
+];
+
+/// Model 2 : programme 8MB, graphiques 64MB, son 8MB
+const MODEL2_WINDOWS: &[WindowSpec] = &[
+    WindowSpec::new(MemoryRegion::ProgramRom, 0x0200_0000, 0x0080_0000, 0x0080_0000, false),
+    WindowSpec::new(MemoryRegion::GraphicsRom, 0x2000_0000, 0x0400_0000, 0x0400_0000, false),
+    WindowSpec::new(MemoryRegion::AudioRom, 0x3100_0000, 0x0080_0000, 0x0080_0000, false),
+    WindowSpec::new(MemoryRegion::IoRegisters, IO_REGISTERS_BASE, 0x1000, 0x1000, true),
+];
+
+/// Model 2A-CRX : programme 16MB, son 16MB, registres I/O déplacés
+const MODEL2A_WINDOWS: &[WindowSpec] = &[
+    WindowSpec::new(MemoryRegion::ProgramRom, 0x0200_0000, 0x0100_0000, 0x0100_0000, false),
+    WindowSpec::new(MemoryRegion::GraphicsRom, 0x2000_0000, 0x0400_0000, 0x0400_0000, false),
+    WindowSpec::new(MemoryRegion::AudioRom, 0x3100_0000, 0x0100_0000, 0x0100_0000, false),
+    WindowSpec::new(MemoryRegion::IoRegisters, 0xF100_0000, 0x1000, 0x1000, true),
+];
+
+/// Model 2B-CRX : comme la 2A, graphiques 128MB
+const MODEL2B_WINDOWS: &[WindowSpec] = &[
+    WindowSpec::new(MemoryRegion::ProgramRom, 0x0200_0000, 0x0100_0000, 0x0100_0000, false),
+    WindowSpec::new(MemoryRegion::GraphicsRom, 0x2000_0000, 0x0800_0000, 0x0800_0000, false),
+    WindowSpec::new(MemoryRegion::AudioRom, 0x3100_0000, 0x0100_0000, 0x0100_0000, false),
+    WindowSpec::new(MemoryRegion::IoRegisters, 0xF100_0000, 0x1000, 0x1000, true),
+];
+
+/// Model 2C-CRX : graphiques 128MB, son 32MB, registres I/O déplacés
+const MODEL2C_WINDOWS: &[WindowSpec] = &[
+    WindowSpec::new(MemoryRegion::ProgramRom, 0x0200_0000, 0x0100_0000, 0x0100_0000, false),
+    WindowSpec::new(MemoryRegion::GraphicsRom, 0x2000_0000, 0x0800_0000, 0x0800_0000, false),
+    WindowSpec::new(MemoryRegion::AudioRom, 0x3100_0000, 0x0200_0000, 0x0200_0000, false),
+    WindowSpec::new(MemoryRegion::IoRegisters, 0xF180_0000, 0x1000, 0x1000, true),
+];
+
+/// Révision de la carte Model 2
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoardRevision {
+    #[default]
+    Model2,
+    Model2A,
+    Model2B,
+    Model2C,
+}
+
+impl BoardRevision {
+    /// Révision d'après le nom de carte de la base des jeux (« Model 2 »,
+    /// « Model 2A-CRX »...) ; `None` si le nom n'est pas reconnu
+    pub fn from_board(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_uppercase();
+        let name = name.strip_prefix("MODEL").unwrap_or(&name).trim_start();
+        match name.split('-').next().unwrap_or(name).trim() {
+            "2" => Some(Self::Model2),
+            "2A" => Some(Self::Model2A),
+            "2B" => Some(Self::Model2B),
+            "2C" => Some(Self::Model2C),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Model2 => "Model 2",
+            Self::Model2A => "Model 2A-CRX",
+            Self::Model2B => "Model 2B-CRX",
+            Self::Model2C => "Model 2C-CRX",
+        }
+    }
+
+    /// Fenêtres propres à la révision : ROMs et registres I/O
+    pub fn windows(self) -> &'static [WindowSpec] {
+        match self {
+            Self::Model2 => MODEL2_WINDOWS,
+            Self::Model2A => MODEL2A_WINDOWS,
+            Self::Model2B => MODEL2B_WINDOWS,
+            Self::Model2C => MODEL2C_WINDOWS,
+        }
+    }
+}
+
 /// Table de mapping mémoire complète
 #[derive(Debug)]
 pub struct MemoryMap {
     entries: Vec<MemoryMapEntry>,
+    board: Option<BoardRevision>,
 }
 
 impl MemoryMap {
@@ -89,100 +293,51 @@ impl MemoryMap {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            board: None,
         }
     }
     
-    /// Crée le mapping mémoire standard du SEGA Model 2
+    /// Crée le mapping mémoire de la carte Model 2 d'origine
     pub fn new_model2() -> Self {
+        Self::for_board(BoardRevision::Model2)
+    }
+
+    /// Crée le mapping mémoire d'une révision de carte : fenêtres communes
+    /// puis fenêtres propres à la révision
+    pub fn for_board(board: BoardRevision) -> Self {
         let mut map = Self::new();
-        
-        // RAM principale - 8MB à partir de 0x00000000
-        // Avec miroirs pour compatibilité
-        map.add_entry(MemoryMapEntry::new(
-            0x00000000, 0x00800000, // 8MB
-            MemoryRegion::MainRam,
-            0,
-            0x00800000, // 8MB réels
-            true
-        ));
-        
-        // Miroir de la RAM principale
-        map.add_entry(MemoryMapEntry::new(
-            0x00800000, 0x01000000, // Miroir 8MB
-            MemoryRegion::MainRam,
-            0,
-            0x00800000, // Taille réelle 8MB
-            true
-        ));
-        
-        // ROM du programme principal - typiquement à 0x02000000
-        map.add_entry(MemoryMapEntry::new(
-            0x02000000, 0x02800000, // 8MB d'espace ROM
-            MemoryRegion::ProgramRom,
-            0,
-            0x00800000, // Taille max 8MB
-            false
-        ));
-        
-        // VRAM - 4MB à partir de 0x10000000
-        map.add_entry(MemoryMapEntry::new(
-            0x10000000, 0x10400000, // 4MB
-            MemoryRegion::VideoRam,
-            0,
-            0x00400000, // 4MB réels
-            true
-        ));
-        
-        // Miroir VRAM
-        map.add_entry(MemoryMapEntry::new(
-            0x10400000, 0x10800000, // Miroir 4MB
-            MemoryRegion::VideoRam,
-            0,
-            0x00400000, // Taille réelle 4MB
-            true
-        ));
-        
-        // ROM graphiques - typiquement à 0x20000000
-        map.add_entry(MemoryMapEntry::new(
-            0x20000000, 0x24000000, // 64MB d'espace pour les ROMs graphiques
-            MemoryRegion::GraphicsRom,
-            0,
-            0x04000000, // Taille max 64MB
-            false
-        ));
-        
-        // RAM audio - 512KB à partir de 0x30000000
-        map.add_entry(MemoryMapEntry::new(
-            0x30000000, 0x30080000, // 512KB
-            MemoryRegion::AudioRam,
-            0,
-            0x00080000, // 512KB réels
-            true
-        ));
-        
-        // ROM audio - typiquement à 0x31000000
-        map.add_entry(MemoryMapEntry::new(
-            0x31000000, 0x31800000, // 8MB d'espace pour ROM audio
-            MemoryRegion::AudioRom,
-            0,
-            0x00800000, // Taille max 8MB
-            false
-        ));
-        
-        // Registres I/O - zone haute de la mémoire
-        map.add_entry(MemoryMapEntry::new(
-            0xF0000000, 0xF0001000, // 4KB de registres
-            MemoryRegion::IoRegisters,
-            0,
-            0x00001000, // 4KB
-            true
-        ));
-        
-        // Trier les entrées par adresse de début pour optimiser la recherche
-        map.entries.sort_by_key(|entry| entry.start);
-        
+        for window in COMMON_WINDOWS.iter().chain(board.windows()) {
+            map.add_entry(window.entry());
+        }
+        map.board = Some(board);
         map
     }
+
+    /// Révision de carte de la disposition, `None` pour un mapping construit
+    /// à la main
+    pub fn board(&self) -> Option<BoardRevision> {
+        self.board
+    }
+
+    /// Entrée d'une région (la première si la région est mappée plusieurs fois)
+    pub fn window(&self, region: MemoryRegion) -> Option<&MemoryMapEntry> {
+        self.entries.iter().find(|entry| entry.region == region)
+    }
+
+    /// Adresse des registres I/O
+    pub fn io_base(&self) -> Option<u32> {
+        self.window(MemoryRegion::IoRegisters).map(|entry| entry.start)
+    }
+
+    /// Vérifie qu'une ROM de `size` octets chargée à `offset` dans la fenêtre
+    /// de `region` y tient
+    pub fn check_rom_bank(&self, name: &str, region: MemoryRegion, offset: u32, size: usize) -> Result<()> {
+        let window = self.window(region).map_or(0, |entry| entry.end - entry.start);
+        if offset as u64 + size as u64 > window as u64 {
+            return Err(RomError::WindowOverflow { name: name.to_string(), region, offset, size, window }.into());
+        }
+        Ok(())
+    }
     
     /// Ajoute une entrée au mapping
     pub fn add_entry(&mut self, entry: MemoryMapEntry) {
@@ -191,10 +346,10 @@ impl MemoryMap {
         self.entries.sort_by_key(|entry| entry.start);
     }
     
-    /// Résout une adresse vers sa région et son offset local
-    pub fn resolve(&self, address: u32) -> Option<(MemoryRegion, u32)> {
+    /// Entrée contenant une adresse
+    pub fn resolve_entry(&self, address: u32) -> Option<&MemoryMapEntry> {
         // Recherche binaire pour optimiser la performance
-        match self.entries.binary_search_by(|entry| {
+        self.entries.binary_search_by(|entry| {
             if address < entry.start {
                 std::cmp::Ordering::Greater
             } else if address >= entry.end {
@@ -202,28 +357,49 @@ impl MemoryMap {
             } else {
                 std::cmp::Ordering::Equal
             }
-        }) {
-            Ok(index) => {
-                let entry = &self.entries[index];
-                entry.to_local_offset(address)
-                    .map(|offset| (entry.region, offset))
-            },
-            Err(_) => None,
+        }).ok().map(|index| &self.entries[index])
+    }
+
+    /// Résout une adresse vers sa région et son offset local
+    pub fn resolve(&self, address: u32) -> Option<(MemoryRegion, u32)> {
+        let entry = self.resolve_entry(address)?;
+        entry.to_local_offset(address)
+            .map(|offset| (entry.region, offset))
+    }
+
+    /// Résout la destination d'une écriture en tenant compte de la protection
+    pub fn resolve_write(&self, address: u32) -> WriteTarget {
+        match self.resolve_entry(address) {
+            Some(entry) if entry.write_protected => WriteTarget::Protected,
+            Some(entry) => entry.to_local_offset(address)
+                .map_or(WriteTarget::Unmapped, |offset| WriteTarget::Mapped(entry.region, offset)),
+            None => WriteTarget::Unmapped,
         }
     }
     
     /// Vérifie si une adresse est accessible en écriture
     pub fn is_writable(&self, address: u32) -> bool {
-        self.entries.iter()
-            .find(|entry| entry.contains(address))
-            .map(|entry| entry.writable)
+        self.resolve_entry(address)
+            .map(|entry| entry.writable && !entry.write_protected)
             .unwrap_or(false)
     }
+
+    /// Active ou lève la protection en écriture de l'entrée contenant `address`.
+    ///
+    /// Retourne `false` si aucune entrée ne contient cette adresse.
+    pub fn set_write_protect(&mut self, address: u32, protected: bool) -> bool {
+        match self.entries.iter_mut().find(|entry| entry.contains(address)) {
+            Some(entry) => {
+                entry.write_protected = protected;
+                true
+            },
+            None => false,
+        }
+    }
     
     /// Obtient des informations sur une région mémoire
     pub fn get_region_info(&self, address: u32) -> Option<&MemoryMapEntry> {
-        self.entries.iter()
-            .find(|entry| entry.contains(address))
+        self.resolve_entry(address)
     }
     
     /// Liste toutes les régions mappées
@@ -238,4 +414,66 @@ impl Default for MemoryMap {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_ram_mirror_resolves_to_same_offset() {
+        let map = MemoryMap::new_model2();
+        assert_eq!(map.resolve(0x0000_1234), Some((MemoryRegion::MainRam, 0x1234)));
+        assert_eq!(map.resolve(0x0080_1234), Some((MemoryRegion::MainRam, 0x1234)));
+        assert_eq!(map.resolve(0x1040_0010), Some((MemoryRegion::VideoRam, 0x10)));
+    }
+
+    #[test]
+    fn test_partial_address_decoding() {
+        // 64KB décodés sur une fenêtre de 1MB : les bits 16-19 sont ignorés
+        let mut map = MemoryMap::new();
+        map.add_entry(MemoryMapEntry::mirrored(0x4000_0000, 0x4010_0000, MemoryRegion::AudioRam, 0x1_0000, true));
+
+        assert_eq!(map.resolve(0x4003_0042), Some((MemoryRegion::AudioRam, 0x42)));
+        assert_eq!(map.resolve(0x4010_0000), None);
+    }
+
+    #[test]
+    fn test_write_protect() {
+        let mut map = MemoryMap::new_model2();
+        assert_eq!(map.resolve_write(0x100), WriteTarget::Mapped(MemoryRegion::MainRam, 0x100));
+
+        assert!(map.set_write_protect(0x100, true));
+        assert_eq!(map.resolve_write(0x0080_0100), WriteTarget::Protected);
+        assert!(!map.is_writable(0x100));
+
+        // Les lectures ne sont pas affectées
+        assert_eq!(map.resolve(0x100), Some((MemoryRegion::MainRam, 0x100)));
+        assert_eq!(map.resolve_write(0x0500_0000), WriteTarget::Unmapped);
+        assert!(!map.set_write_protect(0x0500_0000, true));
+    }
+
+    #[test]
+    fn test_board_layouts_differ_in_rom_windows_and_io_base() {
+        assert_eq!(BoardRevision::from_board("Model 2"), Some(BoardRevision::Model2));
+        assert_eq!(BoardRevision::from_board("Model 2A-CRX"), Some(BoardRevision::Model2A));
+        assert_eq!(BoardRevision::from_board("model 2c"), Some(BoardRevision::Model2C));
+        assert_eq!(BoardRevision::from_board("Model 3"), None);
+
+        let model2 = MemoryMap::new_model2();
+        let model2b = MemoryMap::for_board(BoardRevision::Model2B);
+        assert_eq!(model2.board(), Some(BoardRevision::Model2));
+        assert_eq!(model2.io_base(), Some(IO_REGISTERS_BASE));
+        assert_eq!(model2b.io_base(), Some(0xF100_0000));
+        assert_eq!(model2.resolve(0x2500_0000), None);
+        assert_eq!(model2b.resolve(0x2500_0000), Some((MemoryRegion::GraphicsRom, 0x0500_0000)));
+        // Fenêtres communes identiques
+        assert_eq!(model2b.resolve(0x0080_1234), Some((MemoryRegion::MainRam, 0x1234)));
+
+        // Banques de ROM : 12MB de programme ne tiennent que sur une 2A et après
+        assert!(model2.check_rom_bank("prog", MemoryRegion::ProgramRom, 0x0040_0000, 0x0040_0000).is_ok());
+        assert!(model2.check_rom_bank("prog", MemoryRegion::ProgramRom, 0x0040_0000, 0x0080_0000).is_err());
+        assert!(model2b.check_rom_bank("prog", MemoryRegion::ProgramRom, 0x0040_0000, 0x0080_0000).is_ok());
+        assert!(MemoryMap::new().check_rom_bank("prog", MemoryRegion::ProgramRom, 0, 1).is_err());
+    }
+}
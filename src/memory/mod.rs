@@ -5,39 +5,102 @@
 //! - VRAM (4MB) 
 //! - RAM audio (512KB)
 //! - Zones ROM
-//! - Registres I/O
+//! - Registres I/O (interruptions, timers, interface GPU, port d'entrée)
+//! - Carte de protection
+//! - Horloge temps réel de la carte I/O
+//! - Carte de pilotage (retour de force des jeux de course)
+//! - Lampes de la borne
+//! - Fenêtres partagées avec le coprocesseur géométrique
 
 pub mod interface;
+pub mod endian;
 pub mod mapping;
 pub mod ram;
 pub mod rom;
+pub mod open_bus;
+pub mod protection;
+pub mod sound_latch;
+pub mod rtc;
+pub mod drive_board;
+pub mod lamps;
+pub mod display_list;
+pub mod coprocessor;
+pub mod layers;
+pub mod hacks;
+pub mod watch;
+pub mod heatmap;
+pub mod interrupts;
+pub mod timers;
+pub mod gpu_interface;
+pub mod input_port;
 
-use anyhow::{Result, anyhow};
+use crate::error::{MemoryFault, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::cell::RefCell;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 
 pub use interface::*;
+pub use endian::*;
 pub use mapping::*;
 pub use ram::*;
 pub use rom::*;
+pub use open_bus::*;
+pub use protection::*;
+pub use sound_latch::*;
+pub use rtc::*;
+pub use drive_board::*;
+pub use lamps::*;
+pub use display_list::*;
+pub use coprocessor::*;
+pub use layers::*;
+pub use hacks::*;
+pub use watch::*;
+pub use heatmap::*;
+pub use interrupts::*;
+pub use timers::*;
+pub use gpu_interface::*;
+pub use input_port::*;
 
 // Import du système audio SCSP
 // use crate::audio::ScspAudio;
 
 /// Buffer de commandes GPU pour traitement par lots
+///
+/// Les tris et la déduplication passent par des tampons conservés d'un lot
+/// à l'autre : une fois leur capacité atteinte, le vidage d'un lot n'alloue
+/// plus.
 #[derive(Debug)]
 pub struct GpuCommandBuffer {
     /// Commandes en attente de traitement
     commands: Vec<GpuCommand>,
+
+    /// Lots optimisés lorsque le buffer était plein, rendus au prochain vidage
+    ready: Vec<GpuCommand>,
     
     /// Capacité maximale du buffer
     max_capacity: usize,
+
+    /// Tampons de tri réutilisés
+    scratch: CommandScratch,
     
     /// Statistiques de performance
     stats: CommandBufferStats,
 }
 
-#[derive(Debug, Clone)]
+/// Tampons de l'optimisation des lots
+#[derive(Debug, Default)]
+struct CommandScratch {
+    state: Vec<GpuCommand>,
+    texture: Vec<GpuCommand>,
+    draw: Vec<GpuCommand>,
+    other: Vec<GpuCommand>,
+    seen_matrices: std::collections::HashSet<(u32, u32, u32, u32, u32)>,
+    seen_render_states: std::collections::HashSet<(u32, u32)>,
+    seen_textures: std::collections::HashSet<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CommandBufferStats {
     pub total_commands_processed: u64,
     pub batches_processed: u64,
@@ -50,7 +113,9 @@ impl GpuCommandBuffer {
     pub fn new() -> Self {
         Self {
             commands: Vec::new(),
+            ready: Vec::new(),
             max_capacity: 1024, // Capacité par défaut
+            scratch: CommandScratch::default(),
             stats: CommandBufferStats {
                 total_commands_processed: 0,
                 batches_processed: 0,
@@ -64,117 +129,118 @@ impl GpuCommandBuffer {
     pub fn push(&mut self, command: GpuCommand) {
         self.commands.push(command);
         
-        // Si le buffer est plein, traiter automatiquement
+        // Si le buffer est plein, optimiser le lot en attendant le vidage
         if self.commands.len() >= self.max_capacity {
-            self.flush();
+            let mut ready = std::mem::take(&mut self.ready);
+            self.flush_into(&mut ready);
+            self.ready = ready;
         }
     }
     
     /// Vide le buffer et retourne les commandes triées
     pub fn flush(&mut self) -> Vec<GpuCommand> {
+        let mut commands = Vec::new();
+        self.flush_into(&mut commands);
+        commands
+    }
+
+    /// Vide le buffer en ajoutant les commandes triées à `out`, sans
+    /// allocation une fois les tampons dimensionnés ; renvoie le nombre de
+    /// commandes ajoutées
+    pub fn flush_into(&mut self, out: &mut Vec<GpuCommand>) -> usize {
+        let before = out.len();
+        out.append(&mut self.ready);
         if self.commands.is_empty() {
-            return Vec::new();
+            return out.len() - before;
         }
         
-        // Prendre possession des commandes
-        let commands = std::mem::take(&mut self.commands);
-        
         // Trier les commandes pour optimisation
-        let sorted_commands = self.optimize_commands(commands);
+        let batch_size = self.optimize_commands(out);
         
         // Mettre à jour les statistiques
-        let batch_size = sorted_commands.len();
         self.stats.total_commands_processed += batch_size as u64;
         self.stats.batches_processed += 1;
         self.stats.max_batch_size = self.stats.max_batch_size.max(batch_size);
         self.stats.average_batch_size = self.stats.total_commands_processed as f32 / self.stats.batches_processed as f32;
         
-        sorted_commands
+        out.len() - before
     }
     
-    /// Optimise l'ordre des commandes pour de meilleures performances
-    fn optimize_commands(&self, mut commands: Vec<GpuCommand>) -> Vec<GpuCommand> {
+    /// Optimise l'ordre des commandes en attente pour de meilleures
+    /// performances et les ajoute à `out` ; renvoie la taille du lot
+    fn optimize_commands(&mut self, out: &mut Vec<GpuCommand>) -> usize {
         // Stratégie d'optimisation avancée :
         // 1. Éliminer les commandes redondantes (mêmes matrices, mêmes états)
         // 2. Grouper les changements d'état
         // 3. Grouper les chargements de textures
         // 4. Grouper les appels de dessin
+        let scratch = &mut self.scratch;
+        scratch.seen_matrices.clear();
+        scratch.seen_render_states.clear();
+        scratch.seen_textures.clear();
         
-        let mut optimized = Vec::with_capacity(commands.len());
-        
-        // Extraire et dédupliquer les commandes par type
-        let mut state_commands = Vec::new();
-        let mut texture_commands = Vec::new();
-        let mut draw_commands = Vec::new();
-        let mut other_commands = Vec::new();
-        
-        // Maps pour éviter les doublons
-        let mut seen_matrices = std::collections::HashSet::new();
-        let mut seen_render_states = std::collections::HashSet::new();
-        let mut seen_textures = std::collections::HashSet::new();
-        
-        for command in commands {
+        for command in self.commands.drain(..) {
             match &command {
                 GpuCommand::SetModelMatrix(matrix) => {
                     let key = (0, matrix[0] as u32, matrix[1] as u32, matrix[2] as u32, matrix[3] as u32);
-                    if seen_matrices.insert(key) {
-                        state_commands.push(command);
+                    if scratch.seen_matrices.insert(key) {
+                        scratch.state.push(command);
                     }
                 },
                 GpuCommand::SetViewMatrix(matrix) => {
                     let key = (1, matrix[0] as u32, matrix[1] as u32, matrix[2] as u32, matrix[3] as u32);
-                    if seen_matrices.insert(key) {
-                        state_commands.push(command);
+                    if scratch.seen_matrices.insert(key) {
+                        scratch.state.push(command);
                     }
                 },
                 GpuCommand::SetProjectionMatrix(matrix) => {
                     let key = (2, matrix[0] as u32, matrix[1] as u32, matrix[2] as u32, matrix[3] as u32);
-                    if seen_matrices.insert(key) {
-                        state_commands.push(command);
+                    if scratch.seen_matrices.insert(key) {
+                        scratch.state.push(command);
                     }
                 },
                 GpuCommand::SetRenderState { state, enabled } => {
                     let key = (*state as u32, *enabled as u32);
-                    if seen_render_states.insert(key) {
-                        state_commands.push(command);
+                    if scratch.seen_render_states.insert(key) {
+                        scratch.state.push(command);
                     }
                 },
                 GpuCommand::LoadTexture { id, .. } => {
-                    if seen_textures.insert(*id) {
-                        texture_commands.push(command);
+                    if scratch.seen_textures.insert(*id) {
+                        scratch.texture.push(command);
                     }
                 },
                 GpuCommand::LoadTextureFromRom { id, .. } => {
-                    if seen_textures.insert(*id) {
-                        texture_commands.push(command);
+                    if scratch.seen_textures.insert(*id) {
+                        scratch.texture.push(command);
                     }
                 },
                 GpuCommand::DrawTriangle { .. } | 
                 GpuCommand::DrawQuad { .. } |
                 GpuCommand::DrawLine { .. } => {
-                    draw_commands.push(command);
+                    scratch.draw.push(command);
                 },
-                _ => other_commands.push(command),
+                _ => scratch.other.push(command),
             }
         }
         
         // Réorganiser : état -> textures -> autres -> dessin
-        optimized.extend(state_commands);
-        optimized.extend(texture_commands);
-        optimized.extend(other_commands);
-        optimized.extend(draw_commands);
-        
-        optimized
+        let before = out.len();
+        out.append(&mut scratch.state);
+        out.append(&mut scratch.texture);
+        out.append(&mut scratch.other);
+        out.append(&mut scratch.draw);
+        out.len() - before
     }
     
     /// Retourne le nombre de commandes en attente
     pub fn len(&self) -> usize {
-        self.commands.len()
+        self.commands.len() + self.ready.len()
     }
     
     /// Vérifie si le buffer est vide
     pub fn is_empty(&self) -> bool {
-        self.commands.is_empty()
+        self.commands.is_empty() && self.ready.is_empty()
     }
     
     /// Obtient les statistiques du buffer
@@ -185,202 +251,195 @@ impl GpuCommandBuffer {
     /// Vide complètement le buffer sans retourner les commandes
     pub fn clear(&mut self) {
         self.commands.clear();
+        self.ready.clear();
     }
 }
 
+/// Table de brouillard dans les registres I/O : 4 entrées d'un octet par
+/// registre, l'entrée la plus proche dans l'octet de poids faible
+pub const FOG_TABLE_REGISTER: u32 = 0x100;
+
+/// Taille de la table de brouillard en octets
+pub const FOG_TABLE_LENGTH: u32 = 0x100;
+
+const FOG_TABLE_END: u32 = FOG_TABLE_REGISTER + FOG_TABLE_LENGTH;
+
+/// Octets lus à la fois par [`Model2Memory::dump_range`]
+const DUMP_CHUNK_SIZE: usize = 64 * 1024;
+
 /// Registres I/O du SEGA Model 2
-#[derive(Debug, Clone)]
+///
+/// Chaque périphérique de la carte I/O garde ses registres dans sa propre
+/// structure ; celle-ci les compose derrière le bus et aiguille les accès
+/// par offset. Les registres absents d'un état de sauvegarde plus ancien
+/// gardent leur valeur de démarrage. Les champs des périphériques gardent
+/// dans les états de sauvegarde leur nom d'avant le découpage
+/// (`interrupt_status`, `timer_main`...) et restent à plat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct IoRegisters {
-    /// Registre de contrôle des interruptions (0xC0000000)
-    pub interrupt_control: u32,
-    
-    /// Registre de statut des interruptions (0xC0000004)
-    pub interrupt_status: u32,
-    
-    /// Timer principal (0xC0000010)
-    pub timer_main: u32,
-    
-    /// Timer de sous-système (0xC0000014)
-    pub timer_sub: u32,
-    
-    /// Registre de contrôle GPU (0xC0000020)
-    pub gpu_control: u32,
-    
-    /// Registre de statut GPU (0xC0000024)
-    pub gpu_status: u32,
-    
-    /// Registre de commande GPU (0xC0000028)
-    pub gpu_command: u32,
-    
+    /// Contrôleur d'interruptions (0xC0000000..0xC0000008)
+    #[serde(flatten)]
+    pub interrupts: InterruptController,
+
+    /// Timers (0xC0000010..0xC0000018)
+    #[serde(flatten)]
+    pub timers: Timers,
+
+    /// Interface GPU (0xC0000020..0xC0000030)
+    #[serde(flatten)]
+    pub gpu: GpuInterface,
+
     /// Registre de contrôle audio (0xC0000030)
     pub audio_control: u32,
-    
-    /// Registre d'entrée (0xC0000040)
-    pub input_data: u32,
-    
-    /// Registre de contrôle d'entrée (0xC0000044)
-    pub input_control: u32,
-    
-    /// Compteur de cycles CPU pour timing
-    cycle_counter: u64,
+
+    /// Port d'entrée (0xC0000040..0xC0000048)
+    #[serde(flatten)]
+    pub input: InputPort,
+
+    /// Lampes de la borne (0xC0000064), un bit par lampe
+    pub lamp_output: u32,
+
+    /// Priorités, activation des couches et couleur de fond
+    /// (0xC0000050..0xC000005C)
+    pub layers: LayerRegisters,
+
+    /// Table de brouillard (0xC0000100..0xC0000200), vide tant que le jeu
+    /// ne l'a pas écrite
+    pub fog_table: Vec<u8>,
+
+    /// Table modifiée depuis sa dernière transmission au GPU
+    #[serde(skip)]
+    fog_table_dirty: bool,
+
+    /// Timer rechargé depuis le dernier relevé : son débordement est à redater
+    #[serde(skip)]
+    timers_reloaded: bool,
+}
+
+impl Default for IoRegisters {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl IoRegisters {
     pub fn new() -> Self {
         Self {
-            interrupt_control: 0,
-            interrupt_status: 0,
-            timer_main: 0,
-            timer_sub: 0,
-            gpu_control: 0,
-            gpu_status: 0x00000001, // GPU prêt
-            gpu_command: 0,
+            interrupts: InterruptController::new(),
+            timers: Timers::new(),
+            gpu: GpuInterface::new(),
             audio_control: 0,
-            input_data: 0,
-            input_control: 0,
-            cycle_counter: 0,
+            input: InputPort::new(),
+            lamp_output: 0,
+            layers: LayerRegisters::default(),
+            fog_table: Vec::new(),
+            fog_table_dirty: false,
+            timers_reloaded: false,
         }
     }
     
-    /// Lit un registre I/O
+    /// Lit un registre I/O complet (offset aligné sur 4)
     pub fn read_register(&self, offset: u32) -> u32 {
+        let device = self.interrupts.read(offset)
+            .or_else(|| self.timers.read(offset))
+            .or_else(|| self.gpu.read(offset))
+            .or_else(|| self.input.read(offset));
+        if let Some(value) = device {
+            return value;
+        }
+
         match offset {
-            0x00 => self.interrupt_control,
-            0x04 => self.interrupt_status,
-            0x10 => self.timer_main,
-            0x14 => self.timer_sub,
-            0x20 => self.gpu_control,
-            0x24 => self.gpu_status,
-            0x28 => self.gpu_command,
             0x30 => self.audio_control,
-            0x40 => self.input_data,
-            0x44 => self.input_control,
+            LAMP_OUTPUT_REGISTER => self.lamp_output,
+            LAYER_PRIORITY_REGISTER => self.layers.priority,
+            LAYER_ENABLE_REGISTER => self.layers.enable,
+            BACKDROP_COLOR_REGISTER => self.layers.backdrop,
+            FOG_TABLE_REGISTER..FOG_TABLE_END => {
+                let index = (offset - FOG_TABLE_REGISTER) as usize;
+                self.fog_table
+                    .get(index..index + 4)
+                    .map_or(0, |entries| ByteOrder::Little.read_u32(entries, 0).unwrap_or_default())
+            }
             _ => 0x00000000,
         }
     }
     
-    /// Écrit dans un registre I/O
+    /// Écrit un registre I/O complet (offset aligné sur 4)
     pub fn write_register(&mut self, offset: u32, value: u32) -> Option<GpuCommand> {
+        if let Some(command) = self.gpu.write(offset, value) {
+            return command;
+        }
+        if self.timers.write(offset, value) {
+            self.timers_reloaded = true;
+            return None;
+        }
+        if self.interrupts.write(offset, value) || self.input.write(offset, value) {
+            return None;
+        }
+
         match offset {
-            0x00 => self.interrupt_control = value,
-            0x04 => self.interrupt_status = value,
-            0x10 => self.timer_main = value,
-            0x14 => self.timer_sub = value,
-            0x20 => self.gpu_control = value,
-            0x24 => self.gpu_status = value,
-            0x28 => {
-                self.gpu_command = value;
-                // Pour l'instant, traiter les commandes GPU simples
-                // TODO: Implémenter un système de commandes plus sophistiqué
-                return Some(self.decode_gpu_command(value));
-            },
             0x30 => self.audio_control = value,
-            0x40 => self.input_data = value,
-            0x44 => self.input_control = value,
+            LAMP_OUTPUT_REGISTER => self.lamp_output = value,
+            LAYER_PRIORITY_REGISTER => self.layers.priority = value,
+            LAYER_ENABLE_REGISTER => self.layers.enable = value,
+            BACKDROP_COLOR_REGISTER => self.layers.backdrop = value & 0x00FF_FFFF,
+            FOG_TABLE_REGISTER..FOG_TABLE_END => {
+                if self.fog_table.is_empty() {
+                    self.fog_table = vec![0; FOG_TABLE_LENGTH as usize];
+                }
+                let index = (offset - FOG_TABLE_REGISTER) as usize;
+                ByteOrder::Little.write_u32(&mut self.fog_table, index, value);
+                self.fog_table_dirty = true;
+            }
             _ => {} // Ignorer les registres inconnus
         }
         None
     }
     
-    /// Décode une commande GPU (version étendue)
-    fn decode_gpu_command(&self, command: u32) -> GpuCommand {
-        // Extraire le type de commande des bits de poids fort
-        let cmd_type = (command >> 24) & 0xFF;
-        
-        match cmd_type {
-            0x00 => {
-                // Clear screen - commande simple
-                let r = ((command >> 16) & 0xFF) as f32 / 255.0;
-                let g = ((command >> 8) & 0xFF) as f32 / 255.0;
-                let b = (command & 0xFF) as f32 / 255.0;
-                let a = 1.0; // Alpha par défaut
-                GpuCommand::ClearScreen { 
-                    color: [r, g, b, a], 
-                    depth: 1.0, 
-                    stencil: 0 
-                }
-            },
-            0x01 => {
-                // Set render state
-                let state_bits = (command >> 16) & 0xFF;
-                let enabled = (command & 0x01) != 0;
-                let state_type = match state_bits {
-                    0x01 => RenderStateType::ZBuffer,
-                    0x02 => RenderStateType::Texturing,
-                    0x04 => RenderStateType::Lighting,
-                    0x08 => RenderStateType::Transparency,
-                    0x10 => RenderStateType::AlphaTest,
-                    0x20 => RenderStateType::Fog,
-                    0x40 => RenderStateType::Wireframe,
-                    0x80 => RenderStateType::BackfaceCulling,
-                    _ => RenderStateType::ZBuffer, // Défaut
-                };
-                GpuCommand::SetRenderState { state: state_type, enabled }
-            },
-            0x02 => {
-                // Load texture (placeholder - nécessiterait plus de données)
-                GpuCommand::LoadTexture { 
-                    id: (command >> 16) & 0xFF, 
-                    data: vec![], // Données vides pour l'instant
-                    width: 64, 
-                    height: 64 
-                }
-            },
-            0x10 => {
-                // Set model matrix (placeholder - nécessiterait lecture de données supplémentaires)
-                GpuCommand::SetModelMatrix([
-                    1.0, 0.0, 0.0, 0.0,
-                    0.0, 1.0, 0.0, 0.0,
-                    0.0, 0.0, 1.0, 0.0,
-                    0.0, 0.0, 0.0, 1.0,
-                ])
-            },
-            0x11 => {
-                // Set view matrix (placeholder)
-                GpuCommand::SetViewMatrix([
-                    1.0, 0.0, 0.0, 0.0,
-                    0.0, 1.0, 0.0, 0.0,
-                    0.0, 0.0, 1.0, 0.0,
-                    0.0, 0.0, -2.0, 1.0,
-                ])
-            },
-            0x12 => {
-                // Set projection matrix (placeholder)
-                GpuCommand::SetProjectionMatrix([
-                    1.0, 0.0, 0.0, 0.0,
-                    0.0, 1.0, 0.0, 0.0,
-                    0.0, 0.0, 1.0, 0.0,
-                    0.0, 0.0, 0.0, 1.0,
-                ])
-            },
-            _ => {
-                // Commande inconnue - utiliser clear screen par défaut
-                println!("GPU: Commande inconnue {:08X}, utilisation de ClearScreen par défaut", command);
-                GpuCommand::ClearScreen { 
-                    color: [0.0, 0.0, 0.0, 1.0], 
-                    depth: 1.0, 
-                    stencil: 0 
-                }
-            }
-        }
+    /// Écrit `size` octets (1, 2 ou 4) à un offset quelconque : les autres
+    /// octets du registre sont conservés (lecture-modification-écriture).
+    /// Le registre de commande GPU n'émet sa commande que lorsque l'écriture
+    /// atteint l'octet de poids fort, qui porte le type de commande.
+    pub fn write_sized(&mut self, offset: u32, value: u32, size: u32) -> Option<GpuCommand> {
+        let aligned = offset & !3;
+        let (shift, mask) = byte_lane(offset, size);
+        let merged = (self.read_register(aligned) & !(mask << shift)) | (value & mask) << shift;
+
+        let command = self.write_register(aligned, merged);
+        command.filter(|_| shift + size * 8 == 32)
+    }
+
+    /// Remplace toute la table de brouillard (vide : pas de table)
+    pub fn load_fog_table(&mut self, table: &[u8]) {
+        self.fog_table = table.iter().copied().take(FOG_TABLE_LENGTH as usize).collect();
+        self.fog_table_dirty = true;
+    }
+
+    /// Table de brouillard modifiée depuis le dernier appel
+    pub fn take_fog_table_update(&mut self) -> Option<&[u8]> {
+        std::mem::take(&mut self.fog_table_dirty).then_some(self.fog_table.as_slice())
+    }
+
+    /// Mots de commande GPU non reconnus depuis le dernier appel
+    pub fn take_unknown_gpu_commands(&mut self) -> u32 {
+        self.gpu.take_unknown_commands()
     }
     
-    /// Met à jour les timers et autres registres périodiques
-    pub fn update(&mut self, cycles: u32, cpu: &mut crate::cpu::NecV60) {
-        self.cycle_counter = self.cycle_counter.wrapping_add(cycles as u64);
-        
-        // Mise à jour des timers (simplifiée)
-        self.timer_main = self.timer_main.wrapping_add(cycles);
-        self.timer_sub = self.timer_sub.wrapping_add(cycles / 4); // Timer plus lent
-        
-        // Générer des interruptions périodiques (VBLANK à ~60Hz)
-        if self.cycle_counter % (25_000_000 / 60) == 0 {
-            self.interrupt_status |= 0x00000001; // VBLANK interrupt
-            cpu.queue_interrupt(crate::cpu::Interrupt::VBlank);
-        }
+    /// Signale le VBlank, daté par l'ordonnanceur du cœur
+    pub fn raise_vblank(&mut self, cpu: &mut crate::cpu::NecV60) {
+        self.interrupts.raise_vblank(cpu);
     }
 }
 
+/// Décalage et masque de l'accès de `size` octets à `offset` dans son mot
+/// de 32 bits (petit-boutiste : l'octet de l'offset 0 est le poids faible)
+pub fn byte_lane(offset: u32, size: u32) -> (u32, u32) {
+    let shift = (offset & 3) * 8;
+    let mask = if size >= 4 { u32::MAX } else { (1u32 << (size * 8)) - 1 };
+    (shift, mask)
+}
+
 /// Types de commandes GPU pour SEGA Model 2
 #[derive(Debug, Clone)]
 pub enum GpuCommand {
@@ -402,11 +461,13 @@ pub enum GpuCommand {
     /// Charge une texture depuis la ROM
     LoadTextureFromRom { id: u32, rom_offset: u32, width: u32, height: u32, format: TextureFormat },
     
-    /// Dessine un triangle texturé
-    DrawTriangle { vertices: [GpuVertex; 3], texture_id: Option<u32> },
+    /// Dessine un triangle texturé ; `texture_attributes` est le mot
+    /// d'attributs de texture de l'en-tête du polygone
+    /// (voir [`crate::gpu::geometry::texture_attributes`])
+    DrawTriangle { vertices: [GpuVertex; 3], texture_id: Option<u32>, texture_attributes: u32 },
     
     /// Dessine un quad texturé
-    DrawQuad { vertices: [GpuVertex; 4], texture_id: Option<u32> },
+    DrawQuad { vertices: [GpuVertex; 4], texture_id: Option<u32>, texture_attributes: u32 },
     
     /// Dessine une ligne
     DrawLine { start: GpuVertex, end: GpuVertex },
@@ -457,6 +518,38 @@ pub enum GpuCommand {
     SetGeometryParams { scale: [f32; 3], rotation: [f32; 3], translation: [f32; 3] },
 }
 
+impl GpuCommand {
+    /// Nom de la commande, sans ses paramètres
+    pub fn name(&self) -> &'static str {
+        match self {
+            GpuCommand::SetModelMatrix { .. } => "SetModelMatrix",
+            GpuCommand::SetViewMatrix { .. } => "SetViewMatrix",
+            GpuCommand::SetProjectionMatrix { .. } => "SetProjectionMatrix",
+            GpuCommand::SetTextureMatrix { .. } => "SetTextureMatrix",
+            GpuCommand::LoadTexture { .. } => "LoadTexture",
+            GpuCommand::LoadTextureFromRom { .. } => "LoadTextureFromRom",
+            GpuCommand::DrawTriangle { .. } => "DrawTriangle",
+            GpuCommand::DrawQuad { .. } => "DrawQuad",
+            GpuCommand::DrawLine { .. } => "DrawLine",
+            GpuCommand::SetRenderState { .. } => "SetRenderState",
+            GpuCommand::SetLighting { .. } => "SetLighting",
+            GpuCommand::SetFog { .. } => "SetFog",
+            GpuCommand::SetViewport { .. } => "SetViewport",
+            GpuCommand::SetClipPlanes { .. } => "SetClipPlanes",
+            GpuCommand::ClearScreen { .. } => "ClearScreen",
+            GpuCommand::SetBlendMode { .. } => "SetBlendMode",
+            GpuCommand::SetDepthTest { .. } => "SetDepthTest",
+            GpuCommand::SetCulling { .. } => "SetCulling",
+            GpuCommand::SetAmbientColor { .. } => "SetAmbientColor",
+            GpuCommand::SetTextureEnvironment { .. } => "SetTextureEnvironment",
+            GpuCommand::BeginDisplayList { .. } => "BeginDisplayList",
+            GpuCommand::EndDisplayList { .. } => "EndDisplayList",
+            GpuCommand::ExecuteDisplayList { .. } => "ExecuteDisplayList",
+            GpuCommand::SetGeometryParams { .. } => "SetGeometryParams",
+        }
+    }
+}
+
 /// Formats de texture supportés par SEGA Model 2
 #[derive(Debug, Clone, Copy)]
 pub enum TextureFormat {
@@ -576,14 +669,51 @@ pub struct Model2Memory {
     /// ROMs chargées
     pub roms: HashMap<String, Rom>,
     
-    /// Cache des accès mémoire pour optimisation
-    cache: RefCell<MemoryCache>,
-    
-    /// Activation du cache
-    cache_enabled: bool,
+    /// Comportement des zones sans périphérique
+    open_bus: OpenBusPolicy,
+
+    /// Dernière valeur transférée sur le bus de données
+    bus_latch: AtomicU32,
 
     /// Registres I/O
     io_registers: IoRegisters,
+
+    /// Puce de protection du jeu, si la base des jeux en décrit une ; les
+    /// lectures font avancer sa réponse
+    protection: Option<ProtectionDevice>,
+
+    /// Port série vers la carte son ; les lectures consomment les réponses
+    /// du 68000
+    sound_latch: SoundLatch,
+
+    /// Horloge temps réel, dans les registres I/O ; alimentée par pile, elle
+    /// survit au reset
+    rtc: RtcDevice,
+
+    /// Carte de pilotage, sur la sortie de la carte I/O
+    drive_board: DriveBoard,
+
+    /// Bancs des listes d'affichage ; le CPU accède au banc arrière
+    display_lists: DisplayListBanks,
+
+    /// Fenêtres partagées et boîte aux lettres du TGP ; les lectures de la
+    /// boîte aux lettres consomment la réponse
+    coprocessor: CoprocessorLink,
+
+    /// Contournements actifs pour le jeu chargé
+    hacks: GameHacks,
+
+    /// Outils prévenus des accès du CPU (carte de chaleur, télémétrie)
+    access_observers: Vec<Arc<dyn AccessObserver>>,
+
+    /// Table de brouillard tirée des données du jeu, rechargée à chaque reset
+    fog_table_preset: Vec<u8>,
+
+    /// Ligne en cours de balayage
+    scanline: u32,
+
+    /// Registres de composition de l'image en cours, ligne par ligne
+    scanline_log: ScanlineLog,
     
     /// Système audio SCSP
     // pub scsp_audio: ScspAudio,
@@ -604,9 +734,20 @@ impl Model2Memory {
             audio_ram: Ram::new(512 * 1024), // 512KB
             mapping: MemoryMap::new_model2(),
             roms: HashMap::new(),
-            cache: RefCell::new(MemoryCache::new()),
-            cache_enabled: true,
+            open_bus: OpenBusPolicy::default(),
+            bus_latch: AtomicU32::new(0xFFFF_FFFF),
             io_registers: IoRegisters::new(),
+            protection: None,
+            sound_latch: SoundLatch::new(),
+            rtc: RtcDevice::new(),
+            drive_board: DriveBoard::new(),
+            display_lists: DisplayListBanks::new(),
+            coprocessor: CoprocessorLink::new(),
+            hacks: GameHacks::default(),
+            access_observers: Vec::new(),
+            fog_table_preset: Vec::new(),
+            scanline: 0,
+            scanline_log: ScanlineLog::default(),
             // scsp_audio: ScspAudio::new().unwrap_or_else(|_| {
             //     eprintln!("Warning: Failed to initialize SCSP audio, using default");
             //     ScspAudio::default()
@@ -623,19 +764,477 @@ impl Model2Memory {
         Ok(())
     }
     
-    /// Vide le cache mémoire
-    pub fn clear_cache(&mut self) {
-        if let Ok(mut cache) = self.cache.try_borrow_mut() {
-            cache.clear();
+    /// Remplace la disposition mémoire par celle d'une révision de carte ;
+    /// le contenu des RAMs est conservé
+    pub fn set_board(&mut self, board: BoardRevision) {
+        if self.mapping.board() != Some(board) {
+            log::info!("Disposition mémoire {}", board.name());
+            self.mapping = MemoryMap::for_board(board);
         }
     }
     
-    /// Met à jour les registres I/O (appelé périodiquement)
-    pub fn update_io_registers(&mut self, cycles: u32, cpu: &mut crate::cpu::NecV60) {
-        self.io_registers.update(cycles, cpu);
-        // self.scsp_audio.update(cycles);
+    /// Remplace le comportement des zones non mappées
+    pub fn set_open_bus(&mut self, policy: OpenBusPolicy) {
+        self.open_bus = policy;
+    }
+
+    /// Comportement courant des zones non mappées
+    pub fn open_bus(&self) -> &OpenBusPolicy {
+        &self.open_bus
+    }
+
+    /// Lecture sans périphérique
+    fn open_bus_read(&self, address: u32, size: u8) -> Result<u32> {
+        self.open_bus.read(address, size, self.bus_latch.load(Ordering::Relaxed))
+    }
+
+    /// Active les contournements du jeu et écrit ses correctifs de démarrage
+    pub fn set_hacks(&mut self, hacks: GameHacks) -> Result<()> {
+        if !hacks.is_empty() {
+            log::info!("Hacks actifs: {}", hacks.names().join(", "));
+        }
+        for &(offset, value) in hacks.register_patches() {
+            log::info!("Hack: écriture de 0x{:08X} dans le registre I/O 0x{:02X} au démarrage", value, offset);
+            self.write_io_register(offset, value, 4);
+        }
+        self.hacks = hacks;
+        Ok(())
+    }
+
+    /// Contournements actifs
+    pub fn hacks(&self) -> &GameHacks {
+        &self.hacks
+    }
+
+    /// Branche un outil sur les accès du CPU
+    pub fn add_access_observer(&mut self, observer: Arc<dyn AccessObserver>) {
+        self.access_observers.push(observer);
+    }
+
+    /// Débranche un outil ; vrai s'il était branché
+    pub fn remove_access_observer(&mut self, observer: &Arc<dyn AccessObserver>) -> bool {
+        let count = self.access_observers.len();
+        self.access_observers.retain(|other| !Arc::ptr_eq(other, observer));
+        self.access_observers.len() != count
+    }
+
+    /// Signale un accès du CPU aux outils branchés
+    fn observe(&self, address: u32, size: u8, kind: AccessKind) {
+        for observer in &self.access_observers {
+            observer.on_access(address, size, kind);
+        }
+    }
+
+    /// Installe la table de brouillard lue dans les données du jeu ; le jeu
+    /// peut ensuite la réécrire par les registres
+    pub fn set_fog_table_preset(&mut self, table: Vec<u8>) {
+        self.io_registers.load_fog_table(&table);
+        self.fog_table_preset = table;
+    }
+
+    /// Table de brouillard à transmettre au GPU si elle a changé (vide :
+    /// brouillard analytique)
+    pub fn take_fog_table_update(&mut self) -> Option<&[u8]> {
+        self.io_registers.take_fog_table_update()
+    }
+
+    /// Lecture de `size` octets des registres I/O, bits forcés par les
+    /// contournements compris : seul l'octet ou le mot adressé est extrait
+    fn read_io_register(&self, offset: u32, size: u32) -> u32 {
+        let aligned = offset & !3;
+        let register = match rtc_register(aligned) {
+            Some(register) => self.rtc.read(register),
+            None => self.io_registers.read_register(aligned),
+        };
+        let register = self.hacks.override_register(aligned, register);
+        let (shift, mask) = byte_lane(offset, size);
+        (register >> shift) & mask
+    }
+
+    /// Écriture de `size` octets dans les registres I/O ; une commande GPU
+    /// complète part dans le tampon de commandes
+    fn write_io_register(&mut self, offset: u32, value: u32, size: u32) {
+        if let Some(register) = rtc_register(offset & !3) {
+            // Registres de 4 bits : seule la voie de poids faible compte
+            let (shift, mask) = byte_lane(offset, size);
+            if shift == 0 {
+                self.rtc.write(register, value & mask);
+            }
+            return;
+        }
+        if offset & !3 == DRIVE_BOARD_REGISTER {
+            // Octet de commande sur la voie de poids faible
+            let (shift, mask) = byte_lane(offset, size);
+            if shift == 0 {
+                self.drive_board.write((value & mask) as u8);
+            }
+            return;
+        }
+        if let Some(gpu_command) = self.io_registers.write_sized(offset, value, size) {
+            self.enqueue_gpu_command(gpu_command);
+        }
+        if (LAYER_PRIORITY_REGISTER..=BACKDROP_COLOR_REGISTER).contains(&(offset & !3)) {
+            self.scanline_log.record(self.scanline, self.io_registers.layers);
+        }
+    }
+
+    /// Début d'une image : les registres de composition courants valent
+    /// pour sa première ligne
+    pub fn begin_scanlines(&mut self) {
+        self.scanline = 0;
+        self.scanline_log.restart(self.io_registers.layers);
+    }
+
+    /// Ligne atteinte par le balayage
+    pub fn set_scanline(&mut self, line: u32) {
+        self.scanline = line;
+    }
+
+    /// Registres de composition de l'image, effets de raster compris
+    pub fn scanline_log(&self) -> &ScanlineLog {
+        &self.scanline_log
+    }
+
+    /// Installe la puce de protection du jeu (aucune : bus ouvert)
+    pub fn set_protection(&mut self, config: Option<&ProtectionConfig>) {
+        self.protection = config.map(ProtectionDevice::new);
+    }
+
+    /// Puce de protection installée
+    pub fn protection(&mut self) -> Option<&mut ProtectionDevice> {
+        self.protection.as_mut()
+    }
+
+    /// Lecture de l'horloge, dont les chiffres avancent avec l'heure de l'hôte
+    fn rtc_read(&self, offset: u32, size: u32) -> u32 {
+        let value = self.read_io_register(offset, size);
+        self.bus_latch.store(value, Ordering::Relaxed);
+        value
+    }
+
+    /// Horloge temps réel
+    pub fn rtc(&self) -> &RtcDevice {
+        &self.rtc
+    }
+
+    /// Remplace l'horloge (NVRAM du jeu, état de sauvegarde)
+    pub fn set_rtc(&mut self, rtc: RtcDevice) {
+        self.rtc = rtc;
+    }
+
+    /// Lampes de la borne allumées par le jeu
+    pub fn lamps(&self) -> LampOutputs {
+        LampOutputs::from_bits(self.io_registers.lamp_output)
+    }
+
+    /// Carte de pilotage : effets en cours
+    pub fn drive_board(&self) -> &DriveBoard {
+        &self.drive_board
+    }
+
+    /// Carte de pilotage, pour relever ses commandes
+    pub fn drive_board_mut(&mut self) -> &mut DriveBoard {
+        &mut self.drive_board
+    }
+
+    /// Lecture de la puce de protection, chaque lecture du port de données
+    /// consommant un mot de la réponse
+    fn protection_read(&self, address: u32, offset: u32, size: u8) -> Result<u32> {
+        match self.protection.as_ref().map(|device| device.read(offset)) {
+            Some(value) => {
+                self.bus_latch.store(value, Ordering::Relaxed);
+                Ok(value)
+            }
+            None => self.open_bus_read(address, size),
+        }
+    }
+
+    fn protection_write(&mut self, address: u32, offset: u32, value: u32) -> Result<()> {
+        match &mut self.protection {
+            Some(device) => {
+                device.write(offset, value);
+                Ok(())
+            }
+            None => self.open_bus.write(address),
+        }
+    }
+
+    /// Côté 68000 du port série de la carte son
+    pub fn sound_latch(&mut self) -> &mut SoundLatch {
+        &mut self.sound_latch
+    }
+
+    /// Lecture du port série de la carte son, le port de données consommant
+    /// une réponse à chaque lecture
+    fn sound_latch_read(&self, offset: u32) -> u32 {
+        let value = self.sound_latch.main_read(offset);
+        self.bus_latch.store(value, Ordering::Relaxed);
+        value
+    }
+
+    /// Côté moteur géométrique des fenêtres partagées
+    pub fn coprocessor(&mut self) -> &mut CoprocessorLink {
+        &mut self.coprocessor
+    }
+
+    /// Lecture des fenêtres partagées ou de la boîte aux lettres du TGP
+    fn coprocessor_read(&self, region: MemoryRegion, offset: u32, size: u8) -> Result<u32> {
+        let value = match region {
+            MemoryRegion::CoprocessorMailbox => self.coprocessor.main_read(offset),
+            _ => self.coprocessor.cpu_read(offset, size)?,
+        };
+        self.bus_latch.store(value, Ordering::Relaxed);
+        Ok(value)
+    }
+
+    /// Écriture du CPU dans les fenêtres partagées ou la boîte aux lettres
+    fn coprocessor_write(&mut self, region: MemoryRegion, offset: u32, value: u32, size: u8) -> Result<()> {
+        let link = &mut self.coprocessor;
+        match region {
+            MemoryRegion::CoprocessorMailbox => {
+                link.main_write(offset, value);
+                Ok(())
+            }
+            _ => link.cpu_write(offset, value, size),
+        }
+    }
+
+    /// Écrit dans `out` les `length` octets à partir d'une adresse, sans
+    /// effet de bord sur le bus (débogueur) ; la plage passe par un tampon
+    /// de taille fixe, quelle que soit sa longueur
+    pub fn dump_range<W: std::io::Write>(&self, start: u32, length: usize, mut out: W) -> Result<()> {
+        if start as u64 + length as u64 > 1 << 32 {
+            return Err(MemoryFault::OutOfAddressSpace { address: start, size: length }.into());
+        }
+        let mut chunk = Vec::with_capacity(length.min(DUMP_CHUNK_SIZE));
+        for chunk_start in (0..length).step_by(DUMP_CHUNK_SIZE) {
+            chunk.clear();
+            for i in chunk_start..length.min(chunk_start + DUMP_CHUNK_SIZE) {
+                chunk.push(self.peek_u8(start + i as u32)?);
+            }
+            out.write_all(&chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Écrit un binaire à une adresse, à travers le bus (zones protégées ignorées)
+    pub fn load_binary(&mut self, address: u32, data: &[u8]) -> Result<()> {
+        if address as u64 + data.len() as u64 > 1 << 32 {
+            return Err(MemoryFault::OutOfAddressSpace { address, size: data.len() }.into());
+        }
+        self.write_block(address, data)
+    }
+
+    /// RAM correspondant à une région, pour les instantanés
+    pub fn ram_region(&self, region: MemoryRegion) -> Option<&Ram> {
+        match region {
+            MemoryRegion::MainRam => Some(&self.main_ram),
+            MemoryRegion::VideoRam => Some(&self.video_ram),
+            MemoryRegion::AudioRam => Some(&self.audio_ram),
+            _ => None,
+        }
+    }
+
+    /// Copie complète d'une région de RAM
+    pub fn snapshot_region(&self, region: MemoryRegion) -> Result<Vec<u8>> {
+        self.ram_region(region)
+            .map(|ram| ram.data().to_vec())
+            .ok_or_else(|| MemoryFault::NotRam(region).into())
+    }
+
+    /// Restaure une région de RAM depuis un instantané (éventuellement partiel)
+    pub fn restore_region(&mut self, region: MemoryRegion, data: &[u8]) -> Result<()> {
+        let ram = match region {
+            MemoryRegion::MainRam => &mut self.main_ram,
+            MemoryRegion::VideoRam => &mut self.video_ram,
+            MemoryRegion::AudioRam => &mut self.audio_ram,
+            _ => return Err(MemoryFault::NotRam(region).into()),
+        };
+        ram.load_data(0, data)
+    }
+
+    /// Contenu d'un banc des listes d'affichage
+    pub fn display_list_bank(&self, index: usize) -> &[u8] {
+        self.display_lists.bank(index).data()
+    }
+
+    /// Recharge un banc des listes d'affichage (restauration d'un état)
+    pub fn restore_display_list_bank(&mut self, index: usize, data: &[u8]) -> Result<()> {
+        self.display_lists.bank_mut(index).load_data(0, data)
+    }
+
+    /// Registres I/O courants
+    pub fn io_registers(&self) -> &IoRegisters {
+        &self.io_registers
+    }
+
+    /// Remplace les registres I/O (restauration d'un état)
+    pub fn set_io_registers(&mut self, registers: IoRegisters) {
+        self.io_registers = registers;
+        self.io_registers.fog_table_dirty = true;
+        self.io_registers.timers_reloaded = true;
+    }
+
+    /// Ligne de reset de la carte : registres I/O, commandes GPU en attente,
+    /// puce de protection et carte de pilotage repartent de zéro, les RAMs et
+    /// l'horloge sont conservées ; les correctifs de démarrage du jeu sont
+    /// réécrits
+    pub fn reset_io(&mut self) -> Result<()> {
+        self.io_registers = IoRegisters::new();
+        self.io_registers.load_fog_table(&self.fog_table_preset);
+        self.gpu_command_queue.clear();
+        self.gpu_command_buffer.clear();
+        if let Some(protection) = &mut self.protection {
+            protection.reset();
+        }
+        self.sound_latch.reset();
+        self.drive_board.reset();
+        self.coprocessor.reset();
+        let hacks = std::mem::take(&mut self.hacks);
+        self.set_hacks(hacks)
+    }
+
+    /// Fait avancer les timers de la carte I/O de `cycles` cycles émulés
+    pub fn advance_timers(&mut self, cycles: u32) {
+        self.io_registers.timers.update(cycles);
+    }
+
+    /// Vrai si un timer a été rechargé ou restauré depuis le dernier appel
+    pub fn take_timer_reload(&mut self) -> bool {
+        std::mem::take(&mut self.io_registers.timers_reloaded)
+    }
+
+    /// Signale au V60 le débordement d'un timer, s'il est autorisé
+    pub fn raise_timer_overflow(&mut self, interrupt: crate::cpu::Interrupt, cpu: &mut crate::cpu::NecV60) {
+        let bits = match interrupt {
+            crate::cpu::Interrupt::TimerSub => INTERRUPT_TIMER_SUB,
+            _ => INTERRUPT_TIMER_MAIN,
+        };
+        self.io_registers.interrupts.raise_if_enabled(bits, interrupt, cpu);
+    }
+
+    /// Retire l'acquittement de la carte son (commande lue ou réponse
+    /// écrite) ; vrai s'il y en avait un, à dater par l'ordonnanceur
+    pub fn take_sound_ack(&mut self) -> bool {
+        self.sound_latch.take_main_irq()
+    }
+
+    /// Signale au V60 l'acquittement de la carte son
+    pub fn raise_sound_ack(&mut self, cpu: &mut crate::cpu::NecV60) {
+        self.io_registers.interrupts.raise(INTERRUPT_AUDIO, crate::cpu::Interrupt::Audio, cpu);
+    }
+
+    /// Signale le VBlank au CPU
+    pub fn raise_vblank(&mut self, cpu: &mut crate::cpu::NecV60) {
+        self.io_registers.raise_vblank(cpu);
     }
     
+    /// Banc des listes d'affichage rempli par le CPU
+    fn back_display_bank(&self) -> usize {
+        self.io_registers.gpu.back_bank()
+    }
+
+    /// Lecture de `size` octets servie par la mémoire de stockage, sans
+    /// passer par le bus : ni verrou de données, ni carte de chaleur, ni
+    /// réponse consommée. Les ports sans stockage (protection, carte son,
+    /// boîte aux lettres du TGP) sont refusés.
+    fn peek(&self, address: u32, size: u8) -> Result<u32> {
+        let read = |memory: &dyn MemoryInterface, offset: u32| -> Result<u32> {
+            match size {
+                1 => memory.read_u8(offset).map(u32::from),
+                2 => memory.read_u16(offset).map(u32::from),
+                _ => memory.read_u32(offset),
+            }
+        };
+        let Some((region, offset)) = self.mapping.resolve(address) else {
+            return self.open_bus_read(address, size);
+        };
+        match region {
+            MemoryRegion::MainRam => read(&self.main_ram, offset),
+            MemoryRegion::VideoRam => read(&self.video_ram, offset),
+            MemoryRegion::AudioRam => read(&self.audio_ram, offset),
+            MemoryRegion::DisplayList => read(self.display_lists.bank(self.back_display_bank()), offset),
+            MemoryRegion::ProgramRom | MemoryRegion::GraphicsRom | MemoryRegion::AudioRom => {
+                let name = match region {
+                    MemoryRegion::ProgramRom => "main",
+                    MemoryRegion::GraphicsRom => "graphics",
+                    _ => "audio",
+                };
+                match self.roms.get(name) {
+                    Some(rom) => read(rom, offset),
+                    None => self.open_bus_read(address, size),
+                }
+            }
+            MemoryRegion::CoprocessorWindow => self.coprocessor.cpu_read(offset, size),
+            MemoryRegion::IoRegisters => Ok(self.read_io_register(offset, size as u32)),
+            MemoryRegion::Protection | MemoryRegion::SoundLatch | MemoryRegion::CoprocessorMailbox => {
+                Err(MemoryFault::NotRam(region).into())
+            }
+        }
+    }
+
+    /// VBlank : si le jeu l'a demandé, échange les bancs des listes
+    /// d'affichage et transmet au GPU la liste terminée. Retourne le nombre
+    /// de commandes transmises.
+    pub fn swap_display_lists(&mut self) -> usize {
+        let Some(completed) = self.io_registers.gpu.swap_banks() else {
+            return 0;
+        };
+        let mut count = 0;
+        for word in self.display_lists.command_words(completed) {
+            self.gpu_command_buffer.push(self.io_registers.gpu.decode_command(word));
+            count += 1;
+        }
+        count
+    }
+
+    /// VBlank : le TGP rend les fenêtres partagées de la frame passée et
+    /// reçoit celles que le CPU lui a remises ; la commande de la boîte aux
+    /// lettres puis la géométrie des fenêtres reçues passent au GPU, et le
+    /// nombre de commandes reçues est déposé en réponse. Retourne ce nombre.
+    pub fn hand_off_geometry_windows(&mut self) -> usize {
+        let link = &mut self.coprocessor;
+        let handed = link.vblank();
+        let command = link.take_command();
+        if handed == 0 && command.is_none() {
+            return 0;
+        }
+
+        let mut count = 0;
+        if let Some(word) = command {
+            self.gpu_command_buffer.push(self.io_registers.gpu.decode_command(word));
+            count += 1;
+        }
+        let link = &mut self.coprocessor;
+        for window in (0..COPRO_WINDOW_COUNT).filter(|window| handed & (1 << window) != 0) {
+            for word in link.command_words(window) {
+                self.gpu_command_buffer.push(self.io_registers.gpu.decode_command(word));
+                count += 1;
+            }
+        }
+        link.post_reply(count as u32);
+        count
+    }
+
+    /// Mots de commande GPU non reconnus depuis le dernier appel (décodés en
+    /// effacement de l'écran)
+    pub fn take_unknown_gpu_commands(&mut self) -> u32 {
+        self.io_registers.take_unknown_gpu_commands()
+    }
+
+    /// Présente un nouveau mot d'entrée (actif bas) au connecteur de la
+    /// carte I/O ; le jeu ne le lit qu'après le prochain VBlank
+    pub fn set_input_data(&mut self, value: u32) {
+        self.io_registers.input.set_pending(value);
+    }
+
+    /// VBlank : la carte I/O verrouille le mot présenté dans son registre
+    /// d'entrée, lu par le jeu jusqu'au VBlank suivant. Retourne vrai si
+    /// l'état des boutons a changé
+    pub fn latch_inputs(&mut self) -> bool {
+        self.io_registers.input.latch()
+    }
+
     /// Enfile une commande GPU
     pub fn enqueue_gpu_command(&mut self, command: GpuCommand) {
         self.gpu_command_buffer.push(command);
@@ -645,6 +1244,12 @@ impl Model2Memory {
     pub fn process_gpu_commands(&mut self) -> Vec<GpuCommand> {
         self.gpu_command_buffer.flush()
     }
+
+    /// Ajoute les commandes GPU en attente à `out`, que l'appelant réutilise
+    /// d'une frame à l'autre
+    pub fn process_gpu_commands_into(&mut self, out: &mut Vec<GpuCommand>) -> usize {
+        self.gpu_command_buffer.flush_into(out)
+    }
     
     /// Force le vidage du buffer de commandes GPU (pour synchronisation frame)
     pub fn flush_gpu_command_buffer(&mut self) -> Vec<GpuCommand> {
@@ -654,353 +1259,649 @@ impl Model2Memory {
 
 impl MemoryInterface for Model2Memory {
     fn read_u8(&self, address: u32) -> Result<u8> {
-        // Vérifier le cache d'abord
-        if self.cache_enabled {
-            if let Ok(cache) = self.cache.try_borrow() {
-                if let Some(value) = cache.get_u8(address) {
-                    return Ok(value);
-                }
-            }
-        }
-        
+        self.observe(address, 1, AccessKind::Read);
+
         // Déterminer la région mémoire et l'offset
         let result = if let Some((region, offset)) = self.mapping.resolve(address) {
             match region {
                 MemoryRegion::MainRam => self.main_ram.read_u8(offset),
                 MemoryRegion::VideoRam => self.video_ram.read_u8(offset),
+                MemoryRegion::DisplayList => self.display_lists.bank(self.back_display_bank()).read_u8(offset),
                 MemoryRegion::AudioRam => self.audio_ram.read_u8(offset),
                 MemoryRegion::ProgramRom => {
                     if let Some(rom) = self.roms.get("main") {
                         rom.read_u8(offset)
                     } else {
-                        Ok(0xFF)
+                        return self.open_bus_read(address, 1).map(|value| value as u8);
                     }
                 },
                 MemoryRegion::GraphicsRom => {
                     if let Some(rom) = self.roms.get("graphics") {
                         rom.read_u8(offset)
                     } else {
-                        Ok(0xFF)
+                        return self.open_bus_read(address, 1).map(|value| value as u8);
                     }
                 },
                 MemoryRegion::AudioRom => {
                     if let Some(rom) = self.roms.get("audio") {
                         rom.read_u8(offset)
                     } else {
-                        Ok(0xFF)
+                        return self.open_bus_read(address, 1).map(|value| value as u8);
                     }
                 },
+                MemoryRegion::Protection => return self.protection_read(address, offset, 1).map(|value| value as u8),
+                MemoryRegion::SoundLatch => return Ok(self.sound_latch_read(offset) as u8),
+                MemoryRegion::CoprocessorWindow | MemoryRegion::CoprocessorMailbox => {
+                    return self.coprocessor_read(region, offset, 1).map(|value| value as u8)
+                }
+                MemoryRegion::IoRegisters if rtc_register(offset & !3).is_some() => {
+                    return Ok(self.rtc_read(offset, 1) as u8)
+                }
                 MemoryRegion::IoRegisters => {
                     // Vérifier si c'est un registre SCSP (0x400-0x5FF)
                     // if offset >= 0x400 && offset < 0x600 {
                     //     Ok(self.scsp_audio.read_register(offset - 0x400) as u8)
                     // } else {
                         // Lecture des registres I/O standard
-                        Ok(self.io_registers.read_register(offset) as u8)
+                        Ok(self.read_io_register(offset, 1) as u8)
                     // }
                 },
             }
         } else {
-            Ok(0xFF) // Lecture dans une zone non mappée
+            // Lecture dans une zone non mappée
+            return self.open_bus_read(address, 1).map(|value| value as u8);
         };
 
-        // Mettre en cache le résultat si valide
         if let Ok(value) = result {
-            if let Ok(mut cache) = self.cache.try_borrow_mut() {
-                cache.set_u8(address, value);
-            }
+            self.bus_latch.store(value as u32, Ordering::Relaxed);
         }
 
         result
     }
 
     fn read_u16(&self, address: u32) -> Result<u16> {
-        // Optimisation : lecture directe pour les accès alignés
-        if address % 2 == 0 {
-            if let Ok(cache) = self.cache.try_borrow() {
-                if let Some(value) = cache.get_u16(address) {
-                    return Ok(value);
-                }
-            }
-        }
-        
+        self.observe(address, 2, AccessKind::Read);
+
         // Déterminer la région mémoire et l'offset
         let result = if let Some((region, offset)) = self.mapping.resolve(address) {
             match region {
                 MemoryRegion::MainRam => self.main_ram.read_u16(offset),
                 MemoryRegion::VideoRam => self.video_ram.read_u16(offset),
+                MemoryRegion::DisplayList => self.display_lists.bank(self.back_display_bank()).read_u16(offset),
                 MemoryRegion::AudioRam => self.audio_ram.read_u16(offset),
                 MemoryRegion::ProgramRom => {
                     if let Some(rom) = self.roms.get("main") {
                         rom.read_u16(offset)
                     } else {
-                        Ok(0xFFFF)
+                        return self.open_bus_read(address, 2).map(|value| value as u16);
                     }
                 },
                 MemoryRegion::GraphicsRom => {
                     if let Some(rom) = self.roms.get("graphics") {
                         rom.read_u16(offset)
                     } else {
-                        Ok(0xFFFF)
+                        return self.open_bus_read(address, 2).map(|value| value as u16);
                     }
                 },
                 MemoryRegion::AudioRom => {
                     if let Some(rom) = self.roms.get("audio") {
                         rom.read_u16(offset)
                     } else {
-                        Ok(0xFFFF)
+                        return self.open_bus_read(address, 2).map(|value| value as u16);
                     }
                 },
+                MemoryRegion::Protection => return self.protection_read(address, offset, 2).map(|value| value as u16),
+                MemoryRegion::SoundLatch => return Ok(self.sound_latch_read(offset) as u16),
+                MemoryRegion::CoprocessorWindow | MemoryRegion::CoprocessorMailbox => {
+                    return self.coprocessor_read(region, offset, 2).map(|value| value as u16)
+                }
+                MemoryRegion::IoRegisters if rtc_register(offset & !3).is_some() => {
+                    return Ok(self.rtc_read(offset, 2) as u16)
+                }
                 MemoryRegion::IoRegisters => {
                     // Vérifier si c'est un registre SCSP (0x400-0x5FF)
                     // if offset >= 0x400 && offset < 0x600 {
                     //     Ok(self.scsp_audio.read_register(offset - 0x400) as u16)
                     // } else {
                         // Lecture des registres I/O standard
-                        Ok(self.io_registers.read_register(offset) as u16)
+                        Ok(self.read_io_register(offset, 2) as u16)
                     // }
                 },
             }
         } else {
-            Ok(0xFFFF) // Lecture dans une zone non mappée
+            // Lecture dans une zone non mappée
+            return self.open_bus_read(address, 2).map(|value| value as u16);
         };
 
-        // Mettre en cache le résultat si valide
         if let Ok(value) = result {
-            if let Ok(mut cache) = self.cache.try_borrow_mut() {
-                cache.set_u16(address, value);
-            }
+            self.bus_latch.store(value as u32, Ordering::Relaxed);
         }
 
         result
     }
 
     fn read_u32(&self, address: u32) -> Result<u32> {
-        // Optimisation : lecture directe pour les accès alignés
-        if address % 4 == 0 {
-            if let Ok(cache) = self.cache.try_borrow() {
-                if let Some(value) = cache.get_u32(address) {
-                    return Ok(value);
-                }
-            }
-        }
-        
+        self.observe(address, 4, AccessKind::Read);
+
         // Déterminer la région mémoire et l'offset
         let result = if let Some((region, offset)) = self.mapping.resolve(address) {
             match region {
                 MemoryRegion::MainRam => self.main_ram.read_u32(offset),
                 MemoryRegion::VideoRam => self.video_ram.read_u32(offset),
+                MemoryRegion::DisplayList => self.display_lists.bank(self.back_display_bank()).read_u32(offset),
                 MemoryRegion::AudioRam => self.audio_ram.read_u32(offset),
                 MemoryRegion::ProgramRom => {
                     if let Some(rom) = self.roms.get("main") {
                         rom.read_u32(offset)
                     } else {
-                        Ok(0xFFFFFFFF)
+                        return self.open_bus_read(address, 4);
                     }
                 },
                 MemoryRegion::GraphicsRom => {
                     if let Some(rom) = self.roms.get("graphics") {
                         rom.read_u32(offset)
                     } else {
-                        Ok(0xFFFFFFFF)
+                        return self.open_bus_read(address, 4);
                     }
                 },
                 MemoryRegion::AudioRom => {
                     if let Some(rom) = self.roms.get("audio") {
                         rom.read_u32(offset)
                     } else {
-                        Ok(0xFFFFFFFF)
+                        return self.open_bus_read(address, 4);
                     }
                 },
+                MemoryRegion::Protection => return self.protection_read(address, offset, 4),
+                MemoryRegion::SoundLatch => return Ok(self.sound_latch_read(offset)),
+                MemoryRegion::CoprocessorWindow | MemoryRegion::CoprocessorMailbox => {
+                    return self.coprocessor_read(region, offset, 4)
+                }
+                MemoryRegion::IoRegisters if rtc_register(offset & !3).is_some() => {
+                    return Ok(self.rtc_read(offset, 4))
+                }
                 MemoryRegion::IoRegisters => {
                     // Vérifier si c'est un registre SCSP (0x400-0x5FF)
                     // if offset >= 0x400 && offset < 0x600 {
                     //     Ok(self.scsp_audio.read_register(offset - 0x400))
                     // } else {
                         // Lecture des registres I/O standard
-                        Ok(self.io_registers.read_register(offset))
+                        Ok(self.read_io_register(offset, 4))
                     // }
                 },
             }
         } else {
-            Ok(0xFFFFFFFF) // Lecture dans une zone non mappée
+            // Lecture dans une zone non mappée
+            return self.open_bus_read(address, 4);
         };
 
-        // Mettre en cache le résultat si valide
         if let Ok(value) = result {
-            if let Ok(mut cache) = self.cache.try_borrow_mut() {
-                cache.set_u32(address, value);
-            }
+            self.bus_latch.store(value, Ordering::Relaxed);
         }
 
         result
     }
 
+    fn peek_u8(&self, address: u32) -> Result<u8> {
+        self.peek(address, 1).map(|value| value as u8)
+    }
+
+    fn peek_u16(&self, address: u32) -> Result<u16> {
+        self.peek(address, 2).map(|value| value as u16)
+    }
+
+    fn peek_u32(&self, address: u32) -> Result<u32> {
+        self.peek(address, 4)
+    }
+
     fn write_u8(&mut self, address: u32, value: u8) -> Result<()> {
+        self.bus_latch.store(value as u32, Ordering::Relaxed);
+        self.observe(address, 1, AccessKind::Write);
+
         // Déterminer la région mémoire et l'offset
-        if let Some((region, offset)) = self.mapping.resolve(address) {
-            match region {
-                MemoryRegion::MainRam => self.main_ram.write_u8(offset, value),
-                MemoryRegion::VideoRam => self.video_ram.write_u8(offset, value),
-                MemoryRegion::AudioRam => self.audio_ram.write_u8(offset, value),
-                MemoryRegion::ProgramRom | MemoryRegion::GraphicsRom | MemoryRegion::AudioRom => {
-                    // Les ROMs sont en lecture seule
-                    Err(anyhow!("Tentative d'écriture en ROM à l'adresse {:08X}", address))
-                },
-                MemoryRegion::IoRegisters => {
-                    // Vérifier si c'est un registre SCSP (0x400-0x5FF)
-                    // if offset >= 0x400 && offset < 0x600 {
-                    //     self.scsp_audio.write_register(offset - 0x400, value as u32);
-                    //     Ok(())
-                    // } else {
-                        // Écriture dans les registres I/O standard
-                        self.io_registers.write_register(offset, value as u32);
-                        Ok(())
-                    // }
-                },
-            }
-        } else {
-            // Écriture dans une zone non mappée - ignorer silencieusement
-            Ok(())
+        let (region, offset) = match self.mapping.resolve_write(address) {
+            WriteTarget::Mapped(region, offset) => (region, offset),
+            // Zone protégée en écriture : le matériel ignore l'accès
+            WriteTarget::Protected => return Ok(()),
+            // Écriture dans une zone non mappée - ignorée sauf erreur de bus
+            WriteTarget::Unmapped => return self.open_bus.write(address),
+        };
+
+        match region {
+            MemoryRegion::MainRam => self.main_ram.write_u8(offset, value),
+            MemoryRegion::VideoRam => self.video_ram.write_u8(offset, value),
+            MemoryRegion::DisplayList => {
+                let bank = self.back_display_bank();
+                self.display_lists.bank_mut(bank).write_u8(offset, value)
+            },
+            MemoryRegion::AudioRam => self.audio_ram.write_u8(offset, value),
+            MemoryRegion::ProgramRom | MemoryRegion::GraphicsRom | MemoryRegion::AudioRom => {
+                // Les ROMs sont en lecture seule
+                Err(MemoryFault::ReadOnly { address }.into())
+            },
+            MemoryRegion::Protection => self.protection_write(address, offset, value as u32),
+            MemoryRegion::CoprocessorWindow | MemoryRegion::CoprocessorMailbox => {
+                self.coprocessor_write(region, offset, value as u32, 1)
+            },
+            MemoryRegion::SoundLatch => {
+                self.sound_latch.main_write(offset, value as u32);
+                Ok(())
+            },
+            MemoryRegion::IoRegisters => {
+                // Vérifier si c'est un registre SCSP (0x400-0x5FF)
+                // if offset >= 0x400 && offset < 0x600 {
+                //     self.scsp_audio.write_register(offset - 0x400, value as u32);
+                //     Ok(())
+                // } else {
+                    // Écriture dans les registres I/O standard
+                    self.write_io_register(offset, value as u32, 1);
+                    Ok(())
+                // }
+            },
         }
     }
 
     fn write_u16(&mut self, address: u32, value: u16) -> Result<()> {
+        self.bus_latch.store(value as u32, Ordering::Relaxed);
+        self.observe(address, 2, AccessKind::Write);
+
         // Alignement vérifié
         if address % 2 != 0 {
-            return Err(anyhow!("Écriture u16 non alignée à l'adresse {:08X}", address));
+            return Err(MemoryFault::Misaligned { address, size: 2 }.into());
         }
         
         // Déterminer la région mémoire et l'offset
-        if let Some((region, offset)) = self.mapping.resolve(address) {
-            match region {
-                MemoryRegion::MainRam => self.main_ram.write_u16(offset, value),
-                MemoryRegion::VideoRam => self.video_ram.write_u16(offset, value),
-                MemoryRegion::AudioRam => self.audio_ram.write_u16(offset, value),
-                MemoryRegion::ProgramRom | MemoryRegion::GraphicsRom | MemoryRegion::AudioRom => {
-                    // Les ROMs sont en lecture seule
-                    Err(anyhow!("Tentative d'écriture en ROM à l'adresse {:08X}", address))
-                },
-                MemoryRegion::IoRegisters => {
-                    // Vérifier si c'est un registre SCSP (0x400-0x5FF)
-                    // if offset >= 0x400 && offset < 0x600 {
-                    //     self.scsp_audio.write_register(offset - 0x400, value as u32);
-                    //     Ok(())
-                    // } else {
-                        // Écriture dans les registres I/O standard
-                        self.io_registers.write_register(offset, value as u32);
-                        Ok(())
-                    // }
-                },
-            }
-        } else {
-            // Écriture dans une zone non mappée - ignorer silencieusement
-            Ok(())
+        let (region, offset) = match self.mapping.resolve_write(address) {
+            WriteTarget::Mapped(region, offset) => (region, offset),
+            // Zone protégée en écriture : le matériel ignore l'accès
+            WriteTarget::Protected => return Ok(()),
+            // Écriture dans une zone non mappée - ignorée sauf erreur de bus
+            WriteTarget::Unmapped => return self.open_bus.write(address),
+        };
+
+        match region {
+            MemoryRegion::MainRam => self.main_ram.write_u16(offset, value),
+            MemoryRegion::VideoRam => self.video_ram.write_u16(offset, value),
+            MemoryRegion::DisplayList => {
+                let bank = self.back_display_bank();
+                self.display_lists.bank_mut(bank).write_u16(offset, value)
+            },
+            MemoryRegion::AudioRam => self.audio_ram.write_u16(offset, value),
+            MemoryRegion::ProgramRom | MemoryRegion::GraphicsRom | MemoryRegion::AudioRom => {
+                // Les ROMs sont en lecture seule
+                Err(MemoryFault::ReadOnly { address }.into())
+            },
+            MemoryRegion::Protection => self.protection_write(address, offset, value as u32),
+            MemoryRegion::CoprocessorWindow | MemoryRegion::CoprocessorMailbox => {
+                self.coprocessor_write(region, offset, value as u32, 2)
+            },
+            MemoryRegion::SoundLatch => {
+                self.sound_latch.main_write(offset, value as u32);
+                Ok(())
+            },
+            MemoryRegion::IoRegisters => {
+                // Vérifier si c'est un registre SCSP (0x400-0x5FF)
+                // if offset >= 0x400 && offset < 0x600 {
+                //     self.scsp_audio.write_register(offset - 0x400, value as u32);
+                //     Ok(())
+                // } else {
+                    // Écriture dans les registres I/O standard
+                    self.write_io_register(offset, value as u32, 2);
+                    Ok(())
+                // }
+            },
         }
     }
 
     fn write_u32(&mut self, address: u32, value: u32) -> Result<()> {
+        self.bus_latch.store(value, Ordering::Relaxed);
+        self.observe(address, 4, AccessKind::Write);
+
         // Alignement vérifié
         if address % 4 != 0 {
-            return Err(anyhow!("Écriture u32 non alignée à l'adresse {:08X}", address));
+            return Err(MemoryFault::Misaligned { address, size: 4 }.into());
         }
         
         // Déterminer la région mémoire et l'offset
-        if let Some((region, offset)) = self.mapping.resolve(address) {
-            match region {
-                MemoryRegion::MainRam => self.main_ram.write_u32(offset, value),
-                MemoryRegion::VideoRam => self.video_ram.write_u32(offset, value),
-                MemoryRegion::AudioRam => self.audio_ram.write_u32(offset, value),
-                MemoryRegion::ProgramRom | MemoryRegion::GraphicsRom | MemoryRegion::AudioRom => {
-                    // Les ROMs sont en lecture seule
-                    Err(anyhow!("Tentative d'écriture en ROM à l'adresse {:08X}", address))
-                },
-                MemoryRegion::IoRegisters => {
-                    // Vérifier si c'est un registre SCSP (0x400-0x5FF)
-                    // if offset >= 0x400 && offset < 0x600 {
-                    //     self.scsp_audio.write_register(offset - 0x400, value);
-                    //     Ok(())
-                    // } else {
-                        // Écriture dans les registres I/O standard
-                        if let Some(gpu_command) = self.io_registers.write_register(offset, value) {
-                            self.enqueue_gpu_command(gpu_command);
-                        }
-                        Ok(())
-                    // }
-                },
-            }
-        } else {
-            // Écriture dans une zone non mappée - ignorer silencieusement
-            Ok(())
+        let (region, offset) = match self.mapping.resolve_write(address) {
+            WriteTarget::Mapped(region, offset) => (region, offset),
+            // Zone protégée en écriture : le matériel ignore l'accès
+            WriteTarget::Protected => return Ok(()),
+            // Écriture dans une zone non mappée - ignorée sauf erreur de bus
+            WriteTarget::Unmapped => return self.open_bus.write(address),
+        };
+
+        match region {
+            MemoryRegion::MainRam => self.main_ram.write_u32(offset, value),
+            MemoryRegion::VideoRam => self.video_ram.write_u32(offset, value),
+            MemoryRegion::DisplayList => {
+                let bank = self.back_display_bank();
+                self.display_lists.bank_mut(bank).write_u32(offset, value)
+            },
+            MemoryRegion::AudioRam => self.audio_ram.write_u32(offset, value),
+            MemoryRegion::ProgramRom | MemoryRegion::GraphicsRom | MemoryRegion::AudioRom => {
+                // Les ROMs sont en lecture seule
+                Err(MemoryFault::ReadOnly { address }.into())
+            },
+            MemoryRegion::Protection => self.protection_write(address, offset, value),
+            MemoryRegion::CoprocessorWindow | MemoryRegion::CoprocessorMailbox => {
+                self.coprocessor_write(region, offset, value, 4)
+            },
+            MemoryRegion::SoundLatch => {
+                self.sound_latch.main_write(offset, value);
+                Ok(())
+            },
+            MemoryRegion::IoRegisters => {
+                // Vérifier si c'est un registre SCSP (0x400-0x5FF)
+                // if offset >= 0x400 && offset < 0x600 {
+                //     self.scsp_audio.write_register(offset - 0x400, value);
+                //     Ok(())
+                // } else {
+                    // Écriture dans les registres I/O standard
+                    self.write_io_register(offset, value, 4);
+                    Ok(())
+                // }
+            },
         }
     }
 }
 
-/// Cache mémoire simple pour optimiser les performances
-#[derive(Debug)]
-struct MemoryCache {
-    entries: HashMap<u32, CacheEntry>,
-    max_entries: usize,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-#[derive(Debug, Clone)]
-struct CacheEntry {
-    value: u32,
-    size: u8, // 1, 2, ou 4 octets
-}
+    #[test]
+    fn test_unmapped_read_pulls_up_by_default() {
+        let memory = Model2Memory::new();
+        assert_eq!(memory.read_u8(0x0500_0000).unwrap(), 0xFF);
+        assert_eq!(memory.read_u32(0x0500_0000).unwrap(), 0xFFFF_FFFF);
+    }
 
-impl MemoryCache {
-    fn new() -> Self {
-        Self {
-            entries: HashMap::new(),
-            max_entries: 1024, // Limiter la taille du cache
-        }
+    #[test]
+    fn test_open_bus_returns_last_transferred_value() {
+        let mut memory = Model2Memory::new();
+        memory.set_open_bus(OpenBusPolicy::new().with_default(OpenBusMode::LastValue));
+
+        memory.write_u32(0x100, 0x1234_5678).unwrap();
+        memory.read_u32(0x100).unwrap();
+        assert_eq!(memory.read_u16(0x0500_0000).unwrap(), 0x5678);
+
+        // Une ROM absente se comporte comme une zone non mappée
+        assert_eq!(memory.read_u32(0x0200_0000).unwrap(), 0x1234_5678);
     }
 
-    fn get_u8(&self, address: u32) -> Option<u8> {
-        self.entries.get(&address)
-            .filter(|entry| entry.size == 1)
-            .map(|entry| entry.value as u8)
+    #[test]
+    fn test_peek_leaves_bus_untouched() {
+        let mut memory = Model2Memory::new();
+        memory.set_open_bus(OpenBusPolicy::new().with_default(OpenBusMode::LastValue));
+        memory.write_u32(0x100, 0x1234_5678).unwrap();
+        memory.write_u32(0x104, 0xAB).unwrap();
+
+        assert_eq!(memory.peek_u16(0x102).unwrap(), 0x1234);
+        assert_eq!(memory.read_u32(0x0500_0000).unwrap(), 0xAB);
+
+        // Les ports qui consomment leur réponse ne sont pas lus
+        memory.set_protection(Some(&ProtectionConfig {
+            chip: "315-5881".to_string(),
+            challenges: vec![ProtectionChallenge { command: 0x55, response: vec![7] }],
+        }));
+        memory.write_u32(PROTECTION_BASE + PROTECTION_DATA, 0x55).unwrap();
+        assert!(memory.peek_u32(PROTECTION_BASE + PROTECTION_DATA).is_err());
+        assert_eq!(memory.read_u32(PROTECTION_BASE + PROTECTION_DATA).unwrap(), 7);
     }
 
-    fn get_u16(&self, address: u32) -> Option<u16> {
-        self.entries.get(&address)
-            .filter(|entry| entry.size == 2)
-            .map(|entry| entry.value as u16)
+    #[test]
+    fn test_write_protected_ram_ignores_writes() {
+        let mut memory = Model2Memory::new();
+        memory.write_u32(0x100, 0x1111_1111).unwrap();
+        memory.mapping.set_write_protect(0x100, true);
+
+        memory.write_u32(0x0080_0100, 0x2222_2222).unwrap();
+        assert_eq!(memory.read_u32(0x100).unwrap(), 0x1111_1111);
     }
 
-    fn get_u32(&self, address: u32) -> Option<u32> {
-        self.entries.get(&address)
-            .filter(|entry| entry.size == 4)
-            .map(|entry| entry.value)
+    #[test]
+    fn test_open_bus_error_region() {
+        let mut memory = Model2Memory::new();
+        let mut policy = OpenBusPolicy::new();
+        policy.set_region(0x0500_0000, 0x0600_0000, OpenBusMode::BusError);
+        memory.set_open_bus(policy);
+
+        let err = memory.read_u32(0x0500_0000).unwrap_err();
+        assert!(err.bus_error().is_some());
+        assert!(memory.write_u8(0x0500_0001, 0).is_err());
+        assert_eq!(memory.read_u8(0x0700_0000).unwrap(), 0xFF);
     }
 
-    fn set_u8(&mut self, address: u32, value: u8) {
-        self.insert_entry(address, CacheEntry { value: value as u32, size: 1 });
+    #[test]
+    fn test_snapshot_and_restore_vram() {
+        let mut memory = Model2Memory::new();
+        memory.write_u32(0x1000_0010, 0xCAFE_F00D).unwrap();
+
+        let snapshot = memory.snapshot_region(MemoryRegion::VideoRam).unwrap();
+        assert_eq!(snapshot.len(), 4 * 1024 * 1024);
+        assert_eq!(&snapshot[0x10..0x14], &0xCAFE_F00Du32.to_le_bytes());
+
+        memory.video_ram.clear();
+        memory.restore_region(MemoryRegion::VideoRam, &snapshot).unwrap();
+        assert_eq!(memory.read_u32(0x1000_0010).unwrap(), 0xCAFE_F00D);
+        assert!(memory.snapshot_region(MemoryRegion::ProgramRom).is_err());
     }
 
-    fn set_u16(&mut self, address: u32, value: u16) {
-        self.insert_entry(address, CacheEntry { value: value as u32, size: 2 });
+    #[test]
+    fn test_dump_range_rejects_wraparound() {
+        let mut memory = Model2Memory::new();
+        memory.load_binary(0x200, &[1, 2, 3]).unwrap();
+        let mut dump = Vec::new();
+        memory.dump_range(0x1FF, 5, &mut dump).unwrap();
+        assert_eq!(dump, [0, 1, 2, 3, 0]);
+        assert!(memory.dump_range(0xFFFF_FFF0, 0x20, std::io::sink()).is_err());
+
+        // Plage plus longue que le tampon
+        let mut dump = Vec::new();
+        memory.dump_range(0, DUMP_CHUNK_SIZE + 0x201, &mut dump).unwrap();
+        assert_eq!((dump.len(), &dump[0x200..0x203]), (DUMP_CHUNK_SIZE + 0x201, &[1, 2, 3][..]));
     }
 
-    fn set_u32(&mut self, address: u32, value: u32) {
-        self.insert_entry(address, CacheEntry { value, size: 4 });
+    #[test]
+    fn test_protection_board_on_bus() {
+        let mut memory = Model2Memory::new();
+        let data = PROTECTION_BASE + PROTECTION_DATA;
+        // Sans puce : bus ouvert
+        assert_eq!(memory.read_u32(data).unwrap(), 0xFFFF_FFFF);
+
+        let config = ProtectionConfig {
+            chip: "315-5881".to_string(),
+            challenges: vec![ProtectionChallenge { command: 0x55, response: vec![7, 8] }],
+        };
+        memory.set_protection(Some(&config));
+        memory.write_u32(data, 0x55).unwrap();
+        // Chaque lecture consomme un mot de la réponse
+        assert_eq!(memory.read_u32(data).unwrap(), 7);
+        assert_eq!(memory.read_u32(data).unwrap(), 8);
+        assert_eq!(memory.read_u16(PROTECTION_BASE + PROTECTION_STATUS).unwrap(), 0);
+
+        memory.write_u32(data, 0x66).unwrap();
+        assert_eq!(memory.protection().unwrap().unknown_commands().len(), 1);
     }
 
-    fn insert_entry(&mut self, address: u32, entry: CacheEntry) {
-        // Éviction si le cache est plein
-        if self.entries.len() >= self.max_entries {
-            // Stratégie simple : vider la moitié du cache
-            let keys: Vec<u32> = self.entries.keys().take(self.max_entries / 2).cloned().collect();
-            for key in keys {
-                self.entries.remove(&key);
-            }
-        }
-        
-        self.entries.insert(address, entry);
+    #[test]
+    fn test_sound_latch_acknowledges_main_cpu() {
+        let mut memory = Model2Memory::new();
+        let mut cpu = crate::cpu::NecV60::new();
+        memory.write_u32(SOUND_LATCH_BASE + SOUND_LATCH_DATA, 0x21).unwrap();
+        assert!(memory.sound_latch().sound_irq());
+
+        // Lecture de la commande par le 68000 : un seul acquittement à signaler
+        assert_eq!(memory.sound_latch().sound_read(SCSP_MIDI_INPUT) & 0xFF, 0x21);
+        memory.sound_latch().sound_write(SCSP_MIDI_OUTPUT, 0x01);
+        assert!(memory.take_sound_ack());
+        assert!(!memory.take_sound_ack());
+        memory.raise_sound_ack(&mut cpu);
+        assert_eq!(cpu.pending_interrupts, vec![crate::cpu::Interrupt::Audio]);
+        assert_ne!(memory.io_registers().interrupts.status & 0x10, 0);
+
+        // Réponse lue une seule fois
+        let status = SOUND_LATCH_BASE + SOUND_LATCH_STATUS;
+        assert_eq!(memory.read_u32(status).unwrap(), SOUND_STATUS_TX_READY | SOUND_STATUS_RX_READY);
+        assert_eq!(memory.read_u32(SOUND_LATCH_BASE + SOUND_LATCH_DATA).unwrap(), 0x01);
+        assert_eq!(memory.read_u32(status).unwrap(), SOUND_STATUS_TX_READY);
+    }
+
+    #[test]
+    fn test_display_list_banks_swap_at_vblank() {
+        let mut memory = Model2Memory::new();
+        let bank_register = IO_REGISTERS_BASE + DISPLAY_BANK_REGISTER;
+        // Deux effacements d'écran puis fin de liste dans le banc 0
+        memory.write_u32(DISPLAY_LIST_BASE, 0x00FF_0000).unwrap();
+        memory.write_u32(DISPLAY_LIST_BASE + 4, 0x0000_00FF).unwrap();
+        memory.write_u32(DISPLAY_LIST_BASE + 8, DISPLAY_LIST_END).unwrap();
+
+        // Sans demande du jeu, rien n'est échangé
+        assert_eq!(memory.swap_display_lists(), 0);
+        memory.write_u32(bank_register, DISPLAY_BANK_SWAP | DISPLAY_BANK_BACK).unwrap();
+        assert_eq!(memory.io_registers().gpu.display_bank, DISPLAY_BANK_SWAP);
+        assert_eq!(memory.swap_display_lists(), 2);
+        assert_eq!(memory.process_gpu_commands().len(), 2);
+        assert_eq!(memory.io_registers().gpu.display_bank, DISPLAY_BANK_BACK);
+
+        // Le CPU remplit le banc 1 sans toucher la liste rendue
+        memory.write_u32(DISPLAY_LIST_BASE, DISPLAY_LIST_END).unwrap();
+        assert_eq!(memory.read_u32(DISPLAY_LIST_BASE).unwrap(), DISPLAY_LIST_END);
+        assert_eq!(memory.display_lists.command_words(0).count(), 2);
+        assert_eq!(memory.display_lists.command_words(1).next(), None);
+    }
+
+    #[test]
+    fn test_geometry_windows_handed_off_at_vblank() {
+        let mut memory = Model2Memory::new();
+        let status = COPRO_MAILBOX_BASE + COPRO_MAILBOX_STATUS;
+        // Effacement d'écran puis fin de liste dans la fenêtre 0
+        memory.write_u32(COPRO_WINDOW_BASE, 0x00FF_0000).unwrap();
+        memory.write_u32(COPRO_WINDOW_BASE + 4, DISPLAY_LIST_END).unwrap();
+        memory.write_u32(COPRO_MAILBOX_BASE + COPRO_HANDOFF, 0b01).unwrap();
+        assert_eq!(memory.read_u32(status).unwrap(), 0);
+
+        assert_eq!(memory.hand_off_geometry_windows(), 1);
+        assert_eq!(memory.process_gpu_commands().len(), 1);
+        assert_eq!(memory.read_u32(status).unwrap(), COPRO_STATUS_GEOMETRY_OWNED | COPRO_STATUS_BUSY | COPRO_STATUS_REPLY_READY);
+        assert_eq!(memory.read_u32(COPRO_MAILBOX_BASE + COPRO_MAILBOX_DATA).unwrap(), 1);
+
+        // Fenêtre détenue par le TGP : l'écriture du CPU est refusée
+        memory.write_u32(COPRO_WINDOW_BASE, 0).unwrap();
+        assert_eq!(memory.read_u32(COPRO_WINDOW_BASE).unwrap(), 0x00FF_0000);
+        assert_eq!(memory.coprocessor().refused_writes(), 1);
+
+        // Rendue au CPU au VBlank suivant
+        assert_eq!(memory.hand_off_geometry_windows(), 0);
+        assert_eq!(memory.read_u32(status).unwrap(), 0);
+        memory.write_u32(COPRO_WINDOW_BASE, 0).unwrap();
+        assert_eq!(memory.read_u32(COPRO_WINDOW_BASE).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_hacks_hook_bus_accesses() {
+        let mut memory = Model2Memory::new();
+        memory.set_hacks(GameHacks::new([Hack::SkipGeometryDmaCheck, Hack::ForceServiceUnlock])).unwrap();
+
+        // Correctif de démarrage écrit dans le registre de contrôle d'entrée
+        assert_eq!(memory.io_registers().input.control, INPUT_CONTROL_SERVICE_UNLOCK);
+        // Bit DMA forcé à la lecture, sans modifier le registre
+        assert_eq!(memory.read_u32(IO_REGISTERS_BASE + 0x24).unwrap(), 0x0000_0003);
+        assert_eq!(memory.io_registers().gpu.status, 0x0000_0001);
+        assert_eq!(memory.read_u8(IO_REGISTERS_BASE + 0x24).unwrap(), 0x03);
+
+        // Registres désignés par offset : valables quelle que soit la carte
+        let mut memory = Model2Memory::new();
+        memory.set_board(BoardRevision::Model2C);
+        memory.set_hacks(GameHacks::new([Hack::SkipGeometryDmaCheck, Hack::ForceServiceUnlock])).unwrap();
+        let io_base = memory.mapping.io_base().unwrap();
+        assert_eq!(memory.read_u32(io_base + 0x44).unwrap(), INPUT_CONTROL_SERVICE_UNLOCK);
+        assert_eq!(memory.read_u32(io_base + 0x24).unwrap(), 0x0000_0003);
     }
 
-    fn clear(&mut self) {
-        self.entries.clear();
+    #[test]
+    fn test_io_sub_word_accesses_use_byte_lanes() {
+        let mut memory = Model2Memory::new();
+        let input_control = IO_REGISTERS_BASE + 0x44;
+        memory.write_u32(input_control, 0x1122_3344).unwrap();
+
+        // Un octet ou un mot ne remplace que sa voie
+        memory.write_u8(input_control + 1, 0xAB).unwrap();
+        memory.write_u16(input_control + 2, 0x5566).unwrap();
+        assert_eq!(memory.io_registers().input.control, 0x5566_AB44);
+        assert_eq!(memory.read_u8(input_control + 3).unwrap(), 0x55);
+        assert_eq!(memory.read_u16(input_control + 2).unwrap(), 0x5566);
+        assert_eq!(memory.read_u8(input_control).unwrap(), 0x44);
+
+        // La commande GPU part quand l'octet de poids fort est écrit
+        let gpu_command = IO_REGISTERS_BASE + 0x28;
+        memory.write_u16(gpu_command, 0x00FF).unwrap();
+        assert!(memory.flush_gpu_command_buffer().is_empty());
+        memory.write_u16(gpu_command + 2, 0x0000).unwrap();
+        assert_eq!(memory.flush_gpu_command_buffer().len(), 1);
+        assert_eq!(memory.io_registers().gpu.command, 0x0000_00FF);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_io_registers_keep_flat_saved_fields() {
+        let mut registers = IoRegisters::new();
+        registers.write_register(INTERRUPT_STATUS_REGISTER, 0x11);
+        registers.write_register(TIMER_SUB_REGISTER, 0x22);
+        registers.write_register(GPU_CONTROL_REGISTER, 0x33);
+        registers.write_register(INPUT_CONTROL_REGISTER, 0x44);
+        registers.timers.update(8);
+
+        // Les états de sauvegarde existants nomment les registres à plat
+        let json = serde_json::to_value(&registers).unwrap();
+        assert_eq!(json["interrupt_status"], 0x11);
+        assert_eq!(json["timer_sub"], 0x24);
+        assert_eq!(json["gpu_control"], 0x33);
+        assert_eq!(json["input_control"], 0x44);
+        assert_eq!(json["cycle_counter"], 8);
+
+        let restored: IoRegisters = serde_json::from_str(r#"{"timer_main": 5, "input_data": 66}"#).unwrap();
+        assert_eq!((restored.timers.main, restored.input.data), (5, 66));
+        assert_eq!((restored.gpu.status, restored.read_register(INPUT_CONTROL_REGISTER)), (GPU_STATUS_READY, 0));
+    }
+
+    #[test]
+    fn test_inputs_latched_at_vblank() {
+        let mut memory = Model2Memory::new();
+        let input_data = IO_REGISTERS_BASE + INPUT_DATA_REGISTER;
+        assert_eq!(memory.read_u32(input_data).unwrap(), INPUT_IDLE);
+
+        // Haut du joueur 1 enfoncé (actif bas) : invisible avant le VBlank
+        memory.set_input_data(INPUT_IDLE & !0x01);
+        assert_eq!(memory.read_u32(input_data).unwrap(), INPUT_IDLE);
+        assert!(memory.latch_inputs());
+        assert_eq!(memory.read_u32(input_data).unwrap(), INPUT_IDLE & !0x01);
+        assert!(!memory.latch_inputs());
+
+        // Le registre est en lecture seule pour le jeu
+        memory.write_u32(input_data, 0).unwrap();
+        assert_eq!(memory.read_u32(input_data).unwrap(), INPUT_IDLE & !0x01);
+    }
+
+    #[test]
+    fn test_fog_table_registers_and_preset() {
+        let mut memory = Model2Memory::new();
+        assert!(memory.take_fog_table_update().is_none());
+
+        // Quatre entrées par registre, accessibles aussi par octet
+        let fog_table = IO_REGISTERS_BASE + FOG_TABLE_REGISTER;
+        memory.write_u32(fog_table + 4, 0x4030_2010).unwrap();
+        memory.write_u8(fog_table + 0xFF, 0xFF).unwrap();
+        let update = memory.take_fog_table_update().unwrap();
+        assert_eq!(update.len(), FOG_TABLE_LENGTH as usize);
+        assert_eq!(&update[4..8], &[0x10, 0x20, 0x30, 0x40]);
+        assert_eq!(update[0xFF], 0xFF);
+        assert!(memory.take_fog_table_update().is_none());
+        assert_eq!(memory.read_u8(fog_table + 6).unwrap(), 0x30);
+
+        // La table des données du jeu revient après un reset
+        memory.set_fog_table_preset(vec![0x80; FOG_TABLE_LENGTH as usize]);
+        memory.write_u32(fog_table, 0).unwrap();
+        memory.reset_io().unwrap();
+        assert_eq!(memory.take_fog_table_update().unwrap(), &[0x80; FOG_TABLE_LENGTH as usize][..]);
+    }
+}
@@ -0,0 +1,141 @@
+//! Comportement du bus sur les zones non mappées (bus ouvert)
+//!
+//! Sur la carte, une lecture sans périphérique qui répond ne renvoie pas
+//! forcément 0xFF : selon la zone, les lignes de données gardent la dernière
+//! valeur transférée, ou le contrôleur signale une erreur de bus au V60.
+//! Certains jeux s'en servent pour détecter le matériel présent ou comme
+//! temporisation ; le comportement est donc configurable par plage d'adresses.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Comportement d'une zone sans périphérique
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenBusMode {
+    /// Lignes de données tirées à 1 : 0xFF / 0xFFFF / 0xFFFFFFFF
+    #[default]
+    PullUp,
+
+    /// La dernière valeur transférée sur le bus est relue
+    LastValue,
+
+    /// Le contrôleur signale une erreur de bus (exception CPU)
+    BusError,
+}
+
+/// Erreur de bus levée par un accès à une zone non mappée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("erreur de bus ({}) à l'adresse 0x{address:08X}", if *.write { "écriture" } else { "lecture" })]
+pub struct BusError {
+    pub address: u32,
+    pub write: bool,
+}
+
+/// Plage d'adresses avec un comportement de bus ouvert dédié
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenBusRegion {
+    pub start: u32,
+
+    /// Adresse de fin (exclusive)
+    pub end: u32,
+
+    pub mode: OpenBusMode,
+}
+
+/// Comportement du bus ouvert pour tout l'espace d'adressage, déclarable
+/// dans le profil d'un jeu
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenBusPolicy {
+    default: OpenBusMode,
+    regions: Vec<OpenBusRegion>,
+}
+
+impl OpenBusPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Comportement hors des plages déclarées
+    pub fn with_default(mut self, mode: OpenBusMode) -> Self {
+        self.default = mode;
+        self
+    }
+
+    /// Déclare un comportement pour une plage ; la dernière déclaration l'emporte
+    pub fn set_region(&mut self, start: u32, end: u32, mode: OpenBusMode) {
+        self.regions.push(OpenBusRegion { start, end, mode });
+    }
+
+    /// Comportement applicable à une adresse
+    pub fn mode_for(&self, address: u32) -> OpenBusMode {
+        self.regions
+            .iter()
+            .rev()
+            .find(|region| (region.start..region.end).contains(&address))
+            .map_or(self.default, |region| region.mode)
+    }
+
+    /// Valeur lue sur `size` octets à une adresse sans périphérique
+    pub fn read(&self, address: u32, size: u8, last_value: u32) -> Result<u32> {
+        let mask = match size {
+            1 => 0xFF,
+            2 => 0xFFFF,
+            _ => 0xFFFF_FFFF,
+        };
+
+        match self.mode_for(address) {
+            OpenBusMode::PullUp => Ok(mask),
+            OpenBusMode::LastValue => Ok(last_value & mask),
+            OpenBusMode::BusError => Err(BusError { address, write: false }.into()),
+        }
+    }
+
+    /// Écriture vers une zone sans périphérique : ignorée, sauf erreur de bus
+    pub fn write(&self, address: u32) -> Result<()> {
+        match self.mode_for(address) {
+            OpenBusMode::BusError => Err(BusError { address, write: true }.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_pulls_lines_high() {
+        let policy = OpenBusPolicy::new();
+        assert_eq!(policy.read(0x0500_0000, 1, 0x1234).unwrap(), 0xFF);
+        assert_eq!(policy.read(0x0500_0000, 4, 0x1234).unwrap(), 0xFFFF_FFFF);
+        assert!(policy.write(0x0500_0000).is_ok());
+    }
+
+    #[test]
+    fn test_region_overrides() {
+        let mut policy = OpenBusPolicy::new().with_default(OpenBusMode::LastValue);
+        policy.set_region(0x4000_0000, 0x5000_0000, OpenBusMode::BusError);
+
+        assert_eq!(policy.read(0x0500_0000, 2, 0xABCD_1234).unwrap(), 0x1234);
+
+        let err = policy.read(0x4000_0010, 4, 0).unwrap_err();
+        assert_eq!(err.bus_error(), Some(&BusError { address: 0x4000_0010, write: false }));
+        assert!(policy.write(0x4FFF_FFFF).is_err());
+    }
+
+    #[test]
+    fn test_policy_from_game_profile() {
+        let policy: OpenBusPolicy = serde_json::from_str(
+            r#"{ "default": "last_value", "regions": [{ "start": 1073741824, "end": 1342177280, "mode": "bus_error" }] }"#,
+        )
+        .unwrap();
+        assert_eq!(policy.mode_for(0x0500_0000), OpenBusMode::LastValue);
+        assert_eq!(policy.mode_for(0x4000_0000), OpenBusMode::BusError);
+
+        let regions_only: OpenBusPolicy = serde_json::from_str(r#"{ "regions": [] }"#).unwrap();
+        assert_eq!(regions_only, OpenBusPolicy::default());
+    }
+}
@@ -0,0 +1,170 @@
+//! Carte de protection (puces de type 315-5881)
+//!
+//! Plusieurs jeux Model 2 interrogent une puce de protection au démarrage et
+//! bouclent indéfiniment si elle ne répond pas. Le jeu écrit une commande sur
+//! le port de données puis relit la réponse mot par mot. Quand les réponses
+//! d'un jeu sont connues, elles sont décrites dans sa table de la base des
+//! jeux ; sinon la puce est en transit : chaque accès est journalisé et les
+//! lectures renvoient zéro, ce qui suffit à certains jeux pour atteindre le
+//! mode démonstration.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Adresse de la carte de protection dans l'espace du V60
+pub const PROTECTION_BASE: u32 = 0x01D8_0000;
+
+/// Taille de la fenêtre des registres
+pub const PROTECTION_SIZE: u32 = 0x100;
+
+/// Port de données : commande en écriture, réponse en lecture
+pub const PROTECTION_DATA: u32 = 0x00;
+
+/// État : bit 0 à 1 tant qu'une réponse reste à lire
+pub const PROTECTION_STATUS: u32 = 0x04;
+
+/// Réponse connue à une commande
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtectionChallenge {
+    pub command: u32,
+    /// Mots renvoyés successivement par le port de données
+    pub response: Vec<u32>,
+}
+
+/// Protection d'un jeu dans la base des jeux
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtectionConfig {
+    /// Référence de la puce, pour le journal
+    pub chip: String,
+    /// Réponses connues ; vide, la puce est en transit
+    #[serde(default)]
+    pub challenges: Vec<ProtectionChallenge>,
+}
+
+/// Puce de protection émulée ; les lectures passent par `&self`, la
+/// position dans la réponse est donc atomique
+#[derive(Debug, Default)]
+pub struct ProtectionDevice {
+    chip: String,
+    challenges: BTreeMap<u32, Vec<u32>>,
+    /// Réponse en cours de lecture
+    response: Vec<u32>,
+    /// Mots de la réponse déjà lus
+    cursor: AtomicUsize,
+    /// Commandes sans réponse connue, avec leur nombre
+    unknown_commands: BTreeMap<u32, u64>,
+}
+
+impl ProtectionDevice {
+    pub fn new(config: &ProtectionConfig) -> Self {
+        Self {
+            chip: config.chip.clone(),
+            challenges: config.challenges.iter().map(|c| (c.command, c.response.clone())).collect(),
+            response: Vec::new(),
+            cursor: AtomicUsize::new(0),
+            unknown_commands: BTreeMap::new(),
+        }
+    }
+
+    /// Référence de la puce
+    pub fn chip(&self) -> &str {
+        &self.chip
+    }
+
+    /// Vrai sans aucune réponse connue
+    pub fn is_pass_through(&self) -> bool {
+        self.challenges.is_empty()
+    }
+
+    /// Abandonne la réponse en cours (reset de la carte)
+    pub fn reset(&mut self) {
+        self.response.clear();
+        *self.cursor.get_mut() = 0;
+    }
+
+    /// Lecture d'un registre ; le port de données consomme la réponse
+    pub fn read(&self, offset: u32) -> u32 {
+        let cursor = self.cursor.load(Ordering::Relaxed);
+        let value = match offset & !3 {
+            PROTECTION_DATA => match self.response.get(cursor) {
+                Some(&word) => {
+                    self.cursor.store(cursor + 1, Ordering::Relaxed);
+                    word
+                }
+                None => 0,
+            },
+            PROTECTION_STATUS => (cursor < self.response.len()) as u32,
+            _ => 0,
+        };
+        log::trace!("Protection {}: lecture 0x{:02X} -> 0x{:08X}", self.chip, offset, value);
+        value
+    }
+
+    /// Écriture d'un registre ; une commande remplace la réponse en cours
+    pub fn write(&mut self, offset: u32, value: u32) {
+        if offset & !3 != PROTECTION_DATA {
+            log::debug!("Protection {}: écriture ignorée 0x{:02X} <- 0x{:08X}", self.chip, offset, value);
+            return;
+        }
+
+        self.reset();
+        match self.challenges.get(&value) {
+            Some(response) => {
+                log::debug!("Protection {}: commande 0x{:08X}, {} mot(s) de réponse", self.chip, value, response.len());
+                self.response.extend(response);
+            }
+            None => {
+                let count = self.unknown_commands.entry(value).or_insert(0);
+                *count += 1;
+                if *count == 1 {
+                    log::warn!("Protection {}: commande inconnue 0x{:08X}, réponse nulle", self.chip, value);
+                }
+            }
+        }
+    }
+
+    /// Commandes reçues sans réponse connue, avec leur nombre
+    pub fn unknown_commands(&self) -> &BTreeMap<u32, u64> {
+        &self.unknown_commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(challenges: Vec<ProtectionChallenge>) -> ProtectionConfig {
+        ProtectionConfig { chip: "315-5881".to_string(), challenges }
+    }
+
+    #[test]
+    fn test_known_challenge_streams_response() {
+        let mut device = ProtectionDevice::new(&config(vec![ProtectionChallenge { command: 0x1234, response: vec![0xAA, 0xBB] }]));
+        assert!(!device.is_pass_through());
+        assert_eq!(device.read(PROTECTION_STATUS), 0);
+
+        device.write(PROTECTION_DATA, 0x1234);
+        assert_eq!(device.read(PROTECTION_STATUS), 1);
+        assert_eq!(device.read(PROTECTION_DATA), 0xAA);
+        assert_eq!(device.read(PROTECTION_DATA + 2), 0xBB);
+        assert_eq!(device.read(PROTECTION_STATUS), 0);
+        assert_eq!(device.read(PROTECTION_DATA), 0);
+
+        // Une nouvelle commande abandonne la réponse précédente
+        device.write(PROTECTION_DATA, 0x1234);
+        device.write(PROTECTION_DATA, 0x9999);
+        assert_eq!(device.read(PROTECTION_DATA), 0);
+        assert_eq!(device.unknown_commands().get(&0x9999), Some(&1));
+    }
+
+    #[test]
+    fn test_config_from_json() {
+        let json = r#"{"chip": "315-5838", "challenges": [{"command": 1, "response": [2, 3]}]}"#;
+        let parsed: ProtectionConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.challenges[0].response, vec![2, 3]);
+
+        let pass_through: ProtectionConfig = serde_json::from_str(r#"{"chip": "315-5881"}"#).unwrap();
+        assert!(ProtectionDevice::new(&pass_through).is_pass_through());
+    }
+}
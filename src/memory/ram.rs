@@ -1,7 +1,8 @@
 //! Implémentation de la mémoire RAM
 
+use super::endian::ByteOrder;
 use super::interface::MemoryInterface;
-use anyhow::{Result, anyhow};
+use crate::error::{EmulatorError, MemoryFault, Result};
 
 /// Structure représentant une zone de RAM
 #[derive(Debug, Clone)]
@@ -50,13 +51,18 @@ impl Ram {
     /// Charge des données dans la RAM à partir d'un offset
     pub fn load_data(&mut self, offset: usize, data: &[u8]) -> Result<()> {
         if offset + data.len() > self.size {
-            return Err(anyhow!("Tentative de chargement au-delà de la taille de la RAM"));
+            return Err(MemoryFault::OutOfBounds { address: offset as u32, size: data.len(), limit: self.size }.into());
         }
         
         self.data[offset..offset + data.len()].copy_from_slice(data);
         Ok(())
     }
     
+    /// Contenu complet de la RAM
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
     /// Obtient les statistiques d'accès
     pub fn get_stats(&self) -> &AccessStats {
         &self.stats
@@ -66,12 +72,16 @@ impl Ram {
     fn check_bounds(&self, address: u32, size: usize) -> Result<()> {
         let addr = address as usize;
         if addr + size > self.size {
-            Err(anyhow!("Accès mémoire hors limites: {:#08x} + {} > {:#08x}", 
-                       address, size, self.size))
+            Err(self.out_of_bounds(address, size))
         } else {
             Ok(())
         }
     }
+
+    /// Faute d'un accès de `size` octets hors de la RAM
+    fn out_of_bounds(&self, address: u32, size: usize) -> EmulatorError {
+        MemoryFault::OutOfBounds { address, size, limit: self.size }.into()
+    }
 }
 
 impl MemoryInterface for Ram {
@@ -87,28 +97,12 @@ impl MemoryInterface for Ram {
     
     fn read_u16(&self, address: u32) -> Result<u16> {
         self.check_bounds(address, 2)?;
-        let addr = address as usize;
-        
-        // Lecture little-endian
-        let low = self.data[addr] as u16;
-        let high = self.data[addr + 1] as u16;
-        let value = low | (high << 8);
-        
-        Ok(value)
+        ByteOrder::Little.read_u16(&self.data, address as usize).ok_or_else(|| self.out_of_bounds(address, 2))
     }
     
     fn read_u32(&self, address: u32) -> Result<u32> {
         self.check_bounds(address, 4)?;
-        let addr = address as usize;
-        
-        // Lecture little-endian
-        let b0 = self.data[addr] as u32;
-        let b1 = self.data[addr + 1] as u32;
-        let b2 = self.data[addr + 2] as u32;
-        let b3 = self.data[addr + 3] as u32;
-        let value = b0 | (b1 << 8) | (b2 << 16) | (b3 << 24);
-        
-        Ok(value)
+        ByteOrder::Little.read_u32(&self.data, address as usize).ok_or_else(|| self.out_of_bounds(address, 4))
     }
     
     fn write_u8(&mut self, address: u32, value: u8) -> Result<()> {
@@ -121,11 +115,9 @@ impl MemoryInterface for Ram {
     
     fn write_u16(&mut self, address: u32, value: u16) -> Result<()> {
         self.check_bounds(address, 2)?;
-        let addr = address as usize;
-        
-        // Écriture little-endian
-        self.data[addr] = value as u8;
-        self.data[addr + 1] = (value >> 8) as u8;
+        if !ByteOrder::Little.write_u16(&mut self.data, address as usize, value) {
+            return Err(self.out_of_bounds(address, 2));
+        }
         self.stats.record_write(2);
         
         Ok(())
@@ -133,13 +125,9 @@ impl MemoryInterface for Ram {
     
     fn write_u32(&mut self, address: u32, value: u32) -> Result<()> {
         self.check_bounds(address, 4)?;
-        let addr = address as usize;
-        
-        // Écriture little-endian
-        self.data[addr] = value as u8;
-        self.data[addr + 1] = (value >> 8) as u8;
-        self.data[addr + 2] = (value >> 16) as u8;
-        self.data[addr + 3] = (value >> 24) as u8;
+        if !ByteOrder::Little.write_u32(&mut self.data, address as usize, value) {
+            return Err(self.out_of_bounds(address, 4));
+        }
         self.stats.record_write(4);
         
         Ok(())
@@ -1,7 +1,8 @@
 //! Implémentation de la mémoire ROM (Read-Only Memory)
 
+use super::endian::ByteOrder;
 use super::interface::MemoryInterface;
-use anyhow::{Result, anyhow};
+use crate::error::{EmulatorError, MemoryFault, Result, RomError};
 
 /// Structure représentant une zone de ROM
 #[derive(Debug, Clone)]
@@ -89,12 +90,16 @@ impl Rom {
     fn check_bounds(&self, address: u32, size: usize) -> Result<()> {
         let addr = address as usize;
         if addr + size > self.size {
-            Err(anyhow!("Accès ROM hors limites: {:#08x} + {} > {:#08x}", 
-                       address, size, self.size))
+            Err(self.out_of_bounds(address, size))
         } else {
             Ok(())
         }
     }
+
+    /// Faute d'un accès de `size` octets hors de la ROM
+    fn out_of_bounds(&self, address: u32, size: usize) -> EmulatorError {
+        MemoryFault::OutOfBounds { address, size, limit: self.size }.into()
+    }
     
     /// Recherche un pattern de bytes dans la ROM
     pub fn find_pattern(&self, pattern: &[u8]) -> Vec<usize> {
@@ -132,37 +137,25 @@ impl MemoryInterface for Rom {
     
     fn read_u16(&self, address: u32) -> Result<u16> {
         self.check_bounds(address, 2)?;
-        let addr = address as usize;
-        
-        // Lecture little-endian
-        let low = self.data[addr] as u16;
-        let high = self.data[addr + 1] as u16;
-        Ok(low | (high << 8))
+        ByteOrder::Little.read_u16(&self.data, address as usize).ok_or_else(|| self.out_of_bounds(address, 2))
     }
     
     fn read_u32(&self, address: u32) -> Result<u32> {
         self.check_bounds(address, 4)?;
-        let addr = address as usize;
-        
-        // Lecture little-endian
-        let b0 = self.data[addr] as u32;
-        let b1 = self.data[addr + 1] as u32;
-        let b2 = self.data[addr + 2] as u32;
-        let b3 = self.data[addr + 3] as u32;
-        Ok(b0 | (b1 << 8) | (b2 << 16) | (b3 << 24))
+        ByteOrder::Little.read_u32(&self.data, address as usize).ok_or_else(|| self.out_of_bounds(address, 4))
     }
     
-    fn write_u8(&mut self, _address: u32, _value: u8) -> Result<()> {
+    fn write_u8(&mut self, address: u32, _value: u8) -> Result<()> {
         // Les ROMs sont en lecture seule
-        Err(anyhow!("Tentative d'écriture dans une ROM"))
+        Err(MemoryFault::ReadOnly { address }.into())
     }
     
-    fn write_u16(&mut self, _address: u32, _value: u16) -> Result<()> {
-        Err(anyhow!("Tentative d'écriture dans une ROM"))
+    fn write_u16(&mut self, address: u32, _value: u16) -> Result<()> {
+        Err(MemoryFault::ReadOnly { address }.into())
     }
     
-    fn write_u32(&mut self, _address: u32, _value: u32) -> Result<()> {
-        Err(anyhow!("Tentative d'écriture dans une ROM"))
+    fn write_u32(&mut self, address: u32, _value: u32) -> Result<()> {
+        Err(MemoryFault::ReadOnly { address }.into())
     }
     
     fn read_block(&self, address: u32, size: usize) -> Result<Vec<u8>> {
@@ -171,12 +164,12 @@ impl MemoryInterface for Rom {
         Ok(self.data[addr..addr + size].to_vec())
     }
     
-    fn write_block(&mut self, _address: u32, _data: &[u8]) -> Result<()> {
-        Err(anyhow!("Tentative d'écriture dans une ROM"))
+    fn write_block(&mut self, address: u32, _data: &[u8]) -> Result<()> {
+        Err(MemoryFault::ReadOnly { address }.into())
     }
     
-    fn fill(&mut self, _address: u32, _size: usize, _value: u8) -> Result<()> {
-        Err(anyhow!("Tentative d'écriture dans une ROM"))
+    fn fill(&mut self, address: u32, _size: usize, _value: u8) -> Result<()> {
+        Err(MemoryFault::ReadOnly { address }.into())
     }
 }
 
@@ -229,7 +222,7 @@ impl RomSet {
     pub fn verify_completeness(&self) -> Result<()> {
         for required_rom in &self.game_info.required_roms {
             if !self.roms.contains_key(required_rom) {
-                return Err(anyhow!("ROM manquante: {}", required_rom));
+                return Err(RomError::Missing(required_rom.clone()).into());
             }
         }
         Ok(())
@@ -239,7 +232,7 @@ impl RomSet {
     pub fn verify_integrity(&self) -> Result<()> {
         for (name, rom) in &self.roms {
             if !rom.verify_integrity() {
-                return Err(anyhow!("ROM corrompue: {}", name));
+                return Err(RomError::Corrupt(name.clone()).into());
             }
         }
         Ok(())
@@ -0,0 +1,360 @@
+//! Horloge temps réel (RTC) de la carte I/O
+//!
+//! Les écrans de comptabilité horodatent les parties et les remises à zéro
+//! des compteurs avec une horloge RTC-72421 : seize registres de 4 bits, un
+//! chiffre décimal par registre (secondes, minutes, heures, jour, mois,
+//! année, jour de la semaine) suivis de trois registres de contrôle. Ils
+//! occupent un mot de 32 bits chacun dans les registres I/O, à partir de
+//! [`RTC_REGISTER_BASE`].
+//!
+//! L'horloge émulée suit l'heure de l'hôte décalée de `offset` secondes :
+//! régler l'heure depuis le jeu ne change que le décalage. Celui-ci est
+//! conservé avec la NVRAM du jeu (fichier `<jeu>.rtc`), comme la pile de la
+//! borne conserve l'heure, et dans les états de sauvegarde. Les bits HOLD
+//! (registre D) et STOP (registre F) figent les chiffres : le jeu les lit ou
+//! les réécrit un par un, et l'heure réglée prend effet à la libération.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::clock::{SystemTime, UNIX_EPOCH};
+use crate::error::Result;
+
+/// Premier registre de l'horloge dans les registres I/O
+pub const RTC_REGISTER_BASE: u32 = 0x80;
+
+/// Registres de l'horloge (chiffres puis contrôle)
+pub const RTC_REGISTER_COUNT: usize = 16;
+
+const RTC_REGISTER_END: u32 = RTC_REGISTER_BASE + RTC_REGISTER_COUNT as u32 * 4;
+
+/// Registres des chiffres : secondes, minutes, heures, jour, mois et année
+/// (unités puis dizaines), puis jour de la semaine (0 = dimanche)
+const RTC_DIGITS: usize = 13;
+
+/// Registres de contrôle
+pub const RTC_CONTROL_D: usize = 0xD;
+pub const RTC_CONTROL_E: usize = 0xE;
+pub const RTC_CONTROL_F: usize = 0xF;
+
+/// Registre D : chiffres figés pour la lecture ou le réglage
+pub const RTC_HOLD: u8 = 0x1;
+/// Registre D : mise à jour des chiffres en cours (jamais signalée ici)
+pub const RTC_BUSY: u8 = 0x2;
+/// Registre D : drapeau d'interruption périodique
+pub const RTC_IRQ_FLAG: u8 = 0x4;
+/// Registre D : arrondi à la minute la plus proche (±30 s), remis à zéro seul
+pub const RTC_ADJUST: u8 = 0x8;
+
+/// Registre F : remise à zéro du diviseur (sans effet ici)
+pub const RTC_RESET: u8 = 0x1;
+/// Registre F : horloge arrêtée
+pub const RTC_STOP: u8 = 0x2;
+/// Registre F : heures sur 24 h ; sinon sur 12 h, bit 2 des dizaines = PM
+pub const RTC_24H: u8 = 0x4;
+
+/// Bit PM du registre des dizaines d'heures en mode 12 h
+const PM_FLAG: u8 = 0x4;
+
+/// Extension du fichier de l'horloge dans le répertoire NVRAM
+pub const RTC_FILE_EXTENSION: &str = "rtc";
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Registre de l'horloge correspondant à un offset des registres I/O
+pub fn rtc_register(offset: u32) -> Option<usize> {
+    (RTC_REGISTER_BASE..RTC_REGISTER_END)
+        .contains(&offset)
+        .then(|| ((offset - RTC_REGISTER_BASE) / 4) as usize)
+}
+
+/// Heure de l'hôte, en secondes Unix
+fn host_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+/// Horloge RTC-72421
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RtcDevice {
+    /// Décalage en secondes entre l'heure du jeu et celle de l'hôte
+    pub offset: i64,
+
+    /// Registres de contrôle D, E et F
+    control: [u8; 3],
+
+    /// Chiffres figés pendant HOLD ou STOP, éventuellement réécrits par le jeu
+    held: Option<[u8; RTC_DIGITS]>,
+}
+
+impl Default for RtcDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RtcDevice {
+    /// Horloge à l'heure de l'hôte, en mode 24 h
+    pub fn new() -> Self {
+        Self {
+            offset: 0,
+            control: [0, 0, RTC_24H],
+            held: None,
+        }
+    }
+
+    /// Heure vue par le jeu, en secondes Unix
+    pub fn now(&self) -> i64 {
+        self.now_at(host_now())
+    }
+
+    /// Lit un registre (4 bits)
+    pub fn read(&self, register: usize) -> u32 {
+        self.read_at(register, host_now())
+    }
+
+    /// Écrit un registre ; seuls les 4 bits de poids faible comptent
+    pub fn write(&mut self, register: usize, value: u32) {
+        self.write_at(register, value, host_now());
+    }
+
+    fn now_at(&self, host: i64) -> i64 {
+        match &self.held {
+            Some(digits) => self.time_from_digits(digits),
+            None => host + self.offset,
+        }
+    }
+
+    fn read_at(&self, register: usize, host: i64) -> u32 {
+        let value = match register {
+            RTC_CONTROL_D..=RTC_CONTROL_F => self.control[register - RTC_CONTROL_D],
+            _ if register < RTC_DIGITS => match &self.held {
+                Some(digits) => digits[register],
+                None => self.digits(host + self.offset)[register],
+            },
+            _ => 0,
+        };
+        value as u32
+    }
+
+    fn write_at(&mut self, register: usize, value: u32, host: i64) {
+        let value = (value & 0xF) as u8;
+        match register {
+            RTC_CONTROL_D => {
+                if value & RTC_ADJUST != 0 {
+                    let now = self.now_at(host);
+                    let rounded = now - now.rem_euclid(60) + if now.rem_euclid(60) >= 30 { 60 } else { 0 };
+                    self.set_time_at(rounded, host);
+                }
+                // BUSY est en lecture seule, l'arrondi se remet à zéro seul
+                self.control[0] = value & (RTC_HOLD | RTC_IRQ_FLAG);
+                self.update_hold(host);
+            }
+            RTC_CONTROL_E => self.control[1] = value,
+            RTC_CONTROL_F => {
+                self.control[2] = value;
+                self.update_hold(host);
+            }
+            _ if register < RTC_DIGITS => {
+                let mut digits = match self.held {
+                    Some(digits) => digits,
+                    None => self.digits(host + self.offset),
+                };
+                digits[register] = value;
+                match &mut self.held {
+                    Some(held) => *held = digits,
+                    None => self.offset = self.time_from_digits(&digits) - host,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Règle l'heure vue par le jeu
+    fn set_time_at(&mut self, time: i64, host: i64) {
+        match &mut self.held {
+            Some(_) => self.held = Some(self.digits(time)),
+            None => self.offset = time - host,
+        }
+    }
+
+    /// Fige les chiffres à l'activation de HOLD ou STOP ; à la libération,
+    /// l'heure éventuellement réglée devient le nouveau décalage
+    fn update_hold(&mut self, host: i64) {
+        let frozen = self.control[0] & RTC_HOLD != 0 || self.control[2] & RTC_STOP != 0;
+        match (frozen, self.held) {
+            (true, None) => self.held = Some(self.digits(host + self.offset)),
+            (false, Some(digits)) => {
+                self.offset = self.time_from_digits(&digits) - host;
+                self.held = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn is_24h(&self) -> bool {
+        self.control[2] & RTC_24H != 0
+    }
+
+    /// Chiffres des registres pour une heure donnée
+    fn digits(&self, time: i64) -> [u8; RTC_DIGITS] {
+        let days = time.div_euclid(SECONDS_PER_DAY);
+        let seconds = time.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        let (hour_units, hour_tens) = if self.is_24h() {
+            (hour % 10, hour / 10)
+        } else {
+            let hour12 = if hour % 12 == 0 { 12 } else { hour % 12 };
+            (hour12 % 10, (hour12 / 10) | if hour >= 12 { PM_FLAG as i64 } else { 0 })
+        };
+        let year = year.rem_euclid(100);
+        // 1er janvier 1970 : un jeudi
+        let weekday = (days + 4).rem_euclid(7);
+
+        [
+            second % 10, second / 10,
+            minute % 10, minute / 10,
+            hour_units, hour_tens,
+            day as i64 % 10, day as i64 / 10,
+            month as i64 % 10, month as i64 / 10,
+            year % 10, year / 10,
+            weekday,
+        ]
+        .map(|digit| digit as u8)
+    }
+
+    /// Heure désignée par des chiffres, ramenés dans leurs bornes ; le jour
+    /// de la semaine est déduit de la date. Les années 70 à 99 sont au
+    /// XXe siècle, les autres au XXIe.
+    fn time_from_digits(&self, digits: &[u8; RTC_DIGITS]) -> i64 {
+        let pair = |units: usize| digits[units + 1] as i64 * 10 + digits[units] as i64;
+        let second = pair(0).min(59);
+        let minute = pair(2).min(59);
+        let hour = if self.is_24h() {
+            pair(4).min(23)
+        } else {
+            let hour12 = ((digits[5] & !PM_FLAG) as i64 * 10 + digits[4] as i64).clamp(1, 12);
+            hour12 % 12 + if digits[5] & PM_FLAG != 0 { 12 } else { 0 }
+        };
+        let year = pair(10).min(99);
+        let year = year + if year >= 70 { 1900 } else { 2000 };
+        let month = pair(8).clamp(1, 12) as u32;
+        let day = pair(6).clamp(1, days_in_month(year, month) as i64) as u32;
+
+        days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second
+    }
+
+    /// Fichier de l'horloge d'un jeu dans le répertoire NVRAM
+    pub fn file_path(nvram_dir: &Path, game: &str) -> PathBuf {
+        nvram_dir.join(format!("{}.{}", game, RTC_FILE_EXTENSION))
+    }
+
+    /// Charge l'horloge d'un jeu (heure de l'hôte si absente)
+    pub fn load(nvram_dir: &Path, game: &str) -> Self {
+        fs::read_to_string(Self::file_path(nvram_dir, game))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Enregistre l'horloge d'un jeu ; une heure en cours de réglage est
+    /// appliquée et l'horloge repart à la session suivante
+    pub fn save(&self, nvram_dir: &Path, game: &str) -> Result<()> {
+        let mut settled = self.clone();
+        settled.control[0] &= !RTC_HOLD;
+        settled.control[2] &= !RTC_STOP;
+        settled.update_hold(host_now());
+
+        fs::create_dir_all(nvram_dir)?;
+        let json = serde_json::to_string_pretty(&settled).map_err(std::io::Error::other)?;
+        fs::write(Self::file_path(nvram_dir, game), json)?;
+        Ok(())
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Jours depuis le 1er janvier 1970 (calendrier grégorien proleptique)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date (année, mois, jour) d'un nombre de jours depuis le 1er janvier 1970
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = (if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 }) as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dimanche 9 septembre 2001, 01:46:40 UTC
+    const HOST: i64 = 1_000_000_000;
+
+    fn read_all(rtc: &RtcDevice, host: i64) -> Vec<u32> {
+        (0..RTC_DIGITS).map(|register| rtc.read_at(register, host)).collect()
+    }
+
+    #[test]
+    fn test_rtc_reads_sets_and_persists_time() {
+        let mut rtc = RtcDevice::new();
+        assert_eq!(read_all(&rtc, HOST), vec![0, 4, 6, 4, 1, 0, 9, 0, 9, 0, 1, 0, 0]);
+
+        // Réglage sous HOLD : les chiffres restent figés jusqu'à la libération
+        rtc.write_at(RTC_CONTROL_D, RTC_HOLD as u32, HOST);
+        for (register, digit) in [(6, 1), (7, 3), (8, 2), (9, 1), (10, 8), (11, 9)] {
+            rtc.write_at(register, digit, HOST);
+        }
+        assert_eq!(rtc.read_at(0, HOST + 5), 0);
+        rtc.write_at(RTC_CONTROL_D, 0, HOST + 10);
+
+        // Jeudi 31 décembre 1998, l'horloge continue depuis 01:46:40
+        assert_eq!(read_all(&rtc, HOST + 15), vec![5, 4, 6, 4, 1, 0, 1, 3, 2, 1, 8, 9, 4]);
+        assert_eq!(rtc.now_at(HOST + 10), days_from_civil(1998, 12, 31) * SECONDS_PER_DAY + 6400);
+
+        // Mode 12 h et arrondi à la minute
+        rtc.write_at(RTC_CONTROL_F, 0, HOST);
+        rtc.write_at(RTC_CONTROL_D, RTC_ADJUST as u32, HOST + 28);
+        assert_eq!(read_all(&rtc, HOST + 28)[..6], [0, 0, 7, 4, 1, 0]);
+        rtc.write_at(5, (1 | PM_FLAG) as u32, HOST + 28);
+        assert_eq!(rtc.now_at(HOST + 28).rem_euclid(SECONDS_PER_DAY), 23 * 3600 + 47 * 60);
+
+        // Le décalage est conservé avec la NVRAM
+        let nvram = tempfile::tempdir().unwrap();
+        rtc.save(nvram.path(), "daytona").unwrap();
+        let restored = RtcDevice::load(nvram.path(), "daytona");
+        assert_eq!(restored.offset, rtc.offset);
+        assert_eq!(RtcDevice::load(nvram.path(), "vf2"), RtcDevice::new());
+        assert_eq!(rtc_register(RTC_REGISTER_BASE + 0x34), Some(RTC_CONTROL_D));
+        assert_eq!(rtc_register(RTC_REGISTER_END), None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Communication entre le CPU principal et le CPU audio
+//!
+//! Le programme du V60 envoie ses commandes sonores (musique, effets) au
+//! 68000 de la carte son par un port série relié à l'entrée MIDI du SCSP ;
+//! le 68000 répond par la sortie MIDI. Chaque sens passe par une file de
+//! quatre octets, comme les tampons MIDI du SCSP.
+//!
+//! Côté V60, deux registres : données (commande en écriture, réponse en
+//! lecture) et état. Côté 68000, le registre MIDI du SCSP donne la commande
+//! et l'état des files, et un second reçoit la réponse. Une commande en
+//! attente maintient la ligne d'interruption du 68000 ; chaque commande lue
+//! et chaque réponse écrite par le 68000 signalent une interruption audio au
+//! V60 (acquittement).
+//!
+//! Sans émulation du 68000, le mode HLE du processeur sonore
+//! ([`SoundHle`](crate::audio::SoundHle)) lit les commandes à sa place.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Adresse du port série de la carte son dans l'espace du V60
+pub const SOUND_LATCH_BASE: u32 = 0x01C8_0000;
+
+/// Taille de la fenêtre des registres
+pub const SOUND_LATCH_SIZE: u32 = 0x10;
+
+/// Port de données : commande en écriture, réponse en lecture
+pub const SOUND_LATCH_DATA: u32 = 0x00;
+
+/// État : voir [`SOUND_STATUS_TX_READY`] et [`SOUND_STATUS_RX_READY`]
+pub const SOUND_LATCH_STATUS: u32 = 0x04;
+
+/// Une commande peut être envoyée (file non pleine)
+pub const SOUND_STATUS_TX_READY: u32 = 0x01;
+
+/// Une réponse du 68000 attend d'être lue
+pub const SOUND_STATUS_RX_READY: u32 = 0x02;
+
+/// Registre MIDI du SCSP côté 68000 : commande (MIBUF) et état en lecture
+pub const SCSP_MIDI_INPUT: u32 = 0x00;
+
+/// Registre MIDI du SCSP côté 68000 : réponse (MOBUF) en écriture
+pub const SCSP_MIDI_OUTPUT: u32 = 0x02;
+
+/// File d'entrée vide (MIEMP)
+pub const MIDI_INPUT_EMPTY: u16 = 0x0100;
+
+/// File d'entrée pleine (MIFULL)
+pub const MIDI_INPUT_FULL: u16 = 0x0200;
+
+/// Commande perdue, file d'entrée déjà pleine (MIOVF)
+pub const MIDI_INPUT_OVERFLOW: u16 = 0x0400;
+
+/// File de sortie vide (MOEMP)
+pub const MIDI_OUTPUT_EMPTY: u16 = 0x0800;
+
+/// File de sortie pleine (MOFULL)
+pub const MIDI_OUTPUT_FULL: u16 = 0x1000;
+
+/// Profondeur des files MIDI du SCSP
+const MIDI_FIFO_DEPTH: usize = 4;
+
+/// Files de commandes et de réponses entre le V60 et le 68000
+#[derive(Debug, Default)]
+pub struct SoundLatch {
+    /// Commandes du V60 pas encore lues par le 68000
+    commands: VecDeque<u8>,
+    /// Réponses du 68000 pas encore lues par le V60 : file circulaire dont
+    /// la tête et la longueur sont atomiques, les lectures du V60 passant
+    /// par `&self`
+    responses: [u8; MIDI_FIFO_DEPTH],
+    response_head: AtomicUsize,
+    response_count: AtomicUsize,
+    /// Commande perdue depuis la dernière lecture du 68000
+    overflow: bool,
+    /// Acquittement à signaler au V60
+    main_irq: bool,
+}
+
+impl SoundLatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Vide les files et retire les interruptions (reset de la carte)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Réponses en attente
+    fn responses_len(&self) -> usize {
+        self.response_count.load(Ordering::Relaxed)
+    }
+
+    /// Retire la plus ancienne réponse
+    fn pop_response(&self) -> Option<u8> {
+        let count = self.response_count.load(Ordering::Relaxed);
+        if count == 0 {
+            return None;
+        }
+        let head = self.response_head.load(Ordering::Relaxed);
+        self.response_head.store((head + 1) % MIDI_FIFO_DEPTH, Ordering::Relaxed);
+        self.response_count.store(count - 1, Ordering::Relaxed);
+        Some(self.responses[head])
+    }
+
+    /// Lecture d'un registre côté V60 ; le port de données consomme la réponse
+    pub fn main_read(&self, offset: u32) -> u32 {
+        match offset & !3 {
+            SOUND_LATCH_DATA => self.pop_response().unwrap_or(0) as u32,
+            SOUND_LATCH_STATUS => {
+                let tx = if self.commands.len() < MIDI_FIFO_DEPTH { SOUND_STATUS_TX_READY } else { 0 };
+                let rx = if self.responses_len() == 0 { 0 } else { SOUND_STATUS_RX_READY };
+                tx | rx
+            }
+            _ => 0,
+        }
+    }
+
+    /// Écriture d'un registre côté V60 ; le port de données envoie une commande
+    pub fn main_write(&mut self, offset: u32, value: u32) {
+        if offset & !3 != SOUND_LATCH_DATA {
+            log::debug!("Carte son: écriture ignorée 0x{:02X} <- 0x{:08X}", offset, value);
+            return;
+        }
+
+        if self.commands.len() < MIDI_FIFO_DEPTH {
+            log::trace!("Carte son: commande 0x{:02X}", value as u8);
+            self.commands.push_back(value as u8);
+        } else {
+            log::warn!("Carte son: file pleine, commande 0x{:02X} perdue", value as u8);
+            self.overflow = true;
+        }
+    }
+
+    /// Lecture d'un registre côté 68000 ; l'entrée MIDI consomme la commande
+    /// et acquitte auprès du V60
+    pub fn sound_read(&mut self, offset: u32) -> u16 {
+        if offset & !1 != SCSP_MIDI_INPUT {
+            return 0;
+        }
+
+        // La file était pleine au moment de la lecture
+        let full = self.commands.len() == MIDI_FIFO_DEPTH;
+        let command = self.commands.pop_front();
+        let mut value = command.unwrap_or(0) as u16;
+        if self.commands.is_empty() {
+            value |= MIDI_INPUT_EMPTY;
+        }
+        if full {
+            value |= MIDI_INPUT_FULL;
+        }
+        if std::mem::take(&mut self.overflow) {
+            value |= MIDI_INPUT_OVERFLOW;
+        }
+        if self.responses_len() == 0 {
+            value |= MIDI_OUTPUT_EMPTY;
+        }
+        if self.responses_len() == MIDI_FIFO_DEPTH {
+            value |= MIDI_OUTPUT_FULL;
+        }
+        if command.is_some() {
+            self.main_irq = true;
+        }
+        value
+    }
+
+    /// Écriture d'un registre côté 68000 ; la sortie MIDI envoie une réponse
+    pub fn sound_write(&mut self, offset: u32, value: u16) {
+        if offset & !1 != SCSP_MIDI_OUTPUT {
+            return;
+        }
+
+        let count = *self.response_count.get_mut();
+        if count < MIDI_FIFO_DEPTH {
+            self.responses[(*self.response_head.get_mut() + count) % MIDI_FIFO_DEPTH] = value as u8;
+            *self.response_count.get_mut() = count + 1;
+            self.main_irq = true;
+        } else {
+            log::warn!("Carte son: réponse 0x{:02X} perdue, non lue par le CPU principal", value as u8);
+        }
+    }
+
+    /// Ligne d'interruption du 68000 : active tant qu'une commande attend
+    pub fn sound_irq(&self) -> bool {
+        !self.commands.is_empty()
+    }
+
+    /// Retire l'acquittement en attente pour le V60 ; vrai s'il y en avait un
+    pub fn take_main_irq(&mut self) -> bool {
+        std::mem::take(&mut self.main_irq)
+    }
+
+    /// Commandes pas encore lues par le 68000
+    pub fn pending_commands(&self) -> impl Iterator<Item = u8> + '_ {
+        self.commands.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_and_response_handshake() {
+        let mut latch = SoundLatch::new();
+        assert_eq!(latch.main_read(SOUND_LATCH_STATUS), SOUND_STATUS_TX_READY);
+        assert!(!latch.sound_irq());
+
+        // Le V60 remplit la file : la cinquième commande est perdue
+        for command in 0x10..0x15 {
+            latch.main_write(SOUND_LATCH_DATA, command);
+        }
+        assert_eq!(latch.main_read(SOUND_LATCH_STATUS), 0);
+        assert!(latch.sound_irq());
+        assert!(!latch.take_main_irq());
+
+        // Le 68000 lit la première commande, ce qui acquitte auprès du V60
+        let value = latch.sound_read(SCSP_MIDI_INPUT);
+        assert_eq!(value & 0xFF, 0x10);
+        assert_eq!(value & !0xFF, MIDI_INPUT_FULL | MIDI_INPUT_OVERFLOW | MIDI_OUTPUT_EMPTY);
+        assert!(latch.take_main_irq());
+        assert!(!latch.take_main_irq());
+        for _ in 0..3 {
+            latch.sound_read(SCSP_MIDI_INPUT);
+        }
+        assert!(!latch.sound_irq());
+        assert_eq!(latch.sound_read(SCSP_MIDI_INPUT), MIDI_INPUT_EMPTY | MIDI_OUTPUT_EMPTY);
+
+        // Réponse du 68000
+        latch.take_main_irq();
+        latch.sound_write(SCSP_MIDI_OUTPUT, 0x80);
+        assert!(latch.take_main_irq());
+        assert_eq!(latch.main_read(SOUND_LATCH_STATUS), SOUND_STATUS_TX_READY | SOUND_STATUS_RX_READY);
+        assert_eq!(latch.main_read(SOUND_LATCH_DATA), 0x80);
+        assert_eq!(latch.main_read(SOUND_LATCH_STATUS), SOUND_STATUS_TX_READY);
+    }
+}
@@ -0,0 +1,129 @@
+//! Timers de la carte I/O
+//!
+//! Deux compteurs libres avancent avec le temps émulé, daté par
+//! l'ordonnanceur du cœur : le timer principal à la fréquence du CPU, le
+//! timer de sous-système quatre fois plus lentement. Ils comptent aussi
+//! quand le CPU est arrêté. Le jeu peut les recharger à tout moment : en les
+//! rechargeant à `-période`, il obtient une interruption au débordement,
+//! datée par l'ordonnanceur si elle est autorisée dans le registre de
+//! contrôle des interruptions.
+
+use serde::{Deserialize, Serialize};
+
+/// Timer principal
+pub const TIMER_MAIN_REGISTER: u32 = 0x10;
+
+/// Timer de sous-système
+pub const TIMER_SUB_REGISTER: u32 = 0x14;
+
+/// Diviseur du timer de sous-système
+const TIMER_SUB_DIVIDER: u32 = 4;
+
+/// Registres des timers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timers {
+    /// Timer principal (0xC0000010)
+    #[serde(rename = "timer_main")]
+    pub main: u32,
+
+    /// Timer de sous-système (0xC0000014)
+    #[serde(rename = "timer_sub")]
+    pub sub: u32,
+
+    /// Cycles écoulés depuis la mise sous tension
+    #[serde(rename = "cycle_counter")]
+    cycles: u64,
+}
+
+impl Timers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cycles écoulés depuis la mise sous tension
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Lit un registre (offset aligné sur 4) ; None hors des timers
+    pub fn read(&self, offset: u32) -> Option<u32> {
+        match offset {
+            TIMER_MAIN_REGISTER => Some(self.main),
+            TIMER_SUB_REGISTER => Some(self.sub),
+            _ => None,
+        }
+    }
+
+    /// Recharge un timer (offset aligné sur 4) ; faux hors des timers
+    pub fn write(&mut self, offset: u32, value: u32) -> bool {
+        match offset {
+            TIMER_MAIN_REGISTER => self.main = value,
+            TIMER_SUB_REGISTER => self.sub = value,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Cycles avant le prochain débordement du timer principal, puis de
+    /// celui de sous-système
+    pub fn cycles_until_overflow(&self) -> (u64, u64) {
+        let divider = TIMER_SUB_DIVIDER as u64;
+        let main = (1u64 << 32) - self.main as u64;
+        // Le premier pas du timer de sous-système tombe au prochain multiple
+        // du diviseur
+        let sub = ((1u64 << 32) - self.sub as u64 - 1) * divider + divider - self.cycles % divider;
+        (main, sub)
+    }
+
+    /// Fait avancer les timers de `cycles` cycles ; le timer de
+    /// sous-système suit le total, sans perdre le reste de la division
+    /// quand les avances sont courtes
+    pub fn update(&mut self, cycles: u32) {
+        let divider = TIMER_SUB_DIVIDER as u64;
+        let previous = self.cycles;
+        self.cycles = self.cycles.wrapping_add(cycles as u64);
+        self.main = self.main.wrapping_add(cycles);
+        self.sub = self.sub.wrapping_add((self.cycles / divider).wrapping_sub(previous / divider) as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timers_count_cycles_and_reload() {
+        let mut timers = Timers::new();
+        timers.update(100);
+        assert_eq!((timers.read(TIMER_MAIN_REGISTER), timers.read(TIMER_SUB_REGISTER)), (Some(100), Some(25)));
+
+        assert!(timers.write(TIMER_MAIN_REGISTER, u32::MAX));
+        assert!(!timers.write(0x18, 1));
+        assert_eq!(timers.read(0x18), None);
+        timers.update(2);
+        assert_eq!((timers.main, timers.sub, timers.cycles()), (1, 25, 102));
+
+        // Avances d'un cycle : le timer de sous-système compte quand même
+        for _ in 0..6 {
+            timers.update(1);
+        }
+        assert_eq!((timers.sub, timers.cycles()), (27, 108));
+    }
+
+    #[test]
+    fn test_cycles_until_overflow() {
+        let mut timers = Timers::new();
+        timers.write(TIMER_MAIN_REGISTER, 100u32.wrapping_neg());
+        timers.write(TIMER_SUB_REGISTER, 10u32.wrapping_neg());
+        timers.update(3);
+        assert_eq!(timers.cycles_until_overflow(), (97, 37));
+
+        timers.update(36);
+        assert_eq!(timers.sub, u32::MAX);
+        timers.update(1);
+        assert_eq!(timers.sub, 0);
+        timers.update(60);
+        assert_eq!(timers.main, 0);
+    }
+}
@@ -0,0 +1,283 @@
+//! Surveillance de valeurs en mémoire
+//!
+//! Une [`MemoryWatch`] désigne une valeur du jeu (octet, demi-mot ou mot à
+//! une adresse). Un [`WatchSet`] relève toutes ses valeurs une fois par
+//! frame et garde celles de la frame précédente, ce qui permet de comparer
+//! une valeur à elle-même dans le temps (« le score vient d'augmenter »).
+//! Les [`WatchCondition`] combinent ces comparaisons avec des opérateurs logiques.
+//! Les relevés lisent la mémoire de stockage, sans effet de bord sur le bus.
+//!
+//! Les outils qui suivent les accès du CPU eux-mêmes (carte de chaleur,
+//! télémétrie) se branchent sur le bus comme [`AccessObserver`] : un seul
+//! point d'accroche, quel que soit le nombre d'outils.
+//!
+//! Ces briques ne dépendent d'aucun frontend : elles servent aux succès du
+//! cœur d'émulation et peuvent servir à un moteur de codes de triche.
+
+use crate::error::Result;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::interface::MemoryInterface;
+
+/// Sens d'un accès du CPU au bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+/// Outil prévenu de chaque accès du CPU au bus ; les lectures sans effet de
+/// bord (surveillances, débogueur) ne sont pas signalées
+pub trait AccessObserver: fmt::Debug + Send + Sync {
+    fn on_access(&self, address: u32, size: u8, kind: AccessKind);
+}
+
+/// Largeur de la valeur surveillée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WatchSize {
+    #[default]
+    U8,
+    U16,
+    U32,
+}
+
+impl WatchSize {
+    pub fn name(self) -> &'static str {
+        match self {
+            WatchSize::U8 => "u8",
+            WatchSize::U16 => "u16",
+            WatchSize::U32 => "u32",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "u8" => Some(WatchSize::U8),
+            "u16" => Some(WatchSize::U16),
+            "u32" => Some(WatchSize::U32),
+            _ => None,
+        }
+    }
+}
+
+/// Valeur du jeu à une adresse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoryWatch {
+    pub address: u32,
+    pub size: WatchSize,
+}
+
+impl MemoryWatch {
+    pub fn new(address: u32, size: WatchSize) -> Self {
+        Self { address, size }
+    }
+
+    /// Lit la valeur dans la mémoire de stockage, sans effet de bord sur le
+    /// bus : le jeu ne voit pas les relevés
+    pub fn read<M: MemoryInterface + ?Sized>(&self, memory: &M) -> Result<u32> {
+        match self.size {
+            WatchSize::U8 => memory.peek_u8(self.address).map(u32::from),
+            WatchSize::U16 => memory.peek_u16(self.address).map(u32::from),
+            WatchSize::U32 => memory.peek_u32(self.address),
+        }
+    }
+}
+
+impl fmt::Display for MemoryWatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[0x{:08X}]", self.size.name(), self.address)
+    }
+}
+
+/// Valeurs surveillées, relevées une fois par frame
+#[derive(Debug, Clone, Default)]
+pub struct WatchSet {
+    watches: Vec<MemoryWatch>,
+    indices: HashMap<MemoryWatch, usize>,
+    current: Vec<u32>,
+    previous: Vec<u32>,
+
+    /// Au moins un relevé a eu lieu depuis le dernier ajout
+    primed: bool,
+}
+
+impl WatchSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute une valeur à surveiller ; retourne son indice, partagé si elle
+    /// l'est déjà
+    pub fn add(&mut self, watch: MemoryWatch) -> usize {
+        if let Some(&index) = self.indices.get(&watch) {
+            return index;
+        }
+        let index = self.watches.len();
+        self.watches.push(watch);
+        self.indices.insert(watch, index);
+        self.current.push(0);
+        self.previous.push(0);
+        self.primed = false;
+        index
+    }
+
+    pub fn watches(&self) -> &[MemoryWatch] {
+        &self.watches
+    }
+
+    pub fn len(&self) -> usize {
+        self.watches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+
+    /// Relève toutes les valeurs ; celles de la frame précédente sont
+    /// conservées. Au premier relevé, la valeur précédente est la valeur
+    /// courante, pour qu'aucune variation ne soit détectée au démarrage.
+    /// Une valeur illisible garde son dernier relevé ; la première erreur
+    /// est retournée après la lecture des autres.
+    pub fn update<M: MemoryInterface + ?Sized>(&mut self, memory: &M) -> Result<()> {
+        std::mem::swap(&mut self.current, &mut self.previous);
+        let mut first_error = None;
+        for (index, watch) in self.watches.iter().enumerate() {
+            match watch.read(memory) {
+                Ok(value) => self.current[index] = value,
+                Err(e) => {
+                    self.current[index] = self.previous[index];
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if !self.primed {
+            self.previous.copy_from_slice(&self.current);
+            self.primed = true;
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Valeur relevée à la dernière frame
+    pub fn current(&self, index: usize) -> u32 {
+        self.current[index]
+    }
+
+    /// Valeur relevée à la frame d'avant
+    pub fn previous(&self, index: usize) -> u32 {
+        self.previous[index]
+    }
+}
+
+/// Opérateur de comparaison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    pub fn apply(self, left: u32, right: u32) -> bool {
+        match self {
+            CompareOp::Eq => left == right,
+            CompareOp::Ne => left != right,
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+}
+
+/// Terme d'une comparaison ; les valeurs surveillées sont des indices du
+/// [`WatchSet`] de la condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchOperand {
+    Value(u32),
+    Current(usize),
+    Previous(usize),
+}
+
+impl WatchOperand {
+    pub fn value(self, watches: &WatchSet) -> u32 {
+        match self {
+            WatchOperand::Value(value) => value,
+            WatchOperand::Current(index) => watches.current(index),
+            WatchOperand::Previous(index) => watches.previous(index),
+        }
+    }
+}
+
+/// Condition sur les valeurs surveillées
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchCondition {
+    Compare { left: WatchOperand, op: CompareOp, right: WatchOperand },
+    All(Vec<WatchCondition>),
+    Any(Vec<WatchCondition>),
+    Not(Box<WatchCondition>),
+}
+
+impl WatchCondition {
+    pub fn evaluate(&self, watches: &WatchSet) -> bool {
+        match self {
+            WatchCondition::Compare { left, op, right } => op.apply(left.value(watches), right.value(watches)),
+            WatchCondition::All(conditions) => conditions.iter().all(|c| c.evaluate(watches)),
+            WatchCondition::Any(conditions) => conditions.iter().any(|c| c.evaluate(watches)),
+            WatchCondition::Not(condition) => !condition.evaluate(watches),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::Ram;
+
+    #[test]
+    fn test_watch_set_tracks_previous_frame() {
+        let mut memory = Ram::new(0x1000);
+        let mut watches = WatchSet::new();
+        let score = watches.add(MemoryWatch::new(0x100, WatchSize::U16));
+        assert_eq!(watches.add(MemoryWatch::new(0x100, WatchSize::U16)), score);
+        let lives = watches.add(MemoryWatch::new(0x100, WatchSize::U8));
+        assert_eq!(watches.len(), 2);
+
+        memory.write_u16(0x100, 0x0102).unwrap();
+        watches.update(&memory).unwrap();
+        assert_eq!(watches.previous(score), watches.current(score));
+
+        memory.write_u16(0x100, 0x0203).unwrap();
+        watches.update(&memory).unwrap();
+        assert_eq!((watches.previous(score), watches.current(score)), (0x0102, 0x0203));
+        assert_eq!(watches.current(lives), 0x03);
+
+        let increased = WatchCondition::Compare {
+            left: WatchOperand::Current(score),
+            op: CompareOp::Gt,
+            right: WatchOperand::Previous(score),
+        };
+        let not_three = WatchCondition::Not(Box::new(WatchCondition::Compare {
+            left: WatchOperand::Current(lives),
+            op: CompareOp::Eq,
+            right: WatchOperand::Value(3),
+        }));
+        assert!(increased.evaluate(&watches));
+        assert!(WatchCondition::Any(vec![increased.clone(), not_three.clone()]).evaluate(&watches));
+        assert!(!WatchCondition::All(vec![increased, not_three]).evaluate(&watches));
+    }
+}
@@ -0,0 +1,331 @@
+//! Mesure du temps passé par sous-système à chaque frame
+//!
+//! La boucle de frame est découpée en portées nommées (CPU, TGP, rastérisation,
+//! présentation, audio, E/S). Chaque portée cumule son temps à la nanoseconde
+//! sur la frame courante, et n'est arrondie en microsecondes qu'à la lecture :
+//! les portées d'un triangle durent souvent moins d'une microseconde. Le bilan
+//! de la dernière frame complète est exposé dans
+//! [`RenderStats`](crate::gpu::RenderStats). En option, les portées sont
+//! aussi conservées sous forme d'événements au format chrome://tracing.
+//!
+//! Le profileur ne fait que mesurer : chaque portée entoure un travail que la
+//! boucle de frame fait de toute façon (la génération audio, par exemple, est
+//! déclenchée par les ticks audio du planificateur de [`EmulatorCore`](crate::emulator::EmulatorCore)).
+//!
+//! Les portées très fréquentes (un appel par triangle) sont fusionnées : une
+//! portée n'apparaît qu'une fois par frame dans la trace, avec pour début le
+//! premier appel et pour durée le cumul.
+
+use serde::Serialize;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::clock::Instant;
+
+/// Nombre maximal d'événements conservés dans la trace
+pub const MAX_TRACE_EVENTS: usize = 200_000;
+
+/// Portée mesurée dans la boucle de frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameScope {
+    /// Exécution du V60
+    Cpu,
+    /// Transformation géométrique (TGP)
+    Tgp,
+    /// Rastérisation des triangles
+    Rasterize,
+    /// Copie vers la surface et présentation
+    Present,
+    /// Génération audio SCSP, cumulée sur les ticks audio de la frame
+    Audio,
+    /// Entrées et registres d'E/S
+    Io,
+}
+
+impl FrameScope {
+    pub const ALL: [FrameScope; 6] = [
+        FrameScope::Cpu,
+        FrameScope::Tgp,
+        FrameScope::Rasterize,
+        FrameScope::Present,
+        FrameScope::Audio,
+        FrameScope::Io,
+    ];
+
+    /// Nom affiché de la portée
+    pub fn name(self) -> &'static str {
+        match self {
+            FrameScope::Cpu => "CPU",
+            FrameScope::Tgp => "TGP",
+            FrameScope::Rasterize => "Rastérisation",
+            FrameScope::Present => "Présentation",
+            FrameScope::Audio => "Audio",
+            FrameScope::Io => "E/S",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Temps cumulés par portée sur une frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FrameTimings {
+    durations: [Duration; FrameScope::ALL.len()],
+}
+
+impl FrameTimings {
+    /// Temps passé dans une portée, en microsecondes
+    pub fn get(&self, scope: FrameScope) -> u64 {
+        self.duration(scope).as_micros() as u64
+    }
+
+    /// Temps exact passé dans une portée
+    pub fn duration(&self, scope: FrameScope) -> Duration {
+        self.durations[scope.index()]
+    }
+
+    /// Ajoute une durée à une portée
+    pub fn add(&mut self, scope: FrameScope, duration: Duration) {
+        self.durations[scope.index()] += duration;
+    }
+
+    /// Temps total mesuré sur la frame, en microsecondes
+    pub fn total_us(&self) -> u64 {
+        self.durations.iter().sum::<Duration>().as_micros() as u64
+    }
+
+    /// Parcourt les portées et leurs temps en microsecondes
+    pub fn iter(&self) -> impl Iterator<Item = (FrameScope, u64)> + '_ {
+        FrameScope::ALL.iter().map(move |&scope| (scope, self.get(scope)))
+    }
+}
+
+/// Portée enregistrée pour la trace chrome://tracing
+#[derive(Debug, Clone, Copy)]
+struct TraceEvent {
+    scope: FrameScope,
+    frame: u64,
+    start: Instant,
+    duration: Duration,
+}
+
+/// Événement au format Trace Event de chrome://tracing
+#[derive(Serialize)]
+struct ChromeEvent {
+    name: &'static str,
+    cat: &'static str,
+    ph: &'static str,
+    ts: u64,
+    dur: u64,
+    pid: u32,
+    tid: u32,
+    args: ChromeArgs,
+}
+
+#[derive(Serialize)]
+struct ChromeArgs {
+    frame: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChromeTrace {
+    trace_events: Vec<ChromeEvent>,
+    display_time_unit: &'static str,
+}
+
+/// Profileur de frame
+#[derive(Debug, Clone)]
+pub struct FrameProfiler {
+    frame: u64,
+    current: FrameTimings,
+    last: FrameTimings,
+    trace: Option<Vec<TraceEvent>>,
+}
+
+impl FrameProfiler {
+    pub fn new() -> Self {
+        Self {
+            frame: 0,
+            current: FrameTimings::default(),
+            last: FrameTimings::default(),
+            trace: None,
+        }
+    }
+
+    /// Active l'enregistrement des événements pour chrome://tracing
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Numéro de la frame en cours
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Enregistre une portée commencée à `start` et terminée maintenant
+    pub fn record(&mut self, scope: FrameScope, start: Instant) {
+        self.add_span(scope, start, start.elapsed());
+    }
+
+    /// Enregistre une portée de durée connue
+    pub fn add_span(&mut self, scope: FrameScope, start: Instant, duration: Duration) {
+        self.current.add(scope, duration);
+
+        let frame = self.frame;
+        let Some(trace) = self.trace.as_mut() else {
+            return;
+        };
+
+        // Fusionner avec la même portée déjà vue dans cette frame
+        let existing = trace
+            .iter_mut()
+            .rev()
+            .take_while(|event| event.frame == frame)
+            .find(|event| event.scope == scope);
+
+        if let Some(event) = existing {
+            event.duration += duration;
+        } else if trace.len() < MAX_TRACE_EVENTS {
+            trace.push(TraceEvent { scope, frame, start, duration });
+        }
+    }
+
+    /// Reprend les mesures d'un autre profileur (par exemple celui du GPU)
+    /// dans la frame courante, puis les efface de celui-ci
+    pub fn absorb(&mut self, other: &mut FrameProfiler) {
+        let events = other.trace.as_mut().map(std::mem::take);
+
+        if let (true, Some(events)) = (self.is_tracing(), events) {
+            for event in events {
+                self.add_span(event.scope, event.start, event.duration);
+            }
+        } else {
+            for scope in FrameScope::ALL {
+                self.current.add(scope, other.current.duration(scope));
+            }
+        }
+
+        other.current = FrameTimings::default();
+    }
+
+    /// Termine la frame courante et retourne son bilan
+    pub fn end_frame(&mut self) -> &FrameTimings {
+        self.last = std::mem::take(&mut self.current);
+        self.frame += 1;
+        &self.last
+    }
+
+    /// Bilan de la dernière frame complète
+    pub fn last_frame(&self) -> &FrameTimings {
+        &self.last
+    }
+
+    /// Écrit la trace au format JSON de chrome://tracing
+    pub fn write_chrome_trace<W: Write>(&self, out: W) -> io::Result<()> {
+        let events = self.trace.as_deref().unwrap_or_default();
+        let origin = events.iter().map(|event| event.start).min();
+
+        let trace = ChromeTrace {
+            trace_events: events
+                .iter()
+                .map(|event| ChromeEvent {
+                    name: event.scope.name(),
+                    cat: "frame",
+                    ph: "X",
+                    ts: origin.map_or(0, |origin| event.start.duration_since(origin).as_micros() as u64),
+                    dur: event.duration.as_micros() as u64,
+                    pid: 1,
+                    tid: 1 + event.scope.index() as u32,
+                    args: ChromeArgs { frame: event.frame },
+                })
+                .collect(),
+            display_time_unit: "ms",
+        };
+
+        serde_json::to_writer(out, &trace).map_err(io::Error::other)
+    }
+}
+
+impl Default for FrameProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_accumulate_per_frame() {
+        let mut profiler = FrameProfiler::new();
+        let start = Instant::now();
+        profiler.add_span(FrameScope::Cpu, start, Duration::from_micros(1500));
+        profiler.add_span(FrameScope::Cpu, start, Duration::from_micros(500));
+        profiler.add_span(FrameScope::Audio, start, Duration::from_micros(100));
+
+        let timings = *profiler.end_frame();
+        assert_eq!(timings.get(FrameScope::Cpu), 2000);
+        assert_eq!(timings.get(FrameScope::Audio), 100);
+        assert_eq!(timings.total_us(), 2100);
+
+        // Des portées plus courtes qu'une microseconde s'additionnent
+        for _ in 0..1000 {
+            profiler.add_span(FrameScope::Tgp, start, Duration::from_nanos(400));
+        }
+        assert_eq!(profiler.end_frame().get(FrameScope::Tgp), 400);
+
+        // La frame suivante repart de zéro
+        profiler.end_frame();
+        assert_eq!(profiler.last_frame().total_us(), 0);
+        assert_eq!(profiler.frame(), 3);
+    }
+
+    #[test]
+    fn test_absorb_merges_gpu_timings() {
+        let mut main = FrameProfiler::new();
+        let mut gpu = FrameProfiler::new();
+        gpu.add_span(FrameScope::Rasterize, Instant::now(), Duration::from_micros(300));
+        gpu.add_span(FrameScope::Tgp, Instant::now(), Duration::from_nanos(1500));
+        main.absorb(&mut gpu);
+        gpu.add_span(FrameScope::Tgp, Instant::now(), Duration::from_nanos(1500));
+
+        main.absorb(&mut gpu);
+        let timings = *main.end_frame();
+        assert_eq!((timings.get(FrameScope::Rasterize), timings.get(FrameScope::Tgp)), (300, 3));
+        assert_eq!(gpu.end_frame().total_us(), 0);
+    }
+
+    #[test]
+    fn test_chrome_trace_merges_repeated_scopes() {
+        let mut profiler = FrameProfiler::new();
+        profiler.enable_trace();
+
+        let start = Instant::now();
+        for _ in 0..100 {
+            profiler.add_span(FrameScope::Tgp, start, Duration::from_micros(2));
+            profiler.add_span(FrameScope::Rasterize, start, Duration::from_micros(3));
+        }
+        profiler.end_frame();
+        profiler.add_span(FrameScope::Tgp, start + Duration::from_millis(16), Duration::from_micros(5));
+
+        let mut out = Vec::new();
+        profiler.write_chrome_trace(&mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let events = json["traceEvents"].as_array().unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["name"], "TGP");
+        assert_eq!(events[0]["dur"], 200);
+        assert_eq!(events[1]["dur"], 300);
+        assert_eq!(events[2]["args"]["frame"], 1);
+        assert_eq!(events[2]["ts"], 16_000);
+    }
+}
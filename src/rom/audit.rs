@@ -0,0 +1,126 @@
+//! Audit des jeux : ROMs requises présentes dans les chemins de recherche
+//!
+//! Une ROM est considérée présente si un fichier porte son nom, ou son nom
+//! suivi d'une extension reconnue (comme à la recherche au chargement), ou
+//! si l'archive ZIP du set du jeu ou de son parent existe (organisation de
+//! MAME).
+//! L'audit ne lit pas les fichiers : les checksums sont vérifiés au
+//! chargement du jeu.
+
+use std::path::PathBuf;
+
+use super::database::GameDatabase;
+
+/// Disponibilité des ROMs d'un jeu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditStatus {
+    /// Toutes les ROMs requises sont présentes
+    Complete,
+    /// Une partie seulement des ROMs requises est présente
+    Incomplete,
+    /// Aucune ROM requise n'est présente
+    Missing,
+}
+
+/// Résultat de l'audit d'un jeu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameAudit {
+    pub name: String,
+    pub short_name: String,
+    pub year: u16,
+
+    /// ROMs requises présentes
+    pub found: usize,
+
+    /// ROMs requises au total
+    pub required: usize,
+
+    /// Noms des ROMs requises absentes
+    pub missing: Vec<String>,
+}
+
+impl GameAudit {
+    pub fn status(&self) -> AuditStatus {
+        if self.found == self.required {
+            AuditStatus::Complete
+        } else if self.found > 0 {
+            AuditStatus::Incomplete
+        } else {
+            AuditStatus::Missing
+        }
+    }
+
+    /// Le jeu peut être lancé
+    pub fn is_playable(&self) -> bool {
+        self.status() == AuditStatus::Complete
+    }
+}
+
+/// Audite tous les jeux de la base d'après les fichiers trouvés, triés par nom
+pub fn audit_games(database: &GameDatabase, available_roms: &[PathBuf], extensions: &[String]) -> Vec<GameAudit> {
+    let is_present = |filename: &str| {
+        available_roms.iter().any(|path| {
+            let name_matches = path.file_name().and_then(|n| n.to_str()) == Some(filename);
+            let stem_matches = path.file_stem().and_then(|s| s.to_str()) == Some(filename)
+                && path.extension()
+                    .map(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+                    .unwrap_or(false);
+            name_matches || stem_matches
+        })
+    };
+
+    let mut audits: Vec<GameAudit> = database.list_games()
+        .into_iter()
+        .map(|game| {
+            let set_present = std::iter::once(&game.short_name).chain(&game.parent)
+                .any(|set| is_present(&format!("{}.zip", set)));
+            let missing: Vec<String> = game.required_roms.iter()
+                .filter(|rom| !set_present && !is_present(&rom.filename))
+                .map(|rom| rom.filename.clone())
+                .collect();
+            GameAudit {
+                name: game.name.clone(),
+                short_name: game.short_name.clone(),
+                year: game.year,
+                found: game.required_roms.len() - missing.len(),
+                required: game.required_roms.len(),
+                missing,
+            }
+        })
+        .collect();
+    audits.sort_by(|a, b| a.name.cmp(&b.name));
+    audits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_status_from_found_roms() {
+        let database = GameDatabase::new();
+        let vf2 = database.find_game("vf2").unwrap();
+        let extensions = vec!["zip".to_string()];
+
+        let audits = audit_games(&database, &[], &extensions);
+        assert!(audits.windows(2).all(|pair| pair[0].name <= pair[1].name));
+        let audit = audits.iter().find(|a| a.short_name == "vf2").unwrap();
+        assert_eq!(audit.status(), AuditStatus::Missing);
+        assert_eq!(audit.missing.len(), vf2.required_roms.len());
+
+        // Une ROM seule, une autre dans une archive à son nom
+        let mut available = vec![PathBuf::from("/roms").join(&vf2.required_roms[0].filename)];
+        let audit = audit_games(&database, &available, &extensions).into_iter().find(|a| a.short_name == "vf2").unwrap();
+        let expected = if vf2.required_roms.len() == 1 { AuditStatus::Complete } else { AuditStatus::Incomplete };
+        assert_eq!(audit.status(), expected);
+
+        available.extend(vf2.required_roms[1..].iter().map(|rom| PathBuf::from(format!("/roms/{}.zip", rom.filename))));
+        let audit = audit_games(&database, &available, &extensions).into_iter().find(|a| a.short_name == "vf2").unwrap();
+        assert!(audit.is_playable());
+        assert!(audit.missing.is_empty());
+
+        // Archive de set au nom du jeu
+        let audit = audit_games(&database, &[PathBuf::from("/roms/vf2.zip")], &extensions).into_iter().find(|a| a.short_name == "vf2").unwrap();
+        assert!(audit.is_playable());
+    }
+}
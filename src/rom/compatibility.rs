@@ -0,0 +1,181 @@
+//! Rapport de compatibilité des jeux
+//!
+//! Le rapport livré avec l'émulateur (`compatibility.toml`, intégré au
+//! binaire) donne pour chaque jeu un état, du démarrage impossible à
+//! l'émulation parfaite, une note, les défauts connus et la version de
+//! l'émulateur avec laquelle il a été vérifié. Un défaut corrigé par un
+//! contournement ([`Hack`]) porte son nom : il réapparaît si les
+//! contournements sont désactivés. Le lanceur l'affiche à côté de l'audit
+//! des ROMs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::error::{Result, RomError};
+use crate::memory::Hack;
+
+/// Rapport intégré au binaire
+const BUNDLED_REPORT: &str = include_str!("compatibility.toml");
+
+/// État d'émulation d'un jeu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompatibilityStatus {
+    /// Ne démarre pas
+    Broken,
+    /// Démarre, n'atteint pas le jeu
+    Boots,
+    /// Atteint le jeu, défauts bloquants
+    InGame,
+    /// Jouable, défauts mineurs
+    Playable,
+    /// Aucun défaut connu
+    Perfect,
+    /// Absent du rapport
+    #[default]
+    Untested,
+}
+
+impl CompatibilityStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            CompatibilityStatus::Broken => "ne démarre pas",
+            CompatibilityStatus::Boots => "démarre",
+            CompatibilityStatus::InGame => "en jeu",
+            CompatibilityStatus::Playable => "jouable",
+            CompatibilityStatus::Perfect => "parfait",
+            CompatibilityStatus::Untested => "non testé",
+        }
+    }
+}
+
+/// Défaut connu d'un jeu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownIssue {
+    pub description: String,
+
+    /// Contournement qui masque le défaut, actif par défaut
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hack: Option<Hack>,
+}
+
+/// Entrée du rapport pour un jeu
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatibilityEntry {
+    pub status: CompatibilityStatus,
+
+    #[serde(default)]
+    pub notes: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<KnownIssue>,
+
+    /// Version de l'émulateur de la dernière vérification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_tested: Option<String>,
+}
+
+impl CompatibilityEntry {
+    /// Défauts visibles : sans les contournements, ceux qu'ils masquent
+    /// s'ajoutent aux autres
+    pub fn visible_issues(&self, hacks_enabled: bool) -> impl Iterator<Item = &KnownIssue> {
+        self.issues.iter().filter(move |issue| issue.hack.is_none() || !hacks_enabled)
+    }
+
+    /// L'état a-t-il été vérifié avec cette version de l'émulateur
+    pub fn tested_with_current_version(&self) -> bool {
+        self.last_tested.as_deref() == Some(env!("CARGO_PKG_VERSION"))
+    }
+}
+
+/// États de compatibilité par nom court de jeu
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    #[serde(default)]
+    games: BTreeMap<String, CompatibilityEntry>,
+}
+
+impl CompatibilityReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rapport livré avec l'émulateur
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_REPORT).unwrap_or_else(|e| {
+            log::error!("Rapport de compatibilité intégré invalide: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Analyse un rapport au format TOML
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text).map_err(|e| RomError::Database(e.to_string()))?)
+    }
+
+    pub fn entry(&self, game: &str) -> Option<&CompatibilityEntry> {
+        self.games.get(game)
+    }
+
+    /// État d'un jeu, non testé s'il est absent du rapport
+    pub fn status(&self, game: &str) -> CompatibilityStatus {
+        self.entry(game).map(|entry| entry.status).unwrap_or_default()
+    }
+
+    /// Jeux du rapport par nom court
+    pub fn games(&self) -> impl Iterator<Item = (&str, &CompatibilityEntry)> {
+        self.games.iter().map(|(game, entry)| (game.as_str(), entry))
+    }
+
+    /// Jeux atteignant au moins l'état `status`
+    pub fn games_at_least(&self, status: CompatibilityStatus) -> impl Iterator<Item = &str> {
+        self.games()
+            .filter(move |(_, entry)| entry.status >= status && entry.status != CompatibilityStatus::Untested)
+            .map(|(game, _)| game)
+    }
+
+    /// Défauts masqués par un contournement, avec leur jeu
+    pub fn issues_for_hack(&self, hack: Hack) -> impl Iterator<Item = (&str, &KnownIssue)> {
+        self.games().flat_map(move |(game, entry)| {
+            entry.issues.iter().filter(move |issue| issue.hack == Some(hack)).map(move |issue| (game, issue))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rom::GameDatabase;
+
+    #[test]
+    fn test_bundled_report_covers_known_games() {
+        let report = CompatibilityReport::parse(BUNDLED_REPORT).unwrap();
+        for game in GameDatabase::new().list_games() {
+            assert!(report.entry(&game.short_name).is_some(), "{} absent du rapport", game.short_name);
+        }
+        assert_eq!(report.status("inconnu"), CompatibilityStatus::Untested);
+        assert!(CompatibilityReport::parse("[games.vf2]\nstatus = \"excellent\"").is_err());
+    }
+
+    #[test]
+    fn test_known_issues_and_hack_links() {
+        let report = CompatibilityReport::parse(concat!(
+            "[games.daytona]\nstatus = \"playable\"\nlast_tested = \"0.0.1\"\n",
+            "[[games.daytona.issues]]\ndescription = \"Écran noir après l'attract\"\nhack = \"force_vblank\"\n",
+            "[[games.daytona.issues]]\ndescription = \"Brouillard trop dense\"\n",
+            "[games.vf2]\nstatus = \"boots\"\n",
+            "[games.vcop]\nstatus = \"untested\"\n",
+        )).unwrap();
+        let daytona = report.entry("daytona").unwrap();
+        assert_eq!(daytona.visible_issues(true).count(), 1);
+        assert_eq!(daytona.visible_issues(false).count(), 2);
+        assert!(!daytona.tested_with_current_version());
+        assert!(report.entry("vf2").unwrap().last_tested.is_none());
+
+        let hacked: Vec<_> = report.issues_for_hack(Hack::ForceVblank).map(|(game, _)| game).collect();
+        assert_eq!(hacked, ["daytona"]);
+        assert_eq!(report.issues_for_hack(Hack::ForceServiceUnlock).count(), 0);
+        assert_eq!(report.games_at_least(CompatibilityStatus::Boots).collect::<Vec<_>>(), ["daytona", "vf2"]);
+        assert!(CompatibilityReport::parse("[games.vf2]\nstatus = \"boots\"\n[[games.vf2.issues]]\ndescription = \"x\"\nhack = \"inconnu\"").is_err());
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::error::RomError;
+use crate::memory::{BoardRevision, MemoryMap, MemoryRegion};
 
 /// Informations sur un jeu Model 2
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +25,15 @@ pub struct GameInfo {
     
     /// Version du jeu
     pub version: String,
+
+    /// Révision de la carte (Model 2, 2A-CRX, 2B-CRX, 2C-CRX)
+    #[serde(default = "default_board")]
+    pub board: String,
+
+    /// Nom court du jeu parent pour un clone ; les ROMs de même nom sont
+    /// partagées avec lui
+    #[serde(default)]
+    pub parent: Option<String>,
     
     /// Liste des ROMs requises avec leurs checksums
     pub required_roms: Vec<RomInfo>,
@@ -37,6 +48,32 @@ pub struct GameInfo {
     pub description: String,
 }
 
+fn default_board() -> String {
+    "Model 2".to_string()
+}
+
+fn default_player_count() -> usize {
+    crate::input::BASE_PLAYERS
+}
+
+impl GameInfo {
+    /// Révision de carte d'après `board` ; `None` si le nom est inconnu
+    pub fn board_revision(&self) -> Option<BoardRevision> {
+        BoardRevision::from_board(&self.board)
+    }
+
+    /// Vérifie que chaque ROM tient, à son adresse de chargement, dans la
+    /// fenêtre de son type sur la disposition `map`
+    pub fn check_rom_windows(&self, map: &MemoryMap) -> crate::error::Result<()> {
+        for rom in self.required_roms.iter().chain(&self.optional_roms) {
+            if let Some(region) = rom.rom_type.window() {
+                map.check_rom_bank(&rom.filename, region, rom.load_address, rom.size)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Information sur une ROM individuelle
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RomInfo {
@@ -96,6 +133,19 @@ pub enum RomType {
     Data,
 }
 
+impl RomType {
+    /// Fenêtre de ROM où le type est chargé ; `None` pour les données lues
+    /// par d'autres processeurs
+    pub fn window(&self) -> Option<MemoryRegion> {
+        match self {
+            RomType::Program => Some(MemoryRegion::ProgramRom),
+            RomType::Graphics | RomType::Geometry | RomType::Texture => Some(MemoryRegion::GraphicsRom),
+            RomType::Sound | RomType::Samples => Some(MemoryRegion::AudioRom),
+            RomType::Config | RomType::Microcode | RomType::Data => None,
+        }
+    }
+}
+
 /// Configuration système spécifique au jeu
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemConfig {
@@ -116,6 +166,34 @@ pub struct SystemConfig {
     
     /// Contrôles supportés
     pub supported_controls: Vec<String>,
+
+    /// Places de joueur de la borne ; au-delà de deux, la carte I/O
+    /// étendue des bornes twin / DX
+    #[serde(default = "default_player_count")]
+    pub player_count: usize,
+
+    /// Routines du micrologiciel simulées (HLE) quand les ROM système
+    /// manquent ; chaque entrée nomme la ROM qui la contient. La base
+    /// intégrée n'en fournit pas : une base JSON peut en déclarer
+    #[serde(default)]
+    pub firmware_hle: Vec<crate::cpu::HleEntry>,
+
+    /// Puce de protection interrogée au démarrage, si le jeu en a une
+    #[serde(default)]
+    pub protection: Option<crate::memory::ProtectionConfig>,
+
+    /// Contournements nécessaires au jeu (voir `memory::hacks`)
+    #[serde(default)]
+    pub hacks: Vec<crate::memory::Hack>,
+
+    /// Commandes sonores connues, jouées en HLE sans émulation du 68000
+    #[serde(default)]
+    pub sound_commands: Vec<crate::audio::SoundCommandConfig>,
+
+    /// Comportement du bus ouvert propre au jeu (sinon lignes tirées à 1
+    /// partout)
+    #[serde(default)]
+    pub open_bus: Option<crate::memory::OpenBusPolicy>,
 }
 
 /// Configuration audio
@@ -129,6 +207,11 @@ pub struct AudioConfig {
     
     /// Utilise le DSP SCSP
     pub use_scsp: bool,
+
+    /// Borne d'origine, dont les haut-parleurs sont simulés avec le profil
+    /// de borne `auto`
+    #[serde(default)]
+    pub cabinet: Option<crate::config::CabinetKind>,
 }
 
 /// Configuration graphique
@@ -145,6 +228,23 @@ pub struct GraphicsConfig {
     
     /// Nombre de plans de texture
     pub texture_planes: u8,
+
+    /// Le jeu repose sur l'ordre de tracé des polygones plutôt que sur un
+    /// Z-buffer (rendu par priorité conseillé)
+    #[serde(default)]
+    pub priority_sorting: bool,
+
+    /// Le jeu dépend de la précision en virgule fixe du TGP (transformation
+    /// des sommets en virgule fixe conseillée)
+    #[serde(default)]
+    pub fixed_point_geometry: bool,
+
+    /// Adresse de la table de brouillard du jeu dans les ROMs mappées,
+    /// chargée dans les registres au démarrage (brouillard analytique sinon).
+    /// Aucun jeu de la base intégrée n'en déclare : leurs tables sont écrites
+    /// par le programme dans les registres I/O ; une base JSON peut en fournir
+    #[serde(default)]
+    pub fog_table_address: Option<u32>,
 }
 
 /// Base de données des jeux Model 2
@@ -190,6 +290,11 @@ impl GameDatabase {
         None
     }
     
+    /// Places de joueur d'un jeu ; deux pour un jeu inconnu
+    pub fn player_count(&self, name: &str) -> usize {
+        self.find_game(name).map_or_else(default_player_count, |game| game.system_config.player_count)
+    }
+
     /// Liste tous les jeux disponibles
     pub fn list_games(&self) -> Vec<&GameInfo> {
         self.games.values().collect()
@@ -239,9 +344,10 @@ impl GameDatabase {
     }
     
     /// Charge la base de données depuis un fichier JSON
-    pub fn load_from_file(&mut self, path: &str) -> anyhow::Result<()> {
+    pub fn load_from_file(&mut self, path: &str) -> crate::error::Result<()> {
         let content = std::fs::read_to_string(path)?;
-        let games: Vec<GameInfo> = serde_json::from_str(&content)?;
+        let games: Vec<GameInfo> = serde_json::from_str(&content)
+            .map_err(|e| RomError::Database(e.to_string()))?;
         
         for game in games {
             self.add_game(game);
@@ -251,9 +357,10 @@ impl GameDatabase {
     }
     
     /// Sauvegarde la base de données dans un fichier JSON
-    pub fn save_to_file(&self, path: &str) -> anyhow::Result<()> {
+    pub fn save_to_file(&self, path: &str) -> crate::error::Result<()> {
         let games: Vec<&GameInfo> = self.games.values().collect();
-        let content = serde_json::to_string_pretty(&games)?;
+        let content = serde_json::to_string_pretty(&games)
+            .map_err(|e| RomError::Database(e.to_string()))?;
         std::fs::write(path, content)?;
         Ok(())
     }
@@ -268,6 +375,8 @@ impl GameDatabase {
             year: 1994,
             region: "World".to_string(),
             version: "2.1".to_string(),
+            board: default_board(),
+            parent: None,
             required_roms: vec![
                 RomInfo {
                     filename: "epr-17574.30".to_string(),
@@ -299,14 +408,24 @@ impl GameDatabase {
                     sample_rate: 44100,
                     channels: 2,
                     use_scsp: true,
+                    cabinet: Some(crate::config::CabinetKind::Upright),
                 },
                 graphics_config: GraphicsConfig {
                     texture_mapping: true,
                     transparency: true,
                     antialiasing: false,
                     texture_planes: 4,
+                    priority_sorting: false,
+                    fixed_point_geometry: false,
+                    fog_table_address: None,
                 },
                 supported_controls: vec!["joystick".to_string(), "6buttons".to_string()],
+                player_count: 2,
+                firmware_hle: Vec::new(),
+                protection: None,
+                hacks: Vec::new(),
+                sound_commands: Vec::new(),
+                open_bus: None,
             },
             description: "Revolutionary 3D fighting game featuring realistic character models and fluid animation.".to_string(),
         });
@@ -319,6 +438,8 @@ impl GameDatabase {
             year: 1993,
             region: "World".to_string(),
             version: "1.0".to_string(),
+            board: default_board(),
+            parent: None,
             required_roms: vec![
                 RomInfo {
                     filename: "epr-16724a.6".to_string(),
@@ -340,14 +461,24 @@ impl GameDatabase {
                     sample_rate: 44100,
                     channels: 2,
                     use_scsp: true,
+                    cabinet: Some(crate::config::CabinetKind::Deluxe),
                 },
                 graphics_config: GraphicsConfig {
                     texture_mapping: true,
                     transparency: true,
                     antialiasing: true,
                     texture_planes: 6,
+                    priority_sorting: false,
+                    fixed_point_geometry: false,
+                    fog_table_address: None,
                 },
                 supported_controls: vec!["steering".to_string(), "pedals".to_string()],
+                player_count: 1,
+                firmware_hle: Vec::new(),
+                protection: None,
+                hacks: Vec::new(),
+                sound_commands: Vec::new(),
+                open_bus: None,
             },
             description: "Groundbreaking 3D racing game featuring the Daytona Speedway.".to_string(),
         });
@@ -360,6 +491,8 @@ impl GameDatabase {
             year: 1994,
             region: "World".to_string(),
             version: "1.0".to_string(),
+            board: default_board(),
+            parent: None,
             required_roms: vec![
                 RomInfo {
                     filename: "epr-17168a.6".to_string(),
@@ -381,14 +514,24 @@ impl GameDatabase {
                     sample_rate: 44100,
                     channels: 2,
                     use_scsp: true,
+                    cabinet: Some(crate::config::CabinetKind::Upright),
                 },
                 graphics_config: GraphicsConfig {
                     texture_mapping: true,
                     transparency: true,
                     antialiasing: false,
                     texture_planes: 4,
+                    priority_sorting: false,
+                    fixed_point_geometry: false,
+                    fog_table_address: None,
                 },
                 supported_controls: vec!["lightgun".to_string()],
+                player_count: 2,
+                firmware_hle: Vec::new(),
+                protection: None,
+                hacks: Vec::new(),
+                sound_commands: Vec::new(),
+                open_bus: None,
             },
             description: "Revolutionary light gun shooter with polygonal graphics.".to_string(),
         });
@@ -413,10 +556,21 @@ mod tests {
         assert!(db.find_game("vf2").is_some());
         assert!(db.find_game("Virtua Fighter").is_some());
         assert!(db.find_game("unknown_game").is_none());
+        assert_eq!(db.player_count("daytona"), 1);
+        assert_eq!(db.player_count("unknown_game"), 2);
         
         // Test de liste
         let games = db.list_games();
         assert!(games.len() >= 3);
+
+        // Chaque jeu connu a une carte reconnue dont les fenêtres contiennent ses ROMs
+        for game in games {
+            let board = game.board_revision().unwrap();
+            game.check_rom_windows(&MemoryMap::for_board(board)).unwrap();
+        }
+        let mut vf2 = db.find_game("vf2").unwrap().clone();
+        vf2.required_roms[1].load_address = 0x0080_0000;
+        assert!(vf2.check_rom_windows(&MemoryMap::new_model2()).is_err());
     }
     
     #[test]
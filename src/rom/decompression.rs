@@ -1,9 +1,12 @@
 //! Système de décompression pour les ROMs
 
-use anyhow::{Result, anyhow};
+use crate::error::{Result, RomError};
 use std::path::Path;
-use std::io::{Read, BufReader};
+#[cfg(feature = "rom-tools")]
+use std::io::{BufReader, Cursor, Read, Seek};
+#[cfg(feature = "rom-tools")]
 use zip::ZipArchive;
+#[cfg(feature = "rom-tools")]
 use flate2::read::GzDecoder;
 
 /// Types de compression supportés
@@ -59,38 +62,69 @@ impl RomDecompressor {
     
     /// Décompresse un fichier selon son type
     pub fn decompress_file(path: &Path) -> Result<DecompressionResult> {
-        let compression_type = Self::detect_compression_type(path);
-        
-        match compression_type {
-            CompressionType::None => Self::load_raw_file(path),
-            CompressionType::Zip => Self::decompress_zip(path),
-            CompressionType::Gzip => Self::decompress_gzip(path),
-            CompressionType::SevenZip => Err(anyhow!("Support 7-Zip non encore implémenté")),
-            CompressionType::Rar => Err(anyhow!("Support RAR non encore implémenté")),
+        let name = Self::file_name(path);
+        match Self::detect_compression_type(path) {
+            CompressionType::None => Ok(Self::raw(name, std::fs::read(path)?)),
+            #[cfg(feature = "rom-tools")]
+            CompressionType::Zip => Self::decompress_zip(BufReader::new(std::fs::File::open(path)?)),
+            #[cfg(feature = "rom-tools")]
+            CompressionType::Gzip => Self::decompress_gzip(BufReader::new(std::fs::File::open(path)?), path),
+            #[cfg(not(feature = "rom-tools"))]
+            CompressionType::Zip | CompressionType::Gzip => Err(Self::without_rom_tools(path)),
+            CompressionType::SevenZip => Err(RomError::UnsupportedArchive("7-Zip").into()),
+            CompressionType::Rar => Err(RomError::UnsupportedArchive("RAR").into()),
         }
     }
-    
-    /// Charge un fichier non compressé
-    fn load_raw_file(path: &Path) -> Result<DecompressionResult> {
-        let data = std::fs::read(path)?;
-        let filename = path.file_name()
+
+    /// Décompresse le contenu d'un fichier déjà lu (fichier choisi dans un
+    /// navigateur, par exemple) ; le type est déduit de l'extension de `name`
+    pub fn decompress_bytes(name: &str, data: Vec<u8>) -> Result<DecompressionResult> {
+        let path = Path::new(name);
+        match Self::detect_compression_type(path) {
+            CompressionType::None => Ok(Self::raw(Self::file_name(path), data)),
+            #[cfg(feature = "rom-tools")]
+            CompressionType::Zip => Self::decompress_zip(Cursor::new(data)),
+            #[cfg(feature = "rom-tools")]
+            CompressionType::Gzip => Self::decompress_gzip(Cursor::new(data), path),
+            #[cfg(not(feature = "rom-tools"))]
+            CompressionType::Zip | CompressionType::Gzip => Err(Self::without_rom_tools(path)),
+            CompressionType::SevenZip => Err(RomError::UnsupportedArchive("7-Zip").into()),
+            CompressionType::Rar => Err(RomError::UnsupportedArchive("RAR").into()),
+        }
+    }
+
+    fn file_name(path: &Path) -> String {
+        path.file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown")
-            .to_string();
-        
+            .to_string()
+    }
+    
+    /// Fichier non compressé
+    fn raw(filename: String, data: Vec<u8>) -> DecompressionResult {
         let total_size = data.len();
         
-        Ok(DecompressionResult {
+        DecompressionResult {
             files: vec![(filename, data)],
             compression_type: CompressionType::None,
             total_size,
-        })
+        }
     }
     
+    /// Archive reconnue mais non lue : compilé sans la fonctionnalité
+    /// `rom-tools`
+    #[cfg(not(feature = "rom-tools"))]
+    fn without_rom_tools(path: &Path) -> crate::error::EmulatorError {
+        let archive = match Self::detect_compression_type(path) {
+            CompressionType::Gzip => "GZIP",
+            _ => "ZIP",
+        };
+        RomError::ArchiveSupportDisabled(archive).into()
+    }
+
     /// Décompresse une archive ZIP
-    fn decompress_zip(path: &Path) -> Result<DecompressionResult> {
-        let file = std::fs::File::open(path)?;
-        let reader = BufReader::new(file);
+    #[cfg(feature = "rom-tools")]
+    fn decompress_zip<R: Read + Seek>(reader: R) -> Result<DecompressionResult> {
         let mut archive = ZipArchive::new(reader)?;
         
         let mut files = Vec::new();
@@ -121,9 +155,8 @@ impl RomDecompressor {
     }
     
     /// Décompresse un fichier GZIP
-    fn decompress_gzip(path: &Path) -> Result<DecompressionResult> {
-        let file = std::fs::File::open(path)?;
-        let reader = BufReader::new(file);
+    #[cfg(feature = "rom-tools")]
+    fn decompress_gzip<R: Read>(reader: R, path: &Path) -> Result<DecompressionResult> {
         let mut decoder = GzDecoder::new(reader);
         
         let mut contents = Vec::new();
@@ -281,4 +314,22 @@ mod tests {
         
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "rom-tools")]
+    fn test_zip_from_memory() -> Result<()> {
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        archive.start_file("epr-17567.ic12", zip::write::FileOptions::default())
+            .map_err(crate::error::EmulatorError::from)?;
+        archive.write_all(&[0xAA; 16])?;
+        let data = archive.finish().map_err(crate::error::EmulatorError::from)?.into_inner();
+
+        let result = RomDecompressor::decompress_bytes("vf2.zip", data)?;
+        assert_eq!(result.compression_type, CompressionType::Zip);
+        assert_eq!(result.files, vec![("epr-17567.ic12".to_string(), vec![0xAA; 16])]);
+
+        let raw = RomDecompressor::decompress_bytes("epr-17568.ic13", vec![1, 2])?;
+        assert_eq!(raw.files[0].0, "epr-17568.ic13");
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -2,7 +2,7 @@
 
 use anyhow::Result;
 use crate::rom::{Model2RomSystem, Model2MemoryConfig, LoadConfig};
-use crate::memory::MemoryInterface;
+use crate::memory::{ByteOrder, MemoryInterface};
 
 /// Exemple complet de chargement et mapping ROM
 pub fn example_rom_loading() -> Result<()> {
@@ -92,10 +92,9 @@ pub fn example_rom_loading() -> Result<()> {
     if let Some(data) = rom_system.memory_mapper.read_rom_data(0x00000000, 1024) {
         println!("✅ Lecture rapide depuis le cache: {} octets", data.len());
         
-        // Analyser les premiers octets (vecteurs d'interruption 68000)
-        if data.len() >= 8 {
-            let stack_pointer = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
-            let reset_vector = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+        // Analyser les premiers octets (vecteurs d'interruption du V60,
+        // petit-boutistes)
+        if let (Some(stack_pointer), Some(reset_vector)) = (ByteOrder::Little.read_u32(&data, 0), ByteOrder::Little.read_u32(&data, 4)) {
             println!("  Stack Pointer: 0x{:08X}", stack_pointer);
             println!("  Reset Vector: 0x{:08X}", reset_vector);
         }
@@ -1,6 +1,6 @@
 //! Tests d'intégration du système ROM SEGA Model 2
 
-use anyhow::Result;
+use crate::error::{EmulatorError, Result, RomError};
 use tempfile::TempDir;
 use std::fs;
 
@@ -104,7 +104,7 @@ fn test_complete_rom_system() -> Result<()> {
         Err(e) => {
             // C'est normal, on n'a pas les vraies ROMs
             println!("Chargement échoué comme attendu: {}", e);
-            assert!(e.to_string().contains("ROM non trouvée") || e.to_string().contains("Jeu non trouvé"));
+            assert!(matches!(e, EmulatorError::RomError(RomError::NotFound(_) | RomError::GameNotFound(_))));
         }
     }
     
@@ -119,6 +119,7 @@ fn test_complete_rom_system() -> Result<()> {
 
 /// Test de scan de ROMs disponibles
 #[test]
+#[cfg(feature = "filesystem")]
 fn test_rom_scanning() -> Result<()> {
     let temp_dir = TempDir::new()?;
     
@@ -183,10 +184,11 @@ fn test_mapping_validation() -> Result<()> {
 
 /// Crée des ROMs de test dans le répertoire temporaire
 fn create_test_roms(temp_dir: &TempDir) -> Result<()> {
-    // Créer une ROM programme avec vecteurs d'interruption valides
+    // Créer une ROM programme avec vecteurs d'interruption valides, dans
+    // l'ordre petit-boutiste du V60
     let mut program_rom = vec![0u8; 1024 * 1024]; // 1 MB
-    program_rom[0..4].copy_from_slice(&0x00100000u32.to_be_bytes()); // Stack pointer
-    program_rom[4..8].copy_from_slice(&0x00001000u32.to_be_bytes()); // Reset vector
+    program_rom[0..4].copy_from_slice(&0x00100000u32.to_le_bytes()); // Stack pointer
+    program_rom[4..8].copy_from_slice(&0x00001000u32.to_le_bytes()); // Reset vector
     
     // Remplir avec quelques instructions NOP
     for i in (0x1000..0x2000).step_by(2) {
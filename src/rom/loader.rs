@@ -1,13 +1,17 @@
 //! Système de chargement et mapping mémoire des ROMs
 
-use anyhow::{Result, anyhow};
+use crate::error::{Result, RomError};
 use std::path::{Path, PathBuf};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "filesystem")]
 use walkdir::WalkDir;
 
 use super::database::{GameDatabase, GameInfo, RomInfo, RomType};
-use super::decompression::RomDecompressor;
+use super::decompression::{CompressionType, RomDecompressor};
 use super::validation::{RomValidator, ValidationResult};
+use super::audit::{audit_games, GameAudit};
+use super::patch::RomPatch;
+use crate::config::RomPatchConfig;
 
 /// Gestionnaire principal de ROMs
 pub struct RomManager {
@@ -20,8 +24,35 @@ pub struct RomManager {
     /// Cache des ROMs chargées
     rom_cache: HashMap<String, LoadedRom>,
     
+    /// Fichiers fournis en mémoire, consultés avant les chemins de recherche
+    provided_files: Vec<ProvidedFile>,
+    
     /// Configuration de chargement
     load_config: LoadConfig,
+
+    /// Patchs à appliquer, par nom court de jeu
+    patches: BTreeMap<String, Vec<RomPatchConfig>>,
+
+    /// Compte rendu des patchs du dernier jeu chargé
+    patch_notes: Vec<String>,
+
+    /// Archives de sets du jeu en cours de chargement (le jeu puis son
+    /// parent), consultées quand une ROM n'a pas de fichier à son nom
+    set_names: Vec<String>,
+}
+
+/// Fichier fourni en mémoire (choisi dans un navigateur, par exemple),
+/// décompressé à l'ajout
+#[derive(Debug)]
+struct ProvidedFile {
+    /// Nom du fichier fourni
+    source: PathBuf,
+    
+    /// Compression du fichier fourni
+    compression_type: CompressionType,
+    
+    /// Fichiers qu'il contient
+    files: Vec<(String, Vec<u8>)>,
 }
 
 /// ROM chargée en mémoire
@@ -41,6 +72,9 @@ pub struct LoadedRom {
     
     /// Type de compression utilisé
     pub compression_type: super::decompression::CompressionType,
+
+    /// Patchs appliqués, dans l'ordre
+    pub patches: Vec<PathBuf>,
 }
 
 /// Configuration de chargement
@@ -146,24 +180,84 @@ impl RomManager {
                 PathBuf::from("../roms"),
             ],
             rom_cache: HashMap::new(),
+            provided_files: Vec::new(),
             load_config: LoadConfig::default(),
+            patches: BTreeMap::new(),
+            patch_notes: Vec::new(),
+            set_names: Vec::new(),
         }
     }
     
+    /// Base de données des jeux connus
+    pub fn database(&self) -> &GameDatabase {
+        &self.database
+    }
+
     /// Ajoute un chemin de recherche
     pub fn add_search_path<P: AsRef<Path>>(&mut self, path: P) {
         self.search_paths.push(path.as_ref().to_path_buf());
     }
     
+    /// Fournit un fichier ROM ou une archive déjà en mémoire ; ses ROM sont
+    /// préférées à celles des chemins de recherche
+    pub fn add_rom_file(&mut self, name: &str, data: Vec<u8>) -> Result<()> {
+        let decompressed = RomDecompressor::decompress_bytes(name, data)?;
+        self.provided_files.push(ProvidedFile {
+            source: PathBuf::from(name),
+            compression_type: decompressed.compression_type,
+            files: decompressed.files,
+        });
+        // Une ROM déjà chargée peut venir d'une autre source
+        self.rom_cache.clear();
+        Ok(())
+    }
+    
     /// Configure les paramètres de chargement
     pub fn set_load_config(&mut self, config: LoadConfig) {
         self.load_config = config;
     }
     
+    /// Patchs IPS/BPS à appliquer aux ROMs de chaque jeu (nom court)
+    pub fn set_patches(&mut self, patches: BTreeMap<String, Vec<RomPatchConfig>>) {
+        self.patches = patches;
+    }
+
+    /// Patchs appliqués (ou en échec) lors du dernier chargement de jeu
+    pub fn patch_notes(&self) -> &[String] {
+        &self.patch_notes
+    }
+
+    /// Applique aux ROMs chargées les patchs déclarés pour le jeu ; un patch
+    /// illisible ou qui ne correspond pas laisse la ROM d'origine
+    fn apply_patches(&mut self, game: &str, roms: &mut HashMap<String, LoadedRom>) {
+        self.patch_notes.clear();
+        for patch in self.patches.get(game).into_iter().flatten() {
+            let Some(rom) = roms.get_mut(&patch.rom) else {
+                self.patch_notes.push(format!("❌ {} : ROM {} absente", patch.file.display(), patch.rom));
+                continue;
+            };
+            let result = RomPatch::load(&patch.file).and_then(|loaded| Ok((loaded.format, loaded.apply(&rom.data)?)));
+            match result {
+                Ok((format, data)) => {
+                    self.patch_notes.push(format!(
+                        "✅ {} ({:?}) appliqué à {} ({} -> {} octets)",
+                        patch.file.display(), format, patch.rom, rom.data.len(), data.len()
+                    ));
+                    rom.data = data;
+                    rom.patches.push(patch.file.clone());
+                },
+                Err(e) => {
+                    eprintln!("Patch {} ignoré: {}", patch.file.display(), e);
+                    self.patch_notes.push(format!("❌ {} : {}", patch.file.display(), e));
+                }
+            }
+        }
+    }
+
     /// Charge un jeu complet avec toutes ses ROMs
     pub fn load_game(&mut self, game_name: &str) -> Result<RomSet> {
         let game_info = self.database.find_game(game_name)
-            .ok_or_else(|| anyhow!("Jeu non trouvé: {}", game_name))?
+            .ok_or_else(|| RomError::GameNotFound(game_name.to_string()))?
             .clone();
         
         println!("Chargement du jeu: {}", game_info.name);
@@ -178,6 +272,9 @@ impl RomManager {
             },
         };
         
+        // Sets organisés comme pour MAME : archive du jeu, puis de son parent
+        self.set_names = std::iter::once(game_info.short_name.clone()).chain(game_info.parent.clone()).collect();
+
         // Charger les ROMs requises
         for rom_info in &game_info.required_roms {
             match self.load_rom(&rom_info.filename, Some(rom_info)) {
@@ -209,6 +306,9 @@ impl RomManager {
             }
         }
         
+        // Patchs appliqués après la validation des ROMs d'origine
+        self.apply_patches(&game_info.short_name, &mut rom_set.roms);
+
         // Créer le mapping mémoire
         rom_set.memory_map = self.create_memory_map(&rom_set)?;
         
@@ -228,14 +328,28 @@ impl RomManager {
             return Ok(cached_rom.clone());
         }
         
-        // Chercher le fichier
-        let file_path = self.find_rom_file(filename)?;
-        
-        // Décompresser si nécessaire
-        let decompression_result = RomDecompressor::decompress_file(&file_path)?;
-        
-        // Trouver la ROM dans les fichiers décompressés
-        let (rom_filename, rom_data) = self.find_rom_in_files(filename, decompression_result.files)?;
+        let (file_path, compression_type, rom_filename, rom_data) = match self.find_provided_rom(filename) {
+            Some((provided, (rom_filename, rom_data))) => (
+                provided.source.clone(),
+                provided.compression_type.clone(),
+                rom_filename.clone(),
+                rom_data.clone(),
+            ),
+            None => match self.find_rom_file(filename) {
+                Ok(file_path) => {
+                    // Décompresser si nécessaire
+                    let decompression_result = RomDecompressor::decompress_file(&file_path)?;
+
+                    // Trouver la ROM dans les fichiers décompressés
+                    let (rom_filename, rom_data) = self.find_rom_in_files(filename, decompression_result.files)?;
+                    (file_path, decompression_result.compression_type, rom_filename, rom_data)
+                }
+                Err(e) => {
+                    let (file_path, rom_data) = self.find_rom_in_set_archives(filename).ok_or(e)?;
+                    (file_path, CompressionType::Zip, filename.to_string(), rom_data)
+                }
+            },
+        };
         
         // Créer les informations de ROM si non fournies
         let rom_info = if let Some(info) = expected_info {
@@ -273,7 +387,8 @@ impl RomManager {
             info: rom_info,
             validation,
             source_path: file_path,
-            compression_type: decompression_result.compression_type,
+            compression_type,
+            patches: Vec::new(),
         };
         
         // Ajouter au cache
@@ -283,6 +398,21 @@ impl RomManager {
         Ok(loaded_rom)
     }
     
+    /// Recherche une ROM parmi les fichiers fournis en mémoire : nom exact,
+    /// puis nom sans extension
+    fn find_provided_rom(&self, filename: &str) -> Option<(&ProvidedFile, &(String, Vec<u8>))> {
+        let stem = |name: &str| Path::new(name).file_stem().and_then(|s| s.to_str()).map(str::to_string);
+        let target_stem = stem(filename);
+        let matches_name = |exact: bool| {
+            self.provided_files.iter().find_map(|provided| {
+                provided.files.iter()
+                    .find(|(name, _)| if exact { name == filename } else { stem(name) == target_stem })
+                    .map(|file| (provided, file))
+            })
+        };
+        matches_name(true).or_else(|| matches_name(false))
+    }
+    
     /// Recherche un fichier ROM dans les chemins configurés
     fn find_rom_file(&self, filename: &str) -> Result<PathBuf> {
         for search_path in &self.search_paths {
@@ -297,8 +427,9 @@ impl RomManager {
             }
             
             // Recherche récursive avec extensions
+            #[cfg(feature = "filesystem")]
             for entry in WalkDir::new(search_path).max_depth(3) {
-                let entry = entry.map_err(|e| anyhow!("Erreur de lecture: {}", e))?;
+                let entry = entry.map_err(std::io::Error::from)?;
                 let path = entry.path();
                 
                 if path.is_file() {
@@ -321,9 +452,21 @@ impl RomManager {
             }
         }
         
-        Err(anyhow!("ROM non trouvée: {}", filename))
+        Err(RomError::NotFound(filename.to_string()).into())
     }
     
+    /// Recherche une ROM par son nom exact dans les archives de sets du jeu
+    /// en cours de chargement (`vf2.zip`, puis l'archive du parent)
+    fn find_rom_in_set_archives(&self, filename: &str) -> Option<(PathBuf, Vec<u8>)> {
+        self.set_names.iter().find_map(|set| {
+            let path = self.find_rom_file(&format!("{}.zip", set)).ok()?;
+            let files = RomDecompressor::decompress_file(&path).ok()?.files;
+            // Les sets fusionnés rangent parfois un clone dans un sous-dossier
+            let (_, data) = files.into_iter().find(|(name, _)| name.rsplit('/').next() == Some(filename))?;
+            Some((path, data))
+        })
+    }
+
     /// Trouve une ROM spécifique dans une liste de fichiers décompressés
     fn find_rom_in_files(&self, target_filename: &str, files: Vec<(String, Vec<u8>)>) -> Result<(String, Vec<u8>)> {
         // Recherche exacte
@@ -353,7 +496,7 @@ impl RomManager {
             return Ok(files.into_iter().next().unwrap());
         }
         
-        Err(anyhow!("ROM {} non trouvée dans l'archive", target_filename))
+        Err(RomError::NotInArchive(target_filename.to_string()).into())
     }
     
     /// Crée le mapping mémoire pour un ensemble de ROMs
@@ -410,17 +553,19 @@ impl RomManager {
         Ok(())
     }
     
-    /// Liste les ROMs disponibles dans les chemins de recherche
+    /// Liste les ROMs disponibles dans les chemins de recherche, puis celles
+    /// des fichiers fournis en mémoire (`archive.zip/rom.ic1`)
     pub fn scan_available_roms(&self) -> Result<Vec<PathBuf>> {
         let mut roms = Vec::new();
         
+        #[cfg(feature = "filesystem")]
         for search_path in &self.search_paths {
             if !search_path.exists() {
                 continue;
             }
             
             for entry in WalkDir::new(search_path).max_depth(3) {
-                let entry = entry.map_err(|e| anyhow!("Erreur de lecture: {}", e))?;
+                let entry = entry.map_err(std::io::Error::from)?;
                 let path = entry.path();
                 
                 if path.is_file() {
@@ -433,9 +578,22 @@ impl RomManager {
             }
         }
         
+        for provided in &self.provided_files {
+            match provided.compression_type {
+                CompressionType::None => roms.push(provided.source.clone()),
+                _ => roms.extend(provided.files.iter().map(|(name, _)| provided.source.join(name))),
+            }
+        }
+        
         Ok(roms)
     }
     
+    /// Audite les jeux de la base d'après les ROMs disponibles, triés par nom
+    pub fn audit_games(&self) -> Result<Vec<GameAudit>> {
+        let available_roms = self.scan_available_roms()?;
+        Ok(audit_games(&self.database, &available_roms, &self.load_config.file_extensions))
+    }
+
     /// Génère un rapport sur les ROMs disponibles
     pub fn generate_availability_report(&self) -> Result<String> {
         let mut report = String::new();
@@ -450,19 +608,11 @@ impl RomManager {
         
         report.push_str("\n=== JEUX SUPPORTÉS ===\n\n");
         
-        for game in self.database.list_games() {
-            report.push_str(&format!("{} ({})\n", game.name, game.short_name));
-            
-            let mut available_count = 0;
-            for rom_info in &game.required_roms {
-                if available_roms.iter().any(|p| p.file_name().map(|n| n.to_string_lossy()).as_deref() == Some(&rom_info.filename)) {
-                    available_count += 1;
-                }
-            }
-            
-            report.push_str(&format!("  ROMs disponibles: {}/{}\n", available_count, game.required_roms.len()));
+        for audit in audit_games(&self.database, &available_roms, &self.load_config.file_extensions) {
+            report.push_str(&format!("{} ({})\n", audit.name, audit.short_name));
+            report.push_str(&format!("  ROMs disponibles: {}/{}\n", audit.found, audit.required));
             
-            if available_count == game.required_roms.len() {
+            if audit.is_playable() {
                 report.push_str("  ✅ Prêt à jouer\n");
             } else {
                 report.push_str("  ❌ ROMs manquantes\n");
@@ -521,6 +671,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "filesystem")]
     fn test_scan_available_roms() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let mut manager = RomManager::new();
@@ -537,4 +688,46 @@ mod tests {
         
         Ok(())
     }
+
+    #[test]
+    fn test_provided_rom_preferred() -> Result<()> {
+        let mut manager = RomManager::new();
+        manager.search_paths.clear();
+        manager.add_rom_file("epr-17567.ic12", vec![0x12; 8])?;
+        manager.add_rom_file("epr-17568.bin", vec![0x34; 8])?;
+
+        let rom = manager.load_rom("epr-17567.ic12", None)?;
+        assert_eq!(rom.data, vec![0x12; 8]);
+        assert_eq!(rom.source_path, PathBuf::from("epr-17567.ic12"));
+
+        // Même nom sans l'extension
+        assert_eq!(manager.load_rom("epr-17568.ic13", None)?.data, vec![0x34; 8]);
+        assert!(manager.load_rom("epr-17569.ic14", None).is_err());
+
+        assert_eq!(manager.scan_available_roms()?.len(), 2);
+        Ok(())
+    }
+    #[test]
+    fn test_patches_applied_after_loading() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let patch_path = temp_dir.path().join("vf2.ips");
+        fs::write(&patch_path, b"PATCH\x00\x00\x02\x00\x01\xEEEOF")?;
+
+        let mut manager = RomManager::new();
+        manager.search_paths.clear();
+        manager.add_rom_file("epr-17574.30", vec![0x11; 4])?;
+        let patch = |rom: &str| RomPatchConfig { rom: rom.to_string(), file: patch_path.clone() };
+        manager.set_patches(BTreeMap::from([("vf2".to_string(), vec![patch("epr-17574.30"), patch("absente.bin")])]));
+
+        let rom_set = manager.load_game("vf2")?;
+        let rom = &rom_set.roms["epr-17574.30"];
+        assert_eq!(rom.data, vec![0x11, 0x11, 0xEE, 0x11]);
+        assert_eq!(rom.patches, vec![patch_path.clone()]);
+        assert!(manager.patch_notes()[0].starts_with("✅"));
+        assert!(manager.patch_notes()[1].starts_with("❌"));
+
+        // La ROM du cache reste intacte
+        assert_eq!(manager.load_rom("epr-17574.30", None)?.data, vec![0x11; 4]);
+        Ok(())
+    }
 }
\ No newline at end of file
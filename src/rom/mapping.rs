@@ -1,11 +1,11 @@
 //! Mapping ROM vers système mémoire SEGA Model 2
 
-use anyhow::{Result, anyhow};
+use crate::error::{Result, RomError};
 use std::collections::HashMap;
 
 use super::loader::{RomSet, LoadedRom};
 use super::database::RomType;
-use crate::memory::MemoryInterface;
+use crate::memory::{ByteOrder, MemoryInterface};
 
 /// Gestionnaire de mapping ROM vers mémoire système
 pub struct RomMemoryMapper {
@@ -103,8 +103,11 @@ impl RomMemoryMapper {
         
         // Vérifier la taille
         if loaded_rom.data.len() > self.mapping_config.bank_size as usize {
-            return Err(anyhow!("ROM {} trop grande pour une banque ({} > {})", 
-                              rom_name, loaded_rom.data.len(), self.mapping_config.bank_size));
+            return Err(RomError::TooLarge {
+                name: rom_name.to_string(),
+                size: loaded_rom.data.len(),
+                max: self.mapping_config.bank_size as usize,
+            }.into());
         }
         
         // Écrire les données en mémoire
@@ -162,13 +165,13 @@ impl RomMemoryMapper {
     
     /// Configure le mapping spécifique aux ROMs programme
     fn setup_program_rom_mapping(&self, base_address: u32, data: &[u8], _memory: &mut dyn MemoryInterface) -> Result<()> {
-        // Configuration pour CPU 68000
         println!("Configuration ROM programme à 0x{:08X}", base_address);
         
-        // Vérifier les vecteurs d'interruption (premiers 1024 octets)
+        // Vérifier les vecteurs d'interruption (premiers 1024 octets), dans
+        // l'ordre petit-boutiste du V60
         if data.len() >= 1024 {
-            let stack_pointer = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
-            let reset_vector = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+            let stack_pointer = ByteOrder::Little.read_u32(data, 0).unwrap_or_default();
+            let reset_vector = ByteOrder::Little.read_u32(data, 4).unwrap_or_default();
             
             println!("  Stack Pointer initial: 0x{:08X}", stack_pointer);
             println!("  Reset Vector: 0x{:08X}", reset_vector);
@@ -303,6 +306,11 @@ impl RomMemoryMapper {
         Ok(())
     }
     
+    /// Vrai si la ROM `filename` fait partie de l'ensemble mappé
+    pub fn is_loaded(&self, filename: &str) -> bool {
+        self.current_rom_set.as_ref().is_some_and(|rom_set| rom_set.roms.contains_key(filename))
+    }
+
     /// Obtient les informations sur le mapping actuel
     pub fn get_mapping_info(&self) -> Option<MappingInfo> {
         self.current_rom_set.as_ref().map(|rom_set| {
@@ -10,12 +10,21 @@
 //! - `validation`: Validation d'intégrité des ROMs (CRC32, MD5, SHA256)
 //! - `loader`: Chargement et gestion des ensembles de ROMs
 //! - `mapping`: Mapping mémoire des ROMs vers l'espace d'adressage Model 2
+//! - `audit`: ROMs requises présentes pour chaque jeu
+//! - `compatibility`: Rapport de compatibilité livré avec l'émulateur
+//! - `patch`: Patchs IPS/BPS appliqués aux ROMs chargées
+//! - `romset`: Conversion des dossiers de ROMs entre sets séparés, fusionnés et non fusionnés
 
 pub mod database;
 pub mod decompression;
 pub mod validation;
 pub mod loader;
 pub mod mapping;
+pub mod audit;
+pub mod compatibility;
+pub mod patch;
+#[cfg(all(feature = "filesystem", feature = "rom-tools"))]
+pub mod romset;
 
 #[cfg(test)]
 pub mod integration_tests;
@@ -26,6 +35,11 @@ pub use decompression::{RomDecompressor, CompressionType};
 pub use validation::{RomValidator, ValidationResult};
 pub use loader::{RomManager, RomSet, LoadedRom, LoadConfig};
 pub use mapping::{RomMemoryMapper, Model2MemoryConfig, MappingInfo};
+pub use audit::{AuditStatus, GameAudit};
+pub use compatibility::{CompatibilityEntry, CompatibilityReport, CompatibilityStatus, KnownIssue};
+pub use patch::{PatchFormat, RomPatch};
+#[cfg(all(feature = "filesystem", feature = "rom-tools"))]
+pub use romset::{convert_rom_folder, set_contents, ConversionReport, SetLayout};
 
 /// Système de ROM complet pour SEGA Model 2
 /// 
@@ -48,7 +62,7 @@ impl Model2RomSystem {
     }
     
     /// Charge un jeu et l'installe en mémoire
-    pub fn load_and_map_game(&mut self, game_name: &str, memory: &mut dyn crate::memory::MemoryInterface) -> anyhow::Result<()> {
+    pub fn load_and_map_game(&mut self, game_name: &str, memory: &mut dyn crate::memory::MemoryInterface) -> crate::error::Result<()> {
         // Charger le jeu
         let rom_set = self.rom_manager.load_game(game_name)?;
         
@@ -58,10 +72,20 @@ impl Model2RomSystem {
         Ok(())
     }
     
+    /// Vrai si la ROM `filename` a été chargée avec le dernier jeu
+    pub fn is_rom_loaded(&self, filename: &str) -> bool {
+        self.memory_mapper.is_loaded(filename)
+    }
+
     /// Ajoute un chemin de recherche pour les ROMs
     pub fn add_search_path<P: AsRef<std::path::Path>>(&mut self, path: P) {
         self.rom_manager.add_search_path(path);
     }
+
+    /// Fournit une ROM ou une archive déjà en mémoire (fichier choisi dans un navigateur)
+    pub fn add_rom_file(&mut self, name: &str, data: Vec<u8>) -> crate::error::Result<()> {
+        self.rom_manager.add_rom_file(name, data)
+    }
     
     /// Configure le mapping mémoire
     pub fn set_memory_config(&mut self, config: Model2MemoryConfig) {
@@ -69,12 +93,21 @@ impl Model2RomSystem {
     }
     
     /// Génère un rapport d'état complet
-    pub fn generate_status_report(&self) -> anyhow::Result<String> {
+    pub fn generate_status_report(&self) -> crate::error::Result<String> {
         let mut report = String::new();
         
         // Rapport de disponibilité ROM
         report.push_str(&self.rom_manager.generate_availability_report()?);
         report.push_str("\n\n");
+
+        // Patchs appliqués au dernier jeu chargé
+        if !self.rom_manager.patch_notes().is_empty() {
+            report.push_str("=== PATCHS ===\n\n");
+            for note in self.rom_manager.patch_notes() {
+                report.push_str(&format!("  {}\n", note));
+            }
+            report.push('\n');
+        }
         
         // Rapport de mapping mémoire
         if let Some(mapping_info) = self.memory_mapper.get_mapping_info() {
@@ -0,0 +1,315 @@
+//! Patchs de ROM au format IPS ou BPS
+//!
+//! Les patchs (traductions, corrections de bugs) sont déclarés par jeu dans
+//! la configuration et appliqués par le [`RomManager`](super::RomManager)
+//! après la validation des ROMs d'origine, avant le mapping. Une ROM patchée
+//! n'est jamais remise dans le cache : un autre jeu qui la partage la
+//! retrouve intacte.
+//!
+//! - IPS : enregistrements `offset (24 bits) / taille (16 bits) / données`,
+//!   avec une variante RLE, terminés par `EOF` et une taille de troncature
+//!   facultative ;
+//! - BPS : actions de copie depuis la source, le patch ou la cible déjà
+//!   écrite, avec des CRC32 de la source, de la cible et du patch.
+
+use crate::error::{Result, RomError};
+use crate::memory::ByteOrder;
+use std::path::Path;
+
+use super::validation::RomValidator;
+
+const IPS_MAGIC: &[u8] = b"PATCH";
+const IPS_EOF: &[u8] = b"EOF";
+const BPS_MAGIC: &[u8] = b"BPS1";
+
+/// Taille du pied BPS : CRC32 de la source, de la cible et du patch
+const BPS_FOOTER: usize = 12;
+
+/// Réservation maximale pour la cible BPS ; au-delà, elle grandit au fil
+/// des actions plutôt que sur la foi de la taille annoncée
+const BPS_MAX_RESERVATION: usize = 64 * 1024 * 1024;
+
+/// Format d'un patch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchFormat {
+    Ips,
+    Bps,
+}
+
+/// Patch chargé, prêt à être appliqué
+#[derive(Debug, Clone)]
+pub struct RomPatch {
+    pub format: PatchFormat,
+    data: Vec<u8>,
+}
+
+impl RomPatch {
+    /// Reconnaît le format à son en-tête
+    pub fn parse(data: Vec<u8>) -> Result<Self> {
+        let format = if data.starts_with(IPS_MAGIC) {
+            PatchFormat::Ips
+        } else if data.starts_with(BPS_MAGIC) {
+            PatchFormat::Bps
+        } else {
+            return Err(RomError::Patch("en-tête IPS ou BPS absent".to_string()).into());
+        };
+        Ok(Self { format, data })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(std::fs::read(path)?)
+    }
+
+    /// ROM patchée
+    pub fn apply(&self, rom: &[u8]) -> Result<Vec<u8>> {
+        match self.format {
+            PatchFormat::Ips => apply_ips(&self.data, rom),
+            PatchFormat::Bps => apply_bps(&self.data, rom),
+        }
+    }
+}
+
+fn corrupt(message: &str) -> crate::error::EmulatorError {
+    RomError::Patch(message.to_string()).into()
+}
+
+/// Lecteur séquentiel du contenu d'un patch
+struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8], position: usize) -> Self {
+        Self { data, position }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .position
+            .checked_add(length)
+            .and_then(|end| self.data.get(self.position..end))
+            .ok_or_else(|| corrupt("fin de patch inattendue"))?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn big_endian(&mut self, length: usize) -> Result<usize> {
+        Ok(self.take(length)?.iter().fold(0, |value, &byte| value << 8 | byte as usize))
+    }
+
+    /// Entier variable BPS : 7 bits par octet, bit 7 levé sur le dernier
+    fn varint(&mut self) -> Result<usize> {
+        let (mut value, mut shift) = (0usize, 1usize);
+        loop {
+            let byte = self.take(1)?[0] as usize;
+            value = value
+                .checked_add((byte & 0x7F).checked_mul(shift).ok_or_else(|| corrupt("entier trop grand"))?)
+                .ok_or_else(|| corrupt("entier trop grand"))?;
+            if byte & 0x80 != 0 {
+                return Ok(value);
+            }
+            shift = shift.checked_shl(7).ok_or_else(|| corrupt("entier trop grand"))?;
+            value = value.checked_add(shift).ok_or_else(|| corrupt("entier trop grand"))?;
+        }
+    }
+}
+
+fn apply_ips(patch: &[u8], rom: &[u8]) -> Result<Vec<u8>> {
+    let mut output = rom.to_vec();
+    let mut cursor = Cursor::new(patch, IPS_MAGIC.len());
+
+    loop {
+        if cursor.data[cursor.position..].starts_with(IPS_EOF) {
+            cursor.position += IPS_EOF.len();
+            break;
+        }
+        let offset = cursor.big_endian(3)?;
+        let size = cursor.big_endian(2)?;
+        let (length, fill) = if size == 0 {
+            (cursor.big_endian(2)?, Some(cursor.take(1)?[0]))
+        } else {
+            (size, None)
+        };
+
+        if output.len() < offset + length {
+            output.resize(offset + length, 0);
+        }
+        match fill {
+            Some(value) => output[offset..offset + length].fill(value),
+            None => output[offset..offset + length].copy_from_slice(cursor.take(length)?),
+        }
+    }
+
+    // Extension : taille finale de la ROM après EOF
+    if let Ok(size) = cursor.big_endian(3) {
+        output.truncate(size);
+    }
+    Ok(output)
+}
+
+fn apply_bps(patch: &[u8], source: &[u8]) -> Result<Vec<u8>> {
+    if patch.len() < BPS_MAGIC.len() + BPS_FOOTER {
+        return Err(corrupt("patch BPS tronqué"));
+    }
+    let footer = &patch[patch.len() - BPS_FOOTER..];
+    let crc = |index: usize| ByteOrder::Little.read_u32(footer, index * 4);
+    if Some(RomValidator::calculate_crc32(&patch[..patch.len() - 4])) != crc(2) {
+        return Err(corrupt("CRC32 du patch BPS incorrect"));
+    }
+    if Some(RomValidator::calculate_crc32(source)) != crc(0) {
+        return Err(corrupt("la ROM ne correspond pas à la source attendue par le patch BPS"));
+    }
+
+    let actions_end = patch.len() - BPS_FOOTER;
+    let mut cursor = Cursor::new(&patch[..actions_end], BPS_MAGIC.len());
+    let source_size = cursor.varint()?;
+    let target_size = cursor.varint()?;
+    let metadata_size = cursor.varint()?;
+    cursor.take(metadata_size)?;
+    if source_size != source.len() {
+        return Err(corrupt("taille de la source BPS incorrecte"));
+    }
+
+    let mut target = Vec::with_capacity(target_size.min(BPS_MAX_RESERVATION));
+    let (mut source_offset, mut target_offset) = (0usize, 0usize);
+    let relative = |cursor: &mut Cursor, offset: usize| -> Result<usize> {
+        let delta = cursor.varint()?;
+        let step = delta >> 1;
+        let moved = if delta & 1 != 0 { offset.checked_sub(step) } else { offset.checked_add(step) };
+        moved.ok_or_else(|| corrupt("décalage BPS hors limites"))
+    };
+
+    while cursor.position < actions_end {
+        let command = cursor.varint()?;
+        let length = (command >> 2) + 1;
+        // Action bornée par la taille annoncée avant d'écrire quoi que ce soit
+        let end = target.len().checked_add(length).filter(|&end| end <= target_size);
+        let end = end.ok_or_else(|| corrupt("cible BPS plus grande qu'annoncé"))?;
+        match command & 3 {
+            // SourceRead : mêmes octets qu'à la même position de la source
+            0 => {
+                let bytes = source.get(target.len()..end).ok_or_else(|| corrupt("lecture hors de la source"))?;
+                target.extend_from_slice(bytes);
+            }
+            // TargetRead : octets contenus dans le patch
+            1 => target.extend_from_slice(cursor.take(length)?),
+            // SourceCopy : copie depuis une position relative de la source
+            2 => {
+                source_offset = relative(&mut cursor, source_offset)?;
+                let bytes = source_offset
+                    .checked_add(length)
+                    .and_then(|source_end| source.get(source_offset..source_end))
+                    .ok_or_else(|| corrupt("copie hors de la source"))?;
+                target.extend_from_slice(bytes);
+                source_offset += length;
+            }
+            // TargetCopy : copie octet par octet de la cible déjà écrite
+            _ => {
+                target_offset = relative(&mut cursor, target_offset)?;
+                for _ in 0..length {
+                    let byte = *target.get(target_offset).ok_or_else(|| corrupt("copie hors de la cible"))?;
+                    target.push(byte);
+                    target_offset += 1;
+                }
+            }
+        }
+    }
+
+    if target.len() != target_size || Some(RomValidator::calculate_crc32(&target)) != crc(1) {
+        return Err(corrupt("CRC32 de la ROM patchée incorrect"));
+    }
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: usize, out: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte | 0x80);
+                return;
+            }
+            out.push(byte);
+            value -= 1;
+        }
+    }
+
+    #[test]
+    fn test_ips_records_rle_and_truncation() {
+        let mut patch = b"PATCH".to_vec();
+        patch.extend_from_slice(&[0x00, 0x00, 0x01, 0x00, 0x02, 0xAA, 0xBB]); // 2 octets à 1
+        patch.extend_from_slice(&[0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x03, 0x7F]); // RLE, au-delà de la fin
+        patch.extend_from_slice(b"EOF");
+
+        let rom = [0u8; 4];
+        let patched = RomPatch::parse(patch.clone()).unwrap().apply(&rom).unwrap();
+        assert_eq!(patched, vec![0, 0xAA, 0xBB, 0, 0, 0, 0x7F, 0x7F, 0x7F]);
+
+        patch.extend_from_slice(&[0x00, 0x00, 0x03]);
+        assert_eq!(RomPatch::parse(patch).unwrap().apply(&rom).unwrap(), vec![0, 0xAA, 0xBB]);
+        assert!(RomPatch::parse(b"PATCH\x00\x00".to_vec()).unwrap().apply(&rom).is_err());
+        assert!(RomPatch::parse(b"UPS1".to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_bps_actions_and_checksums() {
+        let source = b"SEGA MODEL 2";
+        let target = b"SEGA MODEL 2 SEGA SEGA!";
+
+        let mut patch = b"BPS1".to_vec();
+        varint(source.len(), &mut patch);
+        varint(target.len(), &mut patch);
+        varint(0, &mut patch);
+        varint((source.len() - 1) << 2, &mut patch); // SourceRead de toute la source
+        varint(1 << 2 | 1, &mut patch); // TargetRead " S"
+        patch.extend_from_slice(b" S");
+        varint(2 << 2 | 2, &mut patch); // SourceCopy "EGA" depuis 1
+        varint(1 << 1, &mut patch);
+        varint(4 << 2 | 3, &mut patch); // TargetCopy " SEGA" depuis 12
+        varint(12 << 1, &mut patch);
+        varint(1, &mut patch); // TargetRead "!"
+        patch.push(b'!');
+        patch.extend_from_slice(&RomValidator::calculate_crc32(source).to_le_bytes());
+        patch.extend_from_slice(&RomValidator::calculate_crc32(target).to_le_bytes());
+        let crc = RomValidator::calculate_crc32(&patch);
+        patch.extend_from_slice(&crc.to_le_bytes());
+
+        let bps = RomPatch::parse(patch).unwrap();
+        assert_eq!(bps.format, PatchFormat::Bps);
+        assert_eq!(bps.apply(source).unwrap(), target.to_vec());
+        assert!(bps.apply(b"SEGA MODEL 3").is_err());
+    }
+
+    #[test]
+    fn test_bps_hostile_sizes_are_rejected() {
+        let source = b"SEGA";
+        let with_footer = |mut patch: Vec<u8>| {
+            patch.extend_from_slice(&RomValidator::calculate_crc32(source).to_le_bytes());
+            patch.extend_from_slice(&0u32.to_le_bytes());
+            let crc = RomValidator::calculate_crc32(&patch);
+            patch.extend_from_slice(&crc.to_le_bytes());
+            RomPatch::parse(patch).unwrap()
+        };
+
+        // Métadonnées annoncées jusqu'au bout de l'espace d'adressage
+        let mut patch = b"BPS1".to_vec();
+        varint(source.len(), &mut patch);
+        varint(1, &mut patch);
+        varint(usize::MAX, &mut patch);
+        assert!(with_footer(patch).apply(source).is_err());
+
+        // Cible démesurée : refusée à la première action qui la dépasse
+        let mut patch = b"BPS1".to_vec();
+        varint(source.len(), &mut patch);
+        varint(usize::MAX >> 1, &mut patch);
+        varint(0, &mut patch);
+        varint(((usize::MAX >> 2) - 1) << 2 | 3, &mut patch);
+        varint(0, &mut patch);
+        assert!(with_footer(patch).apply(source).is_err());
+    }
+}
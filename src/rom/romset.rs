@@ -0,0 +1,371 @@
+//! Conversion des dossiers de ROMs entre organisations de sets
+//!
+//! Les collections rangées pour MAME regroupent les ROMs d'un jeu dans une
+//! archive au nom du set (`vf2.zip`), selon l'une de trois organisations :
+//!
+//! - séparée (`split`) : l'archive d'un clone ne contient que les ROMs
+//!   absentes de son parent ;
+//! - fusionnée (`merged`) : les ROMs des clones sont rangées dans l'archive
+//!   du parent ;
+//! - non fusionnée (`non-merged`) : chaque archive contient toutes les ROMs
+//!   de son jeu.
+//!
+//! [`convert_rom_folder`] repère les ROMs de la base dans un dossier source,
+//! quelle que soit sa disposition (archives de sets, archives d'une ROM,
+//! fichiers isolés), et écrit dans un autre dossier une archive ZIP par set
+//! selon l'organisation demandée. Le dossier source n'est jamais modifié.
+//! Les ROMs dont la base connaît le CRC32 sont reconnues à leur contenu, quel
+//! que soit leur nom ; les autres, par leur nom de fichier.
+//!
+//! La base intégrée ne déclare aucun clone : les trois organisations y
+//! donnent une archive par jeu. Les liens parent/clone viennent d'une base
+//! JSON chargée par [`GameDatabase::load_from_file`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::database::{GameDatabase, GameInfo};
+use super::database::RomInfo;
+use super::decompression::RomDecompressor;
+use super::validation::RomValidator;
+use crate::error::{Result, RomError};
+
+/// Profondeur de recherche dans le dossier source, comme au chargement
+const SCAN_DEPTH: usize = 3;
+
+/// Organisation des archives de sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SetLayout {
+    #[default]
+    Split,
+    Merged,
+    NonMerged,
+}
+
+impl SetLayout {
+    pub fn name(self) -> &'static str {
+        match self {
+            SetLayout::Split => "split",
+            SetLayout::Merged => "merged",
+            SetLayout::NonMerged => "non-merged",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "split" => Some(SetLayout::Split),
+            "merged" => Some(SetLayout::Merged),
+            "non-merged" | "nonmerged" => Some(SetLayout::NonMerged),
+            _ => None,
+        }
+    }
+}
+
+/// ROMs d'un jeu, requises et optionnelles
+fn roms(game: &GameInfo) -> impl Iterator<Item = &RomInfo> {
+    game.required_roms.iter().chain(&game.optional_roms)
+}
+
+/// Deux ROMs sont la même si leurs CRC32 connus sont égaux ; faute de CRC,
+/// on s'en remet au nom de fichier
+fn same_rom(a: &RomInfo, b: &RomInfo) -> bool {
+    if a.crc32 != 0 && b.crc32 != 0 {
+        a.crc32 == b.crc32
+    } else {
+        a.filename == b.filename
+    }
+}
+
+/// ROMs à ranger dans l'archive de chaque set, par nom de set puis par nom
+/// d'entrée dans l'archive. Dans un set fusionné, la ROM d'un clone qui porte
+/// le nom d'une ROM différente du parent est rangée sous `clone/nom`.
+pub fn set_contents(database: &GameDatabase, layout: SetLayout) -> BTreeMap<String, BTreeMap<String, &RomInfo>> {
+    let mut sets: BTreeMap<String, BTreeMap<String, &RomInfo>> = BTreeMap::new();
+    let mut games = database.list_games();
+    // Les parents d'abord, pour que leurs ROMs gardent leur nom dans un set fusionné
+    games.sort_by_key(|game| (game.parent.is_some(), game.short_name.clone()));
+    for game in games {
+        let parent = game.parent.as_deref().and_then(|parent| database.find_game(parent));
+        let (set, inherited): (&str, Vec<&RomInfo>) = match (layout, parent) {
+            (SetLayout::Split, Some(parent)) => (&game.short_name, roms(parent).collect()),
+            (SetLayout::Merged, Some(parent)) => (&parent.short_name, Vec::new()),
+            _ => (&game.short_name, Vec::new()),
+        };
+        let contents = sets.entry(set.to_string()).or_default();
+        for rom in roms(game).filter(|rom| !inherited.iter().any(|parent_rom| same_rom(rom, parent_rom))) {
+            match contents.get(&rom.filename) {
+                Some(existing) if same_rom(existing, rom) => {}
+                Some(_) => {
+                    contents.insert(format!("{}/{}", game.short_name, rom.filename), rom);
+                }
+                None => {
+                    contents.insert(rom.filename.clone(), rom);
+                }
+            }
+        }
+    }
+    sets.retain(|_, roms| !roms.is_empty());
+    sets
+}
+
+/// Emplacement d'une ROM dans le dossier source
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RomSource {
+    /// Entrée d'une archive ZIP
+    Archive { path: PathBuf, entry: String },
+    /// Fichier isolé, éventuellement compressé en GZIP
+    File(PathBuf),
+}
+
+impl RomSource {
+    fn read(&self) -> Result<Vec<u8>> {
+        match self {
+            RomSource::Archive { path, entry } => {
+                let mut archive = ZipArchive::new(File::open(path)?)?;
+                let mut data = Vec::new();
+                archive.by_name(entry)?.read_to_end(&mut data)?;
+                Ok(data)
+            }
+            RomSource::File(path) => {
+                let mut files = RomDecompressor::decompress_file(path)?.files;
+                files.pop().map(|(_, data)| data).ok_or_else(|| RomError::NotFound(path.display().to_string()).into())
+            }
+        }
+    }
+}
+
+/// Fichiers du dossier source, par nom de ROM et par CRC32 de contenu ; une
+/// ROM présente plusieurs fois garde sa première occurrence
+#[derive(Debug, Default)]
+struct FolderIndex {
+    by_name: BTreeMap<String, RomSource>,
+    by_crc: BTreeMap<u32, RomSource>,
+}
+
+impl FolderIndex {
+    fn insert(&mut self, name: &str, crc32: u32, source: RomSource) {
+        self.by_crc.entry(crc32).or_insert_with(|| source.clone());
+        self.by_name.entry(name.to_string()).or_insert(source);
+    }
+
+    /// Fichier d'une ROM de la base : par CRC32 s'il est connu, sinon par nom
+    fn locate(&self, rom: &RomInfo) -> Option<&RomSource> {
+        if rom.crc32 != 0 {
+            self.by_crc.get(&rom.crc32)
+        } else {
+            self.by_name.get(&rom.filename)
+        }
+    }
+}
+
+/// Repère les fichiers du dossier source ; le CRC32 des entrées d'archive est
+/// lu dans le répertoire central, celui des fichiers isolés est calculé
+fn index_folder(source: &Path) -> Result<FolderIndex> {
+    let mut index = FolderIndex::default();
+    let mut paths: Vec<PathBuf> = WalkDir::new(source)
+        .max_depth(SCAN_DEPTH)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("zip") => {
+                let mut archive = ZipArchive::new(File::open(&path)?)?;
+                for i in 0..archive.len() {
+                    let file = archive.by_index_raw(i)?;
+                    if file.is_dir() {
+                        continue;
+                    }
+                    let entry = file.name().to_string();
+                    // Les sets fusionnés rangent parfois un clone dans un sous-dossier
+                    let name = entry.rsplit('/').next().unwrap_or(&entry).to_string();
+                    let crc32 = file.crc32();
+                    index.insert(&name, crc32, RomSource::Archive { path: path.clone(), entry });
+                }
+            }
+            Some("gz") => {
+                if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    let source = RomSource::File(path.clone());
+                    let crc32 = RomValidator::calculate_crc32(&source.read()?);
+                    index.insert(stem, crc32, source);
+                }
+            }
+            _ => {
+                if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                    let crc32 = RomValidator::calculate_crc32(&std::fs::read(&path)?);
+                    index.insert(name, crc32, RomSource::File(path.clone()));
+                }
+            }
+        }
+    }
+    Ok(index)
+}
+
+/// Bilan d'une conversion
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub layout: SetLayout,
+
+    /// Archives écrites
+    pub archives: usize,
+
+    /// ROMs copiées
+    pub roms: usize,
+
+    /// ROMs absentes du dossier source : (set, ROM)
+    pub missing: Vec<(String, String)>,
+}
+
+impl fmt::Display for ConversionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Organisation {} : {} archives, {} ROMs copiées", self.layout.name(), self.archives, self.roms)?;
+        for (set, rom) in &self.missing {
+            writeln!(f, "  ❌ {} : {} absente", set, rom)?;
+        }
+        Ok(())
+    }
+}
+
+/// Chemin absolu sans lien symbolique ; la partie qui n'existe pas encore est
+/// ajoutée telle quelle au plus proche ancêtre existant
+fn resolve_path(path: &Path) -> Result<PathBuf> {
+    let absolute = std::path::absolute(path)?;
+    for ancestor in absolute.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            return Ok(canonical.join(absolute.strip_prefix(ancestor).unwrap_or(Path::new(""))));
+        }
+    }
+    Ok(absolute)
+}
+
+/// Écrit dans `destination` une archive par set d'après les ROMs trouvées
+/// dans `source` ; les sets sans aucune ROM présente ne sont pas écrits
+pub fn convert_rom_folder(database: &GameDatabase, source: &Path, destination: &Path, layout: SetLayout) -> Result<ConversionReport> {
+    let (resolved_source, resolved_destination) = (resolve_path(source)?, resolve_path(destination)?);
+    if resolved_destination.starts_with(&resolved_source) || resolved_source.starts_with(&resolved_destination) {
+        return Err(RomError::Database(format!(
+            "le dossier de destination {} ne doit pas contenir ni être contenu dans le dossier source {}",
+            destination.display(), source.display()
+        )).into());
+    }
+
+    let index = index_folder(source)?;
+    std::fs::create_dir_all(destination)?;
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut report = ConversionReport { layout, ..ConversionReport::default() };
+
+    for (set, roms) in set_contents(database, layout) {
+        let mut present = Vec::new();
+        for (entry, rom) in &roms {
+            match index.locate(rom) {
+                Some(source) => present.push((entry, source)),
+                None => report.missing.push((set.clone(), entry.clone())),
+            }
+        }
+        if present.is_empty() {
+            continue;
+        }
+
+        let mut archive = ZipWriter::new(File::create(destination.join(format!("{}.zip", set)))?);
+        for (entry, source) in present {
+            archive.start_file(entry.as_str(), options)?;
+            archive.write_all(&source.read()?)?;
+            report.roms += 1;
+        }
+        archive.finish()?;
+        report.archives += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clone_of_vf2(database: &GameDatabase) -> GameInfo {
+        let mut clone = database.find_game("vf2").unwrap().clone();
+        clone.short_name = "vf2a".to_string();
+        clone.name = "Virtua Fighter 2 (Revision A)".to_string();
+        clone.parent = Some("vf2".to_string());
+        clone.required_roms[0].filename = "epr-17574a.30".to_string();
+        clone
+    }
+
+    fn archive_names(path: &Path) -> Vec<String> {
+        let archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_convert_between_split_merged_and_non_merged() {
+        let mut database = GameDatabase::new();
+        database.add_game(clone_of_vf2(&database));
+        let sets = |layout| set_contents(&database, layout);
+        assert_eq!(sets(SetLayout::Split)["vf2a"].keys().collect::<Vec<_>>(), ["epr-17574a.30"]);
+        assert_eq!(sets(SetLayout::NonMerged)["vf2a"].len(), 2);
+        assert!(!sets(SetLayout::Merged).contains_key("vf2a"));
+        assert_eq!(sets(SetLayout::Merged)["vf2"].len(), 3);
+
+        // Dossier d'origine : une ROM isolée, les autres dans une archive de set
+        let root = std::env::temp_dir().join(format!("pm2_romset_{}", std::process::id()));
+        let (source, split, merged) = (root.join("source"), root.join("split"), root.join("merged"));
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("epr-17574a.30"), [0xA5; 16]).unwrap();
+        let mut archive = ZipWriter::new(File::create(source.join("vf2.zip")).unwrap());
+        for rom in ["epr-17574.30", "epr-18022.ic2"] {
+            archive.start_file(rom, FileOptions::default()).unwrap();
+            archive.write_all(rom.as_bytes()).unwrap();
+        }
+        archive.finish().unwrap();
+
+        let report = convert_rom_folder(&database, &source, &merged, SetLayout::Merged).unwrap();
+        assert_eq!((report.archives, report.roms), (1, 3));
+        assert_eq!(archive_names(&merged.join("vf2.zip")), ["epr-17574.30", "epr-17574a.30", "epr-18022.ic2"]);
+        assert!(report.missing.iter().all(|(set, _)| set != "vf2"));
+
+        // Retour à des sets séparés depuis le set fusionné
+        let report = convert_rom_folder(&database, &merged, &split, SetLayout::Split).unwrap();
+        assert_eq!((report.archives, report.roms), (2, 3));
+        assert_eq!(archive_names(&split.join("vf2a.zip")), ["epr-17574a.30"]);
+        let copied = RomSource::Archive { path: split.join("vf2a.zip"), entry: "epr-17574a.30".to_string() };
+        assert_eq!(copied.read().unwrap(), [0xA5; 16]);
+
+        assert!(convert_rom_folder(&database, &source, &source.join("out"), SetLayout::Split).is_err());
+        assert!(convert_rom_folder(&database, &source, &source.join("..").join("source").join("out"), SetLayout::Split).is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_roms_with_known_crc_are_matched_by_content() {
+        let mut database = GameDatabase::new();
+        let program = b"programme".to_vec();
+        database.update_rom_checksums("vf2", "epr-17574.30", RomValidator::calculate_crc32(&program), String::new());
+
+        // Programme renommé, et un fichier au bon nom mais au mauvais contenu
+        let root = std::env::temp_dir().join(format!("pm2_romset_crc_{}", std::process::id()));
+        let (source, output) = (root.join("source"), root.join("output"));
+        std::fs::create_dir_all(source.join("autre")).unwrap();
+        std::fs::write(source.join("epr-17574.30"), b"corrompu").unwrap();
+        std::fs::write(source.join("autre").join("vf2_prog.bin"), &program).unwrap();
+
+        let report = convert_rom_folder(&database, &source, &output, SetLayout::NonMerged).unwrap();
+        let copied = RomSource::Archive { path: output.join("vf2.zip"), entry: "epr-17574.30".to_string() };
+        assert_eq!(copied.read().unwrap(), program);
+        assert!(report.missing.iter().all(|(_, rom)| rom != "epr-17574.30"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,7 +1,9 @@
 //! Système de validation et vérification des ROMs
 
-use anyhow::Result;
+use crate::error::Result;
+use crate::memory::ByteOrder;
 use crc32fast::Hasher;
+#[cfg(feature = "rom-tools")]
 use sha2::{Sha256, Digest};
 use super::database::{RomInfo, GameInfo};
 
@@ -114,7 +116,9 @@ impl RomValidator {
         }
         
         // Vérifier le MD5 (seulement si ce n'est pas vide)
-        if !expected.md5.is_empty() && result.calculated_md5 != expected.md5 {
+        if !expected.md5.is_empty() && result.calculated_md5.is_empty() {
+            result.warnings.push("Hash MD5 non vérifié (compilé sans la fonctionnalité rom-tools)".to_string());
+        } else if !expected.md5.is_empty() && result.calculated_md5 != expected.md5 {
             result.errors.push(ValidationError::InvalidMd5 {
                 expected: expected.md5.clone(),
                 found: result.calculated_md5.clone(),
@@ -182,18 +186,36 @@ impl RomValidator {
         hasher.finalize()
     }
     
-    /// Calcule le hash MD5 d'un buffer
+    /// Calcule le hash MD5 d'un buffer ; vide sans la fonctionnalité
+    /// `rom-tools`, le MD5 n'est alors pas vérifié
     pub fn calculate_md5(data: &[u8]) -> String {
-        let mut hasher = md5::Context::new();
-        hasher.consume(data);
-        format!("{:x}", hasher.compute())
+        #[cfg(feature = "rom-tools")]
+        {
+            let mut hasher = md5::Context::new();
+            hasher.consume(data);
+            format!("{:x}", hasher.compute())
+        }
+        #[cfg(not(feature = "rom-tools"))]
+        {
+            let _ = data;
+            String::new()
+        }
     }
     
-    /// Calcule le hash SHA256 d'un buffer
+    /// Calcule le hash SHA256 d'un buffer ; vide sans la fonctionnalité
+    /// `rom-tools`
     pub fn calculate_sha256(data: &[u8]) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        format!("{:x}", hasher.finalize())
+        #[cfg(feature = "rom-tools")]
+        {
+            let mut hasher = Sha256::new();
+            hasher.update(data);
+            format!("{:x}", hasher.finalize())
+        }
+        #[cfg(not(feature = "rom-tools"))]
+        {
+            let _ = data;
+            String::new()
+        }
     }
     
     /// Détecte automatiquement le type de ROM basé sur le contenu
@@ -256,13 +278,11 @@ impl RomValidator {
                 // Vérifier la présence d'instructions valides (heuristique)
                 if data.len() >= 16 {
                     let mut valid_instructions = 0;
-                    for chunk in data.chunks(4) {
-                        if chunk.len() == 4 {
-                            let opcode = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-                            // Heuristique simple pour détecter des opcodes valides
-                            if opcode != 0x00000000 && opcode != 0xFFFFFFFF {
-                                valid_instructions += 1;
-                            }
+                    for chunk in data.chunks_exact(4) {
+                        let opcode = ByteOrder::Little.read_uint(chunk) as u32;
+                        // Heuristique simple pour détecter des opcodes valides
+                        if opcode != 0x00000000 && opcode != 0xFFFFFFFF {
+                            valid_instructions += 1;
                         }
                     }
                     
@@ -377,6 +397,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "rom-tools")]
     fn test_md5_calculation() {
         let data = b"Hello, World!";
         let md5 = RomValidator::calculate_md5(data);
@@ -0,0 +1,159 @@
+//! Blocs étiquetés des fichiers d'état
+//!
+//! À partir de la version 3, le contenu d'un état est une suite de blocs :
+//! étiquette de 4 octets, version du bloc (u16), longueur (u32) puis les
+//! données. Chaque sous-système a son bloc ; un lecteur ignore les blocs qu'il
+//! ne connaît pas et laisse leur valeur par défaut aux blocs absents.
+//!
+//! Les blocs structurés (CPU, registres I/O, en-tête) sont encodés en JSON :
+//! les champs y sont nommés, un champ ajouté après coup reçoit sa valeur par
+//! défaut (`#[serde(default)]`) et un champ retiré est ignoré. Les RAM sont
+//! stockées brutes.
+
+use anyhow::{bail, Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::io::{self, Read, Write};
+
+use crate::memory::ByteOrder;
+
+/// Étiquette d'un bloc
+pub type ChunkTag = [u8; 4];
+
+/// En-tête du fichier (jeu, révisions, date)
+pub const CHUNK_HEADER: ChunkTag = *b"HEAD";
+/// Vignette de l'image
+pub const CHUNK_THUMBNAIL: ChunkTag = *b"THMB";
+/// Registres et interruptions du V60
+pub const CHUNK_CPU: ChunkTag = *b"CPU ";
+/// Registres I/O
+pub const CHUNK_IO: ChunkTag = *b"IO  ";
+/// RAM principale
+pub const CHUNK_MAIN_RAM: ChunkTag = *b"MRAM";
+/// VRAM
+pub const CHUNK_VIDEO_RAM: ChunkTag = *b"VRAM";
+/// RAM audio
+pub const CHUNK_AUDIO_RAM: ChunkTag = *b"ARAM";
+/// Horloge temps réel
+pub const CHUNK_RTC: ChunkTag = *b"RTC ";
+/// Bancs des listes d'affichage, dans l'ordre des bancs
+pub const CHUNK_DISPLAY_LISTS: [ChunkTag; 2] = [*b"DLS0", *b"DLS1"];
+
+/// Taille maximale acceptée pour un bloc (protection contre les fichiers corrompus)
+const MAX_CHUNK_SIZE: u32 = 64 * 1024 * 1024;
+
+/// Bloc lu depuis un fichier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    pub tag: ChunkTag,
+    pub version: u16,
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    /// Décode un bloc structuré
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.data)
+            .with_context(|| format!("Bloc {} corrompu", tag_name(&self.tag)))
+    }
+}
+
+/// Étiquette lisible (pour les messages d'erreur)
+pub fn tag_name(tag: &ChunkTag) -> String {
+    String::from_utf8_lossy(tag).trim_end().to_string()
+}
+
+/// Écrit un bloc de données brutes
+pub fn write_chunk<W: Write>(out: &mut W, tag: ChunkTag, version: u16, data: &[u8]) -> Result<()> {
+    let length = u32::try_from(data.len())
+        .ok()
+        .filter(|&length| length <= MAX_CHUNK_SIZE)
+        .with_context(|| format!("Bloc {} trop grand", tag_name(&tag)))?;
+
+    out.write_all(&tag)?;
+    out.write_all(&ByteOrder::Little.u16_bytes(version))?;
+    out.write_all(&ByteOrder::Little.u32_bytes(length))?;
+    out.write_all(data)?;
+    Ok(())
+}
+
+/// Écrit un bloc structuré
+pub fn write_encoded<W: Write, T: Serialize>(out: &mut W, tag: ChunkTag, version: u16, value: &T) -> Result<()> {
+    let data = serde_json::to_vec(value).with_context(|| format!("Encodage du bloc {} impossible", tag_name(&tag)))?;
+    write_chunk(out, tag, version, &data)
+}
+
+/// Lit l'en-tête du bloc suivant ; None en fin de fichier
+fn read_chunk_header<R: Read>(input: &mut R) -> Result<Option<(ChunkTag, u16, u32)>> {
+    let mut header = [0u8; 10];
+    match input.read_exact(&mut header[..1]) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    input.read_exact(&mut header[1..]).context("En-tête de bloc tronqué")?;
+
+    let tag = [header[0], header[1], header[2], header[3]];
+    let version = ByteOrder::Little.read_u16(&header, 4).unwrap_or_default();
+    let length = ByteOrder::Little.read_u32(&header, 6).unwrap_or_default();
+    if length > MAX_CHUNK_SIZE {
+        bail!("Bloc {} de taille invalide: {}", tag_name(&tag), length);
+    }
+    Ok(Some((tag, version, length)))
+}
+
+/// Lit le bloc suivant ; None en fin de fichier
+pub fn read_chunk<R: Read>(input: &mut R) -> Result<Option<Chunk>> {
+    let Some((tag, version, length)) = read_chunk_header(input)? else {
+        return Ok(None);
+    };
+
+    let mut data = vec![0u8; length as usize];
+    input
+        .read_exact(&mut data)
+        .with_context(|| format!("Bloc {} tronqué", tag_name(&tag)))?;
+    Ok(Some(Chunk { tag, version, data }))
+}
+
+/// Lit tous les blocs jusqu'à la fin du fichier
+pub fn read_chunks<R: Read>(input: &mut R) -> Result<Vec<Chunk>> {
+    let mut chunks = Vec::new();
+    while let Some(chunk) = read_chunk(input)? {
+        chunks.push(chunk);
+    }
+    Ok(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_round_trip() {
+        let mut out = Vec::new();
+        write_chunk(&mut out, CHUNK_MAIN_RAM, 1, &[1, 2, 3]).unwrap();
+        write_encoded(&mut out, CHUNK_CPU, 2, &vec![7u32, 8]).unwrap();
+
+        // En-tête en petit-boutiste quel que soit l'hôte : un état écrit sur
+        // x86 se relit à l'identique sur un hôte grand-boutiste
+        assert_eq!(out[..13], [b'M', b'R', b'A', b'M', 0x01, 0x00, 0x03, 0x00, 0x00, 0x00, 1, 2, 3]);
+
+        let chunks = read_chunks(&mut out.as_slice()).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], Chunk { tag: CHUNK_MAIN_RAM, version: 1, data: vec![1, 2, 3] });
+        assert_eq!(chunks[1].version, 2);
+        assert_eq!(chunks[1].decode::<Vec<u32>>().unwrap(), vec![7, 8]);
+    }
+
+    #[test]
+    fn test_truncated_chunk_is_rejected() {
+        let mut out = Vec::new();
+        write_chunk(&mut out, CHUNK_VIDEO_RAM, 1, &[0; 16]).unwrap();
+        out.truncate(out.len() - 1);
+        assert!(read_chunks(&mut out.as_slice()).is_err());
+
+        // Longueur aberrante
+        let mut bogus = b"VRAM\x01\0".to_vec();
+        bogus.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(read_chunks(&mut bogus.as_slice()).is_err());
+    }
+}
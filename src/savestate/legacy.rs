@@ -0,0 +1,121 @@
+//! Lecture des états écrits avant le format par blocs (versions 1 et 2)
+//!
+//! Ces versions encodaient les structures avec bincode, sans nom de champ :
+//! leur disposition est figée ici, indépendamment des structures courantes
+//! qui peuvent évoluer. La conversion vers les structures courantes se fait
+//! par nom de champ, comme pour les blocs JSON : un champ ajouté depuis prend
+//! sa valeur par défaut.
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::Read;
+
+use super::{CpuState, MemoryState, SaveState, SaveStateHeader, Thumbnail};
+
+/// Première version dont les fichiers portent un en-tête
+const HEADER_VERSION: u32 = 2;
+
+/// Convertit une structure figée en structure courante par nom de champ
+fn migrate<T: Serialize, U: DeserializeOwned>(old: T) -> Result<U> {
+    let value = serde_json::to_value(old)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+#[derive(Serialize, Deserialize)]
+struct ThumbnailV2 {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HeaderV2 {
+    game: String,
+    game_revision: String,
+    board: String,
+    emulator_version: String,
+    created: u64,
+    thumbnail: Option<ThumbnailV2>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CpuStateV1 {
+    general: [u32; 32],
+    pc: u32,
+    sp: u32,
+    fp: u32,
+    psw: u32,
+    control: [u32; 16],
+    cycle_count: u64,
+    halted: bool,
+    interrupts_enabled: bool,
+    pending_interrupts: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IoRegistersV1 {
+    interrupt_control: u32,
+    interrupt_status: u32,
+    timer_main: u32,
+    timer_sub: u32,
+    gpu_control: u32,
+    gpu_status: u32,
+    gpu_command: u32,
+    audio_control: u32,
+    input_data: u32,
+    input_control: u32,
+    cycle_counter: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MemoryStateV1 {
+    main_ram: Vec<u8>,
+    video_ram: Vec<u8>,
+    audio_ram: Vec<u8>,
+    io_registers: IoRegistersV1,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveStateV1 {
+    cpu: CpuStateV1,
+    memory: MemoryStateV1,
+}
+
+/// Lit le contenu d'un état de version 1 ou 2 (après signature et version)
+pub(super) fn read_state<R: Read>(version: u32, input: &mut R) -> Result<SaveState> {
+    let header = read_header(version, input)?;
+    let state: SaveStateV1 = bincode::deserialize_from(input).context("État de sauvegarde corrompu")?;
+
+    let cpu: CpuState = migrate(state.cpu).context("Migration de l'état CPU impossible")?;
+    let memory = MemoryState {
+        main_ram: state.memory.main_ram,
+        video_ram: state.memory.video_ram,
+        audio_ram: state.memory.audio_ram,
+        io_registers: migrate(state.memory.io_registers).context("Migration des registres I/O impossible")?,
+        display_lists: Default::default(),
+        rtc: None,
+    };
+
+    Ok(SaveState { header, cpu, memory })
+}
+
+/// Lit l'en-tête d'un état de version 1 ou 2 (les états de version 1 n'en
+/// ont pas)
+pub(super) fn read_header<R: Read>(version: u32, input: &mut R) -> Result<SaveStateHeader> {
+    if version < HEADER_VERSION {
+        return Ok(SaveStateHeader::default());
+    }
+
+    let header: HeaderV2 = bincode::deserialize_from(input).context("En-tête d'état corrompu")?;
+    let thumbnail = header
+        .thumbnail
+        .map(|t| Thumbnail { width: t.width, height: t.height, rgba: t.rgba });
+    Ok(SaveStateHeader {
+        game: header.game,
+        game_revision: header.game_revision,
+        board: header.board,
+        emulator_version: header.emulator_version,
+        created: header.created,
+        thumbnail,
+    })
+}
@@ -0,0 +1,516 @@
+//! États de sauvegarde (savestates)
+//!
+//! Un état capture tout ce qu'il faut pour reprendre l'émulation à
+//! l'identique : registres et file d'interruptions du V60, RAM principale,
+//! VRAM, RAM audio, listes d'affichage, registres I/O et horloge temps réel. Le
+//! fichier commence par une signature et un numéro de version, suivis de blocs
+//! étiquetés (voir [`chunks`]) : en-tête (jeu, révisions, date), vignette, puis
+//! un bloc par sous-système. Les blocs d'en-tête viennent en premier et se
+//! lisent seuls, ce qui permet de lister les emplacements sans décoder les RAM.
+//!
+//! Les champs des blocs structurés sont nommés et ont une valeur par défaut :
+//! un état écrit par une version antérieure de l'émulateur reste lisible après
+//! l'ajout ou le retrait d'un champ, et les blocs inconnus sont ignorés. Les
+//! anciens formats bincode (versions 1 et 2) sont lus par [`legacy`].
+//!
+//! Les états sont rangés par jeu sous le répertoire `saves/` des données
+//! utilisateur : dix emplacements numérotés, plus l'emplacement `auto` pour la
+//! reprise automatique.
+
+pub mod chunks;
+pub mod thumbnail;
+pub mod slots;
+mod legacy;
+
+pub use thumbnail::*;
+pub use slots::*;
+
+use crate::cpu::{Interrupt, NecV60, ProcessorStatusWord};
+use crate::memory::{ByteOrder, IoRegisters, MemoryRegion, Model2Memory, RtcDevice};
+use anyhow::{Context, Result};
+use chunks::{
+    read_chunk, write_chunk, write_encoded, Chunk, CHUNK_AUDIO_RAM, CHUNK_CPU, CHUNK_DISPLAY_LISTS, CHUNK_HEADER,
+    CHUNK_IO, CHUNK_MAIN_RAM, CHUNK_RTC, CHUNK_THUMBNAIL, CHUNK_VIDEO_RAM,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Signature des fichiers d'état
+pub const SAVESTATE_MAGIC: &[u8; 4] = b"PM2S";
+
+/// Version du format des fichiers d'état
+pub const SAVESTATE_VERSION: u32 = 3;
+
+/// Première version au format par blocs
+const CHUNKED_VERSION: u32 = 3;
+
+/// Version des blocs écrits
+const CHUNK_VERSION: u16 = 1;
+
+/// Nom de l'emplacement utilisé pour la reprise automatique
+pub const AUTO_SLOT: &str = "auto";
+
+/// Extension des fichiers d'état
+pub const SAVESTATE_EXTENSION: &str = "state";
+
+/// État du V60
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CpuState {
+    pub general: [u32; 32],
+    pub pc: u32,
+    pub sp: u32,
+    pub fp: u32,
+    pub psw: u32,
+    pub control: [u32; 16],
+    pub cycle_count: u64,
+    pub halted: bool,
+    pub interrupts_enabled: bool,
+
+    /// Interruptions en attente, désignées par l'adresse de leur vecteur
+    pub pending_interrupts: Vec<u32>,
+}
+
+impl CpuState {
+    pub fn capture(cpu: &NecV60) -> Self {
+        let registers = &cpu.registers;
+        Self {
+            general: registers.general,
+            pc: registers.pc,
+            sp: registers.sp,
+            fp: registers.fp,
+            psw: registers.psw.bits(),
+            control: registers.control,
+            cycle_count: cpu.cycle_count,
+            halted: cpu.halted,
+            interrupts_enabled: cpu.interrupts_enabled,
+            pending_interrupts: cpu.pending_interrupts.iter().map(|i| i.vector_address()).collect(),
+        }
+    }
+
+    pub fn restore(&self, cpu: &mut NecV60) {
+        cpu.reset();
+
+        let registers = &mut cpu.registers;
+        registers.general = self.general;
+        registers.pc = self.pc;
+        registers.sp = self.sp;
+        registers.fp = self.fp;
+        registers.psw = ProcessorStatusWord::from_bits_truncate(self.psw);
+        registers.control = self.control;
+
+        cpu.cycle_count = self.cycle_count;
+        cpu.halted = self.halted;
+        cpu.interrupts_enabled = self.interrupts_enabled;
+        cpu.pending_interrupts = self.pending_interrupts.iter().map(|&v| interrupt_from_vector(v)).collect();
+    }
+}
+
+/// Interruption correspondant à l'adresse de son vecteur
+fn interrupt_from_vector(vector: u32) -> Interrupt {
+    match vector {
+        0x40 => Interrupt::VBlank,
+        0x44 => Interrupt::TimerMain,
+        0x48 => Interrupt::TimerSub,
+        0x4C => Interrupt::Gpu,
+        0x50 => Interrupt::Audio,
+        0x54 => Interrupt::Input,
+        0x10 => Interrupt::BusError,
+        0x14 => Interrupt::FloatingPoint,
+        _ => Interrupt::External((vector.saturating_sub(0x58) / 4) as u8),
+    }
+}
+
+/// État de la mémoire
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryState {
+    pub main_ram: Vec<u8>,
+    pub video_ram: Vec<u8>,
+    pub audio_ram: Vec<u8>,
+    pub io_registers: IoRegisters,
+
+    /// Bancs des listes d'affichage ; vides dans les anciens états
+    pub display_lists: [Vec<u8>; 2],
+
+    /// Horloge temps réel ; absente des anciens états, qui gardent l'heure
+    /// de la NVRAM
+    pub rtc: Option<RtcDevice>,
+}
+
+impl MemoryState {
+    pub fn capture(memory: &Model2Memory) -> Self {
+        Self {
+            main_ram: memory.main_ram.data().to_vec(),
+            video_ram: memory.video_ram.data().to_vec(),
+            audio_ram: memory.audio_ram.data().to_vec(),
+            io_registers: memory.io_registers().clone(),
+            display_lists: [0, 1].map(|bank| memory.display_list_bank(bank).to_vec()),
+            rtc: Some(memory.rtc().clone()),
+        }
+    }
+
+    pub fn restore(&self, memory: &mut Model2Memory) -> Result<()> {
+        memory.restore_region(MemoryRegion::MainRam, &self.main_ram)?;
+        memory.restore_region(MemoryRegion::VideoRam, &self.video_ram)?;
+        memory.restore_region(MemoryRegion::AudioRam, &self.audio_ram)?;
+        for (bank, data) in self.display_lists.iter().enumerate() {
+            memory.restore_display_list_bank(bank, data)?;
+        }
+        memory.set_io_registers(self.io_registers.clone());
+        if let Some(rtc) = &self.rtc {
+            memory.set_rtc(rtc.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Erreur de chargement d'un état
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SaveStateError {
+    #[error("ce fichier n'est pas un état de sauvegarde")]
+    NotASaveState,
+
+    #[error("version d'état non prise en charge: {found} (au plus {supported})")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("état d'un autre jeu: {found} (jeu lancé: {expected})")]
+    GameMismatch { expected: String, found: String },
+
+    #[error("état d'une autre révision de carte: {found} (carte émulée: {expected})")]
+    BoardMismatch { expected: String, found: String },
+}
+
+/// En-tête d'un fichier d'état
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveStateHeader {
+    /// Identifiant du jeu (vide pour les états sans en-tête)
+    pub game: String,
+
+    /// Révision du jeu
+    pub game_revision: String,
+
+    /// Révision de la carte émulée
+    pub board: String,
+
+    /// Version de l'émulateur qui a écrit l'état
+    pub emulator_version: String,
+
+    /// Date de création (secondes depuis l'époque Unix)
+    pub created: u64,
+
+    /// Vignette, écrite dans son propre bloc
+    #[serde(skip)]
+    pub thumbnail: Option<Thumbnail>,
+}
+
+impl SaveStateHeader {
+    /// En-tête daté de maintenant pour un jeu
+    pub fn new(game: &str, game_revision: &str, board: &str) -> Self {
+        Self {
+            game: game.to_string(),
+            game_revision: game_revision.to_string(),
+            board: board.to_string(),
+            emulator_version: crate::VERSION.to_string(),
+            created: crate::clock::SystemTime::now()
+                .duration_since(crate::clock::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            thumbnail: None,
+        }
+    }
+
+    pub fn with_thumbnail(mut self, thumbnail: Option<Thumbnail>) -> Self {
+        self.thumbnail = thumbnail;
+        self
+    }
+
+    /// Vérifie que l'état peut être chargé dans le jeu et la carte émulés ;
+    /// les états sans en-tête sont acceptés
+    pub fn check_compatible(&self, game: &str, board: &str) -> Result<(), SaveStateError> {
+        if self.game.is_empty() {
+            return Ok(());
+        }
+        if self.game != game {
+            return Err(SaveStateError::GameMismatch { expected: game.to_string(), found: self.game.clone() });
+        }
+        if self.board != board {
+            return Err(SaveStateError::BoardMismatch { expected: board.to_string(), found: self.board.clone() });
+        }
+        Ok(())
+    }
+
+    /// Lit uniquement l'en-tête d'un fichier d'état
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Impossible d'ouvrir {}", path.display()))?;
+        Self::read_from(&mut std::io::BufReader::new(file))
+            .with_context(|| format!("État invalide: {}", path.display()))
+    }
+
+    /// Lit l'en-tête d'un état sans décoder son contenu
+    fn read_from<R: Read>(input: &mut R) -> Result<Self> {
+        let version = read_preamble(input)?;
+        if version < CHUNKED_VERSION {
+            return legacy::read_header(version, input);
+        }
+
+        // Les blocs d'en-tête précèdent ceux des sous-systèmes
+        let mut state = SaveState::default();
+        while let Some(chunk) = read_chunk(input)? {
+            if chunk.tag != CHUNK_HEADER && chunk.tag != CHUNK_THUMBNAIL {
+                break;
+            }
+            state.apply_chunk(chunk)?;
+        }
+        Ok(state.header)
+    }
+}
+
+/// Lit la signature et la version d'un fichier d'état
+fn read_preamble<R: Read>(input: &mut R) -> Result<u32> {
+    let mut preamble = [0u8; 8];
+    input.read_exact(&mut preamble).map_err(|_| SaveStateError::NotASaveState)?;
+    if &preamble[..4] != SAVESTATE_MAGIC {
+        return Err(SaveStateError::NotASaveState.into());
+    }
+
+    let version = ByteOrder::Little.read_u32(&preamble, 4).unwrap_or_default();
+    if version == 0 || version > SAVESTATE_VERSION {
+        return Err(SaveStateError::UnsupportedVersion { found: version, supported: SAVESTATE_VERSION }.into());
+    }
+    Ok(version)
+}
+
+/// État complet de la machine émulée
+#[derive(Debug, Clone, Default)]
+pub struct SaveState {
+    pub header: SaveStateHeader,
+
+    pub cpu: CpuState,
+    pub memory: MemoryState,
+}
+
+impl SaveState {
+    /// Capture l'état courant
+    pub fn capture(cpu: &NecV60, memory: &Model2Memory) -> Self {
+        Self {
+            header: SaveStateHeader::default(),
+            cpu: CpuState::capture(cpu),
+            memory: MemoryState::capture(memory),
+        }
+    }
+
+    pub fn with_header(mut self, header: SaveStateHeader) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Restaure l'état dans la machine
+    pub fn restore(&self, cpu: &mut NecV60, memory: &mut Model2Memory) -> Result<()> {
+        self.memory.restore(memory)?;
+        self.cpu.restore(cpu);
+        Ok(())
+    }
+
+    /// Écrit l'état (signature, version puis blocs)
+    pub fn write_to<W: Write>(&self, mut out: W) -> Result<()> {
+        out.write_all(SAVESTATE_MAGIC)?;
+        out.write_all(&ByteOrder::Little.u32_bytes(SAVESTATE_VERSION))?;
+
+        write_encoded(&mut out, CHUNK_HEADER, CHUNK_VERSION, &self.header)?;
+        if let Some(thumbnail) = &self.header.thumbnail {
+            write_chunk(&mut out, CHUNK_THUMBNAIL, CHUNK_VERSION, &thumbnail.to_bytes())?;
+        }
+        write_encoded(&mut out, CHUNK_CPU, CHUNK_VERSION, &self.cpu)?;
+        write_encoded(&mut out, CHUNK_IO, CHUNK_VERSION, &self.memory.io_registers)?;
+        if let Some(rtc) = &self.memory.rtc {
+            write_encoded(&mut out, CHUNK_RTC, CHUNK_VERSION, rtc)?;
+        }
+        write_chunk(&mut out, CHUNK_MAIN_RAM, CHUNK_VERSION, &self.memory.main_ram)?;
+        write_chunk(&mut out, CHUNK_VIDEO_RAM, CHUNK_VERSION, &self.memory.video_ram)?;
+        write_chunk(&mut out, CHUNK_AUDIO_RAM, CHUNK_VERSION, &self.memory.audio_ram)?;
+        for (tag, data) in CHUNK_DISPLAY_LISTS.iter().zip(&self.memory.display_lists) {
+            write_chunk(&mut out, *tag, CHUNK_VERSION, data)?;
+        }
+        Ok(())
+    }
+
+    /// Lit un état écrit par [`SaveState::write_to`] ou par une version
+    /// antérieure de l'émulateur
+    pub fn read_from<R: Read>(mut input: R) -> Result<Self> {
+        let version = read_preamble(&mut input)?;
+        if version < CHUNKED_VERSION {
+            return legacy::read_state(version, &mut input);
+        }
+
+        let mut state = Self::default();
+        while let Some(chunk) = read_chunk(&mut input)? {
+            state.apply_chunk(chunk)?;
+        }
+        Ok(state)
+    }
+
+    /// Intègre un bloc lu ; les blocs inconnus (écrits par une version plus
+    /// récente) sont ignorés
+    fn apply_chunk(&mut self, chunk: Chunk) -> Result<()> {
+        match chunk.tag {
+            CHUNK_HEADER => {
+                let thumbnail = self.header.thumbnail.take();
+                self.header = chunk.decode()?;
+                self.header.thumbnail = thumbnail;
+            }
+            CHUNK_THUMBNAIL => {
+                self.header.thumbnail = Some(Thumbnail::from_bytes(&chunk.data).context("Vignette corrompue")?);
+            }
+            CHUNK_CPU => self.cpu = chunk.decode()?,
+            CHUNK_IO => self.memory.io_registers = chunk.decode()?,
+            CHUNK_RTC => self.memory.rtc = Some(chunk.decode()?),
+            CHUNK_MAIN_RAM => self.memory.main_ram = chunk.data,
+            CHUNK_VIDEO_RAM => self.memory.video_ram = chunk.data,
+            CHUNK_AUDIO_RAM => self.memory.audio_ram = chunk.data,
+            tag if tag == CHUNK_DISPLAY_LISTS[0] => self.memory.display_lists[0] = chunk.data,
+            tag if tag == CHUNK_DISPLAY_LISTS[1] => self.memory.display_lists[1] = chunk.data,
+            _ => log::debug!("Bloc d'état inconnu ignoré: {}", chunks::tag_name(&chunk.tag)),
+        }
+        Ok(())
+    }
+
+    /// Enregistre l'état dans un fichier (remplacement atomique)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let temp = path.with_extension("tmp");
+        let file = std::fs::File::create(&temp)
+            .with_context(|| format!("Impossible de créer {}", temp.display()))?;
+        let mut writer = std::io::BufWriter::new(file);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+
+        std::fs::rename(&temp, path).with_context(|| format!("Impossible d'écrire {}", path.display()))
+    }
+
+    /// Charge un état depuis un fichier
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Impossible d'ouvrir {}", path.display()))?;
+        Self::read_from(std::io::BufReader::new(file))
+            .with_context(|| format!("État invalide: {}", path.display()))
+    }
+}
+
+/// Chemin d'un emplacement d'état pour un jeu : `<saves>/<jeu>/<emplacement>.state`
+pub fn state_path(saves_dir: &Path, game: &str, slot: &str) -> PathBuf {
+    saves_dir.join(game).join(format!("{}.{}", slot, SAVESTATE_EXTENSION))
+}
+
+/// Identifiant de jeu déduit du chemin de la ROM (nom du fichier sans extension)
+pub fn game_id_from_rom(rom_path: &Path) -> Option<String> {
+    rom_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .map(str::to_ascii_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryInterface, DISPLAY_BANK_REGISTER, DISPLAY_BANK_SWAP, DISPLAY_LIST_BASE, IO_REGISTERS_BASE};
+
+    fn sample_machine() -> (NecV60, Model2Memory) {
+        let mut cpu = NecV60::new();
+        cpu.registers.pc = 0x1234;
+        cpu.registers.general[3] = 0xDEAD_BEEF;
+        cpu.registers.psw = ProcessorStatusWord::ZERO;
+        cpu.cycle_count = 42_000;
+        cpu.queue_interrupt(Interrupt::VBlank);
+        cpu.queue_interrupt(Interrupt::External(3));
+
+        let mut memory = Model2Memory::new();
+        memory.write_u32(0x100, 0x0102_0304).unwrap();
+        memory.write_u32(0x1000_0000, 0xCAFE_F00D).unwrap();
+        memory.set_input_data(0x55);
+        memory.latch_inputs();
+        // Une liste dans chaque banc, le CPU laissé sur le banc 1
+        memory.write_u32(DISPLAY_LIST_BASE, 0x0120_0001).unwrap();
+        memory.write_u32(IO_REGISTERS_BASE + DISPLAY_BANK_REGISTER, DISPLAY_BANK_SWAP).unwrap();
+        memory.swap_display_lists();
+        memory.write_u32(DISPLAY_LIST_BASE, 0x0002_0000).unwrap();
+        let mut rtc = RtcDevice::new();
+        rtc.offset = -3600;
+        memory.set_rtc(rtc);
+        (cpu, memory)
+    }
+
+    #[test]
+    fn test_round_trip_restores_machine() {
+        let (cpu, memory) = sample_machine();
+        let state = SaveState::capture(&cpu, &memory);
+
+        let mut bytes = Vec::new();
+        state.write_to(&mut bytes).unwrap();
+        assert_eq!(&bytes[..4], SAVESTATE_MAGIC);
+
+        let mut restored_cpu = NecV60::new();
+        let mut restored_memory = Model2Memory::new();
+        SaveState::read_from(bytes.as_slice())
+            .unwrap()
+            .restore(&mut restored_cpu, &mut restored_memory)
+            .unwrap();
+
+        assert_eq!(restored_cpu.registers.pc, 0x1234);
+        assert_eq!(restored_cpu.registers.general[3], 0xDEAD_BEEF);
+        assert_eq!(restored_cpu.registers.psw, ProcessorStatusWord::ZERO);
+        assert_eq!(restored_cpu.cycle_count, 42_000);
+        assert_eq!(restored_cpu.pending_interrupts, vec![Interrupt::VBlank, Interrupt::External(3)]);
+        assert_eq!(restored_memory.read_u32(0x100).unwrap(), 0x0102_0304);
+        assert_eq!(restored_memory.read_u32(0x1000_0000).unwrap(), 0xCAFE_F00D);
+        assert_eq!(restored_memory.io_registers().input.data, 0x55);
+        assert_eq!(restored_memory.rtc().offset, -3600);
+        assert_eq!(restored_memory.display_list_bank(0), memory.display_list_bank(0));
+        assert_eq!(restored_memory.display_list_bank(1), memory.display_list_bank(1));
+        assert_ne!(restored_memory.display_list_bank(0), restored_memory.display_list_bank(1));
+        assert_eq!(restored_memory.read_u32(DISPLAY_LIST_BASE).unwrap(), 0x0002_0000);
+    }
+
+    #[test]
+    fn test_rejects_foreign_or_newer_files() {
+        assert!(SaveState::read_from(&b"RIFF\x01\0\0\0"[..]).is_err());
+
+        let mut bytes = SAVESTATE_MAGIC.to_vec();
+        bytes.extend_from_slice(&(SAVESTATE_VERSION + 1).to_le_bytes());
+        let err = SaveState::read_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SaveStateError>(),
+            Some(&SaveStateError::UnsupportedVersion { found: SAVESTATE_VERSION + 1, supported: SAVESTATE_VERSION })
+        );
+    }
+
+    #[test]
+    fn test_auto_slot_path_per_game() {
+        let game = game_id_from_rom(Path::new("/roms/Daytona.zip")).unwrap();
+        assert_eq!(game, "daytona");
+        assert_eq!(
+            state_path(Path::new("/data/saves"), &game, AUTO_SLOT),
+            PathBuf::from("/data/saves/daytona/auto.state")
+        );
+    }
+
+    #[test]
+    fn test_save_and_load_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = state_path(dir.path(), "vf2", AUTO_SLOT);
+        let (cpu, memory) = sample_machine();
+
+        SaveState::capture(&cpu, &memory).save(&path).unwrap();
+        let state = SaveState::load(&path).unwrap();
+        assert_eq!(state.cpu, CpuState::capture(&cpu));
+        assert!(!path.with_extension("tmp").exists());
+    }
+}
@@ -0,0 +1,173 @@
+//! Emplacements d'états numérotés d'un jeu
+//!
+//! Chaque jeu dispose de [`SLOT_COUNT`] emplacements `slot0.state` à
+//! `slot9.state` dans son répertoire de sauvegardes. Le chargement vérifie
+//! que l'état a été écrit pour le même jeu et la même révision de carte.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use super::{state_path, SaveState, SaveStateHeader};
+
+/// Nombre d'emplacements numérotés par jeu
+pub const SLOT_COUNT: u8 = 10;
+
+/// Contenu d'un emplacement
+#[derive(Debug, Clone)]
+pub struct SlotInfo {
+    pub slot: u8,
+    pub path: PathBuf,
+
+    /// En-tête de l'état (None si l'emplacement est vide ou illisible)
+    pub header: Option<SaveStateHeader>,
+}
+
+impl SlotInfo {
+    pub fn is_empty(&self) -> bool {
+        self.header.is_none()
+    }
+}
+
+/// Emplacements d'états d'un jeu
+#[derive(Debug, Clone)]
+pub struct SaveSlots {
+    saves_dir: PathBuf,
+    game: String,
+    board: String,
+}
+
+impl SaveSlots {
+    pub fn new(saves_dir: &Path, game: &str, board: &str) -> Self {
+        Self {
+            saves_dir: saves_dir.to_path_buf(),
+            game: game.to_string(),
+            board: board.to_string(),
+        }
+    }
+
+    pub fn game(&self) -> &str {
+        &self.game
+    }
+
+    pub fn board(&self) -> &str {
+        &self.board
+    }
+
+    /// Chemin du fichier d'un emplacement
+    pub fn path(&self, slot: u8) -> PathBuf {
+        state_path(&self.saves_dir, &self.game, &format!("slot{}", slot))
+    }
+
+    /// Contenu d'un emplacement (seul l'en-tête est lu)
+    pub fn info(&self, slot: u8) -> SlotInfo {
+        let path = self.path(slot);
+        let header = path.exists().then(|| SaveStateHeader::load(&path).ok()).flatten();
+        SlotInfo { slot, path, header }
+    }
+
+    /// Contenu de tous les emplacements
+    pub fn list(&self) -> Vec<SlotInfo> {
+        (0..SLOT_COUNT).map(|slot| self.info(slot)).collect()
+    }
+
+    /// Enregistre un état dans un emplacement, avec l'en-tête du jeu
+    pub fn save(&self, slot: u8, state: SaveState, header: SaveStateHeader) -> Result<PathBuf> {
+        anyhow::ensure!(slot < SLOT_COUNT, "Emplacement invalide: {}", slot);
+
+        let path = self.path(slot);
+        state.with_header(header).save(&path)?;
+        Ok(path)
+    }
+
+    /// Charge l'état d'un emplacement en refusant ceux d'un autre jeu ou
+    /// d'une autre carte
+    pub fn load(&self, slot: u8) -> Result<SaveState> {
+        anyhow::ensure!(slot < SLOT_COUNT, "Emplacement invalide: {}", slot);
+
+        let state = SaveState::load(&self.path(slot))?;
+        state.header.check_compatible(&self.game, &self.board)?;
+        Ok(state)
+    }
+}
+
+/// Date UTC lisible (`AAAA-MM-JJ HH:MM:SS`) à partir de secondes Unix
+pub fn format_timestamp(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let time = seconds % 86_400;
+
+    // Conversion jours → date civile (algorithme de H. Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::NecV60;
+    use crate::memory::Model2Memory;
+    use crate::savestate::SaveStateError;
+
+    fn state() -> SaveState {
+        SaveState::capture(&NecV60::new(), &Model2Memory::new())
+    }
+
+    #[test]
+    fn test_slots_list_and_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let slots = SaveSlots::new(dir.path(), "daytona", "Model 2");
+        assert!(slots.list().iter().all(SlotInfo::is_empty));
+
+        let header = SaveStateHeader::new("daytona", "1.0", "Model 2");
+        let path = slots.save(3, state(), header).unwrap();
+        assert!(path.ends_with("daytona/slot3.state"));
+
+        let listed = slots.list();
+        assert_eq!(listed.len(), SLOT_COUNT as usize);
+        assert!(listed[3].header.as_ref().is_some_and(|h| h.game_revision == "1.0"));
+        assert!(listed[2].is_empty());
+
+        assert_eq!(slots.load(3).unwrap().header.game, "daytona");
+        assert!(slots.save(SLOT_COUNT, state(), SaveStateHeader::default()).is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_other_game_or_board() {
+        let dir = tempfile::tempdir().unwrap();
+        let daytona = SaveSlots::new(dir.path(), "daytona", "Model 2");
+        daytona.save(0, state(), SaveStateHeader::new("vf2", "2.1", "Model 2")).unwrap();
+        daytona.save(1, state(), SaveStateHeader::new("daytona", "1.0", "Model 2A-CRX")).unwrap();
+
+        let err = daytona.load(0).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SaveStateError>(),
+            Some(SaveStateError::GameMismatch { found, .. }) if found == "vf2"
+        ));
+
+        let err = daytona.load(1).unwrap_err();
+        assert!(matches!(err.downcast_ref::<SaveStateError>(), Some(SaveStateError::BoardMismatch { .. })));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20");
+    }
+}
@@ -0,0 +1,123 @@
+//! Vignette de l'image émulée enregistrée avec un état
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::ByteOrder;
+
+/// Largeur des vignettes en pixels
+pub const THUMBNAIL_WIDTH: u32 = 80;
+
+/// Vignette RGBA 8 bits réduite par moyenne de blocs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Réduit une image RGBA à [`THUMBNAIL_WIDTH`] pixels de large en
+    /// conservant ses proportions. Retourne None pour une image vide ou
+    /// incohérente.
+    pub fn from_rgba(data: &[u8], width: u32, height: u32) -> Option<Self> {
+        if width == 0 || height == 0 || data.len() < (width * height * 4) as usize {
+            return None;
+        }
+
+        let thumb_width = THUMBNAIL_WIDTH.min(width);
+        let thumb_height = (height * thumb_width / width).max(1);
+        let mut rgba = Vec::with_capacity((thumb_width * thumb_height * 4) as usize);
+
+        for ty in 0..thumb_height {
+            let (y0, y1) = source_span(ty, height, thumb_height);
+            for tx in 0..thumb_width {
+                let (x0, x1) = source_span(tx, width, thumb_width);
+
+                let mut sum = [0u32; 4];
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        let offset = ((y * width + x) * 4) as usize;
+                        for (channel, total) in sum.iter_mut().enumerate() {
+                            *total += data[offset + channel] as u32;
+                        }
+                    }
+                }
+
+                let count = (x1 - x0) * (y1 - y0);
+                rgba.extend(sum.iter().map(|total| (total / count) as u8));
+            }
+        }
+
+        Some(Self { width: thumb_width, height: thumb_height, rgba })
+    }
+
+    /// Encodage brut : largeur et hauteur (u32 petit-boutiste) puis pixels
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.rgba.len());
+        bytes.extend_from_slice(&ByteOrder::Little.u32_bytes(self.width));
+        bytes.extend_from_slice(&ByteOrder::Little.u32_bytes(self.height));
+        bytes.extend_from_slice(&self.rgba);
+        bytes
+    }
+
+    /// Décode une vignette écrite par [`Thumbnail::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (size, rgba) = bytes.split_at_checked(8)?;
+        let width = ByteOrder::Little.read_u32(size, 0)?;
+        let height = ByteOrder::Little.read_u32(size, 4)?;
+        if (width as usize).checked_mul(height as usize)?.checked_mul(4)? != rgba.len() {
+            return None;
+        }
+        Some(Self { width, height, rgba: rgba.to_vec() })
+    }
+
+    /// Couleur d'un pixel de la vignette
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * self.width + x) * 4) as usize;
+        [self.rgba[offset], self.rgba[offset + 1], self.rgba[offset + 2], self.rgba[offset + 3]]
+    }
+}
+
+/// Plage de pixels source couverte par un pixel de la vignette (jamais vide)
+fn source_span(index: u32, source: u32, target: u32) -> (u32, u32) {
+    let start = index * source / target;
+    let end = ((index + 1) * source / target).max(start + 1);
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downscale_averages_blocks() {
+        // Image 160x120 : moitié gauche noire, moitié droite blanche
+        let (width, height) = (160, 120);
+        let mut data = vec![0u8; (width * height * 4) as usize];
+        for y in 0..height {
+            for x in width / 2..width {
+                let offset = ((y * width + x) * 4) as usize;
+                data[offset..offset + 4].copy_from_slice(&[255, 255, 255, 255]);
+            }
+        }
+
+        let thumb = Thumbnail::from_rgba(&data, width, height).unwrap();
+        assert_eq!((thumb.width, thumb.height), (80, 60));
+        assert_eq!(thumb.pixel(0, 0), [0, 0, 0, 0]);
+        assert_eq!(thumb.pixel(79, 59), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let thumb = Thumbnail { width: 2, height: 1, rgba: vec![1, 2, 3, 4, 5, 6, 7, 8] };
+        assert_eq!(Thumbnail::from_bytes(&thumb.to_bytes()), Some(thumb));
+        assert!(Thumbnail::from_bytes(&[2, 0, 0, 0, 2, 0, 0, 0, 1]).is_none());
+        assert!(Thumbnail::from_bytes(&[0; 4]).is_none());
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_image() {
+        assert!(Thumbnail::from_rgba(&[0; 16], 4, 4).is_none());
+        assert!(Thumbnail::from_rgba(&[], 0, 0).is_none());
+    }
+}
@@ -0,0 +1,69 @@
+//! Comptage des allocations du tas
+//!
+//! [`CountingAllocator`] délègue à l'allocateur système et compte les
+//! allocations du thread courant. Installé comme allocateur global d'un
+//! binaire de test, il permet de vérifier que les boucles chaudes (frame
+//! émulée, génération audio, lots de commandes GPU) n'allouent plus une
+//! fois leurs tampons dimensionnés :
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//!
+//! let (_, allocations) = count_allocations(|| audio.update(cycles));
+//! assert_eq!(allocations, 0);
+//! ```
+//!
+//! Le compteur est propre à chaque thread : les tests exécutés en parallèle
+//! ne se perturbent pas.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Allocateur système qui compte les allocations et réallocations
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CountingAllocator;
+
+fn count() {
+    // Pendant la destruction du thread, le compteur n'est plus accessible
+    let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Allocations du thread courant depuis son démarrage (toujours 0 si
+/// [`CountingAllocator`] n'est pas l'allocateur global)
+pub fn allocation_count() -> u64 {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// Exécute `f` et renvoie son résultat avec le nombre d'allocations faites
+/// par le thread courant pendant l'appel
+pub fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, u64) {
+    let before = allocation_count();
+    let result = f();
+    (result, allocation_count() - before)
+}
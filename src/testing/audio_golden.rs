@@ -0,0 +1,187 @@
+//! Rendu audio hors ligne et comparaison à des buffers de référence
+//!
+//! [`CaptureBackend`] remplace la sortie du SCSP et garde les échantillons
+//! produits ; [`render_frames`] fait avancer le processeur sonore d'un
+//! nombre exact de trames. Les buffers de référence (« golden ») sont
+//! stockés en PCM 16 bits little-endian entrelacé, et [`compare_golden`]
+//! accepte un écart de quelques pas de quantification pour absorber les
+//! différences d'arrondi flottant entre plateformes.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::audio::{AudioBackend, ScspAudio};
+
+/// Échantillons capturés, partagés entre le backend et le test
+#[derive(Debug, Clone, Default)]
+pub struct SampleCapture {
+    samples: Arc<Mutex<Vec<f32>>>,
+}
+
+impl SampleCapture {
+    /// Vide la capture et renvoie les échantillons entrelacés
+    pub fn take(&self) -> Vec<f32> {
+        std::mem::take(&mut *self.samples.lock().unwrap())
+    }
+}
+
+/// Backend qui garde tout ce qui lui est poussé
+#[derive(Debug, Clone)]
+pub struct CaptureBackend {
+    sample_rate: u32,
+    channels: u16,
+    capture: SampleCapture,
+    consumed_frames: u64,
+}
+
+impl CaptureBackend {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            capture: SampleCapture::default(),
+            consumed_frames: 0,
+        }
+    }
+
+    /// Accès aux échantillons une fois le backend confié au SCSP
+    pub fn capture(&self) -> SampleCapture {
+        self.capture.clone()
+    }
+}
+
+impl AudioBackend for CaptureBackend {
+    fn name(&self) -> &str {
+        "capture"
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn push_samples(&mut self, samples: &[f32]) {
+        self.capture.samples.lock().unwrap().extend_from_slice(samples);
+        self.consumed_frames += (samples.len() / self.channels as usize) as u64;
+    }
+
+    fn queued_frames(&self) -> usize {
+        0
+    }
+
+    fn consumed_frames(&self) -> u64 {
+        self.consumed_frames
+    }
+}
+
+/// Processeur sonore branché sur une capture, à la fréquence native
+pub fn capture_audio() -> (ScspAudio, SampleCapture) {
+    let backend = CaptureBackend::new(crate::audio::SCSP_SAMPLE_RATE, 2);
+    let capture = backend.capture();
+    (ScspAudio::with_backend(Box::new(backend)), capture)
+}
+
+/// Produit exactement `frames` trames et renvoie les échantillons
+/// entrelacés. L'horloge maître est calée sur la fréquence de sortie, un
+/// cycle donnant une trame ; le rendu avance par tranches d'une image à
+/// 60 Hz pour rester sous la taille du buffer de sortie.
+pub fn render_frames(audio: &mut ScspAudio, capture: &SampleCapture, frames: u32) -> Vec<f32> {
+    let sample_rate = audio.output().sample_rate();
+    let chunk = (sample_rate / 60).max(1);
+    audio.set_master_clock(sample_rate);
+
+    let mut remaining = frames;
+    while remaining > 0 {
+        let cycles = remaining.min(chunk);
+        audio.update(cycles);
+        remaining -= cycles;
+    }
+    capture.take()
+}
+
+/// Encode des échantillons en PCM 16 bits little-endian
+pub fn encode_pcm16(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|&sample| ((sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16).to_le_bytes())
+        .collect()
+}
+
+/// Décode du PCM 16 bits little-endian (un octet isolé final est ignoré)
+pub fn decode_pcm16(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]) as f32 / i16::MAX as f32)
+        .collect()
+}
+
+/// Écart entre un rendu et sa référence
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoldenMismatch {
+    /// Nombres d'échantillons différents (rendu, référence)
+    Length(usize, usize),
+    /// Échantillons hors tolérance : premier index, valeurs et nombre total
+    Samples { index: usize, actual: f32, expected: f32, count: usize },
+}
+
+impl fmt::Display for GoldenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenMismatch::Length(actual, expected) => {
+                write!(f, "{} échantillons rendus, {} attendus", actual, expected)
+            }
+            GoldenMismatch::Samples { index, actual, expected, count } => write!(
+                f,
+                "{} échantillons hors tolérance, le premier à l'index {} : {:.5} au lieu de {:.5}",
+                count, index, actual, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GoldenMismatch {}
+
+/// Compare un rendu à sa référence, échantillon par échantillon ; la
+/// tolérance est exprimée en pas de quantification 16 bits
+pub fn compare_golden(actual: &[f32], golden: &[f32], tolerance_steps: u32) -> Result<(), GoldenMismatch> {
+    if actual.len() != golden.len() {
+        return Err(GoldenMismatch::Length(actual.len(), golden.len()));
+    }
+
+    let tolerance = (tolerance_steps as f32 + 0.5) / i16::MAX as f32;
+    let mut first = None;
+    let mut count = 0;
+    for (index, (&actual, &expected)) in actual.iter().zip(golden).enumerate() {
+        if (actual.clamp(-1.0, 1.0) - expected).abs() > tolerance {
+            first.get_or_insert((index, actual, expected));
+            count += 1;
+        }
+    }
+    match first {
+        None => Ok(()),
+        Some((index, actual, expected)) => Err(GoldenMismatch::Samples { index, actual, expected, count }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pcm16_roundtrip_within_tolerance() {
+        let samples = [0.0, 0.5, -0.5, 1.0, -1.0, 0.123_456];
+        let decoded = decode_pcm16(&encode_pcm16(&samples));
+        assert_eq!(compare_golden(&samples, &decoded, 0), Ok(()));
+
+        let mut shifted = decoded.clone();
+        shifted[2] += 0.01;
+        assert!(matches!(
+            compare_golden(&samples, &shifted, 2),
+            Err(GoldenMismatch::Samples { index: 2, count: 1, .. })
+        ));
+        assert_eq!(compare_golden(&samples, &decoded[1..], 2), Err(GoldenMismatch::Length(6, 5)));
+    }
+}
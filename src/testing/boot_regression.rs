@@ -0,0 +1,131 @@
+//! Non-régression du démarrage des jeux
+//!
+//! [`boot_game`] démarre un jeu sans fenêtre ni sortie audio, sans aucune
+//! entrée, et relève une empreinte du framebuffer à des frames fixes
+//! ([`BOOT_CHECKPOINTS`]). Les empreintes relevées sur une version connue
+//! sont conservées dans un fichier TOML ([`BootHashes`]) ; une empreinte
+//! différente signale qu'un changement a modifié le démarrage du jeu.
+//!
+//! Les ROMs n'étant pas distribuées, la suite `tests/boot_regression_tests.rs`
+//! ne fait rien tant que [`BOOT_ROM_DIR_VAR`] ne désigne pas un dossier de
+//! ROMs.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::config::{AudioBackendKind, EmulatorConfig};
+use crate::emulator::EmulatorCore;
+use crate::error::{Result, RomError};
+use crate::gpu::{Framebuffer, Model2Gpu};
+use crate::rom::Model2RomSystem;
+
+use super::IDLE_INPUT;
+
+/// Variable d'environnement donnant le dossier des ROMs
+pub const BOOT_ROM_DIR_VAR: &str = "PM2_ROM_DIR";
+
+/// Frames après lesquelles l'image est relevée
+pub const BOOT_CHECKPOINTS: [u64; 4] = [60, 300, 600, 1200];
+
+/// Empreinte de l'image après `frame` frames émulées
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameCheckpoint {
+    pub frame: u64,
+    pub hash: String,
+}
+
+/// Empreintes de référence, par nom court de jeu
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootHashes {
+    #[serde(default)]
+    games: BTreeMap<String, Vec<FrameCheckpoint>>,
+}
+
+impl BootHashes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyse un fichier d'empreintes au format TOML
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text).map_err(|e| RomError::Database(e.to_string()))?)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn checkpoints(&self, game: &str) -> Option<&[FrameCheckpoint]> {
+        self.games.get(game).map(Vec::as_slice)
+    }
+
+    /// Remplace les empreintes d'un jeu
+    pub fn record(&mut self, game: &str, checkpoints: Vec<FrameCheckpoint>) {
+        self.games.insert(game.to_string(), checkpoints);
+    }
+
+    /// Première empreinte relevée qui diffère de la référence du jeu :
+    /// (frame, empreinte attendue, empreinte obtenue)
+    pub fn first_mismatch<'a>(&'a self, game: &str, actual: &'a [FrameCheckpoint]) -> Option<(u64, &'a str, &'a str)> {
+        let expected = self.checkpoints(game)?;
+        expected.iter().find_map(|checkpoint| {
+            let found = actual.iter().find(|other| other.frame == checkpoint.frame).map_or("", |other| other.hash.as_str());
+            (found != checkpoint.hash).then_some((checkpoint.frame, checkpoint.hash.as_str(), found))
+        })
+    }
+}
+
+/// Empreinte d'une image : SHA-256 des dimensions et des pixels, tronqué à
+/// 64 bits
+pub fn frame_hash(framebuffer: &Framebuffer) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(framebuffer.width.to_le_bytes());
+    hasher.update(framebuffer.height.to_le_bytes());
+    hasher.update(&framebuffer.color_data);
+    hasher.finalize()[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Démarre `game` et relève l'image après chacune des frames `checkpoints`
+pub fn boot_game(rom_system: &mut Model2RomSystem, game: &str, checkpoints: &[u64]) -> Result<Vec<FrameCheckpoint>> {
+    let mut config = EmulatorConfig::default();
+    config.audio.backend = AudioBackendKind::Null;
+    let mut core = EmulatorCore::new(&config.audio);
+    let mut gpu = Model2Gpu::headless();
+    core.load_game(rom_system, game, &config.emulation)?;
+
+    let last = checkpoints.iter().copied().max().unwrap_or(0);
+    let mut hashes = Vec::with_capacity(checkpoints.len());
+    for frame in 1..=last {
+        core.run_frame(IDLE_INPUT, Some(&mut gpu))?;
+        gpu.end_frame()?;
+        if checkpoints.contains(&frame) {
+            hashes.push(FrameCheckpoint { frame, hash: frame_hash(&gpu.framebuffer) });
+        }
+    }
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_hashes_roundtrip_and_compare() {
+        let checkpoint = |frame, hash: &str| FrameCheckpoint { frame, hash: hash.to_string() };
+        let mut hashes = BootHashes::new();
+        hashes.record("daytona", vec![checkpoint(60, "aa"), checkpoint(300, "bb")]);
+        let hashes = BootHashes::parse(&hashes.to_toml()).unwrap();
+
+        assert_eq!(hashes.first_mismatch("daytona", &[checkpoint(60, "aa"), checkpoint(300, "bb")]), None);
+        assert_eq!(hashes.first_mismatch("daytona", &[checkpoint(60, "aa"), checkpoint(300, "cc")]), Some((300, "bb", "cc")));
+        assert_eq!(hashes.first_mismatch("daytona", &[checkpoint(60, "aa")]), Some((300, "bb", "")));
+        assert_eq!(hashes.first_mismatch("vf2", &[]), None);
+
+        let mut framebuffer = Framebuffer::new(4, 2);
+        let blank = frame_hash(&framebuffer);
+        assert_eq!(blank.len(), 16);
+        framebuffer.color_data[0] ^= 1;
+        assert_ne!(frame_hash(&framebuffer), blank);
+    }
+}
@@ -0,0 +1,287 @@
+//! Co-simulation pas à pas avec un oracle externe
+//!
+//! [`Lockstep`] fait avancer le V60 d'exactement un pas à la fois
+//! ([`Lockstep::step`]) et relève les écritures faites sur le bus pendant ce
+//! pas. [`Lockstep::digest`] en tire un état canonique ([`StateDigest`]) :
+//! registres et octets écrits depuis le début de la session, avec une
+//! empreinte courte à comparer à celle d'un autre émulateur ou d'une trace
+//! relevée sur le matériel. Quand l'oracle fait foi, ses valeurs sont
+//! réinjectées par [`Lockstep::correct`] et la comparaison peut reprendre.
+//!
+//! Un pas est une instruction, ou l'entrée dans une interruption pendante,
+//! ou une routine du micrologiciel remplacée (HLE) : ce que `NecV60::step`
+//! exécute en un appel. Les périphériques n'avancent pas ; l'oracle les
+//! remplace en injectant interruptions et valeurs mémoire.
+
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::cpu::{Interrupt, NecV60, ProcessorStatusWord};
+use crate::error::Result;
+use crate::memory::MemoryInterface;
+
+/// Écriture faite sur le bus pendant un pas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusWrite {
+    pub address: u32,
+
+    /// Taille de l'accès en octets (1, 2 ou 4)
+    pub size: u8,
+    pub value: u32,
+}
+
+/// Pas exécuté
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepRecord {
+    /// PC avant le pas
+    pub pc: u32,
+    pub cycles: u32,
+    pub writes: Vec<BusWrite>,
+}
+
+/// État canonique du CPU et de la mémoire écrite
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDigest {
+    pub pc: u32,
+    pub sp: u32,
+    pub fp: u32,
+    pub psw: u32,
+    pub general: [u32; 32],
+    pub control: [u32; 16],
+
+    /// Dernière valeur de chaque octet écrit, indépendamment de la taille
+    /// des accès qui l'ont écrit
+    pub memory: BTreeMap<u32, u8>,
+}
+
+impl StateDigest {
+    /// Empreinte : SHA-256 de l'état en little-endian, tronqué à 64 bits
+    pub fn hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for word in [self.pc, self.sp, self.fp, self.psw].iter().chain(&self.general).chain(&self.control) {
+            hasher.update(word.to_le_bytes());
+        }
+        for (address, value) in &self.memory {
+            hasher.update(address.to_le_bytes());
+            hasher.update([*value]);
+        }
+        hasher.finalize()[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Premier écart avec l'état de référence `expected`, décrit en clair
+    pub fn first_difference(&self, expected: &StateDigest) -> Option<String> {
+        let registers = [("PC", self.pc, expected.pc), ("SP", self.sp, expected.sp), ("FP", self.fp, expected.fp), ("PSW", self.psw, expected.psw)];
+        if let Some((name, found, wanted)) = registers.into_iter().find(|(_, found, wanted)| found != wanted) {
+            return Some(format!("{} vaut 0x{:08X}, attendu 0x{:08X}", name, found, wanted));
+        }
+        if let Some(index) = (0..32).find(|&i| self.general[i] != expected.general[i]) {
+            return Some(format!("R{} vaut 0x{:08X}, attendu 0x{:08X}", index, self.general[index], expected.general[index]));
+        }
+        if let Some(index) = (0..16).find(|&i| self.control[i] != expected.control[i]) {
+            return Some(format!("CR{} vaut 0x{:08X}, attendu 0x{:08X}", index, self.control[index], expected.control[index]));
+        }
+
+        let addresses = self.memory.keys().chain(expected.memory.keys()).copied().collect::<std::collections::BTreeSet<_>>();
+        addresses.into_iter().find_map(|address| {
+            let (found, wanted) = (self.memory.get(&address), expected.memory.get(&address));
+            (found != wanted).then(|| {
+                let show = |value: Option<&u8>| value.map_or_else(|| "non écrit".to_string(), |value| format!("0x{:02X}", value));
+                format!("octet 0x{:08X} : {}, attendu {}", address, show(found), show(wanted))
+            })
+        })
+    }
+}
+
+/// Valeur imposée par l'oracle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateCorrection {
+    Pc(u32),
+    Sp(u32),
+    Fp(u32),
+    Psw(u32),
+    General { index: usize, value: u32 },
+    Control { index: usize, value: u32 },
+
+    /// Octets écrits en mémoire à partir de `address`
+    Memory { address: u32, bytes: Vec<u8> },
+
+    /// Interruption levée avant le prochain pas
+    Interrupt(Interrupt),
+}
+
+/// Bus qui relève les écritures réussies
+struct WriteTracker<'a, M> {
+    memory: &'a mut M,
+    writes: &'a mut Vec<BusWrite>,
+}
+
+impl<M: MemoryInterface> WriteTracker<'_, M> {
+    fn record(&mut self, address: u32, size: u8, value: u32, result: Result<()>) -> Result<()> {
+        if result.is_ok() {
+            self.writes.push(BusWrite { address, size, value });
+        }
+        result
+    }
+}
+
+impl<M: MemoryInterface> MemoryInterface for WriteTracker<'_, M> {
+    fn read_u8(&self, address: u32) -> Result<u8> {
+        self.memory.read_u8(address)
+    }
+
+    fn read_u16(&self, address: u32) -> Result<u16> {
+        self.memory.read_u16(address)
+    }
+
+    fn read_u32(&self, address: u32) -> Result<u32> {
+        self.memory.read_u32(address)
+    }
+
+    fn write_u8(&mut self, address: u32, value: u8) -> Result<()> {
+        let result = self.memory.write_u8(address, value);
+        self.record(address, 1, value as u32, result)
+    }
+
+    fn write_u16(&mut self, address: u32, value: u16) -> Result<()> {
+        let result = self.memory.write_u16(address, value);
+        self.record(address, 2, value as u32, result)
+    }
+
+    fn write_u32(&mut self, address: u32, value: u32) -> Result<()> {
+        let result = self.memory.write_u32(address, value);
+        self.record(address, 4, value, result)
+    }
+}
+
+/// Session de co-simulation : octets écrits et nombre de pas
+#[derive(Debug, Clone, Default)]
+pub struct Lockstep {
+    memory: BTreeMap<u32, u8>,
+    steps: u64,
+}
+
+impl Lockstep {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pas exécutés depuis le début de la session
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Oublie les octets écrits, par exemple après une synchronisation
+    /// complète de la mémoire avec l'oracle
+    pub fn clear_memory(&mut self) {
+        self.memory.clear();
+    }
+
+    /// Exécute exactement un pas et relève ses écritures
+    pub fn step<M: MemoryInterface>(&mut self, cpu: &mut NecV60, memory: &mut M) -> Result<StepRecord> {
+        let pc = cpu.registers.pc;
+        let mut writes = Vec::new();
+        let cycles = cpu.step(&mut WriteTracker { memory, writes: &mut writes })?;
+        for write in &writes {
+            self.mark(write.address, &write.value.to_le_bytes()[..write.size as usize]);
+        }
+        self.steps += 1;
+        Ok(StepRecord { pc, cycles, writes })
+    }
+
+    /// État canonique courant
+    pub fn digest(&self, cpu: &NecV60) -> StateDigest {
+        let registers = &cpu.registers;
+        StateDigest {
+            pc: registers.pc,
+            sp: registers.sp,
+            fp: registers.fp,
+            psw: registers.psw.bits(),
+            general: registers.general,
+            control: registers.control,
+            memory: self.memory.clone(),
+        }
+    }
+
+    /// Impose une valeur de l'oracle ; les octets corrigés font partie de
+    /// l'état comparé
+    pub fn correct<M: MemoryInterface>(&mut self, cpu: &mut NecV60, memory: &mut M, correction: &StateCorrection) -> Result<()> {
+        let registers = &mut cpu.registers;
+        match correction {
+            StateCorrection::Pc(value) => registers.pc = *value,
+            StateCorrection::Sp(value) => registers.sp = *value,
+            StateCorrection::Fp(value) => registers.fp = *value,
+            StateCorrection::Psw(value) => registers.psw = ProcessorStatusWord::from_bits_truncate(*value),
+            StateCorrection::General { index, value } => registers.write_general(*index, *value),
+            StateCorrection::Control { index, value } => registers.write_control(*index, *value),
+            StateCorrection::Memory { address, bytes } => {
+                memory.write_block(*address, bytes)?;
+                self.mark(*address, bytes);
+            }
+            StateCorrection::Interrupt(interrupt) => cpu.queue_interrupt(*interrupt),
+        }
+        Ok(())
+    }
+
+    fn mark(&mut self, address: u32, bytes: &[u8]) {
+        for (offset, byte) in bytes.iter().enumerate() {
+            self.memory.insert(address.wrapping_add(offset as u32), *byte);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBus;
+
+    /// Instruction de format 1 à 3 : opcode, registres puis opérande
+    fn encode(opcode: u8, r2: u8, r1: u8, operand: &[u8]) -> Vec<u8> {
+        let word = (opcode as u16) << 10 | (r2 as u16) << 5 | r1 as u16;
+        word.to_le_bytes().iter().chain(operand).copied().collect()
+    }
+
+    #[test]
+    fn test_lockstep_against_oracle_with_correction() {
+        // MOV R1, #0x1234 ; ST R1, [R0 + 0x200] ; ADD R1, #1
+        let program: Vec<u8> = [
+            encode(0x10, 1, 0, &0x1234u16.to_le_bytes()),
+            encode(0x21, 1, 0, &0x200u32.to_le_bytes()),
+            encode(0x11, 1, 0, &1u16.to_le_bytes()),
+        ]
+        .concat();
+        let machine = || {
+            let mut bus = MockBus::new();
+            bus.load(0x1000, &program);
+            let mut cpu = NecV60::new();
+            cpu.registers.pc = 0x1000;
+            (cpu, bus, Lockstep::new())
+        };
+        let (mut cpu, mut bus, mut session) = machine();
+        let (mut oracle, mut oracle_bus, mut oracle_session) = machine();
+
+        session.step(&mut cpu, &mut bus).unwrap();
+        oracle_session.step(&mut oracle, &mut oracle_bus).unwrap();
+        let store = session.step(&mut cpu, &mut bus).unwrap();
+        oracle_session.step(&mut oracle, &mut oracle_bus).unwrap();
+        assert_eq!(store.pc, 0x1004);
+        assert_eq!(store.writes, vec![BusWrite { address: 0x200, size: 4, value: 0x1234 }]);
+        assert_eq!(session.digest(&cpu).memory.get(&0x201), Some(&0x12));
+        assert_eq!(session.digest(&cpu).hash(), oracle_session.digest(&oracle).hash());
+
+        // L'oracle diverge : l'écart est signalé puis corrigé
+        oracle.registers.general[1] = 0x4000;
+        session.step(&mut cpu, &mut bus).unwrap();
+        oracle_session.step(&mut oracle, &mut oracle_bus).unwrap();
+        let expected = oracle_session.digest(&oracle);
+        assert_eq!(session.digest(&cpu).first_difference(&expected).unwrap(), "R1 vaut 0x00001235, attendu 0x00004001");
+
+        session.correct(&mut cpu, &mut bus, &StateCorrection::General { index: 1, value: 0x4001 }).unwrap();
+        assert_eq!(session.digest(&cpu), expected);
+
+        // Une correction mémoire entre dans l'état comparé
+        session.correct(&mut cpu, &mut bus, &StateCorrection::Memory { address: 0x300, bytes: vec![0xAA] }).unwrap();
+        assert_eq!(session.digest(&cpu).first_difference(&expected).unwrap(), "octet 0x00000300 : 0xAA, attendu non écrit");
+        assert_eq!(bus.read_u8(0x300).unwrap(), 0xAA);
+        assert_eq!(session.steps(), 3);
+    }
+}
@@ -0,0 +1,396 @@
+//! Outils de test : bus mémoire simulé et scriptable
+//!
+//! [`MockBus`] implémente [`MemoryInterface`] sans instancier `Model2Memory`.
+//! Il permet de :
+//! - décrire des accès attendus, dans l'ordre, avec la valeur à renvoyer ;
+//! - simuler un périphérique dont les lectures successives renvoient une suite
+//!   de valeurs (registre de statut scruté en boucle, par exemple) ;
+//! - injecter des fautes (erreur de bus sur une plage, accès non aligné) ;
+//! - relire le journal complet des accès effectués.
+//!
+//! Toutes les valeurs sont en little-endian, comme dans `Model2Memory`.
+//!
+//! [`v60_fuzz`] s'appuie sur ce bus pour confronter le décodeur et
+//! l'exécuteur du V60 à un modèle de référence. [`audio_golden`] rend le
+//! SCSP hors ligne et compare le résultat à des buffers de référence ;
+//! [`boot_regression`] démarre les jeux et compare des empreintes d'image.
+//! [`allocations`] compte les allocations des boucles chaudes.
+//! [`lockstep`] exécute le CPU pas à pas face à un oracle externe.
+//! `boot_regression` et `lockstep` hachent en SHA-256 et demandent la
+//! fonctionnalité `rom-tools`.
+
+pub mod allocations;
+pub mod audio_golden;
+#[cfg(feature = "rom-tools")]
+pub mod boot_regression;
+#[cfg(feature = "rom-tools")]
+pub mod lockstep;
+pub mod v60_fuzz;
+
+pub use allocations::*;
+pub use audio_golden::*;
+#[cfg(feature = "rom-tools")]
+pub use boot_regression::*;
+#[cfg(feature = "rom-tools")]
+pub use lockstep::*;
+pub use v60_fuzz::*;
+
+use crate::error::{EmulatorError, MemoryFault, Result};
+use crate::memory::{BusError, MemoryInterface};
+use anyhow::anyhow;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use thiserror::Error;
+
+pub(crate) use crate::emulator::IDLE_INPUT;
+
+/// Sens d'un accès au bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+/// Faute renvoyée par le bus simulé.
+///
+/// Elle est remontée sous la forme de la [`MemoryFault`] correspondante de
+/// la mémoire, pour que le CPU la traite comme sur le vrai bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum BusFault {
+    /// Aucune réponse du bus à cette adresse
+    #[error("erreur de bus à l'adresse 0x{0:08X}")]
+    BusError(u32),
+
+    /// Accès 16/32 bits à une adresse non alignée (adresse, taille)
+    #[error("accès non aligné de {1} octets à l'adresse 0x{0:08X}")]
+    Misaligned(u32, u8),
+}
+
+impl BusFault {
+    fn into_error(self, kind: AccessKind) -> EmulatorError {
+        match self {
+            BusFault::BusError(address) => BusError { address, write: kind == AccessKind::Write }.into(),
+            BusFault::Misaligned(address, size) => MemoryFault::Misaligned { address, size }.into(),
+        }
+    }
+}
+
+/// Entrée du journal des accès
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusAccess {
+    pub kind: AccessKind,
+    pub address: u32,
+
+    /// Taille de l'accès en octets (1, 2 ou 4)
+    pub size: u8,
+
+    /// Valeur lue ou écrite (0 en cas de faute)
+    pub value: u32,
+
+    /// Faute éventuellement renvoyée
+    pub fault: Option<BusFault>,
+}
+
+/// Accès attendu par le script de test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Expectation {
+    kind: AccessKind,
+    address: u32,
+    size: u8,
+    value: u32,
+}
+
+/// Bus mémoire déterministe pour les tests du CPU et des DMA
+#[derive(Debug, Default)]
+pub struct MockBus {
+    /// Contenu mémoire (creux, les octets absents valent `fill`)
+    memory: HashMap<u32, u8>,
+
+    /// Valeur des octets jamais écrits
+    fill: u8,
+
+    /// Accès attendus, consommés dans l'ordre
+    expectations: RefCell<VecDeque<Expectation>>,
+
+    /// Accès ne correspondant pas à l'attente en tête de file
+    unexpected: RefCell<Vec<BusAccess>>,
+
+    /// Exiger l'ordre strict : tout accès doit correspondre à l'attente suivante
+    strict: bool,
+
+    /// Réponses successives d'un périphérique, par adresse
+    responses: RefCell<HashMap<u32, VecDeque<u32>>>,
+
+    /// Plages renvoyant une erreur de bus
+    faults: Vec<Range<u32>>,
+
+    /// Refuser les accès 16/32 bits non alignés
+    check_alignment: bool,
+
+    /// Journal des accès
+    log: RefCell<Vec<BusAccess>>,
+}
+
+impl MockBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Valeur renvoyée par les octets jamais écrits
+    pub fn with_fill(mut self, fill: u8) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Tout accès doit correspondre, dans l'ordre, aux attentes déclarées
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Refuse les accès 16/32 bits non alignés
+    pub fn with_alignment_check(mut self) -> Self {
+        self.check_alignment = true;
+        self
+    }
+
+    /// Les accès dans `range` renvoient une erreur de bus
+    pub fn fault_range(mut self, range: Range<u32>) -> Self {
+        self.faults.push(range);
+        self
+    }
+
+    /// Charge des octets en mémoire sans les journaliser
+    pub fn load(&mut self, address: u32, data: &[u8]) {
+        for (i, &byte) in data.iter().enumerate() {
+            self.memory.insert(address.wrapping_add(i as u32), byte);
+        }
+    }
+
+    /// Attend une lecture de `size` octets et y répond par `value`
+    pub fn expect_read(&mut self, address: u32, size: u8, value: u32) -> &mut Self {
+        self.expect(AccessKind::Read, address, size, value)
+    }
+
+    /// Attend une écriture de `size` octets de la valeur `value`
+    pub fn expect_write(&mut self, address: u32, size: u8, value: u32) -> &mut Self {
+        self.expect(AccessKind::Write, address, size, value)
+    }
+
+    fn expect(&mut self, kind: AccessKind, address: u32, size: u8, value: u32) -> &mut Self {
+        self.expectations.get_mut().push_back(Expectation { kind, address, size, value });
+        self
+    }
+
+    /// Les lectures successives de `address` renvoient `values` ; la dernière
+    /// valeur est répétée une fois la suite épuisée
+    pub fn respond(&mut self, address: u32, values: &[u32]) -> &mut Self {
+        self.responses.get_mut().insert(address, values.iter().copied().collect());
+        self
+    }
+
+    /// Journal de tous les accès effectués
+    pub fn accesses(&self) -> Vec<BusAccess> {
+        self.log.borrow().clone()
+    }
+
+    /// Accès d'un seul sens
+    pub fn accesses_of(&self, kind: AccessKind) -> Vec<BusAccess> {
+        self.log.borrow().iter().filter(|access| access.kind == kind).copied().collect()
+    }
+
+    /// Vide le journal
+    pub fn clear_log(&mut self) {
+        self.log.get_mut().clear();
+    }
+
+    /// Vérifie que toutes les attentes ont été satisfaites, sans accès inattendu
+    pub fn verify(&self) -> anyhow::Result<()> {
+        if let Some(access) = self.unexpected.borrow().first() {
+            return Err(anyhow!(
+                "Accès inattendu: {:?} de {} octets à 0x{:08X} (valeur 0x{:X})",
+                access.kind, access.size, access.address, access.value
+            ));
+        }
+
+        let remaining = self.expectations.borrow();
+        if let Some(expected) = remaining.front() {
+            return Err(anyhow!(
+                "{} accès attendus non effectués, le premier: {:?} de {} octets à 0x{:08X}",
+                remaining.len(), expected.kind, expected.size, expected.address
+            ));
+        }
+        Ok(())
+    }
+
+    /// Faute éventuelle pour un accès
+    fn fault_for(&self, address: u32, size: u8) -> Option<BusFault> {
+        let end = address.wrapping_add(size as u32 - 1);
+        if self.faults.iter().any(|range| range.contains(&address) || range.contains(&end)) {
+            return Some(BusFault::BusError(address));
+        }
+        if self.check_alignment && size > 1 && !address.is_multiple_of(size as u32) {
+            return Some(BusFault::Misaligned(address, size));
+        }
+        None
+    }
+
+    /// Consomme l'attente en tête si elle correspond à l'accès
+    fn take_expectation(&self, kind: AccessKind, address: u32, size: u8) -> Option<Expectation> {
+        let mut expectations = self.expectations.borrow_mut();
+        match expectations.front() {
+            Some(e) if e.kind == kind && e.address == address && e.size == size => expectations.pop_front(),
+            _ => None,
+        }
+    }
+
+    fn read_raw(&self, address: u32, size: u8) -> u32 {
+        (0..size as u32).fold(0, |value, i| {
+            let byte = self.memory.get(&address.wrapping_add(i)).copied().unwrap_or(self.fill);
+            value | (byte as u32) << (8 * i)
+        })
+    }
+
+    fn write_raw(&mut self, address: u32, size: u8, value: u32) {
+        for i in 0..size as u32 {
+            self.memory.insert(address.wrapping_add(i), (value >> (8 * i)) as u8);
+        }
+    }
+
+    fn read(&self, address: u32, size: u8) -> Result<u32> {
+        if let Some(fault) = self.fault_for(address, size) {
+            self.log.borrow_mut().push(BusAccess { kind: AccessKind::Read, address, size, value: 0, fault: Some(fault) });
+            return Err(fault.into_error(AccessKind::Read));
+        }
+
+        let expected = self.take_expectation(AccessKind::Read, address, size);
+        let value = if let Some(expected) = expected {
+            expected.value
+        } else {
+            let scripted = self.responses.borrow_mut().get_mut(&address).and_then(|queue| {
+                if queue.len() > 1 { queue.pop_front() } else { queue.front().copied() }
+            });
+            scripted.unwrap_or_else(|| self.read_raw(address, size))
+        };
+
+        let access = BusAccess { kind: AccessKind::Read, address, size, value, fault: None };
+        if self.strict && expected.is_none() {
+            self.unexpected.borrow_mut().push(access);
+        }
+        self.log.borrow_mut().push(access);
+        Ok(value)
+    }
+
+    fn write(&mut self, address: u32, size: u8, value: u32) -> Result<()> {
+        if let Some(fault) = self.fault_for(address, size) {
+            self.log.get_mut().push(BusAccess { kind: AccessKind::Write, address, size, value, fault: Some(fault) });
+            return Err(fault.into_error(AccessKind::Write));
+        }
+
+        // Une écriture attendue doit aussi porter la bonne valeur
+        let access = BusAccess { kind: AccessKind::Write, address, size, value, fault: None };
+        let matched = match self.take_expectation(AccessKind::Write, address, size) {
+            Some(expected) => expected.value == value,
+            None => !self.strict,
+        };
+        if !matched {
+            self.unexpected.get_mut().push(access);
+        }
+
+        self.write_raw(address, size, value);
+        self.log.get_mut().push(access);
+        Ok(())
+    }
+}
+
+impl MemoryInterface for MockBus {
+    fn read_u8(&self, address: u32) -> Result<u8> {
+        self.read(address, 1).map(|value| value as u8)
+    }
+
+    fn read_u16(&self, address: u32) -> Result<u16> {
+        self.read(address, 2).map(|value| value as u16)
+    }
+
+    fn read_u32(&self, address: u32) -> Result<u32> {
+        self.read(address, 4)
+    }
+
+    fn write_u8(&mut self, address: u32, value: u8) -> Result<()> {
+        self.write(address, 1, value as u32)
+    }
+
+    fn write_u16(&mut self, address: u32, value: u16) -> Result<()> {
+        self.write(address, 2, value as u32)
+    }
+
+    fn write_u32(&mut self, address: u32, value: u32) -> Result<()> {
+        self.write(address, 4, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backing_memory_is_little_endian() {
+        let mut bus = MockBus::new().with_fill(0xFF);
+        bus.write_u32(0x100, 0x1234_5678).unwrap();
+
+        assert_eq!(bus.read_u8(0x100).unwrap(), 0x78);
+        assert_eq!(bus.read_u16(0x102).unwrap(), 0x1234);
+        assert_eq!(bus.read_u8(0x200).unwrap(), 0xFF);
+        assert_eq!(bus.accesses().len(), 4);
+        assert_eq!(bus.accesses_of(AccessKind::Write).len(), 1);
+    }
+
+    #[test]
+    fn test_expectations_in_order() {
+        let mut bus = MockBus::new().strict();
+        bus.expect_read(0x10, 4, 0xCAFE).expect_write(0x20, 2, 0xBEEF);
+
+        assert_eq!(bus.read_u32(0x10).unwrap(), 0xCAFE);
+        assert!(bus.verify().is_err());
+
+        bus.write_u16(0x20, 0xBEEF).unwrap();
+        bus.verify().unwrap();
+
+        // En mode strict, un accès non déclaré est signalé
+        bus.read_u8(0x30).unwrap();
+        assert!(bus.verify().is_err());
+    }
+
+    #[test]
+    fn test_write_with_wrong_value_is_reported() {
+        let mut bus = MockBus::new();
+        bus.expect_write(0x20, 4, 1);
+        bus.write_u32(0x20, 2).unwrap();
+        assert!(bus.verify().is_err());
+    }
+
+    #[test]
+    fn test_scripted_device_responses() {
+        let mut bus = MockBus::new();
+        bus.respond(0x40, &[0, 0, 1]);
+
+        let polled: Vec<u32> = (0..4).map(|_| bus.read_u32(0x40).unwrap()).collect();
+        assert_eq!(polled, vec![0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_fault_injection() {
+        let mut bus = MockBus::new().fault_range(0x1000..0x2000).with_alignment_check();
+
+        let err = bus.read_u32(0x0FFE).unwrap_err();
+        assert_eq!(err.bus_error(), Some(&BusError { address: 0x0FFE, write: false }));
+
+        let err = bus.write_u16(0x11, 0).unwrap_err();
+        assert!(matches!(err, EmulatorError::MemoryFault(MemoryFault::Misaligned { address: 0x11, size: 2 })));
+
+        let log = bus.accesses();
+        assert_eq!(log.len(), 2);
+        assert!(log.iter().all(|access| access.fault.is_some()));
+    }
+}
@@ -0,0 +1,358 @@
+//! Banc de fuzzing du décodeur et de l'exécuteur du V60
+//!
+//! [`FuzzCase::from_bytes`] tire d'une entrée arbitraire (celle de
+//! cargo-fuzz, cible `fuzz/fuzz_targets/v60_instructions.rs`) une
+//! instruction que le décodeur sait traiter, l'état initial des registres et
+//! l'adresse d'exécution. [`FuzzCase::run`] l'exécute par `NecV60::step` sur
+//! une mémoire simulée et compare l'état obtenu à un modèle de référence
+//! indépendant de l'interpréteur : avance du PC, registres, flags et
+//! écritures en mémoire. Un panic ou une [`InvariantViolation`] est un bogue.
+
+use std::fmt;
+
+use super::MockBus;
+use crate::cpu::{NecV60, ProcessorStatusWord};
+use crate::error::{CpuException, EmulatorError};
+use crate::memory::MemoryInterface;
+
+/// Opcodes reconnus par le décodeur : formats 1 et 2 (MOV, ADD, SUB, AND,
+/// OR, XOR, CMP), format 3 (chargement, rangement), format 4 (saut, saut
+/// conditionnel, appel)
+pub const FUZZ_OPCODES: [u8; 19] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+    0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16,
+    0x20, 0x21,
+    0x30, 0x31, 0x32,
+];
+
+/// Flags de condition tirés au hasard et comparés
+const CONDITION_FLAGS: u32 = 0x1F;
+
+/// Lecteur d'octets qui complète une entrée trop courte par des zéros
+struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl ByteReader<'_> {
+    fn u8(&mut self) -> u8 {
+        let byte = self.data.get(self.position).copied().unwrap_or(0);
+        self.position += 1;
+        byte
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes([self.u8(), self.u8(), self.u8(), self.u8()])
+    }
+}
+
+/// Instruction et état initial à exécuter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzCase {
+    pub opcode: u8,
+    /// Registre destination (ou condition pour les branchements)
+    pub r2: u8,
+    /// Registre source (ou base de l'adresse)
+    pub r1: u8,
+    /// Immédiat, déplacement ou cible selon le format
+    pub operand: [u8; 4],
+    pub pc: u32,
+    pub sp: u32,
+    pub psw: u32,
+    pub registers: [u32; 32],
+}
+
+/// Résultat d'un cas conforme au modèle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FuzzOutcome {
+    /// Exécutée, état conforme
+    Executed,
+    /// Refusée comme non implémentée par l'interpréteur (CMP)
+    Unimplemented,
+}
+
+/// Écart entre l'interpréteur et le modèle de référence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvariantViolation {
+    pub case: Box<FuzzCase>,
+    pub message: String,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (opcode 0x{:02X} à 0x{:08X}, codage {:02X?})", self.message, self.case.opcode, self.case.pc, self.case.encoding())
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+/// Effet attendu d'après le modèle de référence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    /// Registre écrit ; flags recalculés ou conservés
+    Register { index: usize, value: u32, psw: ProcessorStatusWord },
+    /// Mot rangé en mémoire
+    Store { address: u32, value: u32 },
+    /// Flags seuls (comparaison)
+    Flags(ProcessorStatusWord),
+    /// Branchement : PC final, et adresse de retour empilée pour un appel
+    Branch { pc: u32, call: bool },
+}
+
+impl FuzzCase {
+    /// Construit un cas à partir d'octets quelconques
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let mut reader = ByteReader { data, position: 0 };
+        let opcode = FUZZ_OPCODES[reader.u8() as usize % FUZZ_OPCODES.len()];
+        let r2 = reader.u8() & 0x1F;
+        let r1 = reader.u8() & 0x1F;
+        let operand = [reader.u8(), reader.u8(), reader.u8(), reader.u8()];
+
+        // Un PC nul est réservé aux tests unitaires de l'exécuteur
+        let pc = match reader.u32() & !1 {
+            0 => 0x1000,
+            pc => pc,
+        };
+        let sp = reader.u32() & !3;
+        let psw = reader.u8() as u32 & CONDITION_FLAGS;
+
+        // Les valeurs des opérandes d'abord, pour que les entrées courtes
+        // restent intéressantes
+        let mut registers: [u32; 32] = std::array::from_fn(|index| index as u32 * 0x0101_0101);
+        registers[r2 as usize] = reader.u32();
+        registers[r1 as usize] = reader.u32();
+        for register in registers.iter_mut() {
+            if reader.position < data.len() {
+                *register = reader.u32();
+            }
+        }
+
+        Self { opcode, r2, r1, operand, pc, sp, psw, registers }
+    }
+
+    /// Octets de l'instruction
+    pub fn encoding(&self) -> [u8; 6] {
+        let word = (self.opcode as u16) << 10 | (self.r2 as u16) << 5 | self.r1 as u16;
+        let [low, high] = word.to_le_bytes();
+        let [a, b, c, d] = self.operand;
+        [low, high, a, b, c, d]
+    }
+
+    /// Taille attendue selon le format
+    fn size(&self) -> u32 {
+        match self.opcode {
+            0x00..=0x0F => 2,
+            0x10..=0x1F => 4,
+            0x20..=0x2F => 6,
+            _ => 4,
+        }
+    }
+
+    fn violation(&self, message: String) -> InvariantViolation {
+        InvariantViolation { case: Box::new(self.clone()), message }
+    }
+
+    /// Modèle de référence
+    fn expected(&self, bus: &MockBus) -> Expected {
+        let psw = ProcessorStatusWord::from_bits_truncate(self.psw);
+        let (r2, r1) = (self.r2 as usize, self.r1 as usize);
+        let (dest, src) = (self.registers[r2], self.registers[r1]);
+        let immediate = u16::from_le_bytes([self.operand[0], self.operand[1]]) as u32;
+        let second = if self.opcode & 0x10 != 0 { immediate } else { src };
+        let next = self.pc.wrapping_add(self.size());
+
+        match self.opcode {
+            0x30..=0x3F => {
+                // Cible absolue sur 24 bits : octet haut du premier mot puis
+                // les deux suivants
+                let encoding = self.encoding();
+                let target = u32::from_le_bytes([encoding[1], encoding[2], encoding[3], 0]);
+                let taken = match self.opcode {
+                    0x31 => condition(psw, self.r2),
+                    _ => true,
+                };
+                Expected::Branch { pc: if taken { target } else { next }, call: self.opcode == 0x32 }
+            }
+            0x20..=0x2F => {
+                let address = self.registers[r1].wrapping_add(u32::from_le_bytes(self.operand));
+                if self.opcode == 0x20 {
+                    let value = bus.read_u32(address).unwrap_or(0);
+                    Expected::Register { index: r2, value, psw }
+                } else {
+                    Expected::Store { address, value: dest }
+                }
+            }
+            opcode => self.expected_alu(opcode & 0x0F, r2, dest, second, psw),
+        }
+    }
+
+    /// Modèle de référence des formats 1 et 2
+    fn expected_alu(&self, operation: u8, r2: usize, dest: u32, second: u32, psw: ProcessorStatusWord) -> Expected {
+        match operation {
+            0x00 => Expected::Register { index: r2, value: second, psw },
+            0x01 => {
+                let (value, carry) = dest.overflowing_add(second);
+                let overflow = (dest as i32).overflowing_add(second as i32).1;
+                Expected::Register { index: r2, value, psw: flags(psw, value, carry, overflow) }
+            }
+            0x02 => {
+                let (value, carry) = dest.overflowing_sub(second);
+                let overflow = (dest as i32).overflowing_sub(second as i32).1;
+                Expected::Register { index: r2, value, psw: flags(psw, value, carry, overflow) }
+            }
+            0x03 => Expected::Register { index: r2, value: dest & second, psw: flags(psw, dest & second, false, false) },
+            0x04 => Expected::Register { index: r2, value: dest | second, psw: flags(psw, dest | second, false, false) },
+            0x05 => Expected::Register { index: r2, value: dest ^ second, psw: flags(psw, dest ^ second, false, false) },
+            _ => {
+                let (value, carry) = dest.overflowing_sub(second);
+                let overflow = (dest as i32).overflowing_sub(second as i32).1;
+                Expected::Flags(flags(psw, value, carry, overflow))
+            }
+        }
+    }
+
+    /// Exécute le cas et le compare au modèle de référence
+    pub fn run(&self) -> Result<FuzzOutcome, InvariantViolation> {
+        let mut bus = MockBus::new();
+        bus.load(self.pc, &self.encoding());
+        let expected = self.expected(&bus);
+
+        let mut cpu = NecV60::new();
+        cpu.registers.general = self.registers;
+        cpu.registers.pc = self.pc;
+        cpu.registers.sp = self.sp;
+        cpu.registers.psw = ProcessorStatusWord::from_bits_truncate(self.psw);
+
+        match cpu.step(&mut bus) {
+            Ok(_) => {}
+            Err(EmulatorError::CpuException(CpuException::Unimplemented { .. })) if matches!(expected, Expected::Flags(_)) => {
+                return Ok(FuzzOutcome::Unimplemented);
+            }
+            Err(e) => return Err(self.violation(format!("erreur inattendue: {}", e))),
+        }
+
+        // Registres : seule la destination change
+        let mut registers = self.registers;
+        if let Expected::Register { index, value, .. } = expected {
+            registers[index] = value;
+        }
+        if let Some(index) = (0..32).find(|&i| cpu.registers.general[i] != registers[i]) {
+            return Err(self.violation(format!(
+                "R{} vaut 0x{:08X}, attendu 0x{:08X}",
+                index, cpu.registers.general[index], registers[index]
+            )));
+        }
+
+        // Flags
+        let psw = match expected {
+            Expected::Register { psw, .. } | Expected::Flags(psw) => psw,
+            _ => ProcessorStatusWord::from_bits_truncate(self.psw),
+        };
+        let actual = cpu.registers.psw.bits() & CONDITION_FLAGS;
+        if actual != psw.bits() & CONDITION_FLAGS {
+            return Err(self.violation(format!("flags 0b{:05b}, attendus 0b{:05b}", actual, psw.bits() & CONDITION_FLAGS)));
+        }
+
+        // Avance du PC
+        let pc = match expected {
+            Expected::Branch { pc, .. } => pc,
+            _ => self.pc.wrapping_add(self.size()),
+        };
+        if cpu.registers.pc != pc {
+            return Err(self.violation(format!("PC 0x{:08X}, attendu 0x{:08X}", cpu.registers.pc, pc)));
+        }
+
+        // Mémoire
+        match expected {
+            Expected::Store { address, value } => {
+                let stored = bus.read_u32(address).unwrap_or(0);
+                if stored != value {
+                    return Err(self.violation(format!("0x{:08X} contient 0x{:08X}, attendu 0x{:08X}", address, stored, value)));
+                }
+            }
+            Expected::Branch { call: true, .. } => {
+                let sp = self.sp.wrapping_sub(4);
+                let return_address = self.pc.wrapping_add(self.size());
+                if cpu.registers.sp != sp || bus.read_u32(sp).unwrap_or(0) != return_address {
+                    return Err(self.violation(format!("adresse de retour 0x{:08X} non empilée en 0x{:08X}", return_address, sp)));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(FuzzOutcome::Executed)
+    }
+}
+
+/// Flags d'un résultat d'ALU ; les autres bits du PSW sont conservés
+fn flags(psw: ProcessorStatusWord, value: u32, carry: bool, overflow: bool) -> ProcessorStatusWord {
+    let mut psw = psw;
+    psw.set(ProcessorStatusWord::CARRY, carry);
+    psw.set(ProcessorStatusWord::ZERO, value == 0);
+    psw.set(ProcessorStatusWord::SIGN, value & 0x8000_0000 != 0);
+    psw.set(ProcessorStatusWord::OVERFLOW, overflow);
+    psw.set(ProcessorStatusWord::PARITY, value.count_ones().is_multiple_of(2));
+    psw
+}
+
+/// Condition d'un saut conditionnel ; les codes inconnus valent « toujours »
+fn condition(psw: ProcessorStatusWord, code: u8) -> bool {
+    let zero = psw.contains(ProcessorStatusWord::ZERO);
+    let less = psw.contains(ProcessorStatusWord::SIGN) != psw.contains(ProcessorStatusWord::OVERFLOW);
+    match code {
+        0x01 => zero,
+        0x02 => !zero,
+        0x03 => !zero && !less,
+        0x04 => less,
+        0x05 => !less,
+        0x06 => zero || less,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Générateur xorshift, pour des cas reproductibles
+    fn random_bytes(state: &mut u64, out: &mut [u8]) {
+        for byte in out.iter_mut() {
+            *state ^= *state << 13;
+            *state ^= *state >> 7;
+            *state ^= *state << 17;
+            *byte = *state as u8;
+        }
+    }
+
+    #[test]
+    fn test_random_cases_match_reference() {
+        let mut state = 0x9E37_79B9_7F4A_7C15;
+        let mut data = [0u8; 64];
+        for _ in 0..20_000 {
+            random_bytes(&mut state, &mut data);
+            let length = data[0] as usize % data.len();
+            if let Err(violation) = FuzzCase::from_bytes(&data[..length]).run() {
+                panic!("{}", violation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        // Instruction en fin d'espace d'adressage
+        let mut case = FuzzCase::from_bytes(&[0; 4]);
+        case.pc = 0xFFFF_FFFE;
+        assert_eq!(case.run(), Ok(FuzzOutcome::Executed));
+
+        // 0 - i32::MIN déborde
+        let mut case = FuzzCase::from_bytes(&[2, 1, 2]);
+        (case.registers[1], case.registers[2]) = (0, 0x8000_0000);
+        assert_eq!(case.run(), Ok(FuzzOutcome::Executed));
+
+        // Chargement depuis base + déplacement, puis CMP non implémenté
+        let case = FuzzCase::from_bytes(&[14, 3, 4, 0x10, 0, 0, 0]);
+        assert_eq!(case.opcode, 0x20);
+        assert_eq!(case.run(), Ok(FuzzOutcome::Executed));
+        assert_eq!(FuzzCase::from_bytes(&[6]).run(), Ok(FuzzOutcome::Unimplemented));
+    }
+}
@@ -0,0 +1,180 @@
+//! Frontend navigateur (feature `web`, cible wasm32)
+//!
+//! Le rendu passe par WebGPU dans un `<canvas>` de la page, le son par Web
+//! Audio. Une page n'a pas accès au disque : les ROM sont les fichiers choisis
+//! par l'utilisateur, transmis en mémoire avec
+//! [`WebEmulator::add_rom_file`]. Le clavier est lu par winit sur le canvas,
+//! avec les mêmes touches que le frontend de bureau.
+//!
+//! Exemple d'intégration : `examples/web/index.html`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use wasm_bindgen::prelude::*;
+use web_sys::{AudioContext, HtmlCanvasElement};
+use winit::dpi::PhysicalSize;
+use winit::event::{ElementState, Event, KeyEvent, WindowEvent};
+use winit::event_loop::EventLoop;
+use winit::keyboard::PhysicalKey;
+use winit::platform::web::{EventLoopExtWebSys, WindowBuilderExtWebSys};
+use winit::window::{Window, WindowBuilder};
+
+use crate::audio::{ScspAudio, WebAudioBackend};
+use crate::config::EmulatorConfig;
+use crate::emulator::EmulatorCore;
+use crate::gpu::{AdapterSelection, Model2Gpu, Model2Resolution};
+use crate::input::{InputManager, InputState};
+use crate::rom::Model2RomSystem;
+
+/// Machine émulée, partagée entre la page et la boucle d'événements
+struct WebMachine {
+    core: EmulatorCore,
+    gpu: Model2Gpu,
+    rom_system: Model2RomSystem,
+    config: EmulatorConfig,
+    input: InputManager,
+    input_state: InputState,
+    window: Arc<Window>,
+
+    /// Contexte Web Audio, repris à la première touche
+    audio_context: Option<AudioContext>,
+
+    /// Un jeu est chargé et l'émulation n'a pas rencontré d'erreur
+    running: bool,
+}
+
+impl WebMachine {
+    fn handle_event(&mut self, event: Event<()>) {
+        let Event::WindowEvent { event, .. } = event else {
+            return;
+        };
+        match event {
+            WindowEvent::KeyboardInput {
+                event: KeyEvent { physical_key: PhysicalKey::Code(key), state, .. },
+                ..
+            } => {
+                self.input.handle_key(key, state);
+                if state == ElementState::Pressed {
+                    if let Some(context) = &self.audio_context {
+                        let _ = context.resume();
+                    }
+                }
+            }
+            WindowEvent::Resized(size) => self.gpu.resize_surface(size.width, size.height),
+            WindowEvent::RedrawRequested => {
+                self.run_frame();
+                if let Err(e) = self.gpu.end_frame() {
+                    log::error!("Erreur GPU end_frame: {}", e);
+                }
+                // Une frame émulée par rafraîchissement du navigateur
+                self.window.request_redraw();
+            }
+            _ => {}
+        }
+    }
+
+    fn run_frame(&mut self) {
+        self.input_state.latch(&self.input);
+        if !self.running {
+            return;
+        }
+        if let Err(e) = self.core.run_frame(self.input_state.io_word(), Some(&mut self.gpu)) {
+            log::error!("Erreur d'émulation, machine arrêtée: {}", e);
+            self.running = false;
+        }
+    }
+}
+
+/// Émulateur affiché dans un canvas de la page
+#[wasm_bindgen]
+pub struct WebEmulator {
+    machine: Rc<RefCell<WebMachine>>,
+
+    /// Boucle d'événements, confiée au navigateur par [`WebEmulator::start`]
+    event_loop: Option<EventLoop<()>>,
+}
+
+#[wasm_bindgen]
+impl WebEmulator {
+    /// Ouvre l'émulateur dans `canvas` ; échoue si le navigateur n'expose pas WebGPU
+    pub async fn create(canvas: HtmlCanvasElement) -> Result<WebEmulator, JsError> {
+        let event_loop = EventLoop::new()?;
+        let (width, height) = Model2Resolution::Standard.dimensions();
+        let window = Arc::new(
+            WindowBuilder::new()
+                .with_inner_size(PhysicalSize::new(width, height))
+                .with_canvas(Some(canvas))
+                .with_focusable(true)
+                .build(&event_loop)?,
+        );
+
+        let config = EmulatorConfig::default();
+        let gpu = Model2Gpu::new(window.clone(), config.video.vsync, &AdapterSelection::default()).await?;
+
+        let mut core = EmulatorCore::new(&config.audio);
+        let mut audio_context = None;
+        if config.audio.enabled {
+            match WebAudioBackend::open(config.audio.sample_rate, 2) {
+                Ok(output) => {
+                    audio_context = Some(output.context());
+                    core.audio = ScspAudio::with_backend(Box::new(output));
+                    core.audio.set_volume(config.audio.volume);
+                }
+                Err(e) => log::warn!("Web Audio indisponible, sortie muette: {}", e),
+            }
+        }
+
+        let machine = WebMachine {
+            core,
+            gpu,
+            rom_system: Model2RomSystem::new(),
+            input: InputManager::with_config(&config.input),
+            config,
+            input_state: InputState::new(),
+            window,
+            audio_context,
+            running: false,
+        };
+        Ok(Self { machine: Rc::new(RefCell::new(machine)), event_loop: Some(event_loop) })
+    }
+
+    /// Fournit un fichier choisi par l'utilisateur : ROM seule ou archive ZIP
+    pub fn add_rom_file(&self, name: &str, data: Vec<u8>) -> Result<(), JsError> {
+        self.machine.borrow_mut().rom_system.add_rom_file(name, data)?;
+        Ok(())
+    }
+
+    /// Noms courts des jeux connus de la base
+    pub fn games(&self) -> Vec<String> {
+        let machine = self.machine.borrow();
+        let mut games: Vec<String> = machine.rom_system.rom_manager.database().list_games()
+            .into_iter()
+            .map(|game| game.short_name.clone())
+            .collect();
+        games.sort();
+        games
+    }
+
+    /// Charge un jeu (nom court, par exemple `vf2`) depuis les fichiers fournis
+    pub fn load_game(&self, game: &str) -> Result<(), JsError> {
+        let mut machine = self.machine.borrow_mut();
+        let machine = &mut *machine;
+        machine.running = false;
+        machine.core.load_game(&mut machine.rom_system, game, &machine.config.emulation)?;
+        machine.input_state.set_player_count(machine.rom_system.rom_manager.database().player_count(game));
+        machine.running = true;
+        Ok(())
+    }
+
+    /// Confie la boucle d'événements au navigateur ; l'émulation avance
+    /// ensuite à chaque rafraîchissement de la page
+    pub fn start(&mut self) -> Result<(), JsError> {
+        let event_loop = self.event_loop.take().ok_or_else(|| JsError::new("émulateur déjà démarré"))?;
+        let machine = self.machine.clone();
+        machine.borrow().window.request_redraw();
+        event_loop.spawn(move |event, _| machine.borrow_mut().handle_event(event));
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! Boucles chaudes sans allocation
+//!
+//! Après une première frame qui dimensionne les tampons, les frames
+//! suivantes ne doivent plus allouer : génération audio, lots de commandes
+//! GPU, exécution du CPU et frame complète du cœur.
+
+use pixel_model2_rust::audio::*;
+use pixel_model2_rust::config::{AudioBackendKind, EmulatorConfig};
+use pixel_model2_rust::cpu::NecV60;
+use pixel_model2_rust::emulator::EmulatorCore;
+use pixel_model2_rust::gpu::Model2Gpu;
+use pixel_model2_rust::memory::*;
+use pixel_model2_rust::testing::{count_allocations, CountingAllocator};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Cycles d'une frame à 60 Hz
+const FRAME_CYCLES: u32 = pixel_model2_rust::MAIN_CPU_FREQUENCY / 60;
+
+#[test]
+fn test_audio_generation_does_not_allocate() {
+    let mut audio = ScspAudio::headless();
+    audio.play_sample(0, 0, 0x400, Some(0x100), encode_frequency(0, 0), 0x800);
+    audio.play_sample(7, 0x1000, 0x1800, None, encode_frequency(-1, 256), 0x400);
+    audio.update(FRAME_CYCLES);
+
+    let (_, allocations) = count_allocations(|| {
+        for _ in 0..10 {
+            audio.update(FRAME_CYCLES);
+        }
+    });
+    assert!(audio.slot_active(0));
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_gpu_command_batches_reuse_buffers() {
+    let mut memory = Model2Memory::new();
+    let mut batch: Vec<GpuCommand> = Vec::new();
+    // Deux effacements d'écran par frame, dans le banc que remplit le CPU
+    let frame = |memory: &mut Model2Memory, batch: &mut Vec<GpuCommand>| {
+        memory.write_u32(DISPLAY_LIST_BASE, 0x00FF_0000).unwrap();
+        memory.write_u32(DISPLAY_LIST_BASE + 4, 0x0000_00FF).unwrap();
+        memory.write_u32(DISPLAY_LIST_BASE + 8, DISPLAY_LIST_END).unwrap();
+        memory.write_u32(IO_REGISTERS_BASE + DISPLAY_BANK_REGISTER, DISPLAY_BANK_SWAP).unwrap();
+        memory.swap_display_lists();
+        batch.clear();
+        memory.process_gpu_commands_into(batch)
+    };
+    frame(&mut memory, &mut batch);
+
+    let (count, allocations) = count_allocations(|| (0..10).map(|_| frame(&mut memory, &mut batch)).sum::<usize>());
+    assert_eq!(count, 20);
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_cpu_loop_does_not_allocate_once_decoded() {
+    let mut cpu = NecV60::new();
+    let mut memory = Model2Memory::new();
+    cpu.registers.pc = 0x100;
+    cpu.run_cycles(10_000, &mut memory).unwrap();
+
+    let (_, allocations) = count_allocations(|| {
+        cpu.registers.pc = 0x100;
+        cpu.run_cycles(10_000, &mut memory).unwrap()
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_core_frame_does_not_allocate() {
+    let mut config = EmulatorConfig::default();
+    config.audio.backend = AudioBackendKind::Null;
+    let mut core = EmulatorCore::new(&config.audio);
+    let mut gpu = Model2Gpu::headless();
+    for _ in 0..3 {
+        core.cpu.registers.pc = 0x100;
+        core.run_frame(0xFFFF, Some(&mut gpu)).unwrap();
+    }
+
+    let (_, allocations) = count_allocations(|| {
+        core.cpu.registers.pc = 0x100;
+        core.run_frame(0xFFFF, Some(&mut gpu)).unwrap();
+        gpu.end_frame().unwrap();
+    });
+    assert_eq!(allocations, 0);
+}
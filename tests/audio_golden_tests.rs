@@ -0,0 +1,121 @@
+//! Rendus de référence du SCSP
+//!
+//! Chaque test programme les registres d'un slot comme le ferait le pilote
+//! son d'un jeu, rend un nombre fixe de trames sans sortie matérielle et
+//! compare le résultat au buffer de `tests/golden/`. Après un changement
+//! voulu du rendu, les références se régénèrent avec :
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test --test audio_golden_tests
+//! ```
+
+use std::path::PathBuf;
+
+use pixel_model2_rust::audio::*;
+use pixel_model2_rust::testing::{capture_audio, compare_golden, decode_pcm16, encode_pcm16, render_frames};
+
+/// Écart toléré, en pas de quantification 16 bits
+const TOLERANCE_STEPS: u32 = 2;
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{}.pcm16", name))
+}
+
+/// Compare le rendu à la référence `name`, ou la réécrit si demandé
+fn check_golden(name: &str, samples: &[f32]) {
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, encode_pcm16(samples)).unwrap();
+        return;
+    }
+
+    let bytes = std::fs::read(&path)
+        .unwrap_or_else(|e| panic!("référence {} illisible ({}), UPDATE_GOLDEN=1 pour la créer", path.display(), e));
+    if let Err(mismatch) = compare_golden(samples, &decode_pcm16(&bytes), TOLERANCE_STEPS) {
+        panic!("rendu {} différent de la référence : {}", name, mismatch);
+    }
+}
+
+/// Programme le bloc de registres d'un slot, sans key on
+fn program_slot(audio: &mut ScspAudio, slot: usize, start: u32, loop_start: u16, loop_end: u16, frequency: u16, total_level: u8) {
+    let base = slot as u32 * SLOT_REGISTER_SIZE;
+    // LPCTL = 1 (boucle normale), PCM8B, SA
+    audio.write_register_u16(base, 0x0020 | 0x0010 | (start >> 16) as u16);
+    audio.write_register_u16(base + 0x02, start as u16);
+    audio.write_register_u16(base + 0x04, loop_start);
+    audio.write_register_u16(base + 0x06, loop_end);
+    audio.write_register_u16(base + 0x0C, total_level as u16);
+    audio.write_register_u16(base + 0x10, frequency);
+    audio.write_register_u16(base + 0x16, encode_direct_send(DISDL_MAX, DIPAN_CENTER));
+}
+
+/// Lève KYONB sur un slot (en gardant le reste du mot 0x00) puis KYONEX
+fn key(audio: &mut ScspAudio, slot: usize, on: bool) {
+    let base = slot as u32 * SLOT_REGISTER_SIZE;
+    let word = audio.read_register_u16(base) & !0x0800;
+    audio.write_register_u16(base, word | if on { 0x0800 } else { 0 } | KEY_EXECUTE);
+}
+
+/// Index des passages de négatif à positif sur le canal gauche
+fn rising_edges(samples: &[f32]) -> Vec<usize> {
+    let left: Vec<f32> = samples.iter().step_by(2).copied().collect();
+    left.windows(2).enumerate().filter(|(_, pair)| pair[0] < 0.0 && pair[1] >= 0.0).map(|(i, _)| i + 1).collect()
+}
+
+#[test]
+fn test_square_wave_matches_golden() {
+    let (mut audio, capture) = capture_audio();
+
+    // OCT -7 : un pas de 1/128 par trame, soit une période de 128 trames
+    // (344,5 Hz) ; le type d'onde n'a pas de registre matériel
+    program_slot(&mut audio, 0, 0, 0, 0x1000, encode_frequency(-7, 0), 0);
+    audio.registers.slot_registers[0].wave_type = 1;
+    key(&mut audio, 0, true);
+    let mut samples = render_frames(&mut audio, &capture, 2048);
+
+    // Hauteur : un front montant toutes les 128 trames
+    let edges = rising_edges(&samples);
+    assert!(edges.len() >= 15);
+    assert!(edges.windows(2).all(|pair| pair[1] - pair[0] == 128), "fronts {:?}", edges);
+
+    // Relâchement : le slot s'éteint à la fin de la release
+    key(&mut audio, 0, false);
+    samples.extend(render_frames(&mut audio, &capture, 3072));
+    assert!(!audio.slot_active(0));
+    assert!(samples[samples.len() - 2..].iter().all(|&sample| sample == 0.0));
+
+    check_golden("scsp_square", &samples);
+}
+
+#[test]
+fn test_pcm_loop_matches_golden() {
+    let (mut audio, capture) = capture_audio();
+
+    // Attaque de 16 octets puis une période de sinus de 48 octets bouclée
+    let mut wave = vec![128u8; 0x1000];
+    for (i, byte) in wave[0x1000 - 64..].iter_mut().enumerate() {
+        *byte = if i < 16 {
+            (128 + i * 7) as u8
+        } else {
+            let phase = (i - 16) as f32 / 48.0 * std::f32::consts::TAU;
+            (128.0 + phase.sin() * 100.0).round() as u8
+        };
+    }
+    audio.load_wave_memory(&wave);
+
+    // OCT -1, FNS 512 : pas de 0,75, interpolé entre les échantillons ; TL
+    // 0x10 atténue de 6 dB
+    program_slot(&mut audio, 5, 0x1000 - 64, 16, 64, encode_frequency(-1, 512), 0x10);
+    key(&mut audio, 5, true);
+    let samples = render_frames(&mut audio, &capture, 4096);
+
+    // Après l'attaque, la boucle de 48 octets se répète toutes les 64 trames
+    let edges = rising_edges(&samples);
+    assert!(edges.len() >= 60);
+    assert!(edges[1..].windows(2).all(|pair| pair[1] - pair[0] == 64), "fronts {:?}", edges);
+    assert!(audio.slot_active(5));
+    assert_eq!(samples.len(), 4096 * 2);
+
+    check_golden("scsp_pcm_loop", &samples);
+}
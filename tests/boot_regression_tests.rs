@@ -0,0 +1,67 @@
+//! Démarrage des jeux comparé aux empreintes de `tests/boot_hashes.toml`
+//!
+//! Sans ROMs, le test ne fait rien. Avec un dossier de ROMs, chaque jeu
+//! complet de la base est démarré et son image comparée aux empreintes
+//! enregistrées :
+//!
+//! ```text
+//! PM2_ROM_DIR=~/roms cargo test --release --test boot_regression_tests -- --nocapture
+//! ```
+//!
+//! Après un changement voulu de l'image (ou pour un jeu pas encore
+//! enregistré), les empreintes se réenregistrent avec `UPDATE_BOOT_HASHES=1`.
+
+#![cfg(feature = "rom-tools")]
+
+use std::path::PathBuf;
+
+use pixel_model2_rust::rom::Model2RomSystem;
+use pixel_model2_rust::testing::{boot_game, BootHashes, BOOT_CHECKPOINTS, BOOT_ROM_DIR_VAR};
+
+fn hashes_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/boot_hashes.toml")
+}
+
+#[test]
+fn test_supported_games_boot_like_recorded() {
+    let path = hashes_path();
+    let mut recorded = BootHashes::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let Some(rom_dir) = std::env::var_os(BOOT_ROM_DIR_VAR) else {
+        eprintln!("{} non défini : démarrage des jeux non vérifié", BOOT_ROM_DIR_VAR);
+        return;
+    };
+    let update = std::env::var_os("UPDATE_BOOT_HASHES").is_some();
+
+    let mut rom_system = Model2RomSystem::new();
+    rom_system.add_search_path(&rom_dir);
+    let audits = rom_system.rom_manager.audit_games().unwrap();
+
+    let mut failures = Vec::new();
+    for audit in audits.iter().filter(|audit| audit.is_playable()) {
+        let game = audit.short_name.as_str();
+        let actual = match boot_game(&mut rom_system, game, &BOOT_CHECKPOINTS) {
+            Ok(actual) => actual,
+            Err(e) => {
+                failures.push(format!("{} : démarrage interrompu ({:#})", game, e));
+                continue;
+            }
+        };
+
+        if update {
+            recorded.record(game, actual);
+            eprintln!("{} : empreintes enregistrées", game);
+        } else if recorded.checkpoints(game).is_none() {
+            eprintln!("{} : aucune empreinte enregistrée (UPDATE_BOOT_HASHES=1)", game);
+        } else if let Some((frame, expected, found)) = recorded.first_mismatch(game, &actual) {
+            failures.push(format!("{} : image différente à la frame {} ({} au lieu de {})", game, frame, found, expected));
+        } else {
+            eprintln!("{} : conforme", game);
+        }
+    }
+
+    if update {
+        let header = "# Empreintes du framebuffer au démarrage des jeux, relevées par\n# tests/boot_regression_tests.rs (UPDATE_BOOT_HASHES=1)\n\n";
+        std::fs::write(&path, format!("{}{}", header, recorded.to_toml())).unwrap();
+    }
+    assert!(failures.is_empty(), "régressions au démarrage :\n{}", failures.join("\n"));
+}
@@ -24,17 +24,17 @@ impl TestMemory {
 }
 
 impl MemoryInterface for TestMemory {
-    fn read_u8(&self, address: u32) -> anyhow::Result<u8> {
+    fn read_u8(&self, address: u32) -> pixel_model2_rust::Result<u8> {
         Ok(self.data.get(&address).copied().unwrap_or(0))
     }
     
-    fn read_u16(&self, address: u32) -> anyhow::Result<u16> {
+    fn read_u16(&self, address: u32) -> pixel_model2_rust::Result<u16> {
         let low = self.read_u8(address)? as u16;
         let high = self.read_u8(address + 1)? as u16;
         Ok(low | (high << 8))
     }
     
-    fn read_u32(&self, address: u32) -> anyhow::Result<u32> {
+    fn read_u32(&self, address: u32) -> pixel_model2_rust::Result<u32> {
         let mut bytes = [0u8; 4];
         for i in 0..4 {
             bytes[i] = self.read_u8(address + i as u32)?;
@@ -42,19 +42,19 @@ impl MemoryInterface for TestMemory {
         Ok(u32::from_le_bytes(bytes))
     }
     
-    fn write_u8(&mut self, address: u32, value: u8) -> anyhow::Result<()> {
+    fn write_u8(&mut self, address: u32, value: u8) -> pixel_model2_rust::Result<()> {
         self.data.insert(address, value);
         Ok(())
     }
     
-    fn write_u16(&mut self, address: u32, value: u16) -> anyhow::Result<()> {
+    fn write_u16(&mut self, address: u32, value: u16) -> pixel_model2_rust::Result<()> {
         let bytes = value.to_le_bytes();
         self.write_u8(address, bytes[0])?;
         self.write_u8(address + 1, bytes[1])?;
         Ok(())
     }
     
-    fn write_u32(&mut self, address: u32, value: u32) -> anyhow::Result<()> {
+    fn write_u32(&mut self, address: u32, value: u32) -> pixel_model2_rust::Result<()> {
         let bytes = value.to_le_bytes();
         for (i, byte) in bytes.iter().enumerate() {
             self.write_u8(address + i as u32, *byte)?;
@@ -350,4 +350,246 @@ fn test_performance_statistics() {
     assert_eq!(cpu.stats.instructions_executed, 3);
     assert_eq!(cpu.stats.cycles_executed, 4); // 1+1+2
     assert_eq!(cpu.stats.branches_taken, 1);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_call_and_return_track_call_stack() {
+    let mut cpu = NecV60::new();
+    let mut memory = TestMemory::new();
+    cpu.registers.pc = 0x1000;
+    cpu.registers.sp = 0x8000;
+    cpu.call_stack.enable_trace();
+
+    // CALL 0x2000
+    let call = DecodedInstruction {
+        address: 0x1000,
+        instruction: Instruction::Call {
+            target: Operand::Direct(0x2000),
+        },
+        size: 4,
+        cycles: 5,
+    };
+    cpu.execute_instruction(&call, &mut memory).unwrap();
+
+    assert_eq!(cpu.registers.pc, 0x2000);
+    assert_eq!(cpu.registers.sp, 0x7FFC);
+    assert_eq!(memory.read_u32(0x7FFC).unwrap(), 0x1004);
+    assert_eq!(cpu.call_stack.depth(), 1);
+    assert_eq!(cpu.get_debug_state().call_stack[0].target, 0x2000);
+
+    // RET
+    let ret = DecodedInstruction {
+        address: 0x2000,
+        instruction: Instruction::Return,
+        size: 2,
+        cycles: 5,
+    };
+    cpu.execute_instruction(&ret, &mut memory).unwrap();
+
+    assert_eq!(cpu.registers.pc, 0x1004);
+    assert_eq!(cpu.registers.sp, 0x8000);
+    assert_eq!(cpu.call_stack.depth(), 0);
+
+    let events = cpu.call_stack.events();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].kind, CallEventKind::Enter);
+    assert_eq!(events[1].kind, CallEventKind::Exit);
+    assert_eq!(events[1].function, 0x2000);
+}
+
+#[test]
+fn test_string_move_resumes_across_steps() {
+    let mut cpu = NecV60::new();
+    let mut memory = TestMemory::new();
+    for i in 0..100 {
+        memory.write_word(0x8000 + i * 4, 0x1000_0000 + i);
+    }
+
+    // Copie de 100 mots : R28 source, R27 destination, R26 nombre
+    cpu.registers.write_general(STRING_SOURCE_REGISTER, 0x8000);
+    cpu.registers.write_general(STRING_DESTINATION_REGISTER, 0x9000);
+    cpu.registers.write_general(STRING_COUNT_REGISTER, 100);
+    let instruction = DecodedInstruction::new(Instruction::StringMove { size: DataSize::DWord }, 0x5000, 2);
+
+    // Première tranche : le PC reste sur l'instruction, les registres
+    // gardent la progression
+    cpu.execute_instruction(&instruction, &mut memory).unwrap();
+    assert_eq!(cpu.registers.pc, 0x5000);
+    assert_eq!(cpu.registers.read_general(STRING_COUNT_REGISTER), 100 - STRING_STEP_ELEMENTS);
+
+    // Une autre instruction (une routine d'interruption) ne perturbe pas la reprise
+    cpu.registers.pc = 0x6000;
+    let other = DecodedInstruction::new(Instruction::Mov { dest: Operand::Register(1), src: Operand::Immediate(7) }, 0x6000, 2);
+    cpu.execute_instruction(&other, &mut memory).unwrap();
+    cpu.registers.pc = 0x5000;
+
+    cpu.execute_instruction(&instruction, &mut memory).unwrap();
+    assert_eq!(cpu.registers.pc, 0x5002);
+    assert_eq!(cpu.registers.read_general(STRING_COUNT_REGISTER), 0);
+    assert_eq!(cpu.registers.read_general(STRING_SOURCE_REGISTER), 0x8000 + 400);
+    assert_eq!(memory.read_u32(0x9000 + 99 * 4).unwrap(), 0x1000_0000 + 99);
+    assert!(cpu.registers.psw.contains(ProcessorStatusWord::CARRY));
+
+    // Remplissage puis recherche de la valeur écrite
+    cpu.registers.write_general(STRING_DESTINATION_REGISTER, 0xA000);
+    cpu.registers.write_general(STRING_COUNT_REGISTER, 8);
+    cpu.registers.write_general(STRING_VALUE_REGISTER, 0x5A);
+    cpu.execute_instruction(&DecodedInstruction::new(Instruction::StringFill { size: DataSize::Byte }, 0x5002, 2), &mut memory).unwrap();
+    assert_eq!(memory.read_u8(0xA007).unwrap(), 0x5A);
+    assert_eq!(memory.read_u8(0xA008).unwrap(), 0);
+
+    cpu.registers.write_general(STRING_SOURCE_REGISTER, 0x9FFC);
+    cpu.registers.write_general(STRING_COUNT_REGISTER, 16);
+    cpu.execute_instruction(&DecodedInstruction::new(Instruction::StringScan { size: DataSize::Byte }, 0x5004, 2), &mut memory).unwrap();
+    assert!(cpu.registers.psw.contains(ProcessorStatusWord::ZERO));
+    assert_eq!(cpu.registers.read_general(STRING_SOURCE_REGISTER), 0xA001);
+}
+
+#[test]
+fn test_string_instructions_decode_and_execute() {
+    let mut cpu = NecV60::new();
+    let mut memory = TestMemory::new();
+    let mut decoder = V60InstructionDecoder::new();
+    memory.write_word(0x8000, 0x4433_2211);
+
+    // Format 1 : opcode, taille des éléments dans r1
+    let encode = |opcode: u16, size: u16| ((opcode << 10) | size).to_le_bytes();
+
+    // Copie de deux demi-mots de 0x8000 vers 0x9000
+    cpu.registers.pc = 0x1000;
+    cpu.registers.write_general(STRING_SOURCE_REGISTER, 0x8000);
+    cpu.registers.write_general(STRING_DESTINATION_REGISTER, 0x9000);
+    cpu.registers.write_general(STRING_COUNT_REGISTER, 2);
+    let copy = decoder.decode(&encode(0x07, 1), 0x1000).unwrap();
+    assert_eq!(copy.instruction, Instruction::StringMove { size: DataSize::Word });
+    cpu.execute_instruction(&copy, &mut memory).unwrap();
+    assert_eq!(memory.read_u32(0x9000).unwrap(), 0x4433_2211);
+    assert_eq!(cpu.registers.pc, 0x1002);
+
+    // Comparaison d'un mot : les deux chaînes sont égales
+    cpu.registers.write_general(STRING_SOURCE_REGISTER, 0x8000);
+    cpu.registers.write_general(STRING_DESTINATION_REGISTER, 0x9000);
+    cpu.registers.write_general(STRING_COUNT_REGISTER, 1);
+    let compare = decoder.decode(&encode(0x08, 2), 0x1002).unwrap();
+    cpu.execute_instruction(&compare, &mut memory).unwrap();
+    assert!(cpu.registers.psw.contains(ProcessorStatusWord::ZERO));
+
+    // Recherche de l'octet 0x33, le troisième
+    cpu.registers.write_general(STRING_SOURCE_REGISTER, 0x9000);
+    cpu.registers.write_general(STRING_COUNT_REGISTER, 4);
+    cpu.registers.write_general(STRING_VALUE_REGISTER, 0x33);
+    let scan = decoder.decode(&encode(0x09, 0), 0x1004).unwrap();
+    cpu.execute_instruction(&scan, &mut memory).unwrap();
+    assert!(cpu.registers.psw.contains(ProcessorStatusWord::ZERO));
+    assert_eq!(cpu.registers.read_general(STRING_SOURCE_REGISTER), 0x9003);
+
+    // Remplissage de quatre octets à 0x5A
+    cpu.registers.write_general(STRING_DESTINATION_REGISTER, 0xA000);
+    cpu.registers.write_general(STRING_COUNT_REGISTER, 4);
+    cpu.registers.write_general(STRING_VALUE_REGISTER, 0x5A);
+    let fill = decoder.decode(&encode(0x0A, 0), 0x1006).unwrap();
+    cpu.execute_instruction(&fill, &mut memory).unwrap();
+    assert_eq!(memory.read_u32(0xA000).unwrap(), 0x5A5A_5A5A);
+    assert_eq!(cpu.registers.pc, 0x1008);
+
+    // Taille d'élément invalide
+    assert!(matches!(decoder.decode(&encode(0x07, 3), 0x1008).unwrap().instruction, Instruction::Unknown { .. }));
+}
+
+#[test]
+fn test_bitfield_instructions_decode_and_execute() {
+    let mut cpu = NecV60::new();
+    let mut memory = TestMemory::new();
+    let mut decoder = V60InstructionDecoder::new();
+    memory.write_word(0x8000, 0x89AB_CDEF);
+    memory.write_word(0x8004, 0x0123_4567);
+    cpu.registers.write_general(4, 0x8000);
+
+    // Format 2 : opcode, r2 valeur, r1 base ; décalage et longueur - 1 dans l'immédiat
+    let encode = |opcode: u16, r2: u16, r1: u16, offset: u16, length: u16| {
+        let word = (opcode << 10) | (r2 << 5) | r1;
+        let immediate = offset | ((length - 1) << 11);
+        [word.to_le_bytes(), immediate.to_le_bytes()].concat()
+    };
+
+    // EXTBFS R3, [R4] : 12 bits à cheval sur deux mots, bit 11 à 0
+    let extract = decoder.decode(&encode(0x18, 3, 4, 28, 12), 0x1000).unwrap();
+    cpu.execute_instruction(&extract, &mut memory).unwrap();
+    assert_eq!(cpu.registers.read_general(3), 0x678);
+    assert_eq!(cpu.registers.pc, 0x1004);
+
+    // INSBF R5, [R4] : 8 bits à partir du bit 60
+    cpu.registers.write_general(5, 0xAB);
+    let insert = decoder.decode(&encode(0x19, 5, 4, 60, 8), 0x1004).unwrap();
+    cpu.execute_instruction(&insert, &mut memory).unwrap();
+    assert_eq!(memory.read_u32(0x8004).unwrap(), 0xB123_4567);
+    assert_eq!(memory.read_u8(0x8008).unwrap(), 0x0A);
+
+    // CMPBFS R6, [R4] : le champ 0xB (4 bits) vaut -5
+    cpu.registers.write_general(6, (-5i32) as u32);
+    let compare = decoder.decode(&encode(0x1B, 6, 4, 60, 4), 0x1008).unwrap();
+    cpu.execute_instruction(&compare, &mut memory).unwrap();
+    assert!(cpu.registers.psw.contains(ProcessorStatusWord::ZERO));
+
+    let compare = decoder.decode(&encode(0x1A, 6, 4, 60, 4), 0x100C).unwrap();
+    cpu.execute_instruction(&compare, &mut memory).unwrap();
+    assert!(!cpu.registers.psw.contains(ProcessorStatusWord::ZERO));
+    assert!(cpu.registers.psw.contains(ProcessorStatusWord::CARRY));
+}
+
+#[test]
+fn test_float_exceptions_accumulate_and_trap() {
+    let mut cpu = NecV60::new();
+    let mut memory = TestMemory::new();
+    cpu.registers.write_general(0, 1.0f32.to_bits());
+    cpu.registers.write_general(2, 0);
+    let divide = |address| DecodedInstruction::new(Instruction::FloatDiv {
+        dest: Operand::Register(1),
+        src1: Operand::Register(0),
+        src2: Operand::Register(2),
+    }, address, 2);
+
+    // Sans déroutement : résultat infini, exception cumulée dans le TKCW
+    cpu.execute_instruction(&divide(0x1000), &mut memory).unwrap();
+    assert_eq!(f32::from_bits(cpu.registers.read_general(1)), f32::INFINITY);
+    let control = FpuControl::from_tkcw(cpu.registers.read_control(TKCW_REGISTER));
+    assert_eq!(control.status, FpuExceptions::DIVIDE_BY_ZERO);
+
+    // Déroutement activé : destination intacte, exception après l'instruction
+    cpu.registers.write_general(1, 0);
+    let control = FpuControl { traps: FpuExceptions::DIVIDE_BY_ZERO, rounding: RoundingMode::Down, ..FpuControl::default() };
+    cpu.registers.write_control(TKCW_REGISTER, control.to_tkcw());
+    let error = cpu.execute_instruction(&divide(0x1002), &mut memory).unwrap_err();
+    assert!(matches!(error, pixel_model2_rust::EmulatorError::CpuException(pixel_model2_rust::CpuException::FloatingPoint(e)) if e == FpuExceptions::DIVIDE_BY_ZERO));
+    assert_eq!(cpu.registers.read_general(1), 0);
+    assert_eq!(cpu.registers.pc, 0x1004);
+
+    // Arrondi vers -∞ lu dans le TKCW
+    cpu.registers.write_general(2, 3.0f32.to_bits());
+    cpu.execute_instruction(&divide(0x1004), &mut memory).unwrap();
+    assert_eq!(f32::from_bits(cpu.registers.read_general(1)), (1.0f32 / 3.0).next_down());
+}
+
+#[test]
+fn test_byte_swapped_rom_fixture_decodes_like_the_dump() {
+    // ROM programme lue sur un bus 16 bits grand-boutiste : chaque mot a
+    // ses deux octets inversés par rapport à l'ordre du V60
+    let mut data = vec![0x40, 0x22, 0x12, 0x34, 0x80, 0x64, 0xF0, 0x0D, 0xCA, 0xFE];
+    data.chunks_exact_mut(2).for_each(<[u8]>::reverse);
+
+    let decoder = V60InstructionDecoder::new();
+    assert_eq!(
+        decoder.format(&data, 0).unwrap(),
+        InstructionFormat::Format2 { opcode: 0x10, r2: 1, r1: 2, mode: 0, immediate: 0x1234 }
+    );
+    assert_eq!(
+        decoder.format(&data[4..], 4).unwrap(),
+        InstructionFormat::Format3 { opcode: 0x20, r2: 3, r1: 4, mode: 0, displacement: 0xCAFE_F00D }
+    );
+    assert!(decoder.format(&data[4..8], 4).is_err());
+
+    let rom = Rom::new(data);
+    assert_eq!(rom.read_u16(0).unwrap(), 0x4022);
+    assert_eq!(rom.read_u32(6).unwrap(), 0xCAFE_F00D);
+    assert!(rom.read_u32(8).is_err());
+}
@@ -51,6 +51,7 @@ fn test_gpu_command_buffer_batching() {
                 },
             ],
             texture_id: Some(1),
+            texture_attributes: 0,
         },
     ];
 
@@ -76,8 +76,9 @@ fn test_input_manager() {
     let input = input::InputManager::new();
 
     // Test initial
-    assert!(!input.player1.up);
-    assert!(!input.player1.punch);
+    assert!(!input.players[0].up);
+    assert!(!input.players[0].punch);
+    assert_eq!(input.players.len(), input::MAX_PLAYERS);
 
     // Note: Test de gestion des touches désactivé temporairement
     // à cause de la déprécation de VirtualKeyCode dans winit
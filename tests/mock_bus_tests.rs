@@ -0,0 +1,79 @@
+//! Tests du CPU sur le bus simulé (sans Model2Memory)
+
+use pixel_model2_rust::cpu::*;
+use pixel_model2_rust::memory::{BusError, MemoryInterface};
+use pixel_model2_rust::testing::{AccessKind, MockBus};
+
+fn instruction(address: u32, instruction: Instruction) -> DecodedInstruction {
+    DecodedInstruction {
+        address,
+        instruction,
+        size: 2,
+        cycles: 1,
+    }
+}
+
+#[test]
+fn test_indirect_load_reads_scripted_value() {
+    let mut cpu = NecV60::new();
+    let mut bus = MockBus::new().strict();
+    bus.expect_read(0x8000, 4, 0xDEAD_BEEF);
+
+    cpu.registers.write_general(0, 0x8000);
+    let mov = instruction(0x1000, Instruction::Mov {
+        dest: Operand::Register(1),
+        src: Operand::Indirect(0),
+    });
+
+    cpu.execute_instruction(&mov, &mut bus).unwrap();
+    assert_eq!(cpu.registers.read_general(1), 0xDEAD_BEEF);
+    bus.verify().unwrap();
+}
+
+#[test]
+fn test_interrupt_pushes_pc_and_flags() {
+    let mut cpu = NecV60::new();
+    let mut bus = MockBus::new();
+    bus.load(Interrupt::VBlank.vector_address(), &0x0000_4000u32.to_le_bytes());
+
+    cpu.registers.pc = 0x1234;
+    cpu.registers.sp = 0x0010_0000;
+    cpu.queue_interrupt(Interrupt::VBlank);
+    cpu.process_interrupts(&mut bus).unwrap();
+
+    assert_eq!(cpu.registers.pc, 0x4000);
+    let writes = bus.accesses_of(AccessKind::Write);
+    assert_eq!(writes.len(), 2);
+    assert_eq!((writes[0].address, writes[0].value), (0x000F_FFFC, 0x1234));
+    assert_eq!(bus.read_u32(0x000F_FFFC).unwrap(), 0x1234);
+}
+
+#[test]
+fn test_bus_error_propagates_to_cpu() {
+    let mut cpu = NecV60::new();
+    let mut bus = MockBus::new().fault_range(0x9000..0xA000);
+
+    cpu.registers.write_general(0, 0x9000);
+    let mov = instruction(0x1000, Instruction::Mov {
+        dest: Operand::Register(1),
+        src: Operand::Indirect(0),
+    });
+
+    let err = cpu.execute_instruction(&mov, &mut bus).unwrap_err();
+    assert_eq!(err.bus_error(), Some(&BusError { address: 0x9000, write: false }));
+}
+
+#[test]
+fn test_bus_error_on_fetch_raises_exception() {
+    let mut cpu = NecV60::new();
+    let mut bus = MockBus::new().fault_range(0x9000..0xA000);
+    bus.load(Interrupt::BusError.vector_address(), &0x0000_2000u32.to_le_bytes());
+
+    cpu.registers.pc = 0x9000;
+    cpu.registers.sp = 0x0010_0000;
+    cpu.step(&mut bus).unwrap();
+
+    // Le PC fautif est empilé puis le gestionnaire d'erreur de bus prend la main
+    assert_eq!(cpu.registers.pc, 0x2000);
+    assert_eq!(bus.read_u32(0x000F_FFFC).unwrap(), 0x9000);
+}
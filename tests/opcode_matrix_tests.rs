@@ -0,0 +1,26 @@
+//! `OPCODES.md` comparé à la matrice tirée du décodeur
+//!
+//! Un changement du décodeur ou de l'exécuteur qui modifie la prise en
+//! charge d'un opcode doit s'accompagner de la matrice régénérée :
+//!
+//! ```text
+//! UPDATE_OPCODES=1 cargo test --test opcode_matrix_tests
+//! ```
+
+use std::path::PathBuf;
+
+use pixel_model2_rust::cpu::OpcodeMatrix;
+
+#[test]
+fn test_opcode_matrix_is_up_to_date() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("OPCODES.md");
+    let markdown = OpcodeMatrix::build().to_markdown();
+    if std::env::var_os("UPDATE_OPCODES").is_some() {
+        std::fs::write(&path, markdown).unwrap();
+        return;
+    }
+
+    let committed = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{} illisible ({}), UPDATE_OPCODES=1 pour le créer", path.display(), e));
+    assert!(committed == markdown, "{} ne correspond plus au décodeur, UPDATE_OPCODES=1 pour le régénérer", path.display());
+}
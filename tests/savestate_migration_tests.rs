@@ -0,0 +1,230 @@
+//! Tests de migration des états de sauvegarde
+//!
+//! Les fichiers des anciennes versions sont reconstruits octet par octet à
+//! partir de structures reproduisant leur disposition d'origine, puis chargés
+//! par la version courante.
+
+use pixel_model2_rust::cpu::NecV60;
+use pixel_model2_rust::memory::{MemoryInterface, Model2Memory};
+use pixel_model2_rust::savestate::chunks::{write_chunk, write_encoded, CHUNK_CPU, CHUNK_HEADER, CHUNK_MAIN_RAM};
+use pixel_model2_rust::savestate::*;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct CpuV1 {
+    general: [u32; 32],
+    pc: u32,
+    sp: u32,
+    fp: u32,
+    psw: u32,
+    control: [u32; 16],
+    cycle_count: u64,
+    halted: bool,
+    interrupts_enabled: bool,
+    pending_interrupts: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct IoV1 {
+    interrupt_control: u32,
+    interrupt_status: u32,
+    timer_main: u32,
+    timer_sub: u32,
+    gpu_control: u32,
+    gpu_status: u32,
+    gpu_command: u32,
+    audio_control: u32,
+    input_data: u32,
+    input_control: u32,
+    cycle_counter: u64,
+}
+
+#[derive(Serialize)]
+struct MemoryV1 {
+    main_ram: Vec<u8>,
+    video_ram: Vec<u8>,
+    audio_ram: Vec<u8>,
+    io_registers: IoV1,
+}
+
+#[derive(Serialize)]
+struct StateV1 {
+    cpu: CpuV1,
+    memory: MemoryV1,
+}
+
+#[derive(Serialize)]
+struct ThumbnailV2 {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct HeaderV2 {
+    game: String,
+    game_revision: String,
+    board: String,
+    emulator_version: String,
+    created: u64,
+    thumbnail: Option<ThumbnailV2>,
+}
+
+fn legacy_state() -> StateV1 {
+    let mut general = [0; 32];
+    general[5] = 0x1234_5678;
+    let mut main_ram = vec![0; 0x200];
+    main_ram[0x100..0x104].copy_from_slice(&[0x04, 0x03, 0x02, 0x01]);
+
+    StateV1 {
+        cpu: CpuV1 {
+            general,
+            pc: 0x8000,
+            sp: 0x1F_FF00,
+            fp: 0,
+            psw: 0,
+            control: [0; 16],
+            cycle_count: 123_456,
+            halted: false,
+            interrupts_enabled: true,
+            pending_interrupts: vec![0x40],
+        },
+        memory: MemoryV1 {
+            main_ram,
+            video_ram: Vec::new(),
+            audio_ram: Vec::new(),
+            io_registers: IoV1 {
+                interrupt_control: 0,
+                interrupt_status: 0,
+                timer_main: 0,
+                timer_sub: 0,
+                gpu_control: 0,
+                gpu_status: 1,
+                gpu_command: 0,
+                audio_control: 0,
+                input_data: 0x42,
+                input_control: 0,
+                cycle_counter: 99,
+            },
+        },
+    }
+}
+
+fn preamble(version: u32) -> Vec<u8> {
+    let mut bytes = SAVESTATE_MAGIC.to_vec();
+    bytes.extend_from_slice(&version.to_le_bytes());
+    bytes
+}
+
+fn assert_legacy_machine(state: &SaveState) {
+    let mut cpu = NecV60::new();
+    let mut memory = Model2Memory::new();
+    state.restore(&mut cpu, &mut memory).unwrap();
+
+    assert_eq!(cpu.registers.pc, 0x8000);
+    assert_eq!(cpu.registers.general[5], 0x1234_5678);
+    assert_eq!(cpu.cycle_count, 123_456);
+    assert_eq!(cpu.pending_interrupts.len(), 1);
+    assert_eq!(memory.read_u32(0x100).unwrap(), 0x0102_0304);
+    assert_eq!(memory.io_registers().input.data, 0x42);
+}
+
+#[test]
+fn test_loads_version_1_state() {
+    let mut bytes = preamble(1);
+    bincode::serialize_into(&mut bytes, &legacy_state()).unwrap();
+
+    let state = SaveState::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(state.header, SaveStateHeader::default());
+    assert_legacy_machine(&state);
+}
+
+#[test]
+fn test_loads_version_2_state_and_header() {
+    let header = HeaderV2 {
+        game: "daytona".into(),
+        game_revision: "1.0".into(),
+        board: "Model 2".into(),
+        emulator_version: "0.1.0".into(),
+        created: 1_700_000_000,
+        thumbnail: Some(ThumbnailV2 { width: 1, height: 1, rgba: vec![9, 8, 7, 6] }),
+    };
+    let mut bytes = preamble(2);
+    bincode::serialize_into(&mut bytes, &header).unwrap();
+    bincode::serialize_into(&mut bytes, &legacy_state()).unwrap();
+
+    let state = SaveState::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(state.header.game, "daytona");
+    assert_eq!(state.header.created, 1_700_000_000);
+    assert_eq!(state.header.thumbnail.as_ref().map(|t| t.pixel(0, 0)), Some([9, 8, 7, 6]));
+    assert_legacy_machine(&state);
+
+    // Lecture de l'en-tête seul, comme pour la liste des emplacements
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("slot0.state");
+    std::fs::write(&path, &bytes).unwrap();
+    assert_eq!(SaveStateHeader::load(&path).unwrap(), state.header);
+}
+
+#[test]
+fn test_current_format_round_trip_with_header() {
+    let mut cpu = NecV60::new();
+    cpu.registers.pc = 0x4242;
+    let mut memory = Model2Memory::new();
+    memory.write_u32(0x1000_0010, 0xAABB_CCDD).unwrap();
+
+    let thumbnail = Thumbnail { width: 1, height: 1, rgba: vec![1, 2, 3, 4] };
+    let header = SaveStateHeader::new("vf2", "2.1", "Model 2").with_thumbnail(Some(thumbnail));
+    let mut bytes = Vec::new();
+    SaveState::capture(&cpu, &memory).with_header(header.clone()).write_to(&mut bytes).unwrap();
+    assert_eq!(&bytes[4..8], &SAVESTATE_VERSION.to_le_bytes());
+
+    let state = SaveState::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(state.header, header);
+
+    let mut restored_cpu = NecV60::new();
+    let mut restored_memory = Model2Memory::new();
+    state.restore(&mut restored_cpu, &mut restored_memory).unwrap();
+    assert_eq!(restored_cpu.registers.pc, 0x4242);
+    assert_eq!(restored_memory.read_u32(0x1000_0010).unwrap(), 0xAABB_CCDD);
+}
+
+#[test]
+fn test_tolerates_missing_extra_fields_and_unknown_chunks() {
+    let mut bytes = preamble(SAVESTATE_VERSION);
+
+    // En-tête écrit par une version qui ne connaissait pas la révision de carte
+    let header = serde_json::json!({ "game": "srallyc", "created": 5 });
+    write_encoded(&mut bytes, CHUNK_HEADER, 1, &header).unwrap();
+
+    // Bloc d'un sous-système ajouté par une version plus récente
+    write_chunk(&mut bytes, *b"DSP ", 4, &[0xFF; 32]).unwrap();
+
+    // État CPU partiel, avec un champ disparu depuis
+    let cpu = serde_json::json!({ "pc": 0x2000, "cycle_count": 77, "legacy_cache_hits": 3 });
+    write_encoded(&mut bytes, CHUNK_CPU, 1, &cpu).unwrap();
+    write_chunk(&mut bytes, CHUNK_MAIN_RAM, 1, &[0xEF, 0xBE, 0xAD, 0xDE]).unwrap();
+
+    let state = SaveState::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(state.header.game, "srallyc");
+    assert_eq!(state.header.board, "");
+    assert_eq!(state.cpu.pc, 0x2000);
+    assert_eq!(state.cpu.cycle_count, 77);
+    assert!(state.cpu.pending_interrupts.is_empty());
+
+    // Blocs absents : les registres I/O reprennent leur valeur de démarrage
+    let mut cpu = NecV60::new();
+    let mut memory = Model2Memory::new();
+    state.restore(&mut cpu, &mut memory).unwrap();
+    assert_eq!(cpu.registers.pc, 0x2000);
+    assert_eq!(memory.read_u32(0).unwrap(), 0xDEAD_BEEF);
+    assert_eq!(memory.io_registers().gpu.status, 1);
+}
+
+#[test]
+fn test_rejects_truncated_chunk() {
+    let mut bytes = Vec::new();
+    SaveState::capture(&NecV60::new(), &Model2Memory::new()).write_to(&mut bytes).unwrap();
+    bytes.truncate(bytes.len() - 10);
+    assert!(SaveState::read_from(bytes.as_slice()).is_err());
+}
@@ -2,9 +2,13 @@
 //! 
 //! Valide le décodage authentique des formats SEGA et l'intégration WGPU
 
+#![cfg(feature = "gui")]
+
 use pixel_model2_rust::gpu::texture::{
-    TextureManager, SegaTextureFormat, TextureDecodeParams
+    TextureManager, SegaTextureFormat, TextureDecodeParams, microtexture_id
 };
+use pixel_model2_rust::gpu::{TextureFilter, TextureWrap};
+use pixel_model2_rust::config::MipmapSettings;
 use std::sync::Arc;
 
 /// Configuration mock WGPU pour les tests
@@ -67,6 +71,69 @@ async fn test_load_simple_rgba8_texture() {
     println!("✅ Texture RGBA8 2x2 chargée avec succès");
 }
 
+#[tokio::test]
+async fn test_texture_filter_switch_keeps_textures() {
+    let (device, queue) = create_mock_wgpu().await;
+    let mut texture_manager = TextureManager::new(device, queue);
+    assert_eq!(texture_manager.filter(), TextureFilter::Linear);
+
+    texture_manager.load_texture(3, &[0u8; 16], 2, 2).unwrap();
+    texture_manager.set_filter(TextureFilter::Nearest);
+
+    assert_eq!(texture_manager.filter(), TextureFilter::Nearest);
+    assert!(texture_manager.get_bind_group(3).is_some());
+
+    // Les textures chargées après le changement utilisent le nouveau filtre
+    texture_manager.load_texture(4, &[0u8; 16], 2, 2).unwrap();
+    assert!(texture_manager.get_bind_group(4).is_some());
+}
+
+#[tokio::test]
+async fn test_wrapped_bind_groups_per_polygon_mode() {
+    let (device, queue) = create_mock_wgpu().await;
+    let mut texture_manager = TextureManager::new(device, queue);
+    texture_manager.load_texture(5, &[0u8; 16], 2, 2).unwrap();
+
+    assert!(texture_manager.wrapped_bind_group(5, [TextureWrap::Repeat; 2]).is_some());
+    assert!(texture_manager.wrapped_bind_group(5, [TextureWrap::Clamp, TextureWrap::Mirror]).is_some());
+    assert!(texture_manager.wrapped_bind_group(9, [TextureWrap::Clamp; 2]).is_none());
+    assert_eq!(texture_manager.get_texture(5).unwrap().wrapped_bind_groups.len(), 1);
+
+    // Un changement de filtre invalide les bind groups dérivés
+    texture_manager.set_filter(TextureFilter::Nearest);
+    assert!(texture_manager.get_texture(5).unwrap().wrapped_bind_groups.is_empty());
+}
+
+#[tokio::test]
+async fn test_microtextures_use_reserved_ids() {
+    let (device, queue) = create_mock_wgpu().await;
+    let mut texture_manager = TextureManager::new(device, queue);
+    texture_manager.load_texture(2, &[0u8; 16], 2, 2).unwrap();
+    texture_manager.load_microtexture(2, &[128u8; 16], 2, 2).unwrap();
+
+    let microtexture = texture_manager.get_texture(microtexture_id(2)).unwrap();
+    assert_eq!(microtexture.texels().rgba[0], 128);
+    assert_eq!(texture_manager.get_texture(2).unwrap().texels().rgba[0], 0);
+
+    let stats = texture_manager.stats();
+    assert_eq!((stats.textures, stats.microtextures, stats.bytes), (1, 1, 32));
+}
+
+#[test]
+fn test_software_manager_keeps_texels_only() {
+    let mut texture_manager = TextureManager::software();
+    texture_manager.load_texture(7, &[9u8; 16], 2, 2).unwrap();
+
+    let texture = texture_manager.get_texture(7).unwrap();
+    assert!(texture.gpu.is_none());
+    assert_eq!(texture.texels().rgba, &[9u8; 16]);
+    assert!(texture_manager.get_bind_group(7).is_none());
+    assert!(texture_manager.wrapped_bind_group(7, [TextureWrap::Clamp; 2]).is_none());
+
+    texture_manager.set_filter(TextureFilter::Nearest);
+    assert_eq!(texture_manager.filter(), TextureFilter::Nearest);
+}
+
 #[tokio::test]
 async fn test_sega_palette4bpp_decoding() {
     let (device, queue) = create_mock_wgpu().await;
@@ -231,4 +298,132 @@ async fn test_large_texture_handling() {
     assert_eq!(texture.height, 64);
     
     println!("✅ Texture large 64x64 gérée correctement");
-}
\ No newline at end of file
+}
+
+/// Décode une texture synthétique sans carte graphique et retourne ses
+/// texels RGBA8
+fn decode_fixture(format: SegaTextureFormat, width: u32, height: u32, stride: Option<u32>, data_offset: usize, data: &[u8]) -> Vec<u8> {
+    let mut texture_manager = TextureManager::software();
+    let params = TextureDecodeParams { width, height, format, palette_offset: None, data_offset, stride };
+    texture_manager.load_texture_from_rom(1, data, params).unwrap();
+
+    let texture = texture_manager.get_texture(1).unwrap();
+    assert_eq!((texture.width, texture.height), (width, height));
+    texture.pixels.clone()
+}
+
+/// Texels attendus d'une texture indexée avec la palette par défaut
+fn palette_texels(indices: &[u8]) -> Vec<u8> {
+    let colors = TextureManager::software().palette_colors(None);
+    indices.iter().flat_map(|&index| colors[index as usize]).collect()
+}
+
+#[test]
+fn test_fixture_palette4bpp_odd_width_and_stride() {
+    // 3x2 : chaque ligne occupe 2 octets, le dernier demi-octet est du
+    // remplissage (0xF) qui ne doit pas apparaître
+    let packed = [0x21, 0xF3, 0x54, 0xF6];
+    let expected = palette_texels(&[1, 2, 3, 4, 5, 6]);
+    assert_eq!(decode_fixture(SegaTextureFormat::Palette4bpp, 3, 2, None, 0, &packed), expected);
+
+    // Mêmes lignes espacées de 4 octets, après 2 octets d'en-tête
+    let strided = [0xEE, 0xEE, 0x21, 0xF3, 0xEE, 0xEE, 0x54, 0xF6];
+    assert_eq!(decode_fixture(SegaTextureFormat::Palette4bpp, 3, 2, Some(4), 2, &strided), expected);
+
+    // Largeur 1 : seul le demi-octet inférieur de chaque ligne compte
+    let narrow = [0xA7, 0xB8, 0xC9];
+    assert_eq!(decode_fixture(SegaTextureFormat::Palette4bpp, 1, 3, None, 0, &narrow), palette_texels(&[7, 8, 9]));
+}
+
+#[test]
+fn test_fixture_palette8bpp_with_stride() {
+    let strided = [10, 20, 30, 0xEE, 40, 50, 60, 0xEE];
+    assert_eq!(
+        decode_fixture(SegaTextureFormat::Palette8bpp, 3, 2, Some(4), 0, &strided),
+        palette_texels(&[10, 20, 30, 40, 50, 60])
+    );
+    assert_eq!(
+        decode_fixture(SegaTextureFormat::Palette8bpp, 3, 1, None, 4, &strided),
+        palette_texels(&[40, 50, 60])
+    );
+}
+
+#[test]
+fn test_fixture_rgb565_exact_expansion() {
+    // 2x2, lignes de 4 octets espacées de 6
+    let strided = [
+        0x00, 0xF8, 0xE0, 0x07, 0xEE, 0xEE, // rouge, vert
+        0x1F, 0x00, 0x10, 0x84, 0xEE, 0xEE, // bleu, gris moyen
+    ];
+    assert_eq!(decode_fixture(SegaTextureFormat::Rgb565, 2, 2, Some(6), 0, &strided), [
+        255, 0, 0, 255, 0, 255, 0, 255,
+        0, 0, 255, 255, 132, 130, 132, 255,
+    ]);
+}
+
+#[test]
+fn test_fixture_rgba4444_odd_width() {
+    // 3x1 : R dans les 4 bits supérieurs, A dans les inférieurs
+    let packed = [0x0F, 0xF0, 0x0F, 0x0F, 0x34, 0x12];
+    assert_eq!(decode_fixture(SegaTextureFormat::Rgba4444, 3, 1, None, 0, &packed), [
+        255, 0, 0, 255,
+        0, 255, 0, 255,
+        0x11, 0x22, 0x33, 0x44,
+    ]);
+}
+
+#[test]
+fn test_fixture_rgba8888_with_stride() {
+    // 1x3, un texel utile par ligne de 8 octets
+    let mut strided = vec![0xEE; 24];
+    for (row, texel) in [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]].iter().enumerate() {
+        strided[row * 8..row * 8 + 4].copy_from_slice(texel);
+    }
+    assert_eq!(
+        decode_fixture(SegaTextureFormat::Rgba8888, 1, 3, Some(8), 0, &strided),
+        (1..=12).collect::<Vec<u8>>()
+    );
+}
+
+#[test]
+fn test_fixture_rejects_short_stride_and_truncated_data() {
+    let mut texture_manager = TextureManager::software();
+    let params = |format, stride| TextureDecodeParams {
+        width: 3, height: 2, format, palette_offset: None, data_offset: 0, stride,
+    };
+    assert_eq!(params(SegaTextureFormat::Palette4bpp, None).row_bytes(), 2);
+    assert_eq!(params(SegaTextureFormat::Rgb565, Some(8)).row_pitch(), 8);
+
+    // Pas plus court qu'une ligne
+    assert!(texture_manager.load_texture_from_rom(1, &[0; 64], params(SegaTextureFormat::Rgb565, Some(4))).is_err());
+    // Dernière ligne hors des données : le pas s'applique entre les lignes
+    assert!(texture_manager.load_texture_from_rom(1, &[0; 9], params(SegaTextureFormat::Palette8bpp, Some(8))).is_err());
+    assert!(texture_manager.load_texture_from_rom(1, &[0; 11], params(SegaTextureFormat::Palette8bpp, Some(8))).is_ok());
+    assert!(texture_manager.get_texture(1).is_some());
+}
+
+#[test]
+fn test_mipmaps_generated_when_enabled() {
+    let mut texture_manager = TextureManager::software();
+    // 3x2 : le bloc du bord droit n'a qu'une colonne
+    let rgba: Vec<u8> = [10, 20, 30, 40, 50, 60].iter().flat_map(|&v| [v, v, v, 255]).collect();
+    texture_manager.load_texture(1, &rgba, 3, 2).unwrap();
+    assert!(texture_manager.get_texture(1).unwrap().mipmaps.is_empty());
+
+    texture_manager.set_mipmaps(MipmapSettings { enabled: true, lod_bias: 0.5 });
+    assert_eq!(texture_manager.mipmaps().lod_bias, 0.5);
+    let mipmaps = &texture_manager.get_texture(1).unwrap().mipmaps;
+    assert_eq!(mipmaps.len(), 1);
+    assert_eq!((mipmaps[0].width, mipmaps[0].height), (1, 1));
+    // (10 + 20 + 40 + 50) / 4, arrondi
+    assert_eq!(mipmaps[0].rgba, [30, 30, 30, 255]);
+
+    // Texture chargée après coup : chaîne complète jusqu'à 1x1
+    texture_manager.load_texture(2, &[0u8; 8 * 2 * 4], 8, 2).unwrap();
+    let sizes: Vec<(u32, u32)> = texture_manager.get_texture(2).unwrap().mipmaps.iter().map(|level| (level.width, level.height)).collect();
+    assert_eq!(sizes, [(4, 1), (2, 1), (1, 1)]);
+    assert_eq!(texture_manager.stats().bytes, 24 + 4 + 64 + 16 + 8 + 4);
+
+    texture_manager.set_mipmaps(MipmapSettings::default());
+    assert!(texture_manager.get_texture(2).unwrap().mipmaps.is_empty());
+}